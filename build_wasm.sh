@@ -66,7 +66,7 @@ echo ""
 GOEXPERIMENT=greenteagc GOOS=js GOARCH=wasm go build \
   -ldflags="-s -w" \
   -o static/wasm/physics_greentea.wasm \
-  physics_wasm.go
+  physics_*.go
 
 PHYSICS_SIZE=$(du -h static/wasm/physics_greentea.wasm | cut -f1)
 echo "✓ Physics WASM build complete: static/wasm/physics_greentea.wasm ($PHYSICS_SIZE)"
@@ -163,7 +163,7 @@ echo "  • wasmFindNearestObject(x, y, cat, d) - Find nearest by category"
 echo "  • wasmFindObjectsInRadius(x, y, r, c) - Radius-based search"
 echo ""
 echo "  Car Physics:"
-echo "  • wasmUpdateCarPhysics(state, input)  - Update car physics (NEW)"
+echo "  • wasmUpdateCarPhysics(state, input, dt) - Update car physics (dt in seconds)"
 echo ""
 echo "  Debugging:"
 echo "  • wasmGetGridStats()                  - Debug statistics"
@@ -0,0 +1,143 @@
+package main
+
+import (
+	"errors"
+	"time"
+)
+
+// ============================================================================
+// Autosave (ring of periodic in-memory snapshots)
+// ============================================================================
+
+// Every Interval simulated seconds Step takes a WorldSnapshot
+// (physics_snapshot.go) into a ring of Slots autosaves, the newest
+// overwriting the oldest, so a disastrous edit or a simulation gone wrong
+// can be rolled back to a recent state without JS keeping saves itself.
+// Autosave can also be called directly, e.g. before a risky bulk edit.
+// Restoring an autosave keeps the ring, newer autosaves included, so a
+// restore can itself be undone by restoring a newer one; the interval
+// restarts from the restored time. Autosaves live in memory only: to
+// survive a page reload the host persists one with Save, which encodes it
+// like World.Save for World.Load.
+
+const (
+	DEFAULT_AUTOSAVE_INTERVAL = 60.0 // Simulated s between autosaves
+	DEFAULT_AUTOSAVE_SLOTS    = 5    // Autosaves kept; 0 turns autosaving off
+	MAX_AUTOSAVE_SLOTS        = 100  // Each slot holds a full snapshot
+)
+
+// Autosave is one snapshot in the ring
+type Autosave struct {
+	ID    int       // Increases with every autosave
+	Time  float64   // World time the snapshot was taken at
+	Day   int       // Game day on the town clock
+	Taken time.Time // Wall-clock time, for "3 minutes ago" labels
+
+	snapshot WorldSnapshot
+}
+
+// AutosaveRing holds the most recent autosaves
+type AutosaveRing struct {
+	Interval float64 // Simulated s between autosaves
+	Slots    int     // Autosaves kept
+
+	saves  []Autosave // Ring storage; oldest is the next to be overwritten
+	oldest int
+	nextID int
+	last   float64 // World time of the last autosave
+}
+
+// NewAutosaveRing creates an empty ring with the default interval and slots
+func NewAutosaveRing() *AutosaveRing {
+	return &AutosaveRing{Interval: DEFAULT_AUTOSAVE_INTERVAL, Slots: DEFAULT_AUTOSAVE_SLOTS, nextID: 1}
+}
+
+// Configure changes the interval and number of slots, keeping the newest
+// autosaves that still fit; non-positive intervals keep the current one
+func (a *AutosaveRing) Configure(interval float64, slots int) {
+	a.Interval = positiveOr(interval, a.Interval)
+	saves := a.List()
+	a.Slots = min(max(slots, 0), MAX_AUTOSAVE_SLOTS)
+	if len(saves) > a.Slots {
+		saves = saves[len(saves)-a.Slots:]
+	}
+	a.saves, a.oldest = saves, 0
+}
+
+// List returns the autosaves, oldest first
+func (a *AutosaveRing) List() []Autosave {
+	list := make([]Autosave, 0, len(a.saves))
+	list = append(list, a.saves[a.oldest:]...)
+	return append(list, a.saves[:a.oldest]...)
+}
+
+// Get returns the autosave with an ID, or nil once it has been overwritten
+func (a *AutosaveRing) Get(id int) *Autosave {
+	for i := range a.saves {
+		if a.saves[i].ID == id {
+			return &a.saves[i]
+		}
+	}
+	return nil
+}
+
+// Clear drops every autosave
+func (a *AutosaveRing) Clear() {
+	a.saves, a.oldest = nil, 0
+}
+
+// add stores an autosave, overwriting the oldest when the ring is full
+func (a *AutosaveRing) add(save Autosave) {
+	if len(a.saves) < a.Slots {
+		a.saves = append(a.saves, save)
+		return
+	}
+	a.saves[a.oldest] = save
+	a.oldest = (a.oldest + 1) % len(a.saves)
+}
+
+// Save encodes the autosave as a binary save (physics_save.go)
+func (s *Autosave) Save() ([]byte, error) {
+	return encodeSave(s.snapshot)
+}
+
+// Autosave snapshots the world into the ring now, returning the autosave's
+// ID, or 0 when the ring has no slots
+func (w *World) Autosave() int {
+	a := w.Autosaves
+	if a.Slots <= 0 {
+		return 0
+	}
+	save := Autosave{ID: a.nextID, Time: w.Time, Day: w.Clock.Day(), Taken: time.Now(), snapshot: w.Snapshot()}
+	a.nextID++
+	a.last = w.Time
+	a.add(save)
+	return save.ID
+}
+
+// stepAutosave autosaves once Interval has passed since the last autosave
+func (w *World) stepAutosave() {
+	a := w.Autosaves
+	if a.Slots <= 0 {
+		return
+	}
+	if w.Time < a.last {
+		a.last = w.Time // Rewound by a restore or rollback
+	}
+	if w.Time-a.last >= a.Interval {
+		w.Autosave()
+	}
+}
+
+// RestoreAutosave returns the world to an autosave, keeping the ring
+func (w *World) RestoreAutosave(id int) error {
+	save := w.Autosaves.Get(id)
+	if save == nil {
+		return errors.New("autosave does not exist or has been overwritten")
+	}
+	if err := w.Restore(save.snapshot); err != nil {
+		return err
+	}
+	w.Autosaves.last = w.Time
+	return nil
+}
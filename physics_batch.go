@@ -0,0 +1,214 @@
+package main
+
+import "strconv"
+
+// ============================================================================
+// Batch Car Updates (flat float64 buffers)
+// ============================================================================
+
+// Many cars (AI traffic) are advanced in one call over flat buffers so the
+// frontend crosses the JS/WASM boundary once per frame instead of once per car.
+// Each car occupies CAR_STATE_STRIDE values in the state buffer and
+// CAR_INPUT_STRIDE values in the input buffer, laid out as below.
+
+// Car state buffer offsets
+const (
+	carStateX = iota
+	carStateZ
+	carStateRotationY
+	carStateVelocityX
+	carStateVelocityZ
+	carStateReversing // 0 or 1
+	carStateGear
+	carStateRPM
+	carStateShiftTimer
+	carStateID
+	carStateDamage
+	carStateFuelUsed
+	carStateLean
+	carStateBounce
+	carStateBounceVelocity
+	carStateBodyPitch
+	carStateBodyPitchRate
+	carStateBodyRoll
+	carStateBodyRollRate
+	carStateSkidding // 0 or 1
+	carStateOdometer
+
+	// Outputs: written by the step, ignored on input
+	carStateY
+	carStatePitch
+	carStateRoll
+	carStateSlipAngle
+	carStateSurface // SurfaceType as a number
+	carStateSteerAngle
+	carStateWheelAngleLeft
+	carStateWheelAngleRight
+	carStateThrottleLoad
+	carStateCollided // 0 or 1
+	carStateCollidedWith
+	carStateHitBoundary // 0 or 1
+	carStateHitWater    // 0 or 1
+	carStateSkidIntensity
+	carStateSpeedKmh
+	carStateSpeedMph
+	carStateOdometerKm
+	carStateOdometerMiles
+
+	CAR_STATE_STRIDE
+)
+
+// carStateFieldNames are the JavaScript-facing names, indexed by offset
+var carStateFieldNames = [CAR_STATE_STRIDE]string{
+	"x", "z", "rotation_y", "velocity_x", "velocity_z", "reversing", "gear", "rpm", "shift_timer", "id", "damage", "fuel_used", "lean",
+	"bounce", "bounce_velocity", "body_pitch", "body_pitch_rate", "body_roll", "body_roll_rate", "skidding", "odometer",
+	"y", "pitch", "roll", "slip_angle", "surface", "steer_angle", "wheel_angle_left", "wheel_angle_right",
+	"throttle_load", "collided", "collided_with", "hit_boundary", "hit_water", "skid_intensity",
+	"speed_kmh", "speed_mph", "odometer_km", "odometer_miles",
+}
+
+// Car input buffer offsets
+const (
+	carInputThrottle = iota
+	carInputBrake
+	carInputSteering
+	carInputHandbrake // 0 or 1
+	carInputCruiseSpeed
+	carInputSpeedLimiter // 0 or 1
+
+	CAR_INPUT_STRIDE
+)
+
+// carInputFieldNames are the JavaScript-facing names, indexed by offset
+var carInputFieldNames = [CAR_INPUT_STRIDE]string{"throttle", "brake", "steering", "handbrake", "cruise_speed", "speed_limiter"}
+
+// boolToFloat encodes a flag for the batch buffers
+func boolToFloat(b bool) float64 {
+	if b {
+		return 1
+	}
+	return 0
+}
+
+// loadBatch reads the input fields of one car from its slot in the state buffer
+func (car *CarState) loadBatch(s []float64) {
+	*car = CarState{
+		X:          s[carStateX],
+		Z:          s[carStateZ],
+		RotationY:  s[carStateRotationY],
+		VelocityX:  s[carStateVelocityX],
+		VelocityZ:  s[carStateVelocityZ],
+		Reversing:  s[carStateReversing] != 0,
+		Gear:       int(s[carStateGear]),
+		RPM:        s[carStateRPM],
+		ShiftTimer: s[carStateShiftTimer],
+		ID:         int(s[carStateID]),
+		Damage:     s[carStateDamage],
+		FuelUsed:   s[carStateFuelUsed],
+		Lean:       s[carStateLean],
+
+		Bounce:         s[carStateBounce],
+		BounceVelocity: s[carStateBounceVelocity],
+		BodyPitch:      s[carStateBodyPitch],
+		BodyPitchRate:  s[carStateBodyPitchRate],
+		BodyRoll:       s[carStateBodyRoll],
+		BodyRollRate:   s[carStateBodyRollRate],
+
+		Skidding: s[carStateSkidding] != 0,
+		Odometer: s[carStateOdometer],
+	}
+}
+
+// storeBatch writes one car into its slot in the state buffer
+func (car *CarState) storeBatch(s []float64) {
+	s[carStateX] = car.X
+	s[carStateZ] = car.Z
+	s[carStateRotationY] = car.RotationY
+	s[carStateVelocityX] = car.VelocityX
+	s[carStateVelocityZ] = car.VelocityZ
+	s[carStateReversing] = boolToFloat(car.Reversing)
+	s[carStateGear] = float64(car.Gear)
+	s[carStateRPM] = car.RPM
+	s[carStateShiftTimer] = car.ShiftTimer
+	s[carStateID] = float64(car.ID)
+	s[carStateDamage] = car.Damage
+	s[carStateFuelUsed] = car.FuelUsed
+	s[carStateLean] = car.Lean
+	s[carStateBounce] = car.Bounce
+	s[carStateBounceVelocity] = car.BounceVelocity
+	s[carStateBodyPitch] = car.BodyPitch
+	s[carStateBodyPitchRate] = car.BodyPitchRate
+	s[carStateBodyRoll] = car.BodyRoll
+	s[carStateBodyRollRate] = car.BodyRollRate
+	s[carStateSkidding] = boolToFloat(car.Skidding)
+	s[carStateOdometer] = car.Odometer
+
+	s[carStateY] = car.Y
+	s[carStatePitch] = car.Pitch
+	s[carStateRoll] = car.Roll
+	s[carStateSlipAngle] = car.SlipAngle
+	s[carStateSurface] = float64(car.Surface)
+	s[carStateSteerAngle] = car.SteerAngle
+	s[carStateWheelAngleLeft] = car.WheelAngleLeft
+	s[carStateWheelAngleRight] = car.WheelAngleRight
+	s[carStateThrottleLoad] = car.ThrottleLoad
+	s[carStateCollided] = boolToFloat(car.Collided)
+	s[carStateCollidedWith] = float64(car.CollidedWith)
+	s[carStateHitBoundary] = boolToFloat(car.HitBoundary)
+	s[carStateHitWater] = boolToFloat(car.HitWater)
+	s[carStateSkidIntensity] = car.SkidIntensity
+	s[carStateSpeedKmh] = car.SpeedKmh
+	s[carStateSpeedMph] = car.SpeedMph
+	s[carStateOdometerKm] = car.OdometerKm
+	s[carStateOdometerMiles] = car.OdometerMiles
+}
+
+// inputFromBatch reads one car's controls from its slot in the input buffer
+func inputFromBatch(in []float64) InputState {
+	return InputState{
+		Throttle:  in[carInputThrottle],
+		Brake:     in[carInputBrake],
+		Steering:  in[carInputSteering],
+		Handbrake: in[carInputHandbrake] != 0,
+
+		CruiseSpeed:  in[carInputCruiseSpeed],
+		SpeedLimiter: in[carInputSpeedLimiter] != 0,
+	}
+}
+
+// stepCarBatch advances count cars stored in flat buffers, then resolves
+// car-to-car contacts between them. Car i uses cfgs[i], or the last config
+// when fewer configs than cars are given. Errors are prefixed with the car index
+func (w *World) stepCarBatch(states, inputs []float64, count int, cfgs []VehicleConfig, dt float64) []string {
+	count = min(count, len(states)/CAR_STATE_STRIDE)
+	count = min(count, len(inputs)/CAR_INPUT_STRIDE)
+	if count <= 0 || len(cfgs) == 0 {
+		return nil
+	}
+
+	var errors []string
+	cars := make([]CarState, count)
+	bodies := make([]CarBody, count)
+
+	for i := range cars {
+		car := &cars[i]
+		cfg := &cfgs[min(i, len(cfgs)-1)]
+		car.loadBatch(states[i*CAR_STATE_STRIDE:])
+
+		for _, err := range w.stepCar(car, inputFromBatch(inputs[i*CAR_INPUT_STRIDE:]), cfg, dt) {
+			errors = append(errors, "car "+strconv.Itoa(i)+": "+err)
+		}
+
+		bodies[i] = car.body(cfg)
+	}
+
+	w.resolveCarCollisions(bodies)
+
+	for i := range cars {
+		car := &cars[i]
+		car.applyBody(bodies[i])
+		car.storeBatch(states[i*CAR_STATE_STRIDE:])
+	}
+
+	return errors
+}
@@ -0,0 +1,92 @@
+package main
+
+import "math"
+
+// ============================================================================
+// World Boundaries
+// ============================================================================
+
+// BOUNDARY_RESTITUTION is the fraction of speed kept when bouncing off the world edge
+const BOUNDARY_RESTITUTION = 0.5
+
+// BoundsMode selects what happens when a car reaches the world edge
+type BoundsMode uint8
+
+const (
+	BoundsClamp  BoundsMode = iota // Stop at the edge, keep sliding along it
+	BoundsBounce                   // Reflect off the edge, losing some speed
+	BoundsWrap                     // Reappear at the opposite edge
+)
+
+// boundsModeNames are the JavaScript-facing names, indexed by BoundsMode
+var boundsModeNames = [...]string{"clamp", "bounce", "wrap"}
+
+// boundsModeFromString converts a mode name to its type (unknown names map to clamp)
+func boundsModeFromString(name string) BoundsMode {
+	for i, n := range boundsModeNames {
+		if n == name {
+			return BoundsMode(i)
+		}
+	}
+	return BoundsClamp
+}
+
+// String returns the JavaScript-facing mode name
+func (m BoundsMode) String() string {
+	if int(m) >= len(boundsModeNames) {
+		return boundsModeNames[BoundsClamp]
+	}
+	return boundsModeNames[m]
+}
+
+// WorldBounds limits where cars can drive
+type WorldBounds struct {
+	Box  BoundingBox // X/Z extents (MinY/MaxY hold world Z)
+	Mode BoundsMode
+}
+
+// apply keeps the car inside the bounds, returning true if the edge was hit
+func (b *WorldBounds) apply(car *CarState) bool {
+	hitX := constrainAxis(&car.X, &car.VelocityX, b.Box.MinX, b.Box.MaxX, b.Mode)
+	hitZ := constrainAxis(&car.Z, &car.VelocityZ, b.Box.MinY, b.Box.MaxY, b.Mode)
+	return hitX || hitZ
+}
+
+// constrainAxis applies the bounds mode along one axis
+func constrainAxis(pos, vel *float64, lo, hi float64, mode BoundsMode) bool {
+	if *pos >= lo && *pos <= hi {
+		return false
+	}
+
+	switch mode {
+	case BoundsWrap:
+		*pos = lo + math.Mod(math.Mod(*pos-lo, hi-lo)+(hi-lo), hi-lo)
+	case BoundsBounce:
+		if *pos < lo {
+			*pos = math.Min(lo+(lo-*pos), hi)
+			*vel = math.Abs(*vel) * BOUNDARY_RESTITUTION
+		} else {
+			*pos = math.Max(hi-(*pos-hi), lo)
+			*vel = -math.Abs(*vel) * BOUNDARY_RESTITUTION
+		}
+	default:
+		if *pos < lo {
+			*pos = lo
+			*vel = math.Max(*vel, 0)
+		} else {
+			*pos = hi
+			*vel = math.Min(*vel, 0)
+		}
+	}
+	return true
+}
+
+// applyWorldBounds enforces the world's bounds on the car, if any
+func (w *World) applyWorldBounds(car *CarState) {
+	if w.Bounds == nil {
+		return
+	}
+	if w.Bounds.apply(car) {
+		car.HitBoundary = true
+	}
+}
@@ -0,0 +1,183 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Chase Camera (smoothed follow view behind a car)
+// ============================================================================
+
+// A chase camera trails a car by Distance, stretched by SpeedDistance at
+// the car's top speed, and Height above it, looking at a point LookAhead in
+// front of it (also growing with speed) and LookHeight up. The camera does
+// not sit on that point: it is pulled towards it by a spring of Stiffness
+// damped at Damping times critical, so it swings wide through corners and
+// lags under acceleration. If a building or the terrain comes between the
+// car and the camera, the camera moves in to Clearance in front of it, and
+// back out once the view is clear; it never sinks below Clearance over the
+// ground. The field of view widens by SpeedFOV at top speed as a hint for
+// the renderer. Cameras are render state, stepped by JS once per frame
+// with the frame time: they are not part of snapshots and do not affect
+// the simulation.
+
+const (
+	CAMERA_DISTANCE       = 6.0  // units behind the car at rest
+	CAMERA_SPEED_DISTANCE = 3.0  // units further back at top speed
+	CAMERA_HEIGHT         = 2.5  // units above the car
+	CAMERA_LOOK_AHEAD     = 2.0  // units in front of the car looked at, at top speed
+	CAMERA_LOOK_HEIGHT    = 1.0  // units above the car looked at
+	CAMERA_STIFFNESS      = 30.0 // 1/s², spring pull per unit of lag
+	CAMERA_DAMPING        = 1.0  // Fraction of critical damping
+	CAMERA_FOV            = 60.0 // Degrees at rest
+	CAMERA_SPEED_FOV      = 15.0 // Degrees wider at top speed
+	CAMERA_FOV_RATE       = 3.0  // 1/s, how fast the field of view follows speed
+	CAMERA_CLEARANCE      = 0.3  // units kept from walls and the ground
+	CAMERA_SNAP_DISTANCE  = 50.0 // units of lag past which the camera jumps to place
+	cameraSubstep         = 1.0 / 120.0
+)
+
+// ChaseCameraConfig tunes a chase camera
+type ChaseCameraConfig struct {
+	Distance      float64
+	SpeedDistance float64
+	Height        float64
+	LookAhead     float64
+	LookHeight    float64
+	Stiffness     float64
+	Damping       float64
+	FOV           float64
+	SpeedFOV      float64
+	Clearance     float64
+}
+
+// DefaultChaseCameraConfig returns the standard chase camera tuning
+func DefaultChaseCameraConfig() ChaseCameraConfig {
+	return ChaseCameraConfig{
+		Distance:      CAMERA_DISTANCE,
+		SpeedDistance: CAMERA_SPEED_DISTANCE,
+		Height:        CAMERA_HEIGHT,
+		LookAhead:     CAMERA_LOOK_AHEAD,
+		LookHeight:    CAMERA_LOOK_HEIGHT,
+		Stiffness:     CAMERA_STIFFNESS,
+		Damping:       CAMERA_DAMPING,
+		FOV:           CAMERA_FOV,
+		SpeedFOV:      CAMERA_SPEED_FOV,
+		Clearance:     CAMERA_CLEARANCE,
+	}
+}
+
+// sanitized returns the config with invalid values defaulted
+func (c ChaseCameraConfig) sanitized() ChaseCameraConfig {
+	d := DefaultChaseCameraConfig()
+	c.Distance = positiveOr(c.Distance, d.Distance)
+	c.Stiffness = positiveOr(c.Stiffness, d.Stiffness)
+	c.Damping = positiveOr(c.Damping, d.Damping)
+	c.FOV = clamp(positiveOr(c.FOV, d.FOV), 1, 179)
+	for _, v := range []*float64{&c.SpeedDistance, &c.Height, &c.LookAhead, &c.LookHeight, &c.SpeedFOV, &c.Clearance} {
+		if !isFinite(*v) {
+			*v = 0
+		}
+	}
+	c.SpeedFOV = clamp(c.SpeedFOV, 0, 179-c.FOV)
+	c.Clearance = math.Max(c.Clearance, 0)
+	return c
+}
+
+// ChaseCamera is a camera following one car
+type ChaseCamera struct {
+	Config ChaseCameraConfig
+
+	X, Y, Z                         float64 // Where the camera is, before pulling in for walls
+	VelocityX, VelocityY, VelocityZ float64
+	FOV                             float64 // Degrees
+	placed                          bool    // X, Y, Z are set
+}
+
+// CameraView is the transform a chase camera hands the renderer
+type CameraView struct {
+	X, Y, Z             float64
+	LookX, LookY, LookZ float64
+	Yaw, Pitch          float64 // rad; yaw as RotationY, pitch up positive
+	FOV                 float64 // Degrees
+}
+
+// ChaseCamera returns the chase camera following a car, creating one with
+// the default tuning if it has none
+func (w *World) ChaseCamera(carID int) *ChaseCamera {
+	cam := w.cameras[carID]
+	if cam == nil {
+		cam = &ChaseCamera{Config: DefaultChaseCameraConfig()}
+		w.cameras[carID] = cam
+	}
+	return cam
+}
+
+// Reset makes the camera jump to its place on the next update
+func (cam *ChaseCamera) Reset() {
+	cam.placed = false
+	cam.VelocityX, cam.VelocityY, cam.VelocityZ = 0, 0, 0
+}
+
+// UpdateChaseCamera moves a car's chase camera on by a frame of dt
+// seconds, returning its view and false if the car does not exist
+func (w *World) UpdateChaseCamera(carID int, dt float64) (CameraView, bool) {
+	car := w.Car(carID)
+	if car == nil {
+		return CameraView{}, false
+	}
+	cam := w.ChaseCamera(carID)
+	c := &cam.Config
+	s := &car.State
+
+	speed := simHypot(s.VelocityX, s.VelocityZ)
+	fast := clamp(speed/positiveOr(car.Config.MaxSpeed, MAX_SPEED), 0, 1)
+	hx, hz := math.Sin(s.RotationY), math.Cos(s.RotationY)
+	back := c.Distance + c.SpeedDistance*fast
+	goalX, goalY, goalZ := s.X-hx*back, s.Y+c.Height, s.Z-hz*back
+	lookX, lookY, lookZ := s.X+hx*c.LookAhead*fast, s.Y+c.LookHeight, s.Z+hz*c.LookAhead*fast
+
+	if !cam.placed || simHypot(goalX-cam.X, goalZ-cam.Z) > CAMERA_SNAP_DISTANCE {
+		cam.X, cam.Y, cam.Z = goalX, goalY, goalZ
+		cam.VelocityX, cam.VelocityY, cam.VelocityZ = 0, 0, 0
+		cam.FOV = c.FOV + c.SpeedFOV*fast
+		cam.placed = true
+	}
+
+	// Spring towards the goal in fixed substeps so the swing does not
+	// depend on the frame rate
+	if dt > 0 {
+		dt = math.Min(dt, maxPhysicsDelta)
+		damping := 2 * c.Damping * math.Sqrt(c.Stiffness)
+		for left := dt; left > 0; left -= cameraSubstep {
+			step := math.Min(left, cameraSubstep)
+			cam.VelocityX += (c.Stiffness*(goalX-cam.X) - damping*cam.VelocityX) * step
+			cam.VelocityY += (c.Stiffness*(goalY-cam.Y) - damping*cam.VelocityY) * step
+			cam.VelocityZ += (c.Stiffness*(goalZ-cam.Z) - damping*cam.VelocityZ) * step
+			cam.X += cam.VelocityX * step
+			cam.Y += cam.VelocityY * step
+			cam.Z += cam.VelocityZ * step
+		}
+		cam.FOV += (c.FOV + c.SpeedFOV*fast - cam.FOV) * (1 - simExp(-CAMERA_FOV_RATE*dt))
+	}
+
+	view := CameraView{X: cam.X, Y: cam.Y, Z: cam.Z, LookX: lookX, LookY: lookY, LookZ: lookZ, FOV: cam.FOV}
+	w.clearCameraView(&view, c.Clearance)
+
+	dx, dy, dz := view.LookX-view.X, view.LookY-view.Y, view.LookZ-view.Z
+	view.Yaw = math.Atan2(dx, dz)
+	view.Pitch = math.Atan2(dy, simHypot(dx, dz))
+	return view, true
+}
+
+// clearCameraView moves the camera in front of anything between it and
+// what it looks at, and up off the ground
+func (w *World) clearCameraView(view *CameraView, clearance float64) {
+	dx, dy, dz := view.X-view.LookX, view.Y-view.LookY, view.Z-view.LookZ
+	length := math.Sqrt(dx*dx + dy*dy + dz*dz)
+	if length > 1e-9 {
+		if hit, ok := w.Raycast(view.LookX, view.LookY, view.LookZ, dx, dy, dz, length); ok {
+			reach := math.Max(hit.Distance-clearance, 0) / length
+			view.X, view.Y, view.Z = view.LookX+dx*reach, view.LookY+dy*reach, view.LookZ+dz*reach
+		}
+	}
+	view.Y = math.Max(view.Y, w.groundHeight(view.X, view.Z)+clearance)
+}
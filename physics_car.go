@@ -0,0 +1,642 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Car Physics
+// ============================================================================
+
+// Physics timing
+// The original constants were tuned per frame at 60Hz; they are now expressed
+// per second so the simulation no longer depends on the caller's frame rate.
+const (
+	referenceFrameTime = 1.0 / 60.0 // Frame time the friction constant is defined against
+	maxPhysicsSubstep  = 1.0 / 60.0 // Larger dt values are split into substeps of this size
+	maxPhysicsDelta    = 0.25       // Clamp for huge dt values (tab switches, debugger pauses)
+)
+
+// Default car physics constants (per-second units)
+// These make up DefaultVehicleConfig; presets override them per vehicle
+const (
+	MASS         = 1000.0 // Used for collision response between vehicles
+	ACCELERATION = 18.0   // units/s² (was 0.005 per frame)
+	MAX_SPEED    = 12.0   // units/s (was 0.2 per frame)
+	FRICTION     = 0.98   // Forward velocity retained per reference frame, applied as FRICTION^(dt/referenceFrameTime)
+	BRAKE_POWER  = 36.0   // units/s² (was 0.01 per frame)
+	ROTATE_SPEED = 2.4    // rad/s, upper bound on turn rate (was 0.04 per frame)
+	STOP_SPEED   = 0.06   // units/s, below this the car comes to rest
+
+	REVERSE_ACCELERATION = 9.0 // units/s², reverse gear pulls slower than forward
+	REVERSE_MAX_SPEED    = 4.0 // units/s, reverse gear speed cap
+
+	LATERAL_GRIP         = 60.0 // units/s², max sideways deceleration the tires can provide
+	HANDBRAKE_GRIP       = 0.15 // Fraction of lateral grip left with the handbrake held (car slides)
+	HANDBRAKE_BRAKE      = 6.0  // units/s², handbrake slows the car only gently
+	HANDBRAKE_TURN_BOOST = 1.5  // Yaw rate multiplier while the rear is sliding
+
+	MIN_TURN_RADIUS = 3.0  // units, tightest turn radius (at low speed)
+	MAX_TURN_RADIUS = 10.0 // units, widest turn radius (at max speed)
+	WHEELBASE       = 1.6  // units, distance between front and rear axles
+	TRACK_WIDTH     = 1.0  // units, distance between left and right wheels
+)
+
+// VehicleConfig holds per-vehicle tuning for the car physics step
+type VehicleConfig struct {
+	Mass                float64 // Relative mass for collision response
+	Acceleration        float64 // units/s²
+	MaxSpeed            float64 // units/s
+	ReverseAcceleration float64 // units/s²
+	ReverseMaxSpeed     float64 // units/s
+	Friction            float64 // Forward (rolling) velocity retained per reference frame (0..1]
+	LateralGrip         float64 // units/s², sideways tire grip
+	HandbrakeGrip       float64 // Fraction of LateralGrip kept with the handbrake held (0..1]
+	BrakePower          float64 // units/s²
+	TurnRate            float64 // rad/s, upper bound on yaw rate
+	MinTurnRadius       float64 // units, turn radius at low speed
+	MaxTurnRadius       float64 // units, turn radius at max speed
+	Wheelbase           float64 // units, front-to-rear axle distance
+	TrackWidth          float64 // units, left-to-right wheel distance
+	BodyRadius          float64 // units, collision radius of the body
+	FuelCapacity        float64 // Fuel units in a full tank; 0 for none, never running dry (physics_fuel.go)
+	FuelUse             float64 // Fuel units/s at full load and top speed
+	TwoWheeler          bool    // Leans into turns and rides bike lanes (physics_two_wheelers.go)
+	SuspensionFrequency float64 // Hz, natural frequency of the body on its springs (physics_suspension.go)
+	WeightTransfer      float64 // rad of body pitch or roll per GRAVITY of acceleration
+	Gearbox             GearboxConfig
+}
+
+// DefaultVehicleConfig returns the standard car tuning
+func DefaultVehicleConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                MASS,
+		Acceleration:        ACCELERATION,
+		MaxSpeed:            MAX_SPEED,
+		ReverseAcceleration: REVERSE_ACCELERATION,
+		ReverseMaxSpeed:     REVERSE_MAX_SPEED,
+		Friction:            FRICTION,
+		LateralGrip:         LATERAL_GRIP,
+		HandbrakeGrip:       HANDBRAKE_GRIP,
+		BrakePower:          BRAKE_POWER,
+		TurnRate:            ROTATE_SPEED,
+		MinTurnRadius:       MIN_TURN_RADIUS,
+		MaxTurnRadius:       MAX_TURN_RADIUS,
+		Wheelbase:           WHEELBASE,
+		TrackWidth:          TRACK_WIDTH,
+		BodyRadius:          BODY_RADIUS,
+		FuelUse:             FUEL_USE,
+		SuspensionFrequency: SUSPENSION_FREQUENCY,
+		WeightTransfer:      WEIGHT_TRANSFER,
+		Gearbox:             DefaultGearboxConfig(),
+	}
+}
+
+// SportsCarConfig returns tuning for a light, fast and agile car
+func SportsCarConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                1200.0,
+		Acceleration:        26.0,
+		MaxSpeed:            18.0,
+		ReverseAcceleration: 10.0,
+		ReverseMaxSpeed:     5.0,
+		Friction:            0.985,
+		LateralGrip:         80.0,
+		HandbrakeGrip:       0.12,
+		BrakePower:          48.0,
+		TurnRate:            2.8,
+		MinTurnRadius:       3.5,
+		MaxTurnRadius:       14.0,
+		Wheelbase:           1.5,
+		TrackWidth:          1.0,
+		BodyRadius:          0.9,
+		SuspensionFrequency: 2.0,
+		WeightTransfer:      0.035,
+		Gearbox: GearboxConfig{
+			GearRatios:   []float64{3.5, 2.4, 1.8, 1.4, 1.1, 0.9},
+			IdleRPM:      900.0,
+			RedlineRPM:   8000.0,
+			ShiftUpRPM:   7200.0,
+			ShiftDownRPM: 3000.0,
+		},
+	}
+}
+
+// DeliveryTruckConfig returns tuning for a heavy, slow vehicle with a wide turn
+func DeliveryTruckConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                3500.0,
+		Acceleration:        10.0,
+		MaxSpeed:            9.0,
+		ReverseAcceleration: 6.0,
+		ReverseMaxSpeed:     3.0,
+		Friction:            0.975,
+		LateralGrip:         45.0,
+		HandbrakeGrip:       0.25,
+		BrakePower:          24.0,
+		TurnRate:            1.6,
+		MinTurnRadius:       5.0,
+		MaxTurnRadius:       14.0,
+		Wheelbase:           2.6,
+		TrackWidth:          1.3,
+		BodyRadius:          1.3,
+		SuspensionFrequency: 1.2,
+		WeightTransfer:      0.08,
+		Gearbox: GearboxConfig{
+			GearRatios:   []float64{4.5, 2.8, 1.8, 1.3, 1.0},
+			IdleRPM:      650.0,
+			RedlineRPM:   3500.0,
+			ShiftUpRPM:   3000.0,
+			ShiftDownRPM: 1300.0,
+		},
+	}
+}
+
+// BusConfig returns tuning for a very heavy vehicle with the widest turn
+func BusConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                12000.0,
+		Acceleration:        7.0,
+		MaxSpeed:            8.0,
+		ReverseAcceleration: 4.0,
+		ReverseMaxSpeed:     2.5,
+		Friction:            0.975,
+		LateralGrip:         35.0,
+		HandbrakeGrip:       0.3,
+		BrakePower:          20.0,
+		TurnRate:            1.2,
+		MinTurnRadius:       7.0,
+		MaxTurnRadius:       18.0,
+		Wheelbase:           4.0,
+		TrackWidth:          1.5,
+		BodyRadius:          1.8,
+		SuspensionFrequency: 1.0,
+		WeightTransfer:      0.1,
+		Gearbox: GearboxConfig{
+			GearRatios:   []float64{3.8, 2.2, 1.5, 1.0},
+			IdleRPM:      600.0,
+			RedlineRPM:   2600.0,
+			ShiftUpRPM:   2200.0,
+			ShiftDownRPM: 1100.0,
+		},
+	}
+}
+
+// vehiclePresets maps preset names (as used from JavaScript) to their tuning
+var vehiclePresets = map[string]func() VehicleConfig{
+	"car":            DefaultVehicleConfig,
+	"sports_car":     SportsCarConfig,
+	"delivery_truck": DeliveryTruckConfig,
+	"bus":            BusConfig,
+	"bicycle":        BicycleConfig,
+	"motorcycle":     MotorcycleConfig,
+}
+
+// VehiclePreset looks up a built-in vehicle tuning by name
+func VehiclePreset(name string) (VehicleConfig, bool) {
+	preset, exists := vehiclePresets[name]
+	if !exists {
+		return VehicleConfig{}, false
+	}
+	return preset(), true
+}
+
+// positiveOr returns v if it is a positive finite number, otherwise fallback
+func positiveOr(v, fallback float64) float64 {
+	if v > 0 && !math.IsInf(v, 1) {
+		return v
+	}
+	return fallback
+}
+
+// sanitized replaces invalid tuning values with the defaults
+func (c VehicleConfig) sanitized() VehicleConfig {
+	d := DefaultVehicleConfig()
+	c.Mass = positiveOr(c.Mass, d.Mass)
+	c.Acceleration = positiveOr(c.Acceleration, d.Acceleration)
+	c.MaxSpeed = positiveOr(c.MaxSpeed, d.MaxSpeed)
+	c.ReverseAcceleration = positiveOr(c.ReverseAcceleration, d.ReverseAcceleration)
+	c.ReverseMaxSpeed = positiveOr(c.ReverseMaxSpeed, d.ReverseMaxSpeed)
+	c.BrakePower = positiveOr(c.BrakePower, d.BrakePower)
+	c.TurnRate = positiveOr(c.TurnRate, d.TurnRate)
+	c.MinTurnRadius = positiveOr(c.MinTurnRadius, d.MinTurnRadius)
+	c.MaxTurnRadius = math.Max(positiveOr(c.MaxTurnRadius, d.MaxTurnRadius), c.MinTurnRadius)
+	c.Wheelbase = positiveOr(c.Wheelbase, d.Wheelbase)
+	c.TrackWidth = positiveOr(c.TrackWidth, d.TrackWidth)
+	c.BodyRadius = positiveOr(c.BodyRadius, d.BodyRadius)
+	if !(c.Friction > 0 && c.Friction <= 1) {
+		c.Friction = d.Friction
+	}
+	c.LateralGrip = positiveOr(c.LateralGrip, d.LateralGrip)
+	if !(c.HandbrakeGrip > 0 && c.HandbrakeGrip <= 1) {
+		c.HandbrakeGrip = d.HandbrakeGrip
+	}
+	if !(c.FuelCapacity > 0) || math.IsInf(c.FuelCapacity, 1) {
+		c.FuelCapacity = 0
+	}
+	c.FuelUse = positiveOr(c.FuelUse, d.FuelUse)
+	c.SuspensionFrequency = math.Min(positiveOr(c.SuspensionFrequency, d.SuspensionFrequency), MAX_SUSPENSION_FREQUENCY)
+	c.WeightTransfer = positiveOr(c.WeightTransfer, d.WeightTransfer)
+	c.Gearbox = c.Gearbox.sanitized()
+	return c
+}
+
+// CarState represents the state of a car for physics simulation
+// Velocities are in units per second
+type CarState struct {
+	X, Z      float64
+	Y         float64     // Terrain height under the car (output only)
+	Pitch     float64     // Body pitch from the terrain slope (rad, output only)
+	Roll      float64     // Body roll from the terrain slope (rad, output only)
+	RotationY float64
+	VelocityX float64
+	VelocityZ float64
+	Reversing bool        // Reverse gear engaged (drives reverse lights in the UI)
+	SlipAngle float64     // Signed angle (rad) between heading and velocity, for skid effects
+	Surface   SurfaceType // Ground under the car during the last step
+	Damage    float64     // 0..1 from collision impacts, 1 = wrecked (physics_damage.go)
+	FuelUsed  float64     // Fuel burned since the tank was filled (physics_fuel.go)
+	Lean      float64     // Two-wheeler lean (rad, positive = right, as Roll; physics_two_wheelers.go)
+	Odometer  float64     // units driven
+
+	// Skid state (physics_skids.go)
+	Skidding      bool    // Skidded during the last step; a skid starting next step begins new marks
+	SkidIntensity float64 // 0..1 during the last step (output only)
+
+	// Body motion on the suspension (physics_suspension.go), added to the
+	// terrain pose for rendering
+	Bounce         float64 // units above (+) or below the ride height
+	BounceVelocity float64 // units/s
+	BodyPitch      float64 // rad, nose up positive, as Pitch
+	BodyPitchRate  float64 // rad/s
+	BodyRoll       float64 // rad, left side up positive, as Roll
+	BodyRollRate   float64 // rad/s
+
+	// Entity ID reported in collision events (physics_events.go), -1 if unset
+	ID int
+
+	// Static collision outputs (see physics_collision.go)
+	Collided     bool // Touched a static collider during the last step
+	CollidedWith int  // ID of the first collider touched, -1 if none
+	HitBoundary  bool // Reached the world edge during the last step (physics_bounds.go)
+	HitWater     bool // Stopped at the shore of open water during the last step (physics_water.go)
+
+	// Steering outputs for rendering the front wheels (rad, positive = left,
+	// same sign convention as RotationY)
+	SteerAngle      float64 // Bicycle-model steering angle at the front axle center
+	WheelAngleLeft  float64 // Ackermann angle of the front-left wheel
+	WheelAngleRight float64 // Ackermann angle of the front-right wheel
+
+	// Drivetrain outputs (see physics_drivetrain.go)
+	Gear         int     // GEAR_REVERSE or 1..N
+	RPM          float64 // Engine speed, for audio pitch and the tachometer
+	ThrottleLoad float64 // 0..1, how hard the engine is working
+	ShiftTimer   float64 // s remaining in the current gear change
+
+	// Real-world readouts at the world scale (physics_units.go, output only)
+	SpeedKmh      float64
+	SpeedMph      float64
+	OdometerKm    float64
+	OdometerMiles float64
+}
+
+// InputState represents analog player input for car control
+type InputState struct {
+	Throttle  float64 // 0..1, drives forward
+	Brake     float64 // 0..1, brakes while rolling forward, otherwise reverses
+	Steering  float64 // -1 (full left) .. 1 (full right)
+	Handbrake bool
+
+	// Speed controllers working the pedals (physics_cruise.go)
+	CruiseSpeed  float64 // units/s to hold, 0 = off
+	SpeedLimiter bool    // Never exceed the speed limit of the road under the car
+}
+
+// DigitalInput represents boolean (keyboard) input for car control
+type DigitalInput struct {
+	Forward   bool
+	Backward  bool
+	Left      bool
+	Right     bool
+	Handbrake bool
+}
+
+// Analog converts boolean input to full-deflection analog input
+func (d DigitalInput) Analog() InputState {
+	input := InputState{Handbrake: d.Handbrake}
+	if d.Forward {
+		input.Throttle = 1.0
+	}
+	if d.Backward {
+		input.Brake = 1.0
+	}
+	if d.Left {
+		input.Steering -= 1.0
+	}
+	if d.Right {
+		input.Steering += 1.0
+	}
+	return input
+}
+
+// clamp restricts v to [lo, hi], mapping NaN to lo
+func clamp(v, lo, hi float64) float64 {
+	if !(v >= lo) {
+		return lo
+	}
+	if v > hi {
+		return hi
+	}
+	return v
+}
+
+// clamped returns the input with every axis restricted to its valid range
+func (in InputState) clamped() InputState {
+	in.Throttle = clamp(in.Throttle, 0, 1)
+	in.Brake = clamp(in.Brake, 0, 1)
+	in.Steering = clamp(in.Steering, -1, 1)
+	if !(in.CruiseSpeed > 0) || math.IsInf(in.CruiseSpeed, 1) {
+		in.CruiseSpeed = 0
+	}
+	return in
+}
+
+// normalizeAngle wraps an angle into [-π, π]
+func normalizeAngle(a float64) float64 {
+	return math.Remainder(a, 2*math.Pi)
+}
+
+// isFinite reports whether v is neither NaN nor infinite
+func isFinite(v float64) bool {
+	return !math.IsNaN(v) && !math.IsInf(v, 0)
+}
+
+// sanitize resets non-finite state components to zero so a single bad value
+// (e.g. from a corrupted save) cannot propagate forever
+// Returns a description of every component that had to be reset (nil if none)
+func (car *CarState) sanitize() []string {
+	var errors []string
+	fields := []struct {
+		name  string
+		value *float64
+	}{
+		{"x", &car.X},
+		{"z", &car.Z},
+		{"rotation_y", &car.RotationY},
+		{"velocity_x", &car.VelocityX},
+		{"velocity_z", &car.VelocityZ},
+		{"rpm", &car.RPM},
+		{"shift_timer", &car.ShiftTimer},
+		{"damage", &car.Damage},
+		{"fuel_used", &car.FuelUsed},
+		{"lean", &car.Lean},
+		{"bounce", &car.Bounce},
+		{"bounce_velocity", &car.BounceVelocity},
+		{"body_pitch", &car.BodyPitch},
+		{"body_pitch_rate", &car.BodyPitchRate},
+		{"body_roll", &car.BodyRoll},
+		{"body_roll_rate", &car.BodyRollRate},
+		{"odometer", &car.Odometer},
+	}
+
+	for _, field := range fields {
+		if !isFinite(*field.value) {
+			errors = append(errors, field.name+" was not finite and has been reset to 0")
+			*field.value = 0
+		}
+	}
+
+	car.RotationY = normalizeAngle(car.RotationY)
+	car.Damage = clamp(car.Damage, 0, 1)
+	car.FuelUsed = math.Max(car.FuelUsed, 0)
+	car.Odometer = math.Max(car.Odometer, 0)
+	car.Lean = clamp(car.Lean, -MAX_LEAN_ANGLE, MAX_LEAN_ANGLE)
+	car.Bounce = clamp(car.Bounce, -MAX_BOUNCE, MAX_BOUNCE)
+	car.BodyPitch = clamp(car.BodyPitch, -MAX_BODY_ANGLE, MAX_BODY_ANGLE)
+	car.BodyRoll = clamp(car.BodyRoll, -MAX_BODY_ANGLE, MAX_BODY_ANGLE)
+	return errors
+}
+
+// stepCar advances the car by dt seconds using the given vehicle tuning,
+// driving on the world's terrain and surfaces and against its colliders
+// Large steps are split into fixed-size substeps so low frame rates stay stable
+// Returns any sanitization errors for the incoming state (nil if none)
+func (w *World) stepCar(car *CarState, input InputState, cfg *VehicleConfig, dt float64) []string {
+	errors := car.sanitize()
+	if !(dt > 0) {
+		return errors // Also rejects NaN
+	}
+	if dt > maxPhysicsDelta {
+		dt = maxPhysicsDelta
+	}
+	input = input.clamped()
+
+	// Collision outputs cover the whole frame, not just the last substep
+	car.Collided = false
+	car.CollidedWith = -1
+	car.HitBoundary = false
+	car.HitWater = false
+
+	skid := 0.0
+	for dt > 0 {
+		step := math.Min(dt, maxPhysicsSubstep)
+
+		// Handling depends on the ground under the car (physics_surface.go),
+		// the weather (physics_weather.go), the season (physics_seasons.go),
+		// the wear of the road (physics_road_wear.go), the car's damage
+		// (physics_damage.go), its fuel (physics_fuel.go) and how far a
+		// two-wheeler leans (physics_two_wheelers.go)
+		car.Surface = w.surfaceAt(car.X, car.Z)
+		eff := cfg.onSurface(car.Surface)
+		w.Weather.adjust(&eff)
+		w.Clock.seasonAdjust(&eff, car.Surface)
+		w.roadAdjust(&eff, car.X, car.Z, car.Surface)
+		car.damageAdjust(&eff)
+		car.fuelAdjust(&eff, cfg)
+		car.leanAdjust(&eff)
+
+		// Cruise control and the speed limiter work the pedals (physics_cruise.go)
+		pedals := w.cruiseInput(car, input, &eff)
+
+		prevX, prevZ, heading := car.X, car.Z, car.RotationY
+		prevVX, prevVZ := car.VelocityX, car.VelocityZ
+		w.stepCarOnce(car, pedals, &eff, step)
+		car.RotationY = normalizeAngle(car.RotationY)
+		car.updateLean(&eff, normalizeAngle(car.RotationY-heading)/step, step)
+		w.crossRoadFeatures(car, prevX, prevZ)
+		car.stepSuspension(cfg, prevVX, prevVZ, step)
+		skid = math.Max(skid, car.skidIntensity(pedals, prevVX, prevVZ, step))
+		updateDrivetrain(car, pedals, cfg, step)
+		car.burnFuel(cfg, step)
+		car.Odometer += simHypot(car.X-prevX, car.Z-prevZ)
+		dt -= step
+	}
+	w.markSkid(car, cfg, skid)
+	car.updateReadouts(w.MetersPerUnit)
+	return errors
+}
+
+// maxSteerAngle returns the full-lock steering angle (rad) at a given speed
+// The turn radius widens linearly from min to max as speed approaches the
+// vehicle's max speed, so the car becomes less twitchy at high speed
+func (cfg *VehicleConfig) maxSteerAngle(speed float64) float64 {
+	t := math.Min(math.Abs(speed)/cfg.MaxSpeed, 1.0)
+	radius := cfg.MinTurnRadius + (cfg.MaxTurnRadius-cfg.MinTurnRadius)*t
+	return math.Atan(cfg.Wheelbase / radius)
+}
+
+// ackermannAngles returns per-wheel front steering angles for a bicycle-model
+// steering angle: the inner wheel turns tighter so both follow the same center
+func ackermannAngles(steer, wheelbase, track float64) (left, right float64) {
+	if steer == 0 {
+		return 0, 0
+	}
+
+	radius := wheelbase / math.Tan(math.Abs(steer)) // Turn radius at the rear axle center
+	inner := math.Atan(wheelbase / math.Max(radius-track/2, 1e-3))
+	outer := math.Atan(wheelbase / (radius + track/2))
+	if steer > 0 {
+		return inner, outer // Turning left: left wheel is on the inside
+	}
+	return -outer, -inner
+}
+
+// stepCarOnce integrates a single substep of car physics
+func (w *World) stepCarOnce(car *CarState, input InputState, cfg *VehicleConfig, dt float64) {
+	// Handle steering (turn rate depends on current speed)
+	forwardX := math.Sin(car.RotationY)
+	forwardZ := math.Cos(car.RotationY)
+	forwardSpeed := car.VelocityX*forwardX + car.VelocityZ*forwardZ
+
+	// Bicycle model: yaw rate = v * tan(δ) / wheelbase, so turning radius is
+	// consistent with speed, the car cannot spin in place, and steering
+	// inverts naturally when reversing
+	car.SteerAngle = -input.Steering * cfg.maxSteerAngle(forwardSpeed)
+	car.WheelAngleLeft, car.WheelAngleRight = ackermannAngles(car.SteerAngle, cfg.Wheelbase, cfg.TrackWidth)
+
+	yawRate := forwardSpeed * math.Tan(car.SteerAngle) / cfg.Wheelbase
+	yawRate = clamp(yawRate, -cfg.TurnRate, cfg.TurnRate)
+	if input.Handbrake {
+		yawRate *= HANDBRAKE_TURN_BOOST
+	}
+	car.RotationY += yawRate * dt
+
+	// Recalculate forward vector based on new rotation
+	forwardX = math.Sin(car.RotationY)
+	forwardZ = math.Cos(car.RotationY)
+
+	// Handle throttle/braking with a distinct reverse gear:
+	// - Throttle brakes while rolling backward, otherwise drives forward
+	// - Brake brakes while rolling forward, otherwise engages reverse
+	forwardSpeed = car.VelocityX*forwardX + car.VelocityZ*forwardZ
+
+	if input.Throttle > 0 {
+		if car.Reversing && forwardSpeed < -STOP_SPEED {
+			car.applyBrake(cfg.BrakePower * input.Throttle * dt)
+		} else {
+			car.Reversing = false
+			car.VelocityX += forwardX * cfg.Acceleration * input.Throttle * dt
+			car.VelocityZ += forwardZ * cfg.Acceleration * input.Throttle * dt
+		}
+	}
+
+	if input.Brake > 0 {
+		if !car.Reversing && forwardSpeed > STOP_SPEED {
+			car.applyBrake(cfg.BrakePower * input.Brake * dt)
+		} else {
+			car.Reversing = true
+			car.VelocityX -= forwardX * cfg.ReverseAcceleration * input.Brake * dt
+			car.VelocityZ -= forwardZ * cfg.ReverseAcceleration * input.Brake * dt
+		}
+	}
+
+	// Slopes slow uphill driving and speed up downhill (physics_terrain.go)
+	w.applySlopeGravity(car, dt)
+
+	// Handbrake locks the rear wheels: gentle braking and much less grip
+	if input.Handbrake {
+		car.applyBrake(HANDBRAKE_BRAKE * dt)
+	}
+	car.applyTireFriction(cfg, input.Handbrake, dt)
+
+	// Clamp speed to the current gear's max
+	maxSpeed := cfg.MaxSpeed
+	if car.Reversing {
+		maxSpeed = cfg.ReverseMaxSpeed
+	}
+	speed := math.Sqrt(car.VelocityX*car.VelocityX + car.VelocityZ*car.VelocityZ)
+	if speed > maxSpeed {
+		car.VelocityX = (car.VelocityX / speed) * maxSpeed
+		car.VelocityZ = (car.VelocityZ / speed) * maxSpeed
+	}
+
+	// Stop tiny movements
+	if speed < STOP_SPEED && input.Throttle == 0 && input.Brake == 0 {
+		car.VelocityX = 0.0
+		car.VelocityZ = 0.0
+	}
+
+	// Integrate position, stopping/sliding against buildings and props along
+	// the way (physics_collision.go) and at the shore (physics_water.go)
+	prevX, prevZ := car.X, car.Z
+	w.moveCar(car, cfg, dt)
+	w.resolveStaticCollisions(car, cfg, dt)
+	w.applyWorldBounds(car)
+	w.applyWater(car, prevX, prevZ)
+
+	car.SlipAngle = car.slipAngle()
+	w.updateTerrainPose(car)
+}
+
+// headingBasis returns the travel-direction heading (flipped when rolling
+// backward) and the lateral axis, plus the velocity components along each
+func (car *CarState) headingBasis() (hx, hz, rx, rz, along, lateral float64) {
+	hx, hz = math.Sin(car.RotationY), math.Cos(car.RotationY)
+	rx, rz = hz, -hx
+	along = car.VelocityX*hx + car.VelocityZ*hz
+	if along < 0 {
+		hx, hz, along = -hx, -hz, -along
+	}
+	lateral = car.VelocityX*rx + car.VelocityZ*rz
+	return
+}
+
+// slipAngle returns the signed angle between the heading and the velocity
+func (car *CarState) slipAngle() float64 {
+	if car.VelocityX == 0 && car.VelocityZ == 0 {
+		return 0
+	}
+	_, _, _, _, along, lateral := car.headingBasis()
+	return math.Atan2(lateral, along)
+}
+
+// applyTireFriction splits velocity into forward and lateral components
+// relative to the heading and applies separate friction to each:
+// - Forward: rolling resistance (exponential decay keeps it frame-rate independent)
+// - Lateral: tire grip, capped at LateralGrip so hard turns can still slide
+func (car *CarState) applyTireFriction(cfg *VehicleConfig, handbrake bool, dt float64) {
+	hx, hz := math.Sin(car.RotationY), math.Cos(car.RotationY)
+	rx, rz := hz, -hx
+	forward := car.VelocityX*hx + car.VelocityZ*hz
+	lateral := car.VelocityX*rx + car.VelocityZ*rz
+
+	forward *= simPow(cfg.Friction, dt/referenceFrameTime)
+
+	grip := cfg.LateralGrip
+	if handbrake {
+		grip *= cfg.HandbrakeGrip
+	}
+	lateral -= math.Copysign(math.Min(math.Abs(lateral), grip*dt), lateral)
+
+	car.VelocityX = forward*hx + lateral*rx
+	car.VelocityZ = forward*hz + lateral*rz
+}
+
+// applyBrake reduces speed by up to amount without reversing direction
+func (car *CarState) applyBrake(amount float64) {
+	speed := math.Sqrt(car.VelocityX*car.VelocityX + car.VelocityZ*car.VelocityZ)
+	if speed <= 0.0 {
+		return
+	}
+	brake := math.Min(amount, speed)
+	car.VelocityX -= (car.VelocityX / speed) * brake
+	car.VelocityZ -= (car.VelocityZ / speed) * brake
+}
@@ -0,0 +1,369 @@
+package main
+
+// ============================================================================
+// Citizen Schedules (home, work, shops and parks through the day)
+// ============================================================================
+
+// Citizens are individual residents, up to MaxCitizens of them, drawn from
+// the homes in proportion to who lives there and kept in step with the
+// census (physics_population.go). A citizen has a job at a workplace as
+// often as residents are employed. Each day on the world clock
+// (physics_clock.go) they plan what to do: workers leave home for work
+// around CITIZEN_WORK_START and stay CITIZEN_WORK_HOURS, everyone else runs
+// errands from CITIZEN_ERRAND_START; then maybe a market while stores are
+// open, maybe a park, and home by CITIZEN_HOME_HOUR. Each leg is a real
+// journey: walked along the sidewalks as a pedestrian when shorter than
+// WalkDistance or when MaxCars citizens are already driving, otherwise
+// driven by an AI car between the road nodes nearest each end. The agent is
+// removed on arrival and the citizen stays at the place until their next
+// leg is due, so traffic and foot traffic rise and fall with the schedules.
+// Generated trips (physics_trips.go) carry on alongside; turn them off for
+// traffic from schedules alone. Citizens start disabled.
+
+const (
+	MAX_CITIZENS          = 40      // Citizens followed at once
+	MAX_CITIZEN_CARS      = 16      // Citizens driving at once; the rest walk
+	CITIZEN_WALK_DISTANCE = 30.0    // units, legs shorter than this are walked
+	CITIZEN_WORK_START    = 7.0     // Earliest hour workers leave for work
+	CITIZEN_ERRAND_START  = 9.0     // Earliest hour everyone else goes out
+	CITIZEN_START_SPREAD  = 2.0     // Hours over which the day's first leg is spread
+	CITIZEN_WORK_HOURS    = 8.0     // Hours at work
+	CITIZEN_SHOP_CHANCE   = 0.4     // Share of days with a visit to a market
+	CITIZEN_SHOP_HOURS    = 1.0     // Hours at a market
+	CITIZEN_PARK_CHANCE   = 0.3     // Share of days with a visit to a park
+	CITIZEN_PARK_HOURS    = 1.5     // Hours at a park
+	CITIZEN_HOME_HOUR     = 22.0    // Latest hour citizens set off home
+	CITIZEN_AGENT_ID_BASE = 1 << 24 // Car and pedestrian IDs from here up are citizens travelling
+	CITIZEN_SEED          = 8       // Seed for homes, jobs and plans (schedules repeat run to run)
+)
+
+// CitizenActivity is what a citizen does at a place
+type CitizenActivity uint8
+
+const (
+	ActivityHome CitizenActivity = iota
+	ActivityWork
+	ActivityShopping
+	ActivityLeisure
+	citizenActivityCount
+)
+
+// citizenActivityNames are the JavaScript-facing names, indexed by CitizenActivity
+var citizenActivityNames = [citizenActivityCount]string{"home", "work", "shopping", "leisure"}
+
+// String returns the JavaScript-facing activity name
+func (a CitizenActivity) String() string {
+	if a >= citizenActivityCount {
+		return citizenActivityNames[ActivityHome]
+	}
+	return citizenActivityNames[a]
+}
+
+// TravelMode is how a citizen is getting to their next place
+type TravelMode uint8
+
+const (
+	TravelNone TravelMode = iota // At a place
+	TravelWalking
+	TravelDriving
+	travelModeCount
+)
+
+// travelModeNames are the JavaScript-facing names, indexed by TravelMode
+var travelModeNames = [travelModeCount]string{"none", "walking", "driving"}
+
+// String returns the JavaScript-facing travel mode name
+func (m TravelMode) String() string {
+	if m >= travelModeCount {
+		return travelModeNames[TravelNone]
+	}
+	return travelModeNames[m]
+}
+
+// ScheduleEntry is one leg of a citizen's day: go to a place at an hour
+type ScheduleEntry struct {
+	Hour     float64 // Hour of day the leg is due
+	Activity CitizenActivity
+	Place    int // Placement ID
+}
+
+// Citizen is one resident living to a schedule
+type Citizen struct {
+	ID        int
+	Home      int // Home placement ID
+	Job       int // Workplace placement ID, -1 without a job
+	Activity  CitizenActivity
+	Place     int             // Placement they are at or heading to
+	Mode      TravelMode      // TravelNone while at Place
+	Agent     int             // Car or pedestrian ID while travelling, 0 otherwise
+	Travelled float64         // s on the current leg
+	Commute   float64         // s the last leg to work took, 0 before the first
+	Plan      []ScheduleEntry // The rest of the day's legs, next first
+	PlanDay   int             // Day the plan was made for, -1 before the first
+}
+
+// Citizens follows the town's scheduled residents
+type Citizens struct {
+	Enabled      bool
+	MaxCitizens  int
+	MaxCars      int
+	WalkDistance float64 // units
+	Vehicle      VehicleConfig
+
+	Trips     int // Legs started
+	Driven    int // Legs started by car
+	Walked    int // Legs started on foot
+	Completed int // Legs that reached their place
+
+	list      []*Citizen // In ID order
+	nextID    int
+	nextAgent int
+	rng       *SimRand
+}
+
+// NewCitizens creates disabled schedules without citizens
+func NewCitizens() *Citizens {
+	return &Citizens{
+		MaxCitizens:  MAX_CITIZENS,
+		MaxCars:      MAX_CITIZEN_CARS,
+		WalkDistance: CITIZEN_WALK_DISTANCE,
+		Vehicle:      DefaultVehicleConfig(),
+		nextID:       1,
+		nextAgent:    CITIZEN_AGENT_ID_BASE,
+		rng:          newSimRand(CITIZEN_SEED),
+	}
+}
+
+// List returns the citizens, in ID order
+func (c *Citizens) List() []*Citizen {
+	return c.list
+}
+
+// Driving returns how many citizens are on the road by car
+func (c *Citizens) Driving() int {
+	n := 0
+	for _, ct := range c.list {
+		if ct.Mode == TravelDriving {
+			n++
+		}
+	}
+	return n
+}
+
+// ClearCitizens removes every citizen and their agents and resets the
+// counters; citizens are drawn afresh on the next step while enabled
+func (w *World) ClearCitizens() {
+	c := w.Citizens
+	for _, ct := range c.list {
+		w.removeCitizenAgent(ct)
+	}
+	c.list = nil
+	c.Trips, c.Driven, c.Walked, c.Completed = 0, 0, 0, 0
+}
+
+// removeCitizenAgent takes a travelling citizen's car or pedestrian out
+func (w *World) removeCitizenAgent(ct *Citizen) {
+	switch ct.Mode {
+	case TravelDriving:
+		w.RemoveCar(ct.Agent)
+	case TravelWalking:
+		w.RemovePedestrian(ct.Agent)
+	}
+	ct.Mode, ct.Agent = TravelNone, 0
+}
+
+// stepCitizens lands the citizens who have arrived and, while enabled,
+// keeps the roster in step with the census and starts the legs due
+func (w *World) stepCitizens(dt float64) {
+	c := w.Citizens
+	for _, ct := range c.list {
+		w.stepCitizenTravel(ct, dt)
+	}
+	if !c.Enabled {
+		return
+	}
+
+	w.refreshCitizens()
+	day, hour := w.Clock.Day(), w.Clock.HourOfDay()
+	for _, ct := range c.list {
+		if ct.Mode != TravelNone {
+			continue
+		}
+		if ct.PlanDay < day && ct.Place == ct.Home {
+			w.planCitizenDay(ct, day)
+		}
+		for len(ct.Plan) > 0 && (ct.Plan[0].Hour <= hour || ct.PlanDay < day) {
+			e := ct.Plan[0]
+			ct.Plan = ct.Plan[1:]
+			if w.startCitizenLeg(ct, e) {
+				break
+			}
+		}
+	}
+}
+
+// stepCitizenTravel times a citizen's leg and ends it once their agent has
+// arrived or is gone; a car taken over from JavaScript is left to it
+func (w *World) stepCitizenTravel(ct *Citizen, dt float64) {
+	if ct.Mode != TravelNone {
+		ct.Travelled += dt
+	}
+	switch ct.Mode {
+	case TravelDriving:
+		car := w.Car(ct.Agent)
+		switch {
+		case car == nil || car.AI == nil:
+			// Removed or taken over from JavaScript
+		case car.AI.Arrived:
+			w.RemoveCar(ct.Agent)
+			w.parkArrival(ct.Place, ct.ID) // Until they set off again (physics_parking_lots.go)
+		default:
+			return
+		}
+	case TravelWalking:
+		p := w.Pedestrian(ct.Agent)
+		if p != nil && p.State != PedestrianArrived {
+			return
+		}
+		w.RemovePedestrian(ct.Agent)
+	default:
+		return
+	}
+	ct.Mode, ct.Agent = TravelNone, 0
+	if ct.Activity == ActivityWork {
+		ct.Commute = ct.Travelled
+	}
+	w.Citizens.Completed++
+}
+
+// refreshCitizens drops citizens whose home has fewer residents than
+// citizens, or is gone, and draws new ones up to the town's residents
+func (w *World) refreshCitizens() {
+	c := w.Citizens
+	living := make(map[int]int)
+	kept := c.list[:0]
+	for _, ct := range c.list {
+		if living[ct.Home] >= w.Population.HomeResidents(ct.Home) {
+			w.removeCitizenAgent(ct)
+			continue
+		}
+		living[ct.Home]++
+		if ct.Job >= 0 && w.Occupancy.Placement(ct.Job) == nil {
+			ct.Job = -1
+		}
+		if ct.Mode == TravelNone && w.Occupancy.Placement(ct.Place) == nil {
+			ct.Activity, ct.Place = ActivityHome, ct.Home // Sent home from a demolished place
+		}
+		kept = append(kept, ct)
+	}
+	c.list = kept
+
+	want := min(w.Population.Residents, max(c.MaxCitizens, 0))
+	for len(c.list) < want {
+		home := w.pickPlacement(c.rng, func(p *Placement) int {
+			return w.Population.HomeResidents(p.ID) - living[p.ID]
+		})
+		if home == nil {
+			return
+		}
+		living[home.ID]++
+		ct := &Citizen{ID: c.nextID, Home: home.ID, Job: -1, Place: home.ID, PlanDay: -1}
+		c.nextID++
+		if pop := w.Population; pop.Residents > 0 && c.rng.Float64() < float64(pop.Employed)/float64(pop.Residents) {
+			if job := w.pickPlacement(c.rng, func(p *Placement) int { return w.capacity(p).Jobs }); job != nil {
+				ct.Job = job.ID
+			}
+		}
+		c.list = append(c.list, ct)
+	}
+}
+
+// planCitizenDay lays out a citizen's legs for a day
+func (w *World) planCitizenDay(ct *Citizen, day int) {
+	c := w.Citizens
+	markets := func(p *Placement) int {
+		if w.placementUse(p) != "market" {
+			return 0
+		}
+		return w.capacity(p).Jobs
+	}
+	parks := func(p *Placement) int {
+		if p.Category != "park" {
+			return 0
+		}
+		return 1
+	}
+
+	ct.PlanDay, ct.Plan = day, nil
+	hour := CITIZEN_ERRAND_START + c.rng.Float64()*CITIZEN_START_SPREAD
+	if ct.Job >= 0 {
+		hour = CITIZEN_WORK_START + c.rng.Float64()*CITIZEN_START_SPREAD
+		ct.Plan = append(ct.Plan, ScheduleEntry{Hour: hour, Activity: ActivityWork, Place: ct.Job})
+		hour += CITIZEN_WORK_HOURS
+	}
+	if c.rng.Float64() < CITIZEN_SHOP_CHANCE && openAt(hour, w.Clock.StoreOpen, w.Clock.StoreClose) {
+		if market := w.pickPlacement(c.rng, markets); market != nil {
+			ct.Plan = append(ct.Plan, ScheduleEntry{Hour: hour, Activity: ActivityShopping, Place: market.ID})
+			hour += CITIZEN_SHOP_HOURS
+		}
+	}
+	if c.rng.Float64() < CITIZEN_PARK_CHANCE {
+		if park := w.pickPlacement(c.rng, parks); park != nil {
+			ct.Plan = append(ct.Plan, ScheduleEntry{Hour: hour, Activity: ActivityLeisure, Place: park.ID})
+			hour += CITIZEN_PARK_HOURS
+		}
+	}
+	if len(ct.Plan) > 0 {
+		ct.Plan = append(ct.Plan, ScheduleEntry{Hour: min(hour, CITIZEN_HOME_HOUR), Activity: ActivityHome, Place: ct.Home})
+	}
+}
+
+// startCitizenLeg sends a citizen to the place of a schedule entry, by car
+// or on foot, returning false if there is nothing to travel to
+func (w *World) startCitizenLeg(ct *Citizen, e ScheduleEntry) bool {
+	c := w.Citizens
+	dest := w.Occupancy.Placement(e.Place)
+	from := w.Occupancy.Placement(ct.Place)
+	if from == nil {
+		from = w.Occupancy.Placement(ct.Home)
+	}
+	if dest == nil || from == nil || dest.ID == from.ID {
+		return false
+	}
+	fx, fz := from.Center()
+	tx, tz := dest.Center()
+	w.releaseParking(ct.ID)
+
+	id := c.nextAgent
+	ct.Mode = TravelWalking
+	if simHypot(tx-fx, tz-fz) >= c.WalkDistance && c.Driving() < c.MaxCars {
+		start, goal := w.Roads.NearestNode(fx, fz), w.Roads.NearestNode(tx, tz)
+		if start != nil && goal != nil && start.ID != goal.ID && w.SpawnAICar(id, start.ID, goal.ID, c.Vehicle) == nil {
+			ct.Mode = TravelDriving
+		}
+	}
+	if ct.Mode == TravelWalking {
+		if w.SpawnPedestrian(id, fx, fz, tx, tz, 0, false) != nil {
+			_ = w.SpawnPedestrian(id, fx, fz, tx, tz, 0, true) // Direct walks cannot fail
+		}
+		c.Walked++
+	} else {
+		c.Driven++
+	}
+	c.nextAgent++
+	c.Trips++
+	ct.Agent, ct.Activity, ct.Place, ct.Travelled = id, e.Activity, e.Place, 0
+	return true
+}
+
+// Counts returns how many citizens are at each activity, not counting
+// those still travelling to it
+func (c *Citizens) Counts() [citizenActivityCount]int {
+	var counts [citizenActivityCount]int
+	for _, ct := range c.list {
+		if ct.Mode == TravelNone {
+			counts[ct.Activity]++
+		}
+	}
+	return counts
+}
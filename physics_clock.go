@@ -0,0 +1,113 @@
+package main
+
+import "math"
+
+// ============================================================================
+// World Clock (game time of day)
+// ============================================================================
+
+// The clock turns simulated seconds into game hours at a configurable rate.
+// Everything that follows the time of day reads it: the trip profile
+// (physics_trips.go), store opening hours, the end-of-day population and
+// budget updates, the seasons (physics_seasons.go) and the sun position JS
+// uses for lighting. The sun rises in the east (+X) at 06:00, is overhead at
+// noon and sets in the west at 18:00.
+
+const (
+	SECONDS_PER_GAME_HOUR = 2.5  // s of simulated time per game hour (a 60 s day)
+	HOURS_PER_DAY         = 24.0 // Game hours per day
+	START_HOUR            = 8.0  // Time of day a new world starts at
+	STORE_OPEN_HOUR       = 8.0  // Stores (market use) open
+	STORE_CLOSE_HOUR      = 20.0 // Stores close
+	TWILIGHT_ELEVATION    = 0.1  // Sine of sun elevation over which light fades in and out
+	SUN_TILT              = 0.35 // Southward lean of the sun path (toward -Z)
+)
+
+// WorldClock tracks game time
+type WorldClock struct {
+	SecondsPerHour float64 // s of simulated time per game hour
+	Hours          float64 // Game hours since midnight of day 0
+	StoreOpen      float64 // Hour of day stores open
+	StoreClose     float64 // Hour of day stores close (before StoreOpen for overnight hours)
+	DaysPerSeason  int     // Game days per season (physics_seasons.go)
+}
+
+// NewWorldClock creates a clock at START_HOUR on day 0
+func NewWorldClock() *WorldClock {
+	return &WorldClock{
+		SecondsPerHour: SECONDS_PER_GAME_HOUR,
+		Hours:          START_HOUR,
+		StoreOpen:      STORE_OPEN_HOUR,
+		StoreClose:     STORE_CLOSE_HOUR,
+		DaysPerSeason:  DAYS_PER_SEASON,
+	}
+}
+
+// Day returns the number of whole days elapsed
+func (c *WorldClock) Day() int {
+	return int(math.Floor(c.Hours / HOURS_PER_DAY))
+}
+
+// HourOfDay returns the time of day in hours, 0-24
+func (c *WorldClock) HourOfDay() float64 {
+	return c.Hours - float64(c.Day())*HOURS_PER_DAY
+}
+
+// DayProgress returns the time of day as a share of the day, 0-1
+func (c *WorldClock) DayProgress() float64 {
+	return c.HourOfDay() / HOURS_PER_DAY
+}
+
+// DayLength returns the simulated seconds in one game day
+func (c *WorldClock) DayLength() float64 {
+	return c.SecondsPerHour * HOURS_PER_DAY
+}
+
+// SetTime jumps to an hour of the current day (the day is kept)
+func (c *WorldClock) SetTime(hour float64) {
+	c.Hours = float64(c.Day())*HOURS_PER_DAY + clamp(hour, 0, math.Nextafter(HOURS_PER_DAY, 0))
+}
+
+// advance moves the clock by dt simulated seconds, returning how many days ended
+func (c *WorldClock) advance(dt float64) int {
+	day := c.Day()
+	c.Hours += dt / positiveOr(c.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	return c.Day() - day
+}
+
+// openAt reports whether hour lies in [openHour, closeHour), wrapping past midnight
+func openAt(hour, openHour, closeHour float64) bool {
+	if openHour <= closeHour {
+		return hour >= openHour && hour < closeHour
+	}
+	return hour >= openHour || hour < closeHour
+}
+
+// StoresOpen reports whether stores are open at the current time
+func (c *WorldClock) StoresOpen() bool {
+	return openAt(c.HourOfDay(), c.StoreOpen, c.StoreClose)
+}
+
+// SunDirection returns the unit vector from the ground toward the sun
+// (below the horizon at night)
+func (c *WorldClock) SunDirection() (x, y, z float64) {
+	angle := 2 * math.Pi * (c.HourOfDay() - 6) / HOURS_PER_DAY
+	x, y, z = math.Cos(angle), math.Sin(angle), -SUN_TILT
+	length := math.Sqrt(x*x + y*y + z*z)
+	return x / length, y / length, z / length
+}
+
+// Daylight returns how bright the day is, 0 (night) to 1 (full daylight),
+// fading smoothly through twilight
+func (c *WorldClock) Daylight() float64 {
+	_, elevation, _ := c.SunDirection()
+	return smoothstep(clamp((elevation+TWILIGHT_ELEVATION)/(2*TWILIGHT_ELEVATION), 0, 1))
+}
+
+// stepClock advances the clock, running the end-of-day updates for every
+// day that ends
+func (w *World) stepClock(dt float64) {
+	for days := w.Clock.advance(dt); days > 0; days-- {
+		w.endDay()
+	}
+}
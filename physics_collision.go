@@ -0,0 +1,552 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Collision World (static obstacles for vehicle physics)
+// ============================================================================
+
+const (
+	BODY_RADIUS              = 0.9  // units, default car body radius for collision
+	WALL_FRICTION            = 0.97 // Sliding velocity retained per reference frame while touching a wall
+	maxCollisionIterations   = 4    // Resolution passes per substep (handles corners)
+	COLLIDER_CELL_SIZE       = 4.0  // units, broadphase cell size (a typical building footprint)
+	collisionWorldInitialCap = 256
+)
+
+// ColliderShape selects which fields of a Collider describe its geometry
+type ColliderShape uint8
+
+const (
+	ShapeBox     ColliderShape = iota // Axis-aligned box (buildings)
+	ShapeCircle                       // Round footprint (trees, lamp posts)
+	ShapeCapsule                      // Segment swept by a radius (benches, fences)
+)
+
+// colliderShapeNames are the JavaScript-facing names, indexed by ColliderShape
+var colliderShapeNames = [...]string{"box", "circle", "capsule"}
+
+// colliderShapeFromString converts a shape name to its type (unknown names map to box)
+func colliderShapeFromString(name string) ColliderShape {
+	for i, n := range colliderShapeNames {
+		if n == name {
+			return ColliderShape(i)
+		}
+	}
+	return ShapeBox
+}
+
+// Collider is a static obstacle registered from JavaScript
+type Collider struct {
+	ID     int
+	Shape  ColliderShape
+	Box    BoundingBox // ShapeBox: X/Z extents (MinY/MaxY hold world Z)
+	X, Z   float64     // ShapeCircle: center; ShapeCapsule: first segment end
+	X2, Z2 float64     // ShapeCapsule: second segment end
+	Radius float64     // ShapeCircle and ShapeCapsule
+	Top    float64     // World Y of the top face for raycasts (+Inf when unbounded)
+}
+
+// Bounds returns the X/Z extents of the collider
+func (c *Collider) Bounds() BoundingBox {
+	switch c.Shape {
+	case ShapeCircle:
+		return BoundingBox{MinX: c.X - c.Radius, MinY: c.Z - c.Radius, MaxX: c.X + c.Radius, MaxY: c.Z + c.Radius}
+	case ShapeCapsule:
+		return BoundingBox{
+			MinX: math.Min(c.X, c.X2) - c.Radius,
+			MinY: math.Min(c.Z, c.Z2) - c.Radius,
+			MaxX: math.Max(c.X, c.X2) + c.Radius,
+			MaxY: math.Max(c.Z, c.Z2) + c.Radius,
+		}
+	default:
+		return c.Box
+	}
+}
+
+// contact tests a circular body against the collider
+func (c *Collider) contact(x, z, radius float64) (Contact, bool) {
+	switch c.Shape {
+	case ShapeCircle:
+		return circleVsCircle(x, z, radius, c.X, c.Z, c.Radius)
+	case ShapeCapsule:
+		px, pz := closestPointOnSegment(x, z, c.X, c.Z, c.X2, c.Z2)
+		return circleVsCircle(x, z, radius, px, pz, c.Radius)
+	default:
+		return circleVsAABB(x, z, radius, c.Box)
+	}
+}
+
+// Contact describes a car body touching a collider
+type Contact struct {
+	ColliderID       int
+	NormalX, NormalZ float64 // Unit vector pushing the car out of the collider
+	Depth            float64 // Penetration depth along the normal
+	PointX, PointZ   float64 // Contact point on the collider surface
+}
+
+// CollisionWorld holds static colliders (buildings, props, trees)
+// Colliders are kept in a slice for stable iteration order, with an index
+// map for O(1) lookup and removal. A spatial grid broadphase limits
+// narrow-phase tests to colliders near the query
+type CollisionWorld struct {
+	colliders []Collider
+	index     map[int]int  // Collider ID -> position in colliders
+	grid      *SpatialGrid // Broadphase over collider bounds
+	extent    BoundingBox  // Union of all collider bounds (only grows until Clear)
+}
+
+// NewCollisionWorld creates an empty collision world
+func NewCollisionWorld() *CollisionWorld {
+	return &CollisionWorld{
+		colliders: make([]Collider, 0, collisionWorldInitialCap),
+		index:     make(map[int]int, collisionWorldInitialCap),
+		grid:      NewSpatialGrid(COLLIDER_CELL_SIZE),
+		extent:    emptyBoundingBox(),
+	}
+}
+
+// emptyBoundingBox returns a box that contains nothing and grows on union
+func emptyBoundingBox() BoundingBox {
+	return BoundingBox{
+		MinX: math.Inf(1), MinY: math.Inf(1),
+		MaxX: math.Inf(-1), MaxY: math.Inf(-1),
+	}
+}
+
+// Add registers or replaces a collider
+// Colliders with non-finite bounds are ignored
+func (w *CollisionWorld) Add(c Collider) {
+	bounds := c.Bounds()
+	if !isFinite(bounds.MinX) || !isFinite(bounds.MinY) || !isFinite(bounds.MaxX) || !isFinite(bounds.MaxY) {
+		return
+	}
+
+	w.extent = BoundingBox{
+		MinX: math.Min(w.extent.MinX, bounds.MinX),
+		MinY: math.Min(w.extent.MinY, bounds.MinY),
+		MaxX: math.Max(w.extent.MaxX, bounds.MaxX),
+		MaxY: math.Max(w.extent.MaxY, bounds.MaxY),
+	}
+
+	if i, exists := w.index[c.ID]; exists {
+		w.grid.Remove(c.ID, w.colliders[i].Bounds())
+		w.colliders[i] = c
+		w.grid.Insert(c.ID, bounds)
+		return
+	}
+	w.index[c.ID] = len(w.colliders)
+	w.colliders = append(w.colliders, c)
+	w.grid.Insert(c.ID, bounds)
+}
+
+// Remove unregisters a collider, returning false if it was not registered
+func (w *CollisionWorld) Remove(id int) bool {
+	i, exists := w.index[id]
+	if !exists {
+		return false
+	}
+
+	w.grid.Remove(id, w.colliders[i].Bounds())
+
+	// Swap-remove to keep removal O(1)
+	last := len(w.colliders) - 1
+	if i != last {
+		w.colliders[i] = w.colliders[last]
+		w.index[w.colliders[i].ID] = i
+	}
+	w.colliders = w.colliders[:last]
+	delete(w.index, id)
+	return true
+}
+
+// Clear removes all colliders
+func (w *CollisionWorld) Clear() {
+	w.colliders = w.colliders[:0]
+	w.index = make(map[int]int, collisionWorldInitialCap)
+	w.grid.Clear()
+	w.extent = emptyBoundingBox()
+}
+
+// Collider returns the collider with the given ID, or nil
+func (w *CollisionWorld) Collider(id int) *Collider {
+	i, exists := w.index[id]
+	if !exists {
+		return nil
+	}
+	return &w.colliders[i]
+}
+
+// Len returns the number of registered colliders
+func (w *CollisionWorld) Len() int {
+	return len(w.colliders)
+}
+
+// Query returns the colliders whose broadphase cells overlap the box
+// The result may include colliders that do not actually touch the box
+func (w *CollisionWorld) Query(bbox BoundingBox) []*Collider {
+	// Clip to the occupied area so huge queries (long rays) stay cheap
+	bbox = BoundingBox{
+		MinX: math.Max(bbox.MinX, w.extent.MinX),
+		MinY: math.Max(bbox.MinY, w.extent.MinY),
+		MaxX: math.Min(bbox.MaxX, w.extent.MaxX),
+		MaxY: math.Min(bbox.MaxY, w.extent.MaxY),
+	}
+	if !(bbox.MinX <= bbox.MaxX && bbox.MinY <= bbox.MaxY) {
+		return nil
+	}
+
+	ids := w.grid.Query(bbox)
+	result := make([]*Collider, 0, len(ids))
+	for _, id := range ids {
+		if i, exists := w.index[id]; exists {
+			result = append(result, &w.colliders[i])
+		}
+	}
+	return result
+}
+
+// circleVsAABB tests a circle against a box, returning the contact if they overlap
+func circleVsAABB(cx, cz, radius float64, box BoundingBox) (Contact, bool) {
+	px := clamp(cx, box.MinX, box.MaxX)
+	pz := clamp(cz, box.MinY, box.MaxY)
+	dx, dz := cx-px, cz-pz
+	distSq := dx*dx + dz*dz
+	if distSq > radius*radius {
+		return Contact{}, false
+	}
+
+	if distSq > 1e-12 {
+		dist := math.Sqrt(distSq)
+		return Contact{
+			NormalX: dx / dist,
+			NormalZ: dz / dist,
+			Depth:   radius - dist,
+			PointX:  px,
+			PointZ:  pz,
+		}, true
+	}
+
+	// Center is inside the box: push out through the nearest face
+	contact := Contact{NormalX: -1, Depth: cx - box.MinX, PointX: box.MinX, PointZ: cz}
+	if d := box.MaxX - cx; d < contact.Depth {
+		contact = Contact{NormalX: 1, Depth: d, PointX: box.MaxX, PointZ: cz}
+	}
+	if d := cz - box.MinY; d < contact.Depth {
+		contact = Contact{NormalZ: -1, Depth: d, PointX: cx, PointZ: box.MinY}
+	}
+	if d := box.MaxY - cz; d < contact.Depth {
+		contact = Contact{NormalZ: 1, Depth: d, PointX: cx, PointZ: box.MaxY}
+	}
+	contact.Depth += radius
+	return contact, true
+}
+
+// circleVsCircle tests a body circle against an obstacle circle centered at (ox, oz)
+func circleVsCircle(cx, cz, radius, ox, oz, obstacleRadius float64) (Contact, bool) {
+	dx, dz := cx-ox, cz-oz
+	distSq := dx*dx + dz*dz
+	reach := radius + obstacleRadius
+	if distSq > reach*reach {
+		return Contact{}, false
+	}
+
+	// Coincident centers have no defined normal, push out along +X
+	nx, nz := 1.0, 0.0
+	dist := math.Sqrt(distSq)
+	if dist > 1e-6 {
+		nx, nz = dx/dist, dz/dist
+	}
+
+	return Contact{
+		NormalX: nx,
+		NormalZ: nz,
+		Depth:   reach - dist,
+		PointX:  ox + nx*obstacleRadius,
+		PointZ:  oz + nz*obstacleRadius,
+	}, true
+}
+
+// closestPointOnSegment returns the point on segment a-b nearest to (x, z)
+func closestPointOnSegment(x, z, ax, az, bx, bz float64) (float64, float64) {
+	abx, abz := bx-ax, bz-az
+	lengthSq := abx*abx + abz*abz
+	if lengthSq < 1e-12 {
+		return ax, az // Degenerate capsule is a circle
+	}
+	t := clamp(((x-ax)*abx+(z-az)*abz)/lengthSq, 0, 1)
+	return ax + abx*t, az + abz*t
+}
+
+// DeepestContact returns the most penetrating contact for a circular body
+func (w *CollisionWorld) DeepestContact(x, z, radius float64) (Contact, bool) {
+	var deepest Contact
+	found := false
+
+	query := BoundingBox{MinX: x - radius, MinY: z - radius, MaxX: x + radius, MaxY: z + radius}
+	for _, c := range w.Query(query) {
+		contact, hit := c.contact(x, z, radius)
+		if hit && (!found || contact.Depth > deepest.Depth) {
+			contact.ColliderID = c.ID
+			deepest = contact
+			found = true
+		}
+	}
+
+	return deepest, found
+}
+
+// sweepInterval intersects the path of a moving circle with the collider,
+// treated as a ray against the footprint inflated by the radius (Minkowski sum)
+func (c *Collider) sweepInterval(ox, oz, dx, dz, radius float64) (tEnter, tExit, nx, nz float64, ok bool) {
+	switch c.Shape {
+	case ShapeCircle:
+		return rayCircle2D(ox, oz, dx, dz, c.X, c.Z, c.Radius+radius)
+	case ShapeCapsule:
+		inflated := *c
+		inflated.Radius += radius
+		return rayCapsule2D(ox, oz, dx, dz, &inflated)
+	default:
+		// Rounded box: the box grown along each axis, plus circles at the corners
+		b := c.Box
+		u := newRayInterval()
+		u.merge(raySlab2D(ox, oz, dx, dz, b.MinX-radius, b.MinY, b.MaxX+radius, b.MaxY))
+		u.merge(raySlab2D(ox, oz, dx, dz, b.MinX, b.MinY-radius, b.MaxX, b.MaxY+radius))
+		u.merge(rayCircle2D(ox, oz, dx, dz, b.MinX, b.MinY, radius))
+		u.merge(rayCircle2D(ox, oz, dx, dz, b.MaxX, b.MinY, radius))
+		u.merge(rayCircle2D(ox, oz, dx, dz, b.MinX, b.MaxY, radius))
+		u.merge(rayCircle2D(ox, oz, dx, dz, b.MaxX, b.MaxY, radius))
+		return u.enter, u.exit, u.nx, u.nz, u.ok
+	}
+}
+
+// Sweep finds the first collider a circle touches while moving by (dx, dz)
+// t is the fraction of the motion completed at first contact (1 if nothing is
+// hit); colliders the circle already overlaps are left to DeepestContact
+func (w *CollisionWorld) Sweep(x, z, radius, dx, dz float64) (t float64, contact Contact, hit bool) {
+	t = 1
+	query := BoundingBox{
+		MinX: math.Min(x, x+dx) - radius, MinY: math.Min(z, z+dz) - radius,
+		MaxX: math.Max(x, x+dx) + radius, MaxY: math.Max(z, z+dz) + radius,
+	}
+
+	for _, c := range w.Query(query) {
+		enter, _, nx, nz, ok := c.sweepInterval(x, z, dx, dz, radius)
+		if !ok || enter < 0 || enter > t {
+			continue
+		}
+		if nx*dx+nz*dz >= 0 {
+			continue // Grazing or moving away from the surface
+		}
+
+		t = enter
+		hit = true
+		contact = Contact{
+			ColliderID: c.ID,
+			NormalX:    nx,
+			NormalZ:    nz,
+			PointX:     x + dx*enter - nx*radius,
+			PointZ:     z + dz*enter - nz*radius,
+		}
+	}
+
+	return t, contact, hit
+}
+
+// moveCar integrates the car's position with continuous collision detection:
+// the body is swept along its motion, stopping at the first collider and
+// sliding along it for the rest of the substep, so fast cars cannot tunnel
+// through thin colliders such as fences
+func (w *World) moveCar(car *CarState, cfg *VehicleConfig, dt float64) {
+	remaining := 1.0 // Fraction of this substep's motion still to travel
+	for iter := 0; iter < maxCollisionIterations; iter++ {
+		dx := car.VelocityX * dt * remaining
+		dz := car.VelocityZ * dt * remaining
+		if dx == 0 && dz == 0 {
+			return
+		}
+
+		t, contact, hit := w.Colliders.Sweep(car.X, car.Z, cfg.BodyRadius, dx, dz)
+		car.X += dx * t
+		car.Z += dz * t
+		if !hit {
+			return
+		}
+
+		w.hitWall(car, contact, dt)
+		remaining *= 1 - t
+	}
+}
+
+// resolveStaticCollisions pushes the car out of static colliders it overlaps
+// (e.g. a collider added on top of it) and removes the velocity into each
+func (w *World) resolveStaticCollisions(car *CarState, cfg *VehicleConfig, dt float64) {
+	for iter := 0; iter < maxCollisionIterations; iter++ {
+		contact, hit := w.Colliders.DeepestContact(car.X, car.Z, cfg.BodyRadius)
+		if !hit {
+			return
+		}
+
+		car.X += contact.NormalX * contact.Depth
+		car.Z += contact.NormalZ * contact.Depth
+		w.hitWall(car, contact, dt)
+	}
+}
+
+// hitWall records a static contact and removes the velocity component into
+// it, keeping (slightly damped) motion along the wall
+func (w *World) hitWall(car *CarState, contact Contact, dt float64) {
+	if !car.Collided {
+		car.Collided = true
+		car.CollidedWith = contact.ColliderID
+	}
+
+	into := car.VelocityX*contact.NormalX + car.VelocityZ*contact.NormalZ
+	if into >= 0 {
+		return
+	}
+
+	w.pushCollisionEvent(CollisionEvent{
+		Kind:        CollisionStatic,
+		A:           car.ID,
+		B:           contact.ColliderID,
+		ImpactSpeed: -into,
+		PointX:      contact.PointX,
+		PointZ:      contact.PointZ,
+		NormalX:     contact.NormalX,
+		NormalZ:     contact.NormalZ,
+	})
+	w.damageCar(car, -into)
+
+	car.VelocityX -= into * contact.NormalX
+	car.VelocityZ -= into * contact.NormalZ
+
+	slide := simPow(WALL_FRICTION, dt/referenceFrameTime)
+	car.VelocityX *= slide
+	car.VelocityZ *= slide
+}
+
+// ============================================================================
+// Car-to-Car Collisions
+// ============================================================================
+
+// CAR_RESTITUTION is the bounciness of car-to-car impacts (0 = no bounce, 1 = elastic)
+const CAR_RESTITUTION = 0.3
+
+// CarBody is the collision view of one car: a circle with mass and velocity
+type CarBody struct {
+	ID                   int // Entity ID reported in collision events
+	X, Z                 float64
+	VelocityX, VelocityZ float64
+	Mass                 float64 // kg, must be positive
+	Radius               float64 // units
+}
+
+// body returns the collision body of a car with the given tuning
+func (car *CarState) body(cfg *VehicleConfig) CarBody {
+	return CarBody{
+		ID:        car.ID,
+		X:         car.X,
+		Z:         car.Z,
+		VelocityX: car.VelocityX,
+		VelocityZ: car.VelocityZ,
+		Mass:      cfg.Mass,
+		Radius:    cfg.BodyRadius,
+	}
+}
+
+// applyBody copies a resolved collision body back into the car
+func (car *CarState) applyBody(b CarBody) {
+	car.X, car.Z = b.X, b.Z
+	car.VelocityX, car.VelocityZ = b.VelocityX, b.VelocityZ
+}
+
+// CarContact describes two car bodies touching, reported to JS for effects
+type CarContact struct {
+	A, B             int     // Indices of the two bodies
+	NormalX, NormalZ float64 // Unit vector from A towards B
+	Depth            float64 // Penetration depth before separation
+	Impulse          float64 // kg·units/s exchanged along the normal (0 if separating)
+	ImpactSpeed      float64 // units/s, closing speed along the normal (0 if separating)
+	PointX, PointZ   float64 // Contact point midway through the overlap
+}
+
+// resolveCarCollisions separates overlapping car bodies in place and applies
+// a mass-weighted impulse with restitution, so heavy vehicles shove light ones
+// Bodies sharing an ID (a car and its trailer) never collide
+func (w *World) resolveCarCollisions(bodies []CarBody) []CarContact {
+	var contacts []CarContact
+
+	for i := 0; i < len(bodies); i++ {
+		for j := i + 1; j < len(bodies); j++ {
+			if bodies[i].ID == bodies[j].ID {
+				continue
+			}
+			if contact, hit := resolveCarPair(&bodies[i], &bodies[j]); hit {
+				contact.A, contact.B = i, j
+				contacts = append(contacts, contact)
+				w.pushCollisionEvent(CollisionEvent{
+					Kind:        CollisionVehicle,
+					A:           bodies[i].ID,
+					B:           bodies[j].ID,
+					ImpactSpeed: contact.ImpactSpeed,
+					PointX:      contact.PointX,
+					PointZ:      contact.PointZ,
+					NormalX:     -contact.NormalX,
+					NormalZ:     -contact.NormalZ,
+				})
+			}
+		}
+	}
+
+	return contacts
+}
+
+// resolveCarPair handles one pair of bodies
+func resolveCarPair(a, b *CarBody) (CarContact, bool) {
+	dx, dz := b.X-a.X, b.Z-a.Z
+	distSq := dx*dx + dz*dz
+	reach := a.Radius + b.Radius
+	if distSq > reach*reach {
+		return CarContact{}, false
+	}
+
+	// Coincident bodies have no defined normal, separate along +X
+	nx, nz := 1.0, 0.0
+	dist := math.Sqrt(distSq)
+	if dist > 1e-6 {
+		nx, nz = dx/dist, dz/dist
+	}
+	depth := reach - dist
+
+	invA, invB := 1/a.Mass, 1/b.Mass
+	invSum := invA + invB
+
+	// Positional correction shared by inverse mass (lighter car moves more)
+	a.X -= nx * depth * invA / invSum
+	a.Z -= nz * depth * invA / invSum
+	b.X += nx * depth * invB / invSum
+	b.Z += nz * depth * invB / invSum
+
+	contact := CarContact{
+		NormalX: nx,
+		NormalZ: nz,
+		Depth:   depth,
+		PointX:  a.X + nx*a.Radius,
+		PointZ:  a.Z + nz*a.Radius,
+	}
+
+	// Impulse only when the bodies are approaching each other
+	closing := (b.VelocityX-a.VelocityX)*nx + (b.VelocityZ-a.VelocityZ)*nz
+	if closing < 0 {
+		impulse := -(1 + CAR_RESTITUTION) * closing / invSum
+		a.VelocityX -= impulse * invA * nx
+		a.VelocityZ -= impulse * invA * nz
+		b.VelocityX += impulse * invB * nx
+		b.VelocityZ += impulse * invB * nz
+		contact.Impulse = impulse
+		contact.ImpactSpeed = -closing
+	}
+
+	return contact, true
+}
@@ -0,0 +1,195 @@
+package main
+
+import "sort"
+
+// ============================================================================
+// Construction (building sites progressing over game time)
+// ============================================================================
+
+// A building is paid for up front when placed (Purchase, physics_economy.go)
+// and then stands as a construction site from when it first appears in the
+// occupancy grid until it is complete. Sites make progress on the world
+// clock (physics_clock.go), taking a game hour per CostPerHour of the
+// building's price, between CONSTRUCTION_MIN_HOURS and
+// CONSTRUCTION_MAX_HOURS. Until complete a building houses no one, offers no
+// jobs (physics_population.go), provides no service (physics_livability.go)
+// or utility supply (physics_utilities.go) and does not level up
+// (physics_levels.go); its stage tells the frontend whether to draw the
+// foundations or scaffolding. Completion queues the "building_completed"
+// event (physics_sim_events.go). With Instant on, as for a sandbox, new
+// buildings are complete as soon as they are placed. Progress is kept by
+// placement ID across placement syncs and dropped when the placement is
+// removed.
+
+const (
+	CONSTRUCTION_COST_PER_HOUR = 200.0 // Price built per game hour
+	CONSTRUCTION_MIN_HOURS     = 2.0   // Game hours the cheapest building takes
+	CONSTRUCTION_MAX_HOURS     = 24.0  // Game hours the dearest building takes
+	FOUNDATION_SHARE           = 0.3   // Progress from which a site shows scaffolding rather than foundations
+)
+
+// ConstructionStage is how far a building has been built, for rendering
+type ConstructionStage uint8
+
+const (
+	StageFoundation ConstructionStage = iota
+	StageScaffolding
+	StageComplete
+	constructionStageCount
+)
+
+// constructionStageNames are the JavaScript-facing names, indexed by ConstructionStage
+var constructionStageNames = [constructionStageCount]string{"foundation", "scaffolding", "complete"}
+
+// String returns the JavaScript-facing stage name
+func (s ConstructionStage) String() string {
+	if s >= constructionStageCount {
+		return constructionStageNames[StageFoundation]
+	}
+	return constructionStageNames[s]
+}
+
+// constructionStageOf returns the stage of a progress level
+func constructionStageOf(progress float64) ConstructionStage {
+	switch {
+	case progress >= 1:
+		return StageComplete
+	case progress >= FOUNDATION_SHARE:
+		return StageScaffolding
+	}
+	return StageFoundation
+}
+
+// Construction tracks the progress of every building
+type Construction struct {
+	Instant     bool            // New buildings are complete when placed
+	CostPerHour float64         // Price built per game hour
+	Progress    map[int]float64 // Placement ID -> progress 0 (just placed) to 1 (complete)
+	revision    int             // Occupancy revision buildings were last matched against
+}
+
+// NewConstruction creates construction that takes time, with no sites yet
+func NewConstruction() *Construction {
+	return &Construction{CostPerHour: CONSTRUCTION_COST_PER_HOUR, Progress: make(map[int]float64), revision: -1}
+}
+
+// ConstructionSite is one building being built
+type ConstructionSite struct {
+	ID        int
+	Model     string
+	Progress  float64 // 0-1
+	Stage     ConstructionStage
+	HoursLeft float64 // Game hours until complete
+}
+
+// refreshConstruction opens a site for every new building (complete already
+// with Instant) and forgets removed ones, when the occupancy grid has changed
+func (w *World) refreshConstruction() {
+	c := w.Sites
+	if c.revision == w.Occupancy.Revision() {
+		return
+	}
+	present := make(map[int]bool, len(c.Progress))
+	for _, id := range w.Occupancy.IDs() {
+		if !flammable(w.Occupancy.Placement(id)) {
+			continue
+		}
+		present[id] = true
+		if _, known := c.Progress[id]; !known {
+			c.Progress[id] = 0
+			if c.Instant {
+				c.Progress[id] = 1
+			}
+		}
+	}
+	for id := range c.Progress {
+		if !present[id] {
+			delete(c.Progress, id)
+		}
+	}
+	c.revision = w.Occupancy.Revision()
+}
+
+// underConstruction reports whether a placement is a building not yet complete
+func (w *World) underConstruction(p *Placement) bool {
+	w.refreshConstruction()
+	progress, known := w.Sites.Progress[p.ID]
+	return known && progress < 1
+}
+
+// constructionHours returns the game hours a model takes to build
+func (w *World) constructionHours(model string) float64 {
+	rate := positiveOr(w.Sites.CostPerHour, CONSTRUCTION_COST_PER_HOUR)
+	return clamp(w.PriceOf(model).Cost/rate, CONSTRUCTION_MIN_HOURS, CONSTRUCTION_MAX_HOURS)
+}
+
+// ConstructionSites returns every building not yet complete, by placement ID
+func (w *World) ConstructionSites() []ConstructionSite {
+	w.refreshConstruction()
+	var sites []ConstructionSite
+	for id, progress := range w.Sites.Progress {
+		if progress >= 1 {
+			continue
+		}
+		model := w.Occupancy.Placement(id).Model
+		sites = append(sites, ConstructionSite{
+			ID:        id,
+			Model:     model,
+			Progress:  progress,
+			Stage:     constructionStageOf(progress),
+			HoursLeft: (1 - progress) * w.constructionHours(model),
+		})
+	}
+	sort.Slice(sites, func(i, j int) bool { return sites[i].ID < sites[j].ID })
+	return sites
+}
+
+// CompleteConstruction finishes a building now, returning false if it is
+// not under construction
+func (w *World) CompleteConstruction(id int) bool {
+	p := w.Occupancy.Placement(id)
+	if p == nil || !w.underConstruction(p) {
+		return false
+	}
+	w.completeBuilding(p)
+	return true
+}
+
+// SetInstantConstruction turns instant building on or off; turning it on
+// completes every site now
+func (w *World) SetInstantConstruction(instant bool) {
+	w.Sites.Instant = instant
+	if !instant {
+		return
+	}
+	for _, site := range w.ConstructionSites() {
+		w.completeBuilding(w.Occupancy.Placement(site.ID))
+	}
+}
+
+// completeBuilding marks a site complete and reports the finished building
+func (w *World) completeBuilding(p *Placement) {
+	w.Sites.Progress[p.ID] = 1
+	w.Utilities.invalidate()
+	w.SimEvents.buildings[p.ID] = true
+	x, z := p.Center()
+	w.pushSimEvent(SimEvent{Kind: SimBuildingCompleted, ID: p.ID, Model: p.Model, X: x, Z: z})
+}
+
+// stepConstruction builds every site on the world clock, in placement order
+func (w *World) stepConstruction(dt float64) {
+	w.refreshConstruction()
+	c := w.Sites
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	for _, id := range w.Occupancy.IDs() {
+		progress, known := c.Progress[id]
+		if !known || progress >= 1 {
+			continue
+		}
+		p := w.Occupancy.Placement(id)
+		c.Progress[id] = progress + hours/w.constructionHours(p.Model)
+		if c.Progress[id] >= 1 {
+			w.completeBuilding(p)
+		}
+	}
+}
@@ -0,0 +1,114 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Crowd Simulation (social forces)
+// ============================================================================
+
+// Pedestrians are moved by a social force model (Helbing & Molnár):
+// - A driving force relaxes velocity towards the desired walking velocity
+// - Neighbors push apart with a force that decays exponentially with the gap
+//   between bodies, weighted down for people behind (anisotropy)
+// - Static colliders push away the same way, with their own strength and range
+// Forces are computed for everyone before anyone moves, so the result does
+// not depend on step order.
+
+const CROWD_FORCE_CUTOFF = 3.0 // Ranges beyond which repulsion is ignored
+
+// CrowdConfig tunes the social force model
+type CrowdConfig struct {
+	RelaxationTime    float64 // s, how quickly pedestrians reach their desired velocity
+	RepulsionStrength float64 // units/s², push between touching pedestrians (0 disables)
+	RepulsionRange    float64 // units, distance over which the push decays by 1/e
+	ObstacleStrength  float64 // units/s², push from static colliders (0 disables)
+	ObstacleRange     float64 // units
+	Anisotropy        float64 // 0..1, weight of neighbors directly behind (1 = as strong as ahead)
+	MaxSpeedScale     float64 // Pedestrians may be pushed up to this multiple of their walking speed
+}
+
+// DefaultCrowdConfig returns the default social force tuning
+func DefaultCrowdConfig() CrowdConfig {
+	return CrowdConfig{
+		RelaxationTime:    0.5,
+		RepulsionStrength: 2.0,
+		RepulsionRange:    0.3,
+		ObstacleStrength:  5.0,
+		ObstacleRange:     0.2,
+		Anisotropy:        0.5,
+		MaxSpeedScale:     1.3,
+	}
+}
+
+// sanitized replaces invalid tuning values with the defaults
+func (c CrowdConfig) sanitized() CrowdConfig {
+	d := DefaultCrowdConfig()
+	c.RelaxationTime = positiveOr(c.RelaxationTime, d.RelaxationTime)
+	if !(c.RepulsionStrength >= 0) || math.IsInf(c.RepulsionStrength, 1) {
+		c.RepulsionStrength = d.RepulsionStrength
+	}
+	c.RepulsionRange = positiveOr(c.RepulsionRange, d.RepulsionRange)
+	if !(c.ObstacleStrength >= 0) || math.IsInf(c.ObstacleStrength, 1) {
+		c.ObstacleStrength = d.ObstacleStrength
+	}
+	c.ObstacleRange = positiveOr(c.ObstacleRange, d.ObstacleRange)
+	if !(c.Anisotropy >= 0 && c.Anisotropy <= 1) {
+		c.Anisotropy = d.Anisotropy
+	}
+	c.MaxSpeedScale = math.Max(positiveOr(c.MaxSpeedScale, d.MaxSpeedScale), 1)
+	return c
+}
+
+// crowdForce returns the social force on pedestrian p, given the velocity it
+// wants to walk at
+func (w *World) crowdForce(p *Pedestrian, desiredX, desiredZ float64) (fx, fz float64) {
+	cfg := &w.Crowd
+	fx = (desiredX - p.VelocityX) / cfg.RelaxationTime
+	fz = (desiredZ - p.VelocityZ) / cfg.RelaxationTime
+
+	// Facing direction for anisotropy: where they walk, or where they look when still
+	ex, ez := math.Sin(p.Heading), math.Cos(p.Heading)
+	if d := simHypot(desiredX, desiredZ); d > 1e-9 {
+		ex, ez = desiredX/d, desiredZ/d
+	}
+
+	if cfg.RepulsionStrength > 0 {
+		cutoff := 2*PEDESTRIAN_RADIUS + CROWD_FORCE_CUTOFF*cfg.RepulsionRange
+		for _, other := range w.pedestrians {
+			if other == p {
+				continue
+			}
+			dx, dz := p.X-other.X, p.Z-other.Z
+			distance := simHypot(dx, dz)
+			if distance > cutoff {
+				continue
+			}
+
+			nx, nz := ex, ez // Exactly overlapping: push forwards
+			if distance > 1e-9 {
+				nx, nz = dx/distance, dz/distance
+			}
+			cosPhi := -(nx*ex + nz*ez) // 1 when the other is straight ahead
+			weight := cfg.Anisotropy + (1-cfg.Anisotropy)*(1+cosPhi)/2
+			magnitude := cfg.RepulsionStrength * weight * simExp((2*PEDESTRIAN_RADIUS-distance)/cfg.RepulsionRange)
+			fx += nx * magnitude
+			fz += nz * magnitude
+		}
+	}
+
+	if cfg.ObstacleStrength > 0 {
+		reach := PEDESTRIAN_RADIUS + CROWD_FORCE_CUTOFF*cfg.ObstacleRange
+		box := BoundingBox{MinX: p.X - reach, MinY: p.Z - reach, MaxX: p.X + reach, MaxY: p.Z + reach}
+		for _, c := range w.Colliders.Query(box) {
+			contact, touching := c.contact(p.X, p.Z, reach)
+			if !touching {
+				continue
+			}
+			gap := reach - contact.Depth - PEDESTRIAN_RADIUS // Surface distance minus body radius
+			magnitude := cfg.ObstacleStrength * simExp(-gap/cfg.ObstacleRange)
+			fx += contact.NormalX * magnitude
+			fz += contact.NormalZ * magnitude
+		}
+	}
+	return fx, fz
+}
@@ -0,0 +1,77 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Cruise Control and Speed Limiter
+// ============================================================================
+
+// Two optional speed controllers ride on InputState and work the pedals
+// every substep, after input is read and before the car is driven. Cruise
+// control (CruiseSpeed > 0) holds that forward speed: it opens the throttle
+// to reach it and brakes to hold it downhill. Pressing the throttle harder
+// than it does speeds the car up past it for as long as it is held, and any
+// brake input hands the pedals back to the driver for that step. The speed
+// limiter (SpeedLimiter) never lets the car exceed the speed limit of the
+// road segment it is on (off road it does nothing): it cuts the throttle
+// near the limit and brakes back under it. Both aim the acceleration at
+// CRUISE_GAIN times the speed error, the way AI drivers could hold a target
+// speed without rebuilding the IDM; neither acts while reversing.
+
+const (
+	CRUISE_GAIN = 2.0 // 1/s, acceleration asked for per units/s of speed error
+)
+
+// speedLimitAt returns the speed limit of the road segment nearest a point,
+// and false when the point is not on any road
+func (w *World) speedLimitAt(x, z float64) (float64, bool) {
+	limit, found := 0.0, false
+	best := math.Inf(1)
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		px, pz := closestPointOnSegment(x, z, a.X, a.Z, b.X, b.Z)
+		if d := simHypot(px-x, pz-z); d <= e.curbOffset() && d < best {
+			best, limit, found = d, w.roadSpeedLimit(e, x, z), true
+		}
+	}
+	return limit, found
+}
+
+// holdSpeed returns the pedals that move a car at forward speed towards
+// target, as the IDM inputs in drive are mapped
+func holdSpeed(speed, target float64, cfg *VehicleConfig) (throttle, brake float64) {
+	accel := CRUISE_GAIN * (target - speed)
+	if accel > 0 {
+		return clamp(accel/cfg.Acceleration, 0, 1), 0
+	}
+	if speed > STOP_SPEED {
+		return 0, clamp(-accel/cfg.BrakePower, 0, 1)
+	}
+	return 0, 0
+}
+
+// cruiseInput returns the input with the cruise control and speed limiter
+// applied to the pedals for one substep; cfg is the tuning it is driven with
+func (w *World) cruiseInput(car *CarState, input InputState, cfg *VehicleConfig) InputState {
+	if (input.CruiseSpeed <= 0 && !input.SpeedLimiter) || car.Reversing {
+		return input
+	}
+	speed := car.VelocityX*math.Sin(car.RotationY) + car.VelocityZ*math.Cos(car.RotationY)
+
+	if input.CruiseSpeed > 0 && input.Brake == 0 {
+		throttle, brake := holdSpeed(speed, input.CruiseSpeed, cfg)
+		if input.Throttle == 0 {
+			input.Brake = brake
+		}
+		input.Throttle = math.Max(input.Throttle, throttle)
+	}
+	if input.SpeedLimiter {
+		if limit, ok := w.speedLimitAt(car.X, car.Z); ok {
+			throttle, brake := holdSpeed(speed, limit, cfg)
+			input.Throttle = math.Min(input.Throttle, throttle)
+			input.Brake = math.Max(input.Brake, brake)
+		}
+	}
+	return input
+}
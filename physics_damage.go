@@ -0,0 +1,120 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Vehicle Damage (collision wear, degraded handling and garage repairs)
+// ============================================================================
+
+// Every impact damages a car by the speed change it causes: hitting a wall
+// stops the velocity into it, and a car-to-car impulse changes each car's
+// velocity by impulse / mass, so a heavy truck barely notices a hatchback
+// that bounces off it. Speed changes under DAMAGE_MIN_IMPACT (scrapes,
+// parking bumps) do nothing. Damage lowers top speed, tire grip and turn
+// rate in proportion, after the surface, weather, season and road wear;
+// at 1 the car is wrecked: it loses drive and coasts to a stop, and a
+// "wrecked" town event is reported once. Cars stopped at a garage (on it
+// or within SERVICE_REACH) are repaired at GARAGE_REPAIR_RATE, and
+// RepairCar fixes a car at once (a tow, a repair paid in the UI).
+// Stateless bindings (updateCarPhysics and the batch buffers) carry damage
+// in and out with the rest of the state.
+
+const (
+	DAMAGE_MIN_IMPACT     = 2.0  // units/s of speed change that does no damage
+	DAMAGE_PER_IMPACT     = 0.04 // Damage per units/s above that (a wall at top speed: ~40%)
+	DAMAGE_SPEED_LOSS     = 0.5  // Fraction of top speed lost at full damage
+	DAMAGE_GRIP_LOSS      = 0.3  // Fraction of lateral grip lost at full damage
+	DAMAGE_STEER_LOSS     = 0.3  // Fraction of turn rate lost at full damage
+	GARAGE_USE            = "garage"
+	GARAGE_REPAIR_RATE    = 0.1 // Damage repaired per s while stopped at a garage
+	SERVICE_REACH         = 3.0 // units from a garage or gas station a car may stop to be served
+	SERVICE_STOPPED_SPEED = 0.5 // units/s, slower cars count as stopped
+)
+
+// Wrecked reports whether the car is damaged beyond driving
+func (car *CarState) Wrecked() bool {
+	return car.Damage >= 1
+}
+
+// damageAdjust scales vehicle tuning for a car's damage
+func (car *CarState) damageAdjust(cfg *VehicleConfig) {
+	d := car.Damage
+	if d <= 0 {
+		return
+	}
+	cfg.MaxSpeed *= 1 - DAMAGE_SPEED_LOSS*d
+	cfg.ReverseMaxSpeed *= 1 - DAMAGE_SPEED_LOSS*d
+	cfg.LateralGrip *= 1 - DAMAGE_GRIP_LOSS*d
+	cfg.TurnRate *= 1 - DAMAGE_STEER_LOSS*d
+	if car.Wrecked() {
+		cfg.Acceleration, cfg.ReverseAcceleration = 0, 0
+	}
+}
+
+// damageCar damages a car by the speed change of an impact, reporting the
+// impact that wrecks it
+func (w *World) damageCar(car *CarState, deltaV float64) {
+	if !(deltaV > DAMAGE_MIN_IMPACT) || car.Wrecked() {
+		return
+	}
+	car.Damage = math.Min(car.Damage+(deltaV-DAMAGE_MIN_IMPACT)*DAMAGE_PER_IMPACT, 1)
+	if car.Wrecked() {
+		w.pushSimEvent(SimEvent{Kind: SimCarWrecked, ID: car.ID, Value: deltaV, X: car.X, Z: car.Z})
+	}
+}
+
+// damageFromContacts damages both cars of every car-to-car contact; bodies
+// past the cars are trailers (physics_trailer.go), whose towing cars, at
+// the indices in towing, take their damage
+func (w *World) damageFromContacts(contacts []CarContact, bodies []CarBody, towing []int) {
+	bodyCar := func(i int) *CarState {
+		if i >= len(w.cars) {
+			i = towing[i-len(w.cars)]
+		}
+		return &w.cars[i].State
+	}
+	for _, c := range contacts {
+		w.damageCar(bodyCar(c.A), c.Impulse/bodies[c.A].Mass)
+		w.damageCar(bodyCar(c.B), c.Impulse/bodies[c.B].Mass)
+	}
+}
+
+// RepairCar removes damage from a car: amount (0..1) of it, or all of it
+// when amount is not positive; returns false if the car does not exist
+func (w *World) RepairCar(id int, amount float64) bool {
+	car := w.Car(id)
+	if car == nil {
+		return false
+	}
+	if !(amount > 0) {
+		amount = 1
+	}
+	car.State.Damage = math.Max(car.State.Damage-amount, 0)
+	return true
+}
+
+// servedBy reports whether a car is stopped on or within SERVICE_REACH of
+// a placement of a use: cars pull up beside a garage or gas station
+func (w *World) servedBy(car *CarState, use string) bool {
+	if simHypot(car.VelocityX, car.VelocityZ) > SERVICE_STOPPED_SPEED {
+		return false
+	}
+	reach := BoundingBox{MinX: car.X - SERVICE_REACH, MinY: car.Z - SERVICE_REACH, MaxX: car.X + SERVICE_REACH, MaxY: car.Z + SERVICE_REACH}
+	for _, p := range w.Occupancy.Query(reach) {
+		if w.placementUse(p) == use && !w.underConstruction(p) {
+			return true
+		}
+	}
+	return false
+}
+
+// stepRepairs repairs damaged cars stopped at a garage
+func (w *World) stepRepairs(dt float64) {
+	for _, car := range w.cars {
+		s := &car.State
+		if s.Damage <= 0 || !w.servedBy(s, GARAGE_USE) {
+			continue
+		}
+		s.Damage = math.Max(s.Damage-GARAGE_REPAIR_RATE*dt, 0)
+	}
+}
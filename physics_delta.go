@@ -0,0 +1,509 @@
+package main
+
+import (
+	"bytes"
+	"encoding/binary"
+	"encoding/gob"
+	"errors"
+	"math"
+	"reflect"
+)
+
+// ============================================================================
+// Delta Sync (compact binary differences between snapshots)
+// ============================================================================
+
+// A server sends each client the difference between the last snapshot the
+// client has and the current one, rather than the whole world. Snapshots
+// are compared field by field down to numbers, bools and strings, and only
+// the fields that differ are sent, each as its position in the snapshot
+// (counting from the previous one sent) and its new value. Cars,
+// pedestrians and placements are matched by ID, so a car that moved costs
+// its changed position and speed fields and one that stood still costs
+// nothing; added entities are sent against a zero entity and removed ones
+// simply left out. Slices, maps and other containers outside those lists
+// (roads, terrain, zones, ...) are sent whole, gob-encoded, when anything
+// in them changed. A key frame is encoded against an empty world instead,
+// for clients that join or lose track of the stream.
+//
+// The header holds the DELTA_MAGIC bytes, the format version as a
+// little-endian uint16, a flag byte (deltaKeyFrame) and the checksum of the
+// base (snapshotChecksum, physics_determinism.go), which the decoder checks
+// against its own base; the time alone would pass a base edited while
+// paused. Key frames carry 0. Both ends must run the same build, since
+// fields are identified by position.
+
+const (
+	DELTA_MAGIC          = "TBDL" // First bytes of every delta
+	DELTA_FORMAT_VERSION = 2      // Bumped with any change to the snapshot layout
+	deltaHeaderSize      = len(DELTA_MAGIC) + 2 + 1 + 8
+
+	deltaKeyFrame = 1 << 0 // Encoded against an empty world
+
+	deltaListSame    = 0 // Same entities in the same order as the base
+	deltaListRebuilt = 1 // Every entity listed by key
+	deltaEntityKept  = 0 // Patched from the base entity with the same key
+	deltaEntityAdded = 1 // Patched from a zero entity
+)
+
+// deltaLists are the WorldSnapshot fields diffed entity by entity, with
+// the key that matches an entity across snapshots
+var deltaLists = []struct {
+	field string
+	key   func(v reflect.Value) int64
+}{
+	{"Cars", func(v reflect.Value) int64 { return int64(v.Addr().Interface().(*CarSnapshot).State.ID) }},
+	{"Pedestrians", func(v reflect.Value) int64 { return int64(v.Addr().Interface().(*PedestrianSnapshot).ID) }},
+	{"Placements", func(v reflect.Value) int64 { return int64(v.Addr().Interface().(*Placement).ID) }},
+}
+
+// isDeltaList reports whether a WorldSnapshot field is diffed by entity
+func isDeltaList(field string) bool {
+	for _, l := range deltaLists {
+		if l.field == field {
+			return true
+		}
+	}
+	return false
+}
+
+// deltaLeaves appends the values diffed one by one: exported struct fields
+// and array elements are descended into, anything else is a leaf
+func deltaLeaves(v reflect.Value, out []reflect.Value) []reflect.Value {
+	switch v.Kind() {
+	case reflect.Struct:
+		t := v.Type()
+		for i := 0; i < t.NumField(); i++ {
+			if t.Field(i).IsExported() {
+				out = deltaLeaves(v.Field(i), out)
+			}
+		}
+	case reflect.Array:
+		for i := 0; i < v.Len(); i++ {
+			out = deltaLeaves(v.Index(i), out)
+		}
+	default:
+		out = append(out, v)
+	}
+	return out
+}
+
+// worldLeaves returns the leaves of a snapshot outside the entity lists
+func worldLeaves(s reflect.Value) []reflect.Value {
+	var out []reflect.Value
+	t := s.Type()
+	for i := 0; i < t.NumField(); i++ {
+		if !isDeltaList(t.Field(i).Name) {
+			out = deltaLeaves(s.Field(i), out)
+		}
+	}
+	return out
+}
+
+// leafEqual reports whether two leaves hold the same value; floats are
+// compared by their bits, so NaNs do not count as changes
+func leafEqual(a, b reflect.Value) bool {
+	switch a.Kind() {
+	case reflect.Bool:
+		return a.Bool() == b.Bool()
+	case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+		return a.Int() == b.Int()
+	case reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+		return a.Uint() == b.Uint()
+	case reflect.Float32, reflect.Float64:
+		return math.Float64bits(a.Float()) == math.Float64bits(b.Float())
+	case reflect.String:
+		return a.String() == b.String()
+	default:
+		return reflect.DeepEqual(a.Interface(), b.Interface())
+	}
+}
+
+// changedLeaves returns the positions of the leaves that differ
+func changedLeaves(base, next []reflect.Value) []int {
+	var changed []int
+	for i := range next {
+		if !leafEqual(base[i], next[i]) {
+			changed = append(changed, i)
+		}
+	}
+	return changed
+}
+
+// deltaWriter builds a delta
+type deltaWriter struct {
+	buf []byte
+}
+
+func (d *deltaWriter) uvarint(x uint64) {
+	d.buf = binary.AppendUvarint(d.buf, x)
+}
+
+func (d *deltaWriter) varint(x int64) {
+	d.buf = binary.AppendVarint(d.buf, x)
+}
+
+// patch writes the changed leaves, each after the gap from the last one
+func (d *deltaWriter) patch(leaves []reflect.Value, changed []int) error {
+	d.uvarint(uint64(len(changed)))
+	last := -1
+	for _, i := range changed {
+		d.uvarint(uint64(i - last - 1))
+		last = i
+		if err := d.leaf(leaves[i]); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// leaf writes one value: numbers compactly, containers gob-encoded after a
+// byte telling nil from present
+func (d *deltaWriter) leaf(v reflect.Value) error {
+	switch v.Kind() {
+	case reflect.Bool:
+		if v.Bool() {
+			d.buf = append(d.buf, 1)
+		} else {
+			d.buf = append(d.buf, 0)
+		}
+	case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+		d.varint(v.Int())
+	case reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+		d.uvarint(v.Uint())
+	case reflect.Float32, reflect.Float64:
+		d.buf = binary.LittleEndian.AppendUint64(d.buf, math.Float64bits(v.Float()))
+	case reflect.String:
+		d.uvarint(uint64(v.Len()))
+		d.buf = append(d.buf, v.String()...)
+	default:
+		if v.IsNil() {
+			d.buf = append(d.buf, 0)
+			return nil
+		}
+		d.buf = append(d.buf, 1)
+		if v.Kind() == reflect.Pointer {
+			v = v.Elem()
+		}
+		var blob bytes.Buffer
+		if err := gob.NewEncoder(&blob).EncodeValue(v); err != nil {
+			return err
+		}
+		d.uvarint(uint64(blob.Len()))
+		d.buf = append(d.buf, blob.Bytes()...)
+	}
+	return nil
+}
+
+// list writes the difference between two entity lists
+func (d *deltaWriter) list(base, next reflect.Value, key func(reflect.Value) int64) error {
+	same := base.Len() == next.Len()
+	for i := 0; same && i < next.Len(); i++ {
+		same = key(base.Index(i)) == key(next.Index(i))
+	}
+
+	if same {
+		d.buf = append(d.buf, deltaListSame)
+		type entityPatch struct {
+			index   int
+			leaves  []reflect.Value
+			changed []int
+		}
+		var patches []entityPatch
+		for i := 0; i < next.Len(); i++ {
+			leaves := deltaLeaves(next.Index(i), nil)
+			if changed := changedLeaves(deltaLeaves(base.Index(i), nil), leaves); len(changed) > 0 {
+				patches = append(patches, entityPatch{i, leaves, changed})
+			}
+		}
+		d.uvarint(uint64(len(patches)))
+		last := -1
+		for _, p := range patches {
+			d.uvarint(uint64(p.index - last - 1))
+			last = p.index
+			if err := d.patch(p.leaves, p.changed); err != nil {
+				return err
+			}
+		}
+		return nil
+	}
+
+	d.buf = append(d.buf, deltaListRebuilt)
+	byKey := make(map[int64]int, base.Len())
+	for i := 0; i < base.Len(); i++ {
+		byKey[key(base.Index(i))] = i
+	}
+	zero := deltaLeaves(reflect.New(next.Type().Elem()).Elem(), nil)
+	d.uvarint(uint64(next.Len()))
+	for i := 0; i < next.Len(); i++ {
+		e := next.Index(i)
+		k := key(e)
+		d.varint(k)
+		from := zero
+		if j, kept := byKey[k]; kept {
+			d.buf = append(d.buf, deltaEntityKept)
+			from = deltaLeaves(base.Index(j), nil)
+		} else {
+			d.buf = append(d.buf, deltaEntityAdded)
+		}
+		leaves := deltaLeaves(e, nil)
+		if err := d.patch(leaves, changedLeaves(from, leaves)); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// EncodeDelta returns what a client holding base needs to reach next; a
+// nil base encodes a key frame
+func EncodeDelta(base, next *WorldSnapshot) ([]byte, error) {
+	var flags byte
+	var checksum uint64
+	if base == nil {
+		empty := NewWorld().Snapshot()
+		base, flags = &empty, deltaKeyFrame
+	} else {
+		var err error
+		if checksum, err = snapshotChecksum(base); err != nil {
+			return nil, err
+		}
+	}
+
+	d := &deltaWriter{buf: make([]byte, 0, 256)}
+	d.buf = append(d.buf, DELTA_MAGIC...)
+	d.buf = binary.LittleEndian.AppendUint16(d.buf, DELTA_FORMAT_VERSION)
+	d.buf = append(d.buf, flags)
+	d.buf = binary.LittleEndian.AppendUint64(d.buf, checksum)
+
+	b, n := reflect.ValueOf(base).Elem(), reflect.ValueOf(next).Elem()
+	leaves := worldLeaves(n)
+	if err := d.patch(leaves, changedLeaves(worldLeaves(b), leaves)); err != nil {
+		return nil, err
+	}
+	for _, l := range deltaLists {
+		if err := d.list(b.FieldByName(l.field), n.FieldByName(l.field), l.key); err != nil {
+			return nil, err
+		}
+	}
+	return d.buf, nil
+}
+
+// errDeltaTruncated is returned for a delta that ends early
+var errDeltaTruncated = errors.New("delta is truncated")
+
+// deltaReader reads a delta, stopping at the first error
+type deltaReader struct {
+	data []byte
+	err  error
+}
+
+func (r *deltaReader) flag() byte {
+	if r.err != nil || len(r.data) < 1 {
+		r.fail(errDeltaTruncated)
+		return 0
+	}
+	b := r.data[0]
+	r.data = r.data[1:]
+	return b
+}
+
+func (r *deltaReader) uvarint() uint64 {
+	x, n := binary.Uvarint(r.data)
+	if r.err != nil || n <= 0 {
+		r.fail(errDeltaTruncated)
+		return 0
+	}
+	r.data = r.data[n:]
+	return x
+}
+
+func (r *deltaReader) varint() int64 {
+	x, n := binary.Varint(r.data)
+	if r.err != nil || n <= 0 {
+		r.fail(errDeltaTruncated)
+		return 0
+	}
+	r.data = r.data[n:]
+	return x
+}
+
+// bytes returns the next n bytes
+func (r *deltaReader) bytes(n uint64) []byte {
+	if r.err != nil || uint64(len(r.data)) < n {
+		r.fail(errDeltaTruncated)
+		return nil
+	}
+	b := r.data[:n]
+	r.data = r.data[n:]
+	return b
+}
+
+func (r *deltaReader) fail(err error) {
+	if r.err == nil {
+		r.err = err
+	}
+}
+
+// patch sets the leaves a delta patch changed
+func (r *deltaReader) patch(leaves []reflect.Value) {
+	count := r.uvarint()
+	i := -1
+	for ; count > 0 && r.err == nil; count-- {
+		i += int(r.uvarint()) + 1
+		if i < 0 || i >= len(leaves) {
+			r.fail(errors.New("delta changes a field the snapshot does not have"))
+			return
+		}
+		r.leaf(leaves[i])
+	}
+}
+
+// leaf reads one value written by deltaWriter.leaf into v
+func (r *deltaReader) leaf(v reflect.Value) {
+	switch v.Kind() {
+	case reflect.Bool:
+		v.SetBool(r.flag() != 0)
+	case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+		v.SetInt(r.varint())
+	case reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+		v.SetUint(r.uvarint())
+	case reflect.Float32, reflect.Float64:
+		if b := r.bytes(8); b != nil {
+			v.SetFloat(math.Float64frombits(binary.LittleEndian.Uint64(b)))
+		}
+	case reflect.String:
+		v.SetString(string(r.bytes(r.uvarint())))
+	default:
+		if r.flag() == 0 {
+			v.Set(reflect.Zero(v.Type()))
+			return
+		}
+		blob := r.bytes(r.uvarint())
+		if r.err != nil {
+			return
+		}
+		t := v.Type()
+		if t.Kind() == reflect.Pointer {
+			t = t.Elem()
+		}
+		decoded := reflect.New(t)
+		if err := gob.NewDecoder(bytes.NewReader(blob)).DecodeValue(decoded); err != nil {
+			r.fail(err)
+			return
+		}
+		if v.Kind() == reflect.Pointer {
+			v.Set(decoded)
+			return
+		}
+		switch e := decoded.Elem(); {
+		case e.Kind() == reflect.Slice && e.IsNil():
+			v.Set(reflect.MakeSlice(t, 0, 0)) // Present but empty
+		case e.Kind() == reflect.Map && e.IsNil():
+			v.Set(reflect.MakeMap(t))
+		default:
+			v.Set(e)
+		}
+	}
+}
+
+// list reads an entity list written by deltaWriter.list
+func (r *deltaReader) list(base reflect.Value, key func(reflect.Value) int64) reflect.Value {
+	t := base.Type()
+	switch r.flag() {
+	case deltaListSame:
+		out := base
+		if base.Len() > 0 {
+			out = reflect.MakeSlice(t, base.Len(), base.Len())
+			reflect.Copy(out, base)
+		}
+		count := r.uvarint()
+		i := -1
+		for ; count > 0 && r.err == nil; count-- {
+			i += int(r.uvarint()) + 1
+			if i < 0 || i >= out.Len() {
+				r.fail(errors.New("delta changes an entity the snapshot does not have"))
+				break
+			}
+			r.patch(deltaLeaves(out.Index(i), nil))
+		}
+		return out
+
+	case deltaListRebuilt:
+		byKey := make(map[int64]int, base.Len())
+		for i := 0; i < base.Len(); i++ {
+			byKey[key(base.Index(i))] = i
+		}
+		n := r.uvarint()
+		if r.err != nil || n > uint64(len(r.data)) { // Every entity takes at least a byte
+			r.fail(errDeltaTruncated)
+			return base
+		}
+		if n == 0 {
+			return reflect.Zero(t)
+		}
+		out := reflect.MakeSlice(t, int(n), int(n))
+		for i := 0; i < int(n) && r.err == nil; i++ {
+			k := r.varint()
+			if r.flag() == deltaEntityKept {
+				j, ok := byKey[k]
+				if !ok {
+					r.fail(errors.New("delta keeps an entity the base does not have"))
+					break
+				}
+				out.Index(i).Set(base.Index(j))
+			}
+			r.patch(deltaLeaves(out.Index(i), nil))
+		}
+		return out
+
+	default:
+		r.fail(errors.New("delta has an unknown list encoding"))
+		return base
+	}
+}
+
+// ApplyDelta returns base with an EncodeDelta delta applied; base may be
+// nil for a key frame. base itself is left unchanged
+func ApplyDelta(base *WorldSnapshot, delta []byte) (WorldSnapshot, error) {
+	if len(delta) < deltaHeaderSize || string(delta[:len(DELTA_MAGIC)]) != DELTA_MAGIC {
+		return WorldSnapshot{}, errors.New("not a town delta")
+	}
+	header := delta[len(DELTA_MAGIC):deltaHeaderSize]
+	if binary.LittleEndian.Uint16(header) != DELTA_FORMAT_VERSION {
+		return WorldSnapshot{}, errors.New("delta was encoded by a different build")
+	}
+	if header[2]&deltaKeyFrame != 0 {
+		empty := NewWorld().Snapshot()
+		base = &empty
+	} else {
+		var checksum uint64
+		if base != nil {
+			var err error
+			if checksum, err = snapshotChecksum(base); err != nil {
+				return WorldSnapshot{}, err
+			}
+		}
+		if base == nil || checksum != binary.LittleEndian.Uint64(header[3:]) {
+			return WorldSnapshot{}, errors.New("delta was encoded against a different base state")
+		}
+	}
+
+	next := *base // Leaves replace values rather than change them, so sharing is safe
+	r := &deltaReader{data: delta[deltaHeaderSize:]}
+	n := reflect.ValueOf(&next).Elem()
+	r.patch(worldLeaves(n))
+	b := reflect.ValueOf(base).Elem()
+	for _, l := range deltaLists {
+		if r.err != nil {
+			break
+		}
+		n.FieldByName(l.field).Set(r.list(b.FieldByName(l.field), l.key))
+	}
+	if r.err != nil {
+		return WorldSnapshot{}, r.err
+	}
+	if len(r.data) > 0 {
+		return WorldSnapshot{}, errors.New("delta has trailing data")
+	}
+	return next, nil
+}
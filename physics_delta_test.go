@@ -0,0 +1,27 @@
+package main
+
+import "testing"
+
+func TestApplyDeltaRejectsBaseEditedAtTheSameTime(t *testing.T) {
+	w := NewWorld()
+	base := w.Snapshot()
+	w.Step(0.1)
+	next := w.Snapshot()
+	delta, err := EncodeDelta(&base, &next)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	edited := base
+	edited.Prices = map[string]Price{"market": {Cost: 1}} // Changed while paused
+	if _, err := ApplyDelta(&edited, delta); err == nil {
+		t.Fatal("delta applied to a different base from the same time")
+	}
+	got, err := ApplyDelta(&base, delta)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got.Time != next.Time {
+		t.Fatalf("time = %v after applying the delta, want %v", got.Time, next.Time)
+	}
+}
@@ -0,0 +1,188 @@
+package main
+
+// ============================================================================
+// Demolition (clearing placements with refunds and debris)
+// ============================================================================
+
+// DemolishPlacement clears a placed object as an edit (physics_edits.go),
+// taking it out of the occupancy grid and its collider out of the collision
+// world, and refunds the economy's RefundShare of its price
+// (physics_economy.go). It queues a "demolished" event
+// (physics_sim_events.go) at the middle of the footprint with its width and
+// depth, for the frontend to raise a cloud of dust and debris the size of
+// what came down. Maps built from the occupancy grid rebuild on their own;
+// what demolition must see to is the routes already planned: generated
+// trips bound for the cleared placement turn back to where they started
+// (physics_trips.go), scheduled citizens on their way to it head home
+// (physics_citizens.go), and AI cars and pedestrians whose route still runs
+// over its footprint are routed around it. Undoing a demolition puts the placement back and
+// takes the refund back out of the treasury, and redoing it pays it again,
+// so demolishing over and over through the history earns nothing.
+
+// DemolishPlacement removes a placement, refunding part of its price,
+// returning the refund and false if there is no such placement
+func (w *World) DemolishPlacement(id int) (float64, bool) {
+	cmd := &demolitionEdit{remove: placementEdit{id: id}}
+	if !w.Edit(cmd) {
+		return 0, false
+	}
+	return cmd.refund, true
+}
+
+// demolitionEdit removes a placement as a paid demolition, keeping the
+// refund so reverting can charge it back
+type demolitionEdit struct {
+	remove placementEdit
+	refund float64
+}
+
+func (e *demolitionEdit) apply(w *World) bool {
+	removed := w.placed(e.remove.id)
+	if removed == nil || !e.remove.apply(w) {
+		return false
+	}
+
+	p := &removed.placement
+	e.refund = w.Demolish(p.Model)
+	x, z := p.Center()
+	w.pushSimEvent(SimEvent{
+		Kind:  SimDemolished,
+		ID:    p.ID,
+		Model: p.Model,
+		Value: e.refund,
+		X:     x,
+		Z:     z,
+		Width: p.Footprint.MaxX - p.Footprint.MinX,
+		Depth: p.Footprint.MaxY - p.Footprint.MinY,
+	})
+	w.abandonRoutesTo(p.ID)
+	w.clearRoutesThrough(p.Footprint)
+	return true
+}
+
+func (e *demolitionEdit) revert(w *World) {
+	e.remove.revert(w)
+	w.repayRefund(e.refund)
+}
+
+// abandonRoutesTo turns back every trip and citizen travelling to a
+// demolished placement; AI cars and pedestrians that cannot be turned back
+// are taken off
+func (w *World) abandonRoutesTo(id int) {
+	d := w.Trips
+	for _, trip := range d.active {
+		if car := w.Car(trip.CarID); trip.Destination != id || car == nil || car.AI == nil {
+			continue
+		}
+		trip.Destination = trip.Origin
+		if !w.routeCarTo(trip.CarID, trip.Origin) {
+			w.RemoveCar(trip.CarID)
+			d.Failed++
+		}
+	}
+
+	for _, ct := range w.Citizens.list {
+		if ct.Mode == TravelNone || ct.Place != id || ct.Home == id {
+			continue // Citizens whose home came down leave with the census
+		}
+		ct.Activity, ct.Place = ActivityHome, ct.Home
+		home := w.Occupancy.Placement(ct.Home)
+		switch ct.Mode {
+		case TravelDriving:
+			if car := w.Car(ct.Agent); car == nil || car.AI == nil || w.routeCarTo(ct.Agent, ct.Home) {
+				continue // Cars gone or taken over from JavaScript end the leg next step
+			}
+		case TravelWalking:
+			if p := w.Pedestrian(ct.Agent); p != nil && home != nil {
+				hx, hz := home.Center()
+				if w.SpawnPedestrian(p.ID, p.X, p.Z, hx, hz, p.Speed, false) != nil {
+					_ = w.SpawnPedestrian(p.ID, p.X, p.Z, hx, hz, p.Speed, true) // Direct walks cannot fail
+				}
+				continue
+			}
+		}
+		w.removeCitizenAgent(ct)
+	}
+}
+
+// clearRoutesThrough replans every AI car route and pedestrian walk still
+// to pass over a cleared footprint, such as a demolished road tile, around
+// it; cars with no way around are taken off, and pedestrians with none walk
+// straight to where they were going, over the cleared ground
+func (w *World) clearRoutesThrough(area BoundingBox) {
+	crosses := func(e *RoadEdge) bool {
+		from, to := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		return segmentCrossesBox(from.X, from.Z, to.X, to.Z, area)
+	}
+
+	var stranded []int
+	for _, car := range w.cars {
+		ai := car.AI
+		if ai == nil || ai.Arrived || ai.EdgeIndex >= len(ai.Route.Edges) {
+			continue
+		}
+		ahead := false // The edge the car is on is left to finish
+		for _, id := range ai.Route.Edges[ai.EdgeIndex+1:] {
+			if e := w.Roads.Edge(id); e != nil && crosses(e) {
+				ahead = true
+				break
+			}
+		}
+		if ahead && w.routeAI(car.State.ID, ai.Route.Nodes[len(ai.Route.Nodes)-1], crosses) != nil {
+			stranded = append(stranded, car.State.ID)
+		}
+	}
+	for _, id := range stranded {
+		w.RemoveCar(id)
+		for _, trip := range w.Trips.active {
+			if trip.CarID == id {
+				w.Trips.Failed++
+			}
+		}
+	}
+
+	for _, p := range w.pedestrians {
+		if p.State == PedestrianCrossing || !walkCrosses(p, area) {
+			continue
+		}
+		end := p.path[len(p.path)-1]
+		p.path, p.State = []pedestrianLeg{{X: end.X, Z: end.Z}}, PedestrianWalking
+		start, goal := w.Roads.NearestNode(p.X, p.Z), w.Roads.NearestNode(end.X, end.Z)
+		if start == nil || goal == nil {
+			continue
+		}
+		if route, ok := w.Roads.FindRouteAvoiding(start.ID, goal.ID, crosses); ok {
+			p.path = w.pedestrianPath(route, end.X, end.Z)
+		}
+	}
+}
+
+// walkCrosses reports whether any leg left of a pedestrian's path passes over an area
+func walkCrosses(p *Pedestrian, area BoundingBox) bool {
+	x, z := p.X, p.Z
+	for _, leg := range p.path {
+		if segmentCrossesBox(x, z, leg.X, leg.Z, area) {
+			return true
+		}
+		x, z = leg.X, leg.Z
+	}
+	return false
+}
+
+// segmentCrossesBox reports whether a segment runs through the inside of a
+// bounding box, rather than only touching its edge
+func segmentCrossesBox(ax, az, bx, bz float64, b BoundingBox) bool {
+	enter, exit, _, _, ok := raySlab2D(ax, az, bx-ax, bz-az, b.MinX, b.MinY, b.MaxX, b.MaxY)
+	return ok && enter < exit && enter < 1 && exit > 0
+}
+
+// routeCarTo reroutes an AI car to the road node nearest a placement,
+// returning false if the placement is gone or there is no route
+func (w *World) routeCarTo(carID, placementID int) bool {
+	p := w.Occupancy.Placement(placementID)
+	if p == nil {
+		return false
+	}
+	node := w.Roads.NearestNode(p.Center())
+	return node != nil && w.SetAIDestination(carID, node.ID) == nil
+}
@@ -0,0 +1,114 @@
+package main
+
+import "testing"
+
+func TestDemolishUndoDemolishRefundsOnce(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testHouse}, testPlacement(1, testHouse, 0, 0, 2, 2))
+	w.Economy.Enabled = true
+	start := w.Economy.Funds
+	want := w.PriceOf(testHouse.Name).Cost * DEMOLITION_REFUND
+	if want <= 0 {
+		t.Fatalf("building has no price to refund")
+	}
+
+	refund, ok := w.DemolishPlacement(1)
+	if !ok || refund != want {
+		t.Fatalf("DemolishPlacement = %v, %v; want %v, true", refund, ok, want)
+	}
+	if w.Economy.Funds != start+want {
+		t.Fatalf("funds after demolition = %v, want %v", w.Economy.Funds, start+want)
+	}
+
+	for i := 0; i < 3; i++ {
+		if !w.Undo() {
+			t.Fatal("Undo found nothing to revert")
+		}
+		if w.Occupancy.Placement(1) == nil {
+			t.Fatal("Undo did not put the building back")
+		}
+		if w.Economy.Funds != start {
+			t.Fatalf("funds after undo %d = %v, want %v", i, w.Economy.Funds, start)
+		}
+		if _, ok := w.DemolishPlacement(1); !ok {
+			t.Fatalf("demolishing again %d failed", i)
+		}
+		if w.Economy.Funds != start+want {
+			t.Fatalf("funds after demolishing again %d = %v, want %v", i, w.Economy.Funds, start+want)
+		}
+	}
+
+	w.Undo()
+	if !w.Redo() || w.Occupancy.Placement(1) != nil {
+		t.Fatal("Redo did not demolish the building again")
+	}
+	if w.Economy.Funds != start+want {
+		t.Fatalf("funds after redo = %v, want %v", w.Economy.Funds, start+want)
+	}
+}
+
+// Roads from node 1 to 3 through node 2, with a road tile (placement 2)
+// over the second half, and a way round from node 2 through node 4
+var (
+	detourNodes = [][3]float64{{1, 0, 0}, {2, 10, 0}, {3, 20, 0}, {4, 10, 10}}
+	detourTile  = testPlacement(2, NewModelSpec("road_straight.gltf", "street", 2, 2), 14, -1, 16, 1)
+)
+
+func TestDemolishReroutesCarsThroughFootprint(t *testing.T) {
+	for _, tc := range []struct {
+		detour bool
+		nodes  []int // nil for the car taken off
+	}{
+		{true, []int{1, 2, 4, 3}},
+		{false, nil},
+	} {
+		w := testWorld(t, nil, detourTile)
+		edges := [][2]int{{1, 2}, {2, 3}}
+		if tc.detour {
+			edges = append(edges, [2]int{2, 4}, [2]int{4, 3})
+		}
+		first := testRoads(t, w, detourNodes, edges)[0]
+		if err := w.SpawnAICar(7, 1, 3, DefaultVehicleConfig()); err != nil {
+			t.Fatal(err)
+		}
+		if _, ok := w.DemolishPlacement(detourTile.ID); !ok {
+			t.Fatal("demolishing the road tile failed")
+		}
+
+		car := w.Car(7)
+		if tc.nodes == nil {
+			if car != nil {
+				t.Fatal("car whose only route crossed the demolished tile still drives it")
+			}
+			continue
+		}
+		if car == nil || car.AI == nil {
+			t.Fatal("car with a way round was taken off")
+		}
+		route := car.AI.Route
+		if len(route.Edges) != 3 || route.Edges[0] != first {
+			t.Fatalf("route edges = %v, want the current edge and two round node 4", route.Edges)
+		}
+		if got := route.Nodes; len(got) != len(tc.nodes) || got[2] != tc.nodes[2] || got[3] != tc.nodes[3] {
+			t.Fatalf("route nodes = %v, want %v", got, tc.nodes)
+		}
+	}
+}
+
+func TestDemolishReroutesPedestriansThroughFootprint(t *testing.T) {
+	w := testWorld(t, nil, detourTile)
+	testRoads(t, w, detourNodes, [][2]int{{1, 2}, {2, 3}, {2, 4}, {4, 3}})
+	if err := w.SpawnPedestrian(9, 0, 0, 20, 0, 0, true); err != nil {
+		t.Fatal(err)
+	}
+	if _, ok := w.DemolishPlacement(detourTile.ID); !ok {
+		t.Fatal("demolishing the road tile failed")
+	}
+
+	p := w.Pedestrian(9)
+	if walkCrosses(p, detourTile.Footprint) {
+		t.Fatal("pedestrian still walks over the demolished tile")
+	}
+	if end := p.path[len(p.path)-1]; end.X != 20 || end.Z != 0 {
+		t.Fatalf("pedestrian now walks to %v, %v; want 20, 0", end.X, end.Z)
+	}
+}
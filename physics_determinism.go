@@ -0,0 +1,221 @@
+package main
+
+import (
+	"encoding/json"
+	"hash/fnv"
+	"math"
+	"math/rand"
+)
+
+// ============================================================================
+// Deterministic Simulation (lockstep multiplayer and replays)
+// ============================================================================
+
+// Worlds given the same seed, snapshot and inputs step to bit-identical
+// state, in the browser and in the native build (physics_headless.go), so
+// lockstep peers stay in sync and replays reproduce a session:
+//
+//   - Iteration: the step visits cars and pedestrians in insertion order and
+//     roads, placements and jams in ID order, never in map order, wherever
+//     order changes the outcome or the order of events.
+//   - Randomness: weather, trips, fires, missions and trams draw from SimRand generators
+//     seeded from the world seed (SetSeed). Their whole state is one word,
+//     which snapshots save, so a restored world continues the same sequences.
+//   - Math: float add, multiply, divide and sqrt are exact everywhere, and
+//     Go's sin, cos, tan and atan are plain Go code, so trig needs no tables.
+//     Exp, log and hypot have amd64 assembly, though, so the step uses the
+//     portable simExp, simPow and simHypot below. Native builds must not fuse
+//     multiply-adds: amd64 with the default GOAMD64=v1 matches the browser,
+//     arm64 (which fuses) differs in the last bits.
+//
+// Checksum hashes the snapshot so peers can compare state each turn and
+// catch a desync as soon as it happens.
+
+// simSource is a splitmix64 generator: a rand.Source whose state is a
+// single word that can be saved and restored
+type simSource struct {
+	state uint64
+}
+
+func (s *simSource) Seed(seed int64) {
+	s.state = uint64(seed)
+}
+
+func (s *simSource) Uint64() uint64 {
+	s.state += 0x9e3779b97f4a7c15
+	z := s.state
+	z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9
+	z = (z ^ (z >> 27)) * 0x94d049bb133111eb
+	return z ^ (z >> 31)
+}
+
+func (s *simSource) Int63() int64 {
+	return int64(s.Uint64() >> 1)
+}
+
+// SimRand is a seeded random generator whose place in its sequence can be
+// saved (State) and restored (SetState)
+type SimRand struct {
+	*rand.Rand
+	src *simSource
+}
+
+// newSimRand creates a generator at the start of a seed's sequence
+func newSimRand(seed int64) *SimRand {
+	src := &simSource{state: uint64(seed)}
+	return &SimRand{Rand: rand.New(src), src: src}
+}
+
+// State returns the generator's place in its sequence
+func (r *SimRand) State() uint64 {
+	return r.src.state
+}
+
+// SetState moves the generator to a place saved with State
+func (r *SimRand) SetState(state uint64) {
+	r.src.state = state
+}
+
+// SetSeed restarts every random generator the step draws from, each from
+// its own offset of seed; worlds given the same seed draw the same numbers
+func (w *World) SetSeed(seed int64) {
+	w.Seed = seed
+	w.Weather.rng = newSimRand(seed + WEATHER_SEED)
+	w.Trips.rng = newSimRand(seed + TRIP_SEED)
+	w.Fires.rng = newSimRand(seed + FIRE_SEED)
+	w.Missions.rng = newSimRand(seed + MISSION_SEED)
+	w.Trams.rng = newSimRand(seed + TRAM_SEED)
+	w.Citizens.rng = newSimRand(seed + CITIZEN_SEED)
+}
+
+// Checksum returns a hash of the world's snapshot, equal on every peer
+// whose world is in the same state
+func (w *World) Checksum() (uint64, error) {
+	s := w.Snapshot()
+	return snapshotChecksum(&s)
+}
+
+// snapshotChecksum returns a hash of a snapshot's JSON, which lists map
+// keys in order, so equal states hash alike
+func snapshotChecksum(s *WorldSnapshot) (uint64, error) {
+	data, err := json.Marshal(s)
+	if err != nil {
+		return 0, err
+	}
+	h := fnv.New64a()
+	h.Write(data)
+	return h.Sum64(), nil
+}
+
+// simHypot is math.Hypot without the amd64 assembly: scaled by the longer
+// side so it cannot overflow
+func simHypot(p, q float64) float64 {
+	p, q = math.Abs(p), math.Abs(q)
+	switch {
+	case math.IsInf(p, 1) || math.IsInf(q, 1):
+		return math.Inf(1)
+	case math.IsNaN(p) || math.IsNaN(q):
+		return math.NaN()
+	}
+	if p < q {
+		p, q = q, p
+	}
+	if p == 0 {
+		return 0
+	}
+	q = q / p
+	return p * math.Sqrt(1+q*q)
+}
+
+// simExp is math.Exp without the amd64 assembly (the FreeBSD algorithm the
+// library falls back to elsewhere): e^x = 2^k * e^r with |r| <= ln2/2
+func simExp(x float64) float64 {
+	const (
+		ln2Hi     = 6.93147180369123816490e-01
+		ln2Lo     = 1.90821492927058770002e-10
+		log2e     = 1.44269504088896338700e+00
+		overflow  = 7.09782712893383973096e+02
+		underflow = -7.45133219101941108420e+02
+		nearZero  = 1.0 / (1 << 28)
+
+		p1 = 1.66666666666666657415e-01
+		p2 = -2.77777777770155933842e-03
+		p3 = 6.61375632143793436117e-05
+		p4 = -1.65339022054652515390e-06
+		p5 = 4.13813679705723846039e-08
+	)
+	switch {
+	case math.IsNaN(x) || math.IsInf(x, 1):
+		return x
+	case math.IsInf(x, -1) || x < underflow:
+		return 0
+	case x > overflow:
+		return math.Inf(1)
+	case -nearZero < x && x < nearZero:
+		return 1 + x
+	}
+
+	var k int
+	if x < 0 {
+		k = int(log2e*x - 0.5)
+	} else {
+		k = int(log2e*x + 0.5)
+	}
+	hi := x - float64(k)*ln2Hi
+	lo := float64(k) * ln2Lo
+	r := hi - lo
+	t := r * r
+	c := r - t*(p1+t*(p2+t*(p3+t*(p4+t*p5))))
+	return math.Ldexp(1-((lo-(r*c)/(2-c))-hi), k)
+}
+
+// simLog is math.Log without the amd64 assembly (FreeBSD's algorithm):
+// log(x) = k*ln2 + log(1+f) with sqrt(2)/2 <= 1+f < sqrt(2)
+func simLog(x float64) float64 {
+	const (
+		ln2Hi = 6.93147180369123816490e-01
+		ln2Lo = 1.90821492927058770002e-10
+
+		l1 = 6.666666666666735130e-01
+		l2 = 3.999999999940941908e-01
+		l3 = 2.857142874366239149e-01
+		l4 = 2.222219843214978396e-01
+		l5 = 1.818357216161805012e-01
+		l6 = 1.531383769920937332e-01
+		l7 = 1.479819860511658591e-01
+	)
+	switch {
+	case math.IsNaN(x) || math.IsInf(x, 1):
+		return x
+	case x < 0:
+		return math.NaN()
+	case x == 0:
+		return math.Inf(-1)
+	}
+
+	f1, ki := math.Frexp(x)
+	if f1 < math.Sqrt2/2 {
+		f1 *= 2
+		ki--
+	}
+	f := f1 - 1
+	k := float64(ki)
+	s := f / (2 + f)
+	s2 := s * s
+	s4 := s2 * s2
+	t1 := s2 * (l1 + s4*(l3+s4*(l5+s4*l7)))
+	t2 := s4 * (l2 + s4*(l4+s4*l6))
+	r := t1 + t2
+	hfsq := 0.5 * f * f
+	return k*ln2Hi - ((hfsq - (s*(hfsq+r) + k*ln2Lo)) - f)
+}
+
+// simPow is math.Pow with fractional powers of positive bases taken through
+// simExp and simLog; whole powers and special cases stay with math.Pow,
+// which computes them with plain multiplications
+func simPow(x, y float64) float64 {
+	if x > 0 && isFinite(x) && isFinite(y) && y != math.Trunc(y) {
+		return simExp(y * simLog(x))
+	}
+	return math.Pow(x, y)
+}
@@ -0,0 +1,261 @@
+package main
+
+import (
+	"errors"
+	"sort"
+)
+
+// ============================================================================
+// Districts (painted neighborhoods with their own policies)
+// ============================================================================
+
+// The player creates named districts and paints them onto the occupancy
+// grid's cell layout, like zones; a cell belongs to at most one district.
+// Each district carries policies the town systems respect where it is
+// painted: a speed factor scaling the speed limit of the roads through it,
+// which AI drivers keep to (physics_traffic.go) and plan their routes by
+// (physics_pathfinding.go), the speed limiter holds cars to
+// (physics_cruise.go) and speeding is booked against
+// (physics_violations.go); a tax rate scaling the town's resident and
+// business taxes on its homes and workplaces (physics_economy.go); and an
+// industry ban keeping out everything that may only be built in industrial
+// zones (physics_placement.go). Policies apply to a building by the cell at
+// the middle of its footprint, to cars by the cell they are on and to route
+// planning by the cell at the middle of each segment; cells in no district
+// keep the town's own settings. Painting goes through the edit history
+// (physics_edits.go); creating, changing and removing districts do not.
+
+const (
+	NO_DISTRICT               = 0    // District ID of cells in no district
+	DISTRICT_MIN_SPEED_FACTOR = 0.25 // Slowest a district may make its roads
+	DISTRICT_MAX_SPEED_FACTOR = 2.0  // Fastest a district may make its roads
+	DISTRICT_MAX_TAX_RATE     = 3.0  // Highest multiple of the town's taxes a district may charge
+)
+
+// DistrictPolicies are the rules of one district
+type DistrictPolicies struct {
+	SpeedFactor float64 // Scales road speed limits, 1 leaves them
+	TaxRate     float64 // Scales the town's resident and business taxes, 1 leaves them
+	IndustryBan bool    // Nothing restricted to industrial zones may be built
+}
+
+// DefaultDistrictPolicies returns policies that change nothing
+func DefaultDistrictPolicies() DistrictPolicies {
+	return DistrictPolicies{SpeedFactor: 1, TaxRate: 1}
+}
+
+// District is one named district
+type District struct {
+	ID       int
+	Name     string
+	Policies DistrictPolicies
+	Taxes    float64 // Collected from its homes and workplaces at the end of the last day
+}
+
+// DistrictMap holds a district per cell, on the same layout as the occupancy grid
+type DistrictMap struct {
+	GridSpec
+	cells     []int // District ID per cell, NO_DISTRICT if none
+	districts map[int]*District
+	nextID    int
+}
+
+// NewDistrictMap creates a map without districts
+func NewDistrictMap(spec GridSpec) *DistrictMap {
+	return &DistrictMap{GridSpec: spec, cells: make([]int, spec.Len()), districts: make(map[int]*District), nextID: 1}
+}
+
+// Create adds a district with policies that change nothing, returning it
+func (m *DistrictMap) Create(name string) *District {
+	d := &District{ID: m.nextID, Name: name, Policies: DefaultDistrictPolicies()}
+	m.districts[d.ID] = d
+	m.nextID++
+	return d
+}
+
+// Remove deletes a district and unpaints its cells, returning false if it does not exist
+func (m *DistrictMap) Remove(id int) bool {
+	if _, exists := m.districts[id]; !exists {
+		return false
+	}
+	delete(m.districts, id)
+	for i, c := range m.cells {
+		if c == id {
+			m.cells[i] = NO_DISTRICT
+		}
+	}
+	return true
+}
+
+// District returns the district with an ID, or nil
+func (m *DistrictMap) District(id int) *District {
+	return m.districts[id]
+}
+
+// List returns every district in ID order
+func (m *DistrictMap) List() []*District {
+	list := make([]*District, 0, len(m.districts))
+	for _, d := range m.districts {
+		list = append(list, d)
+	}
+	sort.Slice(list, func(i, j int) bool { return list[i].ID < list[j].ID })
+	return list
+}
+
+// Cell returns the district ID of a cell
+func (m *DistrictMap) Cell(i int) int {
+	return m.cells[i]
+}
+
+// At returns the district at a world position, or nil outside any district
+func (m *DistrictMap) At(x, z float64) *District {
+	i := m.CellIndex(x, z)
+	if i < 0 {
+		return nil
+	}
+	return m.districts[m.cells[i]]
+}
+
+// PoliciesAt returns the policies in force at a world position
+func (m *DistrictMap) PoliciesAt(x, z float64) DistrictPolicies {
+	if d := m.At(x, z); d != nil {
+		return d.Policies
+	}
+	return DefaultDistrictPolicies()
+}
+
+// Paint puts every cell whose interior overlaps a bounding box in a
+// district (NO_DISTRICT to erase), returning the number of cells covered
+func (m *DistrictMap) Paint(bbox BoundingBox, id int) int {
+	minX, minZ, maxX, maxZ, ok := m.CellRange(bbox)
+	if !ok {
+		return 0
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			m.cells[cz*m.Width+cx] = id
+		}
+	}
+	return (maxX - minX + 1) * (maxZ - minZ + 1)
+}
+
+// Resize changes the cell layout, resampling districts at the new cell centers
+func (m *DistrictMap) Resize(spec GridSpec) {
+	cells := make([]int, spec.Len())
+	for i := range cells {
+		if j := m.CellIndex(spec.CellCenter(i)); j >= 0 {
+			cells[i] = m.cells[j]
+		}
+	}
+	m.GridSpec = spec
+	m.cells = cells
+}
+
+// SetDistrictPolicies changes a district's policies, each clamped to its range
+func (w *World) SetDistrictPolicies(id int, p DistrictPolicies) error {
+	d := w.Districts.District(id)
+	if d == nil {
+		return errors.New("no district with that id")
+	}
+	if !isFinite(p.SpeedFactor) || !isFinite(p.TaxRate) {
+		return errors.New("district policies must be finite")
+	}
+	p.SpeedFactor = clamp(p.SpeedFactor, DISTRICT_MIN_SPEED_FACTOR, DISTRICT_MAX_SPEED_FACTOR)
+	p.TaxRate = clamp(p.TaxRate, 0, DISTRICT_MAX_TAX_RATE)
+	d.Policies = p
+	return nil
+}
+
+// roadSpeedLimit returns a road segment's speed limit as the district at a
+// point on it sets it
+func (w *World) roadSpeedLimit(e *RoadEdge, x, z float64) float64 {
+	return e.SpeedLimit * w.Districts.PoliciesAt(x, z).SpeedFactor
+}
+
+// edgeSpeedLimit returns a road segment's speed limit as the district at its
+// middle sets it, the limit cars plan routes by
+func (w *World) edgeSpeedLimit(e *RoadEdge) float64 {
+	from, to := w.Roads.Node(e.From), w.Roads.Node(e.To)
+	return w.roadSpeedLimit(e, (from.X+to.X)/2, (from.Z+to.Z)/2)
+}
+
+// taxRateOf returns the district tax rate of a placement
+func (w *World) taxRateOf(p *Placement) float64 {
+	return w.Districts.PoliciesAt(p.Center()).TaxRate
+}
+
+// districtBans reports whether a model restricted to industrial zones would
+// be built over a footprint touching a district that bans industry
+func (w *World) districtBans(spec ModelSpec, f BoundingBox) bool {
+	if rule, ruled := w.ZoneRules[spec.Use]; !ruled || rule != zoneMaskOf(ZoneIndustrial) {
+		return false
+	}
+	m := w.Districts
+	minX, minZ, maxX, maxZ, ok := m.CellRange(f)
+	if !ok {
+		return false
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			if d := m.districts[m.cells[cz*m.Width+cx]]; d != nil && d.Policies.IndustryBan {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// DistrictStats summarizes one district
+type DistrictStats struct {
+	ID        int
+	Name      string
+	Cells     int
+	Area      float64 // units²
+	Buildings int     // Buildings whose footprint's middle is in the district
+	Residents int
+	Jobs      int
+	LandValue float64 // Mean over its cells, 0-1
+	Taxes     float64 // Collected at the end of the last day
+}
+
+// DistrictStats returns the stats of every district, in ID order
+func (w *World) DistrictStats() []DistrictStats {
+	m := w.Districts
+	list := m.List()
+	index := make(map[int]int, len(list))
+	stats := make([]DistrictStats, len(list))
+	for i, d := range list {
+		index[d.ID] = i
+		stats[i] = DistrictStats{ID: d.ID, Name: d.Name, Taxes: d.Taxes}
+	}
+	if len(list) == 0 {
+		return stats
+	}
+
+	value := w.LandValue()
+	for i, id := range m.cells {
+		if j, ok := index[id]; ok {
+			stats[j].Cells++
+			stats[j].LandValue += value.Cells[i]
+		}
+	}
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		d := m.At(p.Center())
+		if d == nil || !flammable(p) {
+			continue
+		}
+		s := &stats[index[d.ID]]
+		s.Buildings++
+		s.Residents += w.Population.HomeResidents(id)
+		s.Jobs += w.capacity(p).Jobs
+	}
+	for i := range stats {
+		s := &stats[i]
+		s.Area = float64(s.Cells) * m.CellSize * m.CellSize
+		if s.Cells > 0 {
+			s.LandValue /= float64(s.Cells)
+		}
+	}
+	return stats
+}
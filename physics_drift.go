@@ -0,0 +1,119 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Drift Scoring (sustained slides and combos)
+// ============================================================================
+
+// A world car drifts while it slides at a slip angle between
+// DRIFT_MIN_ANGLE and DRIFT_MAX_ANGLE (past that it is spinning out) faster
+// than DRIFT_MIN_SPEED. A drift earns DRIFT_POINTS points per second per
+// unit/s of speed per radian of slip, so it pays to slide fast, wide and
+// long; dipping out of the slide for less than DRIFT_GRACE_SECONDS does not
+// end it. A drift held DRIFT_MIN_SECONDS or longer is banked into a combo at
+// the combo's multiplier, which rises by DRIFT_COMBO_STEP with every drift
+// chained within DRIFT_COMBO_SECONDS of the last; a "drift" simulation
+// event reports each banked drift and a "drift_combo" event each combo once
+// it runs out (physics_sim_events.go). Hitting a building, a prop or the
+// shore loses the drift and the combo in progress.
+
+const (
+	DRIFT_MIN_ANGLE      = 0.25 // rad (~14°) of slip before a slide is a drift
+	DRIFT_MAX_ANGLE      = 1.4  // rad (~80°), wider is a spin
+	DRIFT_MIN_SPEED      = 4.0  // units/s
+	DRIFT_POINTS         = 10.0 // Points per second per units/s per rad of slip
+	DRIFT_GRACE_SECONDS  = 0.3  // s out of the slide that do not end a drift
+	DRIFT_MIN_SECONDS    = 0.5  // s a drift must last to score
+	DRIFT_COMBO_SECONDS  = 2.0  // s after a drift in which the next one chains
+	DRIFT_COMBO_STEP     = 0.5  // Multiplier added by every chained drift
+	DRIFT_MAX_MULTIPLIER = 5.0
+)
+
+// DriftState is a car's drift in progress, its combo and its totals
+type DriftState struct {
+	Drifting bool
+	Time     float64 // s into the current drift
+	Points   float64 // Unmultiplied points of the current drift
+	Grace    float64 // s out of the slide so far
+
+	Combo       int     // Drifts banked into the current combo
+	ComboPoints float64 // Multiplied points of the current combo
+	ComboTimer  float64 // s since the last drift was banked
+
+	Total float64 // Points of every finished combo
+	Best  float64 // Best finished combo
+}
+
+// Multiplier returns the multiplier the next banked drift scores at
+func (d *DriftState) Multiplier() float64 {
+	return math.Min(1+DRIFT_COMBO_STEP*float64(d.Combo), DRIFT_MAX_MULTIPLIER)
+}
+
+// stepDrift scores one step of a car's driving
+func (w *World) stepDrift(car *WorldCar, dt float64) {
+	d := &car.Drift
+	s := &car.State
+	if s.Collided || s.HitWater {
+		*d = DriftState{Total: d.Total, Best: d.Best} // Crashed out of the drift and the combo
+		return
+	}
+
+	speed := simHypot(s.VelocityX, s.VelocityZ)
+	slip := math.Abs(s.SlipAngle)
+	sliding := !s.Reversing && speed >= DRIFT_MIN_SPEED && slip >= DRIFT_MIN_ANGLE && slip <= DRIFT_MAX_ANGLE
+	switch {
+	case sliding:
+		d.Drifting = true
+		d.Time += dt
+		d.Grace = 0
+		d.Points += DRIFT_POINTS * speed * slip * dt
+		return
+	case d.Drifting:
+		d.Grace += dt
+		if d.Grace < DRIFT_GRACE_SECONDS {
+			return
+		}
+		w.bankDrift(car)
+		return
+	}
+
+	if d.Combo > 0 {
+		d.ComboTimer += dt
+		if d.ComboTimer >= DRIFT_COMBO_SECONDS {
+			w.endDriftCombo(car)
+		}
+	}
+}
+
+// bankDrift ends a car's drift, adding it to the combo if it was held long enough
+func (w *World) bankDrift(car *WorldCar) {
+	d := &car.Drift
+	time, points := d.Time, d.Points
+	d.Drifting, d.Time, d.Points, d.Grace = false, 0, 0, 0
+	if time < DRIFT_MIN_SECONDS {
+		return // Too short to score; the combo clock keeps running
+	}
+
+	scored := points * d.Multiplier()
+	d.Combo++
+	d.ComboPoints += scored
+	d.ComboTimer = 0
+	w.pushSimEvent(SimEvent{Kind: SimDriftScored, ID: car.State.ID, To: d.Combo, Value: scored, X: car.State.X, Z: car.State.Z})
+}
+
+// endDriftCombo banks a car's combo into its total
+func (w *World) endDriftCombo(car *WorldCar) {
+	d := &car.Drift
+	d.Total += d.ComboPoints
+	d.Best = math.Max(d.Best, d.ComboPoints)
+	w.pushSimEvent(SimEvent{Kind: SimDriftCombo, ID: car.State.ID, To: d.Combo, Value: d.ComboPoints, X: car.State.X, Z: car.State.Z})
+	d.Combo, d.ComboPoints, d.ComboTimer = 0, 0, 0
+}
+
+// stepDrifts scores every car's driving during a step
+func (w *World) stepDrifts(dt float64) {
+	for _, car := range w.cars {
+		w.stepDrift(car, dt)
+	}
+}
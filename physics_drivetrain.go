@@ -0,0 +1,125 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Drivetrain (engine RPM and automatic gearbox)
+// ============================================================================
+
+// The drivetrain is cosmetic: it does not change acceleration, it derives
+// engine RPM and the selected gear from wheel speed so the frontend can drive
+// engine audio pitch and a tachometer.
+
+const (
+	GEAR_REVERSE = -1 // Gear reported while the reverse gear is engaged
+
+	CLUTCH_RPM  = 2500.0 // Engine speed held by clutch slip when launching from rest
+	RPM_RATE    = 12.0   // 1/s, how quickly the engine converges on its target RPM
+	SHIFT_TIME  = 0.25   // s, power is cut for this long during a shift
+	MIN_GEARBOX = 1      // Minimum number of forward gears
+)
+
+// GearboxConfig describes an automatic gearbox
+// Wheel speed is scaled so the top gear reaches RedlineRPM at the vehicle's MaxSpeed
+type GearboxConfig struct {
+	GearRatios   []float64 // Forward gear ratios, first gear first (descending)
+	IdleRPM      float64
+	RedlineRPM   float64
+	ShiftUpRPM   float64 // Upshift when RPM rises above this
+	ShiftDownRPM float64 // Downshift when RPM falls below this
+}
+
+// DefaultGearboxConfig returns a 5-speed gearbox for the standard car
+func DefaultGearboxConfig() GearboxConfig {
+	return GearboxConfig{
+		GearRatios:   []float64{3.2, 2.1, 1.5, 1.1, 0.85},
+		IdleRPM:      800.0,
+		RedlineRPM:   6500.0,
+		ShiftUpRPM:   5500.0,
+		ShiftDownRPM: 2200.0,
+	}
+}
+
+// sanitized replaces an invalid gearbox with the default one
+func (g GearboxConfig) sanitized() GearboxConfig {
+	d := DefaultGearboxConfig()
+	if len(g.GearRatios) < MIN_GEARBOX {
+		return d
+	}
+	for _, ratio := range g.GearRatios {
+		if !(ratio > 0) {
+			return d
+		}
+	}
+
+	g.IdleRPM = positiveOr(g.IdleRPM, d.IdleRPM)
+	g.RedlineRPM = math.Max(positiveOr(g.RedlineRPM, d.RedlineRPM), g.IdleRPM+1)
+	if !(g.ShiftUpRPM > g.IdleRPM && g.ShiftUpRPM <= g.RedlineRPM) {
+		g.ShiftUpRPM = g.IdleRPM + (g.RedlineRPM-g.IdleRPM)*0.85
+	}
+	if !(g.ShiftDownRPM >= g.IdleRPM && g.ShiftDownRPM < g.ShiftUpRPM) {
+		g.ShiftDownRPM = g.IdleRPM + (g.ShiftUpRPM-g.IdleRPM)*0.3
+	}
+	return g
+}
+
+// wheelRPM returns the engine RPM the wheels would impose in the given gear
+func (cfg *VehicleConfig) wheelRPM(speed float64, gear int) float64 {
+	g := &cfg.Gearbox
+	ratio := g.GearRatios[0] // Reverse uses first-gear gearing
+	if gear >= 1 && gear <= len(g.GearRatios) {
+		ratio = g.GearRatios[gear-1]
+	}
+	topRatio := g.GearRatios[len(g.GearRatios)-1]
+	return g.RedlineRPM * (speed / cfg.MaxSpeed) * (ratio / topRatio)
+}
+
+// updateDrivetrain picks the gear and advances engine RPM for this substep
+func updateDrivetrain(car *CarState, input InputState, cfg *VehicleConfig, dt float64) {
+	g := &cfg.Gearbox
+	speed := math.Sqrt(car.VelocityX*car.VelocityX + car.VelocityZ*car.VelocityZ)
+
+	// Throttle load is whichever pedal is currently driving the wheels
+	load := input.Throttle
+	if car.Reversing {
+		load = input.Brake
+	}
+
+	if car.ShiftTimer > 0 {
+		car.ShiftTimer = math.Max(0, car.ShiftTimer-dt)
+		load = 0 // Power is cut mid-shift
+	}
+
+	if car.Reversing {
+		car.Gear = GEAR_REVERSE
+	} else {
+		if car.Gear < 1 || car.Gear > len(g.GearRatios) {
+			car.Gear = 1
+		}
+
+		// Automatic shifting with hysteresis, one gear at a time
+		if car.ShiftTimer == 0 {
+			rpm := cfg.wheelRPM(speed, car.Gear)
+			if rpm > g.ShiftUpRPM && car.Gear < len(g.GearRatios) {
+				car.Gear++
+				car.ShiftTimer = SHIFT_TIME
+			} else if rpm < g.ShiftDownRPM && car.Gear > 1 {
+				car.Gear--
+				car.ShiftTimer = SHIFT_TIME
+			}
+		}
+	}
+
+	// Target RPM follows the wheels, with clutch slip when launching from rest
+	target := math.Max(g.IdleRPM, cfg.wheelRPM(speed, car.Gear))
+	if load > 0 && target < CLUTCH_RPM {
+		target = math.Max(target, g.IdleRPM+(CLUTCH_RPM-g.IdleRPM)*load)
+	}
+	target = math.Min(target, g.RedlineRPM)
+
+	if car.RPM < g.IdleRPM {
+		car.RPM = g.IdleRPM
+	}
+	car.RPM += (target - car.RPM) * (1 - simExp(-RPM_RATE*dt))
+	car.ThrottleLoad = load
+}
@@ -0,0 +1,212 @@
+package main
+
+// ============================================================================
+// Economy (treasury, construction costs, taxes and upkeep)
+// ============================================================================
+
+// Building costs money up front and upkeep every simulated day; demolishing
+// refunds RefundShare of the price (physics_demolition.go). Residents and
+// filled jobs pay tax at the end of each day, at the rate of the district
+// they are in (physics_districts.go). The economy starts disabled so sandbox
+// towns build for free; once enabled, CanPlace rejects builds the treasury
+// cannot pay for.
+
+const (
+	STARTING_FUNDS    = 20000.0 // Treasury of a new town
+	DEMOLITION_REFUND = 0.5     // Default share of the price returned on demolition
+	RESIDENT_TAX      = 12.0    // Per resident per day
+	BUSINESS_TAX      = 8.0     // Per filled job per day
+)
+
+// Price is what a model costs to build and keep
+type Price struct {
+	Cost   float64 // Paid when placed
+	Upkeep float64 // Paid every simulated day while it stands
+}
+
+// DefaultPrices returns prices by model use, falling back to model category
+func DefaultPrices() map[string]Price {
+	return map[string]Price{
+		"housing":        {Cost: 800, Upkeep: 5},
+		"market":         {Cost: 1500, Upkeep: 15},
+		"office":         {Cost: 2500, Upkeep: 20},
+		"industry":       {Cost: 2000, Upkeep: 25},
+		"school":         {Cost: 3000, Upkeep: 60},
+		"library":        {Cost: 2000, Upkeep: 35},
+		"healthcare":     {Cost: 4000, Upkeep: 80},
+		"fire_station":   {Cost: 2500, Upkeep: 50},
+		"police_station": {Cost: 2000, Upkeep: 40},
+		"garage":         {Cost: 1200, Upkeep: 15},
+		"gas_station":    {Cost: 1000, Upkeep: 10},
+		"power_plant":    {Cost: 5000, Upkeep: 90},
+		"water_tower":    {Cost: 3000, Upkeep: 45},
+		"bus_stop":       {Cost: 150, Upkeep: 2},
+		"tram_station":   {Cost: 600, Upkeep: 6},
+		"parking_lot":    {Cost: 400, Upkeep: 3},
+		"buildings":      {Cost: 1000, Upkeep: 10},
+		"street":         {Cost: 100, Upkeep: 2},
+		"roads":          {Cost: 100, Upkeep: 2},
+		"park":           {Cost: 300, Upkeep: 4},
+		"trees":          {Cost: 50, Upkeep: 0.5},
+		"props":          {Cost: 30, Upkeep: 0.5},
+	}
+}
+
+// Ledger totals one day of money movements
+type Ledger struct {
+	ResidentTax  float64
+	BusinessTax  float64
+	Refunds      float64
+	Construction float64
+	Upkeep       float64
+	Repairs      float64 // Road maintenance (physics_road_wear.go)
+	Missions     float64 // Delivery rewards (physics_missions.go)
+}
+
+// Income returns the day's earnings
+func (l Ledger) Income() float64 {
+	return l.ResidentTax + l.BusinessTax + l.Refunds + l.Missions
+}
+
+// Expenses returns the day's spending
+func (l Ledger) Expenses() float64 {
+	return l.Construction + l.Upkeep + l.Repairs
+}
+
+// Net returns income minus expenses
+func (l Ledger) Net() float64 {
+	return l.Income() - l.Expenses()
+}
+
+// Economy is the town treasury
+type Economy struct {
+	Enabled     bool
+	Funds       float64 // May go negative through upkeep
+	ResidentTax float64 // Per resident per day
+	BusinessTax float64 // Per filled job per day
+	RefundShare float64 // Share of the price returned on demolition, 0-1
+
+	Today     Ledger // Running totals for the current day
+	Yesterday Ledger // Totals of the last finished day
+}
+
+// NewEconomy creates a disabled treasury holding the starting funds
+func NewEconomy() *Economy {
+	return &Economy{
+		Funds:       STARTING_FUNDS,
+		ResidentTax: RESIDENT_TAX,
+		BusinessTax: BUSINESS_TAX,
+		RefundShare: DEMOLITION_REFUND,
+	}
+}
+
+// PriceOf returns the price of a model (zero for unknown models)
+func (w *World) PriceOf(model string) Price {
+	spec, known := w.Models[model]
+	if !known {
+		return Price{}
+	}
+	if price, ok := w.Prices[spec.Use]; ok && spec.Use != "" {
+		return price
+	}
+	return w.Prices[spec.Category]
+}
+
+// affordable reports whether the treasury can pay for a model
+func (w *World) affordable(model string) bool {
+	return !w.Economy.Enabled || w.Economy.Funds >= w.PriceOf(model).Cost
+}
+
+// Purchase pays for building a model, returning false (and paying nothing)
+// when the treasury cannot afford it; free while the economy is disabled
+// Buildings paid for count towards the buildings bought statistic
+// (physics_stats.go)
+func (w *World) Purchase(model string) bool {
+	e := w.Economy
+	if !e.Enabled {
+		return true
+	}
+	if !w.affordable(model) {
+		return false
+	}
+	cost := w.PriceOf(model).Cost
+	e.Funds -= cost
+	e.Today.Construction += cost
+	if w.Models[model].Category == BUILDING_CATEGORY {
+		w.Stats.Buildings++
+	}
+	return true
+}
+
+// PayRepairs pays for road maintenance, returning false (and paying
+// nothing) when the treasury cannot afford it; free while the economy is
+// disabled
+func (w *World) PayRepairs(cost float64) bool {
+	e := w.Economy
+	if !e.Enabled {
+		return true
+	}
+	if e.Funds < cost {
+		return false
+	}
+	e.Funds -= cost
+	e.Today.Repairs += cost
+	return true
+}
+
+// Demolish refunds part of a model's price, returning the refund
+func (w *World) Demolish(model string) float64 {
+	e := w.Economy
+	if !e.Enabled {
+		return 0
+	}
+	refund := w.PriceOf(model).Cost * e.RefundShare
+	e.Funds += refund
+	e.Today.Refunds += refund
+	return refund
+}
+
+// repayRefund takes a demolition refund back out of the treasury when the
+// demolition is undone, booked as construction since the building is back
+func (w *World) repayRefund(refund float64) {
+	e := w.Economy
+	e.Funds -= refund
+	e.Today.Construction += refund
+}
+
+// settleBudget collects the day's taxes and upkeep and closes the ledger;
+// each home and workplace pays at its district's tax rate
+// (physics_districts.go), workplaces for their share of the employed
+func (w *World) settleBudget() {
+	for _, d := range w.Districts.List() {
+		d.Taxes = 0
+	}
+	e := w.Economy
+	if !e.Enabled {
+		e.Today = Ledger{}
+		return
+	}
+
+	pop := w.Population
+	employment := 0.0
+	if pop.Jobs > 0 {
+		employment = float64(pop.Employed) / float64(pop.Jobs)
+	}
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		e.Today.Upkeep += w.PriceOf(p.Model).Upkeep
+
+		rate := w.taxRateOf(p)
+		resident := float64(pop.HomeResidents(id)) * e.ResidentTax * rate
+		business := float64(w.capacity(p).Jobs) * employment * e.BusinessTax * rate
+		e.Today.ResidentTax += resident
+		e.Today.BusinessTax += business
+		if d := w.Districts.At(p.Center()); d != nil {
+			d.Taxes += resident + business
+		}
+	}
+	e.Funds += e.Today.ResidentTax + e.Today.BusinessTax - e.Today.Upkeep
+
+	e.Yesterday = e.Today
+	e.Today = Ledger{}
+}
@@ -0,0 +1,224 @@
+package main
+
+// ============================================================================
+// Edit History (undo and redo of town edits)
+// ============================================================================
+
+// Editor changes to buildings, roads, zones and districts go through Edit
+// as commands that know how to apply and revert themselves. A command edits
+// the world through the same methods as a direct edit, so a placement and
+// its collider come and go together and the occupancy grid repaints as
+// usual, and reverting a road edit restores the graph with its edge IDs,
+// which routes and signal phases refer to. Undo reverts the newest applied
+// edit and Redo applies the newest undone one again; a new edit drops
+// whatever was undone. Bulk changes made outside the history (replacing
+// placements or the road network, generating roads, importing a layout,
+// resizing the town grid, restoring a snapshot) clear it, since the edits
+// it holds would no longer revert cleanly. Only the town is edited: undoing
+// a building does not refund it, and cars, residents and the clock are left
+// alone; the exception is a paid demolition (physics_demolition.go), whose
+// refund goes back and forth with it.
+
+const MAX_EDIT_HISTORY = 100 // Edits kept for undo; older ones are forgotten
+
+// EditCommand is a town edit that can be reverted
+type EditCommand interface {
+	apply(w *World) bool // Returns false if nothing changed
+	revert(w *World)
+}
+
+// EditHistory holds the edits that can be undone and redone, newest last
+type EditHistory struct {
+	done   []EditCommand
+	undone []EditCommand
+}
+
+// Clear forgets every edit
+func (h *EditHistory) Clear() {
+	h.done, h.undone = nil, nil
+}
+
+// UndoCount returns the number of edits Undo can revert
+func (h *EditHistory) UndoCount() int {
+	return len(h.done)
+}
+
+// RedoCount returns the number of edits Redo can apply again
+func (h *EditHistory) RedoCount() int {
+	return len(h.undone)
+}
+
+// Edit applies a command and records it for undo; a command that changes
+// nothing is not recorded and returns false
+func (w *World) Edit(cmd EditCommand) bool {
+	if !cmd.apply(w) {
+		return false
+	}
+	h := &w.History
+	h.done = append(h.done, cmd)
+	if len(h.done) > MAX_EDIT_HISTORY {
+		h.done = append(h.done[:0], h.done[len(h.done)-MAX_EDIT_HISTORY:]...)
+	}
+	h.undone = nil
+	return true
+}
+
+// Undo reverts the newest edit, returning false if there is none
+func (w *World) Undo() bool {
+	h := &w.History
+	if len(h.done) == 0 {
+		return false
+	}
+	cmd := h.done[len(h.done)-1]
+	h.done = h.done[:len(h.done)-1]
+	cmd.revert(w)
+	h.undone = append(h.undone, cmd)
+	return true
+}
+
+// Redo applies the newest undone edit again, returning false if there is none
+func (w *World) Redo() bool {
+	h := &w.History
+	if len(h.undone) == 0 {
+		return false
+	}
+	cmd := h.undone[len(h.undone)-1]
+	h.undone = h.undone[:len(h.undone)-1]
+	cmd.apply(w)
+	h.done = append(h.done, cmd)
+	return true
+}
+
+// placedObject is a placement with the collider registered under its ID
+type placedObject struct {
+	placement Placement
+	collider  *Collider // nil if it has none
+}
+
+// placed copies the placement with an ID and its collider, or returns nil
+func (w *World) placed(id int) *placedObject {
+	p := w.Occupancy.Placement(id)
+	if p == nil {
+		return nil
+	}
+	obj := &placedObject{placement: *p}
+	if c := w.Colliders.Collider(id); c != nil {
+		collider := *c
+		obj.collider = &collider
+	}
+	return obj
+}
+
+// place adds a copy of a placed object
+func (w *World) place(obj *placedObject) bool {
+	var collider *Collider
+	if obj.collider != nil {
+		c := *obj.collider
+		collider = &c
+	}
+	return w.AddPlacement(obj.placement, collider)
+}
+
+// placementEdit puts a placed object under an ID, or removes what is there
+type placementEdit struct {
+	id     int
+	after  *placedObject // nil to remove
+	before *placedObject // What the ID held when applied, nil if nothing
+}
+
+func (e *placementEdit) apply(w *World) bool {
+	e.before = w.placed(e.id)
+	if e.after == nil {
+		return w.RemovePlacement(e.id)
+	}
+	return w.place(e.after)
+}
+
+func (e *placementEdit) revert(w *World) {
+	w.RemovePlacement(e.id)
+	if e.before != nil {
+		w.place(e.before)
+	}
+}
+
+// PlaceEdit adds a placement with an optional collider, replacing any
+// placement with its ID, like World.AddPlacement
+func PlaceEdit(p Placement, collider *Collider) EditCommand {
+	obj := &placedObject{placement: p}
+	if collider != nil {
+		c := *collider
+		obj.collider = &c
+	}
+	return &placementEdit{id: p.ID, after: obj}
+}
+
+// RemovePlacementEdit removes a placement and its collider, like
+// World.RemovePlacement
+func RemovePlacementEdit(id int) EditCommand {
+	return &placementEdit{id: id}
+}
+
+// roadEdit runs an edit of the road graph, reverted from a copy of the
+// graph taken before it ran
+type roadEdit struct {
+	edit   func(g *RoadGraph) bool
+	before RoadGraphSnapshot
+}
+
+func (e *roadEdit) apply(w *World) bool {
+	e.before = w.Roads.snapshot()
+	return e.edit(w.Roads)
+}
+
+func (e *roadEdit) revert(w *World) {
+	// Cannot fail: the snapshot was taken of a valid graph
+	_ = w.Roads.restore(e.before)
+}
+
+// RoadEdit edits the road graph; edit returns false if it changed nothing.
+// Redo runs edit again on the graph it first ran on, so it assigns the
+// same edge IDs
+func RoadEdit(edit func(g *RoadGraph) bool) EditCommand {
+	return &roadEdit{edit: edit}
+}
+
+// zoneEdit runs an edit of the zone map, reverted from a copy of its cells
+type zoneEdit struct {
+	edit   func(m *ZoneMap) bool
+	before []ZoneType
+}
+
+func (e *zoneEdit) apply(w *World) bool {
+	e.before = append(e.before[:0], w.Zones.cells...)
+	return e.edit(w.Zones)
+}
+
+func (e *zoneEdit) revert(w *World) {
+	copy(w.Zones.cells, e.before)
+}
+
+// ZoneEdit edits the zone map; edit returns false if it changed nothing
+func ZoneEdit(edit func(m *ZoneMap) bool) EditCommand {
+	return &zoneEdit{edit: edit}
+}
+
+// districtEdit runs an edit of the district map's cells, reverted from a copy of them
+type districtEdit struct {
+	edit   func(m *DistrictMap) bool
+	before []int
+}
+
+func (e *districtEdit) apply(w *World) bool {
+	e.before = append(e.before[:0], w.Districts.cells...)
+	return e.edit(w.Districts)
+}
+
+func (e *districtEdit) revert(w *World) {
+	copy(w.Districts.cells, e.before)
+}
+
+// DistrictEdit edits which district each cell is in; edit returns false
+// if it changed nothing
+func DistrictEdit(edit func(m *DistrictMap) bool) EditCommand {
+	return &districtEdit{edit: edit}
+}
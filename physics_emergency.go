@@ -0,0 +1,290 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Emergency Vehicles (sirens, priority and incident response)
+// ============================================================================
+
+// A car with its siren on has priority in traffic. Driven by an AIDriver it
+// takes speed limits SpeedFactor times faster and runs red lights and give
+// way signs, and AI cars it approaches within SirenRange (closing on them
+// from any side) pull over: they steer PULL_OVER_OFFSET towards the curb and
+// crawl at PULL_OVER_SPEED until it has passed (physics_traffic.go). Fire
+// trucks (physics_fire.go) run with sirens, and reported incidents get an
+// ambulance from the nearest hospital or a police car from the nearest
+// police station, which spends SceneHours game hours at the scene. The
+// player's car can run a siren too (SetSiren), for a police chase.
+
+const (
+	EMERGENCY_SPEED_FACTOR = 1.5     // Speed limits with a siren on, relative to the posted ones
+	SIREN_RANGE            = 20.0    // units, how far away AI cars hear a siren
+	PULL_OVER_OFFSET       = 1.0     // units towards the curb cars pull over by
+	PULL_OVER_SPEED        = 1.0     // units/s cars crawl at while a siren passes
+	INCIDENT_SCENE_HOURS   = 0.5     // Game hours a crew spends at an incident
+	EMERGENCY_ID_BASE      = 1 << 23 // Car IDs from here up are ambulances and police cars
+	EMERGENCY_SPEED        = 1.4     // Responder top speed relative to the default vehicle
+	POLICE_STATION_USE     = "police_station"
+)
+
+// EmergencyKind is what an incident needs
+type EmergencyKind uint8
+
+const (
+	EmergencyMedical EmergencyKind = iota
+	EmergencyPolice
+	emergencyKindCount
+)
+
+// emergencyKindNames are the JavaScript-facing names, indexed by EmergencyKind
+var emergencyKindNames = [emergencyKindCount]string{"medical", "police"}
+
+// String returns the JavaScript-facing kind name
+func (k EmergencyKind) String() string {
+	if k >= emergencyKindCount {
+		return emergencyKindNames[EmergencyMedical]
+	}
+	return emergencyKindNames[k]
+}
+
+// emergencyKindFromString parses a JavaScript-facing kind name
+func emergencyKindFromString(name string) (EmergencyKind, bool) {
+	for k, n := range emergencyKindNames {
+		if n == name {
+			return EmergencyKind(k), true
+		}
+	}
+	return EmergencyMedical, false
+}
+
+// Incident is an emergency waiting for or being attended by a responder
+type Incident struct {
+	ID        int
+	Kind      EmergencyKind
+	X, Z      float64
+	VehicleID int     // Car ID of the responder sent, 0 when none is on the way
+	OnScene   bool    // The responder has arrived
+	Attended  float64 // Game hours the responder has been on scene
+}
+
+// responderState returns the JavaScript-facing response state
+func (i *Incident) responderState() string {
+	switch {
+	case i.OnScene:
+		return "on_scene"
+	case i.VehicleID != 0:
+		return "en_route"
+	}
+	return "none"
+}
+
+// EmergencyService tunes sirens and responds to incidents
+type EmergencyService struct {
+	SpeedFactor float64 // Speed limits with a siren on, relative to the posted ones
+	SirenRange  float64
+	SceneHours  float64
+	StationUses [emergencyKindCount][]string // Model uses that send responders of each kind
+	Vehicle     VehicleConfig
+
+	Reported int
+	Resolved int
+
+	incidents   []*Incident // In report order
+	nextID      int
+	nextVehicle int
+}
+
+// NewEmergencyService creates an emergency service with no incidents
+func NewEmergencyService() *EmergencyService {
+	responder := DefaultVehicleConfig()
+	responder.MaxSpeed *= EMERGENCY_SPEED
+	return &EmergencyService{
+		SpeedFactor: EMERGENCY_SPEED_FACTOR,
+		SirenRange:  SIREN_RANGE,
+		SceneHours:  INCIDENT_SCENE_HOURS,
+		StationUses: [emergencyKindCount][]string{
+			EmergencyMedical: {"healthcare"},
+			EmergencyPolice:  {POLICE_STATION_USE},
+		},
+		Vehicle:     responder,
+		nextID:      1,
+		nextVehicle: EMERGENCY_ID_BASE,
+	}
+}
+
+// Incidents returns the incidents being responded to, in report order
+func (s *EmergencyService) Incidents() []*Incident {
+	return s.incidents
+}
+
+// SetSiren turns a car's siren on or off, returning false if it does not exist
+func (w *World) SetSiren(id int, on bool) bool {
+	car := w.Car(id)
+	if car == nil {
+		return false
+	}
+	car.Siren = on
+	return true
+}
+
+// SirensNear returns the moving cars with their siren on within radius of
+// a point, in step order
+func (w *World) SirensNear(x, z, radius float64) []*WorldCar {
+	var near []*WorldCar
+	for _, car := range w.cars {
+		s := &car.State
+		if !car.Siren || simHypot(s.VelocityX, s.VelocityZ) < STOP_SPEED {
+			continue
+		}
+		if simHypot(s.X-x, s.Z-z) <= radius {
+			near = append(near, car)
+		}
+	}
+	return near
+}
+
+// yieldsToSiren reports whether an AI car should pull over: a siren within
+// range is closing on it
+func (w *World) yieldsToSiren(car *WorldCar) bool {
+	if car.Siren {
+		return false
+	}
+	s := &car.State
+	for _, other := range w.SirensNear(s.X, s.Z, w.Emergency.SirenRange) {
+		o := &other.State
+		relX, relZ := o.X-s.X, o.Z-s.Z
+		if relX*(o.VelocityX-s.VelocityX)+relZ*(o.VelocityZ-s.VelocityZ) < 0 {
+			return true
+		}
+	}
+	return false
+}
+
+// ReportIncident reports an emergency at a point, returning its ID; a
+// responder is sent from the nearest station of its kind
+func (w *World) ReportIncident(kind EmergencyKind, x, z float64) int {
+	s := w.Emergency
+	incident := &Incident{ID: s.nextID, Kind: kind, X: x, Z: z}
+	s.nextID++
+	s.Reported++
+	s.incidents = append(s.incidents, incident)
+	w.dispatchResponder(incident)
+	return incident.ID
+}
+
+// CancelIncident calls off an incident and its responder, returning false
+// if there is no such incident
+func (w *World) CancelIncident(id int) bool {
+	s := w.Emergency
+	for i, incident := range s.incidents {
+		if incident.ID == id {
+			w.recallResponder(incident)
+			s.incidents = append(s.incidents[:i], s.incidents[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// ClearIncidents calls off every incident and resets the counters
+func (w *World) ClearIncidents() {
+	s := w.Emergency
+	for _, incident := range s.incidents {
+		w.recallResponder(incident)
+	}
+	s.incidents = nil
+	s.Reported, s.Resolved = 0, 0
+}
+
+// recallResponder removes the responder sent to an incident, if any
+func (w *World) recallResponder(incident *Incident) {
+	if incident.VehicleID != 0 {
+		w.RemoveCar(incident.VehicleID)
+		incident.VehicleID = 0
+	}
+}
+
+// dispatchResponder sends a responder to an incident; with no station or no
+// route the incident waits for the next step
+func (w *World) dispatchResponder(incident *Incident) {
+	s := w.Emergency
+	switch sent, here := w.dispatchVehicle(s.nextVehicle, s.StationUses[incident.Kind], incident.X, incident.Z, s.Vehicle); {
+	case here:
+		incident.OnScene = true // The station is around the corner
+	case sent:
+		incident.VehicleID = s.nextVehicle
+		s.nextVehicle++
+	}
+}
+
+// nearestStation returns the placement of one of the uses nearest a point, or nil
+func (w *World) nearestStation(uses []string, x, z float64) *Placement {
+	var station *Placement
+	nearest := math.Inf(1)
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if !hasUse(uses, w.placementUse(p)) || w.underConstruction(p) {
+			continue
+		}
+		px, pz := p.Center()
+		if d := simHypot(px-x, pz-z); d < nearest {
+			station, nearest = p, d
+		}
+	}
+	return station
+}
+
+// dispatchVehicle sends an AI car with its siren on from the station of one
+// of the uses nearest a point to the road node nearest it. here is true
+// (and nothing is sent) when the station shares the scene's node
+func (w *World) dispatchVehicle(id int, uses []string, x, z float64, cfg VehicleConfig) (sent, here bool) {
+	station := w.nearestStation(uses, x, z)
+	if station == nil {
+		return false, false
+	}
+	from := w.Roads.NearestNode(station.Center())
+	to := w.Roads.NearestNode(x, z)
+	switch {
+	case from == nil || to == nil:
+		return false, false
+	case from.ID == to.ID:
+		return false, true
+	}
+	if err := w.SpawnAICar(id, from.ID, to.ID, cfg); err != nil {
+		return false, false
+	}
+	w.Car(id).Siren = true
+	return true, false
+}
+
+// stepEmergencies moves responders to their incidents and resolves the
+// incidents they have attended long enough
+func (w *World) stepEmergencies(dt float64) {
+	s := w.Emergency
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+
+	open := s.incidents[:0]
+	for _, incident := range s.incidents {
+		if incident.VehicleID != 0 && !incident.OnScene {
+			if car := w.Car(incident.VehicleID); car == nil || car.AI == nil {
+				incident.VehicleID = 0 // Removed or taken over from JavaScript
+			} else if car.AI.Arrived {
+				incident.OnScene = true
+				car.Siren = false
+			}
+		}
+		if incident.VehicleID == 0 && !incident.OnScene {
+			w.dispatchResponder(incident)
+		}
+		if incident.OnScene {
+			incident.Attended += hours
+		}
+		if incident.Attended >= positiveOr(s.SceneHours, INCIDENT_SCENE_HOURS) {
+			w.recallResponder(incident)
+			s.Resolved++
+			continue
+		}
+		open = append(open, incident)
+	}
+	s.incidents = open
+}
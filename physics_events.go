@@ -0,0 +1,65 @@
+package main
+
+// ============================================================================
+// Collision Event Queue
+// ============================================================================
+
+// Collisions found during physics steps are queued here so the frontend can
+// drain them once per frame for crash sounds and damage decals.
+
+const (
+	MAX_COLLISION_EVENTS = 256 // Events kept between drains; extras are dropped
+	MIN_IMPACT_SPEED     = 0.5 // units/s, slower contacts (resting, sliding) are not reported
+)
+
+// CollisionEventKind identifies what the car hit
+type CollisionEventKind uint8
+
+const (
+	CollisionStatic  CollisionEventKind = iota // Car against a static collider
+	CollisionVehicle                           // Car against another car
+	CollisionWater                             // Car stopped at the shore of open water (physics_water.go)
+	CollisionRoadFeature                       // Car drove onto a speed bump or pothole (physics_road_features.go)
+	CollisionTrain                             // Car against the freight train (physics_trains.go)
+)
+
+// collisionEventKindNames are the JavaScript-facing names, indexed by CollisionEventKind
+var collisionEventKindNames = [...]string{"static", "vehicle", "water", "road_feature", "train"}
+
+// String returns the JavaScript-facing kind name
+func (k CollisionEventKind) String() string {
+	if int(k) >= len(collisionEventKindNames) {
+		return collisionEventKindNames[CollisionStatic]
+	}
+	return collisionEventKindNames[k]
+}
+
+// CollisionEvent is one impact reported to JavaScript
+type CollisionEvent struct {
+	Kind             CollisionEventKind
+	A, B             int     // Entity IDs: the car, and the collider, other car, road feature or train number (-1 for water)
+	ImpactSpeed      float64 // units/s, closing speed along the normal
+	PointX, PointZ   float64 // Contact point
+	NormalX, NormalZ float64 // Unit vector pushing A away from B
+}
+
+// pushCollisionEvent queues an impact if it is hard enough to report
+func (w *World) pushCollisionEvent(e CollisionEvent) {
+	if e.ImpactSpeed < MIN_IMPACT_SPEED {
+		return
+	}
+	w.countCollision(e)
+	if len(w.events) >= MAX_COLLISION_EVENTS {
+		w.droppedEvents++
+		return
+	}
+	w.events = append(w.events, e)
+}
+
+// takeCollisionEvents returns the queued events and the number dropped, then empties the queue
+func (w *World) takeCollisionEvents() ([]CollisionEvent, int) {
+	events, dropped := w.events, w.droppedEvents
+	w.events = make([]CollisionEvent, 0, MAX_COLLISION_EVENTS)
+	w.droppedEvents = 0
+	return events, dropped
+}
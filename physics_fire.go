@@ -0,0 +1,246 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Fires and Emergency Response
+// ============================================================================
+
+// With disasters enabled, buildings catch fire at random (or on demand with
+// Ignite). A fire spreads to buildings within FIRE_SPREAD_DISTANCE of its
+// footprint and burns its building down after BurnHours game hours unless
+// put out. Every fire gets a fire truck from the nearest emergency station
+// (fire stations, and hospitals until the town has one), an AI car driven
+// along the road graph with its siren on (physics_emergency.go) that spends
+// FIRE_EXTINGUISH_HOURS at the scene.
+// Burned-down buildings leave the occupancy grid and are queued for JS to
+// remove from the scene.
+
+const (
+	FIRE_IGNITION_PER_DAY = 0.01    // Chance each building catches fire per game day
+	FIRE_SPREAD_PER_HOUR  = 0.25    // Rate per game hour a fire spreads to each neighbor
+	FIRE_SPREAD_DISTANCE  = 1.0     // units, gap across which fire reaches another building
+	FIRE_BURN_HOURS       = 6.0     // Game hours an unattended fire takes to destroy its building
+	FIRE_EXTINGUISH_HOURS = 0.5     // Game hours a crew needs on scene to put a fire out
+	FIRE_TRUCK_ID_BASE    = 1 << 21 // Car IDs from here up are fire trucks
+	FIRE_TRUCK_SPEED      = 1.4     // Truck top speed relative to the default vehicle
+	FIRE_SEED             = 3       // Seed for ignition and spread (fires repeat run to run)
+	FIRE_STATION_USE      = "fire_station"
+)
+
+// Fire is one burning building
+type Fire struct {
+	PlacementID int
+	Burned      float64 // Game hours burning
+	Fought      float64 // Game hours a crew has been on scene
+	TruckID     int     // Car ID of the truck sent, 0 when none is on the way
+	OnScene     bool    // A crew has arrived
+}
+
+// truckState returns the JavaScript-facing response state
+func (f *Fire) truckState() string {
+	switch {
+	case f.OnScene:
+		return "on_scene"
+	case f.TruckID != 0:
+		return "en_route"
+	}
+	return "none"
+}
+
+// FireService tracks fires and the trucks sent to them
+type FireService struct {
+	Enabled        bool // Random ignition; spread and response always run
+	IgnitionPerDay float64
+	SpreadPerHour  float64
+	BurnHours      float64
+	StationUses    []string // Model uses that send fire trucks
+	Vehicle        VehicleConfig
+
+	Ignited      int
+	Extinguished int
+	Destroyed    int
+
+	fires     []*Fire // In ignition order
+	destroyed []int   // Placement IDs burned down since the last drain
+	nextID    int
+	rng       *SimRand
+}
+
+// NewFireService creates a fire service with random ignition off
+func NewFireService() *FireService {
+	truck := DefaultVehicleConfig()
+	truck.MaxSpeed *= FIRE_TRUCK_SPEED
+	return &FireService{
+		IgnitionPerDay: FIRE_IGNITION_PER_DAY,
+		SpreadPerHour:  FIRE_SPREAD_PER_HOUR,
+		BurnHours:      FIRE_BURN_HOURS,
+		StationUses:    []string{FIRE_STATION_USE, "healthcare"},
+		Vehicle:        truck,
+		nextID:         FIRE_TRUCK_ID_BASE,
+		rng:            newSimRand(FIRE_SEED),
+	}
+}
+
+// Fires returns the burning buildings, in ignition order
+func (s *FireService) Fires() []*Fire {
+	return s.fires
+}
+
+// takeDestroyed returns the buildings burned down since the last call, then forgets them
+func (s *FireService) takeDestroyed() []int {
+	destroyed := s.destroyed
+	s.destroyed = nil
+	return destroyed
+}
+
+// fireAt returns the fire in a placement, or nil
+func (s *FireService) fireAt(id int) *Fire {
+	for _, f := range s.fires {
+		if f.PlacementID == id {
+			return f
+		}
+	}
+	return nil
+}
+
+// flammable reports whether a placement can burn
+func flammable(p *Placement) bool {
+	return p != nil && p.Category == BUILDING_CATEGORY && p.ID < LOT_PLAN_ID_BASE
+}
+
+// Ignite sets a building on fire, returning false if it cannot burn or is already burning
+func (w *World) Ignite(id int) bool {
+	s := w.Fires
+	if !flammable(w.Occupancy.Placement(id)) || s.fireAt(id) != nil {
+		return false
+	}
+	s.fires = append(s.fires, &Fire{PlacementID: id})
+	s.Ignited++
+	return true
+}
+
+// ClearFires puts out every fire, recalls the trucks and resets the counters
+func (w *World) ClearFires() {
+	s := w.Fires
+	for _, f := range s.fires {
+		w.recallTruck(f)
+	}
+	s.fires, s.destroyed = nil, nil
+	s.Ignited, s.Extinguished, s.Destroyed = 0, 0, 0
+}
+
+// recallTruck removes the truck sent to a fire, if any
+func (w *World) recallTruck(f *Fire) {
+	if f.TruckID != 0 {
+		w.RemoveCar(f.TruckID)
+		f.TruckID = 0
+	}
+}
+
+// dispatchTruck sends a truck from the nearest station to a fire; with no
+// station or no route the fire waits for the next step
+func (w *World) dispatchTruck(f *Fire, building *Placement) {
+	s := w.Fires
+	bx, bz := building.Center()
+	switch sent, here := w.dispatchVehicle(s.nextID, s.StationUses, bx, bz, s.Vehicle); {
+	case here:
+		f.OnScene = true // The station is around the corner
+	case sent:
+		f.TruckID = s.nextID
+		s.nextID++
+	}
+}
+
+// hasUse reports whether a use is in a list of uses
+func hasUse(uses []string, use string) bool {
+	for _, u := range uses {
+		if u == use {
+			return true
+		}
+	}
+	return false
+}
+
+// spreadChance returns the chance an event at rate per hour happens within hours
+func spreadChance(rate, hours float64) float64 {
+	return 1 - simExp(-math.Max(rate, 0)*hours)
+}
+
+// stepFires starts random fires, spreads and burns the current ones, and
+// moves the fire crews
+func (w *World) stepFires(dt float64) {
+	s := w.Fires
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+
+	burning := s.fires[:0]
+	for _, f := range s.fires {
+		building := w.Occupancy.Placement(f.PlacementID)
+		if building == nil {
+			w.recallTruck(f) // Demolished while burning
+			continue
+		}
+
+		f.Burned += hours
+		if f.TruckID != 0 && !f.OnScene {
+			if truck := w.Car(f.TruckID); truck == nil || truck.AI == nil {
+				f.TruckID = 0 // Removed or taken over from JavaScript
+			} else if truck.AI.Arrived {
+				f.OnScene = true
+				truck.Siren = false
+			}
+		}
+		if f.TruckID == 0 && !f.OnScene {
+			w.dispatchTruck(f, building)
+		}
+		if f.OnScene {
+			f.Fought += hours
+		}
+
+		switch {
+		case f.Fought >= FIRE_EXTINGUISH_HOURS:
+			w.recallTruck(f)
+			s.Extinguished++
+		case f.Burned >= positiveOr(s.BurnHours, FIRE_BURN_HOURS):
+			w.recallTruck(f)
+			x, z := building.Center()
+			w.pushSimEvent(SimEvent{Kind: SimBuildingBurned, ID: f.PlacementID, Model: building.Model, X: x, Z: z})
+			w.Occupancy.Remove(f.PlacementID)
+			s.destroyed = append(s.destroyed, f.PlacementID)
+			s.Destroyed++
+		default:
+			burning = append(burning, f)
+		}
+	}
+	s.fires = burning
+
+	// Spread from the fires burning at the start of the step
+	spread := spreadChance(s.SpreadPerHour, hours)
+	for _, f := range burning {
+		fp := w.Occupancy.Placement(f.PlacementID).Footprint
+		reach := BoundingBox{
+			MinX: fp.MinX - FIRE_SPREAD_DISTANCE,
+			MinY: fp.MinY - FIRE_SPREAD_DISTANCE,
+			MaxX: fp.MaxX + FIRE_SPREAD_DISTANCE,
+			MaxY: fp.MaxY + FIRE_SPREAD_DISTANCE,
+		}
+		for _, p := range w.Occupancy.Query(reach) {
+			if p.ID != f.PlacementID && flammable(p) && s.fireAt(p.ID) == nil && s.rng.Float64() < spread {
+				w.Ignite(p.ID)
+			}
+		}
+	}
+
+	if !s.Enabled {
+		return
+	}
+	var buildings []int
+	for _, id := range w.Occupancy.IDs() {
+		if flammable(w.Occupancy.Placement(id)) {
+			buildings = append(buildings, id)
+		}
+	}
+	if len(buildings) > 0 && s.rng.Float64() < spreadChance(s.IgnitionPerDay*float64(len(buildings)), hours/HOURS_PER_DAY) {
+		w.Ignite(buildings[s.rng.Intn(len(buildings))])
+	}
+}
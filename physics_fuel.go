@@ -0,0 +1,86 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Fuel (tank, consumption and gas stations)
+// ============================================================================
+
+// A vehicle with a FuelCapacity burns fuel as it drives: a little at idle
+// and, with the engine load from the drivetrain (physics_drivetrain.go),
+// more the harder and faster it is pushed, up to FuelUse per second flat
+// out at top speed. The car keeps how much it has burned since the tank
+// was filled, so a new car (or one from an older save) starts full. With
+// the tank empty the engine gives no drive and the car coasts to a stop.
+// Cars stopped at a gas station (on it or within SERVICE_REACH,
+// physics_damage.go) refill at FUEL_REFILL_RATE, and Refuel fills a tank
+// directly. Vehicles without a FuelCapacity, the default and what AI
+// traffic uses, never run dry.
+
+const (
+	FUEL_USE         = 0.2  // Fuel units/s at full load and top speed (the default vehicle)
+	FUEL_IDLE_LOAD   = 0.05 // Share of FuelUse burned with the engine idling
+	FUEL_REFILL_RATE = 8.0  // Fuel units/s pumped at a gas station
+	GAS_STATION_USE  = "gas_station"
+)
+
+// hasTank reports whether the vehicle uses fuel
+func (cfg *VehicleConfig) hasTank() bool {
+	return cfg.FuelCapacity > 0
+}
+
+// FuelLevel returns the fraction of the tank left, 0..1 (1 without a tank)
+func (car *CarState) FuelLevel(cfg *VehicleConfig) float64 {
+	if !cfg.hasTank() {
+		return 1
+	}
+	return clamp(1-car.FuelUsed/cfg.FuelCapacity, 0, 1)
+}
+
+// OutOfFuel reports whether the car's tank is empty
+func (car *CarState) OutOfFuel(cfg *VehicleConfig) bool {
+	return cfg.hasTank() && car.FuelUsed >= cfg.FuelCapacity
+}
+
+// fuelAdjust cuts the drive of a car that is out of fuel
+func (car *CarState) fuelAdjust(eff, cfg *VehicleConfig) {
+	if car.OutOfFuel(cfg) {
+		eff.Acceleration, eff.ReverseAcceleration = 0, 0
+	}
+}
+
+// burnFuel consumes the fuel of one substep, after the drivetrain has set
+// the engine load
+func (car *CarState) burnFuel(cfg *VehicleConfig, dt float64) {
+	if !cfg.hasTank() || car.OutOfFuel(cfg) {
+		return
+	}
+	speed := math.Min(simHypot(car.VelocityX, car.VelocityZ)/cfg.MaxSpeed, 1)
+	rate := cfg.FuelUse * (FUEL_IDLE_LOAD + car.ThrottleLoad*(1+speed)/2)
+	car.FuelUsed = math.Min(car.FuelUsed+rate*dt, cfg.FuelCapacity)
+}
+
+// Refuel adds fuel units to a car's tank, or fills it when amount is not
+// positive; returns false if the car does not exist
+func (w *World) Refuel(id int, amount float64) bool {
+	car := w.Car(id)
+	if car == nil {
+		return false
+	}
+	if !(amount > 0) {
+		amount = math.Inf(1)
+	}
+	car.State.FuelUsed = math.Max(car.State.FuelUsed-amount, 0)
+	return true
+}
+
+// stepRefueling fills the tanks of cars stopped at a gas station
+func (w *World) stepRefueling(dt float64) {
+	for _, car := range w.cars {
+		s := &car.State
+		if s.FuelUsed <= 0 || !w.servedBy(s, GAS_STATION_USE) {
+			continue
+		}
+		s.FuelUsed = math.Max(s.FuelUsed-FUEL_REFILL_RATE*dt, 0)
+	}
+}
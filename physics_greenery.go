@@ -0,0 +1,148 @@
+package main
+
+import "sort"
+
+// ============================================================================
+// Greenery Growth (trees and parks maturing over game time)
+// ============================================================================
+
+// Every placement that provides green space (parks and trees, see
+// DefaultServices) is planted as a sapling when it first appears in the
+// occupancy grid and grows on the world clock (physics_clock.go) until it is
+// mature. A young planting provides only part of its green space coverage,
+// so the livability and land value it brings rise as it grows. Growth is
+// kept by placement ID across placement syncs and dropped when the
+// placement is removed.
+
+const (
+	GREENERY_MATURE_DAYS = 10.0 // Game days from planting to full size
+	SAPLING_GREEN_SHARE  = 0.3  // Share of full green space coverage a new planting provides
+	YOUNG_GROWTH         = 0.35 // Growth from which a planting counts as young rather than a sapling
+)
+
+// GrowthStage is how far a planting has grown, for rendering
+type GrowthStage uint8
+
+const (
+	GrowthSapling GrowthStage = iota
+	GrowthYoung
+	GrowthMature
+	growthStageCount
+)
+
+// growthStageNames are the JavaScript-facing names, indexed by GrowthStage
+var growthStageNames = [growthStageCount]string{"sapling", "young", "mature"}
+
+// String returns the JavaScript-facing stage name
+func (s GrowthStage) String() string {
+	if s >= growthStageCount {
+		return growthStageNames[GrowthSapling]
+	}
+	return growthStageNames[s]
+}
+
+// Greenery tracks the growth of every planting
+type Greenery struct {
+	MatureDays float64         // Game days from planting to full size
+	Growth     map[int]float64 // Placement ID -> growth 0 (just planted) to 1 (mature)
+	revision   int             // Occupancy revision plantings were last matched against
+}
+
+// NewGreenery creates an empty greenery layer
+func NewGreenery() *Greenery {
+	return &Greenery{MatureDays: GREENERY_MATURE_DAYS, Growth: make(map[int]float64), revision: -1}
+}
+
+// Planting is one tree or park and its growth
+type Planting struct {
+	ID     int
+	Model  string
+	Growth float64 // 0-1
+	Stage  GrowthStage
+}
+
+// stageOf returns the growth stage of a growth level
+func stageOf(growth float64) GrowthStage {
+	switch {
+	case growth >= 1:
+		return GrowthMature
+	case growth >= YOUNG_GROWTH:
+		return GrowthYoung
+	}
+	return GrowthSapling
+}
+
+// isGreenery reports whether a placement provides green space
+func (w *World) isGreenery(p *Placement) bool {
+	s := w.Service(GREEN_SPACE_SERVICE)
+	return s != nil && s.provides(w.placementUse(p), p.Category)
+}
+
+// refreshPlantings plants new green space placements and forgets removed
+// ones, when the occupancy grid has changed
+func (w *World) refreshPlantings() {
+	g := w.Greenery
+	if g.revision == w.Occupancy.Revision() {
+		return
+	}
+	present := make(map[int]bool, len(g.Growth))
+	for _, id := range w.Occupancy.IDs() {
+		if p := w.Occupancy.Placement(id); p.ID < LOT_PLAN_ID_BASE && w.isGreenery(p) {
+			present[id] = true
+			if _, known := g.Growth[id]; !known {
+				g.Growth[id] = 0
+			}
+		}
+	}
+	for id := range g.Growth {
+		if !present[id] {
+			delete(g.Growth, id)
+		}
+	}
+	g.revision = w.Occupancy.Revision()
+}
+
+// greenShare returns the share of full green space coverage a placement
+// provides at its current growth
+func (w *World) greenShare(p *Placement) float64 {
+	growth, planted := w.Greenery.Growth[p.ID]
+	if !planted {
+		return 1 // Held while planning lots (physics_lots.go)
+	}
+	return SAPLING_GREEN_SHARE + (1-SAPLING_GREEN_SHARE)*growth
+}
+
+// Plantings returns every planting by placement ID
+func (w *World) Plantings() []Planting {
+	w.refreshPlantings()
+	plantings := make([]Planting, 0, len(w.Greenery.Growth))
+	for id, growth := range w.Greenery.Growth {
+		plantings = append(plantings, Planting{ID: id, Model: w.Occupancy.Placement(id).Model, Growth: growth, Stage: stageOf(growth)})
+	}
+	sort.Slice(plantings, func(i, j int) bool { return plantings[i].ID < plantings[j].ID })
+	return plantings
+}
+
+// SetGrowth sets the growth of one planting, returning false if the
+// placement is not a planting
+func (w *World) SetGrowth(id int, growth float64) bool {
+	w.refreshPlantings()
+	if _, planted := w.Greenery.Growth[id]; !planted {
+		return false
+	}
+	w.Greenery.Growth[id] = clamp(growth, 0, 1)
+	return true
+}
+
+// stepGreenery grows every planting on the world clock
+func (w *World) stepGreenery(dt float64) {
+	w.refreshPlantings()
+	g := w.Greenery
+	days := dt / w.Clock.DayLength()
+	rate := days / positiveOr(g.MatureDays, GREENERY_MATURE_DAYS)
+	for id, growth := range g.Growth {
+		if growth < 1 {
+			g.Growth[id] = clamp(growth+rate, 0, 1)
+		}
+	}
+}
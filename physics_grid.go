@@ -0,0 +1,65 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Town Grid (shared cell layout for town simulation maps)
+// ============================================================================
+
+// GridSpec is the cell layout shared by the occupancy grid and the maps
+// derived from it (zones, coverage, scores), so their cells line up
+// Cells are indexed row-major: z*Width+x
+type GridSpec struct {
+	Width, Depth     int
+	CellSize         float64
+	OriginX, OriginZ float64 // World position of the corner of cell (0, 0)
+}
+
+// NewGridSpec creates a grid layout centered on the world origin
+func NewGridSpec(width, depth int, cellSize float64) GridSpec {
+	return GridSpec{
+		Width:    width,
+		Depth:    depth,
+		CellSize: cellSize,
+		OriginX:  -float64(width) * cellSize / 2,
+		OriginZ:  -float64(depth) * cellSize / 2,
+	}
+}
+
+// Len returns the number of cells
+func (g GridSpec) Len() int {
+	return g.Width * g.Depth
+}
+
+// CellIndex returns the cell index for a world position, or -1 outside the grid
+func (g GridSpec) CellIndex(x, z float64) int {
+	cx := int(math.Floor((x - g.OriginX) / g.CellSize))
+	cz := int(math.Floor((z - g.OriginZ) / g.CellSize))
+	if cx < 0 || cx >= g.Width || cz < 0 || cz >= g.Depth {
+		return -1
+	}
+	return cz*g.Width + cx
+}
+
+// CellCenter returns the world position of a cell's center
+func (g GridSpec) CellCenter(i int) (x, z float64) {
+	cx, cz := i%g.Width, i/g.Width
+	return g.OriginX + (float64(cx)+0.5)*g.CellSize, g.OriginZ + (float64(cz)+0.5)*g.CellSize
+}
+
+// CellRange returns the inclusive cell range covered by the interior of a
+// bounding box (X/Z in MinX/MinY/MaxX/MaxY); ok is false if it misses the grid
+// Cells that only touch the box edge are excluded, so neighbors sharing an
+// edge do not claim each other's cells
+func (g GridSpec) CellRange(bbox BoundingBox) (minX, minZ, maxX, maxZ int, ok bool) {
+	minX = max(int(math.Floor((bbox.MinX-g.OriginX)/g.CellSize)), 0)
+	minZ = max(int(math.Floor((bbox.MinY-g.OriginZ)/g.CellSize)), 0)
+	maxX = min(int(math.Ceil((bbox.MaxX-g.OriginX)/g.CellSize))-1, g.Width-1)
+	maxZ = min(int(math.Ceil((bbox.MaxY-g.OriginZ)/g.CellSize))-1, g.Depth-1)
+	return minX, minZ, maxX, maxZ, minX <= maxX && minZ <= maxZ
+}
+
+// valid reports whether the layout has cells and a usable cell size
+func (g GridSpec) valid() bool {
+	return g.Width > 0 && g.Depth > 0 && g.CellSize > 0 && !math.IsInf(g.CellSize, 1)
+}
@@ -0,0 +1,193 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Citizen Happiness (per citizen, neighborhood and town)
+// ============================================================================
+
+// Each scheduled citizen (physics_citizens.go) is scored 0-1 on four
+// factors at their home: commute, 1 for none falling to 0 at a last trip to
+// work of HAPPINESS_LONG_COMMUTE seconds or more; services, the weighted
+// mean coverage of every service but employment (the complement of
+// CoverageGrids' underserved map, physics_livability.go); noise, 1 minus the
+// noise level (physics_noise.go); and housing, the land value
+// (physics_land_value.go). Happiness is their weighted mean, 0-100.
+// Neighborhoods are square blocks of NEIGHBORHOOD_CELLS occupancy cells a
+// side; a neighborhood's and the town's happiness and factors are the means
+// over the citizens living there, and the worst factor names the category
+// failing them most. Scores are computed when asked for, from the maps as
+// they are then.
+
+const (
+	HAPPINESS_MAX_SCORE      = 100.0
+	HAPPINESS_LONG_COMMUTE   = 60.0 // s, a trip to work this long scores 0
+	HAPPINESS_COMMUTE_WEIGHT = 1.0
+	HAPPINESS_SERVICE_WEIGHT = 1.2
+	HAPPINESS_NOISE_WEIGHT   = 0.8
+	HAPPINESS_HOUSING_WEIGHT = 1.0
+	NEIGHBORHOOD_CELLS       = 8 // Occupancy cells along each side of a neighborhood
+)
+
+// HappinessFactor is one part of a citizen's happiness
+type HappinessFactor uint8
+
+const (
+	HappinessCommute HappinessFactor = iota
+	HappinessServices
+	HappinessNoise
+	HappinessHousing
+	happinessFactorCount
+)
+
+// happinessFactorNames are the JavaScript-facing names, indexed by HappinessFactor
+var happinessFactorNames = [happinessFactorCount]string{"commute", "services", "noise", "housing"}
+
+// happinessWeights are the factors' shares of happiness, indexed by HappinessFactor
+var happinessWeights = [happinessFactorCount]float64{
+	HappinessCommute:  HAPPINESS_COMMUTE_WEIGHT,
+	HappinessServices: HAPPINESS_SERVICE_WEIGHT,
+	HappinessNoise:    HAPPINESS_NOISE_WEIGHT,
+	HappinessHousing:  HAPPINESS_HOUSING_WEIGHT,
+}
+
+// String returns the JavaScript-facing factor name
+func (f HappinessFactor) String() string {
+	if f >= happinessFactorCount {
+		return happinessFactorNames[HappinessCommute]
+	}
+	return happinessFactorNames[f]
+}
+
+// HappinessBreakdown is a happiness score with the factors behind it
+type HappinessBreakdown struct {
+	Happiness float64                       // 0-100
+	Factors   [happinessFactorCount]float64 // 0-1, indexed by HappinessFactor
+}
+
+// Worst returns the lowest scoring factor (the first of equals)
+func (b HappinessBreakdown) Worst() HappinessFactor {
+	worst := HappinessFactor(0)
+	for f := HappinessFactor(1); f < happinessFactorCount; f++ {
+		if b.Factors[f] < b.Factors[worst] {
+			worst = f
+		}
+	}
+	return worst
+}
+
+// score sets Happiness from the factors
+func (b *HappinessBreakdown) score() {
+	sum, total := 0.0, 0.0
+	for f, v := range b.Factors {
+		sum += v * happinessWeights[f]
+		total += happinessWeights[f]
+	}
+	b.Happiness = HAPPINESS_MAX_SCORE * sum / total
+}
+
+// CitizenHappiness is one citizen's happiness at their home
+type CitizenHappiness struct {
+	HappinessBreakdown
+	CitizenID    int
+	Home         int // Home placement ID
+	Neighborhood int // Neighborhood index, -1 if the home is off the grid
+}
+
+// NeighborhoodHappiness is the happiness of the citizens living in one neighborhood
+type NeighborhoodHappiness struct {
+	HappinessBreakdown
+	Index    int         // Row-major over neighborhoods, NeighborhoodColumns wide
+	Bounds   BoundingBox // World X/Z extents (MinY/MaxY hold world Z)
+	Citizens int
+}
+
+// HappinessReport is the town's happiness broken down by citizen and neighborhood
+type HappinessReport struct {
+	Town                HappinessBreakdown // Means over every citizen
+	Citizens            []CitizenHappiness // In citizen order
+	Neighborhoods       []NeighborhoodHappiness
+	NeighborhoodColumns int // Neighborhoods along the grid's width
+}
+
+// Happiness scores every citizen and averages them by neighborhood and
+// over the town; neighborhoods without citizens are left out
+func (w *World) Happiness() HappinessReport {
+	g := w.Occupancy.GridSpec
+	columns := (g.Width + NEIGHBORHOOD_CELLS - 1) / NEIGHBORHOOD_CELLS
+	report := HappinessReport{NeighborhoodColumns: columns}
+	citizens := w.Citizens.List()
+	if len(citizens) == 0 {
+		return report
+	}
+
+	_, underserved := w.CoverageGrids()
+	noise := w.NoiseLevels()
+	value := w.LandValue()
+	hoods := make(map[int]*NeighborhoodHappiness)
+	for _, ct := range citizens {
+		ch := CitizenHappiness{CitizenID: ct.ID, Home: ct.Home, Neighborhood: -1}
+		ch.Factors = [happinessFactorCount]float64{1, 0, 1, LAND_VALUE_BASE}
+		if home := w.Occupancy.Placement(ct.Home); home != nil {
+			if cell := g.CellIndex(home.Center()); cell >= 0 {
+				ch.Factors[HappinessServices] = 1 - underserved[cell]
+				ch.Factors[HappinessNoise] = 1 - clamp(noise[cell], 0, 1)
+				ch.Factors[HappinessHousing] = value.Cells[cell]
+				cx, cz := cell%g.Width, cell/g.Width
+				ch.Neighborhood = cz/NEIGHBORHOOD_CELLS*columns + cx/NEIGHBORHOOD_CELLS
+			}
+		}
+		if ct.Job >= 0 {
+			ch.Factors[HappinessCommute] = 1 - clamp(ct.Commute/HAPPINESS_LONG_COMMUTE, 0, 1)
+		}
+		ch.score()
+		report.Citizens = append(report.Citizens, ch)
+
+		for f, v := range ch.Factors {
+			report.Town.Factors[f] += v
+		}
+		if ch.Neighborhood < 0 {
+			continue
+		}
+		n := hoods[ch.Neighborhood]
+		if n == nil {
+			n = &NeighborhoodHappiness{Index: ch.Neighborhood, Bounds: w.neighborhoodBounds(ch.Neighborhood, columns)}
+			hoods[ch.Neighborhood] = n
+		}
+		n.Citizens++
+		for f, v := range ch.Factors {
+			n.Factors[f] += v
+		}
+	}
+
+	for f := range report.Town.Factors {
+		report.Town.Factors[f] /= float64(len(citizens))
+	}
+	report.Town.score()
+	for i := 0; len(report.Neighborhoods) < len(hoods); i++ {
+		n := hoods[i]
+		if n == nil {
+			continue
+		}
+		for f := range n.Factors {
+			n.Factors[f] /= float64(n.Citizens)
+		}
+		n.score()
+		report.Neighborhoods = append(report.Neighborhoods, *n)
+	}
+	return report
+}
+
+// neighborhoodBounds returns the world extents of a neighborhood, clipped to the grid
+func (w *World) neighborhoodBounds(index, columns int) BoundingBox {
+	g := w.Occupancy.GridSpec
+	side := NEIGHBORHOOD_CELLS * g.CellSize
+	minX := g.OriginX + float64(index%columns)*side
+	minZ := g.OriginZ + float64(index/columns)*side
+	return BoundingBox{
+		MinX: minX,
+		MinY: minZ,
+		MaxX: math.Min(minX+side, g.OriginX+float64(g.Width)*g.CellSize),
+		MaxY: math.Min(minZ+side, g.OriginZ+float64(g.Depth)*g.CellSize),
+	}
+}
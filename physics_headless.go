@@ -0,0 +1,204 @@
+//go:build !(js && wasm)
+
+package main
+
+import (
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+)
+
+// ============================================================================
+// Headless Simulation (native builds)
+// ============================================================================
+
+// Only physics_wasm.go depends on syscall/js, so every other physics file
+// also builds as a native program for servers and integration tests:
+//
+//	go build -o town-sim physics_*.go
+//	./town-sim -in town.json -seconds 600 -out town-after.json
+//
+// It loads a JSON snapshot (physics_snapshot.go) or binary save
+// (physics_save.go), or starts from an empty world, runs it for the given
+// simulated time in fixed steps and writes the resulting JSON snapshot.
+// With -events each town event is printed as it happens; -seed reseeds the
+// random generators and -checksum prints the final state hash, to compare
+// against a browser run of the same session. With -replay it instead
+// re-simulates an input recording (physics_replay.go) and fails if the
+// replay does not end in the recorded state, so a physics bug recorded in
+// the browser can be reproduced and debugged natively.
+
+// headlessOptions are the command line flags of the native build
+type headlessOptions struct {
+	In       string  // Snapshot to load, "-" for stdin, "" for an empty world
+	Out      string  // Where to write the final snapshot, "-" for stdout, "" for nowhere
+	Seconds  float64 // Simulated seconds to run
+	Step     float64 // s per step
+	Events   bool    // Print town events to stderr
+	Seed     *int64  // Reseed the random generators; nil keeps the snapshot's seed
+	Checksum bool    // Print the final state hash to stderr
+	Replay   string  // Input recording to re-simulate instead of In
+}
+
+// parseHeadlessOptions reads the command line flags
+func parseHeadlessOptions(args []string) (headlessOptions, error) {
+	var o headlessOptions
+	fs := flag.NewFlagSet("town-sim", flag.ContinueOnError)
+	fs.StringVar(&o.In, "in", "", "world snapshot to load (- for stdin; empty world if unset)")
+	fs.StringVar(&o.Out, "out", "-", "where to write the final snapshot (- for stdout; empty to skip)")
+	fs.Float64Var(&o.Seconds, "seconds", 60, "simulated seconds to run")
+	fs.Float64Var(&o.Step, "dt", FIXED_TIMESTEP, "seconds per step")
+	fs.BoolVar(&o.Events, "events", false, "print town events to stderr")
+	seed := fs.Int64("seed", 0, "reseed the random generators (default: the snapshot's seed)")
+	fs.BoolVar(&o.Checksum, "checksum", false, "print the final state hash to stderr")
+	fs.StringVar(&o.Replay, "replay", "", "input recording to re-simulate (- for stdin; replaces -in, -seconds and -seed)")
+	if err := fs.Parse(args); err != nil {
+		return o, err
+	}
+	fs.Visit(func(f *flag.Flag) {
+		if f.Name == "seed" {
+			o.Seed = seed
+		}
+	})
+	if !(o.Step > 0) || !isFinite(o.Step) || o.Seconds < 0 || !isFinite(o.Seconds) {
+		return o, errors.New("-dt must be positive and -seconds non-negative")
+	}
+	return o, nil
+}
+
+// readSnapshot reads snapshot JSON from a file, or stdin for "-"
+func readSnapshot(path string) ([]byte, error) {
+	if path == "-" {
+		return io.ReadAll(os.Stdin)
+	}
+	return os.ReadFile(path)
+}
+
+// writeSnapshot writes snapshot JSON to a file, or stdout for "-"
+func writeSnapshot(path string, data []byte) error {
+	if path == "-" {
+		_, err := os.Stdout.Write(append(data, '\n'))
+		return err
+	}
+	return os.WriteFile(path, data, 0o644)
+}
+
+// printSimEvent writes one town event as a line of text
+func printSimEvent(out io.Writer, time float64, e SimEvent) {
+	fmt.Fprintf(out, "%.2f %s id=%d to=%d model=%q value=%g x=%.2f z=%.2f",
+		time, e.Kind, e.ID, e.To, e.Model, e.Value, e.X, e.Z)
+	if e.Kind == SimDemolished {
+		fmt.Fprintf(out, " width=%.2f depth=%.2f", e.Width, e.Depth)
+	}
+	fmt.Fprintln(out)
+}
+
+// runReplay re-simulates a recording, reporting whether it reproduced the
+// recorded state, and saves the world it ends in
+func runReplay(o headlessOptions) error {
+	data, err := readSnapshot(o.Replay)
+	if err != nil {
+		return err
+	}
+	rec, err := RecordingFromJSON(data)
+	if err != nil {
+		return err
+	}
+	w, matched, err := Replay(rec)
+	if err != nil {
+		return err
+	}
+	fmt.Fprintf(os.Stderr, "replayed %d frames, %.2f s\n", len(rec.Frames), w.Time-rec.Start.Time)
+	if o.Checksum {
+		sum, err := w.Checksum()
+		if err != nil {
+			return err
+		}
+		fmt.Fprintf(os.Stderr, "checksum %x\n", sum)
+	}
+	if o.Out != "" {
+		out, err := w.ToJSON()
+		if err != nil {
+			return err
+		}
+		if err := writeSnapshot(o.Out, out); err != nil {
+			return err
+		}
+	}
+	if !matched {
+		return fmt.Errorf("replay diverged from the recording (recorded checksum %x)", rec.Checksum)
+	}
+	return nil
+}
+
+// runHeadless loads, steps and saves a world as the options ask
+func runHeadless(o headlessOptions) error {
+	if o.Replay != "" {
+		return runReplay(o)
+	}
+	w := NewWorld()
+	if o.In != "" {
+		data, err := readSnapshot(o.In)
+		if err != nil {
+			return err
+		}
+		load := w.FromJSON
+		if _, err := ReadSaveVersion(data); err == nil {
+			load = w.Load
+		}
+		if err := load(data); err != nil {
+			return err
+		}
+	}
+	if o.Seed != nil {
+		w.SetSeed(*o.Seed)
+	}
+
+	steps := int(o.Seconds/o.Step + 0.5)
+	for i := 0; i < steps; i++ {
+		w.Step(o.Step)
+		events, dropped := w.takeSimEvents()
+		if !o.Events {
+			continue
+		}
+		for _, e := range events {
+			printSimEvent(os.Stderr, w.Time, e)
+		}
+		if dropped > 0 {
+			fmt.Fprintf(os.Stderr, "%.2f dropped %d events\n", w.Time, dropped)
+		}
+	}
+
+	if o.Checksum {
+		sum, err := w.Checksum()
+		if err != nil {
+			return err
+		}
+		fmt.Fprintf(os.Stderr, "checksum %x\n", sum)
+	}
+	if o.Out == "" {
+		return nil
+	}
+	data, err := w.ToJSON()
+	if err != nil {
+		return err
+	}
+	return writeSnapshot(o.Out, data)
+}
+
+func main() {
+	o, err := parseHeadlessOptions(os.Args[1:])
+	if errors.Is(err, flag.ErrHelp) {
+		return
+	}
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "town-sim:", err)
+		os.Exit(2)
+	}
+	if err := runHeadless(o); err != nil {
+		fmt.Fprintln(os.Stderr, "town-sim:", err)
+		os.Exit(1)
+	}
+}
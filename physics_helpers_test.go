@@ -0,0 +1,49 @@
+package main
+
+import "testing"
+
+// TEST_SPEED_LIMIT is the speed limit of every road testRoads lays
+const TEST_SPEED_LIMIT = 10.0
+
+// testWorld returns a new world with models registered by name and
+// placements placed, failing the test if any is rejected
+func testWorld(t *testing.T, models []ModelSpec, placements ...Placement) *World {
+	t.Helper()
+	w := NewWorld()
+	for _, m := range models {
+		w.Models[m.Name] = m
+	}
+	for _, p := range placements {
+		if !w.AddPlacement(p, nil) {
+			t.Fatalf("placing %s as %d failed", p.Model, p.ID)
+		}
+	}
+	return w
+}
+
+// testPlacement returns a placement of a model, in its category, over a
+// footprint from (minX, minZ) to (maxX, maxZ)
+func testPlacement(id int, m ModelSpec, minX, minZ, maxX, maxZ float64) Placement {
+	return Placement{ID: id, Model: m.Name, Category: m.Category, Footprint: BoundingBox{MinX: minX, MinY: minZ, MaxX: maxX, MaxY: maxZ}}
+}
+
+// testRoads lays one-lane roads at TEST_SPEED_LIMIT between nodes
+// {id, x, z} along edges {from, to}, returning the edge IDs in order
+func testRoads(t *testing.T, w *World, nodes [][3]float64, edges [][2]int) []int {
+	t.Helper()
+	for _, n := range nodes {
+		w.Roads.AddNode(int(n[0]), n[1], n[2])
+	}
+	ids := make([]int, len(edges))
+	for i, e := range edges {
+		id, err := w.Roads.AddEdge(e[0], e[1], TEST_SPEED_LIMIT, 1)
+		if err != nil {
+			t.Fatal(err)
+		}
+		ids[i] = id
+	}
+	return ids
+}
+
+// testHouse is a 2x2 building model
+var testHouse = NewModelSpec("house.gltf", BUILDING_CATEGORY, 2, 2)
@@ -0,0 +1,119 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Input Processing (deadzones, response curves and ramping)
+// ============================================================================
+
+// Raw controls go through the world's InputProfile before they reach a car,
+// so driving feel is tuned in one place. Analog stick and pedal readings
+// lose a Deadzone around rest and are bent by a response curve (exponent 1
+// is linear; higher gives finer control near center). Keys are all or
+// nothing, so pedals driven by keys ramp up at KeyPressRate and back down
+// at KeyReleaseRate. Steering, analog or keys, is slew limited: it turns
+// towards lock no faster than SteerRate (KeySteerRate for keys) and returns
+// towards center at CenterRate. Rates are in full travel per second.
+
+const (
+	INPUT_DEADZONE         = 0.08 // Fraction of stick travel ignored at rest
+	INPUT_STEER_CURVE      = 1.5  // Steering response exponent
+	INPUT_PEDAL_CURVE      = 1.0  // Throttle and brake response exponent
+	INPUT_STEER_RATE       = 5.0  // Travel/s towards lock
+	INPUT_CENTER_RATE      = 8.0  // Travel/s back towards center
+	INPUT_KEY_PRESS_RATE   = 4.0  // Pedal travel/s while a key is held
+	INPUT_KEY_RELEASE_RATE = 6.0  // Pedal travel/s after a key is let go
+	INPUT_KEY_STEER_RATE   = 3.0  // Steering travel/s towards lock while a key is held
+)
+
+// InputProfile tunes how raw controls become car input
+type InputProfile struct {
+	Deadzone       float64
+	SteerCurve     float64
+	PedalCurve     float64
+	SteerRate      float64
+	CenterRate     float64
+	KeyPressRate   float64
+	KeyReleaseRate float64
+	KeySteerRate   float64
+}
+
+// DefaultInputProfile returns the standard driving feel
+func DefaultInputProfile() InputProfile {
+	return InputProfile{
+		Deadzone:       INPUT_DEADZONE,
+		SteerCurve:     INPUT_STEER_CURVE,
+		PedalCurve:     INPUT_PEDAL_CURVE,
+		SteerRate:      INPUT_STEER_RATE,
+		CenterRate:     INPUT_CENTER_RATE,
+		KeyPressRate:   INPUT_KEY_PRESS_RATE,
+		KeyReleaseRate: INPUT_KEY_RELEASE_RATE,
+		KeySteerRate:   INPUT_KEY_STEER_RATE,
+	}
+}
+
+// sanitized returns the profile with invalid values defaulted
+func (p InputProfile) sanitized() InputProfile {
+	d := DefaultInputProfile()
+	if !isFinite(p.Deadzone) {
+		p.Deadzone = d.Deadzone
+	}
+	p.Deadzone = clamp(p.Deadzone, 0, 0.9)
+	p.SteerCurve = positiveOr(p.SteerCurve, d.SteerCurve)
+	p.PedalCurve = positiveOr(p.PedalCurve, d.PedalCurve)
+	p.SteerRate = positiveOr(p.SteerRate, d.SteerRate)
+	p.CenterRate = positiveOr(p.CenterRate, d.CenterRate)
+	p.KeyPressRate = positiveOr(p.KeyPressRate, d.KeyPressRate)
+	p.KeyReleaseRate = positiveOr(p.KeyReleaseRate, d.KeyReleaseRate)
+	p.KeySteerRate = positiveOr(p.KeySteerRate, d.KeySteerRate)
+	return p
+}
+
+// shape applies the deadzone and a response curve to an axis reading, keeping its sign
+func (p *InputProfile) shape(v, curve float64) float64 {
+	mag := math.Abs(v)
+	if mag <= p.Deadzone {
+		return 0
+	}
+	return math.Copysign(simPow((mag-p.Deadzone)/(1-p.Deadzone), curve), v)
+}
+
+// slew moves an axis from current towards target, at rate away from rest
+// and at back towards it
+func slew(current, target, rate, back, dt float64) float64 {
+	if math.Abs(target) < math.Abs(current) || target*current < 0 {
+		rate = back
+	}
+	step := rate * dt
+	return current + clamp(target-current, -step, step)
+}
+
+// Process turns one frame of raw controls into car input, carrying on
+// from the input of the previous frame; keys marks raw as key presses
+func (p *InputProfile) Process(prev, raw InputState, keys bool, dt float64) InputState {
+	raw = raw.clamped()
+	dt = clamp(dt, 0, maxPhysicsDelta)
+	out := raw
+
+	if keys {
+		out.Throttle = slew(prev.Throttle, raw.Throttle, p.KeyPressRate, p.KeyReleaseRate, dt)
+		out.Brake = slew(prev.Brake, raw.Brake, p.KeyPressRate, p.KeyReleaseRate, dt)
+		out.Steering = slew(prev.Steering, raw.Steering, p.KeySteerRate, p.CenterRate, dt)
+	} else {
+		out.Throttle = p.shape(raw.Throttle, p.PedalCurve)
+		out.Brake = p.shape(raw.Brake, p.PedalCurve)
+		out.Steering = slew(prev.Steering, p.shape(raw.Steering, p.SteerCurve), p.SteerRate, p.CenterRate, dt)
+	}
+	return out.clamped()
+}
+
+// ProcessInput runs a frame of raw controls through the input profile and
+// holds the result as a car's input, returning false if the car does not exist
+func (w *World) ProcessInput(carID int, raw InputState, keys bool, dt float64) (InputState, bool) {
+	car := w.Car(carID)
+	if car == nil {
+		return InputState{}, false
+	}
+	car.Input = w.InputProfile.Process(car.Input, raw, keys, dt)
+	return car.Input, true
+}
@@ -0,0 +1,157 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Intersection Right of Way (unsignalized crossings)
+// ============================================================================
+
+// At intersections without a signal, the front AI car on each approach claims
+// the crossing when it gets close and waits at the stop line until:
+// - The crossing is clear of cars moving across its path
+// - Minor roads (lower edge Priority than the busiest approach) have come to
+//   a full stop and no major-road car is approaching (stop signs)
+// - Among equal roads, earlier arrivals go first; near-simultaneous arrivals
+//   yield to the car on their right, and long waits fall back to car ID order
+// Opposite approaches do not conflict, so straight-through flows share a gap.
+
+const (
+	INTERSECTION_APPROACH = 8.0  // units before the stop line where cars claim the crossing
+	INTERSECTION_RADIUS   = 1.0  // units, cars this close (plus body radius) to the node occupy it
+	STOP_SIGN_SPEED       = 0.3  // units/s, slow enough to count as a full stop
+	STOP_LINE_TOLERANCE   = 1.0  // units beyond IDM_MIN_GAP that still counts as at the line
+	YIELD_TIE_TIME        = 0.5  // s, arrivals closer than this are simultaneous
+	YIELD_DEADLOCK_TIME   = 4.0  // s, after this long a tie goes to the lowest car ID
+	CONFLICT_DOT          = -0.7 // Headings with a smaller dot product are opposite (no conflict)
+)
+
+// crossingState is an AI car's claim on the intersection ending its current edge
+type crossingState struct {
+	active    bool
+	edge      int     // Edge the claim was made from
+	arrivedAt float64 // World time of the claim
+	stopped   bool    // Came to a full stop (minor roads)
+	entering  bool    // Granted right of way, kept until past the node
+}
+
+// isIntersection reports whether a node joins three or more roads
+func (g *RoadGraph) isIntersection(n *RoadNode) bool {
+	neighbors := make(map[int]bool, len(n.In)+len(n.Out))
+	for _, id := range n.In {
+		neighbors[g.edges[id].From] = true
+	}
+	for _, id := range n.Out {
+		neighbors[g.edges[id].To] = true
+	}
+	return len(neighbors) >= 3
+}
+
+// majorPriority returns the highest priority among a node's approaches
+func (g *RoadGraph) majorPriority(n *RoadNode) int {
+	major := math.MinInt
+	for _, id := range n.In {
+		major = max(major, g.edges[id].Priority)
+	}
+	return major
+}
+
+// edgeDirection returns the unit travel direction of an edge
+func (g *RoadGraph) edgeDirection(e *RoadEdge) (dx, dz float64) {
+	from, to := g.nodes[e.From], g.nodes[e.To]
+	length := simHypot(to.X-from.X, to.Z-from.Z)
+	if length < 1e-9 {
+		return 0, 1
+	}
+	return (to.X - from.X) / length, (to.Z - from.Z) / length
+}
+
+// mustYield reports whether the front AI car on edge e has to wait at the
+// stop line of an unsignalized intersection, updating its claim
+func (w *World) mustYield(car *WorldCar, e *RoadEdge, stopGap, speed float64) bool {
+	node := w.Roads.Node(e.To)
+	if w.Signals[e.To] != nil || !w.Roads.isIntersection(node) {
+		return false
+	}
+
+	c := &car.AI.crossing
+	if !c.active || c.edge != e.ID {
+		if stopGap > INTERSECTION_APPROACH {
+			return false
+		}
+		*c = crossingState{active: true, edge: e.ID, arrivedAt: w.Time}
+	}
+	if c.entering {
+		return false
+	}
+
+	if e.Priority < w.Roads.majorPriority(node) && !c.stopped {
+		if stopGap > IDM_MIN_GAP+STOP_LINE_TOLERANCE || math.Abs(speed) > STOP_SIGN_SPEED {
+			return true
+		}
+		c.stopped = true
+	}
+
+	dx, dz := w.Roads.edgeDirection(e)
+	if w.crossingOccupied(car, node, dx, dz) {
+		return true
+	}
+
+	for _, other := range w.cars {
+		if other == car || other.AI == nil {
+			continue
+		}
+		oc := &other.AI.crossing
+		oe := w.Roads.Edge(oc.edge)
+		if !oc.active || oe == nil || oe.To != e.To || oe.ID == e.ID {
+			continue
+		}
+		odx, odz := w.Roads.edgeDirection(oe)
+		if dx*odx+dz*odz < CONFLICT_DOT {
+			continue // Opposite approach
+		}
+
+		if oc.entering || oe.Priority > e.Priority {
+			return true
+		}
+		if oe.Priority < e.Priority {
+			continue
+		}
+
+		// Equal roads: first come, first served; ties yield to the right
+		switch lead := c.arrivedAt - oc.arrivedAt; {
+		case lead > YIELD_TIE_TIME:
+			return true
+		case lead < -YIELD_TIE_TIME:
+			continue
+		case w.Time-c.arrivedAt > YIELD_DEADLOCK_TIME:
+			if other.State.ID < car.State.ID {
+				return true
+			}
+		case odx*dz-odz*dx > 0: // Other approach comes from our right
+			return true
+		}
+	}
+
+	c.entering = true
+	return false
+}
+
+// crossingOccupied reports whether a car moving across the given heading is
+// inside the intersection (cars going the same or opposite way do not block)
+func (w *World) crossingOccupied(car *WorldCar, node *RoadNode, dx, dz float64) bool {
+	for _, other := range w.cars {
+		if other == car {
+			continue
+		}
+		o := &other.State
+		if simHypot(o.X-node.X, o.Z-node.Z) > INTERSECTION_RADIUS+other.Config.BodyRadius {
+			continue
+		}
+
+		dot := math.Sin(o.RotationY)*dx + math.Cos(o.RotationY)*dz
+		if math.Abs(dot) < -CONFLICT_DOT {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,80 @@
+package main
+
+// ============================================================================
+// Land Value (per-cell desirability)
+// ============================================================================
+
+// Land value starts from a base and rises near green space and services,
+// falling with noise (physics_noise.go) and near industry. Values are 0-1 per
+// occupancy cell so taxes and building upgrades can scale by them.
+
+const (
+	LAND_VALUE_BASE     = 0.4
+	LAND_VALUE_GREEN    = 0.25 // Added at full green space coverage
+	LAND_VALUE_SERVICES = 0.3  // Added at full coverage by every non-green service
+	LAND_VALUE_NOISE    = 0.3  // Removed at full noise
+	LAND_VALUE_INDUSTRY = 0.35 // Removed right next to industry
+	INDUSTRY_RADIUS     = 6.0  // units
+)
+
+// LandValue is a field of land values on the occupancy layout
+type LandValue struct {
+	GridSpec
+	Cells []float64 // 0-1, row-major by z
+	Mean  float64
+}
+
+// At returns the land value at a world position (the base value outside the grid)
+func (v *LandValue) At(x, z float64) float64 {
+	i := v.CellIndex(x, z)
+	if i < 0 {
+		return LAND_VALUE_BASE
+	}
+	return v.Cells[i]
+}
+
+// LandValue computes the land value of every cell
+func (w *World) LandValue() LandValue {
+	g := w.Occupancy.GridSpec
+	value := LandValue{GridSpec: g, Cells: make([]float64, g.Len())}
+	for i := range value.Cells {
+		value.Cells[i] = LAND_VALUE_BASE
+	}
+
+	// Services lift value by their mean coverage; green space counts on its
+	// own and jobs nearby are left to the industry penalty
+	var services [][]float64
+	for i := range w.Services {
+		s := &w.Services[i]
+		coverage, _ := w.serviceCoverage(s)
+		switch s.Name {
+		case GREEN_SPACE_SERVICE:
+			for c, cov := range coverage {
+				value.Cells[c] += LAND_VALUE_GREEN * cov
+			}
+		case EMPLOYMENT_SERVICE:
+		default:
+			services = append(services, coverage)
+		}
+	}
+	for _, coverage := range services {
+		for c, cov := range coverage {
+			value.Cells[c] += LAND_VALUE_SERVICES * cov / float64(len(services))
+		}
+	}
+
+	noise := w.NoiseLevels()
+	industry, _ := w.proximityField(INDUSTRY_RADIUS, func(p *Placement) bool {
+		return w.placementUse(p) == "industry"
+	})
+	sum := 0.0
+	for c := range value.Cells {
+		v := value.Cells[c] - LAND_VALUE_NOISE*noise[c] - LAND_VALUE_INDUSTRY*industry[c]
+		value.Cells[c] = clamp(v, 0, 1)
+		sum += value.Cells[c]
+	}
+	if len(value.Cells) > 0 {
+		value.Mean = sum / float64(len(value.Cells))
+	}
+	return value
+}
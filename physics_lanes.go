@@ -0,0 +1,142 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Lane Changing (multi-lane segments)
+// ============================================================================
+
+// On segments with more than one lane, AI drivers compare the IDM
+// acceleration they would get in each neighboring lane (a simplified MOBIL
+// model). They move over when the gain beats a threshold and the gap is
+// safe: enough room to the new leader and follower, and the new follower
+// would not have to brake harder than LANE_CHANGE_SAFE_DECEL. Drivers keep
+// right: moving back right only needs the gain to be above -LANE_KEEP_RIGHT_BIAS.
+
+const (
+	LANE_CHANGE_THRESHOLD  = 0.5  // units/s², acceleration gain needed to change lanes
+	LANE_KEEP_RIGHT_BIAS   = 0.3  // units/s², acceptable loss when returning right
+	LANE_CHANGE_SAFE_DECEL = 4.0  // units/s², hardest braking forced on the new follower
+	LANE_CHANGE_MIN_GAP    = 2.0  // units, bumper gap needed ahead and behind in the new lane
+	LANE_CHANGE_RATE       = 0.8  // lanes/s, lateral speed while changing
+	LANE_CHANGE_COOLDOWN   = 3.0  // s between lane changes
+	LANE_CHANGE_END_MARGIN = 10.0 // units, no changes this close to the end of a segment
+)
+
+// laneNeighbors finds the nearest cars ahead of and behind the given car in
+// one lane of its edge, by their position relative to the lane centerline
+// Only cars heading roughly along the edge count, so cross traffic is ignored
+func (w *World) laneNeighbors(car *WorldCar, e *RoadEdge, lane int) (leader, follower *WorldCar, leadGap, followGap float64) {
+	ax, az, dx, dz, _ := w.laneSegment(e, float64(lane))
+	s := &car.State
+	along := (s.X-ax)*dx + (s.Z-az)*dz
+	leadGap, followGap = math.Inf(1), math.Inf(1)
+
+	for _, other := range w.cars {
+		if other == car {
+			continue
+		}
+		o := &other.State
+		if math.Sin(o.RotationY)*dx+math.Cos(o.RotationY)*dz < 0.5 {
+			continue
+		}
+
+		relX, relZ := o.X-ax, o.Z-az
+		lateral := relX*dz - relZ*dx // Positive to the left of travel
+		if math.Abs(lateral) > LANE_WIDTH/2 {
+			continue
+		}
+
+		ahead := relX*dx + relZ*dz - along
+		if math.Abs(ahead) > AI_SENSOR_RANGE {
+			continue
+		}
+		gap := math.Abs(ahead) - car.Config.BodyRadius - other.Config.BodyRadius
+		if ahead >= 0 && gap < leadGap {
+			leader, leadGap = other, gap
+		} else if ahead < 0 && gap < followGap {
+			follower, followGap = other, gap
+		}
+	}
+	return leader, follower, leadGap, followGap
+}
+
+// forwardSpeed returns a car's speed along a direction
+func forwardSpeed(s *CarState, dx, dz float64) float64 {
+	return s.VelocityX*dx + s.VelocityZ*dz
+}
+
+// laneAcceleration returns the IDM acceleration the car would have in a lane,
+// and whether moving into it is safe for the car behind
+func (w *World) laneAcceleration(car *WorldCar, e *RoadEdge, lane int, dx, dz float64) (accel float64, safe bool) {
+	leader, follower, leadGap, followGap := w.laneNeighbors(car, e, lane)
+	speed := forwardSpeed(&car.State, dx, dz)
+	limit := w.roadSpeedLimit(e, car.State.X, car.State.Z) // As the district sets it (physics_districts.go)
+
+	leaderSpeed := 0.0
+	if leader != nil {
+		leaderSpeed = forwardSpeed(&leader.State, dx, dz)
+	}
+	accel = idmAcceleration(speed, limit, leadGap, speed-leaderSpeed, car.Config.Acceleration)
+
+	safe = leadGap > LANE_CHANGE_MIN_GAP && followGap > LANE_CHANGE_MIN_GAP
+	if follower != nil && safe {
+		followSpeed := forwardSpeed(&follower.State, dx, dz)
+		followAccel := idmAcceleration(followSpeed, limit, followGap, followSpeed-speed, follower.Config.Acceleration)
+		safe = followAccel > -LANE_CHANGE_SAFE_DECEL
+	}
+	return accel, safe
+}
+
+// updateLane decides on lane changes and glides the car's lateral position
+// towards its target lane
+func (w *World) updateLane(car *WorldCar, e *RoadEdge, dt float64) {
+	ai := car.AI
+	ai.Lane = min(max(ai.Lane, 0), e.Lanes-1)
+	ai.laneChangeTimer = math.Max(ai.laneChangeTimer-dt, 0)
+
+	// Only start a change once settled in the current lane
+	settled := math.Abs(ai.lanePos-float64(ai.Lane)) < 1e-3
+	if e.Lanes > 1 && settled && ai.laneChangeTimer == 0 {
+		w.considerLaneChange(car, e)
+	}
+
+	step := LANE_CHANGE_RATE * dt
+	ai.lanePos += clamp(float64(ai.Lane)-ai.lanePos, -step, step)
+}
+
+// considerLaneChange moves the car's target lane when a neighboring lane is
+// both better and safe
+func (w *World) considerLaneChange(car *WorldCar, e *RoadEdge) {
+	ai := car.AI
+	ax, az, dx, dz, length := w.laneSegment(e, float64(ai.Lane))
+	if (car.State.X-ax)*dx+(car.State.Z-az)*dz > length-LANE_CHANGE_END_MARGIN {
+		return
+	}
+
+	current, _ := w.laneAcceleration(car, e, ai.Lane, dx, dz)
+	best, bestGain := ai.Lane, 0.0
+	for _, lane := range [2]int{ai.Lane + 1, ai.Lane - 1} { // Left first: overtake on the left
+		if lane < 0 || lane >= e.Lanes {
+			continue
+		}
+		accel, safe := w.laneAcceleration(car, e, lane, dx, dz)
+		if !safe {
+			continue
+		}
+
+		gain := accel - current
+		threshold := LANE_CHANGE_THRESHOLD
+		if lane < ai.Lane {
+			threshold = -LANE_KEEP_RIGHT_BIAS
+		}
+		if gain > threshold && (best == ai.Lane || gain > bestGain) {
+			best, bestGain = lane, gain
+		}
+	}
+
+	if best != ai.Lane {
+		ai.Lane = best
+		ai.laneChangeTimer = LANE_CHANGE_COOLDOWN
+	}
+}
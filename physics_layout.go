@@ -0,0 +1,335 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"math"
+	"strings"
+)
+
+// ============================================================================
+// Town Layouts (human-readable JSON of what was built)
+// ============================================================================
+
+// A TownLayout is the town as a player would describe it: the town grid in
+// the setOccupancyGrid format, placed objects in the setPlacements format
+// plus their rotation, the road network in the setRoadNetwork format and
+// the zoning as rectangles. Unlike snapshots (physics_snapshot.go) it holds
+// nothing simulated (cars, clock, treasury, ...) and is written indented in
+// ID order, so layouts can be shared, diffed and edited by hand.
+// ImportLayout checks the whole layout before changing anything and reports
+// every problem it finds.
+
+const LAYOUT_VERSION = 1 // Bumped when the format changes incompatibly
+
+// TownLayout is the exported layout of a town
+type TownLayout struct {
+	Version    int               `json:"version"`
+	Grid       *LayoutGrid       `json:"grid,omitempty"` // Omitted keeps the current grid on import
+	Placements []LayoutPlacement `json:"placements"`
+	Roads      LayoutRoads       `json:"roads"`
+	Zones      []LayoutZone      `json:"zones"`
+}
+
+// LayoutGrid is the town cell layout
+type LayoutGrid struct {
+	Width    int     `json:"width"`
+	Depth    int     `json:"depth"`
+	CellSize float64 `json:"cellSize"`
+	OriginX  float64 `json:"originX"`
+	OriginZ  float64 `json:"originZ"`
+}
+
+// LayoutBox is a world-space rectangle; like every JS bounding box, its Y
+// fields hold world Z
+type LayoutBox struct {
+	MinX float64 `json:"minX"`
+	MinY float64 `json:"minY"`
+	MaxX float64 `json:"maxX"`
+	MaxY float64 `json:"maxY"`
+}
+
+// LayoutPlacement is one placed building, road tile, tree or prop
+type LayoutPlacement struct {
+	ID       int       `json:"id"`
+	Model    string    `json:"model"`
+	Category string    `json:"category"`
+	BBox     LayoutBox `json:"bbox"`
+	Rotation float64   `json:"rotation"` // Radians about Y
+}
+
+// LayoutRoads is the road network
+type LayoutRoads struct {
+	Nodes []LayoutRoadNode `json:"nodes"`
+	Roads []LayoutRoad     `json:"roads"`
+}
+
+// LayoutRoadNode is one road intersection or bend
+type LayoutRoadNode struct {
+	ID int     `json:"id"`
+	X  float64 `json:"x"`
+	Z  float64 `json:"z"`
+}
+
+// LayoutRoad is a road between two nodes; a two-way road stands for both
+// directions, which share their speed limit, lanes, priority and bike lanes
+type LayoutRoad struct {
+	From       int     `json:"from"`
+	To         int     `json:"to"`
+	SpeedLimit float64 `json:"speed_limit"`
+	Lanes      int     `json:"lanes"` // Per direction
+	Priority   int     `json:"priority"`
+	TwoWay     bool    `json:"two_way"`
+	BikeLane   bool    `json:"bike_lane,omitempty"`
+}
+
+// LayoutZone zones the cells whose centers lie inside a rectangle
+type LayoutZone struct {
+	Zone string    `json:"zone"`
+	BBox LayoutBox `json:"bbox"`
+}
+
+// LayoutError lists every problem found importing a layout
+type LayoutError struct {
+	Problems []string
+}
+
+func (e *LayoutError) Error() string {
+	return "invalid layout: " + strings.Join(e.Problems, "; ")
+}
+
+// ExportLayout returns the town's buildings, roads and zones
+func (w *World) ExportLayout() TownLayout {
+	spec := w.Occupancy.GridSpec
+	l := TownLayout{
+		Version:    LAYOUT_VERSION,
+		Grid:       &LayoutGrid{Width: spec.Width, Depth: spec.Depth, CellSize: spec.CellSize, OriginX: spec.OriginX, OriginZ: spec.OriginZ},
+		Placements: []LayoutPlacement{},
+		Roads:      LayoutRoads{Nodes: []LayoutRoadNode{}, Roads: []LayoutRoad{}},
+		Zones:      []LayoutZone{},
+	}
+
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if p.ID >= LOT_PLAN_ID_BASE {
+			continue // Held while planning lots (physics_lots.go)
+		}
+		l.Placements = append(l.Placements, LayoutPlacement{ID: p.ID, Model: p.Model, Category: p.Category, BBox: LayoutBox(p.Footprint), Rotation: p.Rotation})
+	}
+
+	for _, id := range w.Roads.NodeIDs() {
+		n := w.Roads.Node(id)
+		l.Roads.Nodes = append(l.Roads.Nodes, LayoutRoadNode{ID: n.ID, X: n.X, Z: n.Z})
+	}
+	done := make(map[int]bool, w.Roads.EdgeCount())
+	for _, id := range w.Roads.EdgeIDs() {
+		if done[id] {
+			continue
+		}
+		e := w.Roads.Edge(id)
+		done[id] = true
+		road := LayoutRoad{From: e.From, To: e.To, SpeedLimit: e.SpeedLimit, Lanes: e.Lanes, Priority: e.Priority, BikeLane: e.BikeLane}
+		for _, back := range w.Roads.Node(e.To).Out {
+			r := w.Roads.Edge(back)
+			if !done[back] && r.To == e.From && r.SpeedLimit == e.SpeedLimit && r.Lanes == e.Lanes && r.Priority == e.Priority && r.BikeLane == e.BikeLane {
+				done[back] = true
+				road.TwoWay = true
+				break
+			}
+		}
+		l.Roads.Roads = append(l.Roads.Roads, road)
+	}
+
+	l.Zones = w.zoneRects()
+	return l
+}
+
+// zoneRects covers the zoned cells with rectangles, growing each from its
+// first cell (in row order) along X and then down Z while the zone holds
+func (w *World) zoneRects() []LayoutZone {
+	m := w.Zones
+	covered := make([]bool, len(m.cells))
+	rects := []LayoutZone{}
+	for start, zone := range m.cells {
+		if zone == ZoneNone || covered[start] {
+			continue
+		}
+		x0, z0 := start%m.Width, start/m.Width
+		x1 := x0
+		for x1+1 < m.Width && m.cells[z0*m.Width+x1+1] == zone && !covered[z0*m.Width+x1+1] {
+			x1++
+		}
+		z1 := z0
+	rows:
+		for z1+1 < m.Depth {
+			for x := x0; x <= x1; x++ {
+				if i := (z1+1)*m.Width + x; m.cells[i] != zone || covered[i] {
+					break rows
+				}
+			}
+			z1++
+		}
+		for z := z0; z <= z1; z++ {
+			for x := x0; x <= x1; x++ {
+				covered[z*m.Width+x] = true
+			}
+		}
+		rects = append(rects, LayoutZone{Zone: zone.String(), BBox: LayoutBox{
+			MinX: m.OriginX + float64(x0)*m.CellSize,
+			MinY: m.OriginZ + float64(z0)*m.CellSize,
+			MaxX: m.OriginX + float64(x1+1)*m.CellSize,
+			MaxY: m.OriginZ + float64(z1+1)*m.CellSize,
+		}})
+	}
+	return rects
+}
+
+// paintCenters zones the cells whose centers lie inside a box, which
+// round-trips exported rectangles exactly whatever the cell size
+func (m *ZoneMap) paintCenters(b LayoutBox, zone ZoneType) {
+	minX := max(int(math.Ceil((b.MinX-m.OriginX)/m.CellSize-0.5)), 0)
+	minZ := max(int(math.Ceil((b.MinY-m.OriginZ)/m.CellSize-0.5)), 0)
+	maxX := min(int(math.Floor((b.MaxX-m.OriginX)/m.CellSize-0.5)), m.Width-1)
+	maxZ := min(int(math.Floor((b.MaxY-m.OriginZ)/m.CellSize-0.5)), m.Depth-1)
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			m.cells[cz*m.Width+cx] = zone
+		}
+	}
+}
+
+// validBox reports whether a box has finite, ordered bounds
+func validBox(b LayoutBox) bool {
+	return isFinite(b.MinX) && isFinite(b.MinY) && isFinite(b.MaxX) && isFinite(b.MaxY) && b.MinX <= b.MaxX && b.MinY <= b.MaxY
+}
+
+// ImportLayout replaces the town's placements, roads and zones with a
+// layout. Nothing changes unless the whole layout is valid; otherwise the
+// error is a *LayoutError listing every problem. Placements lose their
+// static colliders, which JS registers again with setPlacements as it
+// rebuilds the scene, and generated trips and traffic signals are cleared
+// since their routes and phases refer to the old roads
+func (w *World) ImportLayout(l TownLayout) error {
+	var problems []string
+	problem := func(format string, args ...interface{}) {
+		problems = append(problems, fmt.Sprintf(format, args...))
+	}
+	if l.Version != LAYOUT_VERSION {
+		problem("unsupported layout version %d (expected %d)", l.Version, LAYOUT_VERSION)
+		return &LayoutError{Problems: problems}
+	}
+
+	spec := w.Occupancy.GridSpec
+	if g := l.Grid; g != nil {
+		spec = GridSpec{Width: g.Width, Depth: g.Depth, CellSize: g.CellSize, OriginX: g.OriginX, OriginZ: g.OriginZ}
+		if !spec.valid() || !isFinite(spec.OriginX) || !isFinite(spec.OriginZ) {
+			problem("grid needs a positive width, depth and cellSize and a finite origin")
+			spec = w.Occupancy.GridSpec
+		}
+	}
+
+	placements := make([]Placement, 0, len(l.Placements))
+	ids := make(map[int]bool, len(l.Placements))
+	for i, p := range l.Placements {
+		switch {
+		case p.ID < 0 || p.ID >= LOT_PLAN_ID_BASE:
+			problem("placement %d: id %d is out of range", i, p.ID)
+		case ids[p.ID]:
+			problem("placement %d: id %d is used twice", i, p.ID)
+		case p.Model == "" || p.Category == "":
+			problem("placement %d (id %d): model and category are required", i, p.ID)
+		case !validBox(p.BBox):
+			problem("placement %d (id %d): bbox must be finite with min <= max", i, p.ID)
+		case !isFinite(p.Rotation):
+			problem("placement %d (id %d): rotation must be finite", i, p.ID)
+		default:
+			placements = append(placements, Placement{ID: p.ID, Model: p.Model, Category: p.Category, Footprint: BoundingBox(p.BBox), Rotation: p.Rotation})
+		}
+		ids[p.ID] = true
+	}
+
+	roads := NewRoadGraph()
+	for i, n := range l.Roads.Nodes {
+		switch {
+		case roads.Node(n.ID) != nil:
+			problem("road node %d: id %d is used twice", i, n.ID)
+		case !isFinite(n.X) || !isFinite(n.Z):
+			problem("road node %d (id %d): position must be finite", i, n.ID)
+		default:
+			roads.AddNode(n.ID, n.X, n.Z)
+		}
+	}
+	segments := make(map[[2]int]bool, len(l.Roads.Roads))
+	for i, r := range l.Roads.Roads {
+		duplicate := segments[[2]int{r.From, r.To}] || (r.TwoWay && segments[[2]int{r.To, r.From}])
+		switch {
+		case duplicate:
+			problem("road %d: %d -> %d is given twice", i, r.From, r.To)
+			continue
+		case !isFinite(r.SpeedLimit) || r.SpeedLimit < 0 || r.Lanes < 0:
+			problem("road %d: speed_limit and lanes must not be negative", i)
+			continue
+		}
+		forward, reverse, err := roads.AddRoad(r.From, r.To, r.SpeedLimit, r.Lanes, r.TwoWay)
+		if err != nil {
+			problem("road %d: %s", i, err.Error())
+			continue
+		}
+		roads.SetPriority(forward, r.Priority)
+		roads.SetPriority(reverse, r.Priority) // No-op for one-way roads (-1)
+		roads.SetBikeLane(forward, r.BikeLane)
+		roads.SetBikeLane(reverse, r.BikeLane)
+		segments[[2]int{r.From, r.To}] = true
+		if r.TwoWay {
+			segments[[2]int{r.To, r.From}] = true
+		}
+	}
+
+	zones := NewZoneMap(spec)
+	for i, z := range l.Zones {
+		zone, ok := zoneFromString(z.Zone)
+		switch {
+		case !ok:
+			problem("zone %d: unknown zone %q", i, z.Zone)
+		case !validBox(z.BBox):
+			problem("zone %d: bbox must be finite with min <= max", i)
+		default:
+			zones.paintCenters(z.BBox, zone)
+		}
+	}
+
+	if len(problems) > 0 {
+		return &LayoutError{Problems: problems}
+	}
+
+	w.ClearTrips()
+	w.ClearCitizens()
+	for id := range w.Signals {
+		delete(w.Signals, id)
+	}
+	*w.Roads = *roads
+
+	w.ClearPlacements()
+	if spec != w.Occupancy.GridSpec {
+		w.ResizeTownGrid(spec)
+	}
+	for _, p := range placements {
+		w.Occupancy.Add(p)
+	}
+	copy(w.Zones.cells, zones.cells)
+	return nil
+}
+
+// LayoutJSON encodes the town's layout as indented JSON
+func (w *World) LayoutJSON() ([]byte, error) {
+	return json.MarshalIndent(w.ExportLayout(), "", "  ")
+}
+
+// ImportLayoutJSON replaces the town's layout with LayoutJSON output
+func (w *World) ImportLayoutJSON(data []byte) error {
+	var l TownLayout
+	if err := json.Unmarshal(data, &l); err != nil {
+		return &LayoutError{Problems: []string{err.Error()}}
+	}
+	return w.ImportLayout(l)
+}
@@ -0,0 +1,153 @@
+package main
+
+// ============================================================================
+// Building Levels (upgrades and downgrades from land value and services)
+// ============================================================================
+
+// Homes and workplaces start at level 1 and can rise to BUILDING_MAX_LEVEL.
+// At the end of each game day (physics_clock.go) every one is scored on its
+// cell: a blend of the land value (physics_land_value.go) and its service
+// coverage, every service but employment (physics_livability.go); a building
+// short of its utilities (physics_utilities.go) scores 0. A building scoring
+// at least its level's upgrade score for UpgradeDays days running goes up a
+// level; one scoring LEVEL_HYSTERESIS or more below the score that got it to
+// its level for DowngradeDays days running goes back down. Each level above
+// the first adds LEVEL_CAPACITY_STEP of the use's residents and jobs
+// (physics_population.go), and every change queues a "building_upgraded" or
+// "building_downgraded" event (physics_sim_events.go) so the frontend can
+// swap the model. Levels are kept by placement ID and dropped when the
+// placement is removed.
+
+const (
+	BUILDING_MAX_LEVEL      = 3
+	LEVEL_UPGRADE_SCORE     = 0.55 // Score a level 1 building needs to go up
+	LEVEL_UPGRADE_STEP      = 0.15 // Added to the upgrade score at each level
+	LEVEL_HYSTERESIS        = 0.1  // Score below the one that got a building its level it may fall to
+	LEVEL_UPGRADE_DAYS      = 3    // Days running at the upgrade score to go up
+	LEVEL_DOWNGRADE_DAYS    = 5    // Days running below it to go down
+	LEVEL_CAPACITY_STEP     = 0.5  // Residents and jobs added per level, share of the use's
+	LEVEL_LAND_VALUE_WEIGHT = 0.5  // Share of the score from land value; the rest is service coverage
+)
+
+// BuildingLevel is one building's level and how it is trending
+type BuildingLevel struct {
+	Level  int     // 1 to BUILDING_MAX_LEVEL
+	Streak int     // Days running above the upgrade score (positive) or below the downgrade score (negative)
+	Score  float64 // 0-1 at the last daily check
+}
+
+// BuildingLevels tracks the level of every home and workplace
+type BuildingLevels struct {
+	Enabled       bool
+	UpgradeDays   int
+	DowngradeDays int
+	Buildings     map[int]BuildingLevel // Placement ID -> level; buildings not yet checked are level 1
+}
+
+// NewBuildingLevels creates enabled leveling with every building at level 1
+func NewBuildingLevels() *BuildingLevels {
+	return &BuildingLevels{
+		Enabled:       true,
+		UpgradeDays:   LEVEL_UPGRADE_DAYS,
+		DowngradeDays: LEVEL_DOWNGRADE_DAYS,
+		Buildings:     make(map[int]BuildingLevel),
+	}
+}
+
+// upgradeScore returns the score a building at a level needs to go up
+func upgradeScore(level int) float64 {
+	return LEVEL_UPGRADE_SCORE + LEVEL_UPGRADE_STEP*float64(level-1)
+}
+
+// BuildingLevel returns a placement's level, 1 for anything not leveled
+func (w *World) BuildingLevel(id int) int {
+	if b, ok := w.Levels.Buildings[id]; ok {
+		return b.Level
+	}
+	return 1
+}
+
+// SetBuildingLevel puts a home or workplace at a level (clamped to 1 to
+// BUILDING_MAX_LEVEL) and restarts its streak, returning false for any
+// other placement
+func (w *World) SetBuildingLevel(id, level int) bool {
+	p := w.Occupancy.Placement(id)
+	if p == nil || !w.levelable(p) {
+		return false
+	}
+	b := w.Levels.Buildings[id]
+	b.Level, b.Streak = max(min(level, BUILDING_MAX_LEVEL), 1), 0
+	w.Levels.Buildings[id] = b
+	return true
+}
+
+// levelable reports whether a placement is a completed building with
+// residents or jobs
+func (w *World) levelable(p *Placement) bool {
+	c := w.Capacities[w.placementUse(p)]
+	return flammable(p) && c.Residents+c.Jobs > 0 && !w.underConstruction(p)
+}
+
+// levelCapacity scales a use's capacity to a building's level
+func levelCapacity(c UseCapacity, level int) UseCapacity {
+	scale := 1 + LEVEL_CAPACITY_STEP*float64(level-1)
+	c.Residents = int(float64(c.Residents) * scale)
+	c.Jobs = int(float64(c.Jobs) * scale)
+	return c
+}
+
+// updateBuildingLevels scores every home and workplace for the day and
+// moves those with a long enough streak up or down a level
+func (w *World) updateBuildingLevels() {
+	l := w.Levels
+	if !l.Enabled {
+		return
+	}
+	g := w.Occupancy.GridSpec
+	value := w.LandValue()
+	_, underserved := w.CoverageGrids()
+
+	present := make(map[int]bool, len(l.Buildings))
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if !w.levelable(p) {
+			continue
+		}
+		present[id] = true
+		b, ok := l.Buildings[id]
+		if !ok {
+			b.Level = 1
+		}
+
+		b.Score = 0
+		if cell := g.CellIndex(p.Center()); cell >= 0 && w.utilitiesServed(p) {
+			b.Score = LEVEL_LAND_VALUE_WEIGHT*value.Cells[cell] + (1-LEVEL_LAND_VALUE_WEIGHT)*(1-underserved[cell])
+		}
+		switch {
+		case b.Level < BUILDING_MAX_LEVEL && b.Score >= upgradeScore(b.Level):
+			b.Streak = max(b.Streak, 0) + 1
+		case b.Level > 1 && b.Score < upgradeScore(b.Level-1)-LEVEL_HYSTERESIS:
+			b.Streak = min(b.Streak, 0) - 1
+		default:
+			b.Streak = 0
+		}
+
+		kind := simEventKindCount
+		switch {
+		case b.Streak >= max(l.UpgradeDays, 1):
+			b.Level, b.Streak, kind = b.Level+1, 0, SimBuildingUpgraded
+		case -b.Streak >= max(l.DowngradeDays, 1):
+			b.Level, b.Streak, kind = b.Level-1, 0, SimBuildingDowngraded
+		}
+		l.Buildings[id] = b
+		if kind != simEventKindCount {
+			x, z := p.Center()
+			w.pushSimEvent(SimEvent{Kind: kind, ID: id, To: b.Level, Model: p.Model, Value: b.Score, X: x, Z: z})
+		}
+	}
+	for id := range l.Buildings {
+		if !present[id] {
+			delete(l.Buildings, id)
+		}
+	}
+}
@@ -0,0 +1,241 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Livability Score (service coverage from placed buildings)
+// ============================================================================
+
+// Each service (schools, library, markets, healthcare, employment, green
+// space) covers the cells within its radius of a provider, fading linearly to
+// zero at the edge. A town's score for a service is the mean coverage at its
+// homes (or over the whole grid before any homes are built); livability is
+// the weighted mean of the service scores, 0-100, less a penalty for noise.
+// Green space weighs more in summer and less in winter (physics_seasons.go).
+
+const (
+	LIVABILITY_MAX_SCORE     = 100.0
+	LIVABILITY_NOISE_PENALTY = 30.0 // Points removed for homes at full noise (physics_noise.go)
+	GREEN_SPACE_SERVICE      = "green_space"
+	EMPLOYMENT_SERVICE       = "employment"
+)
+
+// ServiceCategory is one service residents need nearby
+type ServiceCategory struct {
+	Name       string
+	Uses       []string // Model uses that provide the service (ModelSpec.Use)
+	Categories []string // Model categories that provide the service
+	Radius     float64  // units, coverage reaches zero here
+	Weight     float64  // Share of the livability score
+}
+
+// DefaultServices returns the town's services, in report order
+func DefaultServices() []ServiceCategory {
+	return []ServiceCategory{
+		{Name: "schools", Uses: []string{"school"}, Radius: 8, Weight: 1},
+		{Name: "library", Uses: []string{"library"}, Radius: 7, Weight: 0.6},
+		{Name: "markets", Uses: []string{"market"}, Radius: 6, Weight: 1},
+		{Name: "healthcare", Uses: []string{"healthcare"}, Radius: 10, Weight: 1},
+		{Name: EMPLOYMENT_SERVICE, Uses: []string{"office", "industry"}, Radius: 9, Weight: 1.2},
+		{Name: GREEN_SPACE_SERVICE, Categories: []string{"park", "trees"}, Radius: 5, Weight: 0.8},
+	}
+}
+
+// provides reports whether a placement of the given use and category provides the service
+func (s *ServiceCategory) provides(use, category string) bool {
+	for _, u := range s.Uses {
+		if u == use {
+			return true
+		}
+	}
+	for _, c := range s.Categories {
+		if c == category {
+			return true
+		}
+	}
+	return false
+}
+
+// ServiceScore is one service's share of a livability report
+type ServiceScore struct {
+	Name      string
+	Score     float64   // 0-100, mean coverage at the sampled points
+	Providers int       // Placements providing the service
+	Coverage  []float64 // Per-cell coverage 0-1 on the occupancy layout
+}
+
+// LivabilityReport is the town-wide livability breakdown
+type LivabilityReport struct {
+	Score    float64 // 0-100, weighted mean of the service scores less the noise penalty
+	Homes    int     // Homes sampled (0 means the whole grid was sampled)
+	Noise    float64 // 0-1, mean noise at the sampled points
+	Services []ServiceScore
+}
+
+// placementUse returns the use of a placed model ("" if unregistered)
+func (w *World) placementUse(p *Placement) string {
+	return w.Models[p.Model].Use
+}
+
+// serviceCoverage returns the per-cell coverage of a service and its provider count
+// Green space grows in as trees and parks mature (physics_greenery.go), and
+// buildings under construction (physics_construction.go) or without their
+// utilities (physics_utilities.go) provide nothing
+func (w *World) serviceCoverage(s *ServiceCategory) ([]float64, int) {
+	if s.Name == GREEN_SPACE_SERVICE {
+		w.refreshPlantings()
+	}
+	return w.weightedProximityField(s.Radius, func(p *Placement) float64 {
+		switch {
+		case !s.provides(w.placementUse(p), p.Category), w.underConstruction(p), !w.utilitiesServed(p):
+			return 0
+		case s.Name == GREEN_SPACE_SERVICE:
+			return w.greenShare(p)
+		}
+		return 1
+	})
+}
+
+// proximityField returns, per occupancy cell, 1 at the center of the
+// nearest matching placement fading linearly to 0 at radius, and the number
+// of matching placements
+func (w *World) proximityField(radius float64, match func(*Placement) bool) ([]float64, int) {
+	return w.weightedProximityField(radius, func(p *Placement) float64 {
+		if match(p) {
+			return 1
+		}
+		return 0
+	})
+}
+
+// weightedProximityField is proximityField with a strength per placement:
+// a placement of strength s gives s at its center, and placements of
+// strength 0 do not count
+func (w *World) weightedProximityField(radius float64, strength func(*Placement) float64) ([]float64, int) {
+	g := w.Occupancy.GridSpec
+	field := make([]float64, g.Len())
+	count := 0
+	if !(radius > 0) {
+		return field, 0
+	}
+
+	span := math.Ceil(radius/g.CellSize) * g.CellSize
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		weight := strength(p)
+		if !(weight > 0) {
+			continue
+		}
+		count++
+
+		px, pz := p.Center()
+		minX, minZ, maxX, maxZ, ok := g.CellRange(BoundingBox{MinX: px - span, MinY: pz - span, MaxX: px + span, MaxY: pz + span})
+		if !ok {
+			continue
+		}
+		for cz := minZ; cz <= maxZ; cz++ {
+			for cx := minX; cx <= maxX; cx++ {
+				i := cz*g.Width + cx
+				x, z := g.CellCenter(i)
+				field[i] = math.Max(field[i], weight*(1-simHypot(x-px, z-pz)/radius))
+			}
+		}
+	}
+	return field, count
+}
+
+// Livability scores the town's service coverage
+func (w *World) Livability() LivabilityReport {
+	g := w.Occupancy.GridSpec
+
+	// Sample at homes, or every cell before any homes exist
+	var samples []int
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if w.placementUse(p) != "housing" {
+			continue
+		}
+		if i := g.CellIndex(p.Center()); i >= 0 {
+			samples = append(samples, i)
+		}
+	}
+	report := LivabilityReport{Homes: len(samples)}
+	if len(samples) == 0 {
+		samples = make([]int, g.Len())
+		for i := range samples {
+			samples[i] = i
+		}
+	}
+
+	totalWeight := 0.0
+	for i := range w.Services {
+		s := &w.Services[i]
+		coverage, providers := w.serviceCoverage(s)
+		sum := 0.0
+		for _, cell := range samples {
+			sum += coverage[cell]
+		}
+		score := 0.0
+		if len(samples) > 0 {
+			score = LIVABILITY_MAX_SCORE * sum / float64(len(samples))
+		}
+
+		report.Services = append(report.Services, ServiceScore{Name: s.Name, Score: score, Providers: providers, Coverage: coverage})
+		if weight := w.serviceWeight(s); weight > 0 {
+			report.Score += score * weight
+			totalWeight += weight
+		}
+	}
+	if totalWeight > 0 {
+		report.Score /= totalWeight
+	}
+
+	noise := w.NoiseLevels()
+	for _, cell := range samples {
+		report.Noise += noise[cell]
+	}
+	if len(samples) > 0 {
+		report.Noise /= float64(len(samples))
+	}
+	report.Score = math.Max(report.Score-LIVABILITY_NOISE_PENALTY*report.Noise, 0)
+	return report
+}
+
+// CoverageGrids returns the per-cell coverage of every service, in service
+// order, and how underserved each cell is: 1 minus the weighted mean coverage
+// of every service but employment
+func (w *World) CoverageGrids() (coverage [][]float64, underserved []float64) {
+	underserved = make([]float64, w.Occupancy.GridSpec.Len())
+	totalWeight := 0.0
+	for i := range w.Services {
+		s := &w.Services[i]
+		field, _ := w.serviceCoverage(s)
+		coverage = append(coverage, field)
+		weight := w.serviceWeight(s)
+		if s.Name == EMPLOYMENT_SERVICE || !(weight > 0) {
+			continue
+		}
+		for c, cov := range field {
+			underserved[c] += cov * weight
+		}
+		totalWeight += weight
+	}
+	for c := range underserved {
+		served := 0.0
+		if totalWeight > 0 {
+			served = underserved[c] / totalWeight
+		}
+		underserved[c] = 1 - served
+	}
+	return coverage, underserved
+}
+
+// Service returns the service with the given name, or nil
+func (w *World) Service(name string) *ServiceCategory {
+	for i := range w.Services {
+		if w.Services[i].Name == name {
+			return &w.Services[i]
+		}
+	}
+	return nil
+}
@@ -0,0 +1,209 @@
+package main
+
+import (
+	"math"
+	"math/rand"
+	"sort"
+)
+
+// ============================================================================
+// Procedural Lots (buildings along road frontage)
+// ============================================================================
+
+// PlanLots walks the open sides of every road tile, where no other road
+// continues, and proposes one lot facing the road on each. A lot takes a
+// building whose use suits the zone under it (any building on unzoned land,
+// favoring homes) and is kept only if CanPlace accepts it, so generated towns
+// follow the same overlap, zoning, road access, slope and budget rules as the
+// player. Planned lots are held in the occupancy grid while planning so later
+// lots do not overlap them, then released for JS to place the real models.
+
+const (
+	LOT_SETBACK      = 0.1     // units between the road edge and a building
+	LOT_PLAN_ID_BASE = 1 << 30 // Placement IDs from here up are lots held while planning
+	LOT_HOUSING_BIAS = 3.0     // Weight of homes relative to other uses on unzoned lots
+	LOT_PROBE_DEPTH  = 0.25    // units beyond a tile side checked for a continuing road
+)
+
+// LotGenConfig controls lot generation
+type LotGenConfig struct {
+	Seed    int64
+	Models  []string // Candidate buildings; empty means every registered building
+	MaxLots int      // 0 means no limit
+}
+
+// LotPlan is one building a generator proposes
+type LotPlan struct {
+	Model    string
+	X, Z     float64 // Footprint center
+	Rotation float64 // rad about Y; the model's +Z side faces the road
+	Zone     ZoneType
+	Cost     float64
+}
+
+// lotFrontage is one open side of a road tile
+type lotFrontage struct {
+	X, Z       float64 // Middle of the tile side
+	NX, NZ     float64 // Unit normal pointing away from the road
+	SideLength float64
+}
+
+// roadFrontage returns the sides of road tiles with no road beyond them, by tile ID
+func (w *World) roadFrontage() []lotFrontage {
+	var frontage []lotFrontage
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if !p.isRoad() {
+			continue
+		}
+		f := p.Footprint
+		midX, midZ := (f.MinX+f.MaxX)/2, (f.MinY+f.MaxY)/2
+		sides := [4]lotFrontage{
+			{X: f.MaxX, Z: midZ, NX: 1, SideLength: f.MaxY - f.MinY},
+			{X: f.MinX, Z: midZ, NX: -1, SideLength: f.MaxY - f.MinY},
+			{X: midX, Z: f.MaxY, NZ: 1, SideLength: f.MaxX - f.MinX},
+			{X: midX, Z: f.MinY, NZ: -1, SideLength: f.MaxX - f.MinX},
+		}
+		for _, side := range sides {
+			if !w.roadBeyond(side) {
+				frontage = append(frontage, side)
+			}
+		}
+	}
+	return frontage
+}
+
+// roadBeyond reports whether another road tile continues past a tile side
+func (w *World) roadBeyond(side lotFrontage) bool {
+	half := side.SideLength / 4
+	cx, cz := side.X+side.NX*LOT_PROBE_DEPTH, side.Z+side.NZ*LOT_PROBE_DEPTH
+	probe := BoundingBox{MinX: cx - half, MinY: cz - half, MaxX: cx + half, MaxY: cz + half}
+	if side.NX != 0 {
+		probe.MinX, probe.MaxX = cx-LOT_PROBE_DEPTH/2, cx+LOT_PROBE_DEPTH/2
+	} else {
+		probe.MinY, probe.MaxY = cz-LOT_PROBE_DEPTH/2, cz+LOT_PROBE_DEPTH/2
+	}
+	for _, p := range w.Occupancy.Query(probe) {
+		if p.isRoad() {
+			return true
+		}
+	}
+	return false
+}
+
+// lotCandidates returns the candidate building names, sorted
+func (w *World) lotCandidates(cfg *LotGenConfig) []string {
+	var names []string
+	if len(cfg.Models) > 0 {
+		for _, name := range cfg.Models {
+			if _, known := w.Models[name]; known {
+				names = append(names, name)
+			}
+		}
+	} else {
+		for name, spec := range w.Models {
+			if spec.Category == BUILDING_CATEGORY {
+				names = append(names, name)
+			}
+		}
+	}
+	sort.Strings(names)
+	return names
+}
+
+// lotWeights weighs each candidate for a lot in a zone: zero for uses the
+// zone forbids, and a bias toward homes on unzoned land
+func (w *World) lotWeights(candidates []string, zone ZoneType) []float64 {
+	weights := make([]float64, len(candidates))
+	for i, name := range candidates {
+		spec := w.Models[name]
+		allowed, ruled := w.ZoneRules[spec.Use]
+		switch {
+		case zone == ZoneNone && spec.Use == "housing":
+			weights[i] = LOT_HOUSING_BIAS
+		case zone == ZoneNone || !ruled || allowed.Has(zone):
+			weights[i] = 1
+		}
+	}
+	return weights
+}
+
+// pickWeighted returns a random index by weight and zeroes its weight, or -1
+// once every weight is zero
+func pickWeighted(rng *rand.Rand, weights []float64) int {
+	total := 0.0
+	for _, wt := range weights {
+		total += wt
+	}
+	if total <= 0 {
+		return -1
+	}
+	r := rng.Float64() * total
+	for i, wt := range weights {
+		if r < wt {
+			weights[i] = 0
+			return i
+		}
+		r -= wt
+	}
+	for i := len(weights) - 1; i >= 0; i-- {
+		if weights[i] > 0 {
+			weights[i] = 0
+			return i
+		}
+	}
+	return -1
+}
+
+// PlanLots proposes buildings along the open road frontage, in frontage order
+// With the economy enabled, lots stop once their total cost would exceed the funds
+func (w *World) PlanLots(cfg LotGenConfig) []LotPlan {
+	candidates := w.lotCandidates(&cfg)
+	if len(candidates) == 0 {
+		return nil
+	}
+
+	rng := rand.New(rand.NewSource(cfg.Seed))
+	var plans []LotPlan
+	spent := 0.0
+	held := LOT_PLAN_ID_BASE
+	defer func() {
+		for id := LOT_PLAN_ID_BASE; id < held; id++ {
+			w.Occupancy.Remove(id)
+		}
+	}()
+
+	for _, side := range w.roadFrontage() {
+		if cfg.MaxLots > 0 && len(plans) >= cfg.MaxLots {
+			break
+		}
+		probeX := side.X + side.NX*(LOT_SETBACK+side.SideLength/2)
+		probeZ := side.Z + side.NZ*(LOT_SETBACK+side.SideLength/2)
+		zone := w.Zones.At(probeX, probeZ)
+		rotation := math.Atan2(-side.NX, -side.NZ)
+
+		weights := w.lotWeights(candidates, zone)
+		for i := pickWeighted(rng, weights); i >= 0; i = pickWeighted(rng, weights) {
+			name := candidates[i]
+			spec := w.Models[name]
+			cost := w.PriceOf(name).Cost
+			if w.Economy.Enabled && spent+cost > w.Economy.Funds {
+				continue
+			}
+
+			offset := LOT_SETBACK + spec.Depth/2
+			x, z := side.X+side.NX*offset, side.Z+side.NZ*offset
+			check := w.CanPlace(name, x, z, rotation, emptyCell)
+			if !check.OK() {
+				continue
+			}
+
+			w.Occupancy.Add(Placement{ID: held, Model: name, Category: spec.Category, Footprint: check.Footprint})
+			held++
+			spent += cost
+			plans = append(plans, LotPlan{Model: name, X: x, Z: z, Rotation: rotation, Zone: zone, Cost: cost})
+			break
+		}
+	}
+	return plans
+}
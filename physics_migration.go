@@ -0,0 +1,296 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"strings"
+)
+
+// ============================================================================
+// Snapshot Migrations (upgrading saves from older versions)
+// ============================================================================
+
+// When the snapshot format changes incompatibly, SNAPSHOT_VERSION is bumped
+// and a migration from the old version is appended to snapshotMigrations.
+// Loading an older save (ToJSON or Save output) then runs every migration
+// from its version on, one version at a time, over the snapshot as a
+// generic JSON document: a migration renames fields, fills in subsystems
+// that did not exist yet with their defaults, or rewrites values whose
+// meaning changed, and the upgraded document is decoded as usual. Binary
+// saves are decoded into the current WorldSnapshot first (gob matches
+// fields by name), so a field a migration renames must stay in the struct,
+// deprecated, until saves old enough to hold it are no longer supported.
+// That also means a binary save cannot tell a field it lacks from one it
+// holds at zero; for version 1, whose fields were added without a version
+// bump, the save's format minor version (physics_save.go) tells instead,
+// and the fields it predates are taken out of its document before the
+// migrations run. Saves from a newer version than this build are refused.
+
+// SnapshotMigration upgrades a snapshot document by one version
+type SnapshotMigration struct {
+	From    int    // Version migrated from; the document leaves as From+1
+	Summary string // What changed, for error messages
+	Migrate func(doc SnapshotDocument) error
+}
+
+// snapshotMigrations are the upgrades from every older version, in version order
+var snapshotMigrations = []SnapshotMigration{
+	{
+		From:    1,
+		Summary: "subsystems and settings added while at version 1 start at their defaults",
+		Migrate: func(doc SnapshotDocument) error {
+			defaults, err := defaultSnapshotDocument()
+			if err != nil {
+				return err
+			}
+			districts := defaults["Districts"].(map[string]interface{})
+			delete(districts, "Cells") // Cells of an older save are in no district, whatever its grid
+			for _, key := range []string{"Districts", "Levels", "Utilities", "Citizens", "LotParking", "Transit", "Trams", "Railway", "Emergency", "Missions"} {
+				doc.Default("", key, defaults[key])
+			}
+			doc.Default("", "Sites", builtSites(doc))
+			doc.Default("", "MetersPerUnit", METERS_PER_UNIT)
+
+			delete(doc, "Refund") // Flagged whether the economy had its RefundShare
+			doc.Default("Economy", "RefundShare", DEMOLITION_REFUND)
+			return nil
+		},
+	},
+}
+
+// defaultSnapshotDocument returns the snapshot of a new world as a document,
+// for migrations to take the defaults of whole subsystems from
+func defaultSnapshotDocument() (SnapshotDocument, error) {
+	data, err := json.Marshal(NewWorld().Snapshot())
+	if err != nil {
+		return nil, err
+	}
+	return parseSnapshotDocument(data)
+}
+
+// builtSites returns construction with every placement of a document
+// complete, for saves from before construction took time
+func builtSites(doc SnapshotDocument) map[string]interface{} {
+	progress := make(map[string]interface{})
+	for _, p := range doc.Objects("Placements.*") {
+		if id, ok := p["ID"].(json.Number); ok {
+			progress[id.String()] = 1
+		}
+	}
+	return map[string]interface{}{"CostPerHour": CONSTRUCTION_COST_PER_HOUR, "Progress": progress}
+}
+
+// legacySaveField is a field binary saves of snapshot version 1 gained at a
+// format minor version
+type legacySaveField struct {
+	Minor     int // First minor version holding the field
+	Path, Key string
+}
+
+// legacySaveFields are the fields of version 1 binary saves that the
+// migration from version 1 defaults, by the minor version that added them.
+// Bus transit, incidents and missions came in before the first minor bump,
+// so a 1.0 save may or may not hold them; it is taken not to
+var legacySaveFields = []legacySaveField{
+	{1, "", "MetersPerUnit"},
+	{1, "", "Transit"},
+	{1, "", "Emergency"},
+	{1, "", "Missions"},
+	{2, "", "Utilities"},
+	{3, "", "Trams"},
+	{4, "", "Railway"},
+	{5, "", "Citizens"},
+	{6, "", "Levels"},
+	{7, "", "Sites"},
+	{8, "Economy", "RefundShare"},
+	{9, "", "Districts"},
+	{10, "", "LotParking"},
+}
+
+// SnapshotDocument is a snapshot decoded as generic JSON: objects are
+// map[string]interface{}, arrays []interface{} and numbers json.Number
+type SnapshotDocument map[string]interface{}
+
+// Objects returns the objects at a dotted path of keys, where "*" steps
+// into every element of an array or value of an object, e.g. "Cars.*.AI";
+// the empty path is the document itself
+func (d SnapshotDocument) Objects(path string) []map[string]interface{} {
+	values := []interface{}{map[string]interface{}(d)}
+	if path != "" {
+		for _, key := range strings.Split(path, ".") {
+			var next []interface{}
+			for _, v := range values {
+				switch v := v.(type) {
+				case map[string]interface{}:
+					if key != "*" {
+						if child, ok := v[key]; ok {
+							next = append(next, child)
+						}
+						continue
+					}
+					for _, child := range v {
+						next = append(next, child)
+					}
+				case []interface{}:
+					if key == "*" {
+						next = append(next, v...)
+					}
+				}
+			}
+			values = next
+		}
+	}
+
+	var objects []map[string]interface{}
+	for _, v := range values {
+		if obj, ok := v.(map[string]interface{}); ok {
+			objects = append(objects, obj)
+		}
+	}
+	return objects
+}
+
+// Rename moves a field of every object at a path to a new name, unless the
+// object already has the new one
+func (d SnapshotDocument) Rename(path, from, to string) {
+	for _, obj := range d.Objects(path) {
+		v, ok := obj[from]
+		if !ok {
+			continue
+		}
+		delete(obj, from)
+		if _, exists := obj[to]; !exists {
+			obj[to] = v
+		}
+	}
+}
+
+// Default sets a field of every object at a path that lacks it (or holds
+// null); value may be any JSON-encodable value, such as a subsystem's
+// defaults
+func (d SnapshotDocument) Default(path, key string, value interface{}) {
+	for _, obj := range d.Objects(path) {
+		if v, ok := obj[key]; !ok || v == nil {
+			obj[key] = value
+		}
+	}
+}
+
+// version returns the document's snapshot version
+func (d SnapshotDocument) version() (int, error) {
+	n, ok := d["Version"].(json.Number)
+	if !ok {
+		return 0, errors.New("snapshot has no version")
+	}
+	v, err := n.Int64()
+	if err != nil {
+		return 0, fmt.Errorf("snapshot version %s is not an integer", n)
+	}
+	return int(v), nil
+}
+
+// migrate upgrades the document to SNAPSHOT_VERSION
+func (d SnapshotDocument) migrate() error {
+	version, err := d.version()
+	if err != nil {
+		return err
+	}
+	if version > SNAPSHOT_VERSION {
+		return fmt.Errorf("snapshot version %d is newer than this build reads (%d)", version, SNAPSHOT_VERSION)
+	}
+	for _, m := range snapshotMigrations {
+		if m.From < version {
+			continue
+		}
+		if m.From != version {
+			return fmt.Errorf("no migration from snapshot version %d", version)
+		}
+		if err := m.Migrate(d); err != nil {
+			return fmt.Errorf("migrating snapshot from version %d (%s): %w", m.From, m.Summary, err)
+		}
+		version++
+		d["Version"] = json.Number(fmt.Sprint(version))
+	}
+	if version != SNAPSHOT_VERSION {
+		return fmt.Errorf("no migration from snapshot version %d", version)
+	}
+	return nil
+}
+
+// forgetLegacyFields takes the fields a version 1 binary save of a format
+// minor version predates out of its document
+func (d SnapshotDocument) forgetLegacyFields(minor int) {
+	for _, f := range legacySaveFields {
+		if f.Minor <= minor {
+			continue
+		}
+		for _, obj := range d.Objects(f.Path) {
+			delete(obj, f.Key)
+		}
+	}
+}
+
+// upgrade migrates the document and decodes it as a snapshot
+func (d SnapshotDocument) upgrade() (WorldSnapshot, error) {
+	var s WorldSnapshot
+	if err := d.migrate(); err != nil {
+		return s, err
+	}
+	upgraded, err := json.Marshal(d)
+	if err != nil {
+		return s, err
+	}
+	err = json.Unmarshal(upgraded, &s)
+	return s, err
+}
+
+// parseSnapshotDocument decodes snapshot JSON as a generic document
+func parseSnapshotDocument(data []byte) (SnapshotDocument, error) {
+	// Numbers stay json.Number so 64-bit seeds survive the round trip
+	dec := json.NewDecoder(bytes.NewReader(data))
+	dec.UseNumber()
+	var doc SnapshotDocument
+	err := dec.Decode(&doc)
+	return doc, err
+}
+
+// decodeSnapshotJSON decodes ToJSON output of this or an older version
+func decodeSnapshotJSON(data []byte) (WorldSnapshot, error) {
+	var s WorldSnapshot
+	var header struct{ Version int }
+	if err := json.Unmarshal(data, &header); err != nil {
+		return s, err
+	}
+	if header.Version == SNAPSHOT_VERSION {
+		err := json.Unmarshal(data, &s)
+		return s, err
+	}
+
+	doc, err := parseSnapshotDocument(data)
+	if err != nil {
+		return s, err
+	}
+	return doc.upgrade()
+}
+
+// migrateSnapshot upgrades a snapshot of an older version decoded from a
+// binary save of a format minor version
+func migrateSnapshot(s WorldSnapshot, minor int) (WorldSnapshot, error) {
+	if s.Version == SNAPSHOT_VERSION {
+		return s, nil
+	}
+	data, err := json.Marshal(s)
+	if err != nil {
+		return s, err
+	}
+	doc, err := parseSnapshotDocument(data)
+	if err != nil {
+		return s, err
+	}
+	if s.Version == 1 {
+		doc.forgetLegacyFields(minor)
+	}
+	return doc.upgrade()
+}
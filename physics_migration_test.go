@@ -0,0 +1,189 @@
+package main
+
+import (
+	"encoding/binary"
+	"encoding/json"
+	"fmt"
+	"testing"
+)
+
+// versionOneJSON returns ToJSON output of a world rewritten as a version 1
+// snapshot, with edit making it older still
+func versionOneJSON(t *testing.T, w *World, edit func(doc SnapshotDocument)) []byte {
+	t.Helper()
+	data, err := w.ToJSON()
+	if err != nil {
+		t.Fatal(err)
+	}
+	doc, err := parseSnapshotDocument(data)
+	if err != nil {
+		t.Fatal(err)
+	}
+	doc["Version"] = json.Number("1")
+	edit(doc)
+	data, err = json.Marshal(doc)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return data
+}
+
+// versionOneSave returns a binary save of a world as a version 1 snapshot
+// written by a build of a format minor version
+func versionOneSave(t *testing.T, w *World, minor uint16) []byte {
+	t.Helper()
+	s := w.Snapshot()
+	s.Version = 1
+	data, err := encodeSave(s)
+	if err != nil {
+		t.Fatal(err)
+	}
+	binary.LittleEndian.PutUint16(data[len(SAVE_MAGIC)+2:], minor)
+	return data
+}
+
+func TestMigrateVersionOneJSONDefaultsRefundShare(t *testing.T) {
+	w := NewWorld()
+	w.Economy.RefundShare = 0.2
+	data := versionOneJSON(t, w, func(doc SnapshotDocument) {
+		for _, economy := range doc.Objects("Economy") {
+			delete(economy, "RefundShare")
+		}
+	})
+
+	restored := NewWorld()
+	if err := restored.FromJSON(data); err != nil {
+		t.Fatal(err)
+	}
+	if got := restored.Economy.RefundShare; got != DEMOLITION_REFUND {
+		t.Fatalf("refund share = %v, want the default %v", got, DEMOLITION_REFUND)
+	}
+}
+
+func TestMigrateVersionOneJSONKeepsRefundShare(t *testing.T) {
+	w := NewWorld()
+	w.Economy.RefundShare = 0.2
+	data := versionOneJSON(t, w, func(doc SnapshotDocument) {
+		doc["Refund"] = 0.2
+	})
+
+	restored := NewWorld()
+	if err := restored.FromJSON(data); err != nil {
+		t.Fatal(err)
+	}
+	if got := restored.Economy.RefundShare; got != 0.2 {
+		t.Fatalf("refund share = %v, want the saved 0.2", got)
+	}
+}
+
+func TestMigrateVersionOneBinarySaves(t *testing.T) {
+	for _, tc := range []struct {
+		minor uint16
+		share float64 // Saved
+		want  float64
+	}{
+		{7, 0, DEMOLITION_REFUND}, // Before refund shares
+		{8, 0.2, 0.2},
+		{8, 0, 0}, // Set to refund nothing
+	} {
+		t.Run(fmt.Sprint(tc.minor, "/", tc.share), func(t *testing.T) {
+			w := NewWorld()
+			w.Economy.RefundShare = tc.share
+			restored := NewWorld()
+			if err := restored.Load(versionOneSave(t, w, tc.minor)); err != nil {
+				t.Fatal(err)
+			}
+			if got := restored.Economy.RefundShare; got != tc.want {
+				t.Fatalf("refund share = %v, want %v", got, tc.want)
+			}
+		})
+	}
+}
+
+func TestMigrateRefusesNewerVersions(t *testing.T) {
+	data := versionOneJSON(t, NewWorld(), func(doc SnapshotDocument) {
+		doc["Version"] = json.Number(fmt.Sprint(SNAPSHOT_VERSION + 1))
+	})
+	if err := NewWorld().FromJSON(data); err == nil {
+		t.Fatal("a snapshot from a newer version loaded")
+	}
+}
+
+func TestRestoreKeepsZeroValuedLotParking(t *testing.T) {
+	w := NewWorld()
+	w.LotParking = &LotParking{Enabled: true, Arrivals: 3, Parked: []ParkedCar{{Lot: 5, Citizen: -1, Until: 10}}}
+	data, err := w.ToJSON()
+	if err != nil {
+		t.Fatal(err)
+	}
+	save, err := w.Save()
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	for name, load := range map[string]func(r *World) error{
+		"json":   func(r *World) error { return r.FromJSON(data) },
+		"binary": func(r *World) error { return r.Load(save) },
+	} {
+		restored := NewWorld()
+		if err := load(restored); err != nil {
+			t.Fatalf("%s: %v", name, err)
+		}
+		lp := restored.LotParking
+		if !lp.Enabled || lp.Arrivals != 3 || len(lp.Parked) != 1 || lp.WalkRadius != 0 {
+			t.Fatalf("%s: lot parking = %+v, want it as saved", name, *lp)
+		}
+	}
+}
+
+func TestMigrateVersionOneJSONDefaultsSubsystems(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testHouse}, testPlacement(1, testHouse, 0, 0, 2, 2))
+	w.Sites.Progress[1] = 0.5
+	w.LotParking.WalkRadius = 9
+	data := versionOneJSON(t, w, func(doc SnapshotDocument) {
+		for _, key := range []string{"Sites", "Levels", "Citizens", "Districts", "LotParking"} {
+			delete(doc, key)
+		}
+	})
+
+	restored := NewWorld()
+	if err := restored.FromJSON(data); err != nil {
+		t.Fatal(err)
+	}
+	if restored.underConstruction(restored.Occupancy.Placement(1)) {
+		t.Fatal("building of a save from before construction took time is under construction")
+	}
+	if got := restored.LotParking.WalkRadius; got != PARKING_WALK_RADIUS {
+		t.Fatalf("walk radius = %v, want the default %v", got, PARKING_WALK_RADIUS)
+	}
+	if got := restored.Levels.UpgradeDays; got != LEVEL_UPGRADE_DAYS {
+		t.Fatalf("upgrade days = %v, want the default %v", got, LEVEL_UPGRADE_DAYS)
+	}
+	if got := restored.Citizens.nextAgent; got != CITIZEN_AGENT_ID_BASE {
+		t.Fatalf("next citizen agent = %v, want the default %v", got, CITIZEN_AGENT_ID_BASE)
+	}
+	if d := restored.Districts.Create("new"); d.ID != 1 {
+		t.Fatalf("first district of an older save has id %v, want 1", d.ID)
+	}
+}
+
+func TestMigrateVersionOneBinaryDefaultsLotParking(t *testing.T) {
+	w := NewWorld()
+	w.LotParking.Enabled, w.LotParking.WalkRadius = true, 9
+	for _, tc := range []struct {
+		minor   uint16
+		enabled bool
+		radius  float64
+	}{
+		{9, false, PARKING_WALK_RADIUS}, // Before lot parking
+		{10, true, 9},
+	} {
+		restored := NewWorld()
+		if err := restored.Load(versionOneSave(t, w, tc.minor)); err != nil {
+			t.Fatal(err)
+		}
+		if lp := restored.LotParking; lp.Enabled != tc.enabled || lp.WalkRadius != tc.radius {
+			t.Fatalf("minor %d: lot parking enabled %v radius %v, want %v %v", tc.minor, lp.Enabled, lp.WalkRadius, tc.enabled, tc.radius)
+		}
+	}
+}
@@ -0,0 +1,288 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Delivery Missions (pickups, dropoffs and deadlines)
+// ============================================================================
+
+// A delivery mission sends a car from a business, where it picks up the
+// goods, to another building, where it drops them off, before a deadline.
+// Both ends are chosen at random (from the world seed, physics_determinism.go)
+// and get a trigger zone ZoneRadius around the point of road nearest the
+// building; a car slower than TriggerSpeed inside the zone of its current
+// stage advances the mission. The deadline is the route's travel time at
+// the speed limits, TimeSlack times over plus MISSION_GRACE_SECONDS, in
+// simulation seconds, and the reward grows with the route's length. A
+// delivery pays its reward into the treasury (physics_economy.go) and queues
+// a "mission_completed" event; running out of time queues "mission_failed"
+// (physics_sim_events.go). MissionRoute gives the route from the car to its
+// next zone over the road graph, as a hint for the minimap.
+
+const (
+	MISSION_ZONE_RADIUS     = 3.0  // units around the curb in front of a building
+	MISSION_TRIGGER_SPEED   = 2.0  // units/s, cars must be about stopped in a zone
+	MISSION_TIME_SLACK      = 1.5  // Deadline relative to the travel time at the speed limits
+	MISSION_GRACE_SECONDS   = 20.0 // s added to every deadline
+	MISSION_BASE_REWARD     = 50.0 // Paid for any delivery
+	MISSION_REWARD_PER_UNIT = 0.5  // Paid per unit of route from pickup to dropoff
+	MISSION_PICK_TRIES      = 8    // Attempts at a dropoff apart from the pickup
+	MISSION_SEED            = 5    // Seed for endpoint selection (missions repeat run to run)
+)
+
+// MissionStage is where a mission stands
+type MissionStage uint8
+
+const (
+	MissionPickup  MissionStage = iota // On the way to the pickup
+	MissionDropoff                     // Carrying the goods to the dropoff
+	missionStageCount
+)
+
+// missionStageNames are the JavaScript-facing names, indexed by MissionStage
+var missionStageNames = [missionStageCount]string{"pickup", "dropoff"}
+
+// String returns the JavaScript-facing stage name
+func (s MissionStage) String() string {
+	if s >= missionStageCount {
+		return missionStageNames[MissionPickup]
+	}
+	return missionStageNames[s]
+}
+
+// MissionZone is a trigger zone in front of a building
+type MissionZone struct {
+	PlacementID int
+	X, Z        float64 // Center, on the road nearest the building
+}
+
+// Mission is one delivery being driven
+type Mission struct {
+	ID       int
+	CarID    int
+	Stage    MissionStage
+	Pickup   MissionZone
+	Dropoff  MissionZone
+	Deadline float64 // Simulation time the goods must be delivered by
+	Reward   float64
+}
+
+// Missions generates deliveries and tracks them
+type Missions struct {
+	ZoneRadius    float64
+	TriggerSpeed  float64
+	TimeSlack     float64
+	BaseReward    float64
+	RewardPerUnit float64
+
+	Completed int
+	Failed    int
+	Earned    float64 // Rewards of the deliveries made
+
+	active []*Mission // In generation order
+	nextID int
+	rng    *SimRand
+}
+
+// NewMissions creates a mission generator with no missions
+func NewMissions() *Missions {
+	return &Missions{
+		ZoneRadius:    MISSION_ZONE_RADIUS,
+		TriggerSpeed:  MISSION_TRIGGER_SPEED,
+		TimeSlack:     MISSION_TIME_SLACK,
+		BaseReward:    MISSION_BASE_REWARD,
+		RewardPerUnit: MISSION_REWARD_PER_UNIT,
+		nextID:        1,
+		rng:           newSimRand(MISSION_SEED),
+	}
+}
+
+// Active returns the missions being driven, in generation order
+func (m *Missions) Active() []*Mission {
+	return m.active
+}
+
+// Mission returns an active mission, or nil
+func (m *Missions) Mission(id int) *Mission {
+	for _, mission := range m.active {
+		if mission.ID == id {
+			return mission
+		}
+	}
+	return nil
+}
+
+// curbPoint returns the point of road nearest a position, and false
+// without roads
+func (w *World) curbPoint(x, z float64) (float64, float64, bool) {
+	cx, cz, found := 0.0, 0.0, false
+	best := math.Inf(1)
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		px, pz := closestPointOnSegment(x, z, a.X, a.Z, b.X, b.Z)
+		if d := simHypot(px-x, pz-z); d < best {
+			best, cx, cz, found = d, px, pz, true
+		}
+	}
+	return cx, cz, found
+}
+
+// missionZone returns the trigger zone in front of a placement
+func (w *World) missionZone(p *Placement) (MissionZone, bool) {
+	x, z, ok := w.curbPoint(p.Center())
+	return MissionZone{PlacementID: p.ID, X: x, Z: z}, ok
+}
+
+// routeBetween returns the road route from the node nearest one point to
+// the node nearest another, empty when they share a node
+func (w *World) routeBetween(ax, az, bx, bz float64) (Route, bool) {
+	from, to := w.Roads.NearestNode(ax, az), w.Roads.NearestNode(bx, bz)
+	switch {
+	case from == nil || to == nil:
+		return Route{}, false
+	case from.ID == to.ID:
+		return Route{Nodes: []int{from.ID}}, true
+	}
+	return w.driveRoute(from.ID, to.ID, nil)
+}
+
+// GenerateMission gives a car a new delivery from a random business to
+// another random building, returning the mission ID
+func (w *World) GenerateMission(carID int) (int, error) {
+	m := w.Missions
+	car := w.Car(carID)
+	if car == nil {
+		return 0, errors.New("no car to give the mission to")
+	}
+
+	businesses := func(p *Placement) int { return w.capacity(p).Jobs }
+	buildings := func(p *Placement) int { return w.capacity(p).Jobs + w.capacity(p).Residents }
+	pickup := w.pickPlacement(m.rng, businesses)
+	if pickup == nil {
+		return 0, errors.New("no business to pick up from")
+	}
+	var dropoff *Placement
+	for try := 0; try < MISSION_PICK_TRIES && (dropoff == nil || dropoff == pickup); try++ {
+		dropoff = w.pickPlacement(m.rng, buildings)
+	}
+	if dropoff == nil || dropoff == pickup {
+		return 0, errors.New("no building to deliver to")
+	}
+
+	from, okFrom := w.missionZone(pickup)
+	to, okTo := w.missionZone(dropoff)
+	if !okFrom || !okTo {
+		return 0, errors.New("missions need roads")
+	}
+	toPickup, okPickup := w.routeBetween(car.State.X, car.State.Z, from.X, from.Z)
+	delivery, okDelivery := w.routeBetween(from.X, from.Z, to.X, to.Z)
+	if !okPickup || !okDelivery {
+		return 0, errors.New("no route between the pickup and the dropoff")
+	}
+
+	travel := toPickup.TravelTime + delivery.TravelTime
+	mission := &Mission{
+		ID:       m.nextID,
+		CarID:    carID,
+		Stage:    MissionPickup,
+		Pickup:   from,
+		Dropoff:  to,
+		Deadline: w.Time + travel*positiveOr(m.TimeSlack, MISSION_TIME_SLACK) + MISSION_GRACE_SECONDS,
+		Reward:   m.BaseReward + m.RewardPerUnit*delivery.Length,
+	}
+	m.nextID++
+	m.active = append(m.active, mission)
+	return mission.ID, nil
+}
+
+// CancelMission abandons a mission without payout or failure, returning
+// false if it is not active
+func (w *World) CancelMission(id int) bool {
+	m := w.Missions
+	for i, mission := range m.active {
+		if mission.ID == id {
+			m.active = append(m.active[:i], m.active[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// ClearMissions abandons every mission and resets the counters
+func (w *World) ClearMissions() {
+	m := w.Missions
+	m.active = nil
+	m.Completed, m.Failed, m.Earned = 0, 0, 0
+}
+
+// MissionRoute returns the road route from a mission's car to the zone of
+// its current stage
+func (w *World) MissionRoute(id int) (Route, bool) {
+	mission := w.Missions.Mission(id)
+	if mission == nil {
+		return Route{}, false
+	}
+	car := w.Car(mission.CarID)
+	if car == nil {
+		return Route{}, false
+	}
+	zone := mission.target()
+	return w.routeBetween(car.State.X, car.State.Z, zone.X, zone.Z)
+}
+
+// target returns the zone the mission's car must reach next
+func (mission *Mission) target() *MissionZone {
+	if mission.Stage == MissionPickup {
+		return &mission.Pickup
+	}
+	return &mission.Dropoff
+}
+
+// PayMission pays a delivery's reward into the treasury; nothing is paid
+// while the economy is disabled
+func (w *World) PayMission(reward float64) {
+	e := w.Economy
+	if !e.Enabled {
+		return
+	}
+	e.Funds += reward
+	e.Today.Missions += reward
+}
+
+// stepMissions advances the missions whose cars stopped in their zone and
+// fails the ones out of time
+func (w *World) stepMissions() {
+	m := w.Missions
+	kept := m.active[:0]
+	for _, mission := range m.active {
+		car := w.Car(mission.CarID)
+		if car == nil {
+			continue // The car is gone; so is the mission
+		}
+		zone := mission.target()
+		s := &car.State
+		inZone := simHypot(s.X-zone.X, s.Z-zone.Z) <= m.ZoneRadius &&
+			simHypot(s.VelocityX, s.VelocityZ) <= m.TriggerSpeed
+
+		switch {
+		case w.Time > mission.Deadline:
+			m.Failed++
+			w.pushSimEvent(SimEvent{Kind: SimMissionFailed, ID: mission.CarID, To: mission.ID, X: zone.X, Z: zone.Z})
+			continue
+		case inZone && mission.Stage == MissionPickup:
+			mission.Stage = MissionDropoff
+		case inZone:
+			m.Completed++
+			m.Earned += mission.Reward
+			w.PayMission(mission.Reward)
+			w.pushSimEvent(SimEvent{Kind: SimMissionCompleted, ID: mission.CarID, To: mission.ID, Value: mission.Reward, X: zone.X, Z: zone.Z})
+			continue
+		}
+		kept = append(kept, mission)
+	}
+	m.active = kept
+}
@@ -0,0 +1,151 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Noise Map (traffic and industrial noise per cell)
+// ============================================================================
+
+// Moving cars deposit noise into the cells around them in proportion to their
+// speed, and the deposit decays exponentially, so busy roads stay loud while
+// a single passing car fades within seconds. Industry adds a steady level
+// around its buildings. Levels are 0-1 on the occupancy layout and feed the
+// livability score and land value.
+
+const (
+	NOISE_PER_SPEED       = 0.015 // Level per second per unit/s of car speed
+	NOISE_DECAY_TIME      = 6.0   // s, time constant of traffic noise fading
+	NOISE_SPREAD          = 1     // Cells around a car that hear it (full weight at its own cell)
+	INDUSTRY_NOISE        = 0.6   // Level right next to industry
+	INDUSTRY_NOISE_RADIUS = 4.0   // units
+)
+
+// NoiseMap keeps the decaying traffic noise per cell and the steady
+// industrial noise derived from the occupancy grid
+type NoiseMap struct {
+	GridSpec
+	traffic  []float64
+	industry []float64 // Rebuilt when the occupancy revision changes
+	revision int
+}
+
+// NewNoiseMap creates a silent map
+func NewNoiseMap(spec GridSpec) *NoiseMap {
+	return &NoiseMap{GridSpec: spec, traffic: make([]float64, spec.Len()), revision: -1}
+}
+
+// Resize changes the cell layout, resampling traffic noise at the new cell centers
+func (m *NoiseMap) Resize(spec GridSpec) {
+	traffic := make([]float64, spec.Len())
+	for i := range traffic {
+		if j := m.CellIndex(spec.CellCenter(i)); j >= 0 {
+			traffic[i] = m.traffic[j]
+		}
+	}
+	m.GridSpec = spec
+	m.traffic = traffic
+	m.industry = nil
+	m.revision = -1
+}
+
+// Clear silences the traffic noise
+func (m *NoiseMap) Clear() {
+	for i := range m.traffic {
+		m.traffic[i] = 0
+	}
+}
+
+// Level returns the noise of a cell, 0-1
+func (m *NoiseMap) Level(i int) float64 {
+	level := m.traffic[i]
+	if m.industry != nil {
+		level += m.industry[i]
+	}
+	return math.Min(level, 1)
+}
+
+// At returns the noise at a world position (0 outside the grid)
+func (m *NoiseMap) At(x, z float64) float64 {
+	i := m.CellIndex(x, z)
+	if i < 0 {
+		return 0
+	}
+	return m.Level(i)
+}
+
+// Levels returns the noise of every cell
+func (m *NoiseMap) Levels() []float64 {
+	levels := make([]float64, m.Len())
+	for i := range levels {
+		levels[i] = m.Level(i)
+	}
+	return levels
+}
+
+// deposit adds traffic noise around a world position
+func (m *NoiseMap) deposit(x, z, amount float64) {
+	if m.CellIndex(x, z) < 0 {
+		return
+	}
+	cx := int(math.Floor((x - m.OriginX) / m.CellSize))
+	cz := int(math.Floor((z - m.OriginZ) / m.CellSize))
+	for dz := -NOISE_SPREAD; dz <= NOISE_SPREAD; dz++ {
+		for dx := -NOISE_SPREAD; dx <= NOISE_SPREAD; dx++ {
+			nx, nz := cx+dx, cz+dz
+			if nx < 0 || nx >= m.Width || nz < 0 || nz >= m.Depth {
+				continue
+			}
+			falloff := 1 / float64(1+max(abs(dx), abs(dz)))
+			i := nz*m.Width + nx
+			m.traffic[i] = math.Min(m.traffic[i]+amount*falloff, 1)
+		}
+	}
+}
+
+// abs returns the absolute value of an int
+func abs(n int) int {
+	if n < 0 {
+		return -n
+	}
+	return n
+}
+
+// refreshIndustry rebuilds the industrial noise after placements change
+func (w *World) refreshIndustry() {
+	m := w.Noise
+	if m.industry != nil && m.revision == w.Occupancy.Revision() {
+		return
+	}
+	field, _ := w.proximityField(INDUSTRY_NOISE_RADIUS, func(p *Placement) bool {
+		return w.placementUse(p) == "industry"
+	})
+	for i := range field {
+		field[i] *= INDUSTRY_NOISE
+	}
+	m.industry = field
+	m.revision = w.Occupancy.Revision()
+}
+
+// NoiseLevels returns the current noise of every occupancy cell
+func (w *World) NoiseLevels() []float64 {
+	w.refreshIndustry()
+	return w.Noise.Levels()
+}
+
+// stepNoise decays traffic noise and deposits the noise of every moving car
+func (w *World) stepNoise(dt float64) {
+	m := w.Noise
+	w.refreshIndustry()
+
+	decay := simExp(-dt / NOISE_DECAY_TIME)
+	for i := range m.traffic {
+		m.traffic[i] *= decay
+	}
+	for _, car := range w.cars {
+		s := &car.State
+		speed := simHypot(s.VelocityX, s.VelocityZ)
+		if speed > STOP_SPEED {
+			m.deposit(s.X, s.Z, NOISE_PER_SPEED*speed*dt)
+		}
+	}
+}
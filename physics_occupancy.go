@@ -0,0 +1,239 @@
+package main
+
+import "sort"
+
+// ============================================================================
+// Occupancy Grid (placed buildings and their footprints)
+// ============================================================================
+
+// The occupancy grid is the single record of what is placed where. Every
+// placed object registers its footprint, model and category; each cell keeps
+// the ID of the placement covering it. Collisions (via the collider a
+// placement registers), placement validation and town scoring read from it.
+
+const (
+	OCCUPANCY_CELL_SIZE  = 0.5 // units
+	OCCUPANCY_GRID_CELLS = 40  // Cells per side of the default grid (matches the 20-unit ground)
+	emptyCell            = -1
+)
+
+// Placement is one placed object
+type Placement struct {
+	ID        int
+	Model     string      // Model file name, e.g. "building_A.gltf"
+	Category  string      // Model category, e.g. "buildings", "roads"
+	Footprint BoundingBox // World-space X/Z extents (MinY/MaxY hold world Z)
+	Rotation  float64     // Radians about the Y axis (the footprint is already rotated)
+}
+
+// Center returns the middle of the placement's footprint
+func (p *Placement) Center() (x, z float64) {
+	return (p.Footprint.MinX + p.Footprint.MaxX) / 2, (p.Footprint.MinY + p.Footprint.MaxY) / 2
+}
+
+// OccupancyGrid maps cells to the placements covering them
+// Placements outside the grid are still recorded, they just own no cells.
+// Where footprints overlap, the later registration owns the shared cells
+type OccupancyGrid struct {
+	GridSpec
+	cells      []int // Placement ID per cell, emptyCell if free
+	placements map[int]*Placement
+	byModel    map[string]map[int]bool // Model -> IDs of its placements
+	revision   int                     // Bumped on every change, so derived maps know to rebuild
+}
+
+// NewOccupancyGrid creates an empty occupancy grid
+func NewOccupancyGrid(spec GridSpec) *OccupancyGrid {
+	g := &OccupancyGrid{GridSpec: spec, placements: make(map[int]*Placement), byModel: make(map[string]map[int]bool)}
+	g.cells = make([]int, spec.Len())
+	g.fill(emptyCell)
+	return g
+}
+
+// fill sets every cell to id
+func (g *OccupancyGrid) fill(id int) {
+	for i := range g.cells {
+		g.cells[i] = id
+	}
+}
+
+// paint assigns every cell under a footprint to id
+func (g *OccupancyGrid) paint(bbox BoundingBox, id int) {
+	minX, minZ, maxX, maxZ, ok := g.CellRange(bbox)
+	if !ok {
+		return
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			g.cells[cz*g.Width+cx] = id
+		}
+	}
+}
+
+// Add registers (or replaces) a placement
+// Returns false for footprints with non-finite or inverted bounds
+func (g *OccupancyGrid) Add(p Placement) bool {
+	b := p.Footprint
+	if !isFinite(b.MinX) || !isFinite(b.MinY) || !isFinite(b.MaxX) || !isFinite(b.MaxY) || b.MinX > b.MaxX || b.MinY > b.MaxY {
+		return false
+	}
+
+	g.Remove(p.ID)
+	g.placements[p.ID] = &p
+	if g.byModel[p.Model] == nil {
+		g.byModel[p.Model] = make(map[int]bool)
+	}
+	g.byModel[p.Model][p.ID] = true
+	g.paint(p.Footprint, p.ID)
+	g.revision++
+	return true
+}
+
+// Remove unregisters a placement, returning false if it does not exist
+// Freed cells go back to any other placement whose footprint covers them
+func (g *OccupancyGrid) Remove(id int) bool {
+	p, exists := g.placements[id]
+	if !exists {
+		return false
+	}
+	delete(g.placements, id)
+	delete(g.byModel[p.Model], id)
+	g.revision++
+
+	minX, minZ, maxX, maxZ, ok := g.CellRange(p.Footprint)
+	if !ok {
+		return true
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			if g.cells[cz*g.Width+cx] == id {
+				g.cells[cz*g.Width+cx] = emptyCell
+			}
+		}
+	}
+	for _, other := range g.Query(p.Footprint) {
+		g.paint(other.Footprint, other.ID)
+	}
+	return true
+}
+
+// Clear removes every placement
+func (g *OccupancyGrid) Clear() {
+	g.placements = make(map[int]*Placement)
+	g.byModel = make(map[string]map[int]bool)
+	g.fill(emptyCell)
+	g.revision++
+}
+
+// Resize changes the cell layout, re-registering every placement
+func (g *OccupancyGrid) Resize(spec GridSpec) {
+	g.GridSpec = spec
+	g.cells = make([]int, spec.Len())
+	g.fill(emptyCell)
+	g.revision++
+	for _, id := range g.IDs() {
+		p := g.placements[id]
+		g.paint(p.Footprint, p.ID)
+	}
+}
+
+// Count returns the number of placements
+func (g *OccupancyGrid) Count() int {
+	return len(g.placements)
+}
+
+// Placement returns the placement with the given ID, or nil
+func (g *OccupancyGrid) Placement(id int) *Placement {
+	return g.placements[id]
+}
+
+// IDs returns every placement ID in ascending order
+func (g *OccupancyGrid) IDs() []int {
+	ids := make([]int, 0, len(g.placements))
+	for id := range g.placements {
+		ids = append(ids, id)
+	}
+	sort.Ints(ids)
+	return ids
+}
+
+// ModelIDs returns the IDs of every placement of a model in ascending order
+func (g *OccupancyGrid) ModelIDs(model string) []int {
+	ids := make([]int, 0, len(g.byModel[model]))
+	for id := range g.byModel[model] {
+		ids = append(ids, id)
+	}
+	sort.Ints(ids)
+	return ids
+}
+
+// Revision returns a counter that changes whenever a placement or the layout changes
+func (g *OccupancyGrid) Revision() int {
+	return g.revision
+}
+
+// CellOwner returns the placement ID owning a cell, or emptyCell
+func (g *OccupancyGrid) CellOwner(i int) int {
+	return g.cells[i]
+}
+
+// At returns the placement covering a world position, or nil
+func (g *OccupancyGrid) At(x, z float64) *Placement {
+	i := g.CellIndex(x, z)
+	if i < 0 {
+		return nil
+	}
+	return g.placements[g.cells[i]]
+}
+
+// Query returns the placements whose footprints overlap a bounding box, by ID
+// Footprints only touching the box edge do not count
+func (g *OccupancyGrid) Query(bbox BoundingBox) []*Placement {
+	var result []*Placement
+	for _, id := range g.IDs() {
+		f := g.placements[id].Footprint
+		if f.MinX < bbox.MaxX && bbox.MinX < f.MaxX && f.MinY < bbox.MaxY && bbox.MinY < f.MaxY {
+			result = append(result, g.placements[id])
+		}
+	}
+	return result
+}
+
+// ByCategory returns every placement of a category, by ID
+func (g *OccupancyGrid) ByCategory(category string) []*Placement {
+	var result []*Placement
+	for _, id := range g.IDs() {
+		if p := g.placements[id]; p.Category == category {
+			result = append(result, p)
+		}
+	}
+	return result
+}
+
+// AddPlacement registers a placed object with the occupancy grid and, when
+// collider is not nil, as a static collider with the same ID
+func (w *World) AddPlacement(p Placement, collider *Collider) bool {
+	if !w.Occupancy.Add(p) {
+		return false
+	}
+	w.Colliders.Remove(p.ID)
+	if collider != nil {
+		collider.ID = p.ID
+		w.Colliders.Add(*collider)
+	}
+	return true
+}
+
+// RemovePlacement unregisters a placed object and its collider
+func (w *World) RemovePlacement(id int) bool {
+	w.Colliders.Remove(id)
+	return w.Occupancy.Remove(id)
+}
+
+// ClearPlacements removes every placed object and static collider, and the
+// edit history, whose edits assumed them
+func (w *World) ClearPlacements() {
+	w.Occupancy.Clear()
+	w.Colliders.Clear()
+	w.History.Clear()
+}
@@ -0,0 +1,162 @@
+package main
+
+import (
+	"math"
+	"sort"
+)
+
+// ============================================================================
+// Parking Spots (parking challenges)
+// ============================================================================
+
+// JS marks out parking spots, rectangles Length long along Heading and
+// Width across. A car is parked in a spot once its footprint (its wheels
+// plus PARKING_OVERHANG all round) lies wholly inside it, it faces along
+// Heading (either way round for BothWays spots) within AngleTolerance and
+// it has stopped. Parking queues a "parked" simulation event
+// (physics_sim_events.go) with a precision score, 1 for dead center and
+// square, falling to 0 at the edge of the spot or the angle tolerance; the
+// spot then stays taken by that car until it drives out.
+
+const (
+	PARKING_SPOT_LENGTH     = 3.0  // units, default spot length
+	PARKING_SPOT_WIDTH      = 2.0  // units, default spot width
+	PARKING_ANGLE_TOLERANCE = 0.15 // rad (~9°), default misalignment allowed
+	PARKING_OVERHANG        = 0.3  // units the body reaches past the wheels
+	PARKING_STOP_SPEED      = 0.1  // units/s, slower cars count as stopped
+)
+
+// ParkingSpot is an oriented rectangle to park in
+type ParkingSpot struct {
+	ID             int
+	X, Z           float64 // Center
+	Heading        float64 // rad, the direction a parked car faces
+	Length         float64 // units along Heading
+	Width          float64 // units across Heading
+	AngleTolerance float64 // rad
+	BothWays       bool    // Cars may face either way along Heading
+
+	Occupied bool    // A car is parked in the spot
+	Occupant int     // ID of the car parked in it
+	Score    float64 // 0..1, how precisely it parked
+}
+
+// sanitized returns the spot with non-positive sizes defaulted
+func (s ParkingSpot) sanitized() ParkingSpot {
+	s.Length = positiveOr(s.Length, PARKING_SPOT_LENGTH)
+	s.Width = positiveOr(s.Width, PARKING_SPOT_WIDTH)
+	s.AngleTolerance = positiveOr(s.AngleTolerance, PARKING_ANGLE_TOLERANCE)
+	return s
+}
+
+// angleError returns how far a heading is from lining up with the spot
+func (s *ParkingSpot) angleError(heading float64) float64 {
+	off := math.Abs(normalizeAngle(heading - s.Heading))
+	if s.BothWays {
+		off = math.Min(off, math.Pi-off)
+	}
+	return off
+}
+
+// fit returns how precisely a car lies in the spot (0..1), and false if
+// its footprint is not wholly inside or it is not lined up
+func (s *ParkingSpot) fit(car *CarState, cfg *VehicleConfig) (float64, bool) {
+	angle := s.angleError(car.RotationY)
+	if angle > s.AngleTolerance {
+		return 0, false
+	}
+
+	// Car center and corners in spot coordinates: along Heading and to its left
+	dx, dz := math.Sin(s.Heading), math.Cos(s.Heading)
+	relX, relZ := car.X-s.X, car.Z-s.Z
+	along, across := relX*dx+relZ*dz, relX*dz-relZ*dx
+	halfLength := cfg.Wheelbase/2 + PARKING_OVERHANG
+	halfWidth := cfg.TrackWidth/2 + PARKING_OVERHANG
+	turn := car.RotationY - s.Heading
+	sin, cos := math.Abs(math.Sin(turn)), math.Abs(math.Cos(turn))
+	reachAlong := halfLength*cos + halfWidth*sin // Half-extents of the rotated footprint
+	reachAcross := halfLength*sin + halfWidth*cos
+	slackAlong, slackAcross := s.Length/2-reachAlong, s.Width/2-reachAcross
+	if math.Abs(along) > slackAlong || math.Abs(across) > slackAcross {
+		return 0, false
+	}
+
+	// Each of the along, across and angle errors costs a third of the score
+	offAlong := math.Abs(along) / math.Max(slackAlong, 1e-9)
+	offAcross := math.Abs(across) / math.Max(slackAcross, 1e-9)
+	return clamp(1-(offAlong+offAcross+angle/s.AngleTolerance)/3, 0, 1), true
+}
+
+// ParkingSpots holds the parking spots
+type ParkingSpots struct {
+	spots []*ParkingSpot // In ID order
+}
+
+// NewParkingSpots creates an empty set of parking spots
+func NewParkingSpots() *ParkingSpots {
+	return &ParkingSpots{}
+}
+
+// Spots returns the parking spots in ID order
+func (p *ParkingSpots) Spots() []*ParkingSpot {
+	return p.spots
+}
+
+// Set adds a spot, or replaces the spot with the same ID
+func (p *ParkingSpots) Set(s ParkingSpot) {
+	s = s.sanitized()
+	i := sort.Search(len(p.spots), func(i int) bool { return p.spots[i].ID >= s.ID })
+	if i < len(p.spots) && p.spots[i].ID == s.ID {
+		*p.spots[i] = s
+		return
+	}
+	p.spots = append(p.spots, nil)
+	copy(p.spots[i+1:], p.spots[i:])
+	p.spots[i] = &s
+}
+
+// Remove removes a spot, returning false if it does not exist
+func (p *ParkingSpots) Remove(id int) bool {
+	for i, s := range p.spots {
+		if s.ID == id {
+			p.spots = append(p.spots[:i], p.spots[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// Clear removes every spot
+func (p *ParkingSpots) Clear() {
+	p.spots = nil
+}
+
+// stepParking frees the spots whose car drove out and parks every stopped
+// car lying in a free spot
+func (w *World) stepParking() {
+	for _, spot := range w.Parking.spots {
+		if spot.Occupied {
+			car := w.Car(spot.Occupant)
+			if car != nil {
+				if _, inside := spot.fit(&car.State, &car.Config); inside {
+					continue
+				}
+			}
+			spot.Occupied, spot.Score = false, 0
+		}
+
+		for _, car := range w.cars {
+			s := &car.State
+			if simHypot(s.VelocityX, s.VelocityZ) > PARKING_STOP_SPEED {
+				continue
+			}
+			score, inside := spot.fit(s, &car.Config)
+			if !inside {
+				continue
+			}
+			spot.Occupied, spot.Occupant, spot.Score = true, s.ID, score
+			w.pushSimEvent(SimEvent{Kind: SimCarParked, ID: s.ID, To: spot.ID, Value: score, X: spot.X, Z: spot.Z})
+			break
+		}
+	}
+}
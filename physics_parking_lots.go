@@ -0,0 +1,296 @@
+package main
+
+import (
+	"math"
+	"sort"
+)
+
+// ============================================================================
+// Lot Parking (parking demand of businesses and the lots that meet it)
+// ============================================================================
+
+// Businesses want parking spaces in proportion to their jobs, and parking
+// lots, placed like any other building, provide them to the businesses
+// within walking distance. Demand left unmet lowers commercial happiness,
+// and cars arriving at a business with every lot in reach full are turned
+// away. Parking challenge spots are separate (physics_parking.go).
+
+const (
+	PARKING_WALK_RADIUS    = 4.0 // units a lot may be from a business it serves
+	PARKING_DWELL_HOURS    = 1.0 // Game hours a trip car stays parked
+	PARKING_HAPPINESS_LOSS = 0.8 // Commercial happiness lost when no demand is met
+	PARKING_LOT_USE        = "parking_lot"
+)
+
+// DefaultParkingSpaces returns the spaces of each parking model use
+func DefaultParkingSpaces() map[string]int {
+	return map[string]int{PARKING_LOT_USE: 12}
+}
+
+// DefaultParkingDemand returns the spaces each model use wants per job
+func DefaultParkingDemand() map[string]float64 {
+	return map[string]float64{"market": 1.5, "office": 0.5}
+}
+
+// ParkedCar is a car holding a space in a lot
+type ParkedCar struct {
+	Lot     int     // Placement ID of the lot
+	Citizen int     // Citizen whose car it is, -1 for trip cars
+	Until   float64 // World time (s) a trip car leaves
+}
+
+// LotParking tracks parking demand, lots and the cars parked in them
+type LotParking struct {
+	Enabled      bool
+	WalkRadius   float64            // units
+	DwellHours   float64            // Game hours a trip car stays parked
+	Spaces       map[string]int     // Model use -> spaces in a lot
+	DemandPerJob map[string]float64 // Model use -> spaces wanted per job
+
+	Parked     []ParkedCar // In arrival order
+	Arrivals   int         // Cars that parked
+	TurnedAway int         // Cars that found every lot in reach full
+
+	occupied map[int]int // Lot ID -> cars in Parked, nil until counted
+}
+
+// NewLotParking creates disabled parking with the default spaces and
+// demand, so towns without lots keep their commercial demand
+func NewLotParking() *LotParking {
+	return &LotParking{
+		WalkRadius:   PARKING_WALK_RADIUS,
+		DwellHours:   PARKING_DWELL_HOURS,
+		Spaces:       DefaultParkingSpaces(),
+		DemandPerJob: DefaultParkingDemand(),
+	}
+}
+
+// occupancy returns the cars parked in each lot, counting Parked the first
+// time after a load
+func (lp *LotParking) occupancy() map[int]int {
+	if lp.occupied == nil {
+		lp.occupied = make(map[int]int)
+		for _, c := range lp.Parked {
+			lp.occupied[c.Lot]++
+		}
+	}
+	return lp.occupied
+}
+
+// unpark drops a car from the count of its lot
+func (lp *LotParking) unpark(c ParkedCar) {
+	occupied := lp.occupancy()
+	if occupied[c.Lot]--; occupied[c.Lot] <= 0 {
+		delete(occupied, c.Lot)
+	}
+}
+
+// ParkingLot is one lot's spaces, the demand allotted to it and the cars in it
+type ParkingLot struct {
+	ID       int
+	X, Z     float64 // Middle of its footprint
+	Spaces   int
+	Demand   float64 // Spaces businesses in reach count on
+	Occupied int     // Cars parked now
+}
+
+// Occupancy returns the share of spaces taken, 0-1
+func (l ParkingLot) Occupancy() float64 {
+	if l.Spaces == 0 {
+		return 0
+	}
+	return float64(l.Occupied) / float64(l.Spaces)
+}
+
+// ParkingDemand is one business's parking demand and how much of it is met
+type ParkingDemand struct {
+	ID     int
+	Demand float64 // Spaces wanted
+	Unmet  float64 // Spaces no lot in reach can offer
+}
+
+// ParkingReport is the town's parking as it stands
+type ParkingReport struct {
+	Lots                []ParkingLot    // In placement order
+	Businesses          []ParkingDemand // In placement order
+	Demand, Unmet       float64         // Spaces over every business
+	CommercialHappiness float64         // 0-1, 1 with all demand met
+}
+
+// lotSpaces returns the spaces a placement provides, 0 for anything but a
+// complete parking lot
+func (w *World) lotSpaces(p *Placement) int {
+	if p.ID >= LOT_PLAN_ID_BASE || w.underConstruction(p) {
+		return 0
+	}
+	return max(w.LotParking.Spaces[w.placementUse(p)], 0)
+}
+
+// parkingDemandOf returns the spaces a business wants
+func (w *World) parkingDemandOf(p *Placement) float64 {
+	per, ok := w.LotParking.DemandPerJob[w.placementUse(p)]
+	if !ok || p.ID >= LOT_PLAN_ID_BASE {
+		return 0
+	}
+	return math.Max(per, 0) * float64(w.capacity(p).Jobs)
+}
+
+// lotsInReach returns the indices of the lots within the walk radius of a
+// point, nearest first
+func (w *World) lotsInReach(lots []ParkingLot, x, z float64) []int {
+	radius := positiveOr(w.LotParking.WalkRadius, PARKING_WALK_RADIUS)
+	var near []int
+	dist := make([]float64, len(lots))
+	for i, l := range lots {
+		if d := simHypot(l.X-x, l.Z-z); d <= radius {
+			near = append(near, i)
+			dist[i] = d
+		}
+	}
+	sort.SliceStable(near, func(a, b int) bool { return dist[near[a]] < dist[near[b]] })
+	return near
+}
+
+// LotParkingReport allots every business's demand to the lots in reach,
+// nearest first, one business after another in placement order until the
+// spaces are spoken for, and counts the cars parked in each lot
+func (w *World) LotParkingReport() ParkingReport {
+	r := ParkingReport{CommercialHappiness: 1}
+	ids := w.Occupancy.IDs()
+	index := make(map[int]int)
+	for _, id := range ids {
+		p := w.Occupancy.Placement(id)
+		if spaces := w.lotSpaces(p); spaces > 0 {
+			x, z := p.Center()
+			index[id] = len(r.Lots)
+			r.Lots = append(r.Lots, ParkingLot{ID: id, X: x, Z: z, Spaces: spaces})
+		}
+	}
+	for id, n := range w.LotParking.occupancy() {
+		if i, ok := index[id]; ok {
+			r.Lots[i].Occupied = n
+		}
+	}
+
+	for _, id := range ids {
+		p := w.Occupancy.Placement(id)
+		demand := w.parkingDemandOf(p)
+		if demand <= 0 {
+			continue
+		}
+		unmet := demand
+		x, z := p.Center()
+		for _, i := range w.lotsInReach(r.Lots, x, z) {
+			l := &r.Lots[i]
+			if take := math.Min(unmet, float64(l.Spaces)-l.Demand); take > 0 {
+				l.Demand += take
+				unmet -= take
+			}
+		}
+		r.Businesses = append(r.Businesses, ParkingDemand{ID: id, Demand: demand, Unmet: unmet})
+		r.Demand += demand
+		r.Unmet += unmet
+	}
+	if r.Demand > 0 {
+		r.CommercialHappiness = 1 - PARKING_HAPPINESS_LOSS*r.Unmet/r.Demand
+	}
+	return r
+}
+
+// commercialHappiness returns how well the town's businesses are parked
+// for, 1 while parking is disabled; it scales the commercial demand of the
+// census (physics_population.go)
+func (w *World) commercialHappiness() float64 {
+	if !w.LotParking.Enabled {
+		return 1
+	}
+	return w.LotParkingReport().CommercialHappiness
+}
+
+// parkArrival parks a car that has arrived at a placement in the nearest
+// lot in reach with a space free; only businesses draw parked cars. Trip
+// cars (physics_trips.go) stay DwellHours, citizens' cars
+// (physics_citizens.go) until they set off again
+func (w *World) parkArrival(placementID, citizenID int) {
+	lp := w.LotParking
+	p := w.Occupancy.Placement(placementID)
+	if !lp.Enabled || p == nil || w.parkingDemandOf(p) <= 0 {
+		return
+	}
+	x, z := p.Center()
+	lots := w.nearbyLots(x, z)
+	occupied := lp.occupancy()
+	for _, i := range w.lotsInReach(lots, x, z) {
+		l := lots[i]
+		if occupied[l.ID] >= l.Spaces {
+			continue
+		}
+		until := w.Time + positiveOr(lp.DwellHours, PARKING_DWELL_HOURS)*positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+		lp.Parked = append(lp.Parked, ParkedCar{Lot: l.ID, Citizen: citizenID, Until: until})
+		occupied[l.ID]++
+		lp.Arrivals++
+		return
+	}
+	lp.TurnedAway++
+}
+
+// nearbyLots returns the complete lots within the walk radius of a point,
+// by ID, going through the placements of parking models only
+func (w *World) nearbyLots(x, z float64) []ParkingLot {
+	radius := positiveOr(w.LotParking.WalkRadius, PARKING_WALK_RADIUS)
+	var lots []ParkingLot
+	for name, spec := range w.Models {
+		if w.LotParking.Spaces[spec.Use] <= 0 {
+			continue
+		}
+		for _, id := range w.Occupancy.ModelIDs(name) {
+			p := w.Occupancy.Placement(id)
+			lx, lz := p.Center()
+			if spaces := w.lotSpaces(p); spaces > 0 && simHypot(lx-x, lz-z) <= radius {
+				lots = append(lots, ParkingLot{ID: id, X: lx, Z: lz, Spaces: spaces})
+			}
+		}
+	}
+	sort.Slice(lots, func(i, j int) bool { return lots[i].ID < lots[j].ID })
+	return lots
+}
+
+// releaseParking frees the space of a citizen's parked car
+func (w *World) releaseParking(citizenID int) {
+	lp := w.LotParking
+	kept := lp.Parked[:0]
+	for _, c := range lp.Parked {
+		if c.Citizen != citizenID {
+			kept = append(kept, c)
+		} else {
+			lp.unpark(c)
+		}
+	}
+	lp.Parked = kept
+}
+
+// stepLotParking lets trip cars whose time is up leave, and clears cars
+// from lots that are gone or of citizens no longer in town
+func (w *World) stepLotParking() {
+	lp := w.LotParking
+	if len(lp.Parked) == 0 {
+		return
+	}
+	citizens := make(map[int]bool, len(w.Citizens.list))
+	for _, ct := range w.Citizens.list {
+		citizens[ct.ID] = true
+	}
+	kept := lp.Parked[:0]
+	for _, c := range lp.Parked {
+		lot := w.Occupancy.Placement(c.Lot)
+		gone := lot == nil || w.lotSpaces(lot) == 0
+		due := c.Citizen < 0 && w.Time >= c.Until
+		left := c.Citizen >= 0 && !citizens[c.Citizen]
+		if !gone && !due && !left {
+			kept = append(kept, c)
+		} else {
+			lp.unpark(c)
+		}
+	}
+	lp.Parked = kept
+}
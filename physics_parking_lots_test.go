@@ -0,0 +1,54 @@
+package main
+
+import "testing"
+
+// A market (placement 1) with a parking lot beside it (placement 2)
+var (
+	testMarket = ModelSpec{Name: "market.gltf", Category: BUILDING_CATEGORY, Width: 2, Depth: 2, Use: "market"}
+	testLot    = ModelSpec{Name: "lot.gltf", Category: BUILDING_CATEGORY, Width: 2, Depth: 2, Use: PARKING_LOT_USE}
+	parkedTown = []Placement{testPlacement(1, testMarket, 0, 0, 2, 2), testPlacement(2, testLot, 2, 0, 4, 2)}
+)
+
+func TestParkArrivalFillsLotThenTurnsAway(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testMarket, testLot}, parkedTown...)
+	w.LotParking.Enabled = true
+	spaces := w.LotParking.Spaces[PARKING_LOT_USE]
+	for i := 0; i <= spaces; i++ {
+		w.parkArrival(1, i)
+	}
+	lp := w.LotParking
+	if lp.Arrivals != spaces || lp.TurnedAway != 1 {
+		t.Fatalf("arrivals %d turned away %d, want %d 1", lp.Arrivals, lp.TurnedAway, spaces)
+	}
+	if got := w.LotParkingReport().Lots[0].Occupied; got != spaces {
+		t.Fatalf("lot holds %d cars, want %d", got, spaces)
+	}
+
+	w.releaseParking(0)
+	w.parkArrival(1, spaces+1)
+	if lp.Arrivals != spaces+1 || lp.TurnedAway != 1 {
+		t.Fatal("space freed by a citizen setting off was not taken")
+	}
+}
+
+func TestParkArrivalCountsCarsOfARestoredSave(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testMarket, testLot}, parkedTown...)
+	w.LotParking.Enabled = true
+	spaces := w.LotParking.Spaces[PARKING_LOT_USE]
+	for i := 0; i < spaces; i++ {
+		w.parkArrival(1, i)
+	}
+	data, err := w.ToJSON()
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	restored := NewWorld()
+	if err := restored.FromJSON(data); err != nil {
+		t.Fatal(err)
+	}
+	restored.parkArrival(1, spaces)
+	if got := restored.LotParking.TurnedAway; got != 1 {
+		t.Fatalf("turned away %d cars at a full lot of a restored save, want 1", got)
+	}
+}
@@ -0,0 +1,177 @@
+package main
+
+import (
+	"container/heap"
+	"math"
+)
+
+// ============================================================================
+// A* Pathfinding over the Road Graph
+// ============================================================================
+
+// Route is an ordered path through the road graph
+type Route struct {
+	Nodes      []int   // Node IDs from start to goal (inclusive)
+	Edges      []int   // Edge IDs in travel order (len(Nodes)-1 entries)
+	Length     float64 // units
+	TravelTime float64 // s, at each segment's speed limit as the route was planned
+}
+
+// routeItem is a frontier entry in the A* open set
+type routeItem struct {
+	node     int
+	priority float64 // Cost so far plus heuristic
+	index    int     // Position in the heap (maintained by heap.Interface)
+}
+
+// routeQueue is a min-heap of frontier nodes ordered by priority, then node ID
+// so equal-cost routes are chosen deterministically
+type routeQueue []*routeItem
+
+func (q routeQueue) Len() int { return len(q) }
+
+func (q routeQueue) Less(i, j int) bool {
+	if q[i].priority != q[j].priority {
+		return q[i].priority < q[j].priority
+	}
+	return q[i].node < q[j].node
+}
+
+func (q routeQueue) Swap(i, j int) {
+	q[i], q[j] = q[j], q[i]
+	q[i].index = i
+	q[j].index = j
+}
+
+func (q *routeQueue) Push(x interface{}) {
+	item := x.(*routeItem)
+	item.index = len(*q)
+	*q = append(*q, item)
+}
+
+func (q *routeQueue) Pop() interface{} {
+	old := *q
+	item := old[len(old)-1]
+	*q = old[:len(old)-1]
+	return item
+}
+
+// maxSpeedLimit returns the fastest speed limit in the graph (for the heuristic)
+func (g *RoadGraph) maxSpeedLimit(limit func(e *RoadEdge) float64) float64 {
+	fastest := 0.0
+	for _, e := range g.edges {
+		fastest = math.Max(fastest, limit(e))
+	}
+	return fastest
+}
+
+// postedLimit returns a segment's own speed limit, ignoring districts
+func postedLimit(e *RoadEdge) float64 {
+	return e.SpeedLimit
+}
+
+// FindRoute returns the fastest route between two nodes, minimizing travel
+// time at the speed limits. The heuristic is the straight-line distance at
+// the graph's fastest speed limit, which never overestimates, so the route is optimal
+// Cars plan with driveRoute instead, at the limits districts set
+func (g *RoadGraph) FindRoute(from, to int) (Route, bool) {
+	return g.findRoute(from, to, nil, postedLimit)
+}
+
+// FindRouteAvoiding returns the fastest route between two nodes that uses
+// no edge for which avoid returns true
+func (g *RoadGraph) FindRouteAvoiding(from, to int, avoid func(e *RoadEdge) bool) (Route, bool) {
+	return g.findRoute(from, to, avoid, postedLimit)
+}
+
+// driveRoute returns the fastest route for a car between two nodes at the
+// speed limits districts set (physics_districts.go), over edges avoid
+// returns false for (nil avoids none)
+func (w *World) driveRoute(from, to int, avoid func(e *RoadEdge) bool) (Route, bool) {
+	return w.Roads.findRoute(from, to, avoid, w.edgeSpeedLimit)
+}
+
+// findRoute runs the A* search at the speed limits limit returns, skipping
+// edges avoid returns true for (nil avoids none)
+func (g *RoadGraph) findRoute(from, to int, avoid func(e *RoadEdge) bool, limit func(e *RoadEdge) float64) (Route, bool) {
+	start, goal := g.nodes[from], g.nodes[to]
+	if start == nil || goal == nil {
+		return Route{}, false
+	}
+	if from == to {
+		return Route{Nodes: []int{from}}, true
+	}
+
+	fastest := g.maxSpeedLimit(limit)
+	if !(fastest > 0) {
+		return Route{}, false // No edges
+	}
+	heuristic := func(n *RoadNode) float64 {
+		return simHypot(goal.X-n.X, goal.Z-n.Z) / fastest
+	}
+
+	cost := map[int]float64{from: 0}  // Best known travel time to each node
+	cameBy := make(map[int]int)       // Node ID -> edge ID used to reach it
+	items := make(map[int]*routeItem) // Node ID -> open set entry
+	closed := make(map[int]bool)
+	open := &routeQueue{}
+
+	items[from] = &routeItem{node: from, priority: heuristic(start)}
+	heap.Push(open, items[from])
+
+	for open.Len() > 0 {
+		current := heap.Pop(open).(*routeItem).node
+		delete(items, current)
+		if current == to {
+			return g.buildRoute(from, to, cameBy, limit), true
+		}
+		closed[current] = true
+
+		for _, edgeID := range g.nodes[current].Out {
+			e := g.edges[edgeID]
+			if closed[e.To] || (avoid != nil && avoid(e)) {
+				continue
+			}
+
+			tentative := cost[current] + e.Length/limit(e)
+			if known, seen := cost[e.To]; seen && tentative >= known {
+				continue
+			}
+			cost[e.To] = tentative
+			cameBy[e.To] = edgeID
+
+			priority := tentative + heuristic(g.nodes[e.To])
+			if item, queued := items[e.To]; queued {
+				item.priority = priority
+				heap.Fix(open, item.index)
+			} else {
+				items[e.To] = &routeItem{node: e.To, priority: priority}
+				heap.Push(open, items[e.To])
+			}
+		}
+	}
+
+	return Route{}, false
+}
+
+// buildRoute walks the cameBy links back from the goal
+func (g *RoadGraph) buildRoute(from, to int, cameBy map[int]int, limit func(e *RoadEdge) float64) Route {
+	var route Route
+	for node := to; node != from; {
+		e := g.edges[cameBy[node]]
+		route.Edges = append(route.Edges, e.ID)
+		route.Length += e.Length
+		route.TravelTime += e.Length / limit(e)
+		node = e.From
+	}
+
+	// Reverse into travel order and list the nodes along the way
+	for i, j := 0, len(route.Edges)-1; i < j; i, j = i+1, j-1 {
+		route.Edges[i], route.Edges[j] = route.Edges[j], route.Edges[i]
+	}
+	route.Nodes = append(route.Nodes, from)
+	for _, edgeID := range route.Edges {
+		route.Nodes = append(route.Nodes, g.edges[edgeID].To)
+	}
+	return route
+}
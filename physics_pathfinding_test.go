@@ -0,0 +1,38 @@
+package main
+
+import (
+	"math"
+	"testing"
+)
+
+func TestDriveRouteKeepsToDistrictSpeedLimits(t *testing.T) {
+	for _, tc := range []struct {
+		slow  bool // A district quarters the limit of the road from 2 to 3
+		nodes []int
+		time  float64 // s
+	}{
+		{false, []int{1, 2, 3}, 1.6},
+		{true, []int{1, 2, 4, 3}, 2.4}, // 4 s straight on through the district
+	} {
+		w := testWorld(t, nil)
+		testRoads(t, w, [][3]float64{{1, -8, 0}, {2, 0, 0}, {3, 8, 0}, {4, 0, 6}}, [][2]int{{1, 2}, {2, 3}, {2, 4}, {4, 3}})
+		if tc.slow {
+			d := w.Districts.Create("old town")
+			if err := w.SetDistrictPolicies(d.ID, DistrictPolicies{SpeedFactor: DISTRICT_MIN_SPEED_FACTOR, TaxRate: 1}); err != nil {
+				t.Fatal(err)
+			}
+			w.Districts.Paint(BoundingBox{MinX: 3, MinY: -1, MaxX: 5, MaxY: 1}, d.ID)
+		}
+
+		route, ok := w.driveRoute(1, 3, nil)
+		if !ok {
+			t.Fatalf("slow %v: no route", tc.slow)
+		}
+		if len(route.Nodes) != len(tc.nodes) || route.Nodes[len(route.Nodes)-2] != tc.nodes[len(tc.nodes)-2] {
+			t.Fatalf("slow %v: route nodes = %v, want %v", tc.slow, route.Nodes, tc.nodes)
+		}
+		if math.Abs(route.TravelTime-tc.time) > 1e-9 {
+			t.Fatalf("slow %v: travel time = %v, want %v", tc.slow, route.TravelTime, tc.time)
+		}
+	}
+}
@@ -0,0 +1,302 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Pedestrians (sidewalk walking and crosswalks)
+// ============================================================================
+
+// Pedestrians route over the road graph like cars, but walk a sidewalk
+// offset to the right of each segment, beyond its lanes. Corners are cut
+// short of the node; when the walk from one sidewalk to the next crosses a
+// road at the node, that leg is a crosswalk and the pedestrian waits at the
+// curb until no car will reach it before they are across. AI drivers treat
+// pedestrians in their lane as stopped obstacles (physics_traffic.go).
+
+const (
+	PEDESTRIAN_SPEED       = 1.4 // units/s, default walking speed
+	PEDESTRIAN_RADIUS      = 0.3 // units
+	SIDEWALK_MARGIN        = 0.5 // units, sidewalk distance beyond the outer lane's center
+	CROSSWALK_CLEARANCE    = 2.0 // units, cars closer than this to the crosswalk block it
+	CROSSWALK_MARGIN_TIME  = 1.5 // s, extra time a crossing must be clear for
+	PEDESTRIAN_ARRIVE_DIST = 0.3 // units, distance at which a path point counts as reached
+)
+
+// PedestrianState is what a pedestrian is currently doing
+// Waiting pedestrians stand at the curb until a crosswalk is clear
+type PedestrianState uint8
+
+const (
+	PedestrianWalking PedestrianState = iota
+	PedestrianWaiting
+	PedestrianCrossing
+	PedestrianArrived
+)
+
+// pedestrianStateNames are the JavaScript-facing names, indexed by PedestrianState
+var pedestrianStateNames = [...]string{"walking", "waiting", "crossing", "arrived"}
+
+// String returns the JavaScript-facing state name
+func (s PedestrianState) String() string {
+	if int(s) >= len(pedestrianStateNames) {
+		return pedestrianStateNames[PedestrianWalking]
+	}
+	return pedestrianStateNames[s]
+}
+
+// pedestrianLeg is one straight stretch of a pedestrian's path
+type pedestrianLeg struct {
+	X, Z      float64 // End point
+	Crosswalk bool    // Crosses a road; wait for traffic before starting
+}
+
+// Pedestrian is a walking agent
+type Pedestrian struct {
+	ID                   int
+	X, Z                 float64
+	VelocityX, VelocityZ float64
+	Heading              float64 // Radians, same convention as CarState.RotationY
+	Speed                float64 // units/s, preferred walking speed
+	State                PedestrianState
+
+	path []pedestrianLeg // Remaining legs, current first
+}
+
+// sidewalkSegment returns the start and end of the sidewalk on the right of
+// an edge, cut back from both nodes so corners meet at the curb
+func (w *World) sidewalkSegment(e *RoadEdge) (sx, sz, ex, ez float64) {
+	from, to := w.Roads.Node(e.From), w.Roads.Node(e.To)
+	dx, dz := w.Roads.edgeDirection(e)
+	offset := e.curbOffset() + SIDEWALK_MARGIN
+	setback := math.Min(offset, e.Length/2)
+
+	// Right of travel is (-dz, dx)
+	rx, rz := -dz*offset, dx*offset
+	return from.X + rx + dx*setback, from.Z + rz + dz*setback,
+		to.X + rx - dx*setback, to.Z + rz - dz*setback
+}
+
+// crossesRoadAt reports whether a walk between two points crosses any road
+// leaving or entering a node
+func (w *World) crossesRoadAt(node *RoadNode, ax, az, bx, bz float64) bool {
+	for _, ids := range [2][]int{node.In, node.Out} {
+		for _, id := range ids {
+			e := w.Roads.Edge(id)
+			other := w.Roads.Node(e.From)
+			if other == node {
+				other = w.Roads.Node(e.To)
+			}
+			if segmentsIntersect(ax, az, bx, bz, node.X, node.Z, other.X, other.Z) {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// segmentsIntersect reports whether segments AB and CD cross
+func segmentsIntersect(ax, az, bx, bz, cx, cz, dx, dz float64) bool {
+	orient := func(px, pz, qx, qz, rx, rz float64) float64 {
+		return (qx-px)*(rz-pz) - (qz-pz)*(rx-px)
+	}
+	d1 := orient(cx, cz, dx, dz, ax, az)
+	d2 := orient(cx, cz, dx, dz, bx, bz)
+	d3 := orient(ax, az, bx, bz, cx, cz)
+	d4 := orient(ax, az, bx, bz, dx, dz)
+	return d1*d2 < 0 && d3*d4 < 0
+}
+
+// pedestrianPath builds the legs from a start position to a destination via
+// the sidewalks of a route
+func (w *World) pedestrianPath(route Route, toX, toZ float64) []pedestrianLeg {
+	path := make([]pedestrianLeg, 0, 2*len(route.Edges)+1)
+	for i, id := range route.Edges {
+		e := w.Roads.Edge(id)
+		sx, sz, ex, ez := w.sidewalkSegment(e)
+
+		crosswalk := false
+		if i > 0 {
+			prev := path[len(path)-1]
+			crosswalk = w.crossesRoadAt(w.Roads.Node(e.From), prev.X, prev.Z, sx, sz)
+		}
+		path = append(path, pedestrianLeg{X: sx, Z: sz, Crosswalk: crosswalk}, pedestrianLeg{X: ex, Z: ez})
+	}
+	return append(path, pedestrianLeg{X: toX, Z: toZ})
+}
+
+// SpawnPedestrian adds (or replaces) a pedestrian walking from one position
+// to another along the sidewalks between their nearest road nodes, or
+// straight there when direct (plazas and parks)
+// speed falls back to PEDESTRIAN_SPEED when not positive
+func (w *World) SpawnPedestrian(id int, fromX, fromZ, toX, toZ, speed float64, direct bool) error {
+	path := []pedestrianLeg{{X: toX, Z: toZ}}
+	if !direct {
+		start := w.Roads.NearestNode(fromX, fromZ)
+		goal := w.Roads.NearestNode(toX, toZ)
+		if start == nil || goal == nil {
+			return errors.New("road network is empty")
+		}
+		route, ok := w.Roads.FindRoute(start.ID, goal.ID)
+		if !ok {
+			return errors.New("no sidewalk route between the positions")
+		}
+		path = w.pedestrianPath(route, toX, toZ)
+	}
+
+	p := &Pedestrian{
+		ID:    id,
+		X:     fromX,
+		Z:     fromZ,
+		Speed: positiveOr(speed, PEDESTRIAN_SPEED),
+		path:  path,
+	}
+
+	if i, exists := w.pedestrianIndex[id]; exists {
+		w.pedestrians[i] = p
+		return nil
+	}
+	w.pedestrianIndex[id] = len(w.pedestrians)
+	w.pedestrians = append(w.pedestrians, p)
+	return nil
+}
+
+// RemovePedestrian removes a pedestrian, returning false if it does not exist
+func (w *World) RemovePedestrian(id int) bool {
+	i, exists := w.pedestrianIndex[id]
+	if !exists {
+		return false
+	}
+
+	w.pedestrians = append(w.pedestrians[:i], w.pedestrians[i+1:]...)
+	delete(w.pedestrianIndex, id)
+	for j := i; j < len(w.pedestrians); j++ {
+		w.pedestrianIndex[w.pedestrians[j].ID] = j
+	}
+	return true
+}
+
+// SetPedestrianGoal sends a pedestrian straight to a new position, returning
+// false if it does not exist
+func (w *World) SetPedestrianGoal(id int, x, z float64) bool {
+	i, exists := w.pedestrianIndex[id]
+	if !exists {
+		return false
+	}
+
+	p := w.pedestrians[i]
+	p.path = []pedestrianLeg{{X: x, Z: z}}
+	p.State = PedestrianWalking
+	return true
+}
+
+// Pedestrian returns the pedestrian with the given ID, or nil
+func (w *World) Pedestrian(id int) *Pedestrian {
+	if i, exists := w.pedestrianIndex[id]; exists {
+		return w.pedestrians[i]
+	}
+	return nil
+}
+
+// Pedestrians returns every pedestrian in step order
+func (w *World) Pedestrians() []*Pedestrian {
+	return w.pedestrians
+}
+
+// crosswalkClear reports whether no car will reach the crosswalk from A to B
+// before a pedestrian walking at speed is across
+func (w *World) crosswalkClear(ax, az, bx, bz, speed float64) bool {
+	crossTime := simHypot(bx-ax, bz-az)/speed + CROSSWALK_MARGIN_TIME
+	for _, car := range w.cars {
+		s := &car.State
+		cx, cz := closestPointOnSegment(s.X, s.Z, ax, az, bx, bz)
+		toX, toZ := cx-s.X, cz-s.Z
+		distance := simHypot(toX, toZ) - car.Config.BodyRadius
+		if distance < CROSSWALK_CLEARANCE {
+			return false
+		}
+
+		// Only the speed towards the crosswalk matters
+		closing := (s.VelocityX*toX + s.VelocityZ*toZ) / (distance + car.Config.BodyRadius)
+		if closing > 0 && distance-CROSSWALK_CLEARANCE < closing*crossTime {
+			return false
+		}
+	}
+	return true
+}
+
+// stepPedestrians walks every pedestrian along its path, avoiding each other
+// and obstacles with social forces (physics_crowd.go)
+func (w *World) stepPedestrians(dt float64) {
+	forces := make([]float64, 2*len(w.pedestrians))
+	for i, p := range w.pedestrians {
+		desiredX, desiredZ := w.pedestrianIntent(p)
+		forces[2*i], forces[2*i+1] = w.crowdForce(p, desiredX, desiredZ)
+	}
+
+	for i, p := range w.pedestrians {
+		p.VelocityX += forces[2*i] * dt
+		p.VelocityZ += forces[2*i+1] * dt
+
+		speed := simHypot(p.VelocityX, p.VelocityZ)
+		if limit := p.Speed * w.Crowd.MaxSpeedScale; speed > limit {
+			p.VelocityX *= limit / speed
+			p.VelocityZ *= limit / speed
+		}
+		p.X += p.VelocityX * dt
+		p.Z += p.VelocityZ * dt
+
+		if speed > STOP_SPEED {
+			p.Heading = math.Atan2(p.VelocityX, p.VelocityZ)
+		}
+	}
+}
+
+// pedestrianIntent advances a pedestrian's path and state, returning the
+// velocity they want to walk at (zero while waiting or arrived)
+func (w *World) pedestrianIntent(p *Pedestrian) (vx, vz float64) {
+	for len(p.path) > 0 && simHypot(p.path[0].X-p.X, p.path[0].Z-p.Z) < PEDESTRIAN_ARRIVE_DIST {
+		p.path = p.path[1:]
+		p.State = PedestrianWalking
+	}
+	if len(p.path) == 0 {
+		p.State = PedestrianArrived
+		return 0, 0
+	}
+
+	leg := &p.path[0]
+	if leg.Crosswalk && p.State != PedestrianCrossing {
+		if !w.crosswalkClear(p.X, p.Z, leg.X, leg.Z, p.Speed) {
+			p.State = PedestrianWaiting
+			return 0, 0
+		}
+		p.State = PedestrianCrossing
+	}
+
+	dx, dz := leg.X-p.X, leg.Z-p.Z
+	distance := simHypot(dx, dz)
+	return dx / distance * p.Speed, dz / distance * p.Speed
+}
+
+// PEDESTRIAN_TRANSFORM_STRIDE is the number of values per pedestrian written
+// by WritePedestrianTransforms: id, x, z, heading, state
+const PEDESTRIAN_TRANSFORM_STRIDE = 5
+
+// WritePedestrianTransforms writes every pedestrian's render transform into
+// dst, returning the number written (limited by len(dst))
+// state is the PedestrianState index (see pedestrianStateNames)
+func (w *World) WritePedestrianTransforms(dst []float64) int {
+	count := min(len(w.pedestrians), len(dst)/PEDESTRIAN_TRANSFORM_STRIDE)
+	for i := 0; i < count; i++ {
+		p := w.pedestrians[i]
+		out := dst[i*PEDESTRIAN_TRANSFORM_STRIDE:]
+		out[0] = float64(p.ID)
+		out[1] = p.X
+		out[2] = p.Z
+		out[3] = p.Heading
+		out[4] = float64(p.State)
+	}
+	return count
+}
@@ -0,0 +1,242 @@
+package main
+
+import (
+	"math"
+	"strings"
+)
+
+// ============================================================================
+// Placement Validation
+// ============================================================================
+
+// CanPlace checks a model placement against the occupancy grid, the zoning,
+// the road network, the terrain, water, the world bounds and the treasury, and
+// reports the first rule it breaks, so the UI can say why a ghost building is
+// red.
+
+const (
+	ROAD_ACCESS_DISTANCE = 0.6 // units, gap to a road tile that still counts as road access
+	MAX_BUILD_SLOPE      = 0.3 // rise/run, default steepest ground a model can be placed on
+	BUILDING_CATEGORY    = "buildings"
+)
+
+// isRoadModel reports whether a model is a road tile (road_* models, stored
+// under "street" in the model library and "roads" in saved towns)
+func isRoadModel(name, category string) bool {
+	return strings.HasPrefix(name, "road_") || category == "street" || category == "roads"
+}
+
+// isRoad reports whether a placement is a road tile
+func (p *Placement) isRoad() bool {
+	return isRoadModel(p.Model, p.Category)
+}
+
+// ModelSpec describes a placeable model
+type ModelSpec struct {
+	Name         string
+	Category     string
+	Use          string  // What the building is for ("housing", "market", ...), see DefaultZoneRules
+	Width, Depth float64 // units along X and Z when unrotated
+	RequiresRoad bool    // Must touch a road tile
+	MaxSlope     float64 // rise/run
+	Bridge       bool    // May stand on water and carries cars over it (physics_water.go)
+}
+
+// NewModelSpec returns a model spec with the default rules for its category:
+// buildings need road access, everything has the default slope limit, and
+// models named like bridges are bridges
+func NewModelSpec(name, category string, width, depth float64) ModelSpec {
+	return ModelSpec{
+		Name:         name,
+		Category:     category,
+		Width:        width,
+		Depth:        depth,
+		RequiresRoad: category == BUILDING_CATEGORY,
+		MaxSlope:     MAX_BUILD_SLOPE,
+		Bridge:       isBridgeModel(name),
+	}
+}
+
+// PlacementFailure is the reason a placement is rejected
+type PlacementFailure uint8
+
+const (
+	PlacementOK PlacementFailure = iota
+	PlacementUnknownModel
+	PlacementOutOfBounds
+	PlacementOverlap
+	PlacementNoRoadAccess
+	PlacementTooSteep
+	PlacementWrongZone
+	PlacementUnaffordable
+	PlacementInWater
+	PlacementNeedsBridge
+	PlacementDistrictBan
+)
+
+// placementFailureNames are the JavaScript-facing names, indexed by PlacementFailure
+var placementFailureNames = [...]string{"ok", "unknown_model", "out_of_bounds", "overlap", "no_road_access", "too_steep", "wrong_zone", "unaffordable", "in_water", "needs_bridge", "district_ban"}
+
+// placementFailureMessages are player-facing explanations, indexed by PlacementFailure
+var placementFailureMessages = [...]string{
+	"",
+	"This model's size is not known yet",
+	"Outside the buildable area",
+	"Overlaps another object",
+	"Needs to be next to a road",
+	"The ground is too steep here",
+	"Not allowed in this zone",
+	"Not enough money",
+	"Can't build on water",
+	"Roads over water need a bridge",
+	"Industry is banned in this district",
+}
+
+// String returns the JavaScript-facing failure name
+func (f PlacementFailure) String() string {
+	if int(f) >= len(placementFailureNames) {
+		return placementFailureNames[PlacementOK]
+	}
+	return placementFailureNames[f]
+}
+
+// Message returns the player-facing explanation
+func (f PlacementFailure) Message() string {
+	if int(f) >= len(placementFailureMessages) {
+		return ""
+	}
+	return placementFailureMessages[f]
+}
+
+// PlacementCheck is the result of CanPlace
+type PlacementCheck struct {
+	Reason    PlacementFailure
+	Footprint BoundingBox // World-space footprint that was tested
+	Conflicts []int       // Overlapping placement IDs (PlacementOverlap)
+}
+
+// OK reports whether the placement is allowed
+func (c PlacementCheck) OK() bool {
+	return c.Reason == PlacementOK
+}
+
+// rotatedFootprint returns the axis-aligned box around a width x depth
+// rectangle centered at (x, z) and rotated by rotation radians about Y
+func rotatedFootprint(x, z, width, depth, rotation float64) BoundingBox {
+	sin, cos := math.Abs(math.Sin(rotation)), math.Abs(math.Cos(rotation))
+	halfX := (width*cos + depth*sin) / 2
+	halfZ := (width*sin + depth*cos) / 2
+	return BoundingBox{MinX: x - halfX, MinY: z - halfZ, MaxX: x + halfX, MaxY: z + halfZ}
+}
+
+// buildableArea returns the box placements must stay inside: the world
+// bounds when set, otherwise the occupancy grid
+func (w *World) buildableArea() BoundingBox {
+	if w.Bounds != nil {
+		return w.Bounds.Box
+	}
+	g := w.Occupancy.GridSpec
+	return BoundingBox{
+		MinX: g.OriginX,
+		MinY: g.OriginZ,
+		MaxX: g.OriginX + float64(g.Width)*g.CellSize,
+		MaxY: g.OriginZ + float64(g.Depth)*g.CellSize,
+	}
+}
+
+// maxSlopeIn returns the steepest terrain slope sampled over a footprint
+// (its center and corners)
+func (w *World) maxSlopeIn(f BoundingBox) float64 {
+	if w.Terrain == nil {
+		return 0
+	}
+
+	steepest := 0.0
+	samples := [5][2]float64{
+		{(f.MinX + f.MaxX) / 2, (f.MinY + f.MaxY) / 2},
+		{f.MinX, f.MinY}, {f.MaxX, f.MinY}, {f.MinX, f.MaxY}, {f.MaxX, f.MaxY},
+	}
+	for _, s := range samples {
+		gx, gz := w.Terrain.GetGradient(s[0], s[1])
+		steepest = math.Max(steepest, simHypot(gx, gz))
+	}
+	return steepest
+}
+
+// CanPlace checks whether a model can be placed centered at (x, z) with the
+// given Y rotation; the placement with ID ignore (if any) is left out, so an
+// object being moved does not collide with itself
+func (w *World) CanPlace(model string, x, z, rotation float64, ignore int) PlacementCheck {
+	spec, known := w.Models[model]
+	if !known || !isFinite(x) || !isFinite(z) || !isFinite(rotation) {
+		return PlacementCheck{Reason: PlacementUnknownModel}
+	}
+
+	check := PlacementCheck{Footprint: rotatedFootprint(x, z, spec.Width, spec.Depth, rotation)}
+	f := check.Footprint
+
+	area := w.buildableArea()
+	if f.MinX < area.MinX || f.MaxX > area.MaxX || f.MinY < area.MinY || f.MaxY > area.MaxY {
+		check.Reason = PlacementOutOfBounds
+		return check
+	}
+
+	for _, p := range w.Occupancy.Query(f) {
+		if p.ID != ignore {
+			check.Conflicts = append(check.Conflicts, p.ID)
+		}
+	}
+	if len(check.Conflicts) > 0 {
+		check.Reason = PlacementOverlap
+		return check
+	}
+
+	if !spec.Bridge && w.footprintInWater(f) {
+		check.Reason = PlacementInWater
+		if isRoadModel(model, spec.Category) {
+			check.Reason = PlacementNeedsBridge
+		}
+		return check
+	}
+
+	if !w.zoneAllows(spec, f) {
+		check.Reason = PlacementWrongZone
+		return check
+	}
+
+	if w.districtBans(spec, f) {
+		check.Reason = PlacementDistrictBan
+		return check
+	}
+
+	if spec.RequiresRoad && !w.hasRoadAccess(f, ignore) {
+		check.Reason = PlacementNoRoadAccess
+		return check
+	}
+
+	if w.maxSlopeIn(f) > spec.MaxSlope {
+		check.Reason = PlacementTooSteep
+		return check
+	}
+
+	if !w.affordable(model) {
+		check.Reason = PlacementUnaffordable
+	}
+	return check
+}
+
+// hasRoadAccess reports whether a road tile lies within ROAD_ACCESS_DISTANCE of a footprint
+func (w *World) hasRoadAccess(f BoundingBox, ignore int) bool {
+	reach := BoundingBox{
+		MinX: f.MinX - ROAD_ACCESS_DISTANCE,
+		MinY: f.MinY - ROAD_ACCESS_DISTANCE,
+		MaxX: f.MaxX + ROAD_ACCESS_DISTANCE,
+		MaxY: f.MaxY + ROAD_ACCESS_DISTANCE,
+	}
+	for _, p := range w.Occupancy.Query(reach) {
+		if p.isRoad() && p.ID != ignore {
+			return true
+		}
+	}
+	return false
+}
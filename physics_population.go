@@ -0,0 +1,192 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Population (residents, jobs and demand)
+// ============================================================================
+
+// Homes house residents and workplaces offer jobs, both sized by model use.
+// At the end of each game day (physics_clock.go) the town's satisfaction (employment and livability)
+// decides whether people move in or out. Between days the census keeps
+// capacity in sync with what is built, evicting residents of demolished homes.
+// Commercial demand shrinks where businesses lack parking (physics_parking_lots.go).
+
+const (
+	POPULATION_GROWTH       = 0.25 // Share of free housing filled per day at full satisfaction
+	POPULATION_DECLINE      = 0.1  // Share of residents leaving per day at zero satisfaction
+	SATISFIED_THRESHOLD     = 0.5  // Satisfaction above which the town grows
+	JOB_SATISFACTION_WEIGHT = 0.5  // Share of satisfaction from employment (the rest is livability)
+	WORKFORCE_SHARE         = 0.6  // Share of residents who want a job
+	SHOP_JOBS_PER_RESIDENT  = 0.15 // Commercial jobs a resident's shopping supports
+	STARTER_RESIDENTS       = 8    // Residential demand of an empty town
+)
+
+// UseCapacity is what a model use contributes to the population model
+type UseCapacity struct {
+	Residents int      // Homes
+	Jobs      int      // Workplaces
+	Zone      ZoneType // Demand bucket the jobs count towards
+}
+
+// DefaultCapacities returns the residents and jobs of each model use
+func DefaultCapacities() map[string]UseCapacity {
+	return map[string]UseCapacity{
+		"housing":        {Residents: 4, Zone: ZoneResidential},
+		"market":         {Jobs: 6, Zone: ZoneCommercial},
+		"office":         {Jobs: 12, Zone: ZoneCommercial},
+		"industry":       {Jobs: 10, Zone: ZoneIndustrial},
+		"school":         {Jobs: 4},
+		"library":        {Jobs: 2},
+		"healthcare":     {Jobs: 6},
+		"fire_station":   {Jobs: 4},
+		"police_station": {Jobs: 4},
+		"garage":         {Jobs: 3, Zone: ZoneCommercial},
+		"gas_station":    {Jobs: 2, Zone: ZoneCommercial},
+		"power_plant":    {Jobs: 8, Zone: ZoneIndustrial},
+		"water_tower":    {Jobs: 2, Zone: ZoneIndustrial},
+	}
+}
+
+// Population is the town's census
+type Population struct {
+	Residents    int
+	Housing      int // Total home capacity
+	Jobs         int
+	Workers      int     // Residents who want a job
+	Employed     int     // Workers with a job
+	Satisfaction float64 // 0-1, at the last daily update
+
+	// Demand per zone, -1 (oversupplied) to 1 (wanted); ZoneNone is unused
+	Demand [zoneTypeCount]float64
+
+	homes    map[int]int // Home placement ID -> residents
+	zoneJobs [zoneTypeCount]int
+}
+
+// NewPopulation creates an empty town
+func NewPopulation() *Population {
+	return &Population{homes: make(map[int]int)}
+}
+
+// HomeResidents returns the residents of a home placement
+func (p *Population) HomeResidents(id int) int {
+	return p.homes[id]
+}
+
+// capacity returns what a placement contributes to the population model;
+// a building under construction (physics_construction.go) or without its
+// utilities (physics_utilities.go) holds no one, and one above level 1
+// (physics_levels.go) holds more
+func (w *World) capacity(p *Placement) UseCapacity {
+	c := w.Capacities[w.placementUse(p)]
+	if w.underConstruction(p) || !w.utilitiesServed(p) {
+		return UseCapacity{Zone: c.Zone}
+	}
+	return levelCapacity(c, w.BuildingLevel(p.ID))
+}
+
+// Census recounts housing and jobs from what is placed, evicting residents
+// whose homes are gone or shrank, then updates employment and demand
+func (w *World) Census() {
+	pop := w.Population
+	pop.Housing, pop.Jobs = 0, 0
+	pop.zoneJobs = [zoneTypeCount]int{}
+
+	seen := make(map[int]bool, len(pop.homes))
+	for _, id := range w.Occupancy.IDs() {
+		c := w.capacity(w.Occupancy.Placement(id))
+		pop.Jobs += c.Jobs
+		pop.zoneJobs[c.Zone] += c.Jobs
+		if c.Residents > 0 {
+			pop.Housing += c.Residents
+			pop.homes[id] = min(pop.homes[id], c.Residents)
+			seen[id] = true
+		}
+	}
+
+	pop.Residents = 0
+	for id, residents := range pop.homes {
+		if !seen[id] {
+			delete(pop.homes, id)
+			continue
+		}
+		pop.Residents += residents
+	}
+
+	pop.Workers = int(math.Round(float64(pop.Residents) * WORKFORCE_SHARE))
+	pop.Employed = min(pop.Workers, pop.Jobs)
+
+	residentsWanted := math.Max(float64(pop.Jobs)/WORKFORCE_SHARE, STARTER_RESIDENTS)
+	shopJobs := float64(pop.Residents) * SHOP_JOBS_PER_RESIDENT
+	pop.Demand[ZoneResidential] = ratioDemand(residentsWanted, float64(pop.Housing))
+	pop.Demand[ZoneCommercial] = ratioDemand(shopJobs*w.commercialHappiness(), float64(pop.zoneJobs[ZoneCommercial]))
+	pop.Demand[ZoneIndustrial] = ratioDemand(float64(pop.Workers)-shopJobs, float64(pop.zoneJobs[ZoneIndustrial]))
+}
+
+// ratioDemand maps wanted vs available capacity to -1..1
+func ratioDemand(want, have float64) float64 {
+	want = math.Max(want, 0)
+	return clamp((want-have)/math.Max(math.Max(want, have), 1), -1, 1)
+}
+
+// endDay levels buildings (physics_levels.go), moves residents and settles
+// the budget (physics_economy.go) at the end of each game day
+func (w *World) endDay() {
+	w.updateBuildingLevels()
+	w.updatePopulation()
+	w.settleBudget()
+}
+
+// updatePopulation rates the town and moves residents in or out
+func (w *World) updatePopulation() {
+	w.Census()
+	pop := w.Population
+
+	employment := 1.0
+	if pop.Workers > 0 {
+		employment = float64(pop.Employed) / float64(pop.Workers)
+	}
+	livability := w.Livability().Score / LIVABILITY_MAX_SCORE
+	pop.Satisfaction = JOB_SATISFACTION_WEIGHT*employment + (1-JOB_SATISFACTION_WEIGHT)*livability
+
+	if pop.Satisfaction >= SATISFIED_THRESHOLD {
+		rate := (pop.Satisfaction - SATISFIED_THRESHOLD) / (1 - SATISFIED_THRESHOLD)
+		w.moveIn(int(math.Ceil(float64(pop.Housing-pop.Residents) * POPULATION_GROWTH * rate)))
+	} else {
+		rate := (SATISFIED_THRESHOLD - pop.Satisfaction) / SATISFIED_THRESHOLD
+		w.moveOut(int(math.Ceil(float64(pop.Residents) * POPULATION_DECLINE * rate)))
+	}
+	w.Census()
+}
+
+// moveIn fills free homes in placement order
+func (w *World) moveIn(count int) {
+	pop := w.Population
+	for _, id := range w.Occupancy.IDs() {
+		if count <= 0 {
+			return
+		}
+		c := w.capacity(w.Occupancy.Placement(id))
+		free := c.Residents - pop.homes[id]
+		if free <= 0 {
+			continue
+		}
+		n := min(free, count)
+		pop.homes[id] += n
+		count -= n
+	}
+}
+
+// moveOut empties homes in reverse placement order
+func (w *World) moveOut(count int) {
+	pop := w.Population
+	ids := w.Occupancy.IDs()
+	for i := len(ids) - 1; i >= 0 && count > 0; i-- {
+		n := min(pop.homes[ids[i]], count)
+		if n > 0 {
+			pop.homes[ids[i]] -= n
+			count -= n
+		}
+	}
+}
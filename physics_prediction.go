@@ -0,0 +1,161 @@
+package main
+
+import "errors"
+
+// ============================================================================
+// Client Prediction (responsive driving against an authoritative server)
+// ============================================================================
+
+// A networked client cannot wait a round trip to see its car move, so it
+// predicts: every tick it gives the local car the player's input, sends
+// the input to the server (physics_server.go) and steps its own copy of
+// the world straight away, keeping the inputs the server has not yet
+// applied. When a state packet arrives, the client rewinds to the
+// authoritative state it carries, drops the inputs the server says it
+// applied and steps the rest again, which lands its car where the server
+// will put it once those inputs arrive. Prediction and server normally
+// agree; when they do not (a collision with another player, a lost input)
+// the car would jump, so the difference is kept as a display offset that
+// fades over PREDICTION_SMOOTH_TIME, unless it is too large to hide.
+
+const (
+	PREDICTION_SMOOTH_TIME = 0.1 // s for a correction to fade to 1/e
+	PREDICTION_SNAP_DIST   = 4.0 // units; larger corrections are shown at once
+	MAX_PENDING_INPUTS     = 120 // Unapplied inputs kept (2 s at 60 Hz); older ones are dropped
+)
+
+// pendingInput is an input sent to the server and not yet applied by it
+type pendingInput struct {
+	seq   uint32
+	input InputState
+}
+
+// Prediction predicts a client's car and reconciles it with server state
+type Prediction struct {
+	World *World
+	CarID int
+	Dt    float64 // s per tick, the server's
+
+	seq     uint32
+	pending []pendingInput
+	states  map[uint32]*WorldSnapshot // Server states applied, by tick, as delta bases
+	ackTick uint32
+
+	offsetX, offsetZ, offsetRot float64 // Correction still shown, faded each tick
+}
+
+// NewPrediction creates a predictor for the car a client drives, ticking
+// at FIXED_TIMESTEP like the server
+func NewPrediction(w *World, carID int) *Prediction {
+	return &Prediction{World: w, CarID: carID, Dt: FIXED_TIMESTEP, states: make(map[uint32]*WorldSnapshot)}
+}
+
+// stepWith gives the car an input and steps the world one tick
+func (p *Prediction) stepWith(input InputState) {
+	if car := p.World.Car(p.CarID); car != nil {
+		car.Input = input
+	}
+	p.World.Step(p.Dt)
+}
+
+// Predict runs one tick on the player's input, returning the ClientPacket
+// to send to the server
+func (p *Prediction) Predict(input InputState) ([]byte, error) {
+	input = input.clamped()
+	p.seq++
+	p.pending = append(p.pending, pendingInput{seq: p.seq, input: input})
+	if len(p.pending) > MAX_PENDING_INPUTS {
+		p.pending = p.pending[len(p.pending)-MAX_PENDING_INPUTS:]
+	}
+	p.stepWith(input)
+
+	fade := simExp(-p.Dt / PREDICTION_SMOOTH_TIME)
+	p.offsetX *= fade
+	p.offsetZ *= fade
+	p.offsetRot *= fade
+	return ClientPacket{Seq: p.seq, AckTick: p.ackTick, Input: input}.MarshalBinary()
+}
+
+// Reconcile applies a ServerPacket: the world is rewound to the server's
+// state and the inputs the server has not applied yet are stepped again.
+// Packets older than the newest applied are ignored
+func (p *Prediction) Reconcile(data []byte) error {
+	var packet ServerPacket
+	if err := packet.UnmarshalBinary(data); err != nil {
+		return err
+	}
+	if packet.Tick <= p.ackTick {
+		return nil // Arrived out of order
+	}
+	var base *WorldSnapshot
+	if packet.BaseTick != 0 {
+		if base = p.states[packet.BaseTick]; base == nil {
+			return errors.New("state packet refers to a state this client no longer has")
+		}
+	}
+	next, err := ApplyDelta(base, packet.Delta)
+	if err != nil {
+		return err
+	}
+
+	beforeX, beforeZ, beforeRot, hadCar := p.carPose()
+	if err := p.World.Restore(next); err != nil {
+		return err
+	}
+	p.states[packet.Tick] = &next
+	p.ackTick = packet.Tick
+	for tick := range p.states {
+		// Bases only move forward and never leave the server's history
+		if tick < packet.BaseTick || tick+SERVER_HISTORY_TICKS < packet.Tick {
+			delete(p.states, tick)
+		}
+	}
+
+	kept := p.pending[:0]
+	for _, in := range p.pending {
+		if in.seq > packet.InputSeq {
+			kept = append(kept, in)
+		}
+	}
+	p.pending = kept
+	for _, in := range p.pending {
+		p.stepWith(in.input)
+	}
+
+	afterX, afterZ, afterRot, hasCar := p.carPose()
+	if hadCar && hasCar {
+		p.offsetX += beforeX - afterX
+		p.offsetZ += beforeZ - afterZ
+		p.offsetRot = normalizeAngle(p.offsetRot + beforeRot - afterRot)
+		if simHypot(p.offsetX, p.offsetZ) > PREDICTION_SNAP_DIST {
+			p.offsetX, p.offsetZ, p.offsetRot = 0, 0, 0
+		}
+	}
+	return nil
+}
+
+// carPose returns the predicted car's position and heading
+func (p *Prediction) carPose() (x, z, rot float64, ok bool) {
+	car := p.World.Car(p.CarID)
+	if car == nil {
+		return 0, 0, 0, false
+	}
+	return car.State.X, car.State.Z, car.State.RotationY, true
+}
+
+// Display returns where to draw the car: its predicted pose plus the
+// correction still fading out
+func (p *Prediction) Display() (x, z, rot float64, ok bool) {
+	x, z, rot, ok = p.carPose()
+	return x + p.offsetX, z + p.offsetZ, normalizeAngle(rot + p.offsetRot), ok
+}
+
+// Pending returns the number of inputs the server has not applied yet
+func (p *Prediction) Pending() int {
+	return len(p.pending)
+}
+
+// Correction returns the distance of the correction still being faded out
+func (p *Prediction) Correction() float64 {
+	return simHypot(p.offsetX, p.offsetZ)
+}
@@ -0,0 +1,122 @@
+package main
+
+import "time"
+
+// ============================================================================
+// Frame Profiling (time per subsystem and entity counts)
+// ============================================================================
+
+// Every Step times its subsystems and adds the milliseconds to the frame
+// being recorded. A frame ends with each call from JS that runs the world
+// (step, or an advance that ran at least one fixed step), and its totals
+// plus the entity counts at that moment become the latest FrameStats for a
+// performance HUD. The timer is the runtime clock, which browsers may round
+// to 0.1 ms or coarser, so single-step figures are only meaningful averaged.
+
+// ProfileSystem is a subsystem timed by the profiler
+type ProfileSystem uint8
+
+const (
+	ProfileEnvironment ProfileSystem = iota // Clock, weather, road wear
+	ProfileTraffic                          // Trips, signals, AI drivers
+	ProfileFires                            // Fire spread, trucks and incidents
+	ProfilePedestrians                      // Crowd steering
+	ProfilePhysics                          // Car steps
+	ProfileCollision                        // Car-to-car contacts
+	ProfileGrids                            // Noise and greenery
+	ProfileGhosts                           // Replayed ghost worlds
+	ProfileOutput                           // Town events and transform stores
+	profileSystemCount
+)
+
+// profileSystemNames are the JavaScript-facing names, indexed by ProfileSystem
+var profileSystemNames = [profileSystemCount]string{"environment", "traffic", "fires", "pedestrians", "physics", "collision", "grids", "ghosts", "output"}
+
+// String returns the JavaScript-facing subsystem name
+func (s ProfileSystem) String() string {
+	if s >= profileSystemCount {
+		return profileSystemNames[ProfileEnvironment]
+	}
+	return profileSystemNames[s]
+}
+
+// EntityCounts is how much the world holds
+type EntityCounts struct {
+	Cars        int
+	AICars      int // Of Cars, those with an AI driver
+	ManualCars  int // Of Cars, those stepped from JS
+	Pedestrians int
+	Colliders   int
+	Placements  int
+	RoadNodes   int
+	RoadEdges   int
+	Signals     int
+	Trips       int // Generated trips being driven
+	Fires       int
+	Ghosts      int
+}
+
+// FrameStats is what one frame cost
+type FrameStats struct {
+	Steps   int                         // World steps run in the frame
+	StepMs  float64                     // ms spent in those steps
+	Systems [profileSystemCount]float64 // ms per subsystem, indexed by ProfileSystem
+	Counts  EntityCounts                // At the end of the frame
+}
+
+// Profiler records the frame in progress and keeps the last one finished
+type Profiler struct {
+	Last    FrameStats
+	current FrameStats
+}
+
+// lap adds the time since mark to a subsystem and returns the new mark
+func (p *Profiler) lap(system ProfileSystem, mark time.Time) time.Time {
+	now := time.Now()
+	p.current.Systems[system] += milliseconds(now.Sub(mark))
+	return now
+}
+
+// stepDone adds one step taking the time since start to the frame
+func (p *Profiler) stepDone(start time.Time) {
+	p.current.Steps++
+	p.current.StepMs += milliseconds(time.Since(start))
+}
+
+// milliseconds converts a duration to fractional milliseconds
+func milliseconds(d time.Duration) float64 {
+	return float64(d) / float64(time.Millisecond)
+}
+
+// Counts returns how many of each entity the world holds
+func (w *World) Counts() EntityCounts {
+	c := EntityCounts{
+		Cars:        len(w.cars),
+		Pedestrians: len(w.pedestrians),
+		Colliders:   len(w.Colliders.colliders),
+		Placements:  w.Occupancy.Count(),
+		RoadNodes:   w.Roads.NodeCount(),
+		RoadEdges:   w.Roads.EdgeCount(),
+		Signals:     len(w.Signals),
+		Trips:       len(w.Trips.active),
+		Fires:       len(w.Fires.fires),
+		Ghosts:      len(w.ghosts),
+	}
+	for _, car := range w.cars {
+		if car.AI != nil {
+			c.AICars++
+		}
+		if car.Manual {
+			c.ManualCars++
+		}
+	}
+	return c
+}
+
+// EndFrame finishes the frame being recorded, making it the latest FrameStats
+func (w *World) EndFrame() {
+	p := &w.Profile
+	p.Last = p.current
+	p.Last.Counts = w.Counts()
+	p.current = FrameStats{}
+}
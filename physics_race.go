@@ -0,0 +1,212 @@
+package main
+
+import (
+	"errors"
+	"sort"
+)
+
+// ============================================================================
+// Races (checkpoint gates and lap timing)
+// ============================================================================
+
+// A race is an ordered series of gates, line segments across the course,
+// with gate 0 the start/finish line, and the cars entered in it. Each step,
+// after the cars have moved, every racer's path since the last step is
+// tested against the next gate it must pass; gates passed out of order do
+// not count. The first pass of the start line starts a racer's clock, every
+// gate after it ends a sector, and the start line again ends the lap. Times
+// are simulation time (World.Time), and a pass is timed where along the
+// step the car crossed the line, so laps do not depend on the frame rate.
+// Each lap queues a "lap_completed" simulation event, and with a set number
+// of laps a racer finishing them queues "race_finished" with its place
+// (physics_sim_events.go).
+
+// Gate is a checkpoint line from X1, Z1 to X2, Z2
+type Gate struct {
+	X1, Z1, X2, Z2 float64
+}
+
+// crossing returns how far along a move from a to b (0..1) it crosses the
+// gate, and false when it does not
+func (g *Gate) crossing(ax, az, bx, bz float64) (float64, bool) {
+	mx, mz := bx-ax, bz-az
+	gx, gz := g.X2-g.X1, g.Z2-g.Z1
+	denom := mx*gz - mz*gx
+	if denom == 0 {
+		return 0, false // Parallel, or not moving
+	}
+	rx, rz := g.X1-ax, g.Z1-az
+	t := (rx*gz - rz*gx) / denom // Along the move
+	u := (rx*mz - rz*mx) / denom // Along the gate
+	if t < 0 || t > 1 || u < 0 || u > 1 {
+		return 0, false
+	}
+	return t, true
+}
+
+// Racer is one car's progress and times in a race
+type Racer struct {
+	CarID       int
+	Lap         int       // Laps completed
+	NextGate    int       // Index of the gate to pass next
+	Started     bool      // Has passed the start line
+	LapStart    float64   // Simulation time the current lap began
+	SectorStart float64   // Simulation time the current sector began
+	LastPass    float64   // Simulation time of the last gate passed, for standings
+	Sectors     []float64 // s, the sectors of the current lap so far
+	LastLap     float64   // s, 0 before a lap is completed
+	BestLap     float64   // s, 0 before a lap is completed
+	BestSectors []float64 // s per sector on any lap, 0 until driven
+	Finished    bool
+	Place       int     // Finishing place, 0 while racing
+	FinishTime  float64 // s from the start of the race to the finish
+
+	PrevX, PrevZ float64 // Where the car was after the previous step
+	Placed       bool    // PrevX, PrevZ are set
+}
+
+// Race holds the course, the laps to race and the racers
+type Race struct {
+	Gates     []Gate
+	Laps      int     // Laps to finish, 0 for an endless practice session
+	StartTime float64 // Simulation time the race began
+	Finishers int
+
+	racers []*Racer // In entry order
+}
+
+// Racers returns the racers in entry order
+func (r *Race) Racers() []*Racer {
+	return r.racers
+}
+
+// Racer returns a car's entry, or nil
+func (r *Race) Racer(carID int) *Racer {
+	for _, racer := range r.racers {
+		if racer.CarID == carID {
+			return racer
+		}
+	}
+	return nil
+}
+
+// StartRace replaces any race with a new one over the gates for the cars,
+// starting now; laps of 0 make an endless practice session
+func (w *World) StartRace(gates []Gate, laps int, carIDs []int) error {
+	switch {
+	case len(gates) == 0:
+		return errors.New("a race needs at least one gate")
+	case laps < 0:
+		return errors.New("a race cannot have negative laps")
+	}
+	race := &Race{Gates: append([]Gate(nil), gates...), Laps: laps, StartTime: w.Time}
+	for _, id := range carIDs {
+		if w.Car(id) == nil {
+			return errors.New("no car to enter in the race")
+		}
+		if race.Racer(id) == nil {
+			race.racers = append(race.racers, &Racer{CarID: id, BestSectors: make([]float64, len(gates))})
+		}
+	}
+	w.Race = race
+	return nil
+}
+
+// StopRace ends the race, discarding its times
+func (w *World) StopRace() {
+	w.Race = nil
+}
+
+// Standings returns the racers in race order: finishers by place, then
+// by laps and gates passed, the earlier pass ahead on equal progress
+func (r *Race) Standings() []*Racer {
+	standings := append([]*Racer(nil), r.racers...)
+	progress := func(racer *Racer) int {
+		if !racer.Started {
+			return -1
+		}
+		gate := racer.NextGate
+		if gate == 0 {
+			gate = len(r.Gates) // Just through the start line
+		}
+		return racer.Lap*len(r.Gates) + gate
+	}
+	sort.SliceStable(standings, func(i, j int) bool {
+		a, b := standings[i], standings[j]
+		switch {
+		case a.Finished != b.Finished:
+			return a.Finished
+		case a.Finished:
+			return a.Place < b.Place
+		case progress(a) != progress(b):
+			return progress(a) > progress(b)
+		}
+		return a.LastPass < b.LastPass
+	})
+	return standings
+}
+
+// stepRace times the gates the racers passed during a step of dt seconds
+// that began at World.Time
+func (w *World) stepRace(dt float64) {
+	race := w.Race
+	if race == nil {
+		return
+	}
+	for _, racer := range race.racers {
+		car := w.Car(racer.CarID)
+		if car == nil {
+			continue // Left the race; its times stand
+		}
+		x, z := car.State.X, car.State.Z
+		if racer.Placed && !racer.Finished {
+			gate := &race.Gates[racer.NextGate]
+			if t, crossed := gate.crossing(racer.PrevX, racer.PrevZ, x, z); crossed {
+				w.passGate(race, racer, w.Time+t*dt)
+			}
+		}
+		racer.PrevX, racer.PrevZ, racer.Placed = x, z, true
+	}
+}
+
+// passGate records a racer passing its next gate at a simulation time
+func (w *World) passGate(race *Race, racer *Racer, at float64) {
+	index := racer.NextGate
+	racer.NextGate = (index + 1) % len(race.Gates)
+	racer.LastPass = at
+	if !racer.Started {
+		racer.Started = true
+		racer.LapStart, racer.SectorStart = at, at
+		return
+	}
+
+	sector := at - racer.SectorStart
+	if best := &racer.BestSectors[len(racer.Sectors)]; *best == 0 || sector < *best {
+		*best = sector
+	}
+	racer.Sectors = append(racer.Sectors, sector)
+	racer.SectorStart = at
+	if index != 0 {
+		return
+	}
+
+	gate := &race.Gates[0]
+	x, z := (gate.X1+gate.X2)/2, (gate.Z1+gate.Z2)/2
+	lap := at - racer.LapStart
+	racer.LastLap = lap
+	if racer.BestLap == 0 || lap < racer.BestLap {
+		racer.BestLap = lap
+	}
+	racer.Lap++
+	racer.Sectors = nil
+	racer.LapStart = at
+	w.pushSimEvent(SimEvent{Kind: SimLapCompleted, ID: racer.CarID, To: racer.Lap, Value: lap, X: x, Z: z})
+
+	if race.Laps > 0 && racer.Lap >= race.Laps {
+		race.Finishers++
+		racer.Finished = true
+		racer.Place = race.Finishers
+		racer.FinishTime = at - race.StartTime
+		w.pushSimEvent(SimEvent{Kind: SimRaceFinished, ID: racer.CarID, To: racer.Place, Value: racer.FinishTime, X: x, Z: z})
+	}
+}
@@ -0,0 +1,283 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Raycast Queries (mouse picking, AI sensors)
+// ============================================================================
+
+const (
+	MAX_RAY_DISTANCE    = 1000.0 // units, longest ray accepted (also used for infinite rays)
+	terrainRayStepScale = 0.5    // Terrain march step as a fraction of the cell size
+	terrainRayBisects   = 20     // Refinement passes once the ray crosses the terrain
+)
+
+// RayHitKind identifies what a ray hit
+type RayHitKind uint8
+
+const (
+	RayHitCollider RayHitKind = iota
+	RayHitTerrain
+)
+
+// rayHitKindNames are the JavaScript-facing names, indexed by RayHitKind
+var rayHitKindNames = [...]string{"collider", "terrain"}
+
+// String returns the JavaScript-facing kind name
+func (k RayHitKind) String() string {
+	if int(k) >= len(rayHitKindNames) {
+		return rayHitKindNames[RayHitCollider]
+	}
+	return rayHitKindNames[k]
+}
+
+// RayHit describes the first surface a ray hits
+type RayHit struct {
+	Kind                      RayHitKind
+	ID                        int // Collider ID, -1 for terrain
+	X, Y, Z                   float64
+	NormalX, NormalY, NormalZ float64
+	Distance                  float64 // Along the normalized ray direction
+}
+
+// Raycast returns the nearest collider or terrain hit within maxDist
+// Colliders are vertical prisms from below the ground up to their Top
+func (w *World) Raycast(ox, oy, oz, dx, dy, dz, maxDist float64) (RayHit, bool) {
+	length := math.Sqrt(dx*dx + dy*dy + dz*dz)
+	if !(length > 1e-12) || !(maxDist > 0) {
+		return RayHit{}, false // Also rejects NaN
+	}
+	dx, dy, dz = dx/length, dy/length, dz/length
+	maxDist = math.Min(maxDist, MAX_RAY_DISTANCE)
+
+	var best RayHit
+	found := false
+	limit := maxDist
+
+	endX, endZ := ox+dx*maxDist, oz+dz*maxDist
+	rayBounds := BoundingBox{
+		MinX: math.Min(ox, endX), MinY: math.Min(oz, endZ),
+		MaxX: math.Max(ox, endX), MaxY: math.Max(oz, endZ),
+	}
+	for _, c := range w.Colliders.Query(rayBounds) {
+		t, nx, ny, nz, hit := c.rayHit(ox, oy, oz, dx, dy, dz)
+		if hit && t <= limit {
+			best = RayHit{Kind: RayHitCollider, ID: c.ID, NormalX: nx, NormalY: ny, NormalZ: nz, Distance: t}
+			found = true
+			limit = t
+		}
+	}
+
+	// Only search the terrain up to the nearest collider hit
+	if t, hit := w.rayVsTerrain(ox, oy, oz, dx, dy, dz, limit); hit {
+		nx, ny, nz := 0.0, 1.0, 0.0
+		if w.Terrain != nil {
+			nx, ny, nz = w.Terrain.GetNormal(ox+dx*t, oz+dz*t)
+		}
+		best = RayHit{Kind: RayHitTerrain, ID: -1, NormalX: nx, NormalY: ny, NormalZ: nz, Distance: t}
+		found = true
+	}
+
+	if found {
+		best.X = ox + dx*best.Distance
+		best.Y = oy + dy*best.Distance
+		best.Z = oz + dz*best.Distance
+	}
+	return best, found
+}
+
+// rayHit intersects a ray with the collider's prism, returning the distance and
+// outward normal; rays starting inside the prism do not hit it
+func (c *Collider) rayHit(ox, oy, oz, dx, dy, dz float64) (t, nx, ny, nz float64, hit bool) {
+	tEnter, tExit, sx, sz, ok := c.rayInterval2D(ox, oz, dx, dz)
+	if !ok || tExit < 0 {
+		return 0, 0, 0, 0, false
+	}
+
+	// Side wall, if the ray is below the top where it enters the footprint
+	if tEnter >= 0 && oy+dy*tEnter <= c.Top {
+		return tEnter, sx, 0, sz, true
+	}
+	if tEnter < 0 && oy <= c.Top {
+		return 0, 0, 0, 0, false // Starts inside
+	}
+
+	// Top face, if the ray descends onto it within the footprint
+	if dy >= 0 {
+		return 0, 0, 0, 0, false
+	}
+	tTop := (c.Top - oy) / dy
+	if tTop >= math.Max(tEnter, 0) && tTop <= tExit {
+		return tTop, 0, 1, 0, true
+	}
+	return 0, 0, 0, 0, false
+}
+
+// rayInterval2D returns the parameter range where the ray's X/Z projection is
+// inside the collider footprint, and the outward normal at entry
+func (c *Collider) rayInterval2D(ox, oz, dx, dz float64) (tEnter, tExit, nx, nz float64, ok bool) {
+	switch c.Shape {
+	case ShapeCircle:
+		return rayCircle2D(ox, oz, dx, dz, c.X, c.Z, c.Radius)
+	case ShapeCapsule:
+		return rayCapsule2D(ox, oz, dx, dz, c)
+	default:
+		return raySlab2D(ox, oz, dx, dz, c.Box.MinX, c.Box.MinY, c.Box.MaxX, c.Box.MaxY)
+	}
+}
+
+// raySlab2D intersects a ray with an axis-aligned rectangle (slab method)
+func raySlab2D(ox, oz, dx, dz, minX, minZ, maxX, maxZ float64) (tEnter, tExit, nx, nz float64, ok bool) {
+	tEnter, tExit = math.Inf(-1), math.Inf(1)
+
+	if dx == 0 {
+		if ox < minX || ox > maxX {
+			return 0, 0, 0, 0, false
+		}
+	} else {
+		t1, t2, n := (minX-ox)/dx, (maxX-ox)/dx, -1.0
+		if t1 > t2 {
+			t1, t2, n = t2, t1, 1
+		}
+		if t1 > tEnter {
+			tEnter, nx, nz = t1, n, 0
+		}
+		tExit = math.Min(tExit, t2)
+	}
+
+	if dz == 0 {
+		if oz < minZ || oz > maxZ {
+			return 0, 0, 0, 0, false
+		}
+	} else {
+		t1, t2, n := (minZ-oz)/dz, (maxZ-oz)/dz, -1.0
+		if t1 > t2 {
+			t1, t2, n = t2, t1, 1
+		}
+		if t1 > tEnter {
+			tEnter, nx, nz = t1, 0, n
+		}
+		tExit = math.Min(tExit, t2)
+	}
+
+	return tEnter, tExit, nx, nz, tEnter <= tExit
+}
+
+// rayCircle2D intersects a ray with a circle
+func rayCircle2D(ox, oz, dx, dz, cx, cz, radius float64) (tEnter, tExit, nx, nz float64, ok bool) {
+	if !(radius > 0) {
+		return 0, 0, 0, 0, false
+	}
+
+	fx, fz := ox-cx, oz-cz
+	a := dx*dx + dz*dz
+	c := fx*fx + fz*fz - radius*radius
+	if a < 1e-12 {
+		// Vertical ray: inside the footprint for its whole length, or never
+		if c > 0 {
+			return 0, 0, 0, 0, false
+		}
+		return math.Inf(-1), math.Inf(1), 0, 0, true
+	}
+
+	b := fx*dx + fz*dz
+	disc := b*b - a*c
+	if disc < 0 {
+		return 0, 0, 0, 0, false
+	}
+	sq := math.Sqrt(disc)
+	tEnter = (-b - sq) / a
+	tExit = (-b + sq) / a
+	return tEnter, tExit, (fx + dx*tEnter) / radius, (fz + dz*tEnter) / radius, true
+}
+
+// rayInterval accumulates the union of overlapping convex shapes along a ray
+type rayInterval struct {
+	enter, exit float64
+	nx, nz      float64 // Outward normal at entry
+	ok          bool
+}
+
+// newRayInterval returns an empty union
+func newRayInterval() rayInterval {
+	return rayInterval{enter: math.Inf(1), exit: math.Inf(-1)}
+}
+
+// merge adds one shape's interval (as returned by raySlab2D/rayCircle2D)
+func (r *rayInterval) merge(enter, exit, nx, nz float64, hit bool) {
+	if !hit {
+		return
+	}
+	if enter < r.enter {
+		r.enter, r.nx, r.nz = enter, nx, nz
+	}
+	r.exit = math.Max(r.exit, exit)
+	r.ok = true
+}
+
+// rayCapsule2D intersects a ray with a capsule: the union of its end circles
+// and the rectangle swept along the segment
+func rayCapsule2D(ox, oz, dx, dz float64, c *Collider) (tEnter, tExit, nx, nz float64, ok bool) {
+	u := newRayInterval()
+	u.merge(rayCircle2D(ox, oz, dx, dz, c.X, c.Z, c.Radius))
+	u.merge(rayCircle2D(ox, oz, dx, dz, c.X2, c.Z2, c.Radius))
+
+	// Rectangle in the segment's local frame (u along the segment, p across it)
+	segX, segZ := c.X2-c.X, c.Z2-c.Z
+	length := math.Sqrt(segX*segX + segZ*segZ)
+	if length > 1e-9 {
+		ux, uz := segX/length, segZ/length
+		px, pz := -uz, ux
+		relX, relZ := ox-c.X, oz-c.Z
+		e, x, nu, np, hit := raySlab2D(
+			relX*ux+relZ*uz, relX*px+relZ*pz,
+			dx*ux+dz*uz, dx*px+dz*pz,
+			0, -c.Radius, length, c.Radius,
+		)
+		u.merge(e, x, nu*ux+np*px, nu*uz+np*pz, hit)
+	}
+
+	return u.enter, u.exit, u.nx, u.nz, u.ok
+}
+
+// rayVsTerrain finds where a ray first meets the ground within maxDist
+// Flat ground (no terrain) is the plane y = 0
+func (w *World) rayVsTerrain(ox, oy, oz, dx, dy, dz, maxDist float64) (float64, bool) {
+	terrain := w.Terrain
+	if terrain == nil {
+		if dy >= 0 || oy < 0 {
+			return 0, false
+		}
+		t := -oy / dy
+		return t, t <= maxDist
+	}
+
+	// Height of the ray above the ground at parameter t
+	above := func(t float64) float64 {
+		return oy + dy*t - terrain.GetHeight(ox+dx*t, oz+dz*t)
+	}
+	if above(0) < 0 {
+		return 0, false // Starts underground
+	}
+
+	step := terrain.CellSize * terrainRayStepScale
+	prev := 0.0
+	for t := step; prev < maxDist; t += step {
+		t = math.Min(t, maxDist)
+		if above(t) <= 0 {
+			// Bisect the crossing between prev and t
+			lo, hi := prev, t
+			for i := 0; i < terrainRayBisects; i++ {
+				mid := (lo + hi) / 2
+				if above(mid) > 0 {
+					lo = mid
+				} else {
+					hi = mid
+				}
+			}
+			return hi, true
+		}
+		prev = t
+	}
+	return 0, false
+}
@@ -0,0 +1,241 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+)
+
+// ============================================================================
+// Input Recording and Replay (ghost cars and reproducible sessions)
+// ============================================================================
+
+// A recording follows one car (usually the player's): it starts with a
+// snapshot of the whole world and then logs, for every Step and every
+// StepCar of that car, the dt and the input the car held. Since the world
+// is deterministic (physics_determinism.go), restoring the snapshot and
+// making the same calls with the same inputs gives bit-identical state, so
+// Replay can reproduce a physics bug exactly and confirm it did by the
+// checksum taken when recording stopped. Only the recorded car's input is
+// logged: sessions that also drive other manual cars or edit the town
+// while recording diverge on replay, which Replay reports.
+//
+// A ghost plays a recording back alongside the live world, in its own
+// world restored from the recording, for the player to race against. It
+// replays at the recorded pace, keeping its world's steps in step with the
+// live steps; StepCar frames play with the world step that follows them
+// (or, in sessions that never stepped the world, set the pace themselves).
+// Neither recordings nor ghosts are part of snapshots.
+
+const RECORDING_VERSION = 1 // Bumped when the format changes incompatibly
+
+// InputFrame is one recorded call that advanced the recorded car
+type InputFrame struct {
+	Dt    float64
+	Input InputState
+	Car   bool // StepCar of the recorded car alone, rather than a world Step
+}
+
+// InputRecording is a world snapshot and the input stream that followed it
+type InputRecording struct {
+	Version  int
+	CarID    int
+	Start    WorldSnapshot
+	Frames   []InputFrame
+	Checksum uint64 `json:",string"` // State hash when recording stopped
+}
+
+// Ghost is a recording being played back next to the live world
+type Ghost struct {
+	ID        int
+	Recording *InputRecording
+	world     *World  // Restored from the recording's start
+	frame     int     // Next frame to play
+	elapsed   float64 // Live seconds since the ghost was added
+	played    float64 // Recorded seconds played
+	carClock  bool    // Paced by StepCar frames (the session never stepped the world)
+}
+
+// StartRecording begins recording a car's input, replacing any recording
+// in progress; returns false if the car does not exist
+func (w *World) StartRecording(carID int) bool {
+	if w.Car(carID) == nil {
+		return false
+	}
+	w.Recording = &InputRecording{Version: RECORDING_VERSION, CarID: carID, Start: w.Snapshot()}
+	return true
+}
+
+// StopRecording ends the recording in progress and returns it, or nil if
+// nothing was being recorded
+func (w *World) StopRecording() (*InputRecording, error) {
+	rec := w.Recording
+	if rec == nil {
+		return nil, nil
+	}
+	w.Recording = nil
+	sum, err := w.Checksum()
+	if err != nil {
+		return nil, err
+	}
+	rec.Checksum = sum
+	return rec, nil
+}
+
+// recordFrame logs the recorded car's held input for a step; a step after
+// the car was removed is logged with no input, keeping the world's steps
+func (w *World) recordFrame(dt float64, carOnly bool) {
+	rec := w.Recording
+	f := InputFrame{Dt: dt, Car: carOnly}
+	if car := w.Car(rec.CarID); car != nil {
+		f.Input = car.Input
+	}
+	rec.Frames = append(rec.Frames, f)
+}
+
+// playFrame makes a recorded call with the recorded car's input
+func (w *World) playFrame(carID int, f InputFrame) {
+	if car := w.Car(carID); car != nil {
+		car.Input = f.Input
+	}
+	if f.Car {
+		w.StepCar(carID, f.Dt)
+	} else {
+		w.Step(f.Dt)
+	}
+}
+
+// restoreRecording creates a world in the state a recording started from
+func restoreRecording(rec *InputRecording) (*World, error) {
+	if rec.Version != RECORDING_VERSION {
+		return nil, fmt.Errorf("unsupported recording version %d (expected %d)", rec.Version, RECORDING_VERSION)
+	}
+	w := NewWorld()
+	w.Autosaves.Slots = 0 // Replays are not the player's town
+	if err := w.Restore(rec.Start); err != nil {
+		return nil, err
+	}
+	return w, nil
+}
+
+// Replay re-simulates a whole recording in a new world and returns it;
+// matched reports whether it ended in the recorded state
+func Replay(rec *InputRecording) (w *World, matched bool, err error) {
+	w, err = restoreRecording(rec)
+	if err != nil {
+		return nil, false, err
+	}
+	for _, f := range rec.Frames {
+		w.playFrame(rec.CarID, f)
+	}
+	sum, err := w.Checksum()
+	if err != nil {
+		return w, false, err
+	}
+	return w, sum == rec.Checksum, nil
+}
+
+// AddGhost starts playing a recording back as a ghost, replacing the ghost
+// with the same ID
+func (w *World) AddGhost(id int, rec *InputRecording) error {
+	world, err := restoreRecording(rec)
+	if err != nil {
+		return err
+	}
+	if world.Car(rec.CarID) == nil {
+		return errors.New("recording does not start with its car")
+	}
+	g := &Ghost{ID: id, Recording: rec, world: world, carClock: true}
+	for _, f := range rec.Frames {
+		if !f.Car {
+			g.carClock = false
+			break
+		}
+	}
+
+	for i, other := range w.ghosts {
+		if other.ID == id {
+			w.ghosts[i] = g
+			return nil
+		}
+	}
+	w.ghosts = append(w.ghosts, g)
+	return nil
+}
+
+// RemoveGhost stops a ghost, returning false if it does not exist
+func (w *World) RemoveGhost(id int) bool {
+	for i, g := range w.ghosts {
+		if g.ID == id {
+			w.ghosts = append(w.ghosts[:i], w.ghosts[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// Ghosts returns every ghost in the order they were added
+func (w *World) Ghosts() []*Ghost {
+	return w.ghosts
+}
+
+// Car returns the ghost car's current state, or nil once its world has
+// removed it
+func (g *Ghost) Car() *CarState {
+	if car := g.world.Car(g.Recording.CarID); car != nil {
+		return &car.State
+	}
+	return nil
+}
+
+// Finished reports whether every frame has been played
+func (g *Ghost) Finished() bool {
+	return g.frame >= len(g.Recording.Frames)
+}
+
+// Progress returns the fraction of the recording played, 0..1
+func (g *Ghost) Progress() float64 {
+	if len(g.Recording.Frames) == 0 {
+		return 1
+	}
+	return float64(g.frame) / float64(len(g.Recording.Frames))
+}
+
+// step plays the frames due after dt more live seconds
+func (g *Ghost) step(dt float64) {
+	g.elapsed += dt
+	frames := g.Recording.Frames
+	for g.frame < len(frames) {
+		f := frames[g.frame]
+		paced := f.Car == g.carClock
+		if paced && g.played+f.Dt > g.elapsed+1e-9 { // Slack for rounding in the sums
+			break
+		}
+		g.world.playFrame(g.Recording.CarID, f)
+		if paced {
+			g.played += f.Dt
+		}
+		g.frame++
+	}
+}
+
+// stepGhosts advances every ghost by dt live seconds
+func (w *World) stepGhosts(dt float64) {
+	for _, g := range w.ghosts {
+		g.step(dt)
+	}
+}
+
+// RecordingJSON encodes a recording as JSON
+func RecordingJSON(rec *InputRecording) ([]byte, error) {
+	return json.Marshal(rec)
+}
+
+// RecordingFromJSON decodes RecordingJSON output
+func RecordingFromJSON(data []byte) (*InputRecording, error) {
+	var rec InputRecording
+	if err := json.Unmarshal(data, &rec); err != nil {
+		return nil, err
+	}
+	return &rec, nil
+}
@@ -0,0 +1,172 @@
+package main
+
+import (
+	"math"
+	"sort"
+)
+
+// ============================================================================
+// Road Features (speed bumps and potholes)
+// ============================================================================
+
+// JS places small features on the road: a speed bump is a strip Length long
+// lying along Heading, a pothole usually a round one (Length 0), both with a
+// half-width of Radius. A car driving onto one is jolted vertically by
+// speed × Height / Radius (the slope it is thrown up or dropped by: up for
+// bumps, down for potholes), loses a share of its speed that grows with the
+// jolt and it then has to accelerate back, and a "road_feature" collision
+// event is queued (physics_events.go) with the crossing speed, for camera
+// shake and sound. The jolt sets the body bouncing on its suspension
+// (physics_suspension.go).
+
+const (
+	SPEED_BUMP_HEIGHT      = 0.1  // units, default speed bump height
+	POTHOLE_DEPTH          = 0.08 // units, default pothole depth
+	ROAD_FEATURE_RADIUS    = 0.5  // units, default half-width across the direction of travel
+	FEATURE_SPEED_LOSS     = 0.08 // Fraction of speed lost per units/s of jolt
+	FEATURE_MAX_SPEED_LOSS = 0.5  // Most of its speed a car loses to one feature
+)
+
+// RoadFeatureKind identifies a road feature
+type RoadFeatureKind uint8
+
+const (
+	FeatureSpeedBump RoadFeatureKind = iota
+	FeaturePothole
+	roadFeatureKindCount
+)
+
+// roadFeatureKindNames are the JavaScript-facing names, indexed by RoadFeatureKind
+var roadFeatureKindNames = [roadFeatureKindCount]string{"speed_bump", "pothole"}
+
+// String returns the JavaScript-facing kind name
+func (k RoadFeatureKind) String() string {
+	if k >= roadFeatureKindCount {
+		return roadFeatureKindNames[FeatureSpeedBump]
+	}
+	return roadFeatureKindNames[k]
+}
+
+// roadFeatureKindFromString parses a JavaScript-facing kind name
+func roadFeatureKindFromString(name string) (RoadFeatureKind, bool) {
+	for k, n := range roadFeatureKindNames {
+		if n == name {
+			return RoadFeatureKind(k), true
+		}
+	}
+	return FeatureSpeedBump, false
+}
+
+// RoadFeature is a speed bump or pothole
+type RoadFeature struct {
+	ID      int
+	Kind    RoadFeatureKind
+	X, Z    float64 // Center
+	Heading float64 // rad, the direction the strip lies along
+	Length  float64 // units along Heading, 0 for a round feature
+	Radius  float64 // units, half-width of the strip
+	Height  float64 // units, bump height or pothole depth
+}
+
+// defaultHeight returns the height (or depth) of a feature kind
+func (k RoadFeatureKind) defaultHeight() float64 {
+	if k == FeaturePothole {
+		return POTHOLE_DEPTH
+	}
+	return SPEED_BUMP_HEIGHT
+}
+
+// sanitized returns the feature with non-positive sizes defaulted
+func (f RoadFeature) sanitized() RoadFeature {
+	f.Radius = positiveOr(f.Radius, ROAD_FEATURE_RADIUS)
+	f.Height = positiveOr(f.Height, f.Kind.defaultHeight())
+	if !(f.Length > 0) || math.IsInf(f.Length, 1) {
+		f.Length = 0
+	}
+	return f
+}
+
+// covers reports whether a point lies on the feature
+func (f *RoadFeature) covers(x, z float64) bool {
+	dx, dz := math.Sin(f.Heading), math.Cos(f.Heading)
+	relX, relZ := x-f.X, z-f.Z
+	along := clamp(relX*dx+relZ*dz, -f.Length/2, f.Length/2)
+	return simHypot(relX-dx*along, relZ-dz*along) <= f.Radius
+}
+
+// RoadFeatures holds the features placed on the roads
+type RoadFeatures struct {
+	features []*RoadFeature // In ID order
+}
+
+// NewRoadFeatures creates an empty feature set
+func NewRoadFeatures() *RoadFeatures {
+	return &RoadFeatures{}
+}
+
+// Features returns the road features in ID order
+func (r *RoadFeatures) Features() []*RoadFeature {
+	return r.features
+}
+
+// Set adds a feature, or replaces the feature with the same ID
+func (r *RoadFeatures) Set(f RoadFeature) {
+	f = f.sanitized()
+	i := sort.Search(len(r.features), func(i int) bool { return r.features[i].ID >= f.ID })
+	if i < len(r.features) && r.features[i].ID == f.ID {
+		*r.features[i] = f
+		return
+	}
+	r.features = append(r.features, nil)
+	copy(r.features[i+1:], r.features[i:])
+	r.features[i] = &f
+}
+
+// Remove removes a feature, returning false if it does not exist
+func (r *RoadFeatures) Remove(id int) bool {
+	for i, f := range r.features {
+		if f.ID == id {
+			r.features = append(r.features[:i], r.features[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// Clear removes every feature
+func (r *RoadFeatures) Clear() {
+	r.features = nil
+}
+
+// crossRoadFeatures jolts a car for every feature it drove onto in a
+// substep that started at prevX, prevZ
+func (w *World) crossRoadFeatures(car *CarState, prevX, prevZ float64) {
+	for _, f := range w.Features.features {
+		if !f.covers(car.X, car.Z) || f.covers(prevX, prevZ) {
+			continue
+		}
+		speed := simHypot(car.VelocityX, car.VelocityZ)
+		back := math.Max(speed, 1e-9)
+		normalX, normalZ := -car.VelocityX/back, -car.VelocityZ/back
+		jolt := speed * f.Height / f.Radius
+		if f.Kind == FeaturePothole {
+			car.BounceVelocity -= jolt
+		} else {
+			car.BounceVelocity += jolt
+		}
+		keep := 1 - math.Min(jolt*FEATURE_SPEED_LOSS, FEATURE_MAX_SPEED_LOSS)
+		car.VelocityX *= keep
+		car.VelocityZ *= keep
+
+		w.pushCollisionEvent(CollisionEvent{
+			Kind:        CollisionRoadFeature,
+			A:           car.ID,
+			B:           f.ID,
+			ImpactSpeed: speed,
+			PointX:      car.X,
+			PointZ:      car.Z,
+			NormalX:     normalX,
+			NormalZ:     normalZ,
+		})
+	}
+}
@@ -0,0 +1,320 @@
+package main
+
+import (
+	"math"
+	"math/rand"
+)
+
+// ============================================================================
+// Procedural Road Networks (grid, radial and organic layouts)
+// ============================================================================
+
+// GenerateRoadNetwork replaces the road graph with a seeded layout: a grid of
+// blocks, rings joined by spokes, or an organic network grown L-system style
+// from the center, where each segment continues roughly straight and
+// occasionally branches at right angles, snapping to nearby intersections.
+// Main roads get right of way and arterial crossings get traffic signals, so
+// a generated map is ready for AI traffic straight away.
+
+const (
+	ROAD_GEN_FIRST_NODE = 1    // ID of the first generated node
+	ROAD_GEN_MAX_NODES  = 4096 // Hard cap on generated intersections
+	ARTERIAL_EVERY      = 4    // Grid lines between arterial roads
+	ARTERIAL_SPEED      = 1.5  // Arterial speed limit as a multiple of the street limit
+	ORGANIC_MAX_TURN    = 0.35 // rad, largest bend between organic segments
+	ORGANIC_SNAP        = 0.6  // Share of the spacing within which segments join an existing node
+)
+
+// RoadStyle is the layout a road network is generated in
+type RoadStyle uint8
+
+const (
+	RoadStyleGrid RoadStyle = iota
+	RoadStyleRadial
+	RoadStyleOrganic
+	roadStyleCount
+)
+
+// roadStyleNames are the JavaScript-facing names, indexed by RoadStyle
+var roadStyleNames = [roadStyleCount]string{"grid", "radial", "organic"}
+
+// String returns the JavaScript-facing style name
+func (s RoadStyle) String() string {
+	if s >= roadStyleCount {
+		return roadStyleNames[RoadStyleGrid]
+	}
+	return roadStyleNames[s]
+}
+
+// roadStyleFromString parses a style name, reporting whether it is known
+func roadStyleFromString(name string) (RoadStyle, bool) {
+	for i, n := range roadStyleNames {
+		if n == name {
+			return RoadStyle(i), true
+		}
+	}
+	return RoadStyleGrid, false
+}
+
+// RoadGenConfig controls procedural road networks
+type RoadGenConfig struct {
+	Style        RoadStyle
+	Seed         int64
+	Extent       float64 // units, half-size of the square the network covers
+	Spacing      float64 // units, block size (grid), ring gap (radial) or segment length (organic)
+	Spokes       int     // Radial roads leaving the center (radial)
+	Iterations   int     // Segments grown (organic)
+	BranchChance float64 // 0-1, chance a segment sprouts a side street (organic)
+	Jitter       float64 // 0-1, random node offset as a share of half the spacing
+	SpeedLimit   float64 // units/s on ordinary streets
+	Lanes        int     // Lanes per direction
+}
+
+// DefaultRoadGenConfig returns a grid sized to the default ground
+func DefaultRoadGenConfig() RoadGenConfig {
+	return RoadGenConfig{
+		Style:        RoadStyleGrid,
+		Extent:       9,
+		Spacing:      3,
+		Spokes:       6,
+		Iterations:   120,
+		BranchChance: 0.3,
+		SpeedLimit:   DEFAULT_SPEED_LIMIT,
+		Lanes:        DEFAULT_LANES,
+	}
+}
+
+// sanitized replaces invalid settings with their defaults
+func (c RoadGenConfig) sanitized() RoadGenConfig {
+	d := DefaultRoadGenConfig()
+	if c.Style >= roadStyleCount {
+		c.Style = d.Style
+	}
+	c.Extent = positiveOr(c.Extent, d.Extent)
+	c.Spacing = positiveOr(c.Spacing, d.Spacing)
+	if c.Spacing > c.Extent {
+		c.Spacing = c.Extent
+	}
+	if c.Spokes < 3 {
+		c.Spokes = d.Spokes
+	}
+	if c.Iterations < 1 {
+		c.Iterations = d.Iterations
+	}
+	c.BranchChance = clamp(c.BranchChance, 0, 1)
+	c.Jitter = clamp(c.Jitter, 0, 1)
+	c.SpeedLimit = positiveOr(c.SpeedLimit, d.SpeedLimit)
+	if c.Lanes < 1 {
+		c.Lanes = d.Lanes
+	}
+	return c
+}
+
+// RoadGenResult summarizes a generated network
+type RoadGenResult struct {
+	Nodes   int
+	Roads   int // Two-way roads (each is two edges)
+	Signals int
+}
+
+// roadBuilder adds nodes and two-way roads to a graph with sequential IDs
+type roadBuilder struct {
+	graph  *RoadGraph
+	cfg    *RoadGenConfig
+	rng    *rand.Rand
+	nextID int
+	roads  int
+}
+
+// node adds an intersection near (x, z), offset by the configured jitter
+func (b *roadBuilder) node(x, z float64) int {
+	if b.cfg.Jitter > 0 {
+		r := b.cfg.Jitter * b.cfg.Spacing / 2
+		x += (b.rng.Float64()*2 - 1) * r
+		z += (b.rng.Float64()*2 - 1) * r
+	}
+	id := b.nextID
+	b.nextID++
+	b.graph.AddNode(id, x, z)
+	return id
+}
+
+// connected reports whether a road already joins two nodes
+func (b *roadBuilder) connected(from, to int) bool {
+	for _, id := range b.graph.Node(from).Out {
+		if b.graph.Edge(id).To == to {
+			return true
+		}
+	}
+	return false
+}
+
+// road joins two nodes both ways; arterials are faster and have right of way
+func (b *roadBuilder) road(from, to int, arterial bool) {
+	if from == to || b.connected(from, to) {
+		return
+	}
+	speed, priority := b.cfg.SpeedLimit, 0
+	if arterial {
+		speed, priority = speed*ARTERIAL_SPEED, 1
+	}
+	forward, reverse, err := b.graph.AddRoad(from, to, speed, b.cfg.Lanes, true)
+	if err != nil {
+		return
+	}
+	b.graph.SetPriority(forward, priority)
+	b.graph.SetPriority(reverse, priority)
+	b.roads++
+}
+
+// grid lays out square blocks, with every ARTERIAL_EVERY-th line (and the
+// center lines) as arterials
+func (b *roadBuilder) grid() {
+	cfg := b.cfg
+	n := int(2*cfg.Extent/cfg.Spacing) + 1
+	for n*n > ROAD_GEN_MAX_NODES {
+		n--
+	}
+	start := -float64(n-1) * cfg.Spacing / 2
+	center := (n - 1) / 2
+	arterial := func(line int) bool { return (line-center)%ARTERIAL_EVERY == 0 }
+
+	ids := make([]int, n*n)
+	for iz := 0; iz < n; iz++ {
+		for ix := 0; ix < n; ix++ {
+			ids[iz*n+ix] = b.node(start+float64(ix)*cfg.Spacing, start+float64(iz)*cfg.Spacing)
+		}
+	}
+	for iz := 0; iz < n; iz++ {
+		for ix := 0; ix < n; ix++ {
+			if ix+1 < n {
+				b.road(ids[iz*n+ix], ids[iz*n+ix+1], arterial(iz))
+			}
+			if iz+1 < n {
+				b.road(ids[iz*n+ix], ids[(iz+1)*n+ix], arterial(ix))
+			}
+		}
+	}
+}
+
+// radial lays out concentric rings joined by arterial spokes from the center
+func (b *roadBuilder) radial() {
+	cfg := b.cfg
+	rings := int(cfg.Extent / cfg.Spacing)
+	for rings*cfg.Spokes+1 > ROAD_GEN_MAX_NODES {
+		rings--
+	}
+	offset := b.rng.Float64() * 2 * math.Pi / float64(cfg.Spokes)
+
+	center := b.node(0, 0)
+	previous := make([]int, cfg.Spokes)
+	for i := range previous {
+		previous[i] = center
+	}
+	for r := 1; r <= rings; r++ {
+		radius := float64(r) * cfg.Spacing
+		ring := make([]int, cfg.Spokes)
+		for i := range ring {
+			angle := offset + 2*math.Pi*float64(i)/float64(cfg.Spokes)
+			ring[i] = b.node(radius*math.Cos(angle), radius*math.Sin(angle))
+			b.road(previous[i], ring[i], true)
+		}
+		for i := range ring {
+			b.road(ring[i], ring[(i+1)%len(ring)], false)
+		}
+		previous = ring
+	}
+}
+
+// organicSegment is a pending road growing from a node
+type organicSegment struct {
+	from     int
+	heading  float64 // rad, direction of growth
+	arterial bool
+}
+
+// organic grows roads outward from the center in the four compass
+// directions; each segment bends slightly, may branch at right angles and
+// stops when it leaves the extent or joins an existing intersection
+func (b *roadBuilder) organic() {
+	cfg := b.cfg
+	snap := cfg.Spacing * ORGANIC_SNAP
+	center := b.node(0, 0)
+	base := b.rng.Float64() * math.Pi / 2
+	queue := make([]organicSegment, 0, cfg.Iterations)
+	for i := 0; i < 4; i++ {
+		queue = append(queue, organicSegment{from: center, heading: base + float64(i)*math.Pi/2, arterial: true})
+	}
+
+	for grown := 0; grown < cfg.Iterations && len(queue) > 0 && b.graph.NodeCount() < ROAD_GEN_MAX_NODES; grown++ {
+		seg := queue[0]
+		queue = queue[1:]
+
+		from := b.graph.Node(seg.from)
+		heading := seg.heading + (b.rng.Float64()*2-1)*ORGANIC_MAX_TURN
+		length := cfg.Spacing * (0.8 + 0.4*b.rng.Float64())
+		x := from.X + length*math.Cos(heading)
+		z := from.Z + length*math.Sin(heading)
+		if math.Abs(x) > cfg.Extent || math.Abs(z) > cfg.Extent {
+			continue
+		}
+
+		// Join an existing intersection instead of crowding it
+		if near := b.graph.NearestNode(x, z); near != nil && near.ID != seg.from && simHypot(near.X-x, near.Z-z) < snap {
+			b.road(seg.from, near.ID, seg.arterial)
+			continue
+		}
+
+		to := b.nextID
+		b.nextID++
+		b.graph.AddNode(to, x, z)
+		b.road(seg.from, to, seg.arterial)
+
+		queue = append(queue, organicSegment{from: to, heading: heading, arterial: seg.arterial})
+		for _, side := range []float64{-1, 1} {
+			if b.rng.Float64() < cfg.BranchChance {
+				queue = append(queue, organicSegment{from: to, heading: heading + side*math.Pi/2})
+			}
+		}
+	}
+}
+
+// GenerateRoadNetwork replaces the road graph with a generated layout,
+// clearing signals, generated traffic and edit history that referred to the
+// old one
+func (w *World) GenerateRoadNetwork(cfg RoadGenConfig) RoadGenResult {
+	cfg = cfg.sanitized()
+	w.ClearTrips()
+	w.ClearCitizens()
+	w.Roads.Clear()
+	w.History.Clear()
+	for id := range w.Signals {
+		delete(w.Signals, id)
+	}
+
+	b := &roadBuilder{graph: w.Roads, cfg: &cfg, rng: rand.New(rand.NewSource(cfg.Seed)), nextID: ROAD_GEN_FIRST_NODE}
+	switch cfg.Style {
+	case RoadStyleRadial:
+		b.radial()
+	case RoadStyleOrganic:
+		b.organic()
+	default:
+		b.grid()
+	}
+
+	// Signalize intersections where two arterials cross
+	result := RoadGenResult{Nodes: w.Roads.NodeCount(), Roads: b.roads}
+	for _, id := range w.Roads.NodeIDs() {
+		n := w.Roads.Node(id)
+		arterials := 0
+		for _, edgeID := range n.In {
+			if w.Roads.Edge(edgeID).Priority > 0 {
+				arterials++
+			}
+		}
+		if arterials >= 3 && w.SetSignal(id, nil, 0, DEFAULT_YELLOW_TIME) == nil {
+			result.Signals++
+		}
+	}
+	return result
+}
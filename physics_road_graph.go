@@ -0,0 +1,233 @@
+package main
+
+import (
+	"errors"
+	"math"
+	"sort"
+)
+
+// ============================================================================
+// Road Network Graph
+// ============================================================================
+
+// The road graph is the shared map for AI drivers, citizens and transit:
+// nodes are intersections (or road ends) and edges are directed road
+// segments. A two-way road is a pair of edges, one in each direction.
+
+const (
+	DEFAULT_SPEED_LIMIT = 8.0 // units/s, used when a segment does not specify one
+	DEFAULT_LANES       = 1   // Lanes per direction
+	roadGraphInitialCap = 128
+)
+
+// RoadNode is an intersection or road end
+type RoadNode struct {
+	ID   int
+	X, Z float64
+	Out  []int // IDs of edges leaving this node, in insertion order
+	In   []int // IDs of edges arriving at this node, in insertion order
+}
+
+// RoadEdge is a directed road segment between two nodes
+type RoadEdge struct {
+	ID         int
+	From, To   int     // Node IDs
+	Length     float64 // units
+	SpeedLimit float64 // units/s
+	Lanes      int     // Lanes in this direction
+	Priority   int     // Right of way at unsignalized intersections (higher = major road)
+	BikeLane   bool    // A bike lane right of the lanes (physics_two_wheelers.go)
+}
+
+// RoadGraph is a directed graph of road segments
+type RoadGraph struct {
+	nodes      map[int]*RoadNode
+	edges      map[int]*RoadEdge
+	nextEdgeID int
+}
+
+// NewRoadGraph creates an empty road graph
+func NewRoadGraph() *RoadGraph {
+	return &RoadGraph{
+		nodes: make(map[int]*RoadNode, roadGraphInitialCap),
+		edges: make(map[int]*RoadEdge, roadGraphInitialCap),
+	}
+}
+
+// Clear removes every node and edge
+func (g *RoadGraph) Clear() {
+	g.nodes = make(map[int]*RoadNode, roadGraphInitialCap)
+	g.edges = make(map[int]*RoadEdge, roadGraphInitialCap)
+	g.nextEdgeID = 0
+}
+
+// AddNode adds an intersection, or moves an existing one (its edges keep
+// their connections and have their lengths recomputed)
+func (g *RoadGraph) AddNode(id int, x, z float64) *RoadNode {
+	if n, exists := g.nodes[id]; exists {
+		n.X, n.Z = x, z
+		for _, edgeID := range n.Out {
+			g.edges[edgeID].Length = g.distance(g.edges[edgeID].From, g.edges[edgeID].To)
+		}
+		for _, edgeID := range n.In {
+			g.edges[edgeID].Length = g.distance(g.edges[edgeID].From, g.edges[edgeID].To)
+		}
+		return n
+	}
+
+	n := &RoadNode{ID: id, X: x, Z: z}
+	g.nodes[id] = n
+	return n
+}
+
+// RemoveNode removes an intersection and every edge touching it
+func (g *RoadGraph) RemoveNode(id int) bool {
+	n, exists := g.nodes[id]
+	if !exists {
+		return false
+	}
+
+	// Copy: RemoveEdge edits these slices
+	touching := append(append([]int(nil), n.Out...), n.In...)
+	for _, edgeID := range touching {
+		g.RemoveEdge(edgeID)
+	}
+	delete(g.nodes, id)
+	return true
+}
+
+// distance returns the straight-line distance between two nodes
+func (g *RoadGraph) distance(from, to int) float64 {
+	a, b := g.nodes[from], g.nodes[to]
+	return simHypot(b.X-a.X, b.Z-a.Z)
+}
+
+// AddEdge adds a directed segment and returns its ID
+// Length is the straight-line node distance; speedLimit and lanes fall back
+// to DEFAULT_SPEED_LIMIT and DEFAULT_LANES when not positive
+func (g *RoadGraph) AddEdge(from, to int, speedLimit float64, lanes int) (int, error) {
+	fromNode, okFrom := g.nodes[from]
+	toNode, okTo := g.nodes[to]
+	if !okFrom || !okTo {
+		return -1, errors.New("road segment endpoints must be existing nodes")
+	}
+	if from == to {
+		return -1, errors.New("road segment cannot start and end at the same node")
+	}
+
+	e := &RoadEdge{
+		ID:         g.nextEdgeID,
+		From:       from,
+		To:         to,
+		Length:     g.distance(from, to),
+		SpeedLimit: positiveOr(speedLimit, DEFAULT_SPEED_LIMIT),
+		Lanes:      DEFAULT_LANES,
+	}
+	if lanes > 0 {
+		e.Lanes = lanes
+	}
+	g.nextEdgeID++
+
+	g.edges[e.ID] = e
+	fromNode.Out = append(fromNode.Out, e.ID)
+	toNode.In = append(toNode.In, e.ID)
+	return e.ID, nil
+}
+
+// AddRoad adds a road between two nodes: one edge, or two for a two-way road
+// Returns the forward edge ID and the reverse edge ID (-1 for one-way roads)
+func (g *RoadGraph) AddRoad(from, to int, speedLimit float64, lanes int, twoWay bool) (forward, reverse int, err error) {
+	forward, err = g.AddEdge(from, to, speedLimit, lanes)
+	if err != nil || !twoWay {
+		return forward, -1, err
+	}
+	reverse, err = g.AddEdge(to, from, speedLimit, lanes)
+	return forward, reverse, err
+}
+
+// SetPriority sets a segment's right-of-way priority, returning false if it does not exist
+func (g *RoadGraph) SetPriority(id, priority int) bool {
+	e, exists := g.edges[id]
+	if !exists {
+		return false
+	}
+	e.Priority = priority
+	return true
+}
+
+// RemoveEdge removes a directed segment
+func (g *RoadGraph) RemoveEdge(id int) bool {
+	e, exists := g.edges[id]
+	if !exists {
+		return false
+	}
+
+	from, to := g.nodes[e.From], g.nodes[e.To]
+	from.Out = removeID(from.Out, id)
+	to.In = removeID(to.In, id)
+	delete(g.edges, id)
+	return true
+}
+
+// removeID deletes the first occurrence of id, keeping the order of the rest
+func removeID(ids []int, id int) []int {
+	for i, v := range ids {
+		if v == id {
+			return append(ids[:i], ids[i+1:]...)
+		}
+	}
+	return ids
+}
+
+// Node returns the node with the given ID, or nil
+func (g *RoadGraph) Node(id int) *RoadNode {
+	return g.nodes[id]
+}
+
+// Edge returns the edge with the given ID, or nil
+func (g *RoadGraph) Edge(id int) *RoadEdge {
+	return g.edges[id]
+}
+
+// NodeCount returns the number of nodes
+func (g *RoadGraph) NodeCount() int {
+	return len(g.nodes)
+}
+
+// EdgeCount returns the number of edges
+func (g *RoadGraph) EdgeCount() int {
+	return len(g.edges)
+}
+
+// NodeIDs returns every node ID in ascending order (stable for iteration)
+func (g *RoadGraph) NodeIDs() []int {
+	ids := make([]int, 0, len(g.nodes))
+	for id := range g.nodes {
+		ids = append(ids, id)
+	}
+	sort.Ints(ids)
+	return ids
+}
+
+// EdgeIDs returns every edge ID in ascending order (stable for iteration)
+func (g *RoadGraph) EdgeIDs() []int {
+	ids := make([]int, 0, len(g.edges))
+	for id := range g.edges {
+		ids = append(ids, id)
+	}
+	sort.Ints(ids)
+	return ids
+}
+
+// NearestNode returns the node closest to a world position (nil if the graph is empty)
+func (g *RoadGraph) NearestNode(x, z float64) *RoadNode {
+	var nearest *RoadNode
+	best := math.Inf(1)
+	for _, id := range g.NodeIDs() {
+		n := g.nodes[id]
+		if d := simHypot(n.X-x, n.Z-z); d < best {
+			nearest, best = n, d
+		}
+	}
+	return nearest
+}
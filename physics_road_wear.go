@@ -0,0 +1,221 @@
+package main
+
+import (
+	"math"
+	"sort"
+)
+
+// ============================================================================
+// Road Condition and Maintenance
+// ============================================================================
+
+// Every road segment wears as AI cars drive along it and, more slowly, while
+// it rains or snows. A worn segment lowers the top speed and grip of cars on
+// it until it is repaired, which the treasury pays for (physics_economy.go)
+// in proportion to the segment's length and wear. Wear is kept per pair of
+// road nodes rather than per edge, since JS rebuilds the graph (and its edge
+// IDs) whenever roads change, and both directions of a two-way road share it.
+
+const (
+	ROAD_PASSAGE_WEAR = 0.002 // Wear a car adds to each segment it drives the length of
+	ROAD_WEATHER_WEAR = 0.004 // Wear per game hour of heavy rain (snow wears twice as fast)
+	ROAD_SPEED_LOSS   = 0.4   // Share of top speed lost on a worn-out road
+	ROAD_GRIP_LOSS    = 0.3   // Share of lateral grip lost on a worn-out road
+	ROAD_REPAIR_COST  = 25.0  // Cost per unit of length to repair a worn-out road
+	ROAD_WEAR_REACH   = 2.0   // units, how far from a segment's centerline a car is on it
+)
+
+// roadKey identifies a road segment by its end nodes, lower ID first
+type roadKey struct{ A, B int }
+
+// keyOf returns the key of the segment between two nodes, in either direction
+func keyOf(from, to int) roadKey {
+	if from > to {
+		from, to = to, from
+	}
+	return roadKey{from, to}
+}
+
+// RoadWear tracks the condition of every road segment
+type RoadWear struct {
+	PassageWear float64
+	WeatherWear float64
+	SpeedLoss   float64
+	GripLoss    float64
+	RepairCost  float64 // Per unit of length at full wear
+
+	wear map[roadKey]float64 // 0 (new) to 1 (worn out); missing segments are new
+}
+
+// NewRoadWear creates road wear with every segment in new condition
+func NewRoadWear() *RoadWear {
+	return &RoadWear{
+		PassageWear: ROAD_PASSAGE_WEAR,
+		WeatherWear: ROAD_WEATHER_WEAR,
+		SpeedLoss:   ROAD_SPEED_LOSS,
+		GripLoss:    ROAD_GRIP_LOSS,
+		RepairCost:  ROAD_REPAIR_COST,
+		wear:        make(map[roadKey]float64),
+	}
+}
+
+// RoadSegment is one road between two nodes and its wear
+type RoadSegment struct {
+	From, To int // Node IDs, From < To
+	Length   float64
+	Wear     float64
+}
+
+// Condition returns how intact the segment is, 1 (new) to 0 (worn out)
+func (s RoadSegment) Condition() float64 {
+	return 1 - s.Wear
+}
+
+// segmentLength returns the length of the road between a segment's nodes,
+// and false if no edge joins them in either direction
+func (w *World) segmentLength(k roadKey) (float64, bool) {
+	for _, pair := range [2][2]int{{k.A, k.B}, {k.B, k.A}} {
+		n := w.Roads.Node(pair[0])
+		if n == nil {
+			continue
+		}
+		for _, id := range n.Out {
+			if e := w.Roads.Edge(id); e.To == pair[1] {
+				return e.Length, true
+			}
+		}
+	}
+	return 0, false
+}
+
+// RoadSegments returns every road segment once, ordered by node IDs
+func (w *World) RoadSegments() []RoadSegment {
+	seen := make(map[roadKey]bool, w.Roads.EdgeCount())
+	var segments []RoadSegment
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		k := keyOf(e.From, e.To)
+		if seen[k] {
+			continue
+		}
+		seen[k] = true
+		segments = append(segments, RoadSegment{From: k.A, To: k.B, Length: e.Length, Wear: w.RoadWear.wear[k]})
+	}
+	sort.Slice(segments, func(i, j int) bool {
+		if segments[i].From != segments[j].From {
+			return segments[i].From < segments[j].From
+		}
+		return segments[i].To < segments[j].To
+	})
+	return segments
+}
+
+// wearRoad adds wear to the segment between two nodes
+func (w *World) wearRoad(from, to int, amount float64) {
+	if amount <= 0 {
+		return
+	}
+	k := keyOf(from, to)
+	w.RoadWear.wear[k] = math.Min(w.RoadWear.wear[k]+amount, 1)
+}
+
+// SetRoadWear sets the wear (0-1) of the segment between two nodes,
+// returning false if no road joins them
+func (w *World) SetRoadWear(from, to int, wear float64) bool {
+	k := keyOf(from, to)
+	if _, ok := w.segmentLength(k); !ok {
+		return false
+	}
+	if wear = clamp(wear, 0, 1); wear > 0 {
+		w.RoadWear.wear[k] = wear
+	} else {
+		delete(w.RoadWear.wear, k)
+	}
+	return true
+}
+
+// RepairCostOf returns what repairing a segment costs
+func (w *World) RepairCostOf(s RoadSegment) float64 {
+	return s.Length * s.Wear * w.RoadWear.RepairCost
+}
+
+// RepairRoad restores the segment between two nodes to new, returning the
+// cost, or false (repairing nothing) if there is no such road or the
+// treasury cannot afford it
+func (w *World) RepairRoad(from, to int) (float64, bool) {
+	k := keyOf(from, to)
+	length, ok := w.segmentLength(k)
+	if !ok {
+		return 0, false
+	}
+	cost := w.RepairCostOf(RoadSegment{From: k.A, To: k.B, Length: length, Wear: w.RoadWear.wear[k]})
+	if !w.PayRepairs(cost) {
+		return 0, false
+	}
+	delete(w.RoadWear.wear, k)
+	return cost, true
+}
+
+// RepairAllRoads restores every segment to new, returning the cost, or
+// false (repairing nothing) if the treasury cannot afford it all
+func (w *World) RepairAllRoads() (float64, bool) {
+	cost := 0.0
+	for _, s := range w.RoadSegments() {
+		cost += w.RepairCostOf(s)
+	}
+	if !w.PayRepairs(cost) {
+		return 0, false
+	}
+	w.RoadWear.wear = make(map[roadKey]float64)
+	return cost, true
+}
+
+// wearAt returns the wear of the road segment nearest a world position, or
+// 0 when no segment is within ROAD_WEAR_REACH
+func (w *World) wearAt(x, z float64) float64 {
+	best := ROAD_WEAR_REACH
+	wear := 0.0
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		px, pz := closestPointOnSegment(x, z, a.X, a.Z, b.X, b.Z)
+		if d := simHypot(px-x, pz-z); d <= best {
+			best, wear = d, w.RoadWear.wear[keyOf(e.From, e.To)]
+		}
+	}
+	return wear
+}
+
+// roadAdjust scales vehicle tuning for the wear of the road under a car,
+// after the surface, weather and season
+func (w *World) roadAdjust(cfg *VehicleConfig, x, z float64, surface SurfaceType) {
+	r := w.RoadWear
+	if surface != SurfaceRoad || len(r.wear) == 0 {
+		return
+	}
+	wear := w.wearAt(x, z)
+	speed := 1 - clamp(r.SpeedLoss, 0, 1)*wear
+	cfg.MaxSpeed *= speed
+	cfg.ReverseMaxSpeed *= speed
+	cfg.LateralGrip *= 1 - clamp(r.GripLoss, 0, 1)*wear
+}
+
+// stepRoadWear forgets the wear of removed roads and wears every road in
+// rain and snow
+func (w *World) stepRoadWear(dt float64) {
+	r := w.RoadWear
+	for k := range r.wear {
+		if _, ok := w.segmentLength(k); !ok {
+			delete(r.wear, k)
+		}
+	}
+
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	amount := r.WeatherWear * (w.Weather.rain() + 2*w.Weather.snow()) * hours
+	if amount <= 0 {
+		return
+	}
+	for _, s := range w.RoadSegments() {
+		w.wearRoad(s.From, s.To, amount)
+	}
+}
@@ -0,0 +1,106 @@
+package main
+
+import "errors"
+
+// ============================================================================
+// Rollback (in-memory checkpoints and resimulation for netcode)
+// ============================================================================
+
+// Rollback netcode steps ahead on predicted input, and when a peer's real
+// input for an earlier step arrives, goes back to that step and plays the
+// steps since again with the corrected input. A Checkpoint is the world at
+// one step, held in memory: a WorldSnapshot (physics_snapshot.go), which
+// shares nothing the world goes on changing, plus what snapshots rebuild
+// rather than save but a resimulation must continue from exactly: the
+// traffic noise and the town event trackers. No JSON is involved, so
+// taking one every step is cheap enough. Rollback keeps the event queues
+// as they are, since their events have not reached JS yet; resimulated
+// steps report events again and JS should treat those of steps it has
+// already seen as corrections. Cars given input by Resimulate must be
+// stepped by Step, so AI or JS-driven but not manual cars.
+
+// Checkpoint is the world's state at one step, for Rollback
+type Checkpoint struct {
+	snapshot WorldSnapshot
+	noise    []float64  // Traffic noise per town cell
+	events   *SimEvents // Event trackers, without queued events
+}
+
+// StepInputs are the inputs of the cars driven from JS for one step
+type StepInputs map[int]InputState // Car ID -> input
+
+// Checkpoint captures the world's state for rolling back to
+func (w *World) Checkpoint() *Checkpoint {
+	return &Checkpoint{
+		snapshot: w.Snapshot(),
+		noise:    append([]float64(nil), w.Noise.traffic...),
+		events:   w.SimEvents.trackers(),
+	}
+}
+
+// trackers copies what the event queue has matched and counted so far,
+// leaving out its queued events
+func (e *SimEvents) trackers() *SimEvents {
+	c := &SimEvents{
+		buildings: make(map[int]bool, len(e.buildings)),
+		revision:  e.revision,
+		milestone: e.milestone,
+		stuck:     make(map[roadKey]float64, len(e.stuck)),
+		jammed:    make(map[roadKey]bool, len(e.jammed)),
+	}
+	for id := range e.buildings {
+		c.buildings[id] = true
+	}
+	for key, stuck := range e.stuck {
+		c.stuck[key] = stuck
+	}
+	for key := range e.jammed {
+		c.jammed[key] = true
+	}
+	return c
+}
+
+// Time returns the simulated time the checkpoint was taken at
+func (c *Checkpoint) Time() float64 {
+	return c.snapshot.Time
+}
+
+// Rollback returns the world to a checkpoint, which can be rolled back to
+// again; the world is unchanged if that fails
+func (w *World) Rollback(c *Checkpoint) error {
+	queue, dropped := w.SimEvents.queue, w.SimEvents.dropped
+	events, droppedEvents := w.events, w.droppedEvents
+	skids, droppedSkids := w.skids, w.droppedSkids
+	if err := w.Restore(c.snapshot); err != nil {
+		return err
+	}
+
+	copy(w.Noise.traffic, c.noise)
+	w.SimEvents = c.events.trackers()
+	w.SimEvents.queue, w.SimEvents.dropped = queue, dropped
+	w.events, w.droppedEvents = events, droppedEvents
+	w.skids, w.droppedSkids = skids, droppedSkids
+	return nil
+}
+
+// Resimulate rolls back to a checkpoint and steps the world once per entry
+// of inputs by dt, giving the listed cars their input first; cars left out
+// keep the input they held. Afterwards the world stands len(inputs) steps
+// after the checkpoint
+func (w *World) Resimulate(c *Checkpoint, dt float64, inputs []StepInputs) error {
+	if !(dt > 0) {
+		return errors.New("resimulation needs a positive dt")
+	}
+	if err := w.Rollback(c); err != nil {
+		return err
+	}
+	for _, step := range inputs {
+		for id, input := range step {
+			if car := w.Car(id); car != nil {
+				car.Input = input
+			}
+		}
+		w.Step(dt)
+	}
+	return nil
+}
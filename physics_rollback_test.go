@@ -0,0 +1,84 @@
+package main
+
+import "testing"
+
+// rollbackTwice changes the world after a checkpoint, rolls back and checks
+// it, twice over, so neither the checkpoint nor the world restored from it
+// shares what the change touches
+func rollbackTwice(t *testing.T, w *World, change func(), check func() string) {
+	t.Helper()
+	c := w.Checkpoint()
+	for i := 0; i < 2; i++ {
+		change()
+		if err := w.Rollback(c); err != nil {
+			t.Fatal(err)
+		}
+		if problem := check(); problem != "" {
+			t.Fatalf("rollback %d: %s", i+1, problem)
+		}
+	}
+}
+
+func TestRollbackRestoresTables(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testHouse})
+	rule, radius, jobs := w.ZoneRules["market"], w.Services[0].Radius, w.Capacities["market"].Jobs
+	rollbackTwice(t, w, func() {
+		delete(w.Models, "house.gltf")
+		w.ZoneRules["market"] = zoneMaskOf(ZoneIndustrial)
+		w.Service(w.Services[0].Name).Radius = radius + 1
+		w.Capacities["market"] = UseCapacity{Jobs: jobs + 1}
+	}, func() string {
+		switch {
+		case w.Models["house.gltf"].Name == "":
+			return "model registered before the checkpoint is gone"
+		case w.ZoneRules["market"] != rule:
+			return "zone rule changed after the checkpoint was kept"
+		case w.Services[0].Radius != radius:
+			return "service radius changed after the checkpoint was kept"
+		case w.Capacities["market"].Jobs != jobs:
+			return "capacity changed after the checkpoint was kept"
+		}
+		return ""
+	})
+}
+
+func TestRollbackRestoresPrices(t *testing.T) {
+	w := NewWorld()
+	price := w.Prices["market"]
+	rollbackTwice(t, w, func() {
+		w.Prices["market"] = Price{Cost: price.Cost * 2, Upkeep: price.Upkeep * 2}
+	}, func() string {
+		if w.Prices["market"] != price {
+			return "price changed after the checkpoint was kept"
+		}
+		return ""
+	})
+}
+
+func TestRollbackRestoresUtilitySupply(t *testing.T) {
+	w := NewWorld()
+	const use = "power_plant"
+	supply := w.Utilities.Supply[use]
+	rollbackTwice(t, w, func() {
+		delete(w.Utilities.Supply, use)
+	}, func() string {
+		if got, ok := w.Utilities.Supply[use]; !ok || got != supply {
+			return "utility supply changed after the checkpoint was kept"
+		}
+		return ""
+	})
+}
+
+func TestRollbackRestoresParkingUses(t *testing.T) {
+	w := NewWorld()
+	spaces, demand := w.LotParking.Spaces[PARKING_LOT_USE], w.LotParking.DemandPerJob["market"]
+	rollbackTwice(t, w, func() {
+		delete(w.LotParking.Spaces, PARKING_LOT_USE)
+		w.LotParking.DemandPerJob["market"] = demand + 1
+	}, func() string {
+		if w.LotParking.Spaces[PARKING_LOT_USE] != spaces || w.LotParking.DemandPerJob["market"] != demand {
+			return "parking use changed after the checkpoint was kept"
+		}
+		return ""
+	})
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"bytes"
+	"compress/flate"
+	"encoding/binary"
+	"encoding/gob"
+	"errors"
+	"fmt"
+	"io"
+)
+
+// ============================================================================
+// Binary Saves (compact, versioned world state)
+// ============================================================================
+
+// Save writes the same WorldSnapshot as ToJSON (physics_snapshot.go) as a
+// small binary blob: an 8-byte header (the SAVE_MAGIC bytes, then the
+// format's major and minor version as little-endian uint16s) followed by the
+// snapshot gob-encoded and deflated. Gob matches fields by name, so a save
+// with fields this build does not know (a newer minor version) still loads,
+// the extra fields skipped, and an older save loads with what it lacks left
+// at defaults. Only a newer major version, whose meaning changed, is refused.
+// Snapshots of an older SNAPSHOT_VERSION are upgraded on load like JSON
+// ones (physics_migration.go).
+
+const (
+	SAVE_MAGIC        = "TBSV" // First bytes of every binary save
+	SAVE_FORMAT_MAJOR = 1      // Bumped when older builds cannot read new saves
+	SAVE_FORMAT_MINOR = 10     // Bumped when fields are added
+	saveHeaderSize    = len(SAVE_MAGIC) + 4
+)
+
+// SaveVersion is the format version written in a save's header
+type SaveVersion struct {
+	Major, Minor int
+}
+
+// String returns the version as major.minor
+func (v SaveVersion) String() string {
+	return fmt.Sprintf("%d.%d", v.Major, v.Minor)
+}
+
+// Save encodes the world's state as a binary save
+func (w *World) Save() ([]byte, error) {
+	return encodeSave(w.Snapshot())
+}
+
+// encodeSave encodes a snapshot as a binary save
+func encodeSave(s WorldSnapshot) ([]byte, error) {
+	var buf bytes.Buffer
+	buf.WriteString(SAVE_MAGIC)
+	var version [4]byte
+	binary.LittleEndian.PutUint16(version[0:], SAVE_FORMAT_MAJOR)
+	binary.LittleEndian.PutUint16(version[2:], SAVE_FORMAT_MINOR)
+	buf.Write(version[:])
+
+	zw, err := flate.NewWriter(&buf, flate.DefaultCompression)
+	if err != nil {
+		return nil, err
+	}
+	if err := gob.NewEncoder(zw).Encode(s); err != nil {
+		return nil, err
+	}
+	if err := zw.Close(); err != nil {
+		return nil, err
+	}
+	return buf.Bytes(), nil
+}
+
+// ReadSaveVersion returns the format version of a binary save
+func ReadSaveVersion(data []byte) (SaveVersion, error) {
+	if len(data) < saveHeaderSize || string(data[:len(SAVE_MAGIC)]) != SAVE_MAGIC {
+		return SaveVersion{}, errors.New("not a town save")
+	}
+	header := data[len(SAVE_MAGIC):saveHeaderSize]
+	return SaveVersion{
+		Major: int(binary.LittleEndian.Uint16(header[0:])),
+		Minor: int(binary.LittleEndian.Uint16(header[2:])),
+	}, nil
+}
+
+// Load replaces the world's state with a binary save, leaving the world
+// unchanged if the save is invalid or from an incompatible version
+func (w *World) Load(data []byte) error {
+	version, err := ReadSaveVersion(data)
+	if err != nil {
+		return err
+	}
+	if version.Major != SAVE_FORMAT_MAJOR {
+		return fmt.Errorf("save format %s cannot be read by format %d.%d", version, SAVE_FORMAT_MAJOR, SAVE_FORMAT_MINOR)
+	}
+
+	zr := flate.NewReader(bytes.NewReader(data[saveHeaderSize:]))
+	defer zr.Close()
+	var s WorldSnapshot
+	if err := gob.NewDecoder(zr).Decode(&s); err != nil {
+		if errors.Is(err, io.ErrUnexpectedEOF) {
+			return errors.New("save is truncated")
+		}
+		return err
+	}
+	if s, err = migrateSnapshot(s, version.Minor); err != nil {
+		return err
+	}
+	return w.Restore(s)
+}
@@ -0,0 +1,92 @@
+package main
+
+// ============================================================================
+// Seasons (spring, summer, autumn, winter on the world clock)
+// ============================================================================
+
+// Seasons follow the world clock (physics_clock.go), each lasting a fixed
+// number of game days. A season scales road grip (icy winter roads), how
+// much residents use and enjoy green space (less in winter, more in
+// summer) in the livability score, and the odds the automatic weather
+// (physics_weather.go) draws its next spell from.
+
+const DAYS_PER_SEASON = 7 // Game days in each season
+
+// Season is the time of year
+type Season uint8
+
+const (
+	SeasonSpring Season = iota
+	SeasonSummer
+	SeasonAutumn
+	SeasonWinter
+	seasonCount
+)
+
+// seasonNames are the JavaScript-facing names, indexed by Season
+var seasonNames = [seasonCount]string{"spring", "summer", "autumn", "winter"}
+
+// String returns the JavaScript-facing season name
+func (s Season) String() string {
+	if s >= seasonCount {
+		return seasonNames[SeasonSpring]
+	}
+	return seasonNames[s]
+}
+
+// SeasonEffects is how a season changes the simulation
+type SeasonEffects struct {
+	RoadGrip    float64                   // Lateral grip multiplier on road surfaces
+	GreenSpace  float64                   // Green space livability weight multiplier (park use)
+	WeatherOdds [weatherTypeCount]float64 // Relative chance of each weather for the next spell
+}
+
+// seasonEffects is the effect of every season, indexed by Season
+var seasonEffects = [seasonCount]SeasonEffects{
+	SeasonSpring: {RoadGrip: 1, GreenSpace: 1, WeatherOdds: [weatherTypeCount]float64{WeatherClear: 5, WeatherRain: 4}},
+	SeasonSummer: {RoadGrip: 1, GreenSpace: 1.5, WeatherOdds: [weatherTypeCount]float64{WeatherClear: 8, WeatherRain: 2}},
+	SeasonAutumn: {RoadGrip: 1, GreenSpace: 0.9, WeatherOdds: [weatherTypeCount]float64{WeatherClear: 4, WeatherRain: 5, WeatherSnow: 1}},
+	SeasonWinter: {RoadGrip: 0.8, GreenSpace: 0.5, WeatherOdds: [weatherTypeCount]float64{WeatherClear: 4, WeatherRain: 1, WeatherSnow: 5}},
+}
+
+// Effects returns how the season changes the simulation
+func (s Season) Effects() SeasonEffects {
+	if s >= seasonCount {
+		return seasonEffects[SeasonSpring]
+	}
+	return seasonEffects[s]
+}
+
+// seasonDays returns the game days per season, falling back to DAYS_PER_SEASON
+func (c *WorldClock) seasonDays() int {
+	if c.DaysPerSeason > 0 {
+		return c.DaysPerSeason
+	}
+	return DAYS_PER_SEASON
+}
+
+// Season returns the current season; day 0 is the first day of spring
+func (c *WorldClock) Season() Season {
+	return Season((c.Day() / c.seasonDays()) % int(seasonCount))
+}
+
+// SeasonProgress returns how far through the current season the clock is, 0-1
+func (c *WorldClock) SeasonProgress() float64 {
+	days := c.seasonDays()
+	return (float64(c.Day()%days) + c.DayProgress()) / float64(days)
+}
+
+// seasonAdjust scales vehicle tuning for the season on a surface
+func (c *WorldClock) seasonAdjust(cfg *VehicleConfig, surface SurfaceType) {
+	if surface == SurfaceRoad {
+		cfg.LateralGrip *= c.Season().Effects().RoadGrip
+	}
+}
+
+// serviceWeight returns a service's livability weight in the current season
+func (w *World) serviceWeight(s *ServiceCategory) float64 {
+	if s.Name == GREEN_SPACE_SERVICE {
+		return s.Weight * w.Clock.Season().Effects().GreenSpace
+	}
+	return s.Weight
+}
@@ -0,0 +1,257 @@
+package main
+
+import (
+	"encoding/binary"
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Multiplayer Server (authoritative world stepped at a fixed tick)
+// ============================================================================
+
+// The server owns the shared world. Each player drives one car: their
+// client sends input packets, the newest of which the server gives the car
+// before every tick, and after each tick every player gets a state packet
+// with a delta (physics_delta.go) from the last state they acknowledged to
+// the new one. Clients acknowledge a tick by echoing it in their input
+// packets; until they do, deltas stay against the older state they have,
+// from a short history of per-tick snapshots, and a player whose base has
+// left the history (or who never acknowledged one) gets a key frame. A
+// state packet also says which input was applied last, for the client to
+// reconcile its prediction (physics_prediction.go). Transport is left to
+// the host: it hands ReceiveInput the bytes of each ClientPacket, calls
+// Step every Dt seconds and sends each ServerPacket to its player, so a
+// thin WebSocket wrapper around the native build (physics_headless.go) can
+// host a town.
+
+const (
+	SERVER_HISTORY_TICKS = 120 // Ticks of snapshots kept as delta bases (2 s at 60 Hz)
+
+	clientPacketSize = 4 + 4 + 4*8 + 1
+	serverHeaderSize = 4 + 4 + 4
+)
+
+// ClientPacket is one input update from a player
+type ClientPacket struct {
+	Seq     uint32 // Increases with every packet the client sends
+	AckTick uint32 // Last tick whose state the client applied, 0 if none
+	Input   InputState
+}
+
+// ServerPacket is the state update for one player after a tick
+type ServerPacket struct {
+	Tick     uint32
+	BaseTick uint32 // Tick the delta applies to, 0 for a key frame
+	InputSeq uint32 // Seq of the player's last input applied, 0 if none
+	Delta    []byte
+}
+
+// serverPlayer is a connected player
+type serverPlayer struct {
+	id       int
+	carID    int
+	input    InputState
+	seq      uint32 // Newest input received
+	applied  uint32 // Seq of the input the car was given last tick
+	ackTick  uint32
+	received bool // Any input arrived yet
+}
+
+// Server steps a shared world for connected players
+type Server struct {
+	World *World
+	Dt    float64 // s per tick
+
+	tick    uint32
+	players []*serverPlayer // Join order
+	history map[uint32]*WorldSnapshot
+}
+
+// NewServer creates a server for a world, ticking at FIXED_TIMESTEP
+func NewServer(w *World) *Server {
+	return &Server{World: w, Dt: FIXED_TIMESTEP, history: make(map[uint32]*WorldSnapshot)}
+}
+
+// Tick returns the number of ticks run
+func (s *Server) Tick() uint32 {
+	return s.tick
+}
+
+// player returns a connected player, or nil
+func (s *Server) player(id int) *serverPlayer {
+	for _, p := range s.players {
+		if p.id == id {
+			return p
+		}
+	}
+	return nil
+}
+
+// Join connects a player driving an existing car, which from then on is
+// stepped with the world on the player's input; rejoining changes the car
+func (s *Server) Join(playerID, carID int) error {
+	car := s.World.Car(carID)
+	if car == nil {
+		return errors.New("player car does not exist")
+	}
+	for _, other := range s.players {
+		if other.carID == carID && other.id != playerID {
+			return errors.New("car is driven by another player")
+		}
+	}
+	car.AI, car.Manual, car.Input = nil, false, InputState{}
+
+	if p := s.player(playerID); p != nil {
+		*p = serverPlayer{id: playerID, carID: carID}
+		return nil
+	}
+	s.players = append(s.players, &serverPlayer{id: playerID, carID: carID})
+	return nil
+}
+
+// Leave disconnects a player, leaving their car parked; returns false if
+// they were not connected
+func (s *Server) Leave(playerID int) bool {
+	for i, p := range s.players {
+		if p.id == playerID {
+			if car := s.World.Car(p.carID); car != nil {
+				car.Input = InputState{Brake: 1}
+			}
+			s.players = append(s.players[:i], s.players[i+1:]...)
+			return true
+		}
+	}
+	return false
+}
+
+// ReceiveInput takes a ClientPacket from a player; packets older than one
+// already received are ignored, since only the newest input is used
+func (s *Server) ReceiveInput(playerID int, data []byte) error {
+	p := s.player(playerID)
+	if p == nil {
+		return errors.New("input from a player who has not joined")
+	}
+	var packet ClientPacket
+	if err := packet.UnmarshalBinary(data); err != nil {
+		return err
+	}
+	if packet.AckTick > p.ackTick && packet.AckTick <= s.tick {
+		p.ackTick = packet.AckTick
+	}
+	if p.received && packet.Seq <= p.seq {
+		return nil
+	}
+	p.input, p.seq, p.received = packet.Input.clamped(), packet.Seq, true
+	return nil
+}
+
+// Step runs one tick: gives every player's car their newest input, steps
+// the world by Dt and returns each player's state packet, in join order
+func (s *Server) Step() ([]ServerPacket, error) {
+	for _, p := range s.players {
+		if car := s.World.Car(p.carID); car != nil && p.received {
+			car.Input = p.input
+		}
+		p.applied = p.seq
+	}
+	s.World.Step(s.Dt)
+	s.tick++
+	snap := s.World.Snapshot()
+	s.history[s.tick] = &snap
+
+	packets := make([]ServerPacket, 0, len(s.players))
+	deltas := make(map[uint32][]byte, 2) // By base tick; most players share one
+	oldest := s.tick
+	for _, p := range s.players {
+		base := s.history[p.ackTick]
+		baseTick := p.ackTick
+		if base == nil {
+			baseTick = 0
+		}
+		delta, encoded := deltas[baseTick]
+		if !encoded {
+			var err error
+			if delta, err = EncodeDelta(base, &snap); err != nil {
+				return nil, err
+			}
+			deltas[baseTick] = delta
+		}
+		packets = append(packets, ServerPacket{Tick: s.tick, BaseTick: baseTick, InputSeq: p.applied, Delta: delta})
+		if baseTick > 0 && baseTick < oldest {
+			oldest = baseTick
+		}
+	}
+
+	// Keep every base still in use, but no more than the history allows
+	if s.tick-oldest >= SERVER_HISTORY_TICKS {
+		oldest = s.tick - SERVER_HISTORY_TICKS + 1
+	}
+	for tick := range s.history {
+		if tick < oldest {
+			delete(s.history, tick)
+		}
+	}
+	return packets, nil
+}
+
+// MarshalBinary encodes the packet: little-endian seq and ack tick, the
+// three input axes and the cruise speed as float64 bits, and a flags byte
+// (1 handbrake, 2 speed limiter)
+func (c ClientPacket) MarshalBinary() ([]byte, error) {
+	b := make([]byte, 0, clientPacketSize)
+	b = binary.LittleEndian.AppendUint32(b, c.Seq)
+	b = binary.LittleEndian.AppendUint32(b, c.AckTick)
+	for _, axis := range []float64{c.Input.Throttle, c.Input.Brake, c.Input.Steering, c.Input.CruiseSpeed} {
+		b = binary.LittleEndian.AppendUint64(b, math.Float64bits(axis))
+	}
+	var flags byte
+	if c.Input.Handbrake {
+		flags |= 1
+	}
+	if c.Input.SpeedLimiter {
+		flags |= 2
+	}
+	return append(b, flags), nil
+}
+
+// UnmarshalBinary decodes MarshalBinary output
+func (c *ClientPacket) UnmarshalBinary(data []byte) error {
+	if len(data) != clientPacketSize {
+		return errors.New("client packet has the wrong size")
+	}
+	c.Seq = binary.LittleEndian.Uint32(data[0:])
+	c.AckTick = binary.LittleEndian.Uint32(data[4:])
+	c.Input = InputState{
+		Throttle:  math.Float64frombits(binary.LittleEndian.Uint64(data[8:])),
+		Brake:     math.Float64frombits(binary.LittleEndian.Uint64(data[16:])),
+		Steering:  math.Float64frombits(binary.LittleEndian.Uint64(data[24:])),
+		Handbrake: data[40]&1 != 0,
+
+		CruiseSpeed:  math.Float64frombits(binary.LittleEndian.Uint64(data[32:])),
+		SpeedLimiter: data[40]&2 != 0,
+	}
+	return nil
+}
+
+// MarshalBinary encodes the packet: little-endian tick, base tick and
+// input seq, then the delta
+func (p ServerPacket) MarshalBinary() ([]byte, error) {
+	b := make([]byte, 0, serverHeaderSize+len(p.Delta))
+	b = binary.LittleEndian.AppendUint32(b, p.Tick)
+	b = binary.LittleEndian.AppendUint32(b, p.BaseTick)
+	b = binary.LittleEndian.AppendUint32(b, p.InputSeq)
+	return append(b, p.Delta...), nil
+}
+
+// UnmarshalBinary decodes MarshalBinary output; Delta refers into data
+func (p *ServerPacket) UnmarshalBinary(data []byte) error {
+	if len(data) < serverHeaderSize {
+		return errors.New("server packet is truncated")
+	}
+	p.Tick = binary.LittleEndian.Uint32(data[0:])
+	p.BaseTick = binary.LittleEndian.Uint32(data[4:])
+	p.InputSeq = binary.LittleEndian.Uint32(data[8:])
+	p.Delta = data[serverHeaderSize:]
+	return nil
+}
@@ -0,0 +1,207 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Traffic Signals (per-intersection phase controllers)
+// ============================================================================
+
+// A signal cycles through phases. Each phase gives green to a set of incoming
+// road segments, then yellow, then a short all-red clearance before the next
+// phase turns green. Every other incoming segment is red.
+
+const (
+	DEFAULT_GREEN_TIME   = 8.0 // s
+	DEFAULT_YELLOW_TIME  = 2.0 // s
+	ALL_RED_TIME         = 1.0 // s, clearance between phases
+	SIGNAL_STOP_DISTANCE = 2.0 // units, stop line distance before the intersection node
+)
+
+// SignalLight is the light shown to one incoming segment
+type SignalLight uint8
+
+const (
+	LightGreen SignalLight = iota
+	LightYellow
+	LightRed
+)
+
+// signalLightNames are the JavaScript-facing names, indexed by SignalLight
+var signalLightNames = [...]string{"green", "yellow", "red"}
+
+// String returns the JavaScript-facing light name
+func (l SignalLight) String() string {
+	if int(l) >= len(signalLightNames) {
+		return signalLightNames[LightRed]
+	}
+	return signalLightNames[l]
+}
+
+// SignalPhase gives green to a group of incoming segments
+type SignalPhase struct {
+	Edges      []int   // Incoming edge IDs with right of way
+	GreenTime  float64 // s
+	YellowTime float64 // s
+}
+
+// duration returns the length of the phase including the all-red clearance
+func (p *SignalPhase) duration() float64 {
+	return p.GreenTime + p.YellowTime + ALL_RED_TIME
+}
+
+// TrafficSignal controls the approaches to one intersection node
+type TrafficSignal struct {
+	Node    int
+	Phases  []SignalPhase
+	Phase   int     // Index of the current phase
+	Elapsed float64 // s since the current phase turned green
+	Auto    bool    // Phases are derived from the node's incoming edges every step
+
+	greenTime, yellowTime float64 // Timings used for automatic phases
+}
+
+// Light returns the light shown to an incoming edge
+func (s *TrafficSignal) Light(edgeID int) SignalLight {
+	if len(s.Phases) == 0 {
+		return LightGreen // Nothing to control
+	}
+
+	p := &s.Phases[s.Phase]
+	for _, id := range p.Edges {
+		if id != edgeID {
+			continue
+		}
+		switch {
+		case s.Elapsed < p.GreenTime:
+			return LightGreen
+		case s.Elapsed < p.GreenTime+p.YellowTime:
+			return LightYellow
+		}
+	}
+	return LightRed
+}
+
+// step advances the signal's timer, moving through as many phases as dt covers
+func (s *TrafficSignal) step(dt float64) {
+	if len(s.Phases) == 0 {
+		return
+	}
+
+	s.Phase %= len(s.Phases)
+	s.Elapsed += dt
+	for s.Elapsed >= s.Phases[s.Phase].duration() {
+		s.Elapsed -= s.Phases[s.Phase].duration()
+		s.Phase = (s.Phase + 1) % len(s.Phases)
+	}
+}
+
+// autoPhases groups a node's incoming edges into two phases by approach axis,
+// so opposite approaches share a green (mostly-X roads, then mostly-Z roads)
+func (w *World) autoPhases(node *RoadNode, greenTime, yellowTime float64) []SignalPhase {
+	var alongX, alongZ []int
+	for _, id := range node.In {
+		e := w.Roads.Edge(id)
+		from := w.Roads.Node(e.From)
+		if math.Abs(node.X-from.X) >= math.Abs(node.Z-from.Z) {
+			alongX = append(alongX, id)
+		} else {
+			alongZ = append(alongZ, id)
+		}
+	}
+
+	phases := make([]SignalPhase, 0, 2)
+	for _, group := range [][]int{alongX, alongZ} {
+		if len(group) > 0 {
+			phases = append(phases, SignalPhase{Edges: group, GreenTime: greenTime, YellowTime: yellowTime})
+		}
+	}
+	return phases
+}
+
+// SetSignal installs (or replaces) a signal at a road node
+// With no phases, phases are derived automatically from the incoming roads
+func (w *World) SetSignal(node int, phases []SignalPhase, greenTime, yellowTime float64) error {
+	n := w.Roads.Node(node)
+	if n == nil {
+		return errors.New("signal node does not exist")
+	}
+
+	s := &TrafficSignal{
+		Node:       node,
+		Auto:       len(phases) == 0,
+		greenTime:  positiveOr(greenTime, DEFAULT_GREEN_TIME),
+		yellowTime: yellowTime,
+	}
+	if !isFinite(yellowTime) || yellowTime < 0 {
+		s.yellowTime = DEFAULT_YELLOW_TIME
+	}
+
+	if s.Auto {
+		s.Phases = w.autoPhases(n, s.greenTime, s.yellowTime)
+	} else {
+		for _, p := range phases {
+			p.GreenTime = positiveOr(p.GreenTime, s.greenTime)
+			if !isFinite(p.YellowTime) || p.YellowTime < 0 {
+				p.YellowTime = s.yellowTime
+			}
+			s.Phases = append(s.Phases, p)
+		}
+	}
+
+	w.Signals[node] = s
+	return nil
+}
+
+// RemoveSignal removes the signal at a node, returning false if there is none
+func (w *World) RemoveSignal(node int) bool {
+	if _, exists := w.Signals[node]; !exists {
+		return false
+	}
+	delete(w.Signals, node)
+	return true
+}
+
+// stepSignals advances every signal, dropping signals whose node was removed
+func (w *World) stepSignals(dt float64) {
+	for id, s := range w.Signals {
+		n := w.Roads.Node(id)
+		if n == nil {
+			delete(w.Signals, id)
+			continue
+		}
+
+		if s.Auto {
+			s.Phases = w.autoPhases(n, s.greenTime, s.yellowTime)
+		}
+		s.step(dt)
+	}
+}
+
+// SignalLightFor returns the light at the end of a road segment
+// Segments that do not end at a signal are always green
+func (w *World) SignalLightFor(edgeID int) SignalLight {
+	e := w.Roads.Edge(edgeID)
+	if e == nil {
+		return LightGreen
+	}
+	s := w.Signals[e.To]
+	if s == nil {
+		return LightGreen
+	}
+	return s.Light(edgeID)
+}
+
+// mustStopForSignal reports whether a driver approaching a signal should stop
+// at the line: always on red, and on yellow when it can still stop comfortably
+func mustStopForSignal(light SignalLight, gap, speed float64) bool {
+	switch light {
+	case LightRed:
+		return true
+	case LightYellow:
+		return gap > speed*speed/(2*IDM_COMFORT_DECEL)
+	}
+	return false
+}
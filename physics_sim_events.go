@@ -0,0 +1,211 @@
+package main
+
+import "sort"
+
+// ============================================================================
+// Simulation Events (town milestones for JS callbacks)
+// ============================================================================
+
+// Besides collisions (physics_events.go), the world reports what happens to
+// the town: buildings completed, population milestones and traffic jams are
+// spotted here, and the other kinds below are raised by the systems they
+// belong to. Events are queued during the step and delivered to callbacks
+// registered from JS (onEvent) once the step is done.
+
+const (
+	MAX_SIM_EVENTS = 256  // Events kept between deliveries; extras are dropped
+	JAM_SPEED      = 0.5  // units/s, AI cars slower than this are stuck
+	JAM_CARS       = 4    // Stuck cars on one segment that make a jam
+	JAM_SECONDS    = 15.0 // s a segment must stay stuck before a jam is reported
+)
+
+// populationMilestones are the resident counts reported once each as the town grows
+var populationMilestones = []int{50, 100, 250, 500, 1000, 2500, 5000, 10000}
+
+// SimEventKind identifies a simulation event
+type SimEventKind uint8
+
+const (
+	SimBuildingCompleted   SimEventKind = iota // First seen complete, or finished building (physics_construction.go)
+	SimBuildingBurned                          // physics_fire.go
+	SimPopulationMilestone                     // Residents passed one of populationMilestones
+	SimTrafficJam                              // JAM_CARS stuck on one segment for JAM_SECONDS
+	SimCarWrecked                              // physics_damage.go
+	SimTrailerJackknifed                       // physics_trailer.go
+	SimLapCompleted                            // physics_race.go
+	SimRaceFinished                            // physics_race.go
+	SimMissionCompleted                        // physics_missions.go
+	SimMissionFailed                           // physics_missions.go
+	SimCarParked                               // In a parking spot (physics_parking.go)
+	SimDriftScored                             // physics_drift.go
+	SimDriftCombo                              // physics_drift.go
+	SimAchievementUnlocked                     // physics_stats.go
+	SimViolation                               // physics_violations.go
+	SimBuildingUpgraded                        // physics_levels.go
+	SimBuildingDowngraded                      // physics_levels.go
+	SimDemolished                              // physics_demolition.go
+	simEventKindCount
+)
+
+// simEventKindNames are the JavaScript-facing names, indexed by SimEventKind
+var simEventKindNames = [simEventKindCount]string{"building_completed", "building_burned", "milestone", "traffic_jam", "wrecked", "jackknifed", "lap_completed", "race_finished", "mission_completed", "mission_failed", "parked", "drift", "drift_combo", "achievement", "violation", "building_upgraded", "building_downgraded", "demolished"}
+
+// String returns the JavaScript-facing kind name
+func (k SimEventKind) String() string {
+	if k >= simEventKindCount {
+		return simEventKindNames[SimBuildingCompleted]
+	}
+	return simEventKindNames[k]
+}
+
+// SimEvent is one thing that happened to the town
+type SimEvent struct {
+	Kind  SimEventKind
+	ID    int     // Placement ID; jams: the road node the segment starts at; wrecks, jackknifes, races, missions, parking, drifts, violations: the car
+	To    int     // Jams: the road node the segment ends at; laps: laps completed; finishes: the place; missions: the mission; parking: the spot; drifts: drifts in the combo; violations: the segment; levels: the new level
+	Model string  // Buildings: the model placed, burned, leveled or demolished; achievements: the name; violations: the offence
+	Value float64 // Milestones: residents reached; jams: stuck cars; wrecks: impact speed change; jackknifes: articulation (rad); laps: lap time; finishes: race time; deliveries: reward; parking: precision score; drifts: points; achievements: threshold; violations: severity (0-1); levels: the day's score (0-1); demolitions: the refund
+	X, Z  float64 // Where it happened
+
+	Width, Depth float64 // Demolitions: the footprint's extents along X and Z
+}
+
+// SimEvents queues events and keeps what is needed to detect them
+type SimEvents struct {
+	queue   []SimEvent
+	dropped int // Events lost to a full queue since the last delivery
+
+	buildings map[int]bool // Building placements already reported
+	revision  int          // Occupancy revision buildings were last matched against
+	milestone int          // Index of the next population milestone
+	stuck     map[roadKey]float64
+	jammed    map[roadKey]bool
+}
+
+// NewSimEvents creates an empty event queue
+func NewSimEvents() *SimEvents {
+	return &SimEvents{
+		buildings: make(map[int]bool),
+		revision:  -1,
+		stuck:     make(map[roadKey]float64),
+		jammed:    make(map[roadKey]bool),
+	}
+}
+
+// pushSimEvent queues an event, dropping it when the queue is full
+func (w *World) pushSimEvent(e SimEvent) {
+	s := w.SimEvents
+	if len(s.queue) >= MAX_SIM_EVENTS {
+		s.dropped++
+		return
+	}
+	s.queue = append(s.queue, e)
+}
+
+// takeSimEvents returns the queued events and the number dropped, then empties the queue
+func (w *World) takeSimEvents() ([]SimEvent, int) {
+	s := w.SimEvents
+	events, dropped := s.queue, s.dropped
+	s.queue, s.dropped = nil, 0
+	return events, dropped
+}
+
+// trackBuildings reports buildings that appeared complete since the
+// occupancy grid last changed; sites report when they are finished
+// (physics_construction.go)
+func (w *World) trackBuildings() {
+	s := w.SimEvents
+	if s.revision == w.Occupancy.Revision() {
+		return
+	}
+	present := make(map[int]bool, len(s.buildings))
+	for _, id := range w.Occupancy.IDs() {
+		p := w.Occupancy.Placement(id)
+		if !flammable(p) || w.underConstruction(p) {
+			continue
+		}
+		present[id] = true
+		if !s.buildings[id] {
+			x, z := p.Center()
+			w.pushSimEvent(SimEvent{Kind: SimBuildingCompleted, ID: id, Model: p.Model, X: x, Z: z})
+		}
+	}
+	s.buildings = present
+	s.revision = w.Occupancy.Revision()
+}
+
+// trackMilestones reports each population milestone the first time it is reached
+func (w *World) trackMilestones() {
+	s := w.SimEvents
+	for s.milestone < len(populationMilestones) && w.Population.Residents >= populationMilestones[s.milestone] {
+		w.pushSimEvent(SimEvent{Kind: SimPopulationMilestone, Value: float64(populationMilestones[s.milestone])})
+		s.milestone++
+	}
+}
+
+// trackJams reports road segments where AI cars have been stuck for JAM_SECONDS
+func (w *World) trackJams(dt float64) {
+	s := w.SimEvents
+	stuckCars := make(map[roadKey]int)
+	for _, car := range w.cars {
+		ai := car.AI
+		if ai == nil || ai.Arrived || ai.EdgeIndex >= len(ai.Route.Edges) {
+			continue
+		}
+		e := w.Roads.Edge(ai.Route.Edges[ai.EdgeIndex])
+		if e != nil && simHypot(car.State.VelocityX, car.State.VelocityZ) < JAM_SPEED {
+			stuckCars[keyOf(e.From, e.To)]++
+		}
+	}
+
+	for k := range s.stuck {
+		if stuckCars[k] < JAM_CARS {
+			delete(s.stuck, k)
+			delete(s.jammed, k)
+		}
+	}
+	keys := make([]roadKey, 0, len(stuckCars))
+	for k, count := range stuckCars {
+		if count >= JAM_CARS {
+			keys = append(keys, k)
+		}
+	}
+	// Report in node order so jams found in the same step queue the same way every run
+	sort.Slice(keys, func(i, j int) bool {
+		if keys[i].A != keys[j].A {
+			return keys[i].A < keys[j].A
+		}
+		return keys[i].B < keys[j].B
+	})
+	for _, k := range keys {
+		count := stuckCars[k]
+		s.stuck[k] += dt
+		if s.stuck[k] >= JAM_SECONDS && !s.jammed[k] {
+			s.jammed[k] = true
+			a, b := w.Roads.Node(k.A), w.Roads.Node(k.B)
+			w.pushSimEvent(SimEvent{Kind: SimTrafficJam, ID: k.A, To: k.B, Value: float64(count), X: (a.X + b.X) / 2, Z: (a.Z + b.Z) / 2})
+		}
+	}
+}
+
+// primeSimEvents counts what is already built and reached as reported, so
+// a restored town does not announce it all again
+func (w *World) primeSimEvents() {
+	s := w.SimEvents
+	for _, id := range w.Occupancy.IDs() {
+		if p := w.Occupancy.Placement(id); flammable(p) && !w.underConstruction(p) {
+			s.buildings[id] = true
+		}
+	}
+	s.revision = w.Occupancy.Revision()
+	for s.milestone < len(populationMilestones) && w.Population.Residents >= populationMilestones[s.milestone] {
+		s.milestone++
+	}
+}
+
+// stepSimEvents looks for new buildings, milestones and jams
+func (w *World) stepSimEvents(dt float64) {
+	w.trackBuildings()
+	w.trackMilestones()
+	w.trackJams(dt)
+}
@@ -0,0 +1,119 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Skids (tire marks and screech)
+// ============================================================================
+
+// A car skids while its tires slide across the ground faster than
+// SKID_SLIP_SPEED (cornering past its grip, or handbraked into a slide) or
+// while it brakes harder than SKID_BRAKE_DECEL. Every step it skids, a skid
+// event is queued with the world-space contact points of its wheels, so the
+// frontend can extend a tire mark per wheel and play screech audio there;
+// Start marks the first step of a skid, where new marks begin, and a car
+// that stops skidding simply stops appearing. Two-wheelers report their two
+// wheels, four-wheelers all four. Intensity grows with the slide or the
+// braking, for mark opacity and screech volume. The frontend drains the
+// queue once per frame, as it does collision events (physics_events.go).
+
+const (
+	MAX_SKID_EVENTS  = 256  // Events kept between drains; extras are dropped
+	SKID_SLIP_SPEED  = 1.5  // units/s of sideways slide before the tires skid
+	SKID_FULL_SLIP   = 6.0  // units/s of sideways slide for a full-intensity skid
+	SKID_BRAKE_DECEL = 25.0 // units/s² of braking before the wheels lock
+	SKID_FULL_DECEL  = 45.0 // units/s² of braking for a full-intensity skid
+)
+
+// WheelContact is where a wheel touches the ground
+type WheelContact struct {
+	X, Y, Z float64
+}
+
+// SkidEvent is one step of a car skidding
+type SkidEvent struct {
+	CarID     int
+	Start     bool        // The car was not skidding the step before
+	Intensity float64     // 0..1
+	Surface   SurfaceType // Ground under the car, for the mark and the sound
+	Wheels    []WheelContact
+}
+
+// skidIntensity returns how hard a car skids over one substep in which its
+// velocity changed from prevVX, prevVZ, 0 when it does not
+func (car *CarState) skidIntensity(input InputState, prevVX, prevVZ, dt float64) float64 {
+	_, _, _, _, _, lateral := car.headingBasis()
+	slide := 0.0
+	if slip := math.Abs(lateral); slip > SKID_SLIP_SPEED {
+		slide = (slip - SKID_SLIP_SPEED) / (SKID_FULL_SLIP - SKID_SLIP_SPEED)
+	}
+
+	// Braking is the forward speed lost with the brakes on, so a crash
+	// into a wall leaves no marks
+	brake := 0.0
+	if input.Brake > 0 && !car.Reversing {
+		hx, hz := math.Sin(car.RotationY), math.Cos(car.RotationY)
+		decel := ((prevVX-car.VelocityX)*hx + (prevVZ-car.VelocityZ)*hz) / dt
+		if decel > SKID_BRAKE_DECEL && !car.Collided {
+			brake = (decel - SKID_BRAKE_DECEL) / (SKID_FULL_DECEL - SKID_BRAKE_DECEL)
+		}
+	}
+	if slide == 0 && brake == 0 {
+		return 0
+	}
+	return clamp(math.Max(slide, brake), 0.05, 1) // A faint mark at the threshold
+}
+
+// wheelContacts returns the ground points under a car's wheels: front
+// left, front right, rear left and rear right, or front and rear for a
+// two-wheeler
+func (w *World) wheelContacts(car *CarState, cfg *VehicleConfig) []WheelContact {
+	fx, fz := math.Sin(car.RotationY), math.Cos(car.RotationY)
+	lx, lz := fz, -fx
+	axle := cfg.Wheelbase / 2
+	var offsets [][2]float64 // Along, left
+	if cfg.TwoWheeler {
+		offsets = [][2]float64{{axle, 0}, {-axle, 0}}
+	} else {
+		track := cfg.TrackWidth / 2
+		offsets = [][2]float64{{axle, track}, {axle, -track}, {-axle, track}, {-axle, -track}}
+	}
+
+	wheels := make([]WheelContact, len(offsets))
+	for i, o := range offsets {
+		x := car.X + fx*o[0] + lx*o[1]
+		z := car.Z + fz*o[0] + lz*o[1]
+		wheels[i] = WheelContact{X: x, Y: w.groundHeight(x, z), Z: z}
+	}
+	return wheels
+}
+
+// markSkid records whether a car skidded during a step, at the strongest
+// intensity of its substeps, and queues a skid event while it does
+func (w *World) markSkid(car *CarState, cfg *VehicleConfig, intensity float64) {
+	start := !car.Skidding
+	car.Skidding = intensity > 0
+	car.SkidIntensity = intensity
+	if !car.Skidding {
+		return
+	}
+	if len(w.skids) >= MAX_SKID_EVENTS {
+		w.droppedSkids++
+		return
+	}
+	w.skids = append(w.skids, SkidEvent{
+		CarID:     car.ID,
+		Start:     start,
+		Intensity: intensity,
+		Surface:   car.Surface,
+		Wheels:    w.wheelContacts(car, cfg),
+	})
+}
+
+// takeSkidEvents returns the queued skid events and the number dropped, then empties the queue
+func (w *World) takeSkidEvents() ([]SkidEvent, int) {
+	skids, dropped := w.skids, w.droppedSkids
+	w.skids = make([]SkidEvent, 0, MAX_SKID_EVENTS)
+	w.droppedSkids = 0
+	return skids, dropped
+}
@@ -0,0 +1,789 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"maps"
+	"math"
+)
+
+// ============================================================================
+// World Snapshots (JSON save and restore of the whole simulation)
+// ============================================================================
+
+// A WorldSnapshot holds everything a World simulates: cars and their AI
+// drivers, pedestrians, colliders, road features, the road graph (edge IDs
+// included, since routes and signal phases refer to them), terrain,
+// surfaces, placements, zoning, districts, construction sites, building
+// levels, the clock, weather, population, trips, citizens, parked cars,
+// trams, the railway, fires, the treasury, greenery and road wear. It
+// encodes with encoding/json using the Go field names, so saves, debugging
+// dumps and tests can round-trip complete state. The seed and the place of
+// every random generator are saved too, so a restored world draws the
+// numbers the original would have (physics_determinism.go). Left out is
+// what is rebuilt on its own (noise, livability caches, the collision and
+// simulation event queues, intersection claims).
+
+const SNAPSHOT_VERSION = 2 // Bumped when the format changes, with a migration (physics_migration.go)
+
+// WorldSnapshot is the serializable state of a World
+type WorldSnapshot struct {
+	Version int
+	Time    float64
+	Seed    int64
+
+	Cars        []CarSnapshot
+	Pedestrians []PedestrianSnapshot
+	Colliders   []ColliderSnapshot
+	Features    []RoadFeature
+	Parking     []ParkingSpot
+	Roads       RoadGraphSnapshot
+	RoadWear    RoadWearSnapshot
+	Signals     []SignalSnapshot
+	Crowd       CrowdConfig
+
+	MetersPerUnit float64
+
+	Terrain  *Terrain
+	Surfaces *SurfaceMap
+	Bounds   *WorldBounds
+
+	Occupancy  GridSpec
+	Placements []Placement
+	Models     map[string]ModelSpec
+	Zones      []ZoneType // One per occupancy cell
+	Districts  DistrictSnapshot
+	ZoneRules  map[string]ZoneMask
+	Services   []ServiceCategory
+	Greenery   GreenerySnapshot
+	Levels     BuildingLevels
+	Sites      Construction
+
+	Clock      WorldClock
+	Weather    WeatherSnapshot
+	Population PopulationSnapshot
+	Capacities map[string]UseCapacity
+	Trips      TripSnapshot
+	Citizens   CitizenSnapshot
+	LotParking LotParking
+	Fires      FireSnapshot
+	Transit    TransitSnapshot
+	Trams      TramSnapshot
+	Railway    Railway
+	Emergency  EmergencySnapshot
+	Race       *RaceSnapshot // nil when no race is on
+	Missions   MissionSnapshot
+	Stats      Stats
+	Utilities  Utilities
+	Economy    Economy
+	Prices     map[string]Price
+	Timestep   FixedTimestep
+}
+
+// CarSnapshot is one world car
+type CarSnapshot struct {
+	State      CarState
+	Config     VehicleConfig
+	Input      InputState
+	Manual     bool
+	AI         *AISnapshot // nil for cars driven from JavaScript
+	Trailer    *Trailer    // nil if the car tows none
+	Siren      bool
+	Drift      DriftState
+	Violations ViolationState
+}
+
+// AISnapshot is an AI driver's progress along its route
+type AISnapshot struct {
+	AIDriver
+	LanePos float64
+}
+
+// PedestrianSnapshot is one pedestrian and the legs still to walk
+type PedestrianSnapshot struct {
+	Pedestrian
+	Path []PedestrianLegSnapshot
+}
+
+// PedestrianLegSnapshot is one leg of a pedestrian's path
+type PedestrianLegSnapshot struct {
+	X, Z      float64
+	Crosswalk bool
+}
+
+// ColliderSnapshot is a static collider; JSON cannot hold the +Inf top of
+// an unbounded collider, so it is flagged instead
+type ColliderSnapshot struct {
+	Collider
+	Unbounded bool
+}
+
+// RoadGraphSnapshot is the road graph with its node and edge IDs
+type RoadGraphSnapshot struct {
+	Nodes      []RoadNode
+	Edges      []RoadEdge
+	NextEdgeID int
+}
+
+// RoadWearSnapshot is road wear tuning and the wear of each worn segment
+type RoadWearSnapshot struct {
+	RoadWear
+	Segments []RoadSegment // Length is not used on restore
+}
+
+// SignalSnapshot is a traffic signal with its automatic phase timings
+type SignalSnapshot struct {
+	TrafficSignal
+	GreenTime, YellowTime float64
+}
+
+// DistrictSnapshot is every district and the district of each cell
+type DistrictSnapshot struct {
+	Cells  []int // One per occupancy cell
+	List   []District
+	NextID int
+}
+
+// GreenerySnapshot is the growth of every planting
+type GreenerySnapshot struct {
+	MatureDays float64
+	Growth     map[int]float64
+}
+
+// WeatherSnapshot is the weather and the time until it next changes
+type WeatherSnapshot struct {
+	Weather
+	Next float64
+	Rand uint64 `json:",string"` // Generator state; strings survive JavaScript number precision
+}
+
+// PopulationSnapshot is the population with the residents of each home
+type PopulationSnapshot struct {
+	Population
+	Homes map[int]int
+}
+
+// TripSnapshot is trip generation with the trips being driven
+type TripSnapshot struct {
+	TripDemand
+	Active  []Trip
+	Pending [tripPurposeCount]float64
+	NextID  int
+	Rand    uint64 `json:",string"`
+}
+
+// CitizenSnapshot is the citizen schedules with every citizen
+type CitizenSnapshot struct {
+	Citizens
+	List      []Citizen
+	NextID    int
+	NextAgent int
+	Rand      uint64 `json:",string"`
+}
+
+// FireSnapshot is the fire service with the fires burning
+type FireSnapshot struct {
+	FireService
+	Burning      []Fire
+	DestroyedIDs []int // Burned down and not yet drained by JavaScript
+	NextID       int
+	Rand         uint64 `json:",string"`
+}
+
+// TransitSnapshot is the transit system with its routes, buses and riders
+type TransitSnapshot struct {
+	Transit
+	Routes      []BusRouteSnapshot
+	Riders      []BusRider
+	NextBusID   int
+	NextRiderID int
+	Rand        uint64 `json:",string"`
+}
+
+// TramSnapshot is the tram system with its lines and trams
+type TramSnapshot struct {
+	Trams
+	Lines      []TramLineSnapshot
+	NextTramID int
+	Rand       uint64 `json:",string"`
+}
+
+// TramLineSnapshot is a tram line with its trams
+type TramLineSnapshot struct {
+	TramLine
+	TramList []Tram
+}
+
+// BusRouteSnapshot is a bus route with its buses and riders due
+type BusRouteSnapshot struct {
+	BusRoute
+	BusList []Bus
+	Pending []float64
+}
+
+// EmergencySnapshot is the emergency service with its open incidents
+type EmergencySnapshot struct {
+	EmergencyService
+	Open        []Incident
+	NextID      int
+	NextVehicle int
+}
+
+// RaceSnapshot is a race with its racers
+type RaceSnapshot struct {
+	Race
+	Entries []Racer
+}
+
+// MissionSnapshot is the mission generator with its active missions
+type MissionSnapshot struct {
+	Missions
+	Active []Mission
+	NextID int
+	Rand   uint64 `json:",string"`
+}
+
+// cloneServices copies service categories with their lists of uses and
+// categories
+func cloneServices(services []ServiceCategory) []ServiceCategory {
+	if services == nil {
+		return nil
+	}
+	c := make([]ServiceCategory, len(services))
+	for i, sc := range services {
+		sc.Uses = append([]string(nil), sc.Uses...)
+		sc.Categories = append([]string(nil), sc.Categories...)
+		c[i] = sc
+	}
+	return c
+}
+
+// Snapshot captures the world's state
+func (w *World) Snapshot() WorldSnapshot {
+	s := WorldSnapshot{
+		Version:    SNAPSHOT_VERSION,
+		Time:       w.Time,
+		Seed:       w.Seed,
+		Crowd:      w.Crowd,
+		Terrain:    w.Terrain,
+		Surfaces:   w.Surfaces,
+		Bounds:     w.Bounds,
+		Occupancy:  w.Occupancy.GridSpec,
+		Models:     maps.Clone(w.Models),
+		Zones:      append([]ZoneType(nil), w.Zones.cells...),
+		Districts:  DistrictSnapshot{Cells: append([]int(nil), w.Districts.cells...), NextID: w.Districts.nextID},
+		ZoneRules:  maps.Clone(w.ZoneRules),
+		Services:   cloneServices(w.Services),
+		Greenery:   GreenerySnapshot{MatureDays: w.Greenery.MatureDays, Growth: make(map[int]float64, len(w.Greenery.Growth))},
+		Levels:     BuildingLevels{Enabled: w.Levels.Enabled, UpgradeDays: w.Levels.UpgradeDays, DowngradeDays: w.Levels.DowngradeDays, Buildings: maps.Clone(w.Levels.Buildings)},
+		Sites:      Construction{Instant: w.Sites.Instant, CostPerHour: w.Sites.CostPerHour, Progress: maps.Clone(w.Sites.Progress)},
+		Clock:      *w.Clock,
+		Weather:    WeatherSnapshot{Weather: *w.Weather, Next: w.Weather.next, Rand: w.Weather.rng.State()},
+		Population: PopulationSnapshot{Population: *w.Population, Homes: make(map[int]int, len(w.Population.homes))},
+		Capacities: maps.Clone(w.Capacities),
+		Stats:      *w.Stats,
+		Utilities:  Utilities{Enabled: w.Utilities.Enabled, Supply: maps.Clone(w.Utilities.Supply)},
+		Economy:    *w.Economy,
+		Prices:     maps.Clone(w.Prices),
+		Timestep:   w.Timestep,
+	}
+	s.MetersPerUnit = w.MetersPerUnit
+	for _, d := range w.Districts.List() {
+		s.Districts.List = append(s.Districts.List, *d)
+	}
+
+	// Nothing the world goes on changing is shared, so an in-memory snapshot
+	// stays as taken (physics_rollback.go)
+	if m := w.Surfaces; m != nil {
+		surfaces := *m
+		surfaces.Cells = append([]SurfaceType(nil), m.Cells...)
+		s.Surfaces = &surfaces
+	}
+	for id, growth := range w.Greenery.Growth {
+		s.Greenery.Growth[id] = growth
+	}
+	for id, residents := range w.Population.homes {
+		s.Population.Homes[id] = residents
+	}
+
+	for _, car := range w.cars {
+		c := CarSnapshot{State: car.State, Config: car.Config, Input: car.Input, Manual: car.Manual, Siren: car.Siren, Drift: car.Drift, Violations: car.Violations}
+		if car.AI != nil {
+			c.AI = &AISnapshot{AIDriver: *car.AI, LanePos: car.AI.lanePos}
+		}
+		if car.Trailer != nil {
+			t := *car.Trailer
+			c.Trailer = &t
+		}
+		s.Cars = append(s.Cars, c)
+	}
+	for _, p := range w.pedestrians {
+		ps := PedestrianSnapshot{Pedestrian: *p}
+		for _, leg := range p.path {
+			ps.Path = append(ps.Path, PedestrianLegSnapshot(leg))
+		}
+		s.Pedestrians = append(s.Pedestrians, ps)
+	}
+	for _, c := range w.Colliders.colliders {
+		cs := ColliderSnapshot{Collider: c, Unbounded: math.IsInf(c.Top, 1)}
+		if cs.Unbounded {
+			cs.Top = 0
+		}
+		s.Colliders = append(s.Colliders, cs)
+	}
+	for _, f := range w.Features.Features() {
+		s.Features = append(s.Features, *f)
+	}
+	for _, spot := range w.Parking.Spots() {
+		s.Parking = append(s.Parking, *spot)
+	}
+
+	s.Roads = w.Roads.snapshot()
+	s.RoadWear.RoadWear = *w.RoadWear
+	for _, seg := range w.RoadSegments() {
+		if seg.Wear > 0 {
+			s.RoadWear.Segments = append(s.RoadWear.Segments, seg)
+		}
+	}
+	for _, node := range w.Roads.NodeIDs() {
+		if sig := w.Signals[node]; sig != nil {
+			s.Signals = append(s.Signals, SignalSnapshot{TrafficSignal: *sig, GreenTime: sig.greenTime, YellowTime: sig.yellowTime})
+		}
+	}
+
+	for _, id := range w.Occupancy.IDs() {
+		s.Placements = append(s.Placements, *w.Occupancy.Placement(id))
+	}
+
+	t := w.Trips
+	s.Trips = TripSnapshot{TripDemand: *t, Pending: t.pending, NextID: t.nextID, Rand: t.rng.State()}
+	for _, trip := range t.active {
+		s.Trips.Active = append(s.Trips.Active, *trip)
+	}
+	ci := w.Citizens
+	s.Citizens = CitizenSnapshot{Citizens: *ci, NextID: ci.nextID, NextAgent: ci.nextAgent, Rand: ci.rng.State()}
+	for _, ct := range ci.list {
+		saved := *ct
+		saved.Plan = append([]ScheduleEntry(nil), ct.Plan...)
+		s.Citizens.List = append(s.Citizens.List, saved)
+	}
+	lp := *w.LotParking
+	lp.Spaces, lp.DemandPerJob = maps.Clone(lp.Spaces), maps.Clone(lp.DemandPerJob)
+	lp.Parked, lp.occupied = append([]ParkedCar(nil), lp.Parked...), nil
+	s.LotParking = lp
+	f := w.Fires
+	s.Fires = FireSnapshot{FireService: *f, DestroyedIDs: append([]int(nil), f.destroyed...), NextID: f.nextID, Rand: f.rng.State()}
+	for _, fire := range f.fires {
+		s.Fires.Burning = append(s.Fires.Burning, *fire)
+	}
+	tr := w.Transit
+	s.Transit = TransitSnapshot{Transit: *tr, NextBusID: tr.nextBusID, NextRiderID: tr.nextRiderID, Rand: tr.rng.State()}
+	for _, r := range tr.routes {
+		rs := BusRouteSnapshot{BusRoute: *r, Pending: append([]float64(nil), r.pending...)}
+		rs.Stops = append([]int(nil), r.Stops...)
+		for _, bus := range r.buses {
+			b := *bus
+			b.Riders = append([]int(nil), bus.Riders...)
+			rs.BusList = append(rs.BusList, b)
+		}
+		s.Transit.Routes = append(s.Transit.Routes, rs)
+	}
+	for _, rider := range tr.riders {
+		s.Transit.Riders = append(s.Transit.Riders, *rider)
+	}
+	tm := w.Trams
+	s.Trams = TramSnapshot{Trams: *tm, NextTramID: tm.nextTramID, Rand: tm.rng.State()}
+	for _, l := range tm.lines {
+		ls := TramLineSnapshot{TramLine: *l}
+		ls.Track = append([]TrackPoint(nil), l.Track...)
+		ls.Waiting, ls.Pending = maps.Clone(l.Waiting), maps.Clone(l.Pending)
+		for _, tram := range l.trams {
+			t := *tram
+			t.Aboard = maps.Clone(tram.Aboard)
+			ls.TramList = append(ls.TramList, t)
+		}
+		s.Trams.Lines = append(s.Trams.Lines, ls)
+	}
+	s.Railway = *w.Railway
+	s.Railway.Track = append([]TrackPoint(nil), w.Railway.Track...)
+	s.Railway.Crossings = append([]RailCrossing(nil), w.Railway.Crossings...)
+	em := w.Emergency
+	s.Emergency = EmergencySnapshot{EmergencyService: *em, NextID: em.nextID, NextVehicle: em.nextVehicle}
+	for _, incident := range em.incidents {
+		s.Emergency.Open = append(s.Emergency.Open, *incident)
+	}
+	ms := w.Missions
+	s.Missions = MissionSnapshot{Missions: *ms, NextID: ms.nextID, Rand: ms.rng.State()}
+	for _, mission := range ms.active {
+		s.Missions.Active = append(s.Missions.Active, *mission)
+	}
+	s.Stats.Unlocked = append([]string(nil), w.Stats.Unlocked...)
+	if race := w.Race; race != nil {
+		s.Race = &RaceSnapshot{Race: *race}
+		s.Race.Gates = append([]Gate(nil), race.Gates...)
+		s.Race.racers = nil
+		for _, racer := range race.racers {
+			entry := *racer
+			entry.Sectors = append([]float64(nil), racer.Sectors...)
+			entry.BestSectors = append([]float64(nil), racer.BestSectors...)
+			s.Race.Entries = append(s.Race.Entries, entry)
+		}
+	}
+	return s
+}
+
+// snapshot copies the road graph's nodes and edges with their IDs
+func (g *RoadGraph) snapshot() RoadGraphSnapshot {
+	var s RoadGraphSnapshot
+	for _, id := range g.NodeIDs() {
+		s.Nodes = append(s.Nodes, *g.Node(id))
+	}
+	for _, id := range g.EdgeIDs() {
+		s.Edges = append(s.Edges, *g.Edge(id))
+	}
+	s.NextEdgeID = g.nextEdgeID
+	return s
+}
+
+// restore replaces the road graph with a snapshot of one
+// Roads keep their edge IDs: routes and signal phases refer to them
+func (g *RoadGraph) restore(s RoadGraphSnapshot) error {
+	g.Clear()
+	for _, n := range s.Nodes {
+		g.AddNode(n.ID, n.X, n.Z)
+	}
+	for _, e := range s.Edges {
+		from, to := g.Node(e.From), g.Node(e.To)
+		if from == nil || to == nil || g.Edge(e.ID) != nil {
+			return errors.New("road snapshot has an invalid edge")
+		}
+		edge := e
+		g.edges[e.ID] = &edge
+		from.Out = append(from.Out, e.ID)
+		to.In = append(to.In, e.ID)
+		g.nextEdgeID = max(g.nextEdgeID, e.ID+1)
+	}
+	g.nextEdgeID = max(g.nextEdgeID, s.NextEdgeID)
+	return nil
+}
+
+// Restore replaces the world's state with a snapshot, leaving the world
+// unchanged if the snapshot is inconsistent. The frame profiler, ghosts,
+// autosaves and a recording in progress are kept, since snapshots do not
+// hold them; the edit history is cleared (physics_edits.go)
+func (w *World) Restore(s WorldSnapshot) error {
+	if s.Version != SNAPSHOT_VERSION {
+		return errors.New("unsupported snapshot version")
+	}
+	r := NewWorld()
+	r.Time = s.Time
+	r.SetSeed(s.Seed)
+	r.Crowd = s.Crowd.sanitized()
+	r.SetMetersPerUnit(s.MetersPerUnit) // Invalid scales keep the default
+	r.Bounds = s.Bounds
+	r.Timestep = s.Timestep
+
+	if t := s.Terrain; t != nil {
+		terrain, err := NewTerrain(t.Width, t.Depth, t.CellSize, t.Heights)
+		if err != nil {
+			return err
+		}
+		terrain.OriginX, terrain.OriginZ = t.OriginX, t.OriginZ
+		r.Terrain = terrain
+	}
+	if m := s.Surfaces; m != nil {
+		if m.Width <= 0 || m.Depth <= 0 || len(m.Cells) != m.Width*m.Depth {
+			return errors.New("surface map cells do not match its size")
+		}
+		surfaces := *m
+		surfaces.Cells = append([]SurfaceType(nil), m.Cells...)
+		r.Surfaces = &surfaces
+	}
+
+	if err := r.Roads.restore(s.Roads); err != nil {
+		return err
+	}
+
+	wear := s.RoadWear.RoadWear
+	wear.wear = make(map[roadKey]float64, len(s.RoadWear.Segments))
+	r.RoadWear = &wear
+	for _, seg := range s.RoadWear.Segments {
+		r.SetRoadWear(seg.From, seg.To, seg.Wear)
+	}
+	for _, sig := range s.Signals {
+		if err := r.SetSignal(sig.Node, sig.Phases, sig.GreenTime, sig.YellowTime); err != nil {
+			return err
+		}
+		restored := r.Signals[sig.Node]
+		restored.Phase, restored.Elapsed = sig.Phase, sig.Elapsed
+	}
+
+	for _, c := range s.Colliders {
+		if c.Unbounded {
+			c.Top = math.Inf(1)
+		}
+		r.Colliders.Add(c.Collider)
+	}
+	for _, f := range s.Features {
+		r.Features.Set(f)
+	}
+	for _, spot := range s.Parking {
+		r.Parking.Set(spot)
+	}
+
+	if s.Occupancy.Len() > 0 {
+		r.ResizeTownGrid(s.Occupancy)
+	}
+	for i, p := range s.Placements {
+		if r.Occupancy.Placement(p.ID) != nil {
+			return fmt.Errorf("snapshot placement %d repeats id %d", i, p.ID)
+		}
+		if !r.Occupancy.Add(p) {
+			return fmt.Errorf("snapshot placement %d has invalid bounds", p.ID)
+		}
+	}
+	if s.Zones != nil {
+		if len(s.Zones) != len(r.Zones.cells) {
+			return errors.New("zone cells do not match the occupancy grid")
+		}
+		copy(r.Zones.cells, s.Zones)
+	}
+	// Tables are copied, so the snapshot can be restored again however the
+	// world changes them (physics_rollback.go)
+	if s.Models != nil {
+		r.Models = maps.Clone(s.Models)
+	}
+	if s.ZoneRules != nil {
+		r.ZoneRules = maps.Clone(s.ZoneRules)
+	}
+	if s.Services != nil {
+		r.Services = cloneServices(s.Services)
+	}
+	if s.Capacities != nil {
+		r.Capacities = maps.Clone(s.Capacities)
+	}
+	if s.Prices != nil {
+		r.Prices = maps.Clone(s.Prices)
+	}
+	if s.Districts.Cells != nil {
+		if len(s.Districts.Cells) != len(r.Districts.cells) {
+			return errors.New("district cells do not match the occupancy grid")
+		}
+		copy(r.Districts.cells, s.Districts.Cells)
+	}
+	for _, d := range s.Districts.List {
+		district := d
+		r.Districts.districts[d.ID] = &district
+	}
+	r.Districts.nextID = s.Districts.NextID
+	r.Greenery.MatureDays = s.Greenery.MatureDays
+	for id, growth := range s.Greenery.Growth {
+		r.Greenery.Growth[id] = clamp(growth, 0, 1)
+	}
+	levels := s.Levels
+	levels.Buildings = make(map[int]BuildingLevel, len(s.Levels.Buildings))
+	for id, b := range s.Levels.Buildings {
+		b.Level = max(min(b.Level, BUILDING_MAX_LEVEL), 1)
+		levels.Buildings[id] = b
+	}
+	r.Levels = &levels
+	r.Sites.Instant, r.Sites.CostPerHour = s.Sites.Instant, s.Sites.CostPerHour
+	for id, progress := range s.Sites.Progress {
+		r.Sites.Progress[id] = clamp(progress, 0, 1)
+	}
+
+	for _, c := range s.Cars {
+		car := r.AddCar(c.State.ID, c.State, c.Config)
+		car.Input, car.Manual, car.Siren, car.Drift, car.Violations = c.Input, c.Manual, c.Siren, c.Drift, c.Violations
+		if c.AI != nil {
+			ai := c.AI.AIDriver
+			ai.lanePos = c.AI.LanePos
+			car.AI = &ai
+		}
+		if c.Trailer != nil {
+			t := *c.Trailer
+			t.Config = t.Config.sanitized()
+			car.Trailer = &t
+		}
+	}
+	for _, p := range s.Pedestrians {
+		ped := p.Pedestrian
+		ped.path = nil
+		for _, leg := range p.Path {
+			ped.path = append(ped.path, pedestrianLeg(leg))
+		}
+		r.pedestrianIndex[ped.ID] = len(r.pedestrians)
+		r.pedestrians = append(r.pedestrians, &ped)
+	}
+
+	clock := s.Clock
+	r.Clock = &clock
+	weather := s.Weather.Weather
+	weather.next, weather.rng = s.Weather.Next, r.Weather.rng
+	restoreRand(weather.rng, s.Weather.Rand)
+	r.Weather = &weather
+	pop := s.Population.Population
+	pop.homes = make(map[int]int, len(s.Population.Homes))
+	for id, residents := range s.Population.Homes {
+		pop.homes[id] = residents
+	}
+	r.Population = &pop
+	stats := s.Stats
+	stats.Unlocked = append([]string(nil), s.Stats.Unlocked...)
+	r.Stats = &stats
+	utilities := NewUtilities()
+	utilities.Enabled, utilities.Supply = s.Utilities.Enabled, maps.Clone(s.Utilities.Supply)
+	r.Utilities = utilities
+	economy := s.Economy
+	r.Economy = &economy
+
+	trips := s.Trips.TripDemand
+	trips.rng, trips.pending, trips.nextID, trips.active = r.Trips.rng, s.Trips.Pending, s.Trips.NextID, nil
+	restoreRand(trips.rng, s.Trips.Rand)
+	for _, trip := range s.Trips.Active {
+		active := trip
+		trips.active = append(trips.active, &active)
+	}
+	r.Trips = &trips
+	citizens := s.Citizens.Citizens
+	citizens.rng, citizens.nextID, citizens.nextAgent, citizens.list = r.Citizens.rng, s.Citizens.NextID, s.Citizens.NextAgent, nil
+	restoreRand(citizens.rng, s.Citizens.Rand)
+	for _, ct := range s.Citizens.List {
+		citizen := ct
+		citizen.Plan = append([]ScheduleEntry(nil), ct.Plan...)
+		citizens.list = append(citizens.list, &citizen)
+	}
+	r.Citizens = &citizens
+	lp := s.LotParking
+	lp.Parked, lp.occupied = append([]ParkedCar(nil), s.LotParking.Parked...), nil
+	lp.Spaces, lp.DemandPerJob = maps.Clone(lp.Spaces), maps.Clone(lp.DemandPerJob)
+	if lp.Spaces == nil {
+		lp.Spaces = make(map[string]int)
+	}
+	if lp.DemandPerJob == nil {
+		lp.DemandPerJob = make(map[string]float64)
+	}
+	r.LotParking = &lp
+	fires := s.Fires.FireService
+	fires.rng, fires.nextID, fires.fires = r.Fires.rng, s.Fires.NextID, nil
+	fires.destroyed = append([]int(nil), s.Fires.DestroyedIDs...)
+	restoreRand(fires.rng, s.Fires.Rand)
+	for _, fire := range s.Fires.Burning {
+		burning := fire
+		fires.fires = append(fires.fires, &burning)
+	}
+	r.Fires = &fires
+	transit := s.Transit.Transit
+	transit.rng, transit.nextBusID, transit.nextRiderID = r.Transit.rng, s.Transit.NextBusID, s.Transit.NextRiderID
+	transit.routes, transit.riders = nil, nil
+	restoreRand(transit.rng, s.Transit.Rand)
+	for _, rs := range s.Transit.Routes {
+		route := rs.BusRoute
+		route.Stops = append([]int(nil), rs.Stops...)
+		route.pending = append([]float64(nil), rs.Pending...)
+		route.buses = nil
+		for _, b := range rs.BusList {
+			bus := b
+			bus.Riders = append([]int(nil), b.Riders...)
+			route.buses = append(route.buses, &bus)
+		}
+		transit.routes = append(transit.routes, &route)
+	}
+	for _, rider := range s.Transit.Riders {
+		on := rider
+		transit.riders = append(transit.riders, &on)
+	}
+	r.Transit = &transit
+	trams := s.Trams.Trams
+	trams.rng, trams.nextTramID, trams.lines, trams.closed = r.Trams.rng, s.Trams.NextTramID, nil, make(map[int][]float64)
+	restoreRand(trams.rng, s.Trams.Rand)
+	for _, ls := range s.Trams.Lines {
+		if len(ls.Track) < 2 {
+			continue
+		}
+		line := ls.TramLine
+		line.Track = append([]TrackPoint(nil), ls.Track...)
+		line.Waiting, line.Pending = make(map[int]int), make(map[int]float64)
+		maps.Copy(line.Waiting, ls.Waiting)
+		maps.Copy(line.Pending, ls.Pending)
+		line.trams, line.stations, line.crossings, line.stationRev = nil, nil, nil, -1
+		line.measure()
+		for _, t := range ls.TramList {
+			tram := t
+			tram.Aboard = make(map[int]int)
+			maps.Copy(tram.Aboard, t.Aboard)
+			line.trams = append(line.trams, &tram)
+		}
+		trams.lines = append(trams.lines, &line)
+	}
+	r.Trams = &trams
+	railway := s.Railway
+	railway.Track = append([]TrackPoint(nil), s.Railway.Track...)
+	railway.Crossings = append([]RailCrossing(nil), s.Railway.Crossings...)
+	railway.distances = nil
+	if len(railway.Track) >= 2 {
+		railway.distances = trackDistances(railway.Track)
+	}
+	r.Railway = &railway
+	emergency := s.Emergency.EmergencyService
+	emergency.nextID, emergency.nextVehicle, emergency.incidents = s.Emergency.NextID, s.Emergency.NextVehicle, nil
+	for _, incident := range s.Emergency.Open {
+		open := incident
+		emergency.incidents = append(emergency.incidents, &open)
+	}
+	r.Emergency = &emergency
+	missions := s.Missions.Missions
+	missions.rng, missions.nextID, missions.active = r.Missions.rng, s.Missions.NextID, nil
+	restoreRand(missions.rng, s.Missions.Rand)
+	for _, mission := range s.Missions.Active {
+		active := mission
+		missions.active = append(missions.active, &active)
+	}
+	r.Missions = &missions
+	if s.Race != nil {
+		race := s.Race.Race
+		race.Gates = append([]Gate(nil), race.Gates...)
+		race.racers = nil
+		for _, entry := range s.Race.Entries {
+			racer := entry
+			racer.Sectors = append([]float64(nil), entry.Sectors...)
+			racer.BestSectors = append([]float64(nil), entry.BestSectors...)
+			if len(racer.BestSectors) != len(race.Gates) {
+				racer.BestSectors = make([]float64, len(race.Gates))
+			}
+			race.racers = append(race.racers, &racer)
+		}
+		r.Race = &race
+	}
+
+	r.Census()
+	r.primeSimEvents()
+	r.Profile, r.Recording, r.ghosts, r.Autosaves = w.Profile, w.Recording, w.ghosts, w.Autosaves
+	r.cameras, r.InputProfile, r.Touch = w.cameras, w.InputProfile, w.Touch
+	*w = *r
+	return nil
+}
+
+// restoreRand moves a generator to its saved place; 0 (a snapshot from
+// before generators were saved) leaves it at the start of its seed
+func restoreRand(r *SimRand, state uint64) {
+	if state != 0 {
+		r.SetState(state)
+	}
+}
+
+// ToJSON encodes the world's state
+func (w *World) ToJSON() ([]byte, error) {
+	return json.Marshal(w.Snapshot())
+}
+
+// FromJSON restores the world's state from ToJSON output, upgrading output
+// of older versions (physics_migration.go)
+func (w *World) FromJSON(data []byte) error {
+	s, err := decodeSnapshotJSON(data)
+	if err != nil {
+		return err
+	}
+	return w.Restore(s)
+}
@@ -0,0 +1,22 @@
+package main
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestRestoreRejectsRepeatedPlacementIDs(t *testing.T) {
+	w := NewWorld()
+	s := w.Snapshot()
+	s.Placements = []Placement{
+		testPlacement(3, testHouse, 0, 0, 2, 2),
+		testPlacement(3, testHouse, 4, 0, 6, 2),
+	}
+	err := w.Restore(s)
+	if err == nil || !strings.Contains(err.Error(), "repeats id 3") {
+		t.Fatalf("Restore = %v, want the repeated id reported", err)
+	}
+	if w.Occupancy.Count() != 0 {
+		t.Fatal("a snapshot that failed to restore changed the world")
+	}
+}
@@ -0,0 +1,331 @@
+package main
+
+import (
+	"math"
+	"sync"
+)
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+// Vec2 represents a 2D vector with float64 precision
+type Vec2 struct {
+	X, Y float64
+}
+
+// BoundingBox represents an axis-aligned bounding box
+type BoundingBox struct {
+	MinX, MinY, MaxX, MaxY float64
+}
+
+// CategoryMask represents object categories as bit flags for fast filtering
+type CategoryMask uint32
+
+const (
+	CategoryUnknown   CategoryMask = 1 << iota // 0b00001
+	CategoryVehicle                             // 0b00010
+	CategoryBuilding                            // 0b00100
+	CategoryTerrain                             // 0b01000
+	CategoryProp                                // 0b10000
+	CategoryRoad                                // 0b100000
+	CategoryTree                                // 0b1000000
+	CategoryPark                                // 0b10000000
+)
+
+// categoryFromString converts string category to bitmask
+func categoryFromString(category string) CategoryMask {
+	switch category {
+	case "vehicles":
+		return CategoryVehicle
+	case "buildings":
+		return CategoryBuilding
+	case "terrain":
+		return CategoryTerrain
+	case "props":
+		return CategoryProp
+	case "roads":
+		return CategoryRoad
+	case "trees":
+		return CategoryTree
+	case "park":
+		return CategoryPark
+	default:
+		return CategoryUnknown
+	}
+}
+
+// GameObject represents a game object with position and bounding box
+type GameObject struct {
+	ID           int
+	X, Y         float64
+	BBox         BoundingBox
+	Category     string       // Original string category
+	CategoryMask CategoryMask // Bitmask for fast filtering
+}
+
+// GridKey represents a cell in the spatial grid
+type GridKey struct {
+	X, Y int
+}
+
+// ============================================================================
+// Bit Vector for Grid Occupancy
+// ============================================================================
+
+// BitVector efficiently tracks grid cell occupancy
+// Uses larger bit array to minimize hash collisions
+type BitVector struct {
+	bits             []uint64
+	maxSize          int // Maximum size limit for security
+	boundaryHitCount int // Track how many times safety check triggers (indicates hash issues)
+	mu               sync.Mutex
+}
+
+const (
+	bitVectorInitialSize = 8192  // 524288 bits initially (8x larger)
+	bitVectorMaxSize     = 65536 // 4MB max (security limit)
+)
+
+// NewBitVector creates a new bit vector
+func NewBitVector() *BitVector {
+	return &BitVector{
+		bits:    make([]uint64, bitVectorInitialSize),
+		maxSize: bitVectorMaxSize,
+	}
+}
+
+// gridKeyToIndex converts GridKey to a unique index
+// Uses improved spatial hash to minimize collisions
+func (bv *BitVector) gridKeyToIndex(key GridKey) uint {
+	// Improved spatial hash with better distribution
+	// Offset coordinates to handle negatives
+	const offset = 16384 // Larger offset for better range
+	x := uint(key.X + offset)
+	y := uint(key.Y + offset)
+
+	// Use prime numbers and bit rotation for better distribution
+	hash := x*73856093 ^ y*19349669 ^ (x<<13 | x>>19) ^ (y<<7 | y>>25)
+	return hash
+}
+
+// Set marks a grid cell as occupied
+func (bv *BitVector) Set(key GridKey) {
+	bv.mu.Lock()
+	defer bv.mu.Unlock()
+
+	hash := bv.gridKeyToIndex(key)
+	// Use current size for modulo to ensure consistent hashing
+	idx := hash % uint(len(bv.bits)*64)
+	wordIdx := idx / 64
+	bitIdx := idx % 64
+
+	// Due to modulo operation above, wordIdx should always be < len(bv.bits)
+	// This safety check should never trigger unless there's integer overflow or logic error
+	if int(wordIdx) >= len(bv.bits) {
+		// Track this anomaly - indicates potential hash distribution issue
+		bv.boundaryHitCount++
+		// Log first few occurrences for debugging
+		if bv.boundaryHitCount <= 10 {
+			println("WARNING: BitVector boundary hit detected!")
+			println("  wordIdx:", int(wordIdx), "len(bits):", len(bv.bits))
+			println("  GridKey:", key.X, key.Y, "hash:", hash, "idx:", idx)
+			println("  Total boundary hits:", bv.boundaryHitCount)
+		}
+		// Wrap to stay safe, but this indicates a problem that should be investigated
+		wordIdx = wordIdx % uint(len(bv.bits))
+	}
+
+	bv.bits[wordIdx] |= (1 << bitIdx)
+}
+
+// IsSet checks if a grid cell is occupied
+func (bv *BitVector) IsSet(key GridKey) bool {
+	bv.mu.Lock()
+	defer bv.mu.Unlock()
+
+	hash := bv.gridKeyToIndex(key)
+	// Use current size for modulo to ensure consistent hashing
+	idx := hash % uint(len(bv.bits)*64)
+	wordIdx := idx / 64
+	bitIdx := idx % 64
+
+	// Due to modulo operation above, wordIdx should always be < len(bv.bits)
+	// This safety check should never trigger unless there's integer overflow or logic error
+	if int(wordIdx) >= len(bv.bits) {
+		// Track this anomaly - indicates potential hash distribution issue
+		bv.boundaryHitCount++
+		// Log first few occurrences for debugging
+		if bv.boundaryHitCount <= 10 {
+			println("WARNING: BitVector boundary hit detected in IsSet!")
+			println("  wordIdx:", int(wordIdx), "len(bits):", len(bv.bits))
+			println("  GridKey:", key.X, key.Y, "hash:", hash, "idx:", idx)
+			println("  Total boundary hits:", bv.boundaryHitCount)
+		}
+		// Wrap to stay safe, but this indicates a problem that should be investigated
+		wordIdx = wordIdx % uint(len(bv.bits))
+	}
+
+	return (bv.bits[wordIdx] & (1 << bitIdx)) != 0
+}
+
+// Helper function for min
+func min(a, b int) int {
+	if a < b {
+		return a
+	}
+	return b
+}
+
+// Clear resets all bits
+func (bv *BitVector) Clear() {
+	bv.mu.Lock()
+	defer bv.mu.Unlock()
+
+	for i := range bv.bits {
+		bv.bits[i] = 0
+	}
+	// Reset boundary hit count on clear
+	bv.boundaryHitCount = 0
+}
+
+// GetBoundaryHitCount returns the number of times boundary checks triggered
+// Non-zero values indicate potential hash distribution issues
+func (bv *BitVector) GetBoundaryHitCount() int {
+	bv.mu.Lock()
+	defer bv.mu.Unlock()
+	return bv.boundaryHitCount
+}
+
+// ============================================================================
+// Spatial Grid (Leveraging Go 1.24 Swiss Tables)
+// ============================================================================
+
+// SpatialGrid implements spatial partitioning for efficient collision detection
+//
+// Go 1.24 Performance Optimizations (enabled by default):
+// - Swiss Tables: 30% faster map access, 35% faster assignment, 10-60% faster iteration
+// - Better stack allocation: Small slices allocated on stack vs heap
+// - Improved mutex performance: SpinbitMutex for faster RWMutex operations
+// - Enhanced small object allocation
+// - Bit vector for O(1) occupancy checks (saves memory and CPU)
+type SpatialGrid struct {
+	cellSize   float64
+	cells      map[GridKey][]int // Swiss Tables for object storage
+	occupancy  *BitVector        // Bit vector for fast occupancy checks
+	mu         sync.RWMutex      // SpinbitMutex optimization
+}
+
+// NewSpatialGrid creates a new spatial grid with the given cell size
+func NewSpatialGrid(cellSize float64) *SpatialGrid {
+	return &SpatialGrid{
+		cellSize:  cellSize,
+		cells:     make(map[GridKey][]int, 256), // Swiss Tables optimization
+		occupancy: NewBitVector(),               // Bit vector for fast occupancy checks
+	}
+}
+
+// getCellKey returns the grid cell key for a given position
+func (g *SpatialGrid) getCellKey(x, y float64) GridKey {
+	return GridKey{
+		X: int(math.Floor(x / g.cellSize)),
+		Y: int(math.Floor(y / g.cellSize)),
+	}
+}
+
+// getCellsForBBox returns all grid cells that intersect with a bounding box
+func (g *SpatialGrid) getCellsForBBox(bbox BoundingBox) []GridKey {
+	minKey := g.getCellKey(bbox.MinX, bbox.MinY)
+	maxKey := g.getCellKey(bbox.MaxX, bbox.MaxY)
+
+	// Go 1.25: This slice will likely be stack-allocated due to improved compiler
+	cells := make([]GridKey, 0, (maxKey.X-minKey.X+1)*(maxKey.Y-minKey.Y+1))
+
+	for x := minKey.X; x <= maxKey.X; x++ {
+		for y := minKey.Y; y <= maxKey.Y; y++ {
+			cells = append(cells, GridKey{X: x, Y: y})
+		}
+	}
+
+	return cells
+}
+
+// Insert adds an object to the spatial grid
+func (g *SpatialGrid) Insert(id int, bbox BoundingBox) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+
+	cells := g.getCellsForBBox(bbox)
+	for _, cell := range cells {
+		g.cells[cell] = append(g.cells[cell], id)
+		g.occupancy.Set(cell) // Mark cell as occupied in bit vector
+	}
+}
+
+// Remove removes an object from the spatial grid
+func (g *SpatialGrid) Remove(id int, bbox BoundingBox) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+
+	cells := g.getCellsForBBox(bbox)
+	for _, cell := range cells {
+		objects := g.cells[cell]
+		for i, objID := range objects {
+			if objID == id {
+				// Remove object from slice
+				g.cells[cell] = append(objects[:i], objects[i+1:]...)
+				break
+			}
+		}
+	}
+}
+
+// Query returns all object IDs in cells that intersect with the given bounding box
+// Uses bit vector for O(1) occupancy check before map lookup
+func (g *SpatialGrid) Query(bbox BoundingBox) []int {
+	g.mu.RLock()
+	defer g.mu.RUnlock()
+
+	cells := g.getCellsForBBox(bbox)
+	seen := make(map[int]bool, 16) // Track unique objects
+	results := make([]int, 0, 16)
+
+	// Go 1.24: Map iteration is 10-60% faster with Swiss Tables
+	for _, cell := range cells {
+		// Bit vector pre-check: O(1) operation to skip empty cells
+		if !g.occupancy.IsSet(cell) {
+			continue // Cell definitely empty, skip expensive map lookup
+		}
+
+		if objects, exists := g.cells[cell]; exists {
+			for _, id := range objects {
+				if !seen[id] {
+					seen[id] = true
+					results = append(results, id)
+				}
+			}
+		}
+	}
+
+	return results
+}
+
+// Clear removes all objects from the grid
+func (g *SpatialGrid) Clear() {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+
+	// Go 1.24: Pre-sizing map for 35% faster subsequent assignments
+	g.cells = make(map[GridKey][]int, 256)
+	g.occupancy.Clear() // Clear bit vector
+}
+
+// ============================================================================
+// Collision Detection
+// ============================================================================
+
+// checkAABBCollision checks if two axis-aligned bounding boxes intersect
+func checkAABBCollision(a, b BoundingBox) bool {
+	return a.MinX <= b.MaxX && a.MaxX >= b.MinX &&
+		a.MinY <= b.MaxY && a.MaxY >= b.MinY
+}
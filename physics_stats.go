@@ -0,0 +1,148 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Statistics and Achievements
+// ============================================================================
+
+// The world keeps running totals over the town's whole life: distance driven
+// and top speed reached by the cars driven from JavaScript (AI traffic does
+// not count), their hard collisions with walls and other cars, buildings
+// bought while the economy is enabled (Purchase, physics_economy.go) and the
+// most residents housed at once. Each achievement unlocks the first step its
+// statistic reaches its threshold, queueing an "achievement" simulation
+// event (physics_sim_events.go); unlocks are kept by name, so they survive
+// the table below growing. Statistics and unlocks are part of every snapshot
+// and save (physics_snapshot.go).
+
+// StatKind identifies a statistic
+type StatKind uint8
+
+const (
+	StatDistance   StatKind = iota // units driven
+	StatTopSpeed                   // units/s, fastest speed reached
+	StatCollisions                 // Hard collisions
+	StatBuildingsBought            // Buildings bought with the economy enabled
+	StatResidents                  // Most residents at once
+	statKindCount
+)
+
+// statKindNames are the JavaScript-facing names, indexed by StatKind
+var statKindNames = [statKindCount]string{"distance", "top_speed", "collisions", "buildings_bought", "residents"}
+
+// String returns the JavaScript-facing statistic name
+func (k StatKind) String() string {
+	if k >= statKindCount {
+		return statKindNames[StatDistance]
+	}
+	return statKindNames[k]
+}
+
+// Achievement unlocks when its statistic reaches Threshold
+type Achievement struct {
+	Name      string
+	Stat      StatKind
+	Threshold float64
+}
+
+// achievements are checked in this order every step
+var achievements = []Achievement{
+	{"first_mile", StatDistance, 1000},
+	{"road_trip", StatDistance, 10000},
+	{"long_haul", StatDistance, 100000},
+	{"full_throttle", StatTopSpeed, MAX_SPEED},
+	{"speed_demon", StatTopSpeed, 25},
+	{"fender_bender", StatCollisions, 1},
+	{"demolition_derby", StatCollisions, 100},
+	{"groundbreaking", StatBuildingsBought, 1},
+	{"developer", StatBuildingsBought, 50},
+	{"master_planner", StatBuildingsBought, 250},
+	{"village", StatResidents, 100},
+	{"town", StatResidents, 1000},
+	{"city", StatResidents, 10000},
+}
+
+// Stats are the town's running totals and the achievements unlocked
+type Stats struct {
+	Distance   float64 // units
+	TopSpeed   float64 // units/s
+	Collisions int
+	Buildings  int // Bought with the economy enabled, under its old name for saves
+	Residents  int // Most housed at once
+
+	Unlocked []string // Achievement names, in unlock order
+}
+
+// NewStats creates zeroed statistics with nothing unlocked
+func NewStats() *Stats {
+	return &Stats{}
+}
+
+// Value returns a statistic
+func (s *Stats) Value(k StatKind) float64 {
+	switch k {
+	case StatTopSpeed:
+		return s.TopSpeed
+	case StatCollisions:
+		return float64(s.Collisions)
+	case StatBuildingsBought:
+		return float64(s.Buildings)
+	case StatResidents:
+		return float64(s.Residents)
+	}
+	return s.Distance
+}
+
+// Has reports whether an achievement is unlocked
+func (s *Stats) Has(name string) bool {
+	for _, unlocked := range s.Unlocked {
+		if unlocked == name {
+			return true
+		}
+	}
+	return false
+}
+
+// Reset zeroes the statistics and locks every achievement again
+func (s *Stats) Reset() {
+	*s = Stats{}
+}
+
+// countCollision counts a reported impact involving a car driven from JavaScript
+func (w *World) countCollision(e CollisionEvent) {
+	driven := func(id int) bool {
+		car := w.Car(id)
+		return car != nil && car.AI == nil
+	}
+	switch {
+	case (e.Kind == CollisionStatic || e.Kind == CollisionTrain) && driven(e.A),
+		e.Kind == CollisionVehicle && (driven(e.A) || driven(e.B)):
+		w.Stats.Collisions++
+	}
+}
+
+// stepStats adds a step of dt seconds to the totals and unlocks the
+// achievements reached
+func (w *World) stepStats(dt float64) {
+	s := w.Stats
+	for _, car := range w.cars {
+		if car.AI != nil {
+			continue
+		}
+		speed := simHypot(car.State.VelocityX, car.State.VelocityZ)
+		s.Distance += speed * dt
+		s.TopSpeed = math.Max(s.TopSpeed, speed)
+	}
+	if w.Population.Residents > s.Residents {
+		s.Residents = w.Population.Residents
+	}
+
+	for _, a := range achievements {
+		if s.Value(a.Stat) < a.Threshold || s.Has(a.Name) {
+			continue
+		}
+		s.Unlocked = append(s.Unlocked, a.Name)
+		w.pushSimEvent(SimEvent{Kind: SimAchievementUnlocked, Model: a.Name, Value: a.Threshold})
+	}
+}
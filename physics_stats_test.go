@@ -0,0 +1,37 @@
+package main
+
+import "testing"
+
+func TestBuildingsBoughtCountsOnlyPaidBuildings(t *testing.T) {
+	w := testWorld(t, []ModelSpec{testHouse, NewModelSpec("tree.gltf", "nature", 1, 1)})
+	if !w.Purchase("house.gltf") || w.Stats.Buildings != 0 {
+		t.Fatalf("buildings bought = %d with the economy disabled, want 0", w.Stats.Buildings)
+	}
+
+	w.Economy.Enabled = true
+	if !w.Purchase("house.gltf") || !w.Purchase("tree.gltf") {
+		t.Fatal("buying failed")
+	}
+	if !w.Edit(PlaceEdit(testPlacement(1, testHouse, 0, 0, 2, 2), nil)) {
+		t.Fatal("placing the building failed")
+	}
+	if w.Stats.Buildings != 1 {
+		t.Fatalf("buildings bought = %d after buying one, want 1", w.Stats.Buildings)
+	}
+
+	for i := 0; i < 3; i++ {
+		if !w.Undo() || !w.Redo() {
+			t.Fatal("undo or redo of the placement failed")
+		}
+	}
+	data, err := w.ToJSON()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := w.FromJSON(data); err != nil {
+		t.Fatal(err)
+	}
+	if w.Stats.Buildings != 1 {
+		t.Fatalf("buildings bought = %d after undo, redo and a load, want 1", w.Stats.Buildings)
+	}
+}
@@ -0,0 +1,151 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Surface Map (per-cell ground type)
+// ============================================================================
+
+// SurfaceType identifies the ground material under a cell
+type SurfaceType uint8
+
+const (
+	SurfaceRoad SurfaceType = iota
+	SurfaceGrass
+	SurfaceDirt
+	SurfaceIce
+	SurfaceMud
+	SurfaceWater // Impassable to cars except on bridges (physics_water.go)
+	surfaceTypeCount
+)
+
+// surfaceNames are the JavaScript-facing names, indexed by SurfaceType
+var surfaceNames = [surfaceTypeCount]string{"road", "grass", "dirt", "ice", "mud", "water"}
+
+// surfaceFromString converts a surface name to its type (unknown names map to road)
+func surfaceFromString(name string) SurfaceType {
+	for i, n := range surfaceNames {
+		if n == name {
+			return SurfaceType(i)
+		}
+	}
+	return SurfaceRoad
+}
+
+// String returns the JavaScript-facing surface name
+func (s SurfaceType) String() string {
+	if s >= surfaceTypeCount {
+		return surfaceNames[SurfaceRoad]
+	}
+	return surfaceNames[s]
+}
+
+// SurfaceProperties scales vehicle tuning while driving on a surface
+type SurfaceProperties struct {
+	DragScale     float64 // Multiplier on rolling resistance (1 - Friction)
+	GripScale     float64 // Multiplier on lateral tire grip
+	TractionScale float64 // Multiplier on acceleration and braking
+	MaxSpeedScale float64 // Multiplier on forward and reverse speed caps
+}
+
+// surfaceProperties holds the handling of each surface, indexed by SurfaceType
+var surfaceProperties = [surfaceTypeCount]SurfaceProperties{
+	SurfaceRoad:  {DragScale: 1.0, GripScale: 1.0, TractionScale: 1.0, MaxSpeedScale: 1.0},
+	SurfaceGrass: {DragScale: 2.5, GripScale: 0.8, TractionScale: 0.8, MaxSpeedScale: 0.6},
+	SurfaceDirt:  {DragScale: 1.8, GripScale: 0.7, TractionScale: 0.85, MaxSpeedScale: 0.75},
+	SurfaceIce:   {DragScale: 0.5, GripScale: 0.15, TractionScale: 0.3, MaxSpeedScale: 1.0},
+	SurfaceMud:   {DragScale: 4.0, GripScale: 0.5, TractionScale: 0.6, MaxSpeedScale: 0.4},
+	SurfaceWater: {DragScale: 1.0, GripScale: 1.0, TractionScale: 1.0, MaxSpeedScale: 1.0}, // Only reached on bridges
+}
+
+// onSurface returns the vehicle tuning adjusted for the given surface
+func (cfg *VehicleConfig) onSurface(s SurfaceType) VehicleConfig {
+	if s >= surfaceTypeCount {
+		s = SurfaceRoad
+	}
+	p := surfaceProperties[s]
+
+	eff := *cfg
+	eff.Friction = clamp(1-(1-cfg.Friction)*p.DragScale, 0, 1)
+	eff.LateralGrip *= p.GripScale
+	eff.Acceleration *= p.TractionScale
+	eff.ReverseAcceleration *= p.TractionScale
+	eff.BrakePower *= p.TractionScale
+	eff.MaxSpeed *= p.MaxSpeedScale
+	eff.ReverseMaxSpeed *= p.MaxSpeedScale
+	return eff
+}
+
+// SurfaceMap is a regular grid of surface types
+// Cells are stored row-major: Cells[z*Width+x]
+type SurfaceMap struct {
+	Width, Depth     int
+	CellSize         float64
+	OriginX, OriginZ float64 // World position of the corner of cell (0, 0)
+	Cells            []SurfaceType
+	Default          SurfaceType // Surface reported outside the grid
+}
+
+// NewSurfaceMap creates a surface grid centered on the world origin, filled with fill
+func NewSurfaceMap(width, depth int, cellSize float64, fill SurfaceType) *SurfaceMap {
+	cells := make([]SurfaceType, width*depth)
+	for i := range cells {
+		cells[i] = fill
+	}
+
+	return &SurfaceMap{
+		Width:    width,
+		Depth:    depth,
+		CellSize: cellSize,
+		OriginX:  -float64(width) * cellSize / 2,
+		OriginZ:  -float64(depth) * cellSize / 2,
+		Cells:    cells,
+		Default:  SurfaceRoad,
+	}
+}
+
+// cellIndex returns the cell index for a world position, or -1 outside the grid
+func (m *SurfaceMap) cellIndex(x, z float64) int {
+	cx := int(math.Floor((x - m.OriginX) / m.CellSize))
+	cz := int(math.Floor((z - m.OriginZ) / m.CellSize))
+	if cx < 0 || cx >= m.Width || cz < 0 || cz >= m.Depth {
+		return -1
+	}
+	return cz*m.Width + cx
+}
+
+// At returns the surface at a world position
+func (m *SurfaceMap) At(x, z float64) SurfaceType {
+	idx := m.cellIndex(x, z)
+	if idx < 0 {
+		return m.Default
+	}
+	return m.Cells[idx]
+}
+
+// Paint sets every cell overlapping the bounding box (X/Z in MinX/MinY/MaxX/MaxY)
+func (m *SurfaceMap) Paint(bbox BoundingBox, s SurfaceType) {
+	minX := max(int(math.Floor((bbox.MinX-m.OriginX)/m.CellSize)), 0)
+	minZ := max(int(math.Floor((bbox.MinY-m.OriginZ)/m.CellSize)), 0)
+	maxX := min(int(math.Floor((bbox.MaxX-m.OriginX)/m.CellSize)), m.Width-1)
+	maxZ := min(int(math.Floor((bbox.MaxY-m.OriginZ)/m.CellSize)), m.Depth-1)
+
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			m.Cells[cz*m.Width+cx] = s
+		}
+	}
+}
+
+// surfaceAt returns the surface under a world position; bridges count as
+// road over water (physics_water.go)
+func (w *World) surfaceAt(x, z float64) SurfaceType {
+	if w.Surfaces == nil {
+		return SurfaceRoad
+	}
+	s := w.Surfaces.At(x, z)
+	if s == SurfaceWater && w.onBridge(x, z) {
+		return SurfaceRoad
+	}
+	return s
+}
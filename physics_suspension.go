@@ -0,0 +1,63 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Suspension (body heave, pitch and roll)
+// ============================================================================
+
+// The body rides on the springs and dampers of both axles, modelled as its
+// three modes, each a damped spring at the vehicle's SuspensionFrequency:
+// heave (Bounce, set off by road features, physics_road_features.go), pitch
+// and roll. Weight transfer sets where pitch and roll settle: each substep's
+// acceleration, WeightTransfer rad per GRAVITY of it, squats the body under
+// acceleration, dives it under braking (or into a wall) and rolls it away
+// from the turn, so axle loads shift as they would with separate springs
+// front and rear, left and right. Two-wheelers lean instead of rolling
+// (physics_two_wheelers.go). BodyPitch and BodyRoll are reported beside the
+// terrain's Pitch and Roll, and the transform views add them together.
+
+const (
+	SUSPENSION_FREQUENCY     = 1.5  // Hz, the default body's natural frequency on its springs
+	MAX_SUSPENSION_FREQUENCY = 5.0  // Hz, stiffer springs would outrun the substep
+	SUSPENSION_DAMPING_RATIO = 0.5  // Share of critical damping: the body settles after one overshoot
+	WEIGHT_TRANSFER          = 0.06 // rad of body pitch or roll per GRAVITY of acceleration
+	MAX_BODY_ANGLE           = 0.15 // rad the body pitches or rolls at most on its springs
+	MAX_BOUNCE               = 0.3  // units the body moves up or down at most
+)
+
+// springRates returns the stiffness (1/s²) and damping (1/s) of the
+// vehicle's suspension
+func (cfg *VehicleConfig) springRates() (stiffness, damping float64) {
+	omega := 2 * math.Pi * cfg.SuspensionFrequency
+	return omega * omega, 2 * SUSPENSION_DAMPING_RATIO * omega
+}
+
+// settleSpring advances a damped spring towards its rest position by one
+// substep, limited to ±limit
+func settleSpring(x, v *float64, rest, limit, stiffness, damping, dt float64) {
+	*v -= (stiffness*(*x-rest) + damping*(*v)) * dt
+	*x += *v * dt
+	if math.Abs(*x) > limit {
+		*x = math.Copysign(limit, *x)
+		*v = 0 // Bottomed (or topped) out
+	}
+}
+
+// stepSuspension moves the body on its springs over one substep, in which
+// the car's velocity changed from prevVX, prevVZ
+func (car *CarState) stepSuspension(cfg *VehicleConfig, prevVX, prevVZ, dt float64) {
+	stiffness, damping := cfg.springRates()
+	ax, az := (car.VelocityX-prevVX)/dt, (car.VelocityZ-prevVZ)/dt
+	sin, cos := math.Sin(car.RotationY), math.Cos(car.RotationY)
+	transfer := cfg.WeightTransfer / GRAVITY
+
+	pitch := clamp((ax*sin+az*cos)*transfer, -MAX_BODY_ANGLE, MAX_BODY_ANGLE) // Nose up accelerating
+	roll := 0.0
+	if !cfg.TwoWheeler {
+		roll = clamp((ax*cos-az*sin)*transfer, -MAX_BODY_ANGLE, MAX_BODY_ANGLE) // Left side up turning left
+	}
+	settleSpring(&car.BodyPitch, &car.BodyPitchRate, pitch, MAX_BODY_ANGLE, stiffness, damping, dt)
+	settleSpring(&car.BodyRoll, &car.BodyRollRate, roll, MAX_BODY_ANGLE, stiffness, damping, dt)
+	settleSpring(&car.Bounce, &car.BounceVelocity, 0, MAX_BOUNCE, stiffness, damping, dt)
+}
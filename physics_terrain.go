@@ -0,0 +1,136 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Terrain Heightmap
+// ============================================================================
+
+// GRAVITY is the gravitational acceleration used for slopes (units/s²)
+const GRAVITY = 9.81
+
+// Terrain is a regular heightmap grid sampled with bilinear interpolation
+// Positions outside the grid are treated as flat at the nearest edge height
+type Terrain struct {
+	Width, Depth     int       // Sample counts along X and Z (at least 2 each)
+	CellSize         float64   // World units between neighboring samples
+	OriginX, OriginZ float64   // World position of sample (0, 0)
+	Heights          []float64 // Row-major samples: Heights[z*Width+x]
+}
+
+// NewTerrain creates a heightmap centered on the world origin
+func NewTerrain(width, depth int, cellSize float64, data []float64) (*Terrain, error) {
+	if width < 2 || depth < 2 {
+		return nil, errors.New("terrain needs at least 2x2 samples")
+	}
+	if !(cellSize > 0) {
+		return nil, errors.New("terrain cell size must be positive")
+	}
+	if len(data) != width*depth {
+		return nil, errors.New("terrain data length must equal width*depth")
+	}
+
+	heights := make([]float64, len(data))
+	for i, h := range data {
+		if isFinite(h) {
+			heights[i] = h
+		}
+	}
+
+	return &Terrain{
+		Width:    width,
+		Depth:    depth,
+		CellSize: cellSize,
+		OriginX:  -float64(width-1) * cellSize / 2,
+		OriginZ:  -float64(depth-1) * cellSize / 2,
+		Heights:  heights,
+	}, nil
+}
+
+// sample returns the height stored at grid coordinates (ix, iz)
+func (t *Terrain) sample(ix, iz int) float64 {
+	return t.Heights[iz*t.Width+ix]
+}
+
+// locate maps a world position to its grid cell and the fractional offset
+// within it; inside is false when the position lies beyond the grid edge
+func (t *Terrain) locate(x, z float64) (ix, iz int, fx, fz float64, inside bool) {
+	gx := (x - t.OriginX) / t.CellSize
+	gz := (z - t.OriginZ) / t.CellSize
+	maxX := float64(t.Width - 1)
+	maxZ := float64(t.Depth - 1)
+	inside = gx >= 0 && gx <= maxX && gz >= 0 && gz <= maxZ
+
+	gx = clamp(gx, 0, maxX)
+	gz = clamp(gz, 0, maxZ)
+	ix = min(int(gx), t.Width-2)
+	iz = min(int(gz), t.Depth-2)
+	return ix, iz, gx - float64(ix), gz - float64(iz), inside
+}
+
+// GetHeight returns the interpolated terrain height at a world position
+func (t *Terrain) GetHeight(x, z float64) float64 {
+	ix, iz, fx, fz, _ := t.locate(x, z)
+	h00 := t.sample(ix, iz)
+	h10 := t.sample(ix+1, iz)
+	h01 := t.sample(ix, iz+1)
+	h11 := t.sample(ix+1, iz+1)
+
+	return h00*(1-fx)*(1-fz) + h10*fx*(1-fz) + h01*(1-fx)*fz + h11*fx*fz
+}
+
+// GetGradient returns the slope (dh/dx, dh/dz) at a world position
+func (t *Terrain) GetGradient(x, z float64) (gx, gz float64) {
+	ix, iz, fx, fz, inside := t.locate(x, z)
+	if !inside {
+		return 0, 0 // Flat beyond the edge, matching GetHeight's clamping
+	}
+
+	h00 := t.sample(ix, iz)
+	h10 := t.sample(ix+1, iz)
+	h01 := t.sample(ix, iz+1)
+	h11 := t.sample(ix+1, iz+1)
+
+	gx = ((h10-h00)*(1-fz) + (h11-h01)*fz) / t.CellSize
+	gz = ((h01-h00)*(1-fx) + (h11-h10)*fx) / t.CellSize
+	return gx, gz
+}
+
+// GetNormal returns the unit surface normal at a world position
+func (t *Terrain) GetNormal(x, z float64) (nx, ny, nz float64) {
+	gx, gz := t.GetGradient(x, z)
+	length := math.Sqrt(gx*gx + 1 + gz*gz)
+	return -gx / length, 1 / length, -gz / length
+}
+
+// applySlopeGravity accelerates the car down the local slope
+// Uses the horizontal component of gravity on an incline, g·sinθ·cosθ
+func (w *World) applySlopeGravity(car *CarState, dt float64) {
+	if w.Terrain == nil {
+		return
+	}
+
+	gx, gz := w.Terrain.GetGradient(car.X, car.Z)
+	scale := GRAVITY / (1 + gx*gx + gz*gz)
+	car.VelocityX -= gx * scale * dt
+	car.VelocityZ -= gz * scale * dt
+}
+
+// updateTerrainPose follows the terrain under the car for rendering
+// Pitch is positive when the nose points uphill; roll is positive when the
+// car's left side (the +rotation_y direction) sits higher than its right
+func (w *World) updateTerrainPose(car *CarState) {
+	if w.Terrain == nil {
+		car.Y, car.Pitch, car.Roll = 0, 0, 0
+		return
+	}
+
+	car.Y = w.Terrain.GetHeight(car.X, car.Z)
+	gx, gz := w.Terrain.GetGradient(car.X, car.Z)
+	sin, cos := math.Sin(car.RotationY), math.Cos(car.RotationY)
+	car.Pitch = math.Atan(gx*sin + gz*cos)
+	car.Roll = math.Atan(gx*cos - gz*sin)
+}
@@ -0,0 +1,184 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Procedural Terrain (seeded fractal value noise)
+// ============================================================================
+
+// GenerateTerrain builds a heightmap from octaves of value noise and paints a
+// matching surface map: water in the deepest hollows, mud in the lowlands
+// around it, dirt on steep slopes, ice on the peaks and grass everywhere
+// else. The same seed and config always produce the same landscape, and the
+// town center can be kept flat for building.
+
+const (
+	TERRAIN_GEN_SAMPLES = 41   // Default samples per side (matches the 20-unit ground at 0.5 spacing)
+	TERRAIN_GEN_MAX     = 513  // Largest accepted samples per side
+	WATER_LEVEL         = 0.06 // Normalized height below which ground is water (physics_water.go)
+	WETLAND_LEVEL       = 0.12 // Normalized height below which ground is mud
+	SNOW_LEVEL          = 0.9  // Normalized height above which ground is ice
+	STEEP_GROUND_SLOPE  = 0.6  // rise/run above which ground is bare dirt
+	FLAT_EDGE_BLEND     = 0.35 // Share of the flat radius used to blend into the hills
+)
+
+// TerrainGenConfig controls procedural terrain
+type TerrainGenConfig struct {
+	Seed         int64
+	Width, Depth int     // Height samples along X and Z
+	CellSize     float64 // units between samples
+	Octaves      int
+	Frequency    float64 // Features per unit in the first octave
+	Persistence  float64 // Amplitude multiplier per octave
+	Lacunarity   float64 // Frequency multiplier per octave
+	Amplitude    float64 // units, height of the highest possible peak
+	FlatRadius   float64 // units, radius around the origin kept level (0 disables)
+}
+
+// DefaultTerrainGenConfig returns gentle hills sized to the default ground
+func DefaultTerrainGenConfig() TerrainGenConfig {
+	return TerrainGenConfig{
+		Width:       TERRAIN_GEN_SAMPLES,
+		Depth:       TERRAIN_GEN_SAMPLES,
+		CellSize:    0.5,
+		Octaves:     4,
+		Frequency:   0.12,
+		Persistence: 0.5,
+		Lacunarity:  2.0,
+		Amplitude:   2.0,
+		FlatRadius:  4.0,
+	}
+}
+
+// sanitized replaces invalid settings with their defaults
+func (c TerrainGenConfig) sanitized() TerrainGenConfig {
+	d := DefaultTerrainGenConfig()
+	if c.Width < 2 || c.Width > TERRAIN_GEN_MAX {
+		c.Width = d.Width
+	}
+	if c.Depth < 2 || c.Depth > TERRAIN_GEN_MAX {
+		c.Depth = d.Depth
+	}
+	if c.Octaves < 1 || c.Octaves > 10 {
+		c.Octaves = d.Octaves
+	}
+	c.CellSize = positiveOr(c.CellSize, d.CellSize)
+	c.Frequency = positiveOr(c.Frequency, d.Frequency)
+	c.Persistence = positiveOr(c.Persistence, d.Persistence)
+	c.Lacunarity = positiveOr(c.Lacunarity, d.Lacunarity)
+	if !(c.Amplitude >= 0) || !isFinite(c.Amplitude) {
+		c.Amplitude = d.Amplitude
+	}
+	if !(c.FlatRadius >= 0) || !isFinite(c.FlatRadius) {
+		c.FlatRadius = 0
+	}
+	return c
+}
+
+// latticeValue hashes an integer lattice point to a value in [-1, 1)
+func latticeValue(seed int64, ix, iz int) float64 {
+	h := uint64(seed)*0x9E3779B97F4A7C15 ^ uint64(ix)*0xBF58476D1CE4E5B9 ^ uint64(iz)*0x94D049BB133111EB
+	h ^= h >> 31
+	h *= 0xD6E8FEB86659FD93
+	h ^= h >> 32
+	return float64(h>>11)/(1<<53)*2 - 1
+}
+
+// smoothstep eases t in [0, 1] so noise has no creases at lattice lines
+func smoothstep(t float64) float64 {
+	return t * t * (3 - 2*t)
+}
+
+// valueNoise samples smoothly interpolated lattice noise, in [-1, 1]
+func valueNoise(seed int64, x, z float64) float64 {
+	fx, fz := math.Floor(x), math.Floor(z)
+	ix, iz := int(fx), int(fz)
+	tx, tz := smoothstep(x-fx), smoothstep(z-fz)
+
+	v00 := latticeValue(seed, ix, iz)
+	v10 := latticeValue(seed, ix+1, iz)
+	v01 := latticeValue(seed, ix, iz+1)
+	v11 := latticeValue(seed, ix+1, iz+1)
+	top := v00 + (v10-v00)*tx
+	bottom := v01 + (v11-v01)*tx
+	return top + (bottom-top)*tz
+}
+
+// fractalNoise sums octaves of value noise, normalized to [0, 1]
+func (c *TerrainGenConfig) fractalNoise(x, z float64) float64 {
+	sum, amplitude, total := 0.0, 1.0, 0.0
+	frequency := c.Frequency
+	for o := 0; o < c.Octaves; o++ {
+		sum += amplitude * valueNoise(c.Seed+int64(o)*7919, x*frequency, z*frequency)
+		total += amplitude
+		amplitude *= c.Persistence
+		frequency *= c.Lacunarity
+	}
+	return (sum/total + 1) / 2
+}
+
+// GenerateTerrain builds a centered heightmap and the surface map covering its cells
+func GenerateTerrain(cfg TerrainGenConfig) (*Terrain, *SurfaceMap, error) {
+	cfg = cfg.sanitized()
+	heights := make([]float64, cfg.Width*cfg.Depth)
+	originX := -float64(cfg.Width-1) * cfg.CellSize / 2
+	originZ := -float64(cfg.Depth-1) * cfg.CellSize / 2
+	flat := cfg.fractalNoise(0, 0)
+
+	for iz := 0; iz < cfg.Depth; iz++ {
+		for ix := 0; ix < cfg.Width; ix++ {
+			x := originX + float64(ix)*cfg.CellSize
+			z := originZ + float64(iz)*cfg.CellSize
+			n := cfg.fractalNoise(x, z)
+
+			// Level the town center, blending into the hills at its edge
+			if cfg.FlatRadius > 0 {
+				inner := cfg.FlatRadius * (1 - FLAT_EDGE_BLEND)
+				t := clamp((simHypot(x, z)-inner)/(cfg.FlatRadius-inner), 0, 1)
+				n = flat + (n-flat)*smoothstep(t)
+			}
+			heights[iz*cfg.Width+ix] = n * cfg.Amplitude
+		}
+	}
+
+	terrain, err := NewTerrain(cfg.Width, cfg.Depth, cfg.CellSize, heights)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	surfaces := NewSurfaceMap(cfg.Width-1, cfg.Depth-1, cfg.CellSize, SurfaceGrass)
+	surfaces.Default = SurfaceGrass
+	for i := range surfaces.Cells {
+		cx, cz := i%surfaces.Width, i/surfaces.Width
+		x := surfaces.OriginX + (float64(cx)+0.5)*cfg.CellSize
+		z := surfaces.OriginZ + (float64(cz)+0.5)*cfg.CellSize
+		level := 0.0
+		if cfg.Amplitude > 0 {
+			level = terrain.GetHeight(x, z) / cfg.Amplitude
+		}
+		gx, gz := terrain.GetGradient(x, z)
+
+		switch {
+		case simHypot(gx, gz) > STEEP_GROUND_SLOPE:
+			surfaces.Cells[i] = SurfaceDirt
+		case level < WATER_LEVEL:
+			surfaces.Cells[i] = SurfaceWater
+		case level < WETLAND_LEVEL:
+			surfaces.Cells[i] = SurfaceMud
+		case level > SNOW_LEVEL:
+			surfaces.Cells[i] = SurfaceIce
+		}
+	}
+	return terrain, surfaces, nil
+}
+
+// GenerateTerrain replaces the world's terrain and surface map with a
+// generated landscape
+func (w *World) GenerateTerrain(cfg TerrainGenConfig) error {
+	terrain, surfaces, err := GenerateTerrain(cfg)
+	if err != nil {
+		return err
+	}
+	w.Terrain, w.Surfaces = terrain, surfaces
+	return nil
+}
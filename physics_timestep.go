@@ -0,0 +1,68 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Fixed Timestep (accumulator and render interpolation)
+// ============================================================================
+
+// Advance runs the world in fixed steps whatever the frame rate: frame time
+// is banked in an accumulator and spent FIXED_TIMESTEP at a time, so the
+// simulation is the same at 30, 60 or 144 Hz. What is left over becomes the
+// interpolation alpha, the share of a step the renderer is ahead of the
+// latest state; drawing each entity at previous + (current-previous)*alpha
+// stays smooth at any refresh rate. The previous transforms are kept in
+// stores of their own (physics_views.go).
+
+const (
+	FIXED_TIMESTEP  = 1.0 / 60.0 // s of simulated time per fixed step
+	MAX_FIXED_STEPS = 8          // Steps per Advance before the rest of the backlog is dropped
+)
+
+// FixedTimestep banks frame time until it adds up to whole steps
+type FixedTimestep struct {
+	Step        float64 // s per step
+	Alpha       float64 // 0-1, share of a step banked after the last Advance
+	accumulator float64
+}
+
+// copyFrom makes the store a copy of src
+func (s *TransformStore) copyFrom(src *TransformStore) {
+	if len(s.data) != len(src.data) {
+		s.data = make([]float64, len(src.data))
+	}
+	copy(s.data, src.data)
+	s.Count, s.Capacity = src.Count, src.Capacity
+}
+
+// Advance banks dt seconds of frame time and runs as many fixed steps as it
+// covers, returning the number run. A backlog beyond MAX_FIXED_STEPS (after a
+// stall) is dropped rather than caught up, so slow frames cannot snowball
+func (w *World) Advance(dt float64) int {
+	t := &w.Timestep
+	if !(dt > 0) || !isFinite(dt) {
+		return 0
+	}
+	step := positiveOr(t.Step, FIXED_TIMESTEP)
+
+	t.accumulator += dt
+	steps := 0
+	for t.accumulator >= step && steps < MAX_FIXED_STEPS {
+		w.prevCarTransforms.copyFrom(&w.carTransforms)
+		w.prevPedestrianTransforms.copyFrom(&w.pedestrianTransforms)
+		w.prevTrailerTransforms.copyFrom(&w.trailerTransforms)
+		w.prevTramTransforms.copyFrom(&w.tramTransforms)
+		w.prevTrainTransforms.copyFrom(&w.trainTransforms)
+		w.Step(step)
+		t.accumulator -= step
+		steps++
+	}
+	if t.accumulator >= step {
+		t.accumulator = math.Mod(t.accumulator, step)
+	}
+	t.Alpha = t.accumulator / step
+	if steps > 0 {
+		w.EndFrame()
+	}
+	return steps
+}
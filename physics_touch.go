@@ -0,0 +1,123 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Touch and Tilt Controls (mobile input mapping)
+// ============================================================================
+
+// Mobile players drive with an on-screen joystick or by tilting the device,
+// and both are mapped to raw analog controls here before going through the
+// input profile (physics_input.go) like a gamepad's. The joystick vector
+// (x right, y up, 1 at the edge of the pad) is scaled by
+// JoystickSensitivity: up is throttle, down brake, sideways steering. Tilt
+// steers by the device's roll away from its neutral angle, reaching full
+// lock at TiltRange degrees over TiltSensitivity; with TiltThrottle,
+// pitching forward is throttle and back brake over the same range,
+// otherwise the pedals come from on-screen buttons. The neutral angles are
+// taken from the first reading after CalibrateTilt; with AutoCenter they
+// then drift towards readings within TILT_CENTER_WINDOW degrees of neutral
+// at RecenterRate, following a player who slowly changes how they hold the
+// device without undoing a held turn.
+
+const (
+	TOUCH_SENSITIVITY   = 1.0  // Joystick deflection per unit of vector
+	TILT_RANGE          = 30.0 // Degrees of tilt for full deflection
+	TILT_SENSITIVITY    = 1.0
+	TILT_RECENTER_RATE  = 0.2 // 1/s, how fast the neutral angles follow the device
+	TILT_CENTER_WINDOW  = 5.0 // Degrees either side of neutral that recenter
+	touchProfileMaxGain = 10.0
+)
+
+// TouchProfile tunes the mobile control mapping
+type TouchProfile struct {
+	JoystickSensitivity float64
+	TiltRange           float64 // Degrees
+	TiltSensitivity     float64
+	TiltThrottle        bool // Pitch drives the pedals
+	AutoCenter          bool
+	RecenterRate        float64 // 1/s
+}
+
+// DefaultTouchProfile returns the standard mobile control mapping
+func DefaultTouchProfile() TouchProfile {
+	return TouchProfile{
+		JoystickSensitivity: TOUCH_SENSITIVITY,
+		TiltRange:           TILT_RANGE,
+		TiltSensitivity:     TILT_SENSITIVITY,
+		AutoCenter:          true,
+		RecenterRate:        TILT_RECENTER_RATE,
+	}
+}
+
+// sanitized returns the profile with invalid values defaulted
+func (p TouchProfile) sanitized() TouchProfile {
+	d := DefaultTouchProfile()
+	p.JoystickSensitivity = clamp(positiveOr(p.JoystickSensitivity, d.JoystickSensitivity), 0, touchProfileMaxGain)
+	p.TiltRange = clamp(positiveOr(p.TiltRange, d.TiltRange), 1, 90)
+	p.TiltSensitivity = clamp(positiveOr(p.TiltSensitivity, d.TiltSensitivity), 0, touchProfileMaxGain)
+	p.RecenterRate = positiveOr(p.RecenterRate, d.RecenterRate)
+	return p
+}
+
+// TouchControls maps the device's touch and tilt readings to controls
+type TouchControls struct {
+	Profile TouchProfile
+
+	NeutralRoll, NeutralPitch float64 // Degrees
+	Calibrated                bool    // The neutral angles are set
+}
+
+// NewTouchControls creates the mobile mapping with the default profile,
+// calibrating on the first tilt reading
+func NewTouchControls() *TouchControls {
+	return &TouchControls{Profile: DefaultTouchProfile()}
+}
+
+// CalibrateTilt takes the next tilt reading as the neutral angles
+func (t *TouchControls) CalibrateTilt() {
+	t.Calibrated = false
+}
+
+// Joystick maps an on-screen joystick vector to raw controls
+func (t *TouchControls) Joystick(x, y float64) InputState {
+	if !isFinite(x) || !isFinite(y) {
+		return InputState{}
+	}
+	x, y = x*t.Profile.JoystickSensitivity, y*t.Profile.JoystickSensitivity
+	if mag := simHypot(x, y); mag > 1 {
+		x, y = x/mag, y/mag // Past the edge of the pad is full deflection
+	}
+	return InputState{Throttle: math.Max(y, 0), Brake: math.Max(-y, 0), Steering: x}
+}
+
+// Tilt maps the device's roll and pitch (degrees, right and forward
+// positive) to raw controls during a frame of dt seconds; without
+// TiltThrottle the pedals are left at 0
+func (t *TouchControls) Tilt(roll, pitch, dt float64) InputState {
+	if !isFinite(roll) || !isFinite(pitch) {
+		return InputState{}
+	}
+	p := &t.Profile
+	if !t.Calibrated {
+		t.NeutralRoll, t.NeutralPitch, t.Calibrated = roll, pitch, true
+	}
+	dr, dp := roll-t.NeutralRoll, pitch-t.NeutralPitch
+	if p.AutoCenter && dt > 0 {
+		follow := 1 - simExp(-p.RecenterRate*dt)
+		if math.Abs(dr) <= TILT_CENTER_WINDOW {
+			t.NeutralRoll += dr * follow
+		}
+		if math.Abs(dp) <= TILT_CENTER_WINDOW {
+			t.NeutralPitch += dp * follow
+		}
+	}
+
+	gain := p.TiltSensitivity / p.TiltRange
+	input := InputState{Steering: clamp(dr*gain, -1, 1)}
+	if p.TiltThrottle {
+		input.Throttle = clamp(dp*gain, 0, 1)
+		input.Brake = clamp(-dp*gain, 0, 1)
+	}
+	return input
+}
@@ -0,0 +1,273 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// AI Traffic (route following with IDM car following)
+// ============================================================================
+
+// AI cars are ordinary World cars with an AIDriver attached. Before each step
+// the driver turns its route into throttle/brake/steering input:
+// - Steering: pure pursuit towards a point ahead on the lane centerline
+// - Speed: Intelligent Driver Model (IDM) towards the speed limit, slowing for
+//   the nearest car ahead in the lane, red lights, cars with right of way,
+//   level crossings with a tram or train coming and the end of the route
+// - Two-wheelers ride the bike lane on segments with one
+//   (physics_two_wheelers.go)
+// - Sirens: cars with a siren on drive faster and ignore signals and right
+//   of way; the cars they close on pull over (physics_emergency.go)
+
+const (
+	LANE_WIDTH          = 1.2  // units, lanes are offset to the right of the road centerline
+	AI_LOOKAHEAD        = 3.0  // units, pure pursuit target distance along the lane
+	AI_STEER_GAIN       = 2.0  // Steering per radian of heading error
+	AI_SENSOR_RANGE     = 25.0 // units, how far ahead drivers look for a leader
+	NODE_REACHED_RADIUS = 1.0  // units, distance from a segment end that counts as reaching it
+
+	IDM_TIME_HEADWAY   = 1.2 // s, desired time gap to the leader
+	IDM_MIN_GAP        = 1.5 // units, bumper gap kept when stopped
+	IDM_COMFORT_DECEL  = 6.0 // units/s², comfortable braking
+	IDM_ACCEL_EXPONENT = 4.0 // How sharply acceleration falls off near the desired speed
+)
+
+// AIDriver steers a car along a route through the road graph
+type AIDriver struct {
+	Route     Route
+	EdgeIndex int  // Current position in Route.Edges
+	Lane      int  // Target lane, 0 = rightmost (physics_lanes.go)
+	Arrived   bool // Stopped at the end of the route
+	Yielding  bool // Pulled over for a siren
+
+	lanePos         float64       // Current lateral position in lanes, moving towards Lane
+	laneChangeTimer float64       // s until another lane change may start
+	crossing        crossingState // Claim on the next unsignalized intersection
+}
+
+// laneSegment returns the lane centerline of an edge, offset to the right of
+// travel, as start point, unit direction and length
+// lane may be fractional while changing lanes
+func (w *World) laneSegment(e *RoadEdge, lane float64) (ax, az, dx, dz, length float64) {
+	from, to := w.Roads.Node(e.From), w.Roads.Node(e.To)
+	length = simHypot(to.X-from.X, to.Z-from.Z)
+	if length < 1e-9 {
+		return from.X, from.Z, 0, 1, 0
+	}
+	dx, dz = (to.X-from.X)/length, (to.Z-from.Z)/length
+
+	// Right of travel is (-dz, dx); lane 0 is the outermost (rightmost) lane
+	offset := (float64(e.Lanes-1) - clamp(lane, 0, float64(e.Lanes-1)) + 0.5) * LANE_WIDTH
+	return from.X - dz*offset, from.Z + dx*offset, dx, dz, length
+}
+
+// SpawnAICar adds an AI car at the start of the route between two road
+// nodes, facing along its first segment
+func (w *World) SpawnAICar(id, from, to int, cfg VehicleConfig) error {
+	route, ok := w.driveRoute(from, to, nil)
+	if !ok || len(route.Edges) == 0 {
+		return errors.New("no route between the spawn and destination nodes")
+	}
+
+	ax, az, dx, dz, _ := w.rideSegment(w.Roads.Edge(route.Edges[0]), 0, cfg.TwoWheeler)
+	car := w.AddCar(id, CarState{X: ax, Z: az, RotationY: math.Atan2(dx, dz)}, cfg)
+	car.AI = &AIDriver{Route: route}
+	return nil
+}
+
+// SetAIDestination reroutes an AI car, finishing its current segment first
+// (or starting from the nearest node once it has arrived)
+func (w *World) SetAIDestination(id, to int) error {
+	return w.routeAI(id, to, nil)
+}
+
+// routeAI reroutes an AI car like SetAIDestination, over edges avoid returns
+// false for (nil avoids none)
+func (w *World) routeAI(id, to int, avoid func(e *RoadEdge) bool) error {
+	car := w.Car(id)
+	if car == nil || car.AI == nil {
+		return errors.New("no AI car with that id")
+	}
+
+	ai := car.AI
+	var current *RoadEdge
+	if ai.EdgeIndex < len(ai.Route.Edges) {
+		current = w.Roads.Edge(ai.Route.Edges[ai.EdgeIndex])
+	}
+
+	start := -1
+	if current != nil {
+		start = current.To
+	} else if n := w.Roads.NearestNode(car.State.X, car.State.Z); n != nil {
+		start = n.ID
+	}
+	route, ok := w.driveRoute(start, to, avoid)
+	if !ok {
+		return errors.New("no route to the destination node")
+	}
+
+	if current != nil {
+		route.Nodes = append([]int{current.From}, route.Nodes...)
+		route.Edges = append([]int{current.ID}, route.Edges...)
+		route.Length += current.Length
+		route.TravelTime += current.Length / w.edgeSpeedLimit(current)
+	}
+	car.AI = &AIDriver{Route: route, Lane: ai.Lane, Yielding: ai.Yielding, lanePos: ai.lanePos}
+	return nil
+}
+
+// updateDrivers sets the input of every AI car for the coming dt seconds
+func (w *World) updateDrivers(dt float64) {
+	for _, car := range w.cars {
+		if car.AI != nil {
+			car.Input = w.drive(car, dt)
+		}
+	}
+}
+
+// drive computes one AI car's input
+func (w *World) drive(car *WorldCar, dt float64) InputState {
+	ai := car.AI
+	s := &car.State
+
+	// Drop segments that were removed from the graph since routing
+	if ai.EdgeIndex < len(ai.Route.Edges) && w.Roads.Edge(ai.Route.Edges[ai.EdgeIndex]) == nil {
+		ai.EdgeIndex = len(ai.Route.Edges)
+	}
+	if ai.EdgeIndex >= len(ai.Route.Edges) {
+		ai.Arrived = true
+		return InputState{}
+	}
+
+	e := w.Roads.Edge(ai.Route.Edges[ai.EdgeIndex])
+	w.updateLane(car, e, dt)
+	ax, az, dx, dz, length := w.rideSegment(e, ai.lanePos, car.Config.TwoWheeler)
+	along := (s.X-ax)*dx + (s.Z-az)*dz
+
+	// Advance to the next segment near the end of this one
+	lastEdge := ai.EdgeIndex == len(ai.Route.Edges)-1
+	if along >= length-NODE_REACHED_RADIUS && !lastEdge {
+		w.wearRoad(e.From, e.To, w.RoadWear.PassageWear)
+		ai.EdgeIndex++
+		ai.crossing = crossingState{}
+		return w.drive(car, dt)
+	}
+
+	// Sirens raise the limit for the car running one and slow the cars it
+	// closes on to a crawl towards the curb
+	limit := w.roadSpeedLimit(e, s.X, s.Z)
+	ai.Yielding = w.yieldsToSiren(car)
+	switch {
+	case car.Siren:
+		limit *= positiveOr(w.Emergency.SpeedFactor, EMERGENCY_SPEED_FACTOR)
+	case ai.Yielding:
+		limit = math.Min(limit, PULL_OVER_SPEED)
+	}
+
+	// Pure pursuit: aim at a point AI_LOOKAHEAD ahead along the lane
+	target := math.Min(math.Max(along, 0)+AI_LOOKAHEAD, length)
+	tx, tz := ax+dx*target, az+dz*target
+	if overflow := math.Max(along, 0) + AI_LOOKAHEAD - length; overflow > 0 && !lastEdge {
+		if next := w.Roads.Edge(ai.Route.Edges[ai.EdgeIndex+1]); next != nil {
+			nx, nz, ndx, ndz, nlength := w.rideSegment(next, ai.lanePos, car.Config.TwoWheeler)
+			overflow = math.Min(overflow, nlength)
+			tx, tz = nx+ndx*overflow, nz+ndz*overflow
+		}
+	}
+	if ai.Yielding {
+		tx, tz = tx-dz*PULL_OVER_OFFSET, tz+dx*PULL_OVER_OFFSET // Right of travel
+	}
+	headingError := normalizeAngle(math.Atan2(tx-s.X, tz-s.Z) - s.RotationY)
+	steering := clamp(-headingError*AI_STEER_GAIN, -1, 1) // Positive error = turn left
+
+	// IDM: the leader is the nearest car ahead in the lane, a stop line on red
+	// (physics_signals.go) or when yielding (physics_intersections.go), or the
+	// route end. Only the front car of a queue reaches the stop line check
+	hx, hz := math.Sin(s.RotationY), math.Cos(s.RotationY)
+	speed := s.VelocityX*hx + s.VelocityZ*hz
+	gap, leaderSpeed := math.Inf(1), 0.0
+	if lastEdge {
+		gap = length - along
+	}
+	if stopGap := length - SIGNAL_STOP_DISTANCE - along; stopGap > 0 && stopGap < gap && !car.Siren &&
+		(mustStopForSignal(w.SignalLightFor(e.ID), stopGap, speed) || w.mustYield(car, e, stopGap, speed)) {
+		gap = stopGap // Treat the stop line as a stopped car
+	}
+	if g, found := w.levelCrossingGap(e.ID, along); found && g < gap {
+		gap = g // Closed level crossing (physics_trams.go, physics_trains.go)
+	}
+	if g, v, found := w.leaderAhead(car, hx, hz); found && g < gap {
+		gap, leaderSpeed = g, v
+	}
+
+	accel := idmAcceleration(speed, limit, gap, speed-leaderSpeed, car.Config.Acceleration)
+	if lastEdge && gap < NODE_REACHED_RADIUS && math.Abs(speed) < STOP_SPEED {
+		ai.Arrived = true
+	}
+
+	input := InputState{Steering: steering}
+	switch {
+	case accel > 0:
+		input.Throttle = clamp(accel/car.Config.Acceleration, 0, 1)
+	case speed > STOP_SPEED:
+		input.Brake = clamp(-accel/car.Config.BrakePower, 0, 1)
+	default:
+		// Stopped: releasing the pedals holds still (brake would engage reverse)
+	}
+	return input
+}
+
+// leaderAhead returns the bumper gap to and along-heading speed of the
+// nearest car or pedestrian in front of the given car, overlapping its lane
+func (w *World) leaderAhead(car *WorldCar, hx, hz float64) (gap, speed float64, found bool) {
+	s := &car.State
+	gap = math.Inf(1)
+	for _, other := range w.cars {
+		if other == car {
+			continue
+		}
+		o := &other.State
+		relX, relZ := o.X-s.X, o.Z-s.Z
+		ahead := relX*hx + relZ*hz
+		lateral := relX*hz - relZ*hx
+		if ahead <= 0 || ahead > AI_SENSOR_RANGE || math.Abs(lateral) > LANE_WIDTH*0.8 {
+			continue
+		}
+
+		if g := ahead - car.Config.BodyRadius - other.Config.BodyRadius; g < gap {
+			gap = g
+			speed = o.VelocityX*hx + o.VelocityZ*hz
+			found = true
+		}
+	}
+
+	// Pedestrians on the road (crossing) are stopped obstacles
+	for _, p := range w.pedestrians {
+		relX, relZ := p.X-s.X, p.Z-s.Z
+		ahead := relX*hx + relZ*hz
+		lateral := relX*hz - relZ*hx
+		if ahead <= 0 || ahead > AI_SENSOR_RANGE || math.Abs(lateral) > LANE_WIDTH*0.8 {
+			continue
+		}
+
+		if g := ahead - car.Config.BodyRadius - PEDESTRIAN_RADIUS; g < gap {
+			gap, speed, found = g, 0, true
+		}
+	}
+	return gap, speed, found
+}
+
+// idmAcceleration returns the Intelligent Driver Model acceleration
+// speed and desired are in units/s, gap in units, closing is speed minus the leader's speed
+func idmAcceleration(speed, desired, gap, closing, maxAccel float64) float64 {
+	speed = math.Max(speed, 0)
+	free := 1 - simPow(speed/desired, IDM_ACCEL_EXPONENT)
+	if math.IsInf(gap, 1) {
+		return maxAccel * free
+	}
+
+	desiredGap := IDM_MIN_GAP + math.Max(0, speed*IDM_TIME_HEADWAY+speed*closing/(2*math.Sqrt(maxAccel*IDM_COMFORT_DECEL)))
+	gap = math.Max(gap, 0.01)
+	return maxAccel * (free - (desiredGap/gap)*(desiredGap/gap))
+}
@@ -0,0 +1,203 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Trailers (articulated towing)
+// ============================================================================
+
+// A car can tow one trailer, hitched HitchOffset behind its center. The
+// trailer is a rigid bar from the hitch back to its axle, which is its
+// pivot, and its wheels only roll along the bar: each step the axle is
+// drawn towards the hitch's new position along the bar, so the trailer cuts
+// inside corners when pulled and, pushed in reverse, folds further the more
+// it is already angled, as a real one does. Once the articulation (the car's
+// heading less the trailer's) passes JACKKNIFE_ANGLE the rig jackknifes: the
+// trailer is held at the limit, the car stops dead against it and a
+// "jackknifed" town event is reported; pulling forward straightens it out
+// again. The trailer's Mass slows the car's acceleration and braking. Its
+// body, a circle of BodyRadius around the axle, is pushed out of static
+// colliders and collides with other cars (not its own), under the towing
+// car's ID, and damage from those impacts goes to the towing car. Trailer
+// transforms are written every step beside the cars' (physics_views.go).
+
+const (
+	TRAILER_HITCH_OFFSET = 1.5    // units behind the car's center to the hitch
+	TRAILER_LENGTH       = 4.0    // units from the hitch to the trailer's axle
+	TRAILER_MASS         = 1500.0 // Same scale as VehicleConfig.Mass
+	TRAILER_BODY_RADIUS  = 1.2    // units, collision radius around the axle
+	JACKKNIFE_ANGLE      = 1.4    // rad of articulation (~80°) that jackknifes the rig
+	JACKKNIFE_RELEASE    = 1.2    // rad the articulation must fall back under to clear it
+)
+
+// TrailerConfig holds the dimensions and mass of a trailer
+type TrailerConfig struct {
+	HitchOffset float64 // units behind the towing car's center
+	Length      float64 // units from the hitch to the axle
+	Mass        float64 // Added to the towing car's for acceleration and braking
+	BodyRadius  float64 // units, collision radius around the axle
+}
+
+// DefaultTrailerConfig returns the standard trailer, sized for the delivery truck
+func DefaultTrailerConfig() TrailerConfig {
+	return TrailerConfig{
+		HitchOffset: TRAILER_HITCH_OFFSET,
+		Length:      TRAILER_LENGTH,
+		Mass:        TRAILER_MASS,
+		BodyRadius:  TRAILER_BODY_RADIUS,
+	}
+}
+
+// sanitized returns the config with every non-positive value defaulted
+func (c TrailerConfig) sanitized() TrailerConfig {
+	d := DefaultTrailerConfig()
+	c.HitchOffset = positiveOr(c.HitchOffset, d.HitchOffset)
+	c.Length = positiveOr(c.Length, d.Length)
+	c.Mass = positiveOr(c.Mass, d.Mass)
+	c.BodyRadius = positiveOr(c.BodyRadius, d.BodyRadius)
+	return c
+}
+
+// Trailer is a trailer hitched to a world car
+type Trailer struct {
+	Config               TrailerConfig
+	X, Z                 float64 // Axle position, the trailer's pivot and render origin
+	Y                    float64 // Terrain height at the axle
+	Heading              float64 // rad, from the axle towards the hitch
+	VelocityX, VelocityZ float64 // units/s, the axle's motion over the last step
+	Jackknifed           bool
+}
+
+// hitch returns where a trailer with the given config hooks onto the car
+func (car *CarState) hitch(cfg *TrailerConfig) (x, z float64) {
+	return car.X - math.Sin(car.RotationY)*cfg.HitchOffset, car.Z - math.Cos(car.RotationY)*cfg.HitchOffset
+}
+
+// Articulation returns the signed angle between the towing car's heading
+// and the trailer's (0 when straight)
+func (t *Trailer) Articulation(car *CarState) float64 {
+	return normalizeAngle(car.RotationY - t.Heading)
+}
+
+// HitchTrailer hitches a trailer to a car, lined up straight behind it and
+// replacing any it already tows; returns false if the car does not exist
+func (w *World) HitchTrailer(id int, cfg TrailerConfig) bool {
+	car := w.Car(id)
+	if car == nil {
+		return false
+	}
+	t := &Trailer{Config: cfg.sanitized(), Heading: car.State.RotationY}
+	hx, hz := car.State.hitch(&t.Config)
+	t.X = hx - math.Sin(t.Heading)*t.Config.Length
+	t.Z = hz - math.Cos(t.Heading)*t.Config.Length
+	t.Y = w.groundHeight(t.X, t.Z)
+	car.Trailer = t
+	return true
+}
+
+// UnhitchTrailer drops a car's trailer; returns false if the car does not
+// exist or tows none
+func (w *World) UnhitchTrailer(id int) bool {
+	car := w.Car(id)
+	if car == nil || car.Trailer == nil {
+		return false
+	}
+	car.Trailer = nil
+	return true
+}
+
+// groundHeight returns the terrain height at a point, 0 without terrain
+func (w *World) groundHeight(x, z float64) float64 {
+	if w.Terrain == nil {
+		return 0
+	}
+	return w.Terrain.GetHeight(x, z)
+}
+
+// towingConfig returns the car's tuning with the drive and brakes shared
+// with the trailer's mass
+func (car *WorldCar) towingConfig() VehicleConfig {
+	cfg := car.Config
+	if car.Trailer == nil {
+		return cfg
+	}
+	share := cfg.Mass / (cfg.Mass + car.Trailer.Config.Mass)
+	cfg.Acceleration *= share
+	cfg.ReverseAcceleration *= share
+	cfg.BrakePower *= share
+	return cfg
+}
+
+// stepTrailer drags a car's trailer after the car has moved by dt seconds
+func (w *World) stepTrailer(car *WorldCar, dt float64) {
+	t := car.Trailer
+	if t == nil || !(dt > 0) {
+		return
+	}
+	dt = math.Min(dt, maxPhysicsDelta)
+	s := &car.State
+	length := t.Config.Length
+
+	// The axle keeps to the line from the hitch through where it was
+	hx, hz := s.hitch(&t.Config)
+	dx, dz := t.X-hx, t.Z-hz
+	dist := simHypot(dx, dz)
+	if dist < 1e-9 {
+		dx, dz, dist = -math.Sin(t.Heading), -math.Cos(t.Heading), 1
+	}
+	x, z := hx+dx/dist*length, hz+dz/dist*length
+	heading := math.Atan2(hx-x, hz-z)
+
+	articulation := normalizeAngle(s.RotationY - heading)
+	if math.Abs(articulation) > JACKKNIFE_ANGLE {
+		// Folded as far as it goes: the trailer binds and stops the car
+		heading = normalizeAngle(s.RotationY - math.Copysign(JACKKNIFE_ANGLE, articulation))
+		x, z = hx-math.Sin(heading)*length, hz-math.Cos(heading)*length
+		s.VelocityX, s.VelocityZ = 0, 0
+		if !t.Jackknifed {
+			t.Jackknifed = true
+			w.pushSimEvent(SimEvent{Kind: SimTrailerJackknifed, ID: s.ID, Value: articulation, X: x, Z: z})
+		}
+	} else if t.Jackknifed && math.Abs(articulation) < JACKKNIFE_RELEASE {
+		t.Jackknifed = false
+	}
+
+	for iter := 0; iter < maxCollisionIterations; iter++ {
+		contact, hit := w.Colliders.DeepestContact(x, z, t.Config.BodyRadius)
+		if !hit {
+			break
+		}
+		x += contact.NormalX * contact.Depth
+		z += contact.NormalZ * contact.Depth
+	}
+
+	t.VelocityX, t.VelocityZ = (x-t.X)/dt, (z-t.Z)/dt
+	t.X, t.Z, t.Heading = x, z, heading
+	t.Y = w.groundHeight(x, z)
+}
+
+// trailerBodies returns the collision bodies of every trailer, in car
+// order, with the index of the car towing each
+func (w *World) trailerBodies() (bodies []CarBody, towing []int) {
+	for i, car := range w.cars {
+		if t := car.Trailer; t != nil {
+			bodies = append(bodies, CarBody{
+				ID:        car.State.ID,
+				X:         t.X,
+				Z:         t.Z,
+				VelocityX: t.VelocityX,
+				VelocityZ: t.VelocityZ,
+				Mass:      t.Config.Mass,
+				Radius:    t.Config.BodyRadius,
+			})
+			towing = append(towing, i)
+		}
+	}
+	return bodies, towing
+}
+
+// applyBody copies a resolved collision body back into the trailer
+func (t *Trailer) applyBody(b CarBody) {
+	t.X, t.Z = b.X, b.Z
+	t.VelocityX, t.VelocityZ = b.VelocityX, b.VelocityZ
+}
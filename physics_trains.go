@@ -0,0 +1,312 @@
+package main
+
+import (
+	"errors"
+	"math"
+)
+
+// ============================================================================
+// Freight Train (a railway across the map and its gated crossings)
+// ============================================================================
+
+// The town may have one railway: a rail polyline, usually laid from one
+// edge of the map to another. When enabled, a freight train of a locomotive
+// and Wagons wagons enters at the start of the line every Interval seconds
+// and runs through at Speed until its last wagon has left the far end. It
+// does not stop for anything. Every road segment the rails cross has a gated
+// level crossing: the gates start to lower TRAIN_GATE_WARNING seconds before
+// the locomotive arrives, taking TRAIN_GATE_TRAVEL seconds, and rise once the
+// last wagon is clear. AI cars stop short of a gate that is not fully up and
+// queue behind it (physics_traffic.go). A car that runs into the train, or
+// is run into, is thrown aside with the train's velocity and reports a
+// "train" collision event (physics_events.go), damaging it as a head-on
+// impact at the closing speed would. Locomotive and wagons are written into
+// their own transform store, like cars (physics_views.go).
+
+const (
+	TRAIN_SPEED         = 12.0  // units/s
+	TRAIN_INTERVAL      = 180.0 // s between trains
+	TRAIN_WAGONS        = 8     // Wagons behind the locomotive
+	TRAIN_MAX_WAGONS    = 40    // Longest train JS may ask for
+	TRAIN_WAGON_LENGTH  = 5.0   // units, coupler to coupler, locomotive included
+	TRAIN_WIDTH         = 1.6   // units
+	TRAIN_GATE_WARNING  = 8.0   // s of warning before the locomotive reaches a crossing
+	TRAIN_GATE_TRAVEL   = 2.0   // s for the gates to lower or rise
+	TRAIN_GATE_STOP     = 2.0   // units before the rails cars stop at a gate
+	TRAIN_RESTITUTION   = 0.2   // Bounce of a car off the train
+	TRAIN_LOCOMOTIVE_ID = 0     // Transform ID of the locomotive; wagon i (from 1) has ID i
+	trainContactSlop    = 1e-3  // units, pushed clear past the train's side
+)
+
+// GateState is where a crossing's gates are
+type GateState uint8
+
+const (
+	GateOpen GateState = iota
+	GateLowering
+	GateClosed
+	GateRising
+	gateStateCount
+)
+
+// gateStateNames are the JavaScript-facing names, indexed by GateState
+var gateStateNames = [gateStateCount]string{"open", "lowering", "closed", "rising"}
+
+// String returns the JavaScript-facing gate state name
+func (g GateState) String() string {
+	if g >= gateStateCount {
+		return gateStateNames[GateOpen]
+	}
+	return gateStateNames[g]
+}
+
+// RailCrossing is a gated level crossing of the railway
+type RailCrossing struct {
+	LevelCrossing
+	Gate    GateState
+	Lowered float64 // 0 up to 1 down
+}
+
+// Railway is the freight line and the train on it
+type Railway struct {
+	Enabled  bool
+	Track    []TrackPoint
+	Interval float64 // s between trains
+	Speed    float64 // units/s
+	Wagons   int
+
+	Running   bool    // A train is on the line
+	Along     float64 // Distance of the locomotive's nose along the track
+	NextTrain float64 // s until the next train enters
+	Trains    int     // Trains sent so far; the running one's number is its ID in collision events
+
+	Crossings []RailCrossing
+
+	distances []float64
+}
+
+// NewRailway creates a town without a railway
+func NewRailway() *Railway {
+	return &Railway{Interval: TRAIN_INTERVAL, Speed: TRAIN_SPEED, Wagons: TRAIN_WAGONS, NextTrain: TRAIN_INTERVAL}
+}
+
+// Length returns the length of the track
+func (r *Railway) Length() float64 {
+	if len(r.distances) == 0 {
+		return 0
+	}
+	return r.distances[len(r.distances)-1]
+}
+
+// TrainLength returns the length of the train, locomotive included
+func (r *Railway) TrainLength() float64 {
+	return float64(r.Wagons+1) * TRAIN_WAGON_LENGTH
+}
+
+// SetRailTrack lays the railway on a track of at least two points, taking
+// any running train off it
+func (w *World) SetRailTrack(track []TrackPoint) error {
+	if len(track) < 2 {
+		return errors.New("a railway needs at least two points")
+	}
+	for _, p := range track {
+		if !isFinite(p.X) || !isFinite(p.Z) {
+			return errors.New("railway points must be finite")
+		}
+	}
+	distances := trackDistances(track)
+	if !(distances[len(distances)-1] > 0) {
+		return errors.New("a railway needs some length")
+	}
+	r := w.Railway
+	r.Track, r.distances = append([]TrackPoint(nil), track...), distances
+	r.Running, r.Along, r.Crossings = false, 0, nil
+	return nil
+}
+
+// SendTrain starts a train at the start of the line now, returning false
+// if there is no track or a train is already running
+func (w *World) SendTrain() bool {
+	r := w.Railway
+	if len(r.Track) < 2 || r.Running {
+		return false
+	}
+	r.Running, r.Along = true, 0
+	r.Trains++
+	r.NextTrain = r.Interval
+	return true
+}
+
+// wagonAt returns the center and heading of wagon i (0 is the locomotive)
+func (r *Railway) wagonAt(i int) (x, z, heading float64) {
+	return trackPointAt(r.Track, r.distances, r.Along-(float64(i)+0.5)*TRAIN_WAGON_LENGTH)
+}
+
+// wagonOnTrack reports whether any of wagon i is between the ends of the track
+func (r *Railway) wagonOnTrack(i int) bool {
+	nose := r.Along - float64(i)*TRAIN_WAGON_LENGTH
+	return nose > 0 && nose-TRAIN_WAGON_LENGTH < r.Length()
+}
+
+// stepRailway runs the train and works the crossing gates
+func (w *World) stepRailway(dt float64) {
+	r := w.Railway
+	if len(r.Track) < 2 {
+		return
+	}
+	if r.Running {
+		r.Along += r.Speed * dt
+		if r.Along-r.TrainLength() >= r.Length() {
+			r.Running = false // The last wagon has left
+		}
+	} else if r.Enabled {
+		r.NextTrain -= dt
+		if r.NextTrain <= 0 {
+			w.SendTrain()
+		}
+	}
+
+	// Rebuild the crossings for road edits, keeping the gates where they were
+	old := r.Crossings
+	r.Crossings = nil
+	for _, c := range w.levelCrossings(r.Track, r.distances) {
+		rc := RailCrossing{LevelCrossing: c}
+		for _, o := range old {
+			if o.LevelCrossing == c {
+				rc.Gate, rc.Lowered = o.Gate, o.Lowered
+				break
+			}
+		}
+		r.Crossings = append(r.Crossings, rc)
+	}
+
+	warning := r.Speed * TRAIN_GATE_WARNING
+	for i := range r.Crossings {
+		c := &r.Crossings[i]
+		down := r.Running && c.TrackAlong >= r.Along-r.TrainLength() && c.TrackAlong <= r.Along+warning
+		switch step := dt / TRAIN_GATE_TRAVEL; {
+		case down:
+			c.Lowered = math.Min(c.Lowered+step, 1)
+			c.Gate = GateLowering
+			if c.Lowered >= 1 {
+				c.Gate = GateClosed
+			}
+		default:
+			c.Lowered = math.Max(c.Lowered-step, 0)
+			c.Gate = GateRising
+			if c.Lowered <= 0 {
+				c.Gate = GateOpen
+			}
+		}
+	}
+}
+
+// levelCrossingGap returns how far a car along a road segment is short of
+// where it must stop for the nearest closed tram crossing or railway gate
+// that is not up ahead
+func (w *World) levelCrossingGap(edgeID int, along float64) (float64, bool) {
+	gap, found := w.tramCrossingGap(edgeID, along)
+	for _, c := range w.Railway.Crossings {
+		if c.EdgeID != edgeID || c.Gate == GateOpen {
+			continue
+		}
+		if g := c.EdgeAlong - TRAIN_GATE_STOP - along; g > 0 && g < gap {
+			gap, found = g, true
+		}
+	}
+	return gap, found
+}
+
+// collideTrain throws every car touching the train aside with the train's
+// velocity, reporting and damaging it when the two were closing
+func (w *World) collideTrain() {
+	r := w.Railway
+	if !r.Running {
+		return
+	}
+	half := TRAIN_WAGON_LENGTH / 2
+	for i := 0; i <= r.Wagons; i++ {
+		if !r.wagonOnTrack(i) {
+			continue
+		}
+		wx, wz, heading := r.wagonAt(i)
+		hx, hz := math.Sin(heading), math.Cos(heading)
+		trainVX, trainVZ := hx*r.Speed, hz*r.Speed
+
+		for _, car := range w.cars {
+			s := &car.State
+			radius := car.Config.BodyRadius
+			relX, relZ := s.X-wx, s.Z-wz
+			along := relX*hx + relZ*hz   // Forward of the wagon's center
+			lateral := relX*hz - relZ*hx // Across it
+			overAlong := half + radius - math.Abs(along)
+			overSide := TRAIN_WIDTH/2 + radius - math.Abs(lateral)
+			if overAlong <= 0 || overSide <= 0 {
+				continue
+			}
+
+			// Push out through the nearer face
+			var nx, nz, depth float64
+			if overSide < overAlong {
+				side := math.Copysign(1, lateral)
+				nx, nz, depth = hz*side, -hx*side, overSide
+			} else {
+				end := math.Copysign(1, along)
+				nx, nz, depth = hx*end, hz*end, overAlong
+			}
+			s.X += nx * (depth + trainContactSlop)
+			s.Z += nz * (depth + trainContactSlop)
+
+			into := (s.VelocityX-trainVX)*nx + (s.VelocityZ-trainVZ)*nz
+			if into >= 0 {
+				continue
+			}
+			s.Collided = true
+			w.pushCollisionEvent(CollisionEvent{
+				Kind:        CollisionTrain,
+				A:           s.ID,
+				B:           r.Trains,
+				ImpactSpeed: -into,
+				PointX:      s.X - nx*radius,
+				PointZ:      s.Z - nz*radius,
+				NormalX:     nx,
+				NormalZ:     nz,
+			})
+			w.damageCar(s, -into)
+			bounce := -into * (1 + TRAIN_RESTITUTION)
+			s.VelocityX += bounce * nx
+			s.VelocityZ += bounce * nz
+		}
+	}
+}
+
+// storeTrainTransforms writes the locomotive and every wagon on the track
+// into the train transform store; state is 0 for the locomotive, 1 for wagons
+func (w *World) storeTrainTransforms() {
+	r := w.Railway
+	count := 0
+	if r.Running {
+		for i := 0; i <= r.Wagons; i++ {
+			if r.wagonOnTrack(i) {
+				count++
+			}
+		}
+	}
+	w.trainTransforms.reserve(count)
+	if count == 0 {
+		return
+	}
+	n := 0
+	for i := 0; i <= r.Wagons; i++ {
+		if !r.wagonOnTrack(i) {
+			continue
+		}
+		x, z, heading := r.wagonAt(i)
+		state := 1.0
+		if i == 0 {
+			state = 0
+		}
+		w.trainTransforms.set(n, TRAIN_LOCOMOTIVE_ID+i, x, w.groundHeight(x, z), z, heading, 0, 0, state)
+		n++
+	}
+}
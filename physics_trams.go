@@ -0,0 +1,689 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"math"
+)
+
+// ============================================================================
+// Trams (tram lines on dedicated tracks, stations and level crossings)
+// ============================================================================
+
+// A tram line is a track laid as a polyline, either through world points or
+// along a chain of road nodes (the rails then run down the middle of those
+// roads). Trams are not cars: they run on the rails at up to TRAM_SPEED,
+// braking to stop at every tram station placement within TRAM_STATION_REACH
+// of the track and to keep TRAM_GAP behind the tram ahead. A new tram leaves
+// the start of the line every Headway seconds while fewer than MaxTrams run;
+// on an open line trams are retired at the end, on a loop they go round.
+// Residents within BUS_STOP_CATCHMENT of a station wait there for a tram at
+// RidesPerResident rides a day, following the daily trip profile
+// (physics_trips.go), and board for a station further on chosen by the jobs
+// around it. Where the track crosses a road segment that it does not run
+// along there is a level crossing: while a tram is within
+// TRAM_CROSSING_WARNING of it, or still on it, AI cars stop
+// TRAM_CROSSING_STOP short of the rails (physics_traffic.go). Trams are
+// written into their own transform store every step, like cars
+// (physics_views.go).
+
+const (
+	TRAM_STATION_USE        = "tram_station"
+	TRAM_SPEED              = 8.0  // units/s on open track
+	TRAM_ACCELERATION       = 1.5  // units/s²
+	TRAM_DECELERATION       = 2.0  // units/s², service braking
+	TRAM_LENGTH             = 6.0  // units, nose to tail
+	TRAM_GAP                = 4.0  // units kept behind the tram ahead
+	TRAM_CAPACITY           = 60   // Passengers a tram carries
+	TRAM_HEADWAY            = 60.0 // s between departures
+	TRAM_DWELL_TIME         = 5.0  // s a tram waits at every station
+	TRAM_BOARDING_TIME      = 0.2  // s added to the wait per passenger getting off or on
+	TRAM_STATION_REACH      = 2.0  // units from the track a station may stand
+	TRAM_RIDES_PER_RESIDENT = 0.5  // Tram trips each resident in a catchment makes per day
+	TRAM_CROSSING_WARNING   = 15.0 // units before a crossing an approaching tram closes it
+	TRAM_CROSSING_STOP      = 1.5  // units before the rails cars stop
+	MAX_LINE_TRAMS          = 8    // Trams one line may run
+	TRAM_SEED               = 6    // Seed for passenger destinations (rides repeat run to run)
+)
+
+// TrackPoint is one point of a tram track
+type TrackPoint struct {
+	X, Z float64
+}
+
+// Tram is one tram running on a line
+type Tram struct {
+	ID        int
+	Along     float64     // Distance of its nose along the track
+	Speed     float64     // units/s
+	Served    float64     // Along at the last station it stopped at, -1 before the first
+	Dwell     float64     // s left at the station it is stopped at
+	AtStation int         // Station placement ID while stopped at one, 0 otherwise
+	Aboard    map[int]int // Destination station placement ID -> passengers
+
+	X, Z, Heading float64 // Where Along puts it, for rendering
+}
+
+// Passengers returns how many ride the tram
+func (t *Tram) Passengers() int {
+	n := 0
+	for _, count := range t.Aboard {
+		n += count
+	}
+	return n
+}
+
+// TramLine is a track with the trams on it and the passengers at its stations
+type TramLine struct {
+	ID       int
+	Name     string
+	Track    []TrackPoint
+	Loop     bool    // The track closes back to its first point
+	Headway  float64 // s between departures
+	MaxTrams int
+
+	Boardings  int
+	Alightings int
+	Waiting    map[int]int     // Station placement ID -> passengers waiting
+	Pending    map[int]float64 // Station placement ID -> passengers due, fractional
+	Departure  float64         // s until the next tram leaves the start
+
+	trams      []*Tram    // In departure order
+	distances  []float64  // Along at each track point, and at the closing point of a loop
+	stations   []tramStop // In order along the track
+	stationRev int        // Occupancy revision stations were found at, -1 if stale
+	crossings  []TramCrossing
+}
+
+// tramStop is a station serving a line
+type tramStop struct {
+	ID    int     // Station placement ID
+	Along float64 // Distance along the track
+}
+
+// LevelCrossing is where a track crosses a road segment
+type LevelCrossing struct {
+	EdgeID     int
+	EdgeAlong  float64 // Distance along the segment from its start
+	TrackAlong float64 // Distance along the track
+}
+
+// TramCrossing is a level crossing of a tram line
+type TramCrossing struct {
+	LevelCrossing
+	Closed bool // A tram is approaching or on it
+}
+
+// Trams runs the tram lines
+type Trams struct {
+	RidesPerResident float64
+	Capacity         int // Passengers per tram
+	Speed            float64
+
+	Generated  int // Passengers who came to a station
+	Boardings  int
+	Alightings int
+	Suppressed int // Passengers turned away by a full station
+
+	lines      []*TramLine       // In ID order
+	closed     map[int][]float64 // Road edge ID -> EdgeAlong of its closed crossings
+	nextTramID int
+	rng        *SimRand
+}
+
+// NewTrams creates a tram system without lines
+func NewTrams() *Trams {
+	return &Trams{
+		RidesPerResident: TRAM_RIDES_PER_RESIDENT,
+		Capacity:         TRAM_CAPACITY,
+		Speed:            TRAM_SPEED,
+		closed:           make(map[int][]float64),
+		nextTramID:       1,
+		rng:              newSimRand(TRAM_SEED),
+	}
+}
+
+// Lines returns the tram lines, in ID order
+func (t *Trams) Lines() []*TramLine {
+	return t.lines
+}
+
+// Line returns the line with an ID, or nil
+func (t *Trams) Line(id int) *TramLine {
+	for _, l := range t.lines {
+		if l.ID == id {
+			return l
+		}
+	}
+	return nil
+}
+
+// TramsOn returns the trams running on a line, in departure order
+func (l *TramLine) TramsOn() []*Tram {
+	return l.trams
+}
+
+// Crossings returns the line's level crossings as of the last step
+func (l *TramLine) Crossings() []TramCrossing {
+	return l.crossings
+}
+
+// Stations returns the station placement IDs serving the line, in order along the track
+func (l *TramLine) Stations() []int {
+	ids := make([]int, len(l.stations))
+	for i, s := range l.stations {
+		ids[i] = s.ID
+	}
+	return ids
+}
+
+// Length returns the length of the track, round the loop for loops
+func (l *TramLine) Length() float64 {
+	if len(l.distances) == 0 {
+		return 0
+	}
+	return l.distances[len(l.distances)-1]
+}
+
+// points returns the track points, with the first repeated at the end for loops
+func (l *TramLine) points() []TrackPoint {
+	if l.Loop {
+		return append(append([]TrackPoint(nil), l.Track...), l.Track[0])
+	}
+	return l.Track
+}
+
+// measure sets the distance along the track at each point
+func (l *TramLine) measure() {
+	l.distances = trackDistances(l.points())
+}
+
+// trackDistances returns the distance along a polyline at each of its points
+func trackDistances(pts []TrackPoint) []float64 {
+	distances := make([]float64, len(pts))
+	for i := 1; i < len(pts); i++ {
+		distances[i] = distances[i-1] + simHypot(pts[i].X-pts[i-1].X, pts[i].Z-pts[i-1].Z)
+	}
+	return distances
+}
+
+// pointAt returns the position and heading (as RotationY) at a distance
+// along the track, wrapped round a loop and clamped to the ends otherwise
+func (l *TramLine) pointAt(along float64) (x, z, heading float64) {
+	if length := l.Length(); l.Loop && length > 0 {
+		along = math.Mod(math.Mod(along, length)+length, length)
+	}
+	return trackPointAt(l.points(), l.distances, along)
+}
+
+// trackPointAt returns the position and heading at a distance along a
+// polyline measured by trackDistances, clamped to its ends
+func trackPointAt(pts []TrackPoint, distances []float64, along float64) (x, z, heading float64) {
+	along = clamp(along, 0, distances[len(distances)-1])
+	for i := 1; i < len(pts); i++ {
+		if along <= distances[i] || i == len(pts)-1 {
+			a, b := pts[i-1], pts[i]
+			seg := distances[i] - distances[i-1]
+			f := 0.0
+			if seg > 0 {
+				f = (along - distances[i-1]) / seg
+			}
+			return a.X + (b.X-a.X)*f, a.Z + (b.Z-a.Z)*f, math.Atan2(b.X-a.X, b.Z-a.Z)
+		}
+	}
+	return pts[0].X, pts[0].Z, 0
+}
+
+// ahead returns how far a point along the track is in front of another,
+// going round a loop; negative when it is behind on an open line
+func (l *TramLine) ahead(from, to float64) float64 {
+	d := to - from
+	if l.Loop && d < 0 {
+		d += l.Length()
+	}
+	return d
+}
+
+// TrackAlongRoads returns a track running along a chain of road nodes, each
+// joined to the next by a road segment in either direction
+func (w *World) TrackAlongRoads(nodes []int) ([]TrackPoint, error) {
+	track := make([]TrackPoint, 0, len(nodes))
+	for i, id := range nodes {
+		n := w.Roads.Node(id)
+		if n == nil {
+			return nil, fmt.Errorf("road node %d does not exist", id)
+		}
+		if i > 0 && w.roadBetween(nodes[i-1], id) == nil {
+			return nil, fmt.Errorf("no road joins nodes %d and %d", nodes[i-1], id)
+		}
+		track = append(track, TrackPoint{X: n.X, Z: n.Z})
+	}
+	return track, nil
+}
+
+// roadBetween returns a segment joining two nodes in either direction, or nil
+func (w *World) roadBetween(a, b int) *RoadEdge {
+	for _, id := range w.Roads.EdgeIDs() {
+		if e := w.Roads.Edge(id); (e.From == a && e.To == b) || (e.From == b && e.To == a) {
+			return e
+		}
+	}
+	return nil
+}
+
+// SetTramLine adds or replaces a line on a track of at least two points;
+// changing only the name, headway or tram count keeps the trams running
+func (w *World) SetTramLine(id int, name string, track []TrackPoint, loop bool, headway float64, trams int) error {
+	if len(track) < 2 {
+		return errors.New("a tram track needs at least two points")
+	}
+	for _, p := range track {
+		if !isFinite(p.X) || !isFinite(p.Z) {
+			return errors.New("tram track points must be finite")
+		}
+	}
+	headway = positiveOr(headway, TRAM_HEADWAY)
+	trams = min(max(trams, 0), MAX_LINE_TRAMS)
+
+	t := w.Trams
+	if l := t.Line(id); l != nil {
+		if l.Loop == loop && sameTrack(l.Track, track) {
+			l.Name, l.Headway, l.MaxTrams = name, headway, trams
+			return nil
+		}
+		w.RemoveTramLine(id)
+	}
+	l := &TramLine{
+		ID: id, Name: name, Track: append([]TrackPoint(nil), track...), Loop: loop, Headway: headway, MaxTrams: trams,
+		Waiting: make(map[int]int), Pending: make(map[int]float64), stationRev: -1,
+	}
+	l.measure()
+	if !(l.Length() > 0) {
+		return errors.New("a tram track needs some length")
+	}
+	i := len(t.lines)
+	for i > 0 && t.lines[i-1].ID > id {
+		i--
+	}
+	t.lines = append(t.lines, nil)
+	copy(t.lines[i+1:], t.lines[i:])
+	t.lines[i] = l
+	return nil
+}
+
+// sameTrack reports whether two tracks hold the same points in order
+func sameTrack(a, b []TrackPoint) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for i := range a {
+		if a[i] != b[i] {
+			return false
+		}
+	}
+	return true
+}
+
+// RemoveTramLine takes a line's trams off the rails, sending its waiting
+// passengers home; returns false if there is no such line
+func (w *World) RemoveTramLine(id int) bool {
+	t := w.Trams
+	for i, l := range t.lines {
+		if l.ID == id {
+			t.lines = append(t.lines[:i], t.lines[i+1:]...)
+			w.closeCrossings()
+			return true
+		}
+	}
+	return false
+}
+
+// ClearTrams removes every line and tram and resets the counters
+func (w *World) ClearTrams() {
+	t := w.Trams
+	t.lines = nil
+	t.Generated, t.Boardings, t.Alightings, t.Suppressed = 0, 0, 0, 0
+	w.closeCrossings()
+}
+
+// tramStation returns the tram station placement with an ID, or nil
+func (w *World) tramStation(id int) *Placement {
+	p := w.Occupancy.Placement(id)
+	if p == nil || w.placementUse(p) != TRAM_STATION_USE {
+		return nil
+	}
+	return p
+}
+
+// findStations lists the stations beside a line when anything placed has changed
+func (w *World) findStations(l *TramLine) {
+	if l.stationRev == w.Occupancy.Revision() {
+		return
+	}
+	l.stationRev = w.Occupancy.Revision()
+	l.stations = l.stations[:0]
+
+	pts := l.points()
+	for _, id := range w.Occupancy.IDs() {
+		p := w.tramStation(id)
+		if p == nil {
+			continue
+		}
+		px, pz := p.Center()
+		best, along := math.Inf(1), 0.0
+		for i := 1; i < len(pts); i++ {
+			cx, cz := closestPointOnSegment(px, pz, pts[i-1].X, pts[i-1].Z, pts[i].X, pts[i].Z)
+			if d := simHypot(cx-px, cz-pz); d < best {
+				best, along = d, l.distances[i-1]+simHypot(cx-pts[i-1].X, cz-pts[i-1].Z)
+			}
+		}
+		if best <= TRAM_STATION_REACH {
+			l.stations = append(l.stations, tramStop{ID: id, Along: along})
+		}
+	}
+	for i := 1; i < len(l.stations); i++ { // Insertion sort keeps ties in ID order
+		for j := i; j > 0 && l.stations[j].Along < l.stations[j-1].Along; j-- {
+			l.stations[j], l.stations[j-1] = l.stations[j-1], l.stations[j]
+		}
+	}
+	for id := range l.Waiting {
+		if w.tramStation(id) == nil {
+			delete(l.Waiting, id) // Their station was demolished
+		}
+	}
+	for id := range l.Pending {
+		if w.tramStation(id) == nil {
+			delete(l.Pending, id)
+		}
+	}
+}
+
+// findCrossings lists where the track crosses road segments it does not run along
+func (w *World) findCrossings(l *TramLine) {
+	l.crossings = l.crossings[:0]
+	for _, c := range w.levelCrossings(l.points(), l.distances) {
+		l.crossings = append(l.crossings, TramCrossing{LevelCrossing: c})
+	}
+}
+
+// levelCrossings returns where a polyline measured by trackDistances
+// crosses road segments, in road segment order
+func (w *World) levelCrossings(pts []TrackPoint, distances []float64) []LevelCrossing {
+	var crossings []LevelCrossing
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		for i := 1; i < len(pts); i++ {
+			t, u, ok := segmentIntersection(pts[i-1].X, pts[i-1].Z, pts[i].X, pts[i].Z, a.X, a.Z, b.X, b.Z)
+			if !ok || u <= 0 { // Crossing at the segment's start belongs to the segment before
+				continue
+			}
+			crossings = append(crossings, LevelCrossing{
+				EdgeID:     id,
+				EdgeAlong:  u * simHypot(b.X-a.X, b.Z-a.Z),
+				TrackAlong: distances[i-1] + t*(distances[i]-distances[i-1]),
+			})
+		}
+	}
+	return crossings
+}
+
+// segmentIntersection returns where segments p1-p2 and p3-p4 cross, as a
+// fraction along each; parallel segments do not cross
+func segmentIntersection(x1, z1, x2, z2, x3, z3, x4, z4 float64) (t, u float64, ok bool) {
+	dx1, dz1 := x2-x1, z2-z1
+	dx2, dz2 := x4-x3, z4-z3
+	denom := dx1*dz2 - dz1*dx2
+	if math.Abs(denom) < 1e-9 {
+		return 0, 0, false
+	}
+	t = ((x3-x1)*dz2 - (z3-z1)*dx2) / denom
+	u = ((x3-x1)*dz1 - (z3-z1)*dx1) / denom
+	return t, u, t >= 0 && t <= 1 && u >= 0 && u <= 1
+}
+
+// closeCrossings marks the crossings trams are approaching or on, and
+// hands the closed ones to the AI drivers
+func (w *World) closeCrossings() {
+	t := w.Trams
+	clear(t.closed)
+	for _, l := range t.lines {
+		for i := range l.crossings {
+			c := &l.crossings[i]
+			c.Closed = false
+			for _, tram := range l.trams {
+				if d := l.ahead(tram.Along, c.TrackAlong); (d >= 0 && d <= TRAM_CROSSING_WARNING) ||
+					(tram.Along >= c.TrackAlong && tram.Along-TRAM_LENGTH <= c.TrackAlong) {
+					c.Closed = true
+					break
+				}
+			}
+			if c.Closed {
+				t.closed[c.EdgeID] = append(t.closed[c.EdgeID], c.EdgeAlong)
+			}
+		}
+	}
+}
+
+// tramCrossingGap returns how far a car along a road segment is short of
+// where it must stop for the nearest closed crossing ahead
+func (w *World) tramCrossingGap(edgeID int, along float64) (float64, bool) {
+	gap, found := math.Inf(1), false
+	for _, at := range w.Trams.closed[edgeID] {
+		if g := at - TRAM_CROSSING_STOP - along; g > 0 && g < gap {
+			gap, found = g, true
+		}
+	}
+	return gap, found
+}
+
+// stepTrams dispatches, runs and retires trams, exchanges their passengers
+// at the stations and closes the level crossings ahead of them
+func (w *World) stepTrams(dt float64) {
+	t := w.Trams
+	for _, l := range t.lines {
+		w.findStations(l)
+		w.findCrossings(l)
+		w.dispatchTrams(l, dt)
+		w.runTrams(l, dt)
+	}
+	if t.RidesPerResident > 0 && len(t.lines) > 0 {
+		w.spawnPassengers(dt)
+	}
+	w.closeCrossings()
+}
+
+// dispatchTrams starts a tram from the start of the line when it is due and
+// the rails there are clear
+func (w *World) dispatchTrams(l *TramLine, dt float64) {
+	t := w.Trams
+	l.Departure -= dt
+	if l.Departure > 0 || len(l.trams) >= l.MaxTrams {
+		return
+	}
+	if n := len(l.trams); n > 0 && l.trams[n-1].Along < TRAM_LENGTH+TRAM_GAP {
+		return // The last tram out is still at the start
+	}
+	tram := &Tram{ID: t.nextTramID, Served: -1, Aboard: make(map[int]int)}
+	tram.X, tram.Z, tram.Heading = l.pointAt(0)
+	l.trams = append(l.trams, tram)
+	t.nextTramID++
+	l.Departure = l.Headway
+}
+
+// runTrams moves a line's trams on, front first
+func (w *World) runTrams(l *TramLine, dt float64) {
+	t := w.Trams
+	length := l.Length()
+	kept := l.trams[:0]
+	for i, tram := range l.trams {
+		if tram.Dwell > 0 {
+			tram.Dwell -= dt
+			if tram.Dwell <= 0 {
+				tram.Dwell, tram.AtStation = 0, 0
+			}
+			kept = append(kept, tram)
+			continue
+		}
+
+		// Stop at the next station, behind the tram ahead, or at the end
+		gap := math.Inf(1)
+		if !l.Loop {
+			gap = length - tram.Along
+		}
+		var next *tramStop
+		for j := range l.stations {
+			s := &l.stations[j]
+			if d := l.ahead(tram.Along, s.Along); d >= 0 && d < gap && s.Along != tram.Served {
+				next, gap = s, d
+			}
+		}
+		leaderGap := math.Inf(1)
+		switch {
+		case i > 0:
+			leaderGap = l.ahead(tram.Along, l.trams[i-1].Along) - TRAM_LENGTH - TRAM_GAP
+		case l.Loop && len(l.trams) > 1:
+			leaderGap = l.ahead(tram.Along, l.trams[len(l.trams)-1].Along) - TRAM_LENGTH - TRAM_GAP
+		}
+		stopGap := math.Max(math.Min(gap, leaderGap), 0)
+
+		target := math.Min(positiveOr(t.Speed, TRAM_SPEED), math.Sqrt(2*TRAM_DECELERATION*stopGap))
+		if tram.Speed < target {
+			tram.Speed = math.Min(tram.Speed+TRAM_ACCELERATION*dt, target)
+		} else {
+			tram.Speed = target // Braking to hold the stopping distance
+		}
+		move := math.Min(tram.Speed*dt, stopGap)
+		tram.Along += move
+
+		switch {
+		case next != nil && gap-move < 1e-6 && leaderGap >= gap:
+			tram.Along, tram.Speed, tram.Served = next.Along, 0, next.Along
+			tram.AtStation = next.ID
+			tram.Dwell = TRAM_DWELL_TIME + TRAM_BOARDING_TIME*float64(w.exchangePassengers(l, tram, next))
+		case !l.Loop && tram.Along >= length-1e-6:
+			for _, count := range tram.Aboard {
+				l.Alightings += count // Everyone gets off at the end of the line
+				t.Alightings += count
+			}
+			continue
+		case l.Loop && tram.Along >= length:
+			tram.Along -= length
+			if tram.Served >= 0 {
+				tram.Served -= length
+			}
+		}
+		tram.X, tram.Z, tram.Heading = l.pointAt(tram.Along)
+		kept = append(kept, tram)
+	}
+	l.trams = kept
+}
+
+// exchangePassengers lets the passengers for a station off a tram and the
+// ones waiting there on, returning how many got off or on
+func (w *World) exchangePassengers(l *TramLine, tram *Tram, stop *tramStop) int {
+	t := w.Trams
+	off := tram.Aboard[stop.ID]
+	delete(tram.Aboard, stop.ID)
+	l.Alightings += off
+	t.Alightings += off
+
+	on := min(l.Waiting[stop.ID], max(t.Capacity-tram.Passengers(), 0))
+	boarded := 0
+	for ; boarded < on; boarded++ {
+		dest := w.pickDestination(l, stop)
+		if dest == 0 {
+			break
+		}
+		tram.Aboard[dest]++
+	}
+	l.Waiting[stop.ID] -= boarded
+	if l.Waiting[stop.ID] <= 0 {
+		delete(l.Waiting, stop.ID)
+	}
+	l.Boardings += boarded
+	t.Boardings += boarded
+	return off + boarded
+}
+
+// pickDestination chooses a station further along the line for a passenger
+// boarding at a stop, busier the more jobs are around it; 0 if there is none
+func (w *World) pickDestination(l *TramLine, from *tramStop) int {
+	jobs := func(p *Placement) int { return w.capacity(p).Jobs }
+	weights := make([]int, len(l.stations))
+	total := 0
+	for i, s := range l.stations {
+		if s.ID == from.ID || (!l.Loop && s.Along <= from.Along) {
+			continue
+		}
+		weights[i] = 1
+		for _, q := range w.nearStop(w.tramStation(s.ID)) {
+			weights[i] += jobs(q)
+		}
+		total += weights[i]
+	}
+	if total == 0 {
+		return 0
+	}
+	n := w.Trams.rng.Intn(total)
+	for i, weight := range weights {
+		if n -= weight; n < 0 {
+			return l.stations[i].ID
+		}
+	}
+	return 0
+}
+
+// spawnPassengers brings the passengers due this step from the homes
+// around each station that has somewhere to go
+func (w *World) spawnPassengers(dt float64) {
+	t := w.Trams
+	intensity := 0.0
+	for _, v := range tripIntensity(w.Clock.HourOfDay()) {
+		intensity += v
+	}
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+
+	for _, l := range t.lines {
+		for i, s := range l.stations {
+			if !l.Loop && i == len(l.stations)-1 {
+				continue // Nowhere further to go
+			}
+			residents := 0
+			for _, q := range w.nearStop(w.tramStation(s.ID)) {
+				residents += w.Population.HomeResidents(q.ID)
+			}
+			l.Pending[s.ID] += float64(residents) * t.RidesPerResident * intensity * hours
+			for l.Pending[s.ID] >= 1 {
+				l.Pending[s.ID]--
+				if l.Waiting[s.ID] >= MAX_WAITING_RIDERS {
+					t.Suppressed++
+					continue
+				}
+				l.Waiting[s.ID]++
+				t.Generated++
+			}
+		}
+	}
+}
+
+// storeTramTransforms writes every tram, in line order, into the tram
+// transform store; state is 1 while stopped at a station
+func (w *World) storeTramTransforms() {
+	count := 0
+	for _, l := range w.Trams.lines {
+		count += len(l.trams)
+	}
+	w.tramTransforms.reserve(count)
+	i := 0
+	for _, l := range w.Trams.lines {
+		for _, tram := range l.trams {
+			stopped := 0.0
+			if tram.AtStation != 0 {
+				stopped = 1
+			}
+			w.tramTransforms.set(i, tram.ID, tram.X, w.groundHeight(tram.X, tram.Z), tram.Z, tram.Heading, 0, 0, stopped)
+			i++
+		}
+	}
+}
@@ -0,0 +1,595 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+)
+
+// ============================================================================
+// Bus Transit (routes, stops and riders)
+// ============================================================================
+
+// A bus route is a loop of bus stop placements laid out by the player. Its
+// buses are AI cars that drive from the road node nearest one stop to the
+// node nearest the next, wait there while riders get off and on, and carry
+// on round the loop. Residents living within BUS_STOP_CATCHMENT of a stop
+// make RidesPerResident bus trips a day, spread over it like car trips
+// (physics_trips.go): each rider is a pedestrian who walks from their home
+// to the stop, waits, boards a bus of the route going to a stop chosen by
+// the jobs around it, rides there and walks on to a workplace near it,
+// leaving the world on arrival. Riders who would find MAX_WAITING_RIDERS
+// already at their stop stay home and are counted as suppressed. Boardings
+// and alightings are counted per route and town-wide for ridership stats.
+
+const (
+	BUS_STOP_USE           = "bus_stop"
+	BUS_CAR_ID_BASE        = 1 << 22 // Car IDs from here up are buses
+	BUS_RIDER_ID_BASE      = 1 << 22 // Pedestrian IDs from here up are bus riders
+	BUS_CAPACITY           = 30      // Riders a bus carries
+	BUS_DWELL_TIME         = 3.0     // s a bus waits at every stop
+	BUS_BOARDING_TIME      = 0.5     // s added to the wait per rider getting off or on
+	BUS_STOP_REACH         = 3.0     // units from a stop's road node a bus must stop to serve it
+	BUS_STOP_CATCHMENT     = 8.0     // units from a stop that riders walk from and to
+	BUS_RIDES_PER_RESIDENT = 0.5     // Bus trips each resident in a catchment makes per day
+	MAX_WAITING_RIDERS     = 20      // Riders walking to or waiting at one stop of a route
+	MAX_ROUTE_BUSES        = 10      // Buses one route may run
+	BUS_SEED               = 4       // Seed for rider selection (riders repeat run to run)
+)
+
+// BusRiderState is where a rider is on their journey
+type BusRiderState uint8
+
+const (
+	RiderWalking BusRiderState = iota // To the stop
+	RiderWaiting                      // At the stop
+	RiderRiding                       // On a bus
+	RiderLeaving                      // From the stop they got off at to their destination
+	busRiderStateCount
+)
+
+// busRiderStateNames are the JavaScript-facing names, indexed by BusRiderState
+var busRiderStateNames = [busRiderStateCount]string{"walking", "waiting", "riding", "leaving"}
+
+// String returns the JavaScript-facing state name
+func (s BusRiderState) String() string {
+	if s >= busRiderStateCount {
+		return busRiderStateNames[RiderWalking]
+	}
+	return busRiderStateNames[s]
+}
+
+// BusRoute is a loop of bus stops and the buses serving it
+type BusRoute struct {
+	ID    int
+	Name  string
+	Stops []int // Bus stop placement IDs, served in order and back to the first
+	Buses int   // Buses to keep on the route
+
+	Boardings  int
+	Alightings int
+
+	buses   []*Bus
+	pending []float64 // Riders due at each stop, fractional
+}
+
+// Bus is an AI car serving a route
+type Bus struct {
+	CarID  int
+	Stop   int     // Index in the route's Stops of the stop driven to or being served
+	AtStop bool    // Waiting at the stop
+	Dwell  float64 // s left at the stop
+	Riders []int   // Rider IDs aboard
+}
+
+// BusRider is one resident's bus journey
+type BusRider struct {
+	ID           int // Also the pedestrian ID while on foot
+	Route        int
+	From, To     int // Bus stop placement IDs
+	State        BusRiderState
+	BusID        int     // Car ID of the bus while riding, 0 otherwise
+	DestX, DestZ float64 // Where they walk after getting off
+}
+
+// Transit runs the bus routes and their riders
+type Transit struct {
+	RidesPerResident float64
+	Capacity         int // Riders per bus
+	Vehicle          VehicleConfig
+
+	Generated  int // Riders who set off for a stop
+	Boardings  int
+	Alightings int
+	Completed  int // Riders who reached their destination
+	Suppressed int // Riders turned away by a full stop
+
+	routes      []*BusRoute // In ID order
+	riders      []*BusRider // In spawn order
+	nextBusID   int
+	nextRiderID int
+	rng         *SimRand
+}
+
+// NewTransit creates a transit system without routes
+func NewTransit() *Transit {
+	return &Transit{
+		RidesPerResident: BUS_RIDES_PER_RESIDENT,
+		Capacity:         BUS_CAPACITY,
+		Vehicle:          BusConfig(),
+		nextBusID:        BUS_CAR_ID_BASE,
+		nextRiderID:      BUS_RIDER_ID_BASE,
+		rng:              newSimRand(BUS_SEED),
+	}
+}
+
+// Routes returns the bus routes, in ID order
+func (t *Transit) Routes() []*BusRoute {
+	return t.routes
+}
+
+// Riders returns the riders on their way, in spawn order
+func (t *Transit) Riders() []*BusRider {
+	return t.riders
+}
+
+// Route returns the route with an ID, or nil
+func (t *Transit) Route(id int) *BusRoute {
+	for _, r := range t.routes {
+		if r.ID == id {
+			return r
+		}
+	}
+	return nil
+}
+
+// BusesOn returns the buses serving a route
+func (r *BusRoute) BusesOn() []*Bus {
+	return r.buses
+}
+
+// rider returns the rider with an ID, or nil
+func (t *Transit) rider(id int) *BusRider {
+	for _, r := range t.riders {
+		if r.ID == id {
+			return r
+		}
+	}
+	return nil
+}
+
+// Waiting returns the riders walking to or waiting at a stop of a route
+// (every stop when stop is 0)
+func (t *Transit) Waiting(route, stop int) int {
+	n := 0
+	for _, r := range t.riders {
+		if r.Route == route && (stop == 0 || r.From == stop) && (r.State == RiderWalking || r.State == RiderWaiting) {
+			n++
+		}
+	}
+	return n
+}
+
+// Riding returns the riders aboard a route's buses
+func (r *BusRoute) Riding() int {
+	n := 0
+	for _, bus := range r.buses {
+		n += len(bus.Riders)
+	}
+	return n
+}
+
+// busStop returns the bus stop placement with an ID, or nil
+func (w *World) busStop(id int) *Placement {
+	p := w.Occupancy.Placement(id)
+	if p == nil || w.placementUse(p) != BUS_STOP_USE {
+		return nil
+	}
+	return p
+}
+
+// stopNode returns the road node buses serve a stop from, or nil
+func (w *World) stopNode(id int) *RoadNode {
+	p := w.busStop(id)
+	if p == nil {
+		return nil
+	}
+	return w.Roads.NearestNode(p.Center())
+}
+
+// SetBusRoute adds or replaces a route through at least two bus stops run
+// by a number of buses; changing only the name or bus count keeps the buses
+// on the road and their riders aboard
+func (w *World) SetBusRoute(id int, name string, stops []int, buses int) error {
+	if len(stops) < 2 {
+		return errors.New("a bus route needs at least two stops")
+	}
+	for _, stop := range stops {
+		if w.busStop(stop) == nil {
+			return fmt.Errorf("placement %d is not a bus stop", stop)
+		}
+	}
+	buses = min(max(buses, 0), MAX_ROUTE_BUSES)
+
+	t := w.Transit
+	if r := t.Route(id); r != nil {
+		if sameInts(r.Stops, stops) {
+			r.Name, r.Buses = name, buses
+			return nil
+		}
+		w.RemoveBusRoute(id)
+	}
+	r := &BusRoute{ID: id, Name: name, Stops: append([]int(nil), stops...), Buses: buses, pending: make([]float64, len(stops))}
+	i := len(t.routes)
+	for i > 0 && t.routes[i-1].ID > id {
+		i--
+	}
+	t.routes = append(t.routes, nil)
+	copy(t.routes[i+1:], t.routes[i:])
+	t.routes[i] = r
+	return nil
+}
+
+// sameInts reports whether two slices hold the same values in order
+func sameInts(a, b []int) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for i := range a {
+		if a[i] != b[i] {
+			return false
+		}
+	}
+	return true
+}
+
+// RemoveBusRoute takes a route's buses off the road and sends its riders
+// who have not got off yet home; returns false if there is no such route
+func (w *World) RemoveBusRoute(id int) bool {
+	t := w.Transit
+	for i, r := range t.routes {
+		if r.ID != id {
+			continue
+		}
+		for _, bus := range r.buses {
+			w.RemoveCar(bus.CarID)
+		}
+		kept := t.riders[:0]
+		for _, rider := range t.riders {
+			if rider.Route == id && rider.State != RiderLeaving {
+				w.RemovePedestrian(rider.ID)
+				continue
+			}
+			kept = append(kept, rider)
+		}
+		t.riders = kept
+		t.routes = append(t.routes[:i], t.routes[i+1:]...)
+		return true
+	}
+	return false
+}
+
+// ClearTransit removes every route, bus and rider and resets the counters
+func (w *World) ClearTransit() {
+	t := w.Transit
+	for len(t.routes) > 0 {
+		w.RemoveBusRoute(t.routes[0].ID)
+	}
+	for _, rider := range t.riders {
+		w.RemovePedestrian(rider.ID)
+	}
+	t.riders = nil
+	t.Generated, t.Boardings, t.Alightings, t.Completed, t.Suppressed = 0, 0, 0, 0, 0
+}
+
+// stepTransit moves riders along, runs the buses and sends new riders to
+// the stops
+func (w *World) stepTransit(dt float64) {
+	t := w.Transit
+	w.stepRiders()
+	for _, r := range t.routes {
+		w.stepRoute(r, dt)
+	}
+	if t.RidesPerResident > 0 && len(t.routes) > 0 {
+		w.spawnRiders(dt)
+	}
+}
+
+// stepRiders starts riders who reached their stop waiting and retires those
+// who reached their destination or whose stop is gone
+func (w *World) stepRiders() {
+	t := w.Transit
+	kept := t.riders[:0]
+	for _, r := range t.riders {
+		if r.State == RiderRiding {
+			kept = append(kept, r)
+			continue
+		}
+		p := w.Pedestrian(r.ID)
+		switch {
+		case p == nil:
+			continue // Removed from JavaScript
+		case r.State == RiderLeaving:
+			if p.State == PedestrianArrived {
+				w.RemovePedestrian(r.ID)
+				t.Completed++
+				continue
+			}
+		case w.busStop(r.From) == nil:
+			w.RemovePedestrian(r.ID) // Their stop was demolished
+			continue
+		case r.State == RiderWalking && p.State == PedestrianArrived:
+			r.State = RiderWaiting
+		}
+		kept = append(kept, r)
+	}
+	t.riders = kept
+}
+
+// stepRoute keeps a route's buses on the road and serves their stops
+func (w *World) stepRoute(r *BusRoute, dt float64) {
+	kept := r.buses[:0]
+	for i, bus := range r.buses {
+		car := w.Car(bus.CarID)
+		if car != nil && car.AI != nil && i < r.Buses {
+			kept = append(kept, bus)
+			continue
+		}
+		if car != nil && car.AI != nil {
+			w.RemoveCar(bus.CarID) // Beyond the route's bus count
+		}
+		w.dropRiders(bus) // Taken over from JavaScript, removed or retired
+	}
+	r.buses = kept
+
+	for len(r.buses) < r.Buses {
+		if !w.deployBus(r) {
+			break
+		}
+	}
+	for _, bus := range r.buses {
+		w.runBus(r, bus, dt)
+	}
+}
+
+// dropRiders forgets the riders aboard a bus that left the route
+func (w *World) dropRiders(bus *Bus) {
+	t := w.Transit
+	kept := t.riders[:0]
+	for _, r := range t.riders {
+		if r.State != RiderRiding || r.BusID != bus.CarID {
+			kept = append(kept, r)
+		}
+	}
+	t.riders = kept
+}
+
+// deployBus puts a new bus on a route at a stop spaced along the loop from
+// the buses already running, returning false if it cannot start
+func (w *World) deployBus(r *BusRoute) bool {
+	t := w.Transit
+	start := len(r.buses) * len(r.Stops) / max(r.Buses, 1)
+	from := w.stopNode(r.Stops[start])
+	if from == nil {
+		return false
+	}
+	for i := 1; i < len(r.Stops); i++ {
+		next := (start + i) % len(r.Stops)
+		to := w.stopNode(r.Stops[next])
+		if to == nil || to.ID == from.ID {
+			continue
+		}
+		if err := w.SpawnAICar(t.nextBusID, from.ID, to.ID, t.Vehicle); err != nil {
+			return false
+		}
+		r.buses = append(r.buses, &Bus{CarID: t.nextBusID, Stop: next})
+		t.nextBusID++
+		return true
+	}
+	return false
+}
+
+// runBus serves the stop a bus has pulled up at and sends it on when done
+func (w *World) runBus(r *BusRoute, bus *Bus, dt float64) {
+	car := w.Car(bus.CarID)
+	if bus.AtStop {
+		bus.Dwell -= dt
+		if bus.Dwell > 0 {
+			return
+		}
+		bus.AtStop = false
+		bus.Stop = (bus.Stop + 1) % len(r.Stops)
+		w.sendBus(r, bus)
+		return
+	}
+	if !car.AI.Arrived {
+		return
+	}
+
+	node := w.stopNode(r.Stops[bus.Stop])
+	switch {
+	case node == nil:
+		// Demolished stop: skip it
+		bus.Stop = (bus.Stop + 1) % len(r.Stops)
+		w.sendBus(r, bus)
+	case simHypot(car.State.X-node.X, car.State.Z-node.Z) > BUS_STOP_REACH:
+		w.sendBus(r, bus) // Cut short by a road edit, or the stop's node moved
+	default:
+		bus.AtStop = true
+		bus.Dwell = BUS_DWELL_TIME + BUS_BOARDING_TIME*float64(w.exchangeRiders(r, bus))
+	}
+}
+
+// sendBus routes a bus to its stop, moving on to the next one if the stop
+// cannot be reached
+func (w *World) sendBus(r *BusRoute, bus *Bus) {
+	node := w.stopNode(r.Stops[bus.Stop])
+	if node == nil || w.SetAIDestination(bus.CarID, node.ID) != nil {
+		bus.Stop = (bus.Stop + 1) % len(r.Stops)
+	}
+}
+
+// exchangeRiders lets the riders for a stop off a bus and the riders
+// waiting there on, returning how many got off or on
+func (w *World) exchangeRiders(r *BusRoute, bus *Bus) int {
+	t := w.Transit
+	stop := r.Stops[bus.Stop]
+	sx, sz := w.busStop(stop).Center()
+	moved := 0
+
+	aboard := bus.Riders[:0]
+	for _, id := range bus.Riders {
+		rider := t.rider(id)
+		if rider == nil {
+			continue
+		}
+		if rider.To != stop && w.busStop(rider.To) != nil {
+			aboard = append(aboard, id)
+			continue
+		}
+		w.walkRider(rider, sx, sz, rider.DestX, rider.DestZ)
+		rider.State, rider.BusID = RiderLeaving, 0
+		r.Alightings++
+		t.Alightings++
+		moved++
+	}
+	bus.Riders = aboard
+
+	for _, rider := range t.riders {
+		if len(bus.Riders) >= t.Capacity {
+			break
+		}
+		if rider.State != RiderWaiting || rider.Route != r.ID || rider.From != stop {
+			continue
+		}
+		w.RemovePedestrian(rider.ID)
+		rider.State, rider.BusID = RiderRiding, bus.CarID
+		bus.Riders = append(bus.Riders, rider.ID)
+		r.Boardings++
+		t.Boardings++
+		moved++
+	}
+	return moved
+}
+
+// walkRider puts a rider on foot, along the sidewalks or straight there
+// when the roads do not connect
+func (w *World) walkRider(r *BusRider, fromX, fromZ, toX, toZ float64) {
+	if w.SpawnPedestrian(r.ID, fromX, fromZ, toX, toZ, 0, false) != nil {
+		_ = w.SpawnPedestrian(r.ID, fromX, fromZ, toX, toZ, 0, true) // Direct walks cannot fail
+	}
+}
+
+// spawnRiders sends the riders due this step from the homes around each
+// stop; rides follow the daily trip profile
+func (w *World) spawnRiders(dt float64) {
+	t := w.Transit
+	intensity := 0.0
+	for _, v := range tripIntensity(w.Clock.HourOfDay()) {
+		intensity += v
+	}
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	homes := func(p *Placement) int { return w.Population.HomeResidents(p.ID) }
+
+	for _, r := range t.routes {
+		if len(r.pending) != len(r.Stops) {
+			r.pending = make([]float64, len(r.Stops))
+		}
+		for i, stop := range r.Stops {
+			p := w.busStop(stop)
+			if p == nil {
+				continue
+			}
+			residents := 0
+			for _, q := range w.nearStop(p) {
+				residents += homes(q)
+			}
+			r.pending[i] += float64(residents) * t.RidesPerResident * intensity * hours
+			for r.pending[i] >= 1 {
+				r.pending[i]--
+				w.spawnRider(r, i)
+			}
+		}
+	}
+}
+
+// nearStop returns the placements within BUS_STOP_CATCHMENT of a stop
+func (w *World) nearStop(p *Placement) []*Placement {
+	f := p.Footprint
+	return w.Occupancy.Query(BoundingBox{
+		MinX: f.MinX - BUS_STOP_CATCHMENT,
+		MinY: f.MinY - BUS_STOP_CATCHMENT,
+		MaxX: f.MaxX + BUS_STOP_CATCHMENT,
+		MaxY: f.MaxY + BUS_STOP_CATCHMENT,
+	})
+}
+
+// pickNear chooses one of the placements at random in proportion to weight,
+// or nil when every weight is zero
+func (w *World) pickNear(placements []*Placement, weight func(*Placement) int) *Placement {
+	total := 0
+	for _, p := range placements {
+		total += max(weight(p), 0)
+	}
+	if total == 0 {
+		return nil
+	}
+	n := w.Transit.rng.Intn(total)
+	for _, p := range placements {
+		n -= max(weight(p), 0)
+		if n < 0 {
+			return p
+		}
+	}
+	return nil
+}
+
+// spawnRider sends a resident from a home near stop i of a route to the
+// stop, bound for another stop chosen by the jobs around it
+func (w *World) spawnRider(r *BusRoute, i int) {
+	t := w.Transit
+	from := r.Stops[i]
+	if t.Waiting(r.ID, from) >= MAX_WAITING_RIDERS {
+		t.Suppressed++
+		return
+	}
+	stop := w.busStop(from)
+	home := w.pickNear(w.nearStop(stop), func(p *Placement) int { return w.Population.HomeResidents(p.ID) })
+	if home == nil {
+		return
+	}
+
+	// Every other stop is a possible destination, busier the more jobs it serves
+	jobs := func(p *Placement) int { return w.capacity(p).Jobs }
+	var destinations []*Placement
+	weights := make(map[int]int)
+	for j, to := range r.Stops {
+		p := w.busStop(to)
+		if j == i || to == from || p == nil {
+			continue
+		}
+		weight := 1
+		for _, q := range w.nearStop(p) {
+			weight += jobs(q)
+		}
+		if weights[p.ID] == 0 {
+			destinations = append(destinations, p)
+		}
+		weights[p.ID] += weight
+	}
+	dest := w.pickNear(destinations, func(p *Placement) int { return weights[p.ID] })
+	if dest == nil {
+		return
+	}
+	destX, destZ := dest.Center()
+	if work := w.pickNear(w.nearStop(dest), jobs); work != nil {
+		destX, destZ = work.Center()
+	}
+
+	rider := &BusRider{ID: t.nextRiderID, Route: r.ID, From: from, To: dest.ID, DestX: destX, DestZ: destZ}
+	hx, hz := home.Center()
+	sx, sz := stop.Center()
+	w.walkRider(rider, hx, hz, sx, sz)
+	t.nextRiderID++
+	t.Generated++
+	t.riders = append(t.riders, rider)
+}
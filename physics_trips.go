@@ -0,0 +1,238 @@
+package main
+
+// ============================================================================
+// Traffic Demand (trips generated from land use)
+// ============================================================================
+
+// Residents make a fixed number of car trips per game day, spread over the
+// day by purpose on the world clock (physics_clock.go): commutes home->work in
+// the morning, shopping trips while stores are open and trips home in the
+// evening. Each trip picks its endpoints weighted
+// by residents (homes) or jobs (workplaces, markets), starts at the road node
+// nearest each end, and is driven by an AI car that is removed on arrival.
+
+const (
+	TRIPS_PER_RESIDENT = 2.0     // Car trips each resident makes per simulated day
+	MAX_TRIP_CARS      = 24      // Generated cars on the road at once
+	TRIP_CAR_ID_BASE   = 1 << 20 // Car IDs from here up belong to generated trips
+	TRIP_SEED          = 1       // Seed for endpoint selection (trips repeat run to run)
+)
+
+// TripPurpose is why a trip is made: commutes go home -> workplace, shopping
+// trips home -> market, and trips home start at a workplace or market
+type TripPurpose uint8
+
+const (
+	TripCommute TripPurpose = iota
+	TripShopping
+	TripHome
+	tripPurposeCount
+)
+
+// tripPurposeNames are the JavaScript-facing names, indexed by TripPurpose
+var tripPurposeNames = [tripPurposeCount]string{"commute", "shopping", "home"}
+
+// String returns the JavaScript-facing purpose name
+func (p TripPurpose) String() string {
+	if p >= tripPurposeCount {
+		return tripPurposeNames[TripCommute]
+	}
+	return tripPurposeNames[p]
+}
+
+// tripWindow weights trip purposes over part of the day (hours 0-24)
+type tripWindow struct {
+	Start, End float64
+	Weights    [tripPurposeCount]float64
+}
+
+// tripWindows is the daily trip profile; hours outside every window see no trips
+var tripWindows = []tripWindow{
+	{Start: 5, End: 8.5, Weights: [tripPurposeCount]float64{TripCommute: 3}},
+	{Start: 8.5, End: 15.5, Weights: [tripPurposeCount]float64{TripShopping: 1, TripHome: 0.3}},
+	{Start: 15.5, End: 19, Weights: [tripPurposeCount]float64{TripShopping: 0.5, TripHome: 3}},
+	{Start: 19, End: 23, Weights: [tripPurposeCount]float64{TripHome: 0.5}},
+}
+
+// tripIntensity returns the purpose weights per hour at an hour of the day,
+// normalized so they integrate to 1 over the whole day
+func tripIntensity(t float64) [tripPurposeCount]float64 {
+	total := 0.0
+	for _, win := range tripWindows {
+		for _, weight := range win.Weights {
+			total += weight * (win.End - win.Start)
+		}
+	}
+
+	var weights [tripPurposeCount]float64
+	for _, win := range tripWindows {
+		if t >= win.Start && t < win.End {
+			for p, weight := range win.Weights {
+				weights[p] += weight / total
+			}
+		}
+	}
+	return weights
+}
+
+// Trip is one generated journey
+type Trip struct {
+	CarID       int
+	Purpose     TripPurpose
+	Origin      int // Placement IDs
+	Destination int
+}
+
+// TripDemand generates trips and tracks the cars driving them
+type TripDemand struct {
+	Enabled          bool
+	TripsPerResident float64
+	MaxCars          int
+	Vehicle          VehicleConfig
+
+	Generated  int // Trips spawned
+	Completed  int // Trips that reached their destination
+	Failed     int // Trips with no usable route
+	Suppressed int // Trips dropped while MaxCars were on the road
+
+	active  []*Trip // In spawn order
+	pending [tripPurposeCount]float64
+	nextID  int
+	rng     *SimRand
+}
+
+// NewTripDemand creates a disabled trip generator
+func NewTripDemand() *TripDemand {
+	return &TripDemand{
+		TripsPerResident: TRIPS_PER_RESIDENT,
+		MaxCars:          MAX_TRIP_CARS,
+		Vehicle:          DefaultVehicleConfig(),
+		nextID:           TRIP_CAR_ID_BASE,
+		rng:              newSimRand(TRIP_SEED),
+	}
+}
+
+// Active returns the trips being driven, in spawn order
+func (d *TripDemand) Active() []*Trip {
+	return d.active
+}
+
+// ClearTrips removes every generated car and resets the counters
+func (w *World) ClearTrips() {
+	d := w.Trips
+	for _, trip := range d.active {
+		w.RemoveCar(trip.CarID)
+	}
+	d.active = nil
+	d.pending = [tripPurposeCount]float64{}
+	d.Generated, d.Completed, d.Failed, d.Suppressed = 0, 0, 0, 0
+}
+
+// stepTrips retires finished trips and spawns the ones due this step
+func (w *World) stepTrips(dt float64) {
+	d := w.Trips
+	kept := d.active[:0]
+	for _, trip := range d.active {
+		car := w.Car(trip.CarID)
+		switch {
+		case car == nil || car.AI == nil:
+			// Removed or taken over from JavaScript
+		case car.AI.Arrived:
+			w.RemoveCar(trip.CarID)
+			w.parkArrival(trip.Destination, -1)
+			d.Completed++
+		default:
+			kept = append(kept, trip)
+		}
+	}
+	d.active = kept
+
+	if !d.Enabled || w.Roads.NodeCount() == 0 {
+		return
+	}
+
+	// Shopping trips only happen while stores are open
+	daily := float64(w.Population.Residents) * d.TripsPerResident
+	intensity := tripIntensity(w.Clock.HourOfDay())
+	if !w.Clock.StoresOpen() {
+		intensity[TripShopping] = 0
+	}
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	for p := range d.pending {
+		d.pending[p] += daily * intensity[p] * hours
+		for d.pending[p] >= 1 {
+			d.pending[p]--
+			if len(d.active) >= d.MaxCars {
+				d.Suppressed++
+				continue
+			}
+			w.spawnTrip(TripPurpose(p))
+		}
+	}
+}
+
+// spawnTrip picks endpoints for a trip and starts its car
+func (w *World) spawnTrip(purpose TripPurpose) {
+	d := w.Trips
+	homes := func(p *Placement) int { return w.Population.HomeResidents(p.ID) }
+	jobs := func(p *Placement) int { return w.capacity(p).Jobs }
+	markets := func(p *Placement) int {
+		if w.placementUse(p) != "market" {
+			return 0
+		}
+		return w.capacity(p).Jobs
+	}
+
+	var origin, destination *Placement
+	switch purpose {
+	case TripCommute:
+		origin, destination = w.pickPlacement(d.rng, homes), w.pickPlacement(d.rng, jobs)
+	case TripShopping:
+		origin, destination = w.pickPlacement(d.rng, homes), w.pickPlacement(d.rng, markets)
+	default:
+		origin, destination = w.pickPlacement(d.rng, jobs), w.pickPlacement(d.rng, homes)
+	}
+	if origin == nil || destination == nil {
+		d.Failed++
+		return
+	}
+
+	from := w.Roads.NearestNode(origin.Center())
+	to := w.Roads.NearestNode(destination.Center())
+	if from == nil || to == nil || from.ID == to.ID {
+		d.Failed++
+		return
+	}
+
+	id := d.nextID
+	if err := w.SpawnAICar(id, from.ID, to.ID, d.Vehicle); err != nil {
+		d.Failed++
+		return
+	}
+	d.nextID++
+	d.Generated++
+	d.active = append(d.active, &Trip{CarID: id, Purpose: purpose, Origin: origin.ID, Destination: destination.ID})
+}
+
+// pickPlacement chooses a placement at random from rng in proportion to
+// weight, or nil when every weight is zero
+func (w *World) pickPlacement(rng *SimRand, weight func(*Placement) int) *Placement {
+	ids := w.Occupancy.IDs()
+	total := 0
+	for _, id := range ids {
+		total += max(weight(w.Occupancy.Placement(id)), 0)
+	}
+	if total == 0 {
+		return nil
+	}
+
+	r := rng.Intn(total)
+	for _, id := range ids {
+		p := w.Occupancy.Placement(id)
+		r -= max(weight(p), 0)
+		if r < 0 {
+			return p
+		}
+	}
+	return nil
+}
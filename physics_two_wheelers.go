@@ -0,0 +1,155 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Two-Wheelers (bicycles, motorcycles and bike lanes)
+// ============================================================================
+
+// Bicycles and motorcycles are vehicles with TwoWheeler set: they steer and
+// slide like cars (the bicycle model already is one), on narrow colliders
+// and their own acceleration and grip (the "bicycle" and "motorcycle"
+// presets). A two-wheeler leans into turns, by the angle that balances the
+// turn's lateral acceleration against GRAVITY (never past the tires' grip or
+// MAX_LEAN_ANGLE), reported as Lean for rendering and added to the body roll
+// in the transform views. Lean costs grip: leaned over it accelerates and
+// brakes with only the cos(lean) share of its tires left. Road segments may
+// have a bike lane BIKE_LANE_WIDTH wide on the right of their traffic lanes
+// (the sidewalk moves out beside it); AI two-wheelers ride it where there is
+// one, out of the way of the cars in the lane beside them.
+
+const (
+	BIKE_LANE_WIDTH = 0.8 // units, beside the rightmost lane; cars in it do not see riders here as leaders
+	MAX_LEAN_ANGLE  = 0.8 // rad (~45°), the furthest a two-wheeler leans
+	LEAN_RESPONSE   = 8.0 // 1/s, how quickly the lean follows the turn
+)
+
+// BicycleConfig returns tuning for a light, slow bicycle; RPM is the
+// rider's pedalling cadence
+func BicycleConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                90.0,
+		Acceleration:        6.0,
+		MaxSpeed:            6.0,
+		ReverseAcceleration: 1.5,
+		ReverseMaxSpeed:     1.0,
+		Friction:            0.99,
+		LateralGrip:         30.0,
+		HandbrakeGrip:       0.5,
+		BrakePower:          20.0,
+		TurnRate:            2.0,
+		MinTurnRadius:       2.0,
+		MaxTurnRadius:       8.0,
+		Wheelbase:           1.0,
+		TrackWidth:          0.1,
+		BodyRadius:          0.4,
+		TwoWheeler:          true,
+		SuspensionFrequency: 2.0,
+		WeightTransfer:      0.05,
+		Gearbox: GearboxConfig{
+			GearRatios:   []float64{2.0, 1.6, 1.3, 1.0},
+			IdleRPM:      1.0,
+			RedlineRPM:   110.0,
+			ShiftUpRPM:   90.0,
+			ShiftDownRPM: 55.0,
+		},
+	}
+}
+
+// MotorcycleConfig returns tuning for a light, quick motorcycle with strong
+// brakes and grip
+func MotorcycleConfig() VehicleConfig {
+	return VehicleConfig{
+		Mass:                250.0,
+		Acceleration:        28.0,
+		MaxSpeed:            20.0,
+		ReverseAcceleration: 3.0,
+		ReverseMaxSpeed:     1.5,
+		Friction:            0.985,
+		LateralGrip:         70.0,
+		HandbrakeGrip:       0.3,
+		BrakePower:          40.0,
+		TurnRate:            2.6,
+		MinTurnRadius:       2.5,
+		MaxTurnRadius:       14.0,
+		Wheelbase:           1.4,
+		TrackWidth:          0.1,
+		BodyRadius:          0.5,
+		TwoWheeler:          true,
+		SuspensionFrequency: 1.8,
+		WeightTransfer:      0.06,
+		Gearbox: GearboxConfig{
+			GearRatios:   []float64{2.8, 2.0, 1.6, 1.3, 1.1, 0.95},
+			IdleRPM:      1200.0,
+			RedlineRPM:   11000.0,
+			ShiftUpRPM:   9500.0,
+			ShiftDownRPM: 4000.0,
+		},
+	}
+}
+
+// leanAdjust shares a two-wheeler's grip between cornering and the drive
+// and brakes
+func (car *CarState) leanAdjust(cfg *VehicleConfig) {
+	if !cfg.TwoWheeler {
+		return
+	}
+	share := math.Cos(car.Lean)
+	cfg.Acceleration *= share
+	cfg.ReverseAcceleration *= share
+	cfg.BrakePower *= share
+}
+
+// updateLean moves a two-wheeler's lean towards the angle that balances its
+// turn at yawRate rad/s over one substep; cfg is the tuning the substep
+// was driven with
+func (car *CarState) updateLean(cfg *VehicleConfig, yawRate, dt float64) {
+	if !cfg.TwoWheeler {
+		car.Lean = 0
+		return
+	}
+	forward := car.VelocityX*math.Sin(car.RotationY) + car.VelocityZ*math.Cos(car.RotationY)
+	lateral := clamp(forward*yawRate, -cfg.LateralGrip, cfg.LateralGrip) // Towards the left
+	target := clamp(-math.Atan(lateral/GRAVITY), -MAX_LEAN_ANGLE, MAX_LEAN_ANGLE)
+	car.Lean += (target - car.Lean) * (1 - simExp(-LEAN_RESPONSE*dt))
+}
+
+// SetBikeLane adds or removes a segment's bike lane, returning false if it
+// does not exist
+func (g *RoadGraph) SetBikeLane(id int, on bool) bool {
+	e, exists := g.edges[id]
+	if !exists {
+		return false
+	}
+	e.BikeLane = on
+	return true
+}
+
+// curbOffset returns the distance from a segment's centerline to its right
+// curb, past the traffic lanes and any bike lane
+func (e *RoadEdge) curbOffset() float64 {
+	offset := float64(e.Lanes) * LANE_WIDTH
+	if e.BikeLane {
+		offset += BIKE_LANE_WIDTH
+	}
+	return offset
+}
+
+// rideSegment returns the line a vehicle drives along an edge, as
+// laneSegment does: the bike lane for two-wheelers where there is one,
+// otherwise the (possibly fractional) lane
+func (w *World) rideSegment(e *RoadEdge, lane float64, twoWheeler bool) (ax, az, dx, dz, length float64) {
+	if !twoWheeler || !e.BikeLane {
+		return w.laneSegment(e, lane)
+	}
+	from, to := w.Roads.Node(e.From), w.Roads.Node(e.To)
+	length = simHypot(to.X-from.X, to.Z-from.Z)
+	if length < 1e-9 {
+		return from.X, from.Z, 0, 1, 0
+	}
+	dx, dz = (to.X-from.X)/length, (to.Z-from.Z)/length
+
+	// Right of travel is (-dz, dx), the bike lane right of every traffic lane
+	offset := float64(e.Lanes)*LANE_WIDTH + BIKE_LANE_WIDTH/2
+	return from.X - dz*offset, from.Z + dx*offset, dx, dz, length
+}
@@ -0,0 +1,39 @@
+package main
+
+// ============================================================================
+// Real-World Units (meters per world unit)
+// ============================================================================
+
+// The simulation measures everything in world units. World.MetersPerUnit
+// says how big one is, and every car step converts its speed and odometer,
+// the distance it has covered, into km/h, mph, kilometers and miles on the
+// car state, so the HUD does not each time need to know the conversions.
+// The default puts the standard car's WHEELBASE at about 2.7 m. Changing
+// the scale changes only the readouts: the physics stays in world units.
+
+const (
+	METERS_PER_UNIT = 1.7 // Default world scale
+	METERS_PER_MILE = 1609.344
+	KMH_PER_MPS     = 3.6 // km/h per m/s
+)
+
+// SetMetersPerUnit sets the world scale, returning false unless it is a
+// positive finite number
+func (w *World) SetMetersPerUnit(meters float64) bool {
+	if !isFinite(meters) || meters <= 0 {
+		return false
+	}
+	w.MetersPerUnit = meters
+	return true
+}
+
+// updateReadouts converts the car's speed and odometer to real-world units
+func (car *CarState) updateReadouts(metersPerUnit float64) {
+	metersPerUnit = positiveOr(metersPerUnit, METERS_PER_UNIT)
+	mps := simHypot(car.VelocityX, car.VelocityZ) * metersPerUnit
+	meters := car.Odometer * metersPerUnit
+	car.SpeedKmh = mps * KMH_PER_MPS
+	car.SpeedMph = mps * KMH_PER_MPS * 1000 / METERS_PER_MILE
+	car.OdometerKm = meters / 1000
+	car.OdometerMiles = meters / METERS_PER_MILE
+}
@@ -0,0 +1,270 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Utility Networks (power and water along the roads)
+// ============================================================================
+
+// Power plants and water towers produce a supply of their utility, measured
+// in the residents and jobs it can serve. Lines and pipes follow the roads:
+// road tiles within ROAD_ACCESS_DISTANCE of each other form a network, and a
+// building joins every network passing alongside it, joining them too. Each
+// network's supply is shared out in placement order, a building taking its
+// residents plus jobs (at least 1) of each utility it does not produce.
+// While utilities are enabled, a building short of either has no residents
+// or jobs (physics_population.go) and provides no service towards
+// livability (physics_livability.go). They start disabled so towns built
+// before utilities keep working; the connection status is kept up to date
+// either way for overlays.
+
+const (
+	POWER_PLANT_SUPPLY = 200.0 // Residents and jobs one power plant serves
+	WATER_TOWER_SUPPLY = 150.0 // Residents and jobs one water tower serves
+)
+
+// UtilityKind identifies a utility
+type UtilityKind uint8
+
+const (
+	UtilityPower UtilityKind = iota
+	UtilityWater
+	utilityKindCount
+)
+
+// utilityKindNames are the JavaScript-facing names, indexed by UtilityKind
+var utilityKindNames = [utilityKindCount]string{"power", "water"}
+
+// String returns the JavaScript-facing utility name
+func (k UtilityKind) String() string {
+	if k >= utilityKindCount {
+		return utilityKindNames[UtilityPower]
+	}
+	return utilityKindNames[k]
+}
+
+// UtilitySupply is what a producer serves of each utility
+type UtilitySupply [utilityKindCount]float64
+
+// DefaultUtilitySupply returns the producers by model use
+func DefaultUtilitySupply() map[string]UtilitySupply {
+	return map[string]UtilitySupply{
+		"power_plant": {UtilityPower: POWER_PLANT_SUPPLY},
+		"water_tower": {UtilityWater: WATER_TOWER_SUPPLY},
+	}
+}
+
+// UtilityStatus is one building's connection to the utilities
+type UtilityStatus struct {
+	Network   int                    // Lowest road tile ID of its network, -1 if off the roads
+	Connected [utilityKindCount]bool // Its network has a producer of the utility
+	Served    [utilityKindCount]bool // It got its share of the supply
+	Demand    float64                // Residents and jobs it needs served
+}
+
+// Functional reports whether the building has every utility
+func (s UtilityStatus) Functional() bool {
+	for _, served := range s.Served {
+		if !served {
+			return false
+		}
+	}
+	return true
+}
+
+// UtilityTotals sums one utility over the town
+type UtilityTotals struct {
+	Supply   float64
+	Demand   float64
+	Served   int // Buildings supplied
+	Unserved int // Buildings short of supply or off the network
+}
+
+// Utilities are the town's power and water networks
+type Utilities struct {
+	Enabled bool
+	Supply  map[string]UtilitySupply // Model use -> what it produces
+
+	status   map[int]UtilityStatus // Building placement ID -> connection
+	totals   [utilityKindCount]UtilityTotals
+	revision int // Occupancy revision the status was built at, -1 if stale
+}
+
+// NewUtilities creates disabled networks with the default producers
+func NewUtilities() *Utilities {
+	return &Utilities{Supply: DefaultUtilitySupply(), revision: -1}
+}
+
+// invalidate makes the next query rebuild the networks
+func (u *Utilities) invalidate() {
+	u.revision = -1
+}
+
+// utilityPool is one network's supply and what is left of it
+type utilityPool struct {
+	supply, left UtilitySupply
+}
+
+// refreshUtilities rebuilds the networks and shares out their supply when
+// anything placed has changed
+func (w *World) refreshUtilities() {
+	u := w.Utilities
+	if rev := w.Occupancy.Revision(); u.revision == rev && u.status != nil {
+		return
+	}
+	u.revision = w.Occupancy.Revision()
+	u.status = make(map[int]UtilityStatus)
+	u.totals = [utilityKindCount]UtilityTotals{}
+
+	// Networks by union-find over road tile IDs, rooted at the lowest ID
+	parent := make(map[int]int)
+	var find func(int) int
+	find = func(id int) int {
+		if parent[id] != id {
+			parent[id] = find(parent[id])
+		}
+		return parent[id]
+	}
+	union := func(a, b int) {
+		ra, rb := find(a), find(b)
+		if ra > rb {
+			ra, rb = rb, ra
+		}
+		parent[rb] = ra
+	}
+	alongside := func(f BoundingBox) []*Placement {
+		return w.Occupancy.Query(BoundingBox{
+			MinX: f.MinX - ROAD_ACCESS_DISTANCE,
+			MinY: f.MinY - ROAD_ACCESS_DISTANCE,
+			MaxX: f.MaxX + ROAD_ACCESS_DISTANCE,
+			MaxY: f.MaxY + ROAD_ACCESS_DISTANCE,
+		})
+	}
+
+	ids := w.Occupancy.IDs()
+	var roads, buildings []*Placement
+	for _, id := range ids {
+		switch p := w.Occupancy.Placement(id); {
+		case p.isRoad():
+			parent[id] = id
+			roads = append(roads, p)
+		case flammable(p):
+			buildings = append(buildings, p)
+		}
+	}
+	for _, r := range roads {
+		for _, q := range alongside(r.Footprint) {
+			if q.isRoad() && q.ID != r.ID {
+				union(r.ID, q.ID)
+			}
+		}
+	}
+	joined := make(map[int]int, len(buildings)) // Building ID -> a road tile alongside it
+	for _, b := range buildings {
+		for _, q := range alongside(b.Footprint) {
+			if !q.isRoad() {
+				continue
+			}
+			if first, ok := joined[b.ID]; ok {
+				union(first, q.ID)
+			} else {
+				joined[b.ID] = q.ID
+			}
+		}
+	}
+
+	pools := make(map[int]*utilityPool)
+	for _, b := range buildings {
+		road, ok := joined[b.ID]
+		if !ok {
+			continue
+		}
+		root := find(road)
+		pool := pools[root]
+		if pool == nil {
+			pool = &utilityPool{}
+			pools[root] = pool
+		}
+		if w.underConstruction(b) {
+			continue // Producers supply nothing until built (physics_construction.go)
+		}
+		for k, s := range u.Supply[w.placementUse(b)] {
+			pool.supply[k] += s
+			pool.left[k] += s
+		}
+	}
+
+	for _, b := range buildings {
+		use := w.placementUse(b)
+		produced := u.Supply[use]
+		if w.underConstruction(b) {
+			produced = UtilitySupply{}
+		}
+		c := w.Capacities[use]
+		st := UtilityStatus{Network: -1, Demand: math.Max(float64(c.Residents+c.Jobs), 1)}
+		var pool *utilityPool
+		if road, ok := joined[b.ID]; ok {
+			st.Network = find(road)
+			pool = pools[st.Network]
+		}
+		for k := UtilityKind(0); k < utilityKindCount; k++ {
+			t := &u.totals[k]
+			t.Supply += produced[k]
+			switch {
+			case produced[k] > 0:
+				st.Connected[k], st.Served[k] = true, true
+			case pool != nil:
+				t.Demand += st.Demand
+				st.Connected[k] = pool.supply[k] > 0
+				if pool.left[k] >= st.Demand {
+					pool.left[k] -= st.Demand
+					st.Served[k] = true
+				}
+			default:
+				t.Demand += st.Demand
+			}
+			if st.Served[k] {
+				t.Served++
+			} else {
+				t.Unserved++
+			}
+		}
+		u.status[b.ID] = st
+	}
+}
+
+// UtilityStatus returns a building's connection, false if the placement is
+// not a building
+func (w *World) UtilityStatus(id int) (UtilityStatus, bool) {
+	w.refreshUtilities()
+	st, ok := w.Utilities.status[id]
+	return st, ok
+}
+
+// UtilityBuildings returns the IDs of the buildings on or off the networks, in placement order
+func (w *World) UtilityBuildings() []int {
+	w.refreshUtilities()
+	var ids []int
+	for _, id := range w.Occupancy.IDs() {
+		if _, ok := w.Utilities.status[id]; ok {
+			ids = append(ids, id)
+		}
+	}
+	return ids
+}
+
+// UtilityTotals returns the town-wide supply and demand of each utility
+func (w *World) UtilityTotals() [utilityKindCount]UtilityTotals {
+	w.refreshUtilities()
+	return w.Utilities.totals
+}
+
+// utilitiesServed reports whether a placement works as far as utilities
+// go: always while they are disabled and for anything but buildings
+func (w *World) utilitiesServed(p *Placement) bool {
+	if !w.Utilities.Enabled || !flammable(p) {
+		return true
+	}
+	st, ok := w.UtilityStatus(p.ID)
+	return !ok || st.Functional()
+}
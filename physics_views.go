@@ -0,0 +1,125 @@
+package main
+
+import "unsafe"
+
+// ============================================================================
+// Transform Views (struct-of-arrays buffers shared with JS)
+// ============================================================================
+
+// Every step writes the render transform of each car, pedestrian, trailer
+// (under its car's ID), tram (physics_trams.go) and train wagon
+// (physics_trains.go) into a TransformStore: one contiguous
+// float64 allocation holding one plane per field (all ids, then all x, then
+// all y, ...); a car's y, pitch and roll include its body's motion on the
+// suspension and a two-wheeler's roll its lean. JS wraps the planes in
+// Float64Array views over the WASM memory (getTransformViews) and copies
+// them straight into instanced meshes, so nothing is marshalled per entity.
+// Go's collector never moves the allocation; it is only replaced when the
+// entity count outgrows it, and the WASM memory itself may grow, so JS must
+// rebuild its views when the pointer, capacity or memory buffer changes.
+
+// Transform plane indices
+const (
+	transformID = iota
+	transformX
+	transformY
+	transformZ
+	transformHeading
+	transformPitch
+	transformRoll
+	transformState // Cars: SurfaceType under the car; pedestrians: PedestrianState; trailers: 1 if jackknifed; trams: 1 at a station; trains: 0 for the locomotive
+
+	TRANSFORM_FIELDS
+)
+
+// transformFieldNames are the JavaScript-facing names, indexed by plane
+var transformFieldNames = [TRANSFORM_FIELDS]string{"id", "x", "y", "z", "heading", "pitch", "roll", "state"}
+
+const transformMinCapacity = 64
+
+// TransformStore holds entity transforms as TRANSFORM_FIELDS planes of
+// Capacity values each; the first Count values of every plane are in use
+type TransformStore struct {
+	data     []float64
+	Count    int
+	Capacity int
+}
+
+// reserve sets the count, reallocating (and moving the planes) only when it
+// exceeds the capacity
+func (s *TransformStore) reserve(count int) {
+	if count > s.Capacity || s.data == nil {
+		capacity := max(transformMinCapacity, s.Capacity)
+		for capacity < count {
+			capacity *= 2
+		}
+		s.data = make([]float64, capacity*TRANSFORM_FIELDS)
+		s.Capacity = capacity
+	}
+	s.Count = count
+}
+
+// set writes every field of entity i
+func (s *TransformStore) set(i, id int, x, y, z, heading, pitch, roll, state float64) {
+	for field, v := range [TRANSFORM_FIELDS]float64{float64(id), x, y, z, heading, pitch, roll, state} {
+		s.data[field*s.Capacity+i] = v
+	}
+}
+
+// pointer returns the byte address of the store in WASM memory, allocating
+// it if no step has yet
+func (s *TransformStore) pointer() uintptr {
+	s.reserve(s.Count)
+	return uintptr(unsafe.Pointer(&s.data[0]))
+}
+
+// storeCarTransform writes car i into the car transform store
+func (w *World) storeCarTransform(i int) {
+	car := &w.cars[i].State
+	w.carTransforms.set(i, car.ID, car.X, car.Y+car.Bounce, car.Z, car.RotationY, car.Pitch+car.BodyPitch, car.Roll+car.BodyRoll+car.Lean, float64(car.Surface))
+}
+
+// storeTransforms writes every car, pedestrian, trailer, tram and train
+// wagon into the transform stores
+func (w *World) storeTransforms() {
+	w.carTransforms.reserve(len(w.cars))
+	for i := range w.cars {
+		w.storeCarTransform(i)
+	}
+
+	w.pedestrianTransforms.reserve(len(w.pedestrians))
+	for i, p := range w.pedestrians {
+		y := 0.0
+		if w.Terrain != nil {
+			y = w.Terrain.GetHeight(p.X, p.Z)
+		}
+		w.pedestrianTransforms.set(i, p.ID, p.X, y, p.Z, p.Heading, 0, 0, float64(p.State))
+	}
+
+	w.storeTrailerTransforms()
+	w.storeTramTransforms()
+	w.storeTrainTransforms()
+}
+
+// storeTrailerTransforms writes every trailer, in car order, into the
+// trailer transform store
+func (w *World) storeTrailerTransforms() {
+	count := 0
+	for _, car := range w.cars {
+		if car.Trailer != nil {
+			count++
+		}
+	}
+	w.trailerTransforms.reserve(count)
+	i := 0
+	for _, car := range w.cars {
+		if t := car.Trailer; t != nil {
+			jackknifed := 0.0
+			if t.Jackknifed {
+				jackknifed = 1
+			}
+			w.trailerTransforms.set(i, car.State.ID, t.X, t.Y, t.Z, t.Heading, 0, 0, jackknifed)
+			i++
+		}
+	}
+}
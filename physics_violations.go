@@ -0,0 +1,169 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Traffic Violations (red lights, speeding and wrong-way driving)
+// ============================================================================
+
+// Cars driven from JavaScript are watched for three offences every step.
+// Running a red light is crossing a segment's stop line, SIGNAL_STOP_DISTANCE
+// before its signal, while the signal shows the segment red. Speeding is
+// driving more than VIOLATION_SPEED_MARGIN over the segment's speed limit
+// for VIOLATION_HOLD_SECONDS, and driving the wrong way is moving against a
+// one-way segment (one with no segment back alongside it) for as long. Each
+// offence queues a "violation" simulation event (physics_sim_events.go)
+// naming it, with a severity from 0 to 1 for fines: how fast the car ran the
+// light or drove against the traffic relative to the limit, or how far over
+// the limit it sped. Each spell of speeding or wrong-way driving is booked once.
+
+const (
+	VIOLATION_SPEED_MARGIN = 0.1 // Fraction over the limit that is tolerated
+	VIOLATION_SEVERE_OVER  = 0.5 // Fraction over the limit that is fully severe
+	VIOLATION_HOLD_SECONDS = 1.0 // s speeding or driving the wrong way before it counts
+	VIOLATION_AGAINST_COS  = 0.5 // Moving within ~60° of straight against a segment
+	VIOLATION_MIN_SPEED    = 1.0 // units/s, slower cars are not moving against the traffic
+)
+
+// ViolationKind identifies a traffic offence
+type ViolationKind uint8
+
+const (
+	ViolationRedLight ViolationKind = iota
+	ViolationSpeeding
+	ViolationWrongWay
+	violationKindCount
+)
+
+// violationKindNames are the JavaScript-facing names, indexed by ViolationKind
+var violationKindNames = [violationKindCount]string{"red_light", "speeding", "wrong_way"}
+
+// String returns the JavaScript-facing offence name
+func (k ViolationKind) String() string {
+	if k >= violationKindCount {
+		return violationKindNames[ViolationRedLight]
+	}
+	return violationKindNames[k]
+}
+
+// ViolationState is a car's offences being committed and its record
+type ViolationState struct {
+	Counts [violationKindCount]int
+
+	Speeding float64 // s over the limit so far
+	WrongWay float64 // s against a one-way segment so far
+	Booked   [violationKindCount]bool
+
+	PrevX, PrevZ float64 // Where the car was after the previous step
+	Placed       bool    // PrevX, PrevZ are set
+}
+
+// roadUnder returns the segment under a point that a car moving along
+// dx, dz is driving, and whether it is driving against it: a segment
+// running along the motion if any, else a one-way segment running against it
+func (w *World) roadUnder(x, z, dx, dz float64) (*RoadEdge, bool) {
+	var with, against *RoadEdge
+	bestWith, bestAgainst := math.Inf(1), math.Inf(1)
+	for _, id := range w.Roads.EdgeIDs() {
+		e := w.Roads.Edge(id)
+		a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+		px, pz := closestPointOnSegment(x, z, a.X, a.Z, b.X, b.Z)
+		d := simHypot(px-x, pz-z)
+		length := simHypot(b.X-a.X, b.Z-a.Z)
+		if d > e.curbOffset() || length == 0 {
+			continue
+		}
+		switch along := (dx*(b.X-a.X) + dz*(b.Z-a.Z)) / length; {
+		case along >= 0 && d < bestWith:
+			with, bestWith = e, d
+		case along < -VIOLATION_AGAINST_COS && d < bestAgainst:
+			against, bestAgainst = e, d
+		}
+	}
+	if with != nil {
+		return with, false
+	}
+	if against != nil && w.reverseEdge(against) == nil {
+		return against, true
+	}
+	return nil, false
+}
+
+// reverseEdge returns the segment running back alongside an edge, or nil
+// if the edge is one-way
+func (w *World) reverseEdge(e *RoadEdge) *RoadEdge {
+	for _, id := range w.Roads.EdgeIDs() {
+		if back := w.Roads.Edge(id); back.From == e.To && back.To == e.From {
+			return back
+		}
+	}
+	return nil
+}
+
+// stopLineRemaining returns how far a point is short of an edge's stop line
+func (w *World) stopLineRemaining(e *RoadEdge, x, z float64) float64 {
+	a, b := w.Roads.Node(e.From), w.Roads.Node(e.To)
+	length := simHypot(b.X-a.X, b.Z-a.Z)
+	along := ((x-a.X)*(b.X-a.X) + (z-a.Z)*(b.Z-a.Z)) / length
+	return length - SIGNAL_STOP_DISTANCE - along
+}
+
+// bookViolation records an offence and queues its event
+func (w *World) bookViolation(car *WorldCar, kind ViolationKind, e *RoadEdge, severity float64) {
+	car.Violations.Counts[kind]++
+	w.pushSimEvent(SimEvent{Kind: SimViolation, ID: car.State.ID, To: e.ID, Model: kind.String(), Value: clamp(severity, 0, 1), X: car.State.X, Z: car.State.Z})
+}
+
+// stepViolation checks one car's driving during a step of dt seconds
+func (w *World) stepViolation(car *WorldCar, dt float64) {
+	v := &car.Violations
+	s := &car.State
+	prevX, prevZ, placed := v.PrevX, v.PrevZ, v.Placed
+	v.PrevX, v.PrevZ, v.Placed = s.X, s.Z, true
+
+	speed := simHypot(s.VelocityX, s.VelocityZ)
+	var e *RoadEdge
+	against := false
+	if speed >= VIOLATION_MIN_SPEED {
+		e, against = w.roadUnder(s.X, s.Z, s.VelocityX/speed, s.VelocityZ/speed)
+	}
+	limit := 0.0
+	if e != nil {
+		limit = w.roadSpeedLimit(e, s.X, s.Z) // As the district sets it (physics_districts.go)
+	}
+	speeding := e != nil && !against && limit > 0 && speed > limit*(1+VIOLATION_SPEED_MARGIN)
+
+	if e != nil && !against && placed && w.SignalLightFor(e.ID) == LightRed &&
+		w.stopLineRemaining(e, prevX, prevZ) > 0 && w.stopLineRemaining(e, s.X, s.Z) <= 0 {
+		w.bookViolation(car, ViolationRedLight, e, speed/positiveOr(limit, MAX_SPEED))
+	}
+
+	if speeding {
+		v.Speeding += dt
+		if v.Speeding >= VIOLATION_HOLD_SECONDS && !v.Booked[ViolationSpeeding] {
+			v.Booked[ViolationSpeeding] = true
+			w.bookViolation(car, ViolationSpeeding, e, (speed/limit-1)/VIOLATION_SEVERE_OVER)
+		}
+	} else {
+		v.Speeding, v.Booked[ViolationSpeeding] = 0, false
+	}
+
+	if against {
+		v.WrongWay += dt
+		if v.WrongWay >= VIOLATION_HOLD_SECONDS && !v.Booked[ViolationWrongWay] {
+			v.Booked[ViolationWrongWay] = true
+			w.bookViolation(car, ViolationWrongWay, e, speed/positiveOr(limit, MAX_SPEED))
+		}
+	} else {
+		v.WrongWay, v.Booked[ViolationWrongWay] = 0, false
+	}
+}
+
+// stepViolations checks the driving of every car driven from JavaScript
+func (w *World) stepViolations(dt float64) {
+	for _, car := range w.cars {
+		if car.AI == nil {
+			w.stepViolation(car, dt)
+		}
+	}
+}
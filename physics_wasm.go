@@ -3,336 +3,18 @@
 package main
 
 import (
+	"encoding/binary"
 	"math"
+	"strconv"
 	"sync"
 	"syscall/js"
+	"time"
 )
 
 // ============================================================================
-// Data Structures
+// Legacy Collision State
 // ============================================================================
 
-// Vec2 represents a 2D vector with float64 precision
-type Vec2 struct {
-	X, Y float64
-}
-
-// BoundingBox represents an axis-aligned bounding box
-type BoundingBox struct {
-	MinX, MinY, MaxX, MaxY float64
-}
-
-// CategoryMask represents object categories as bit flags for fast filtering
-type CategoryMask uint32
-
-const (
-	CategoryUnknown   CategoryMask = 1 << iota // 0b00001
-	CategoryVehicle                             // 0b00010
-	CategoryBuilding                            // 0b00100
-	CategoryTerrain                             // 0b01000
-	CategoryProp                                // 0b10000
-	CategoryRoad                                // 0b100000
-	CategoryTree                                // 0b1000000
-	CategoryPark                                // 0b10000000
-)
-
-// categoryFromString converts string category to bitmask
-func categoryFromString(category string) CategoryMask {
-	switch category {
-	case "vehicles":
-		return CategoryVehicle
-	case "buildings":
-		return CategoryBuilding
-	case "terrain":
-		return CategoryTerrain
-	case "props":
-		return CategoryProp
-	case "roads":
-		return CategoryRoad
-	case "trees":
-		return CategoryTree
-	case "park":
-		return CategoryPark
-	default:
-		return CategoryUnknown
-	}
-}
-
-// GameObject represents a game object with position and bounding box
-type GameObject struct {
-	ID           int
-	X, Y         float64
-	BBox         BoundingBox
-	Category     string       // Original string category
-	CategoryMask CategoryMask // Bitmask for fast filtering
-}
-
-// GridKey represents a cell in the spatial grid
-type GridKey struct {
-	X, Y int
-}
-
-// ============================================================================
-// Bit Vector for Grid Occupancy
-// ============================================================================
-
-// BitVector efficiently tracks grid cell occupancy
-// Uses larger bit array to minimize hash collisions
-type BitVector struct {
-	bits             []uint64
-	maxSize          int // Maximum size limit for security
-	boundaryHitCount int // Track how many times safety check triggers (indicates hash issues)
-	mu               sync.Mutex
-}
-
-const (
-	bitVectorInitialSize = 8192  // 524288 bits initially (8x larger)
-	bitVectorMaxSize     = 65536 // 4MB max (security limit)
-)
-
-// NewBitVector creates a new bit vector
-func NewBitVector() *BitVector {
-	return &BitVector{
-		bits:    make([]uint64, bitVectorInitialSize),
-		maxSize: bitVectorMaxSize,
-	}
-}
-
-// gridKeyToIndex converts GridKey to a unique index
-// Uses improved spatial hash to minimize collisions
-func (bv *BitVector) gridKeyToIndex(key GridKey) uint {
-	// Improved spatial hash with better distribution
-	// Offset coordinates to handle negatives
-	const offset = 16384 // Larger offset for better range
-	x := uint(key.X + offset)
-	y := uint(key.Y + offset)
-
-	// Use prime numbers and bit rotation for better distribution
-	hash := x*73856093 ^ y*19349669 ^ (x<<13 | x>>19) ^ (y<<7 | y>>25)
-	return hash
-}
-
-// Set marks a grid cell as occupied
-func (bv *BitVector) Set(key GridKey) {
-	bv.mu.Lock()
-	defer bv.mu.Unlock()
-
-	hash := bv.gridKeyToIndex(key)
-	// Use current size for modulo to ensure consistent hashing
-	idx := hash % uint(len(bv.bits)*64)
-	wordIdx := idx / 64
-	bitIdx := idx % 64
-
-	// Due to modulo operation above, wordIdx should always be < len(bv.bits)
-	// This safety check should never trigger unless there's integer overflow or logic error
-	if int(wordIdx) >= len(bv.bits) {
-		// Track this anomaly - indicates potential hash distribution issue
-		bv.boundaryHitCount++
-		// Log first few occurrences for debugging
-		if bv.boundaryHitCount <= 10 {
-			println("WARNING: BitVector boundary hit detected!")
-			println("  wordIdx:", int(wordIdx), "len(bits):", len(bv.bits))
-			println("  GridKey:", key.X, key.Y, "hash:", hash, "idx:", idx)
-			println("  Total boundary hits:", bv.boundaryHitCount)
-		}
-		// Wrap to stay safe, but this indicates a problem that should be investigated
-		wordIdx = wordIdx % uint(len(bv.bits))
-	}
-
-	bv.bits[wordIdx] |= (1 << bitIdx)
-}
-
-// IsSet checks if a grid cell is occupied
-func (bv *BitVector) IsSet(key GridKey) bool {
-	bv.mu.Lock()
-	defer bv.mu.Unlock()
-
-	hash := bv.gridKeyToIndex(key)
-	// Use current size for modulo to ensure consistent hashing
-	idx := hash % uint(len(bv.bits)*64)
-	wordIdx := idx / 64
-	bitIdx := idx % 64
-
-	// Due to modulo operation above, wordIdx should always be < len(bv.bits)
-	// This safety check should never trigger unless there's integer overflow or logic error
-	if int(wordIdx) >= len(bv.bits) {
-		// Track this anomaly - indicates potential hash distribution issue
-		bv.boundaryHitCount++
-		// Log first few occurrences for debugging
-		if bv.boundaryHitCount <= 10 {
-			println("WARNING: BitVector boundary hit detected in IsSet!")
-			println("  wordIdx:", int(wordIdx), "len(bits):", len(bv.bits))
-			println("  GridKey:", key.X, key.Y, "hash:", hash, "idx:", idx)
-			println("  Total boundary hits:", bv.boundaryHitCount)
-		}
-		// Wrap to stay safe, but this indicates a problem that should be investigated
-		wordIdx = wordIdx % uint(len(bv.bits))
-	}
-
-	return (bv.bits[wordIdx] & (1 << bitIdx)) != 0
-}
-
-// Helper function for min
-func min(a, b int) int {
-	if a < b {
-		return a
-	}
-	return b
-}
-
-// Clear resets all bits
-func (bv *BitVector) Clear() {
-	bv.mu.Lock()
-	defer bv.mu.Unlock()
-
-	for i := range bv.bits {
-		bv.bits[i] = 0
-	}
-	// Reset boundary hit count on clear
-	bv.boundaryHitCount = 0
-}
-
-// GetBoundaryHitCount returns the number of times boundary checks triggered
-// Non-zero values indicate potential hash distribution issues
-func (bv *BitVector) GetBoundaryHitCount() int {
-	bv.mu.Lock()
-	defer bv.mu.Unlock()
-	return bv.boundaryHitCount
-}
-
-// ============================================================================
-// Spatial Grid (Leveraging Go 1.24 Swiss Tables)
-// ============================================================================
-
-// SpatialGrid implements spatial partitioning for efficient collision detection
-//
-// Go 1.24 Performance Optimizations (enabled by default):
-// - Swiss Tables: 30% faster map access, 35% faster assignment, 10-60% faster iteration
-// - Better stack allocation: Small slices allocated on stack vs heap
-// - Improved mutex performance: SpinbitMutex for faster RWMutex operations
-// - Enhanced small object allocation
-// - Bit vector for O(1) occupancy checks (saves memory and CPU)
-type SpatialGrid struct {
-	cellSize   float64
-	cells      map[GridKey][]int // Swiss Tables for object storage
-	occupancy  *BitVector        // Bit vector for fast occupancy checks
-	mu         sync.RWMutex      // SpinbitMutex optimization
-}
-
-// NewSpatialGrid creates a new spatial grid with the given cell size
-func NewSpatialGrid(cellSize float64) *SpatialGrid {
-	return &SpatialGrid{
-		cellSize:  cellSize,
-		cells:     make(map[GridKey][]int, 256), // Swiss Tables optimization
-		occupancy: NewBitVector(),               // Bit vector for fast occupancy checks
-	}
-}
-
-// getCellKey returns the grid cell key for a given position
-func (g *SpatialGrid) getCellKey(x, y float64) GridKey {
-	return GridKey{
-		X: int(math.Floor(x / g.cellSize)),
-		Y: int(math.Floor(y / g.cellSize)),
-	}
-}
-
-// getCellsForBBox returns all grid cells that intersect with a bounding box
-func (g *SpatialGrid) getCellsForBBox(bbox BoundingBox) []GridKey {
-	minKey := g.getCellKey(bbox.MinX, bbox.MinY)
-	maxKey := g.getCellKey(bbox.MaxX, bbox.MaxY)
-
-	// Go 1.25: This slice will likely be stack-allocated due to improved compiler
-	cells := make([]GridKey, 0, (maxKey.X-minKey.X+1)*(maxKey.Y-minKey.Y+1))
-
-	for x := minKey.X; x <= maxKey.X; x++ {
-		for y := minKey.Y; y <= maxKey.Y; y++ {
-			cells = append(cells, GridKey{X: x, Y: y})
-		}
-	}
-
-	return cells
-}
-
-// Insert adds an object to the spatial grid
-func (g *SpatialGrid) Insert(id int, bbox BoundingBox) {
-	g.mu.Lock()
-	defer g.mu.Unlock()
-
-	cells := g.getCellsForBBox(bbox)
-	for _, cell := range cells {
-		g.cells[cell] = append(g.cells[cell], id)
-		g.occupancy.Set(cell) // Mark cell as occupied in bit vector
-	}
-}
-
-// Remove removes an object from the spatial grid
-func (g *SpatialGrid) Remove(id int, bbox BoundingBox) {
-	g.mu.Lock()
-	defer g.mu.Unlock()
-
-	cells := g.getCellsForBBox(bbox)
-	for _, cell := range cells {
-		objects := g.cells[cell]
-		for i, objID := range objects {
-			if objID == id {
-				// Remove object from slice
-				g.cells[cell] = append(objects[:i], objects[i+1:]...)
-				break
-			}
-		}
-	}
-}
-
-// Query returns all object IDs in cells that intersect with the given bounding box
-// Uses bit vector for O(1) occupancy check before map lookup
-func (g *SpatialGrid) Query(bbox BoundingBox) []int {
-	g.mu.RLock()
-	defer g.mu.RUnlock()
-
-	cells := g.getCellsForBBox(bbox)
-	seen := make(map[int]bool, 16) // Track unique objects
-	results := make([]int, 0, 16)
-
-	// Go 1.24: Map iteration is 10-60% faster with Swiss Tables
-	for _, cell := range cells {
-		// Bit vector pre-check: O(1) operation to skip empty cells
-		if !g.occupancy.IsSet(cell) {
-			continue // Cell definitely empty, skip expensive map lookup
-		}
-
-		if objects, exists := g.cells[cell]; exists {
-			for _, id := range objects {
-				if !seen[id] {
-					seen[id] = true
-					results = append(results, id)
-				}
-			}
-		}
-	}
-
-	return results
-}
-
-// Clear removes all objects from the grid
-func (g *SpatialGrid) Clear() {
-	g.mu.Lock()
-	defer g.mu.Unlock()
-
-	// Go 1.24: Pre-sizing map for 35% faster subsequent assignments
-	g.cells = make(map[GridKey][]int, 256)
-	g.occupancy.Clear() // Clear bit vector
-}
-
-// ============================================================================
-// Collision Detection
-// ============================================================================
-
-// checkAABBCollision checks if two axis-aligned bounding boxes intersect
-func checkAABBCollision(a, b BoundingBox) bool {
-	return a.MinX <= b.MaxX && a.MaxX >= b.MinX &&
-		a.MinY <= b.MaxY && a.MaxY >= b.MinY
-}
-
 // Global spatial grid instance
 var spatialGrid = NewSpatialGrid(10.0) // 10 unit cells
 
@@ -659,121 +341,5602 @@ func getGridStats(this js.Value, args []js.Value) interface{} {
 // Car Physics
 // ============================================================================
 
-// CarState represents the state of a car for physics simulation
-type CarState struct {
-	X, Z         float64
-	RotationY    float64
-	VelocityX    float64
-	VelocityZ    float64
+// Car simulation lives in physics_car.go; these functions marshal to/from JS
+
+// optionalFloat reads a numeric property, returning fallback if it is missing
+func optionalFloat(obj js.Value, key string, fallback float64) float64 {
+	v := obj.Get(key)
+	if v.Type() != js.TypeNumber {
+		return fallback
+	}
+	return v.Float()
+}
+
+// parseInputState reads analog {throttle, brake, steering} input when present,
+// falling back to boolean {forward, backward, left, right} keyboard input;
+// either may add {cruise_speed, speed_limiter} (physics_cruise.go)
+func parseInputState(inputJS js.Value) InputState {
+	var input InputState
+	if !isKeyInput(inputJS) {
+		input = InputState{
+			Throttle:  optionalFloat(inputJS, "throttle", 0),
+			Brake:     optionalFloat(inputJS, "brake", 0),
+			Steering:  optionalFloat(inputJS, "steering", 0),
+			Handbrake: inputJS.Get("handbrake").Truthy(),
+		}
+	} else {
+		input = DigitalInput{
+			Forward:   inputJS.Get("forward").Truthy(),
+			Backward:  inputJS.Get("backward").Truthy(),
+			Left:      inputJS.Get("left").Truthy(),
+			Right:     inputJS.Get("right").Truthy(),
+			Handbrake: inputJS.Get("handbrake").Truthy(),
+		}.Analog()
+	}
+
+	input.CruiseSpeed = optionalFloat(inputJS, "cruise_speed", 0)
+	input.SpeedLimiter = inputJS.Get("speed_limiter").Truthy()
+	return input
+}
+
+// isKeyInput reports whether a JS input object holds keyboard input rather than analog axes
+func isKeyInput(inputJS js.Value) bool {
+	return inputJS.Get("throttle").Type() != js.TypeNumber &&
+		inputJS.Get("steering").Type() != js.TypeNumber &&
+		inputJS.Get("brake").Type() != js.TypeNumber
+}
+
+// optionalFloatSlice reads a numeric array property, returning fallback if it is missing
+func optionalFloatSlice(obj js.Value, key string, fallback []float64) []float64 {
+	v := obj.Get(key)
+	if v.Type() != js.TypeObject || v.Length() == 0 {
+		return fallback
+	}
+
+	values := make([]float64, v.Length())
+	for i := range values {
+		values[i] = v.Index(i).Float()
+	}
+	return values
+}
+
+// float64sFromJS copies a Float64Array (fast path) or plain numeric array into Go
+func float64sFromJS(v js.Value) []float64 {
+	if v.Type() != js.TypeObject {
+		return nil
+	}
+
+	// Typed arrays: copy the raw bytes in one call instead of per-element access
+	if v.InstanceOf(js.Global().Get("Float64Array")) {
+		byteView := js.Global().Get("Uint8Array").New(v.Get("buffer"), v.Get("byteOffset"), v.Get("byteLength"))
+		raw := make([]byte, byteView.Length())
+		js.CopyBytesToGo(raw, byteView)
+
+		values := make([]float64, len(raw)/8)
+		for i := range values {
+			values[i] = math.Float64frombits(binary.LittleEndian.Uint64(raw[i*8:]))
+		}
+		return values
+	}
+
+	values := make([]float64, v.Length())
+	for i := range values {
+		values[i] = v.Index(i).Float()
+	}
+	return values
+}
+
+// float64sToJS writes values into an existing Float64Array (fast path) or plain array
+func float64sToJS(dst js.Value, values []float64) {
+	if dst.InstanceOf(js.Global().Get("Float64Array")) {
+		raw := make([]byte, len(values)*8)
+		for i, v := range values {
+			binary.LittleEndian.PutUint64(raw[i*8:], math.Float64bits(v))
+		}
+		byteView := js.Global().Get("Uint8Array").New(dst.Get("buffer"), dst.Get("byteOffset"), len(raw))
+		js.CopyBytesToJS(byteView, raw)
+		return
+	}
+
+	for i, v := range values {
+		dst.SetIndex(i, v)
+	}
+}
+
+// bytesFromJS copies a Uint8Array (fast path) or plain numeric array into Go
+func bytesFromJS(v js.Value) []byte {
+	if v.Type() != js.TypeObject {
+		return nil
+	}
+
+	values := make([]byte, v.Length())
+	if v.InstanceOf(js.Global().Get("Uint8Array")) {
+		js.CopyBytesToGo(values, v)
+		return values
+	}
+
+	for i := range values {
+		values[i] = byte(v.Index(i).Int())
+	}
+	return values
+}
+
+// parseBoundingBox reads a {minX, minY, maxX, maxY} object (Y is world Z)
+func parseBoundingBox(bboxJS js.Value) BoundingBox {
+	return BoundingBox{
+		MinX: bboxJS.Get("minX").Float(),
+		MinY: bboxJS.Get("minY").Float(),
+		MaxX: bboxJS.Get("maxX").Float(),
+		MaxY: bboxJS.Get("maxY").Float(),
+	}
+}
+
+// floatsToJS converts a float slice to a JS-compatible array
+func floatsToJS(values []float64) []interface{} {
+	result := make([]interface{}, len(values))
+	for i, v := range values {
+		result[i] = v
+	}
+	return result
+}
+
+// parseVehicleConfig reads a preset name ("sports_car") or a config object
+// Objects may name a base preset via "preset" and override individual fields
+func parseVehicleConfig(v js.Value) VehicleConfig {
+	cfg := DefaultVehicleConfig()
+
+	switch v.Type() {
+	case js.TypeString:
+		if preset, ok := VehiclePreset(v.String()); ok {
+			cfg = preset
+		}
+	case js.TypeObject:
+		if name := v.Get("preset"); name.Type() == js.TypeString {
+			if preset, ok := VehiclePreset(name.String()); ok {
+				cfg = preset
+			}
+		}
+		cfg.Mass = optionalFloat(v, "mass", cfg.Mass)
+		cfg.Acceleration = optionalFloat(v, "acceleration", cfg.Acceleration)
+		cfg.MaxSpeed = optionalFloat(v, "max_speed", cfg.MaxSpeed)
+		cfg.ReverseAcceleration = optionalFloat(v, "reverse_acceleration", cfg.ReverseAcceleration)
+		cfg.ReverseMaxSpeed = optionalFloat(v, "reverse_max_speed", cfg.ReverseMaxSpeed)
+		cfg.Friction = optionalFloat(v, "friction", cfg.Friction)
+		cfg.LateralGrip = optionalFloat(v, "lateral_grip", cfg.LateralGrip)
+		cfg.HandbrakeGrip = optionalFloat(v, "handbrake_grip", cfg.HandbrakeGrip)
+		cfg.BrakePower = optionalFloat(v, "brake_power", cfg.BrakePower)
+		cfg.TurnRate = optionalFloat(v, "turn_rate", cfg.TurnRate)
+		cfg.MinTurnRadius = optionalFloat(v, "min_turn_radius", cfg.MinTurnRadius)
+		cfg.MaxTurnRadius = optionalFloat(v, "max_turn_radius", cfg.MaxTurnRadius)
+		cfg.Wheelbase = optionalFloat(v, "wheelbase", cfg.Wheelbase)
+		cfg.TrackWidth = optionalFloat(v, "track_width", cfg.TrackWidth)
+		cfg.BodyRadius = optionalFloat(v, "body_radius", cfg.BodyRadius)
+		cfg.FuelCapacity = optionalFloat(v, "fuel_capacity", cfg.FuelCapacity)
+		cfg.FuelUse = optionalFloat(v, "fuel_use", cfg.FuelUse)
+		if tw := v.Get("two_wheeler"); tw.Type() == js.TypeBoolean {
+			cfg.TwoWheeler = tw.Bool()
+		}
+		cfg.SuspensionFrequency = optionalFloat(v, "suspension_frequency", cfg.SuspensionFrequency)
+		cfg.WeightTransfer = optionalFloat(v, "weight_transfer", cfg.WeightTransfer)
+
+		gearbox := &cfg.Gearbox
+		gearbox.GearRatios = optionalFloatSlice(v, "gear_ratios", gearbox.GearRatios)
+		gearbox.IdleRPM = optionalFloat(v, "idle_rpm", gearbox.IdleRPM)
+		gearbox.RedlineRPM = optionalFloat(v, "redline_rpm", gearbox.RedlineRPM)
+		gearbox.ShiftUpRPM = optionalFloat(v, "shift_up_rpm", gearbox.ShiftUpRPM)
+		gearbox.ShiftDownRPM = optionalFloat(v, "shift_down_rpm", gearbox.ShiftDownRPM)
+	}
+
+	return cfg.sanitized()
+}
+
+// vehicleConfigToJS converts a vehicle config to a JS-friendly map
+func vehicleConfigToJS(cfg VehicleConfig) map[string]interface{} {
+	result := make(map[string]interface{})
+	result["mass"] = cfg.Mass
+	result["acceleration"] = cfg.Acceleration
+	result["max_speed"] = cfg.MaxSpeed
+	result["reverse_acceleration"] = cfg.ReverseAcceleration
+	result["reverse_max_speed"] = cfg.ReverseMaxSpeed
+	result["friction"] = cfg.Friction
+	result["lateral_grip"] = cfg.LateralGrip
+	result["handbrake_grip"] = cfg.HandbrakeGrip
+	result["brake_power"] = cfg.BrakePower
+	result["turn_rate"] = cfg.TurnRate
+	result["min_turn_radius"] = cfg.MinTurnRadius
+	result["max_turn_radius"] = cfg.MaxTurnRadius
+	result["wheelbase"] = cfg.Wheelbase
+	result["track_width"] = cfg.TrackWidth
+	result["body_radius"] = cfg.BodyRadius
+	result["fuel_capacity"] = cfg.FuelCapacity
+	result["fuel_use"] = cfg.FuelUse
+	result["two_wheeler"] = cfg.TwoWheeler
+	result["suspension_frequency"] = cfg.SuspensionFrequency
+	result["weight_transfer"] = cfg.WeightTransfer
+	result["gear_ratios"] = floatsToJS(cfg.Gearbox.GearRatios)
+	result["idle_rpm"] = cfg.Gearbox.IdleRPM
+	result["redline_rpm"] = cfg.Gearbox.RedlineRPM
+	result["shift_up_rpm"] = cfg.Gearbox.ShiftUpRPM
+	result["shift_down_rpm"] = cfg.Gearbox.ShiftDownRPM
+	return result
 }
 
-// InputState represents player input for car control
-type InputState struct {
-	Forward  bool
-	Backward bool
-	Left     bool
-	Right    bool
+// getVehiclePreset returns the tuning for a built-in vehicle preset
+// JavaScript signature: getVehiclePreset(name: "car"|"sports_car"|"delivery_truck"|"bus"|"bicycle"|"motorcycle") -> config | null
+func getVehiclePreset(this js.Value, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(nil)
+	}
+
+	cfg, ok := VehiclePreset(args[0].String())
+	if !ok {
+		return js.ValueOf(nil)
+	}
+
+	return js.ValueOf(vehicleConfigToJS(cfg))
 }
 
 // updateCarPhysics updates car physics based on input
-// JavaScript signature: updateCarPhysics(carState, inputState) -> carState
-func updateCarPhysics(this js.Value, args []js.Value) interface{} {
+// inputState may be analog {throttle, brake, steering, handbrake} or
+// boolean {forward, backward, left, right, handbrake}, plus optional
+// {cruise_speed, speed_limiter} for cruise control and the speed limiter
+// JavaScript signature: updateCarPhysics(carState, inputState, dt?, vehicle?) -> carState
+// dt is the elapsed time in seconds (defaults to 1/60 when omitted)
+// vehicle is a preset name or config object (see parseVehicleConfig)
+func updateCarPhysics(w *World, args []js.Value) interface{} {
 	if len(args) < 2 {
 		return js.ValueOf(nil)
 	}
 
-	// Physics constants
-	const (
-		ACCELERATION  = 0.005
-		MAX_SPEED     = 0.2
-		FRICTION      = 0.98
-		BRAKE_POWER   = 0.01
-		ROTATE_SPEED  = 0.04
-	)
-
 	// Parse car state
-	carJS := args[0]
-	car := CarState{
+	car := parseCarState(args[0])
+
+	// Parse input state
+	input := parseInputState(args[1])
+
+	// Parse elapsed time
+	dt := referenceFrameTime
+	if len(args) >= 3 && args[2].Type() == js.TypeNumber {
+		dt = args[2].Float()
+	}
+
+	// Parse vehicle tuning
+	cfg := DefaultVehicleConfig()
+	if len(args) >= 4 {
+		cfg = parseVehicleConfig(args[3])
+	}
+
+	errors := w.stepCar(&car, input, &cfg, dt)
+	return js.ValueOf(carStateToJS(&car, &cfg, errors))
+}
+
+// parseCarState reads a JS car state object
+func parseCarState(carJS js.Value) CarState {
+	return CarState{
+		ID:        int(optionalFloat(carJS, "id", -1)),
 		X:         carJS.Get("x").Float(),
 		Z:         carJS.Get("z").Float(),
 		RotationY: carJS.Get("rotation_y").Float(),
 		VelocityX: carJS.Get("velocity_x").Float(),
 		VelocityZ: carJS.Get("velocity_z").Float(),
+		Reversing: carJS.Get("reversing").Truthy(),
+
+		Gear:       int(optionalFloat(carJS, "gear", 1)),
+		RPM:        optionalFloat(carJS, "rpm", 0),
+		ShiftTimer: optionalFloat(carJS, "shift_timer", 0),
+		Damage:     optionalFloat(carJS, "damage", 0),
+		FuelUsed:   optionalFloat(carJS, "fuel_used", 0),
+		Lean:       optionalFloat(carJS, "lean", 0),
+
+		Bounce:         optionalFloat(carJS, "bounce", 0),
+		BounceVelocity: optionalFloat(carJS, "bounce_velocity", 0),
+		BodyPitch:      optionalFloat(carJS, "body_pitch", 0),
+		BodyPitchRate:  optionalFloat(carJS, "body_pitch_rate", 0),
+		BodyRoll:       optionalFloat(carJS, "body_roll", 0),
+		BodyRollRate:   optionalFloat(carJS, "body_roll_rate", 0),
+
+		Skidding: carJS.Get("skidding").Truthy(),
+		Odometer: optionalFloat(carJS, "odometer", 0),
 	}
+}
 
-	// Parse input state
-	inputJS := args[1]
-	input := InputState{
-		Forward:  inputJS.Get("forward").Bool(),
-		Backward: inputJS.Get("backward").Bool(),
-		Left:     inputJS.Get("left").Bool(),
-		Right:    inputJS.Get("right").Bool(),
+// carStateToJS converts a car state (and any sanitization errors) for JS;
+// cfg is the vehicle driving it, for the fuel gauge
+func carStateToJS(car *CarState, cfg *VehicleConfig, errors []string) map[string]interface{} {
+	result := make(map[string]interface{})
+	result["id"] = car.ID
+	result["x"] = car.X
+	result["y"] = car.Y
+	result["z"] = car.Z
+	result["pitch"] = car.Pitch
+	result["roll"] = car.Roll
+	result["rotation_y"] = car.RotationY
+	result["velocity_x"] = car.VelocityX
+	result["velocity_z"] = car.VelocityZ
+	result["reversing"] = car.Reversing
+	result["slip_angle"] = car.SlipAngle
+	result["surface"] = car.Surface.String()
+	result["collided"] = car.Collided
+	result["collided_with"] = car.CollidedWith
+	result["hit_boundary"] = car.HitBoundary
+	result["hit_water"] = car.HitWater
+	result["steer_angle"] = car.SteerAngle
+	result["wheel_angle_left"] = car.WheelAngleLeft
+	result["wheel_angle_right"] = car.WheelAngleRight
+	result["gear"] = car.Gear
+	result["rpm"] = car.RPM
+	result["throttle_load"] = car.ThrottleLoad
+	result["shift_timer"] = car.ShiftTimer
+	result["damage"] = car.Damage
+	result["fuel_used"] = car.FuelUsed
+	result["lean"] = car.Lean
+	result["bounce"] = car.Bounce
+	result["bounce_velocity"] = car.BounceVelocity
+	result["body_pitch"] = car.BodyPitch
+	result["body_pitch_rate"] = car.BodyPitchRate
+	result["body_roll"] = car.BodyRoll
+	result["body_roll_rate"] = car.BodyRollRate
+	result["skidding"] = car.Skidding
+	result["skid_intensity"] = car.SkidIntensity
+	result["odometer"] = car.Odometer
+	result["speed_kmh"] = car.SpeedKmh
+	result["speed_mph"] = car.SpeedMph
+	result["odometer_km"] = car.OdometerKm
+	result["odometer_miles"] = car.OdometerMiles
+	result["fuel"] = car.FuelLevel(cfg)
+	result["out_of_fuel"] = car.OutOfFuel(cfg)
+	if len(errors) > 0 {
+		messages := make([]interface{}, len(errors))
+		for i, message := range errors {
+			messages[i] = message
+		}
+		result["errors"] = messages // Only present when the input state was invalid
 	}
 
-	// Handle steering
-	if input.Left {
-		car.RotationY += ROTATE_SPEED
+	return result
+}
+
+// getCarBatchLayout describes the flat buffers used by updateCars
+// JavaScript signature: getCarBatchLayout() -> {state_stride, input_stride, state_fields: {name: offset}, input_fields: {name: offset}}
+func getCarBatchLayout(this js.Value, args []js.Value) interface{} {
+	stateFields := make(map[string]interface{}, CAR_STATE_STRIDE)
+	for i, name := range carStateFieldNames {
+		stateFields[name] = i
 	}
-	if input.Right {
-		car.RotationY -= ROTATE_SPEED
+	inputFields := make(map[string]interface{}, CAR_INPUT_STRIDE)
+	for i, name := range carInputFieldNames {
+		inputFields[name] = i
 	}
 
-	// Calculate forward vector based on rotation
-	forwardX := math.Sin(car.RotationY)
-	forwardZ := math.Cos(car.RotationY)
+	return js.ValueOf(map[string]interface{}{
+		"state_stride": CAR_STATE_STRIDE,
+		"input_stride": CAR_INPUT_STRIDE,
+		"state_fields": stateFields,
+		"input_fields": inputFields,
+	})
+}
 
-	// Handle acceleration
-	if input.Forward {
-		car.VelocityX += forwardX * ACCELERATION
-		car.VelocityZ += forwardZ * ACCELERATION
+// updateCars advances many cars in one call, updating the state buffer in place
+// JavaScript signature: updateCars(states: Float64Array, inputs: Float64Array, count: number, dt?, vehicles?) -> errors | null
+// See getCarBatchLayout for the buffer layout; vehicles is one preset/config for
+// every car, or an array with one per car
+func updateCars(w *World, args []js.Value) interface{} {
+	if len(args) < 3 {
+		return js.ValueOf(nil)
 	}
 
-	// Handle braking/reverse
-	if input.Backward {
-		// Calculate current speed and dot product
-		speed := math.Sqrt(car.VelocityX*car.VelocityX + car.VelocityZ*car.VelocityZ)
-		dot := car.VelocityX*forwardX + car.VelocityZ*forwardZ
+	states := float64sFromJS(args[0])
+	inputs := float64sFromJS(args[1])
+	count := args[2].Int()
+
+	dt := referenceFrameTime
+	if len(args) >= 4 && args[3].Type() == js.TypeNumber {
+		dt = args[3].Float()
+	}
 
-		if dot > 0.0 && speed > 0.0 {
-			// Brake when moving forward
-			car.VelocityX -= (car.VelocityX / speed) * BRAKE_POWER
-			car.VelocityZ -= (car.VelocityZ / speed) * BRAKE_POWER
+	cfgs := []VehicleConfig{DefaultVehicleConfig()}
+	if len(args) >= 5 {
+		vehicles := args[4]
+		if vehicles.InstanceOf(js.Global().Get("Array")) {
+			cfgs = make([]VehicleConfig, max(vehicles.Length(), 1))
+			cfgs[0] = DefaultVehicleConfig()
+			for i := 0; i < vehicles.Length(); i++ {
+				cfgs[i] = parseVehicleConfig(vehicles.Index(i))
+			}
 		} else {
-			// Accelerate backward
-			car.VelocityX -= forwardX * ACCELERATION
-			car.VelocityZ -= forwardZ * ACCELERATION
+			cfgs[0] = parseVehicleConfig(vehicles)
 		}
 	}
 
-	// Apply friction
-	car.VelocityX *= FRICTION
-	car.VelocityZ *= FRICTION
+	errors := w.stepCarBatch(states, inputs, count, cfgs, dt)
+	float64sToJS(args[0], states)
+
+	if len(errors) == 0 {
+		return js.ValueOf(nil)
+	}
+	messages := make([]interface{}, len(errors))
+	for i, message := range errors {
+		messages[i] = message
+	}
+	return js.ValueOf(messages)
+}
+
+// ============================================================================
+// Terrain Bindings
+// ============================================================================
+
+// setTerrain installs the heightmap used by the car physics
+// JavaScript signature: setTerrain({width, depth, cellSize, heights, originX?, originZ?}) -> boolean
+// heights is a Float64Array or number[] in row-major order (heights[z*width + x])
+func setTerrain(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	cfg := args[0]
+	terrain, err := NewTerrain(
+		int(optionalFloat(cfg, "width", 0)),
+		int(optionalFloat(cfg, "depth", 0)),
+		optionalFloat(cfg, "cellSize", 1.0),
+		float64sFromJS(cfg.Get("heights")),
+	)
+	if err != nil {
+		println("setTerrain:", err.Error())
+		return js.ValueOf(false)
+	}
+
+	terrain.OriginX = optionalFloat(cfg, "originX", terrain.OriginX)
+	terrain.OriginZ = optionalFloat(cfg, "originZ", terrain.OriginZ)
+	w.Terrain = terrain
+	return js.ValueOf(true)
+}
+
+// clearTerrain removes the heightmap (back to flat ground)
+// JavaScript signature: clearTerrain() -> void
+func clearTerrain(w *World, args []js.Value) interface{} {
+	w.Terrain = nil
+	return nil
+}
+
+// generateTerrain replaces the heightmap and surface map with a seeded landscape
+// JavaScript signature: generateTerrain({seed?, width?, depth?, cellSize?, octaves?,
+//   frequency?, persistence?, lacunarity?, amplitude?, flatRadius?}, out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, waterLevel, heights: Float64Array, surfaces: Float64Array} | null
+// heights is row-major by z (heights[z*width + x]); surfaces holds the
+// (width-1)*(depth-1) SurfaceType ids of the cells between samples;
+// waterLevel is the height below which cells are water
+func generateTerrain(w *World, args []js.Value) interface{} {
+	cfg := DefaultTerrainGenConfig()
+	if len(args) >= 1 && args[0].Type() == js.TypeObject {
+		o := args[0]
+		cfg.Seed = int64(optionalFloat(o, "seed", 0))
+		cfg.Width = int(optionalFloat(o, "width", float64(cfg.Width)))
+		cfg.Depth = int(optionalFloat(o, "depth", float64(cfg.Depth)))
+		cfg.CellSize = optionalFloat(o, "cellSize", cfg.CellSize)
+		cfg.Octaves = int(optionalFloat(o, "octaves", float64(cfg.Octaves)))
+		cfg.Frequency = optionalFloat(o, "frequency", cfg.Frequency)
+		cfg.Persistence = optionalFloat(o, "persistence", cfg.Persistence)
+		cfg.Lacunarity = optionalFloat(o, "lacunarity", cfg.Lacunarity)
+		cfg.Amplitude = optionalFloat(o, "amplitude", cfg.Amplitude)
+		cfg.FlatRadius = optionalFloat(o, "flatRadius", cfg.FlatRadius)
+	}
+	if err := w.GenerateTerrain(cfg); err != nil {
+		println("generateTerrain:", err.Error())
+		return js.Null()
+	}
 
-	// Clamp speed to max
-	speed := math.Sqrt(car.VelocityX*car.VelocityX + car.VelocityZ*car.VelocityZ)
-	if speed > MAX_SPEED {
-		car.VelocityX = (car.VelocityX / speed) * MAX_SPEED
-		car.VelocityZ = (car.VelocityZ / speed) * MAX_SPEED
+	t, s := w.Terrain, w.Surfaces
+	surfaces := make([]float64, len(s.Cells))
+	for i, c := range s.Cells {
+		surfaces[i] = float64(c)
+	}
+	var out []js.Value
+	if len(args) >= 2 {
+		out = args[1:]
 	}
+	return js.ValueOf(map[string]interface{}{
+		"width":      t.Width,
+		"depth":      t.Depth,
+		"cellSize":   t.CellSize,
+		"originX":    t.OriginX,
+		"originZ":    t.OriginZ,
+		"waterLevel": cfg.sanitized().Amplitude * WATER_LEVEL,
+		"heights":    reusableFloat64Array(out, t.Heights),
+		"surfaces":   reusableFloat64Array(nil, surfaces),
+	})
+}
 
-	// Stop tiny movements
-	if speed < 0.001 {
-		car.VelocityX = 0.0
-		car.VelocityZ = 0.0
+// getTerrainHeight samples the terrain height at a world position
+// JavaScript signature: getTerrainHeight(x: number, z: number) -> number
+func getTerrainHeight(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || w.Terrain == nil {
+		return js.ValueOf(0.0)
 	}
+	return js.ValueOf(w.Terrain.GetHeight(args[0].Float(), args[1].Float()))
+}
 
-	// Update position
-	car.X += car.VelocityX
-	car.Z += car.VelocityZ
+// getTerrainNormal samples the unit surface normal at a world position
+// JavaScript signature: getTerrainNormal(x: number, z: number) -> {x, y, z}
+func getTerrainNormal(w *World, args []js.Value) interface{} {
+	nx, ny, nz := 0.0, 1.0, 0.0
+	if len(args) >= 2 && w.Terrain != nil {
+		nx, ny, nz = w.Terrain.GetNormal(args[0].Float(), args[1].Float())
+	}
 
-	// Return updated state
 	result := make(map[string]interface{})
-	result["x"] = car.X
-	result["z"] = car.Z
-	result["rotation_y"] = car.RotationY
-	result["velocity_x"] = car.VelocityX
-	result["velocity_z"] = car.VelocityZ
+	result["x"] = nx
+	result["y"] = ny
+	result["z"] = nz
+	return js.ValueOf(result)
+}
 
+// ============================================================================
+// Surface Map Bindings
+// ============================================================================
+
+// parseSurface reads a surface given by name ("grass") or numeric SurfaceType
+func parseSurface(v js.Value) SurfaceType {
+	if v.Type() == js.TypeNumber {
+		return SurfaceType(v.Int())
+	}
+	if v.Type() == js.TypeString {
+		return surfaceFromString(v.String())
+	}
+	return SurfaceRoad
+}
+
+// setSurfaceMap installs the ground-type grid used by the car physics
+// JavaScript signature: setSurfaceMap({width, depth, cellSize, cells?, fill?, default?, originX?, originZ?}) -> boolean
+// cells is a Uint8Array or number[] of SurfaceType ids (0=road, 1=grass, 2=dirt, 3=ice, 4=mud)
+func setSurfaceMap(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	cfg := args[0]
+	width := int(optionalFloat(cfg, "width", 0))
+	depth := int(optionalFloat(cfg, "depth", 0))
+	cellSize := optionalFloat(cfg, "cellSize", 1.0)
+	if width <= 0 || depth <= 0 || !(cellSize > 0) {
+		return js.ValueOf(false)
+	}
+
+	surfaces := NewSurfaceMap(width, depth, cellSize, parseSurface(cfg.Get("fill")))
+	if cells := bytesFromJS(cfg.Get("cells")); cells != nil {
+		if len(cells) != width*depth {
+			return js.ValueOf(false)
+		}
+		for i, c := range cells {
+			surfaces.Cells[i] = SurfaceType(c)
+		}
+	}
+
+	surfaces.Default = parseSurface(cfg.Get("default"))
+	surfaces.OriginX = optionalFloat(cfg, "originX", surfaces.OriginX)
+	surfaces.OriginZ = optionalFloat(cfg, "originZ", surfaces.OriginZ)
+	w.Surfaces = surfaces
+	return js.ValueOf(true)
+}
+
+// paintSurface sets the surface of every cell overlapping a bounding box
+// JavaScript signature: paintSurface(bbox: {minX, minY, maxX, maxY}, surface: string|number) -> boolean
+func paintSurface(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || w.Surfaces == nil {
+		return js.ValueOf(false)
+	}
+	w.Surfaces.Paint(parseBoundingBox(args[0]), parseSurface(args[1]))
+	return js.ValueOf(true)
+}
+
+// clearSurfaceMap removes the surface grid (back to road everywhere)
+// JavaScript signature: clearSurfaceMap() -> void
+func clearSurfaceMap(w *World, args []js.Value) interface{} {
+	w.Surfaces = nil
+	return nil
+}
+
+// getSurfaceAt returns the surface name at a world position
+// JavaScript signature: getSurfaceAt(x: number, z: number) -> string
+func getSurfaceAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(SurfaceRoad.String())
+	}
+	return js.ValueOf(w.surfaceAt(args[0].Float(), args[1].Float()).String())
+}
+
+// ============================================================================
+// Collision World Bindings
+// ============================================================================
+
+// parseCollider converts a JS collider descriptor
+// Accepts {shape: 'circle', x, z, radius}, {shape: 'capsule', x1, z1, x2, z2, radius},
+// {shape: 'box', bbox} or a bare bbox {minX, minY, maxX, maxY}, each with an
+// optional top (world Y of the top face, unbounded if omitted)
+func parseCollider(id int, v js.Value) Collider {
+	shape := ShapeBox
+	if s := v.Get("shape"); s.Type() == js.TypeString {
+		shape = colliderShapeFromString(s.String())
+	}
+
+	c := Collider{ID: id, Shape: shape, Top: optionalFloat(v, "top", math.Inf(1))}
+	switch shape {
+	case ShapeCircle:
+		c.X = v.Get("x").Float()
+		c.Z = v.Get("z").Float()
+		c.Radius = math.Max(optionalFloat(v, "radius", 0), 0)
+	case ShapeCapsule:
+		c.X = v.Get("x1").Float()
+		c.Z = v.Get("z1").Float()
+		c.X2 = v.Get("x2").Float()
+		c.Z2 = v.Get("z2").Float()
+		c.Radius = math.Max(optionalFloat(v, "radius", 0), 0)
+	default:
+		if bbox := v.Get("bbox"); bbox.Type() == js.TypeObject {
+			c.Box = parseBoundingBox(bbox)
+		} else {
+			c.Box = parseBoundingBox(v)
+		}
+	}
+	return c
+}
+
+// setStaticColliders replaces all static colliders used by the car physics
+// JavaScript signature: setStaticColliders(colliders: Array<{id, shape?, ...}>) -> boolean
+// See parseCollider for the accepted shapes
+func setStaticColliders(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	collidersArray := args[0]
+	w.Colliders.Clear()
+	for i := 0; i < collidersArray.Length(); i++ {
+		entry := collidersArray.Index(i)
+		w.Colliders.Add(parseCollider(entry.Get("id").Int(), entry))
+	}
+
+	return js.ValueOf(true)
+}
+
+// addStaticCollider registers (or replaces) a single static collider
+// JavaScript signature: addStaticCollider(id: number, collider: {shape?, ...} | {minX, minY, maxX, maxY}) -> boolean
+func addStaticCollider(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(false)
+	}
+
+	w.Colliders.Add(parseCollider(args[0].Int(), args[1]))
+	return js.ValueOf(true)
+}
+
+// removeStaticCollider unregisters a static collider
+// JavaScript signature: removeStaticCollider(id: number) -> boolean
+func removeStaticCollider(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Colliders.Remove(args[0].Int()))
+}
+
+// clearStaticColliders removes all static colliders
+// JavaScript signature: clearStaticColliders() -> void
+func clearStaticColliders(w *World, args []js.Value) interface{} {
+	w.Colliders.Clear()
+	return nil
+}
+
+// parseVector3 reads a {x, y, z} object
+func parseVector3(v js.Value) (x, y, z float64) {
+	return v.Get("x").Float(), v.Get("y").Float(), v.Get("z").Float()
+}
+
+// raycast returns the first collider or terrain hit along a ray
+// JavaScript signature: raycast(origin: {x, y, z}, direction: {x, y, z}, maxDist?: number)
+//   -> {x, y, z, normal: {x, y, z}, distance, id, kind: "collider"|"terrain"} | null
+// id is the collider ID, or -1 for terrain
+func raycast(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(nil)
+	}
+
+	ox, oy, oz := parseVector3(args[0])
+	dx, dy, dz := parseVector3(args[1])
+	maxDist := MAX_RAY_DISTANCE
+	if len(args) >= 3 && args[2].Type() == js.TypeNumber {
+		maxDist = args[2].Float()
+	}
+
+	hit, ok := w.Raycast(ox, oy, oz, dx, dy, dz, maxDist)
+	if !ok {
+		return js.ValueOf(nil)
+	}
+
+	return js.ValueOf(map[string]interface{}{
+		"x": hit.X,
+		"y": hit.Y,
+		"z": hit.Z,
+		"normal": map[string]interface{}{
+			"x": hit.NormalX,
+			"y": hit.NormalY,
+			"z": hit.NormalZ,
+		},
+		"distance": hit.Distance,
+		"id":       hit.ID,
+		"kind":     hit.Kind.String(),
+	})
+}
+
+// collisionEventToJS converts a collision event for JS
+func collisionEventToJS(e CollisionEvent) map[string]interface{} {
+	return map[string]interface{}{
+		"kind":         e.Kind.String(),
+		"a":            e.A,
+		"b":            e.B,
+		"impact_speed": e.ImpactSpeed,
+		"point_x":      e.PointX,
+		"point_z":      e.PointZ,
+		"normal_x":     e.NormalX,
+		"normal_z":     e.NormalZ,
+	}
+}
+
+// drainCollisionEvents returns and clears the impacts queued since the last call
+// JavaScript signature: drainCollisionEvents()
+//   -> {events: Array<{kind: "static"|"vehicle"|"water"|"road_feature"|"train", a, b, impact_speed, point_x, point_z, normal_x, normal_z}>, dropped}
+// Road features report the feature id as b and the crossing speed as impact_speed
+func drainCollisionEvents(w *World, args []js.Value) interface{} {
+	events, dropped := w.takeCollisionEvents()
+
+	eventsResult := make([]interface{}, len(events))
+	for i, e := range events {
+		eventsResult[i] = collisionEventToJS(e)
+	}
+
+	return js.ValueOf(map[string]interface{}{
+		"events":  eventsResult,
+		"dropped": dropped,
+	})
+}
+
+// skidEventToJS converts a skid event for JS
+func skidEventToJS(e SkidEvent) map[string]interface{} {
+	wheels := make([]interface{}, len(e.Wheels))
+	for i, wheel := range e.Wheels {
+		wheels[i] = map[string]interface{}{"x": wheel.X, "y": wheel.Y, "z": wheel.Z}
+	}
+	return map[string]interface{}{
+		"car_id":    e.CarID,
+		"start":     e.Start,
+		"intensity": e.Intensity,
+		"surface":   e.Surface.String(),
+		"wheels":    wheels,
+	}
+}
+
+// drainSkidEvents returns and clears the skids queued since the last call
+// JavaScript signature: drainSkidEvents()
+//   -> {events: Array<{car_id, start, intensity, surface, wheels: Array<{x, y, z}>}>, dropped}
+// wheels are front left, front right, rear left, rear right (front, rear
+// for two-wheelers); start begins new marks
+func drainSkidEvents(w *World, args []js.Value) interface{} {
+	skids, dropped := w.takeSkidEvents()
+
+	eventsResult := make([]interface{}, len(skids))
+	for i, e := range skids {
+		eventsResult[i] = skidEventToJS(e)
+	}
+
+	return js.ValueOf(map[string]interface{}{
+		"events":  eventsResult,
+		"dropped": dropped,
+	})
+}
+
+// setWorldBounds limits where cars can drive
+// JavaScript signature: setWorldBounds(bbox: {minX, minY, maxX, maxY}, mode?: "clamp"|"bounce"|"wrap") -> boolean
+func setWorldBounds(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	box := parseBoundingBox(args[0])
+	if !(box.MaxX > box.MinX && box.MaxY > box.MinY) {
+		return js.ValueOf(false) // Also rejects NaN
+	}
+
+	mode := BoundsClamp
+	if len(args) >= 2 && args[1].Type() == js.TypeString {
+		mode = boundsModeFromString(args[1].String())
+	}
+
+	w.Bounds = &WorldBounds{Box: box, Mode: mode}
+	return js.ValueOf(true)
+}
+
+// clearWorldBounds removes the world bounds so cars can drive anywhere
+// JavaScript signature: clearWorldBounds() -> void
+func clearWorldBounds(w *World, args []js.Value) interface{} {
+	w.Bounds = nil
+	return nil
+}
+
+// resolveCarCollisions separates overlapping cars and exchanges impulses
+// JavaScript signature: resolveCarCollisions(cars: Array<{id?, x, z, velocity_x, velocity_z, vehicle?}>)
+//   -> {cars: Array<{x, z, velocity_x, velocity_z}>, contacts: Array<{a, b, normal_x, normal_z, depth, impulse, impact_speed, point_x, point_z}>}
+// Contact a/b are indices into the cars array; id (default: the index) is used in
+// collision events; vehicle is a preset name or config (see updateCarPhysics)
+func resolveCarCollisionsJS(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(nil)
+	}
+
+	carsArray := args[0]
+	bodies := make([]CarBody, carsArray.Length())
+	for i := range bodies {
+		carJS := carsArray.Index(i)
+		cfg := DefaultVehicleConfig()
+		if vehicle := carJS.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+			cfg = parseVehicleConfig(vehicle)
+		}
+		bodies[i] = CarBody{
+			ID:        int(optionalFloat(carJS, "id", float64(i))),
+			X:         carJS.Get("x").Float(),
+			Z:         carJS.Get("z").Float(),
+			VelocityX: optionalFloat(carJS, "velocity_x", 0),
+			VelocityZ: optionalFloat(carJS, "velocity_z", 0),
+			Mass:      cfg.Mass,
+			Radius:    cfg.BodyRadius,
+		}
+	}
+
+	contacts := w.resolveCarCollisions(bodies)
+
+	carsResult := make([]interface{}, len(bodies))
+	for i, b := range bodies {
+		carsResult[i] = map[string]interface{}{
+			"x":          b.X,
+			"z":          b.Z,
+			"velocity_x": b.VelocityX,
+			"velocity_z": b.VelocityZ,
+		}
+	}
+
+	contactsResult := make([]interface{}, len(contacts))
+	for i, c := range contacts {
+		contactsResult[i] = map[string]interface{}{
+			"a":            c.A,
+			"b":            c.B,
+			"normal_x":     c.NormalX,
+			"normal_z":     c.NormalZ,
+			"depth":        c.Depth,
+			"impulse":      c.Impulse,
+			"impact_speed": c.ImpactSpeed,
+			"point_x":      c.PointX,
+			"point_z":      c.PointZ,
+		}
+	}
+
+	return js.ValueOf(map[string]interface{}{
+		"cars":     carsResult,
+		"contacts": contactsResult,
+	})
+}
+
+// ============================================================================
+// Road Network Bindings
+// ============================================================================
+
+// parseRoadOptions reads optional {speed_limit, lanes, priority, two_way} road settings
+// (bike_lane is read by addRoadFromOptions)
+// Roads are two-way unless two_way is false
+func parseRoadOptions(v js.Value) (speedLimit float64, lanes, priority int, twoWay bool) {
+	if v.Type() != js.TypeObject {
+		return 0, 0, 0, true
+	}
+	twoWay = true
+	if tw := v.Get("two_way"); tw.Type() == js.TypeBoolean {
+		twoWay = tw.Bool()
+	}
+	return optionalFloat(v, "speed_limit", 0), int(optionalFloat(v, "lanes", 0)), int(optionalFloat(v, "priority", 0)), twoWay
+}
+
+// addRoadFromOptions adds a road with parsed options, applying its priority
+// and bike lane to both directions
+func addRoadFromOptions(g *RoadGraph, from, to int, options js.Value) (forward, reverse int, err error) {
+	speedLimit, lanes, priority, twoWay := parseRoadOptions(options)
+	forward, reverse, err = g.AddRoad(from, to, speedLimit, lanes, twoWay)
+	if err != nil {
+		return forward, reverse, err
+	}
+	g.SetPriority(forward, priority)
+	g.SetPriority(reverse, priority) // No-op for one-way roads (-1)
+	if options.Type() == js.TypeObject && options.Get("bike_lane").Truthy() {
+		g.SetBikeLane(forward, true)
+		g.SetBikeLane(reverse, true)
+	}
+	return forward, reverse, nil
+}
+
+// setRoadNetwork replaces the road graph, clearing the edit history
+// JavaScript signature: setRoadNetwork({nodes: Array<{id, x, z}>, roads: Array<{from, to, speed_limit?, lanes?, priority?, two_way?, bike_lane?}>})
+//   -> {node_count, edge_count, errors?}
+// Invalid roads are skipped and reported in errors
+func setRoadNetwork(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(nil)
+	}
+
+	w.Roads.Clear()
+	w.History.Clear()
+	nodes := args[0].Get("nodes")
+	if nodes.Type() == js.TypeObject {
+		for i := 0; i < nodes.Length(); i++ {
+			n := nodes.Index(i)
+			w.Roads.AddNode(n.Get("id").Int(), n.Get("x").Float(), n.Get("z").Float())
+		}
+	}
+
+	var messages []interface{}
+	roads := args[0].Get("roads")
+	if roads.Type() == js.TypeObject {
+		for i := 0; i < roads.Length(); i++ {
+			r := roads.Index(i)
+			if _, _, err := addRoadFromOptions(w.Roads, r.Get("from").Int(), r.Get("to").Int(), r); err != nil {
+				messages = append(messages, "road "+strconv.Itoa(i)+": "+err.Error())
+			}
+		}
+	}
+
+	result := map[string]interface{}{
+		"node_count": w.Roads.NodeCount(),
+		"edge_count": w.Roads.EdgeCount(),
+	}
+	if len(messages) > 0 {
+		result["errors"] = messages
+	}
+	return js.ValueOf(result)
+}
+
+// clearRoadNetwork removes every road node and segment
+// JavaScript signature: clearRoadNetwork() -> void
+func clearRoadNetwork(w *World, args []js.Value) interface{} {
+	w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		empty := g.NodeCount() == 0
+		g.Clear()
+		return !empty
+	}))
+	return nil
+}
+
+// generateRoadNetwork replaces the road graph with a seeded procedural layout
+// JavaScript signature: generateRoadNetwork({style?: "grid"|"radial"|"organic", seed?, extent?, spacing?,
+//   spokes?, iterations?, branch_chance?, jitter?, speed_limit?, lanes?})
+//   -> {node_count, edge_count, roads, signals} | null
+// Returns null for an unknown style; read the layout back with getRoadGraph
+func generateRoadNetwork(w *World, args []js.Value) interface{} {
+	cfg := DefaultRoadGenConfig()
+	if len(args) >= 1 && args[0].Type() == js.TypeObject {
+		o := args[0]
+		if style := o.Get("style"); style.Type() == js.TypeString {
+			s, ok := roadStyleFromString(style.String())
+			if !ok {
+				return js.ValueOf(nil)
+			}
+			cfg.Style = s
+		}
+		cfg.Seed = int64(optionalFloat(o, "seed", 0))
+		cfg.Extent = optionalFloat(o, "extent", cfg.Extent)
+		cfg.Spacing = optionalFloat(o, "spacing", cfg.Spacing)
+		cfg.Spokes = int(optionalFloat(o, "spokes", float64(cfg.Spokes)))
+		cfg.Iterations = int(optionalFloat(o, "iterations", float64(cfg.Iterations)))
+		cfg.BranchChance = optionalFloat(o, "branch_chance", cfg.BranchChance)
+		cfg.Jitter = optionalFloat(o, "jitter", cfg.Jitter)
+		cfg.SpeedLimit = optionalFloat(o, "speed_limit", cfg.SpeedLimit)
+		cfg.Lanes = int(optionalFloat(o, "lanes", float64(cfg.Lanes)))
+	}
+
+	result := w.GenerateRoadNetwork(cfg)
+	return js.ValueOf(map[string]interface{}{
+		"node_count": result.Nodes,
+		"edge_count": w.Roads.EdgeCount(),
+		"roads":      result.Roads,
+		"signals":    result.Signals,
+	})
+}
+
+// addRoadNode adds or moves an intersection
+// JavaScript signature: addRoadNode(id: number, x: number, z: number) -> boolean
+func addRoadNode(w *World, args []js.Value) interface{} {
+	if len(args) < 3 {
+		return js.ValueOf(false)
+	}
+	id, x, z := args[0].Int(), args[1].Float(), args[2].Float()
+	w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		g.AddNode(id, x, z)
+		return true
+	}))
+	return js.ValueOf(true)
+}
+
+// removeRoadNode removes an intersection and every segment touching it
+// JavaScript signature: removeRoadNode(id: number) -> boolean
+func removeRoadNode(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	id := args[0].Int()
+	return js.ValueOf(w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		return g.RemoveNode(id)
+	})))
+}
+
+// addRoad connects two nodes with a road
+// JavaScript signature: addRoad(from: number, to: number, options?: {speed_limit, lanes, priority, two_way, bike_lane}) -> {forward, reverse} | null
+// priority gives right of way at unsignalized intersections; lower-priority roads have stop signs
+// bike_lane adds a bike lane beside both directions; reverse is -1 for one-way roads
+func addRoad(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(nil)
+	}
+
+	var options js.Value
+	if len(args) >= 3 {
+		options = args[2]
+	}
+
+	from, to := args[0].Int(), args[1].Int()
+	var forward, reverse int
+	var err error
+	w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		forward, reverse, err = addRoadFromOptions(g, from, to, options)
+		return err == nil
+	}))
+	if err != nil {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(map[string]interface{}{"forward": forward, "reverse": reverse})
+}
+
+// setBikeLane adds or removes the bike lane beside one directed segment
+// JavaScript signature: setBikeLane(id: number, on: boolean) -> boolean
+// Returns false if the segment does not exist or already has (or lacks) one
+func setBikeLane(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeBoolean {
+		return js.ValueOf(false)
+	}
+	id, on := args[0].Int(), args[1].Bool()
+	return js.ValueOf(w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		e := g.Edge(id)
+		return e != nil && e.BikeLane != on && g.SetBikeLane(id, on)
+	})))
+}
+
+// removeRoadSegment removes one directed segment
+// JavaScript signature: removeRoadSegment(id: number) -> boolean
+func removeRoadSegment(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	id := args[0].Int()
+	return js.ValueOf(w.Edit(RoadEdit(func(g *RoadGraph) bool {
+		return g.RemoveEdge(id)
+	})))
+}
+
+// getRoadGraph returns the whole road graph (for debugging overlays)
+// JavaScript signature: getRoadGraph() -> {nodes: Array<{id, x, z}>, edges: Array<{id, from, to, length, speed_limit, lanes, priority, bike_lane}>}
+func getRoadGraph(w *World, args []js.Value) interface{} {
+	nodeIDs := w.Roads.NodeIDs()
+	nodes := make([]interface{}, len(nodeIDs))
+	for i, id := range nodeIDs {
+		n := w.Roads.Node(id)
+		nodes[i] = map[string]interface{}{"id": n.ID, "x": n.X, "z": n.Z}
+	}
+
+	edgeIDs := w.Roads.EdgeIDs()
+	edges := make([]interface{}, len(edgeIDs))
+	for i, id := range edgeIDs {
+		e := w.Roads.Edge(id)
+		edges[i] = map[string]interface{}{
+			"id":          e.ID,
+			"from":        e.From,
+			"to":          e.To,
+			"length":      e.Length,
+			"speed_limit": e.SpeedLimit,
+			"lanes":       e.Lanes,
+			"priority":    e.Priority,
+			"bike_lane":   e.BikeLane,
+		}
+	}
+
+	return js.ValueOf(map[string]interface{}{"nodes": nodes, "edges": edges})
+}
+
+// findRoute returns the fastest route for a car between two road nodes, at
+// the speed limits districts set
+// JavaScript signature: findRoute(fromNode: number, toNode: number)
+//   -> {nodes: number[], edges: number[], waypoints: Array<{x, z}>, length, travel_time} | null
+func findRoute(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(nil)
+	}
+
+	route, ok := w.driveRoute(args[0].Int(), args[1].Int(), nil)
+	if !ok {
+		return js.ValueOf(nil)
+	}
+
+	nodes := make([]interface{}, len(route.Nodes))
+	waypoints := make([]interface{}, len(route.Nodes))
+	for i, id := range route.Nodes {
+		n := w.Roads.Node(id)
+		nodes[i] = id
+		waypoints[i] = map[string]interface{}{"x": n.X, "z": n.Z}
+	}
+	edges := make([]interface{}, len(route.Edges))
+	for i, id := range route.Edges {
+		edges[i] = id
+	}
+
+	return js.ValueOf(map[string]interface{}{
+		"nodes":       nodes,
+		"edges":       edges,
+		"waypoints":   waypoints,
+		"length":      route.Length,
+		"travel_time": route.TravelTime,
+	})
+}
+
+// nearestRoadNode returns the road node closest to a world position
+// JavaScript signature: nearestRoadNode(x: number, z: number) -> {id, x, z, distance} | null
+func nearestRoadNode(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(nil)
+	}
+
+	x, z := args[0].Float(), args[1].Float()
+	n := w.Roads.NearestNode(x, z)
+	if n == nil {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(map[string]interface{}{
+		"id":       n.ID,
+		"x":        n.X,
+		"z":        n.Z,
+		"distance": math.Hypot(n.X-x, n.Z-z),
+	})
+}
+
+// ============================================================================
+// Traffic Bindings (AI drivers and signals)
+// ============================================================================
+
+// spawnAICar adds an AI car that drives the route between two road nodes
+// JavaScript signature: spawnAICar(id: number, fromNode: number, toNode: number, vehicle?) -> boolean
+// vehicle is a preset name or config; the car replaces any car with the same id
+func spawnAICar(w *World, args []js.Value) interface{} {
+	if len(args) < 3 {
+		return js.ValueOf(false)
+	}
+
+	cfg := DefaultVehicleConfig()
+	if len(args) >= 4 {
+		cfg = parseVehicleConfig(args[3])
+	}
+	return js.ValueOf(w.SpawnAICar(args[0].Int(), args[1].Int(), args[2].Int(), cfg) == nil)
+}
+
+// setAIDestination reroutes an AI car after its current road segment
+// JavaScript signature: setAIDestination(id: number, toNode: number) -> boolean
+func setAIDestination(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.SetAIDestination(args[0].Int(), args[1].Int()) == nil)
+}
+
+// getAIDrivers lists every AI car's route progress
+// JavaScript signature: getAIDrivers() -> Array<{id, edge, lane, destination, arrived, siren, yielding}>
+// edge is the road segment being driven (-1 once arrived); lane 0 is the rightmost;
+// yielding is true while pulled over for a siren
+func getAIDrivers(w *World, args []js.Value) interface{} {
+	drivers := make([]interface{}, 0)
+	for _, car := range w.Cars() {
+		ai := car.AI
+		if ai == nil {
+			continue
+		}
+
+		edge, destination := -1, -1
+		if ai.EdgeIndex < len(ai.Route.Edges) && !ai.Arrived {
+			edge = ai.Route.Edges[ai.EdgeIndex]
+		}
+		if len(ai.Route.Nodes) > 0 {
+			destination = ai.Route.Nodes[len(ai.Route.Nodes)-1]
+		}
+		drivers = append(drivers, map[string]interface{}{
+			"id":          car.State.ID,
+			"edge":        edge,
+			"lane":        ai.Lane,
+			"destination": destination,
+			"arrived":     ai.Arrived,
+			"siren":       car.Siren,
+			"yielding":    ai.Yielding,
+		})
+	}
+	return js.ValueOf(drivers)
+}
+
+// setTrafficSignal installs (or replaces) a traffic signal at a road node
+// JavaScript signature: setTrafficSignal(node: number, options?: {green_time?, yellow_time?,
+//   phases?: Array<{edges: number[], green_time?, yellow_time?}>}) -> boolean
+// Without phases, opposite approaches share a green and phases follow road edits
+func setTrafficSignal(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+
+	greenTime, yellowTime := DEFAULT_GREEN_TIME, DEFAULT_YELLOW_TIME
+	var phases []SignalPhase
+	if len(args) >= 2 && args[1].Type() == js.TypeObject {
+		opts := args[1]
+		greenTime = optionalFloat(opts, "green_time", greenTime)
+		yellowTime = optionalFloat(opts, "yellow_time", yellowTime)
+
+		if list := opts.Get("phases"); list.Type() == js.TypeObject {
+			for i := 0; i < list.Length(); i++ {
+				entry := list.Index(i)
+				phase := SignalPhase{
+					GreenTime:  optionalFloat(entry, "green_time", 0),   // 0 = signal default
+					YellowTime: optionalFloat(entry, "yellow_time", -1), // -1 = signal default
+				}
+				if edges := entry.Get("edges"); edges.Type() == js.TypeObject {
+					for j := 0; j < edges.Length(); j++ {
+						phase.Edges = append(phase.Edges, edges.Index(j).Int())
+					}
+				}
+				phases = append(phases, phase)
+			}
+		}
+	}
+
+	return js.ValueOf(w.SetSignal(args[0].Int(), phases, greenTime, yellowTime) == nil)
+}
+
+// removeTrafficSignal removes the signal at a road node
+// JavaScript signature: removeTrafficSignal(node: number) -> boolean
+func removeTrafficSignal(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemoveSignal(args[0].Int()))
+}
+
+// getTrafficSignals returns every signal's phase and the light on each approach
+// JavaScript signature: getTrafficSignals()
+//   -> Array<{node, phase, elapsed, lights: Array<{edge, light: "green"|"yellow"|"red"}>}>
+func getTrafficSignals(w *World, args []js.Value) interface{} {
+	signals := make([]interface{}, 0, len(w.Signals))
+	for _, id := range w.Roads.NodeIDs() {
+		s := w.Signals[id]
+		if s == nil {
+			continue
+		}
+
+		node := w.Roads.Node(id)
+		lights := make([]interface{}, len(node.In))
+		for i, edgeID := range node.In {
+			lights[i] = map[string]interface{}{
+				"edge":  edgeID,
+				"light": s.Light(edgeID).String(),
+			}
+		}
+		signals = append(signals, map[string]interface{}{
+			"node":    id,
+			"phase":   s.Phase,
+			"elapsed": s.Elapsed,
+			"lights":  lights,
+		})
+	}
+	return js.ValueOf(signals)
+}
+
+// getSignalLight returns the light at the end of a road segment
+// JavaScript signature: getSignalLight(edge: number) -> "green"|"yellow"|"red"
+// Segments that do not end at a signal are always green
+func getSignalLight(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(LightGreen.String())
+	}
+	return js.ValueOf(w.SignalLightFor(args[0].Int()).String())
+}
+
+// ============================================================================
+// Pedestrian Bindings
+// ============================================================================
+
+// spawnPedestrian adds (or replaces) a pedestrian walking between two positions
+// JavaScript signature: spawnPedestrian(id: number, from: {x, z}, to: {x, z}, speed?: number, direct?: boolean) -> boolean
+// Pedestrians walk the sidewalks of the road network (false if there is no
+// route), or straight to the goal when direct is true (plazas and parks)
+func spawnPedestrian(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[1].Type() != js.TypeObject || args[2].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	speed := 0.0
+	if len(args) >= 4 && args[3].Type() == js.TypeNumber {
+		speed = args[3].Float()
+	}
+	direct := len(args) >= 5 && args[4].Type() == js.TypeBoolean && args[4].Bool()
+	from, to := args[1], args[2]
+	err := w.SpawnPedestrian(args[0].Int(), from.Get("x").Float(), from.Get("z").Float(), to.Get("x").Float(), to.Get("z").Float(), speed, direct)
+	return js.ValueOf(err == nil)
+}
+
+// setPedestrianGoal sends a pedestrian straight to a new position
+// JavaScript signature: setPedestrianGoal(id: number, x: number, z: number) -> boolean
+func setPedestrianGoal(w *World, args []js.Value) interface{} {
+	if len(args) < 3 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.SetPedestrianGoal(args[0].Int(), args[1].Float(), args[2].Float()))
+}
+
+// setCrowdConfig tunes the pedestrian social forces; omitted keys keep their value
+// JavaScript signature: setCrowdConfig({relaxation_time?, repulsion_strength?, repulsion_range?,
+//   obstacle_strength?, obstacle_range?, anisotropy?, max_speed_scale?}) -> object
+// Returns the sanitized config (see getCrowdConfig)
+func setCrowdConfig(w *World, args []js.Value) interface{} {
+	if len(args) >= 1 && args[0].Type() == js.TypeObject {
+		v, c := args[0], w.Crowd
+		w.Crowd = CrowdConfig{
+			RelaxationTime:    optionalFloat(v, "relaxation_time", c.RelaxationTime),
+			RepulsionStrength: optionalFloat(v, "repulsion_strength", c.RepulsionStrength),
+			RepulsionRange:    optionalFloat(v, "repulsion_range", c.RepulsionRange),
+			ObstacleStrength:  optionalFloat(v, "obstacle_strength", c.ObstacleStrength),
+			ObstacleRange:     optionalFloat(v, "obstacle_range", c.ObstacleRange),
+			Anisotropy:        optionalFloat(v, "anisotropy", c.Anisotropy),
+			MaxSpeedScale:     optionalFloat(v, "max_speed_scale", c.MaxSpeedScale),
+		}.sanitized()
+	}
+	return getCrowdConfig(w, nil)
+}
+
+// getCrowdConfig returns the pedestrian social force tuning
+// JavaScript signature: getCrowdConfig() -> {relaxation_time, repulsion_strength, repulsion_range,
+//   obstacle_strength, obstacle_range, anisotropy, max_speed_scale}
+func getCrowdConfig(w *World, args []js.Value) interface{} {
+	c := w.Crowd
+	return js.ValueOf(map[string]interface{}{
+		"relaxation_time":    c.RelaxationTime,
+		"repulsion_strength": c.RepulsionStrength,
+		"repulsion_range":    c.RepulsionRange,
+		"obstacle_strength":  c.ObstacleStrength,
+		"obstacle_range":     c.ObstacleRange,
+		"anisotropy":         c.Anisotropy,
+		"max_speed_scale":    c.MaxSpeedScale,
+	})
+}
+
+// removePedestrian removes a pedestrian
+// JavaScript signature: removePedestrian(id: number) -> boolean
+func removePedestrian(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemovePedestrian(args[0].Int()))
+}
+
+// getPedestrianTransforms returns every pedestrian's position and heading in one buffer
+// JavaScript signature: getPedestrianTransforms(out?: Float64Array) -> Float64Array
+// Each pedestrian uses PEDESTRIAN_TRANSFORM_STRIDE values: id, x, z, heading, state
+// (0 walking, 1 waiting, 2 crossing, 3 arrived). out is reused when large enough
+func getPedestrianTransforms(w *World, args []js.Value) interface{} {
+	transforms := make([]float64, len(w.Pedestrians())*PEDESTRIAN_TRANSFORM_STRIDE)
+	w.WritePedestrianTransforms(transforms)
+	return reusableFloat64Array(args, transforms)
+}
+
+// ============================================================================
+// Occupancy Bindings
+// ============================================================================
+
+// parsePlacement converts a JS placement descriptor
+// Accepts {id, model, category, bbox: {minX, minY, maxX, maxY}, collider?}, where
+// collider uses the parseCollider format and is registered with the same id
+func parsePlacement(v js.Value) (Placement, *Collider) {
+	p := Placement{ID: v.Get("id").Int(), Footprint: BoundingBox{MinX: math.NaN()}} // Rejected unless bbox is given
+	if bbox := v.Get("bbox"); bbox.Type() == js.TypeObject {
+		p.Footprint = parseBoundingBox(bbox)
+	}
+	if model := v.Get("model"); model.Type() == js.TypeString {
+		p.Model = model.String()
+	}
+	if category := v.Get("category"); category.Type() == js.TypeString {
+		p.Category = category.String()
+	}
+	p.Rotation = optionalFloat(v, "rotation", 0)
+
+	if c := v.Get("collider"); c.Type() == js.TypeObject {
+		collider := parseCollider(p.ID, c)
+		return p, &collider
+	}
+	return p, nil
+}
+
+// placementToJS converts a placement to its JS descriptor (without collider)
+func placementToJS(p *Placement) map[string]interface{} {
+	return map[string]interface{}{
+		"id":       p.ID,
+		"model":    p.Model,
+		"category": p.Category,
+		"bbox": map[string]interface{}{
+			"minX": p.Footprint.MinX,
+			"minY": p.Footprint.MinY,
+			"maxX": p.Footprint.MaxX,
+			"maxY": p.Footprint.MaxY,
+		},
+		"rotation": p.Rotation,
+	}
+}
+
+// setPlacements replaces every placed object (and all static colliders),
+// clearing the edit history
+// JavaScript signature: setPlacements(placements: Array<{id, model, category, bbox, rotation?, collider?}>)
+//   -> {count, rejected?: number[]}
+// rejected lists ids with invalid footprints
+func setPlacements(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(nil)
+	}
+
+	w.ClearPlacements()
+	var rejected []interface{}
+	for i := 0; i < args[0].Length(); i++ {
+		p, collider := parsePlacement(args[0].Index(i))
+		if !w.AddPlacement(p, collider) {
+			rejected = append(rejected, p.ID)
+		}
+	}
+
+	result := map[string]interface{}{"count": w.Occupancy.Count()}
+	if len(rejected) > 0 {
+		result["rejected"] = rejected
+	}
+	return js.ValueOf(result)
+}
+
+// addPlacement registers (or replaces) one placed object
+// JavaScript signature: addPlacement(placement: {id, model, category, bbox, rotation?, collider?}) -> boolean
+func addPlacement(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	p, collider := parsePlacement(args[0])
+	return js.ValueOf(w.Edit(PlaceEdit(p, collider)))
+}
+
+// removePlacement unregisters a placed object and its collider
+// JavaScript signature: removePlacement(id: number) -> boolean
+func removePlacement(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Edit(RemovePlacementEdit(args[0].Int())))
+}
+
+// getPlacementAt returns the placed object covering a position
+// JavaScript signature: getPlacementAt(x: number, z: number) -> {id, model, category, bbox} | null
+func getPlacementAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(nil)
+	}
+	p := w.Occupancy.At(args[0].Float(), args[1].Float())
+	if p == nil {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(placementToJS(p))
+}
+
+// queryPlacements returns the placed objects overlapping a box, optionally of one category
+// JavaScript signature: queryPlacements(bbox: {minX, minY, maxX, maxY}, category?: string)
+//   -> Array<{id, model, category, bbox}>
+func queryPlacements(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf([]interface{}{})
+	}
+
+	category := ""
+	if len(args) >= 2 && args[1].Type() == js.TypeString {
+		category = args[1].String()
+	}
+
+	result := make([]interface{}, 0)
+	for _, p := range w.Occupancy.Query(parseBoundingBox(args[0])) {
+		if category == "" || p.Category == category {
+			result = append(result, placementToJS(p))
+		}
+	}
+	return js.ValueOf(result)
+}
+
+// setOccupancyGrid changes the town cell layout, keeping every placement and
+// resampling the zone map
+// JavaScript signature: setOccupancyGrid({width, depth, cellSize, originX?, originZ?}) -> boolean
+func setOccupancyGrid(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	cfg := args[0]
+	spec := NewGridSpec(int(optionalFloat(cfg, "width", 0)), int(optionalFloat(cfg, "depth", 0)), optionalFloat(cfg, "cellSize", OCCUPANCY_CELL_SIZE))
+	spec.OriginX = optionalFloat(cfg, "originX", spec.OriginX)
+	spec.OriginZ = optionalFloat(cfg, "originZ", spec.OriginZ)
+	if !spec.valid() {
+		return js.ValueOf(false)
+	}
+	w.ResizeTownGrid(spec)
+	return js.ValueOf(true)
+}
+
+// getOccupancyGrid returns the cell layout and the placement id owning each cell
+// JavaScript signature: getOccupancyGrid(out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, cells: Float64Array}
+// cells holds width*depth placement ids (-1 for free cells), row-major by z;
+// out is reused for cells when it is large enough
+func getOccupancyGrid(w *World, args []js.Value) interface{} {
+	g := w.Occupancy
+	cells := make([]float64, g.GridSpec.Len())
+	for i := range cells {
+		cells[i] = float64(g.CellOwner(i))
+	}
+	return js.ValueOf(map[string]interface{}{
+		"width":    g.Width,
+		"depth":    g.Depth,
+		"cellSize": g.CellSize,
+		"originX":  g.OriginX,
+		"originZ":  g.OriginZ,
+		"cells":    reusableFloat64Array(args, cells),
+	})
+}
+
+// registerModel describes a placeable model so canPlace can check it
+// JavaScript signature: registerModel(name: string, {category, use?, width, depth, requires_road?, max_slope?, bridge?}) -> boolean
+// width/depth are the unrotated X/Z size; buildings require road access by default;
+// use ("housing", "market", "office", "industry", "school", ...) picks the zones it may go in;
+// bridges (by default models named *bridge*) may stand on water and carry cars over it
+func registerModel(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	category := ""
+	if c := v.Get("category"); c.Type() == js.TypeString {
+		category = c.String()
+	}
+	width, depth := optionalFloat(v, "width", 0), optionalFloat(v, "depth", 0)
+	if !(width >= 0 && depth >= 0) || !isFinite(width) || !isFinite(depth) {
+		return js.ValueOf(false)
+	}
+
+	spec := NewModelSpec(args[0].String(), category, width, depth)
+	if u := v.Get("use"); u.Type() == js.TypeString {
+		spec.Use = u.String()
+	}
+	if r := v.Get("requires_road"); r.Type() == js.TypeBoolean {
+		spec.RequiresRoad = r.Bool()
+	}
+	if b := v.Get("bridge"); b.Type() == js.TypeBoolean {
+		spec.Bridge = b.Bool()
+	}
+	spec.MaxSlope = positiveOr(optionalFloat(v, "max_slope", spec.MaxSlope), spec.MaxSlope)
+	w.Models[spec.Name] = spec
+	w.Utilities.invalidate()
+	return js.ValueOf(true)
+}
+
+// canPlace checks whether a model can be placed at a position
+// JavaScript signature: canPlace(model: string, x: number, z: number, rotation?: number, ignoreId?: number)
+//   -> {ok, reason: "ok"|"unknown_model"|"out_of_bounds"|"overlap"|"no_road_access"|"too_steep"|"wrong_zone"|"unaffordable"
+//       |"in_water"|"needs_bridge"|"district_ban",
+//       message, footprint: {minX, minY, maxX, maxY}, conflicts: number[]}
+// ignoreId leaves out an existing placement (when moving it)
+func canPlace(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[0].Type() != js.TypeString {
+		return js.ValueOf(nil)
+	}
+
+	rotation := 0.0
+	if len(args) >= 4 && args[3].Type() == js.TypeNumber {
+		rotation = args[3].Float()
+	}
+	ignore := emptyCell
+	if len(args) >= 5 && args[4].Type() == js.TypeNumber {
+		ignore = args[4].Int()
+	}
+
+	check := w.CanPlace(args[0].String(), args[1].Float(), args[2].Float(), rotation, ignore)
+	conflicts := make([]interface{}, len(check.Conflicts))
+	for i, id := range check.Conflicts {
+		conflicts[i] = id
+	}
+	return js.ValueOf(map[string]interface{}{
+		"ok":      check.OK(),
+		"reason":  check.Reason.String(),
+		"message": check.Reason.Message(),
+		"footprint": map[string]interface{}{
+			"minX": check.Footprint.MinX,
+			"minY": check.Footprint.MinY,
+			"maxX": check.Footprint.MaxX,
+			"maxY": check.Footprint.MaxY,
+		},
+		"conflicts": conflicts,
+	})
+}
+
+// planLots proposes buildings along the open sides of road tiles
+// JavaScript signature: planLots({seed?, models?: string[], max_lots?})
+//   -> Array<{model, x, z, rotation, zone, cost}>
+// Every lot passes canPlace and lots do not overlap each other; nothing is
+// placed, so JS loads the models and registers them as usual
+func planLots(w *World, args []js.Value) interface{} {
+	var cfg LotGenConfig
+	if len(args) >= 1 && args[0].Type() == js.TypeObject {
+		o := args[0]
+		cfg.Seed = int64(optionalFloat(o, "seed", 0))
+		cfg.MaxLots = int(optionalFloat(o, "max_lots", 0))
+		if models := o.Get("models"); models.Type() == js.TypeObject {
+			for i := 0; i < models.Length(); i++ {
+				cfg.Models = append(cfg.Models, models.Index(i).String())
+			}
+		}
+	}
+
+	plans := w.PlanLots(cfg)
+	result := make([]interface{}, len(plans))
+	for i, lot := range plans {
+		result[i] = map[string]interface{}{
+			"model":    lot.Model,
+			"x":        lot.X,
+			"z":        lot.Z,
+			"rotation": lot.Rotation,
+			"zone":     lot.Zone.String(),
+			"cost":     lot.Cost,
+		}
+	}
+	return js.ValueOf(result)
+}
+
+// ============================================================================
+// Zoning Bindings
+// ============================================================================
+
+// paintZone zones every cell under a bounding box
+// JavaScript signature: paintZone({minX, minY, maxX, maxY}, zone: "none"|"residential"|"commercial"|"industrial") -> number
+// Returns the number of cells painted (0 for an unknown zone)
+func paintZone(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeObject || args[1].Type() != js.TypeString {
+		return js.ValueOf(0)
+	}
+	zone, ok := zoneFromString(args[1].String())
+	if !ok {
+		return js.ValueOf(0)
+	}
+	bbox := parseBoundingBox(args[0])
+	cells := 0
+	w.Edit(ZoneEdit(func(m *ZoneMap) bool {
+		cells = m.Paint(bbox, zone)
+		return cells > 0
+	}))
+	return js.ValueOf(cells)
+}
+
+// clearZones unzones the whole town
+// JavaScript signature: clearZones() -> undefined
+func clearZones(w *World, args []js.Value) interface{} {
+	w.Edit(ZoneEdit(func(m *ZoneMap) bool {
+		m.Clear()
+		return true
+	}))
+	return nil
+}
+
+// getZoneAt returns the zone at a world position
+// JavaScript signature: getZoneAt(x: number, z: number) -> string
+func getZoneAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(ZoneNone.String())
+	}
+	return js.ValueOf(w.Zones.At(args[0].Float(), args[1].Float()).String())
+}
+
+// setZoneRule sets the zones a model use may be built in
+// JavaScript signature: setZoneRule(use: string, zones: string[] | null) -> boolean
+// null removes the rule, letting the use be built anywhere
+func setZoneRule(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString {
+		return js.ValueOf(false)
+	}
+
+	use := args[0].String()
+	list := args[1]
+	if list.IsNull() || list.IsUndefined() {
+		delete(w.ZoneRules, use)
+		return js.ValueOf(true)
+	}
+
+	var mask ZoneMask
+	for i := 0; i < list.Length(); i++ {
+		zone, ok := zoneFromString(list.Index(i).String())
+		if !ok {
+			return js.ValueOf(false)
+		}
+		mask |= zoneMaskOf(zone)
+	}
+	w.ZoneRules[use] = mask
+	return js.ValueOf(true)
+}
+
+// getZoneCoverage returns how much of each zone is designated and built on
+// JavaScript signature: getZoneCoverage()
+//   -> {none|residential|commercial|industrial: {cells, area, built, fraction}}
+func getZoneCoverage(w *World, args []js.Value) interface{} {
+	result := make(map[string]interface{}, zoneTypeCount)
+	for z, c := range w.ZoneCoverage() {
+		result[ZoneType(z).String()] = map[string]interface{}{
+			"cells":    c.Cells,
+			"area":     c.Area,
+			"built":    c.Built,
+			"fraction": c.Fraction,
+		}
+	}
+	return js.ValueOf(result)
+}
+
+// getZoneMap returns the zone of every cell
+// JavaScript signature: getZoneMap(out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, zones: string[], cells: Float64Array}
+// cells holds width*depth indices into zones, row-major by z; out is reused
+// for cells when it is large enough
+func getZoneMap(w *World, args []js.Value) interface{} {
+	m := w.Zones
+	cells := make([]float64, m.GridSpec.Len())
+	for i := range cells {
+		cells[i] = float64(m.Cell(i))
+	}
+	names := make([]interface{}, zoneTypeCount)
+	for i, n := range zoneNames {
+		names[i] = n
+	}
+	return js.ValueOf(map[string]interface{}{
+		"width":    m.Width,
+		"depth":    m.Depth,
+		"cellSize": m.CellSize,
+		"originX":  m.OriginX,
+		"originZ":  m.OriginZ,
+		"zones":    names,
+		"cells":    reusableFloat64Array(args, cells),
+	})
+}
+
+// ============================================================================
+// District Bindings
+// ============================================================================
+
+// createDistrict adds a district whose policies change nothing
+// JavaScript signature: createDistrict(name: string) -> number (the district id)
+func createDistrict(w *World, args []js.Value) interface{} {
+	name := ""
+	if len(args) > 0 && args[0].Type() == js.TypeString {
+		name = args[0].String()
+	}
+	return js.ValueOf(w.Districts.Create(name).ID)
+}
+
+// removeDistrict deletes a district, taking its cells out of any district
+// JavaScript signature: removeDistrict(id: number) -> boolean
+func removeDistrict(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Districts.Remove(args[0].Int()))
+}
+
+// paintDistrict puts every cell under a bounding box in a district
+// JavaScript signature: paintDistrict({minX, minY, maxX, maxY}, id: number) -> number
+// id 0 takes the cells out of any district; returns the number of cells
+// painted (0 for an unknown district)
+func paintDistrict(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeObject || args[1].Type() != js.TypeNumber {
+		return js.ValueOf(0)
+	}
+	id := args[1].Int()
+	if id != NO_DISTRICT && w.Districts.District(id) == nil {
+		return js.ValueOf(0)
+	}
+	bbox := parseBoundingBox(args[0])
+	cells := 0
+	w.Edit(DistrictEdit(func(m *DistrictMap) bool {
+		cells = m.Paint(bbox, id)
+		return cells > 0
+	}))
+	return js.ValueOf(cells)
+}
+
+// setDistrict renames a district and changes its policies
+// JavaScript signature: setDistrict(id: number, {name?, speed_factor?, tax_rate?, industry_ban?}) -> boolean
+// speed_factor scales road speed limits (0.25-2), tax_rate the town's taxes
+// (0-3); industry_ban keeps out uses restricted to industrial zones
+func setDistrict(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	d := w.Districts.District(args[0].Int())
+	if d == nil {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	p := d.Policies
+	p.SpeedFactor = optionalFloat(v, "speed_factor", p.SpeedFactor)
+	p.TaxRate = optionalFloat(v, "tax_rate", p.TaxRate)
+	if ban := v.Get("industry_ban"); ban.Type() == js.TypeBoolean {
+		p.IndustryBan = ban.Bool()
+	}
+	if w.SetDistrictPolicies(d.ID, p) != nil {
+		return js.ValueOf(false)
+	}
+	if name := v.Get("name"); name.Type() == js.TypeString {
+		d.Name = name.String()
+	}
+	return js.ValueOf(true)
+}
+
+// getDistricts returns every district with its policies and stats
+// JavaScript signature: getDistricts()
+//   -> [{id, name, speed_factor, tax_rate, industry_ban, cells, area, buildings, residents, jobs, land_value, taxes}]
+// taxes is what the district paid at the end of the last day
+func getDistricts(w *World, args []js.Value) interface{} {
+	stats := w.DistrictStats()
+	result := make([]interface{}, len(stats))
+	for i, s := range stats {
+		p := w.Districts.District(s.ID).Policies
+		result[i] = map[string]interface{}{
+			"id":           s.ID,
+			"name":         s.Name,
+			"speed_factor": p.SpeedFactor,
+			"tax_rate":     p.TaxRate,
+			"industry_ban": p.IndustryBan,
+			"cells":        s.Cells,
+			"area":         s.Area,
+			"buildings":    s.Buildings,
+			"residents":    s.Residents,
+			"jobs":         s.Jobs,
+			"land_value":   s.LandValue,
+			"taxes":        s.Taxes,
+		}
+	}
+	return js.ValueOf(result)
+}
+
+// getDistrictAt returns the district at a world position
+// JavaScript signature: getDistrictAt(x: number, z: number) -> number (0 outside any district)
+func getDistrictAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(NO_DISTRICT)
+	}
+	if d := w.Districts.At(args[0].Float(), args[1].Float()); d != nil {
+		return js.ValueOf(d.ID)
+	}
+	return js.ValueOf(NO_DISTRICT)
+}
+
+// ============================================================================
+// Livability Bindings
+// ============================================================================
+
+// getLivability scores the town's service coverage
+// JavaScript signature: getLivability()
+//   -> {score, homes, noise, services: [{name, score, providers, radius, weight}]}
+// Scores are 0-100; homes is 0 when no housing is placed and the whole grid was sampled;
+// noise (0-1) is the mean at the sampled points and lowers the score
+func getLivability(w *World, args []js.Value) interface{} {
+	report := w.Livability()
+	services := make([]interface{}, len(report.Services))
+	for i, s := range report.Services {
+		services[i] = map[string]interface{}{
+			"name":      s.Name,
+			"score":     s.Score,
+			"providers": s.Providers,
+			"radius":    w.Services[i].Radius,
+			"weight":    w.Services[i].Weight,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"score":    report.Score,
+		"homes":    report.Homes,
+		"noise":    report.Noise,
+		"services": services,
+	})
+}
+
+// getServiceCoverage returns the per-cell coverage of one service
+// JavaScript signature: getServiceCoverage(name: string, out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, cells: Float64Array} | null
+// cells holds width*depth coverage values 0-1, row-major by z, on the occupancy layout
+func getServiceCoverage(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(nil)
+	}
+	s := w.Service(args[0].String())
+	if s == nil {
+		return js.ValueOf(nil)
+	}
+
+	cells, _ := w.serviceCoverage(s)
+	g := w.Occupancy.GridSpec
+	return js.ValueOf(map[string]interface{}{
+		"width":    g.Width,
+		"depth":    g.Depth,
+		"cellSize": g.CellSize,
+		"originX":  g.OriginX,
+		"originZ":  g.OriginZ,
+		"cells":    reusableFloat64Array(args[1:], cells),
+	})
+}
+
+// getLandValue returns the land value field for rendering as an overlay
+// JavaScript signature: getLandValue(out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, mean, cells: Float64Array}
+// cells holds width*depth values 0-1, row-major by z, on the occupancy layout
+func getLandValue(w *World, args []js.Value) interface{} {
+	value := w.LandValue()
+	return js.ValueOf(map[string]interface{}{
+		"width":    value.Width,
+		"depth":    value.Depth,
+		"cellSize": value.CellSize,
+		"originX":  value.OriginX,
+		"originZ":  value.OriginZ,
+		"mean":     value.Mean,
+		"cells":    reusableFloat64Array(args, value.Cells),
+	})
+}
+
+// getLandValueAt returns the land value (0-1) at a world position
+// JavaScript signature: getLandValueAt(x: number, z: number) -> number
+func getLandValueAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(LAND_VALUE_BASE)
+	}
+	value := w.LandValue()
+	return js.ValueOf(value.At(args[0].Float(), args[1].Float()))
+}
+
+// getNoiseMap returns the traffic and industry noise of every cell
+// JavaScript signature: getNoiseMap(out?: Float64Array)
+//   -> {width, depth, cellSize, originX, originZ, cells: Float64Array}
+// cells holds width*depth levels 0-1, row-major by z, on the occupancy layout
+func getNoiseMap(w *World, args []js.Value) interface{} {
+	m := w.Noise
+	return js.ValueOf(map[string]interface{}{
+		"width":    m.Width,
+		"depth":    m.Depth,
+		"cellSize": m.CellSize,
+		"originX":  m.OriginX,
+		"originZ":  m.OriginZ,
+		"cells":    reusableFloat64Array(args, w.NoiseLevels()),
+	})
+}
+
+// getNoiseAt returns the noise level (0-1) at a world position
+// JavaScript signature: getNoiseAt(x: number, z: number) -> number
+func getNoiseAt(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(0)
+	}
+	w.refreshIndustry()
+	return js.ValueOf(w.Noise.At(args[0].Float(), args[1].Float()))
+}
+
+// getGreenery returns the growth of every tree and park for rendering
+// JavaScript signature: getGreenery() -> {mature_days, plantings: Array<{id, model, growth, stage}>}
+// growth is 0 (just planted) to 1; stage is "sapling", "young" or "mature".
+// Young plantings count for part of their green space coverage
+func getGreenery(w *World, args []js.Value) interface{} {
+	plantings := w.Plantings()
+	result := make([]interface{}, len(plantings))
+	for i, p := range plantings {
+		result[i] = map[string]interface{}{
+			"id":     p.ID,
+			"model":  p.Model,
+			"growth": p.Growth,
+			"stage":  p.Stage.String(),
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"mature_days": w.Greenery.MatureDays,
+		"plantings":   result,
+	})
+}
+
+// setGreenery changes how fast plantings grow, or sets their growth
+// JavaScript signature: setGreenery({mature_days?, growth?, id?}) -> boolean
+// growth (0-1) applies to planting id, or to every planting without an id
+// (e.g. 1 to restore a saved town fully grown). Returns false for an unknown id
+func setGreenery(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	g := w.Greenery
+	g.MatureDays = positiveOr(optionalFloat(v, "mature_days", g.MatureDays), g.MatureDays)
+	growth := v.Get("growth")
+	if growth.Type() != js.TypeNumber {
+		return js.ValueOf(true)
+	}
+	if id := v.Get("id"); id.Type() == js.TypeNumber {
+		return js.ValueOf(w.SetGrowth(id.Int(), growth.Float()))
+	}
+	for _, p := range w.Plantings() {
+		w.SetGrowth(p.ID, growth.Float())
+	}
+	return js.ValueOf(true)
+}
+
+// getConstruction returns every building under construction
+// JavaScript signature: getConstruction()
+//   -> {instant, cost_per_hour, sites: Array<{id, model, progress, stage, hours_left}>}
+// progress is 0 (just placed) to 1; stage is "foundation" or "scaffolding";
+// hours_left is in game hours. Buildings not listed are complete
+func getConstruction(w *World, args []js.Value) interface{} {
+	sites := w.ConstructionSites()
+	result := make([]interface{}, len(sites))
+	for i, site := range sites {
+		result[i] = map[string]interface{}{
+			"id":         site.ID,
+			"model":      site.Model,
+			"progress":   site.Progress,
+			"stage":      site.Stage.String(),
+			"hours_left": site.HoursLeft,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"instant":       w.Sites.Instant,
+		"cost_per_hour": w.Sites.CostPerHour,
+		"sites":         result,
+	})
+}
+
+// setConstruction tunes construction, or finishes buildings now
+// JavaScript signature: setConstruction({instant?, cost_per_hour?, complete?: number | true}) -> boolean
+// instant (a sandbox) completes new buildings when placed and, once turned
+// on, every site; complete finishes one building by id, or every site.
+// Returns false for an id not under construction
+func setConstruction(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	c := w.Sites
+	c.CostPerHour = positiveOr(optionalFloat(v, "cost_per_hour", c.CostPerHour), c.CostPerHour)
+	if instant := v.Get("instant"); instant.Type() == js.TypeBoolean {
+		w.SetInstantConstruction(instant.Bool())
+	}
+	switch complete := v.Get("complete"); complete.Type() {
+	case js.TypeNumber:
+		return js.ValueOf(w.CompleteConstruction(complete.Int()))
+	case js.TypeBoolean:
+		if complete.Bool() {
+			for _, site := range w.ConstructionSites() {
+				w.CompleteConstruction(site.ID)
+			}
+		}
+	}
+	return js.ValueOf(true)
+}
+
+// getBuildingLevels returns the level of every home and workplace checked so far
+// JavaScript signature: getBuildingLevels()
+//   -> {enabled, max_level, upgrade_days, downgrade_days, buildings: Array<{id, model, level, score, streak}>}
+// score (0-1) is the last daily check; streak counts the days running above
+// the upgrade score (positive) or below the downgrade score (negative).
+// Buildings not listed are level 1
+func getBuildingLevels(w *World, args []js.Value) interface{} {
+	l := w.Levels
+	var buildings []interface{}
+	for _, id := range w.Occupancy.IDs() {
+		b, ok := l.Buildings[id]
+		if !ok {
+			continue
+		}
+		buildings = append(buildings, map[string]interface{}{
+			"id":     id,
+			"model":  w.Occupancy.Placement(id).Model,
+			"level":  b.Level,
+			"score":  b.Score,
+			"streak": b.Streak,
+		})
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":        l.Enabled,
+		"max_level":      BUILDING_MAX_LEVEL,
+		"upgrade_days":   l.UpgradeDays,
+		"downgrade_days": l.DowngradeDays,
+		"buildings":      buildings,
+	})
+}
+
+// setBuildingLevels turns building leveling on or off and tunes it, or sets a level
+// JavaScript signature: setBuildingLevels({enabled?, upgrade_days?, downgrade_days?, id?, level?}) -> boolean
+// level (1 to max_level) applies to building id, e.g. to restore a saved
+// town. Returns false for an id that is not a home or workplace
+func setBuildingLevels(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	l := w.Levels
+	if e := v.Get("enabled"); e.Type() == js.TypeBoolean {
+		l.Enabled = e.Bool()
+	}
+	if days := optionalFloat(v, "upgrade_days", float64(l.UpgradeDays)); days >= 1 && isFinite(days) {
+		l.UpgradeDays = int(days)
+	}
+	if days := optionalFloat(v, "downgrade_days", float64(l.DowngradeDays)); days >= 1 && isFinite(days) {
+		l.DowngradeDays = int(days)
+	}
+	id, level := v.Get("id"), v.Get("level")
+	if id.Type() != js.TypeNumber || level.Type() != js.TypeNumber {
+		return js.ValueOf(true)
+	}
+	return js.ValueOf(w.SetBuildingLevel(id.Int(), level.Int()))
+}
+
+// getCoverageGrids returns every service's coverage grid for overlays
+// JavaScript signature: getCoverageGrids()
+//   -> {width, depth, cellSize, originX, originZ, services: {[name]: Float64Array}, underserved: Float64Array}
+// Each grid holds width*depth values 0-1, row-major by z, on the occupancy
+// layout; underserved is 1 where no service but employment reaches a cell
+func getCoverageGrids(w *World, args []js.Value) interface{} {
+	coverage, underserved := w.CoverageGrids()
+	services := make(map[string]interface{}, len(coverage))
+	for i, cells := range coverage {
+		services[w.Services[i].Name] = reusableFloat64Array(nil, cells)
+	}
+
+	g := w.Occupancy.GridSpec
+	return js.ValueOf(map[string]interface{}{
+		"width":       g.Width,
+		"depth":       g.Depth,
+		"cellSize":    g.CellSize,
+		"originX":     g.OriginX,
+		"originZ":     g.OriginZ,
+		"services":    services,
+		"underserved": reusableFloat64Array(nil, underserved),
+	})
+}
+
+// setService tunes a livability service
+// JavaScript signature: setService(name: string, {radius?, weight?}) -> boolean
+func setService(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	s := w.Service(args[0].String())
+	if s == nil {
+		return js.ValueOf(false)
+	}
+
+	s.Radius = positiveOr(optionalFloat(args[1], "radius", s.Radius), s.Radius)
+	if weight := optionalFloat(args[1], "weight", s.Weight); weight >= 0 && isFinite(weight) {
+		s.Weight = weight
+	}
+	return js.ValueOf(true)
+}
+
+// ============================================================================
+// Clock Bindings
+// ============================================================================
+
+// getClock returns the game time and the sun for lighting
+// JavaScript signature: getClock()
+//   -> {day, hour, day_progress, seconds_per_hour, daylight, sun: {x, y, z}, stores_open, store_open, store_close, season}
+// hour is 0-24; daylight is 0 (night) to 1; sun is the unit direction toward the sun
+func getClock(w *World, args []js.Value) interface{} {
+	c := w.Clock
+	sx, sy, sz := c.SunDirection()
+	return js.ValueOf(map[string]interface{}{
+		"day":              c.Day(),
+		"hour":             c.HourOfDay(),
+		"day_progress":     c.DayProgress(),
+		"seconds_per_hour": c.SecondsPerHour,
+		"daylight":         c.Daylight(),
+		"sun":              map[string]interface{}{"x": sx, "y": sy, "z": sz},
+		"stores_open":      c.StoresOpen(),
+		"store_open":       c.StoreOpen,
+		"store_close":      c.StoreClose,
+		"season":           c.Season().String(),
+	})
+}
+
+// setClock changes the clock rate, the time of day, store hours or season length
+// JavaScript signature: setClock({seconds_per_hour?, hour?, store_open?, store_close?, days_per_season?}) -> boolean
+// Jumping the hour does not run end-of-day updates
+func setClock(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	c := w.Clock
+	c.SecondsPerHour = positiveOr(optionalFloat(v, "seconds_per_hour", c.SecondsPerHour), c.SecondsPerHour)
+	if hour := v.Get("hour"); hour.Type() == js.TypeNumber && isFinite(hour.Float()) {
+		c.SetTime(hour.Float())
+	}
+	c.StoreOpen = clamp(optionalFloat(v, "store_open", c.StoreOpen), 0, HOURS_PER_DAY)
+	c.StoreClose = clamp(optionalFloat(v, "store_close", c.StoreClose), 0, HOURS_PER_DAY)
+	if days := optionalFloat(v, "days_per_season", 0); days >= 1 {
+		c.DaysPerSeason = int(days)
+	}
+	return js.ValueOf(true)
+}
+
+// getSeason returns the season and how it changes the simulation
+// JavaScript signature: getSeason()
+//   -> {season, progress, days_per_season, road_grip, green_space, weather_odds: {clear, rain, snow}}
+// season is "spring", "summer", "autumn" or "winter"; progress is 0-1 through it.
+// road_grip scales grip on roads and green_space the weight of parks in livability
+func getSeason(w *World, args []js.Value) interface{} {
+	c := w.Clock
+	season := c.Season()
+	effects := season.Effects()
+	odds := make(map[string]interface{}, weatherTypeCount)
+	for t, chance := range effects.WeatherOdds {
+		odds[WeatherType(t).String()] = chance
+	}
+	return js.ValueOf(map[string]interface{}{
+		"season":          season.String(),
+		"progress":        c.SeasonProgress(),
+		"days_per_season": c.seasonDays(),
+		"road_grip":       effects.RoadGrip,
+		"green_space":     effects.GreenSpace,
+		"weather_odds":    odds,
+	})
+}
+
+// ============================================================================
+// Weather Bindings
+// ============================================================================
+
+// getWeather returns the current weather and what it does to driving, so
+// particle effects can follow the physics
+// JavaScript signature: getWeather()
+//   -> {type, intensity, target, target_intensity, auto, grip_scale, max_speed_scale, brake_scale}
+// type is "clear", "rain" or "snow"; intensity is 0-1 and fades during transitions
+func getWeather(w *World, args []js.Value) interface{} {
+	wx := w.Weather
+	return js.ValueOf(map[string]interface{}{
+		"type":             wx.Type.String(),
+		"intensity":        wx.Intensity,
+		"target":           wx.Target.String(),
+		"target_intensity": wx.TargetIntensity,
+		"auto":             wx.Auto,
+		"grip_scale":       wx.GripScale(),
+		"max_speed_scale":  wx.MaxSpeedScale(),
+		"brake_scale":      wx.BrakeScale(),
+	})
+}
+
+// setWeather starts a weather transition or turns automatic weather on or off
+// JavaScript signature: setWeather({type?, intensity?, immediate?, auto?}) -> boolean
+// intensity defaults to 1 (0 for clear); immediate skips the transition.
+// Returns false for an unknown type
+func setWeather(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	wx := w.Weather
+	if name := v.Get("type"); name.Type() == js.TypeString {
+		t, ok := weatherFromString(name.String())
+		if !ok {
+			return js.ValueOf(false)
+		}
+		immediate := v.Get("immediate").Type() == js.TypeBoolean && v.Get("immediate").Bool()
+		wx.Set(t, optionalFloat(v, "intensity", 1), immediate)
+	}
+	if auto := v.Get("auto"); auto.Type() == js.TypeBoolean {
+		wx.Auto = auto.Bool()
+	}
+	return js.ValueOf(true)
+}
+
+// ============================================================================
+// Population Bindings
+// ============================================================================
+
+// getPopulation returns the town census, recounted from what is placed now
+// JavaScript signature: getPopulation()
+//   -> {day, day_progress, residents, housing, jobs, workers, employed, satisfaction,
+//       demand: {residential, commercial, industrial}}
+// Demand runs from -1 (oversupplied) to 1; satisfaction (0-1) and growth
+// update at the end of each game day (see getClock)
+func getPopulation(w *World, args []js.Value) interface{} {
+	w.Census()
+	pop := w.Population
+	return js.ValueOf(map[string]interface{}{
+		"day":          w.Clock.Day(),
+		"day_progress": w.Clock.DayProgress(),
+		"residents":    pop.Residents,
+		"housing":      pop.Housing,
+		"jobs":         pop.Jobs,
+		"workers":      pop.Workers,
+		"employed":     pop.Employed,
+		"satisfaction": pop.Satisfaction,
+		"demand": map[string]interface{}{
+			"residential": pop.Demand[ZoneResidential],
+			"commercial":  pop.Demand[ZoneCommercial],
+			"industrial":  pop.Demand[ZoneIndustrial],
+		},
+	})
+}
+
+// setUseCapacity sets the residents and jobs a model use holds
+// JavaScript signature: setUseCapacity(use: string, {residents?, jobs?, zone?}) -> boolean
+// zone ("residential", "commercial", "industrial") is where its jobs count towards demand
+func setUseCapacity(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	c := w.Capacities[args[0].String()]
+	residents := optionalFloat(v, "residents", float64(c.Residents))
+	jobs := optionalFloat(v, "jobs", float64(c.Jobs))
+	if !(residents >= 0 && jobs >= 0) || !isFinite(residents) || !isFinite(jobs) {
+		return js.ValueOf(false)
+	}
+	c.Residents, c.Jobs = int(residents), int(jobs)
+	if z := v.Get("zone"); z.Type() == js.TypeString {
+		zone, ok := zoneFromString(z.String())
+		if !ok {
+			return js.ValueOf(false)
+		}
+		c.Zone = zone
+	}
+	w.Capacities[args[0].String()] = c
+	w.Utilities.invalidate()
+	return js.ValueOf(true)
+}
+
+// setTripDemand turns trip generation on or off and tunes it
+// JavaScript signature: setTripDemand({enabled?, trips_per_resident?, max_cars?, vehicle?}) -> boolean
+// vehicle is a preset name or vehicle config used for every generated car
+func setTripDemand(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	d := w.Trips
+	if e := v.Get("enabled"); e.Type() == js.TypeBoolean {
+		d.Enabled = e.Bool()
+	}
+	if rate := optionalFloat(v, "trips_per_resident", d.TripsPerResident); rate >= 0 && isFinite(rate) {
+		d.TripsPerResident = rate
+	}
+	if cars := optionalFloat(v, "max_cars", float64(d.MaxCars)); cars >= 0 && isFinite(cars) {
+		d.MaxCars = int(cars)
+	}
+	if vehicle := v.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+		d.Vehicle = parseVehicleConfig(vehicle)
+	}
+	return js.ValueOf(true)
+}
+
+// getTripDemand returns the trips being driven and the generator's counters
+// JavaScript signature: getTripDemand()
+//   -> {enabled, generated, completed, failed, suppressed,
+//       trips: [{car_id, purpose: "commute"|"shopping"|"home", origin, destination}]}
+// origin/destination are placement ids
+func getTripDemand(w *World, args []js.Value) interface{} {
+	d := w.Trips
+	trips := make([]interface{}, len(d.Active()))
+	for i, t := range d.Active() {
+		trips[i] = map[string]interface{}{
+			"car_id":      t.CarID,
+			"purpose":     t.Purpose.String(),
+			"origin":      t.Origin,
+			"destination": t.Destination,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":    d.Enabled,
+		"generated":  d.Generated,
+		"completed":  d.Completed,
+		"failed":     d.Failed,
+		"suppressed": d.Suppressed,
+		"trips":      trips,
+	})
+}
+
+// clearTrips removes every generated car and resets the trip counters
+// JavaScript signature: clearTrips() -> undefined
+func clearTrips(w *World, args []js.Value) interface{} {
+	w.ClearTrips()
+	return nil
+}
+
+// ============================================================================
+// Citizen Bindings
+// ============================================================================
+
+// setCitizens turns citizen schedules on or off and tunes them
+// JavaScript signature: setCitizens({enabled?, max_citizens?, max_cars?, walk_distance?, vehicle?}) -> boolean
+// vehicle is a preset name or vehicle config used for every citizen's car
+func setCitizens(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	c := w.Citizens
+	if e := v.Get("enabled"); e.Type() == js.TypeBoolean {
+		c.Enabled = e.Bool()
+	}
+	if n := optionalFloat(v, "max_citizens", float64(c.MaxCitizens)); n >= 0 && isFinite(n) {
+		c.MaxCitizens = int(n)
+	}
+	if cars := optionalFloat(v, "max_cars", float64(c.MaxCars)); cars >= 0 && isFinite(cars) {
+		c.MaxCars = int(cars)
+	}
+	if d := optionalFloat(v, "walk_distance", c.WalkDistance); d >= 0 && isFinite(d) {
+		c.WalkDistance = d
+	}
+	if vehicle := v.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+		c.Vehicle = parseVehicleConfig(vehicle)
+	}
+	return js.ValueOf(true)
+}
+
+// getCitizens returns every citizen with their schedule, and the counters
+// JavaScript signature: getCitizens()
+//   -> {enabled, trips, driven, walked, completed, driving, walking,
+//       at: {home, work, shopping, leisure},
+//       citizens: [{id, home, job, activity, place, mode: "none"|"walking"|"driving", agent,
+//                   plan: [{hour, activity, place}]}]}
+// home, job and place are placement ids (job -1 without one); activity is
+// what they do at place, which they are still travelling to unless mode is
+// "none"; agent is their car or pedestrian id while travelling
+func getCitizens(w *World, args []js.Value) interface{} {
+	c := w.Citizens
+	citizens := make([]interface{}, len(c.List()))
+	walking := 0
+	for i, ct := range c.List() {
+		plan := make([]interface{}, len(ct.Plan))
+		for j, e := range ct.Plan {
+			plan[j] = map[string]interface{}{"hour": e.Hour, "activity": e.Activity.String(), "place": e.Place}
+		}
+		if ct.Mode == TravelWalking {
+			walking++
+		}
+		citizens[i] = map[string]interface{}{
+			"id":       ct.ID,
+			"home":     ct.Home,
+			"job":      ct.Job,
+			"activity": ct.Activity.String(),
+			"place":    ct.Place,
+			"mode":     ct.Mode.String(),
+			"agent":    ct.Agent,
+			"plan":     plan,
+		}
+	}
+	counts := c.Counts()
+	at := make(map[string]interface{}, len(counts))
+	for a, n := range counts {
+		at[CitizenActivity(a).String()] = n
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":   c.Enabled,
+		"trips":     c.Trips,
+		"driven":    c.Driven,
+		"walked":    c.Walked,
+		"completed": c.Completed,
+		"driving":   c.Driving(),
+		"walking":   walking,
+		"at":        at,
+		"citizens":  citizens,
+	})
+}
+
+// clearCitizens removes every citizen and their cars and pedestrians and
+// resets the counters
+// JavaScript signature: clearCitizens() -> undefined
+func clearCitizens(w *World, args []js.Value) interface{} {
+	w.ClearCitizens()
+	return nil
+}
+
+// happinessToJS converts a happiness breakdown to its JavaScript fields
+func happinessToJS(b HappinessBreakdown, fields map[string]interface{}) map[string]interface{} {
+	factors := make(map[string]interface{}, len(b.Factors))
+	for f, v := range b.Factors {
+		factors[HappinessFactor(f).String()] = v
+	}
+	fields["happiness"] = b.Happiness
+	fields["worst"] = b.Worst().String()
+	fields["factors"] = factors
+	return fields
+}
+
+// getHappiness scores every citizen's happiness and breaks it down by
+// neighborhood and over the town
+// JavaScript signature: getHappiness()
+//   -> {town: Breakdown, columns, neighborhoods: [{index, min_x, min_z, max_x, max_z, citizens, ...Breakdown}],
+//       citizens: [{id, home, neighborhood, ...Breakdown}]}
+// Breakdown is {happiness: 0-100, worst, factors: {commute, services, noise, housing}} with factors
+// 0-1 and worst the lowest of them; neighborhoods are indexed row-major, columns
+// wide, and only those with citizens are listed (a citizen's is -1 off the grid)
+func getHappiness(w *World, args []js.Value) interface{} {
+	report := w.Happiness()
+	neighborhoods := make([]interface{}, len(report.Neighborhoods))
+	for i, n := range report.Neighborhoods {
+		neighborhoods[i] = happinessToJS(n.HappinessBreakdown, map[string]interface{}{
+			"index":    n.Index,
+			"min_x":    n.Bounds.MinX,
+			"min_z":    n.Bounds.MinY,
+			"max_x":    n.Bounds.MaxX,
+			"max_z":    n.Bounds.MaxY,
+			"citizens": n.Citizens,
+		})
+	}
+	citizens := make([]interface{}, len(report.Citizens))
+	for i, c := range report.Citizens {
+		citizens[i] = happinessToJS(c.HappinessBreakdown, map[string]interface{}{
+			"id":           c.CitizenID,
+			"home":         c.Home,
+			"neighborhood": c.Neighborhood,
+		})
+	}
+	return js.ValueOf(map[string]interface{}{
+		"town":          happinessToJS(report.Town, map[string]interface{}{}),
+		"columns":       report.NeighborhoodColumns,
+		"neighborhoods": neighborhoods,
+		"citizens":      citizens,
+	})
+}
+
+// ============================================================================
+// Fire Bindings
+// ============================================================================
+
+// setFires turns random fires on or off and tunes how they spread and burn
+// JavaScript signature: setFires({enabled?, ignition_per_day?, spread_per_hour?, burn_hours?, station_uses?, vehicle?}) -> boolean
+// Fires started with igniteBuilding spread and are fought even while disabled
+func setFires(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	s := w.Fires
+	if e := v.Get("enabled"); e.Type() == js.TypeBoolean {
+		s.Enabled = e.Bool()
+	}
+	if rate := optionalFloat(v, "ignition_per_day", s.IgnitionPerDay); rate >= 0 && isFinite(rate) {
+		s.IgnitionPerDay = rate
+	}
+	if rate := optionalFloat(v, "spread_per_hour", s.SpreadPerHour); rate >= 0 && isFinite(rate) {
+		s.SpreadPerHour = rate
+	}
+	s.BurnHours = positiveOr(optionalFloat(v, "burn_hours", s.BurnHours), s.BurnHours)
+	if uses := v.Get("station_uses"); uses.Type() == js.TypeObject {
+		s.StationUses = s.StationUses[:0]
+		for i := 0; i < uses.Length(); i++ {
+			s.StationUses = append(s.StationUses, uses.Index(i).String())
+		}
+	}
+	if vehicle := v.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+		s.Vehicle = parseVehicleConfig(vehicle)
+	}
+	return js.ValueOf(true)
+}
+
+// getFires returns the burning buildings and the buildings burned down since
+// the last call, which JS should remove from the scene
+// JavaScript signature: getFires()
+//   -> {enabled, ignited, extinguished, destroyed_count, destroyed: number[],
+//       fires: [{id, model, burned, progress, truck_id, truck: "none"|"en_route"|"on_scene"}]}
+// id and destroyed are placement ids; burned is game hours on fire and
+// progress (0-1) how close the building is to burning down
+func getFires(w *World, args []js.Value) interface{} {
+	s := w.Fires
+	fires := make([]interface{}, len(s.Fires()))
+	for i, f := range s.Fires() {
+		fires[i] = map[string]interface{}{
+			"id":       f.PlacementID,
+			"model":    w.Occupancy.Placement(f.PlacementID).Model,
+			"burned":   f.Burned,
+			"progress": math.Min(f.Burned/positiveOr(s.BurnHours, FIRE_BURN_HOURS), 1),
+			"truck_id": f.TruckID,
+			"truck":    f.truckState(),
+		}
+	}
+	destroyed := make([]interface{}, 0, len(s.destroyed))
+	for _, id := range s.takeDestroyed() {
+		destroyed = append(destroyed, id)
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":         s.Enabled,
+		"ignited":         s.Ignited,
+		"extinguished":    s.Extinguished,
+		"destroyed_count": s.Destroyed,
+		"destroyed":       destroyed,
+		"fires":           fires,
+	})
+}
+
+// igniteBuilding sets a placed building on fire
+// JavaScript signature: igniteBuilding(id: number) -> boolean
+// Returns false if the placement is not a building or is already burning
+func igniteBuilding(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Ignite(args[0].Int()))
+}
+
+// clearFires puts out every fire, recalls the trucks and resets the fire counters
+// JavaScript signature: clearFires() -> undefined
+func clearFires(w *World, args []js.Value) interface{} {
+	w.ClearFires()
+	return nil
+}
+
+// ============================================================================
+// Transit Bindings
+// ============================================================================
+
+// setTransit tunes bus ridership and the buses
+// JavaScript signature: setTransit({rides_per_resident?, capacity?, vehicle?}) -> boolean
+// vehicle is a preset name or vehicle config used for buses put on the road
+// from now on
+func setTransit(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	t := w.Transit
+	if rate := optionalFloat(v, "rides_per_resident", t.RidesPerResident); rate >= 0 && isFinite(rate) {
+		t.RidesPerResident = rate
+	}
+	if capacity := optionalFloat(v, "capacity", float64(t.Capacity)); capacity >= 0 && isFinite(capacity) {
+		t.Capacity = int(capacity)
+	}
+	if vehicle := v.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+		t.Vehicle = parseVehicleConfig(vehicle)
+	}
+	return js.ValueOf(true)
+}
+
+// setBusRoute adds or replaces a bus route through placed bus stops
+// JavaScript signature: setBusRoute(id: number, {name?, stops: number[], buses?}) -> boolean
+// stops are bus stop placement ids, served in order as a loop; buses
+// (default 1) are kept on the route. Returns false (logging why) if a stop
+// is not a bus stop or there are fewer than two
+func setBusRoute(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	var stops []int
+	if list := v.Get("stops"); list.Type() == js.TypeObject {
+		for i := 0; i < list.Length(); i++ {
+			stops = append(stops, list.Index(i).Int())
+		}
+	}
+	name := ""
+	if n := v.Get("name"); n.Type() == js.TypeString {
+		name = n.String()
+	}
+	buses := optionalFloat(v, "buses", 1)
+	if !isFinite(buses) {
+		return js.ValueOf(false)
+	}
+	if err := w.SetBusRoute(args[0].Int(), name, stops, int(buses)); err != nil {
+		println("setBusRoute:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// removeBusRoute takes a route's buses off the road
+// JavaScript signature: removeBusRoute(id: number) -> boolean
+func removeBusRoute(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemoveBusRoute(args[0].Int()))
+}
+
+// getTransit returns the bus routes, their buses and ridership
+// JavaScript signature: getTransit()
+//   -> {rides_per_resident, capacity, generated, boardings, alightings, completed, suppressed,
+//       routes: [{id, name, stops, bus_count, boardings, alightings, waiting, riding,
+//                 buses: [{car_id, stop, at_stop, riders}]}]}
+// stops are placement ids; a bus's stop is the one it is driving to or
+// serving, riders the number aboard. generated counts riders who set off
+// for a stop, completed those who reached their destination and suppressed
+// those who stayed home because their stop was full
+func getTransit(w *World, args []js.Value) interface{} {
+	t := w.Transit
+	routes := make([]interface{}, len(t.Routes()))
+	for i, r := range t.Routes() {
+		stops := make([]interface{}, len(r.Stops))
+		for j, id := range r.Stops {
+			stops[j] = id
+		}
+		buses := make([]interface{}, len(r.BusesOn()))
+		for j, bus := range r.BusesOn() {
+			buses[j] = map[string]interface{}{
+				"car_id":  bus.CarID,
+				"stop":    r.Stops[bus.Stop],
+				"at_stop": bus.AtStop,
+				"riders":  len(bus.Riders),
+			}
+		}
+		routes[i] = map[string]interface{}{
+			"id":         r.ID,
+			"name":       r.Name,
+			"stops":      stops,
+			"bus_count":  r.Buses,
+			"boardings":  r.Boardings,
+			"alightings": r.Alightings,
+			"waiting":    t.Waiting(r.ID, 0),
+			"riding":     r.Riding(),
+			"buses":      buses,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"rides_per_resident": t.RidesPerResident,
+		"capacity":           t.Capacity,
+		"generated":          t.Generated,
+		"boardings":          t.Boardings,
+		"alightings":         t.Alightings,
+		"completed":          t.Completed,
+		"suppressed":         t.Suppressed,
+		"routes":             routes,
+	})
+}
+
+// clearTransit removes every bus route, bus and rider and resets the counters
+// JavaScript signature: clearTransit() -> undefined
+func clearTransit(w *World, args []js.Value) interface{} {
+	w.ClearTransit()
+	return nil
+}
+
+// ============================================================================
+// Tram Bindings
+// ============================================================================
+
+// setTrams tunes tram ridership and the trams
+// JavaScript signature: setTrams({rides_per_resident?, capacity?, speed?}) -> boolean
+func setTrams(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	t := w.Trams
+	if rate := optionalFloat(v, "rides_per_resident", t.RidesPerResident); rate >= 0 && isFinite(rate) {
+		t.RidesPerResident = rate
+	}
+	if capacity := optionalFloat(v, "capacity", float64(t.Capacity)); capacity >= 0 && isFinite(capacity) {
+		t.Capacity = int(capacity)
+	}
+	if speed := optionalFloat(v, "speed", t.Speed); speed > 0 && isFinite(speed) {
+		t.Speed = speed
+	}
+	return js.ValueOf(true)
+}
+
+// setTramLine adds or replaces a tram line
+// JavaScript signature: setTramLine(id: number, {name?, track?: Array<{x, z}>, nodes?: number[],
+//   loop?, headway?, trams?}) -> boolean
+// The track runs through the given points, or along the roads through nodes
+// (road node ids, each joined to the next by a road); loop closes it back to
+// its start. A tram leaves the start every headway seconds (default 60)
+// while fewer than trams (default 1) run. Returns false (logging why) if the
+// track has fewer than two points or a node is not on a road to the next
+func setTramLine(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	var track []TrackPoint
+	if list := v.Get("nodes"); list.Type() == js.TypeObject {
+		nodes := make([]int, list.Length())
+		for i := range nodes {
+			nodes[i] = list.Index(i).Int()
+		}
+		var err error
+		if track, err = w.TrackAlongRoads(nodes); err != nil {
+			println("setTramLine:", err.Error())
+			return js.ValueOf(false)
+		}
+	} else if list := v.Get("track"); list.Type() == js.TypeObject {
+		for i := 0; i < list.Length(); i++ {
+			p := list.Index(i)
+			track = append(track, TrackPoint{X: optionalFloat(p, "x", math.NaN()), Z: optionalFloat(p, "z", math.NaN())})
+		}
+	}
+	name := ""
+	if n := v.Get("name"); n.Type() == js.TypeString {
+		name = n.String()
+	}
+	loop := false
+	if l := v.Get("loop"); l.Type() == js.TypeBoolean {
+		loop = l.Bool()
+	}
+	headway := optionalFloat(v, "headway", TRAM_HEADWAY)
+	trams := optionalFloat(v, "trams", 1)
+	if !isFinite(headway) || !isFinite(trams) {
+		return js.ValueOf(false)
+	}
+	if err := w.SetTramLine(args[0].Int(), name, track, loop, headway, int(trams)); err != nil {
+		println("setTramLine:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// removeTramLine takes a line's trams off the rails
+// JavaScript signature: removeTramLine(id: number) -> boolean
+func removeTramLine(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemoveTramLine(args[0].Int()))
+}
+
+// getTrams returns the tram lines, their trams, crossings and ridership
+// JavaScript signature: getTrams()
+//   -> {rides_per_resident, capacity, speed, generated, boardings, alightings, suppressed,
+//       lines: [{id, name, loop, headway, max_trams, length, stations, boardings, alightings, waiting,
+//                crossings: [{edge_id, along, closed}],
+//                trams: [{id, x, z, heading, along, speed, at_station, passengers}]}]}
+// stations are tram station placement ids in order along the track; along
+// is the distance along the track; at_station is 0 while moving
+func getTrams(w *World, args []js.Value) interface{} {
+	t := w.Trams
+	lines := make([]interface{}, len(t.Lines()))
+	for i, l := range t.Lines() {
+		stations := make([]interface{}, 0, len(l.Stations()))
+		waiting := 0
+		for _, id := range l.Stations() {
+			stations = append(stations, id)
+			waiting += l.Waiting[id]
+		}
+		crossings := make([]interface{}, len(l.Crossings()))
+		for j, c := range l.Crossings() {
+			crossings[j] = map[string]interface{}{"edge_id": c.EdgeID, "along": c.TrackAlong, "closed": c.Closed}
+		}
+		trams := make([]interface{}, len(l.TramsOn()))
+		for j, tram := range l.TramsOn() {
+			trams[j] = map[string]interface{}{
+				"id":         tram.ID,
+				"x":          tram.X,
+				"z":          tram.Z,
+				"heading":    tram.Heading,
+				"along":      tram.Along,
+				"speed":      tram.Speed,
+				"at_station": tram.AtStation,
+				"passengers": tram.Passengers(),
+			}
+		}
+		lines[i] = map[string]interface{}{
+			"id":         l.ID,
+			"name":       l.Name,
+			"loop":       l.Loop,
+			"headway":    l.Headway,
+			"max_trams":  l.MaxTrams,
+			"length":     l.Length(),
+			"stations":   stations,
+			"boardings":  l.Boardings,
+			"alightings": l.Alightings,
+			"waiting":    waiting,
+			"crossings":  crossings,
+			"trams":      trams,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"rides_per_resident": t.RidesPerResident,
+		"capacity":           t.Capacity,
+		"speed":              t.Speed,
+		"generated":          t.Generated,
+		"boardings":          t.Boardings,
+		"alightings":         t.Alightings,
+		"suppressed":         t.Suppressed,
+		"lines":              lines,
+	})
+}
+
+// clearTrams removes every tram line and tram and resets the counters
+// JavaScript signature: clearTrams() -> undefined
+func clearTrams(w *World, args []js.Value) interface{} {
+	w.ClearTrams()
+	return nil
+}
+
+// ============================================================================
+// Railway Bindings
+// ============================================================================
+
+// setRailway lays and tunes the freight line
+// JavaScript signature: setRailway({track?: Array<{x, z}>, enabled?, interval?, speed?, wagons?}) -> boolean
+// A new track takes any running train off the line. While enabled, a train
+// of a locomotive and wagons wagons enters at the start every interval
+// seconds. Returns false (logging why) if the track has fewer than two
+// points or no length
+func setRailway(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	if list := v.Get("track"); list.Type() == js.TypeObject {
+		track := make([]TrackPoint, list.Length())
+		for i := range track {
+			p := list.Index(i)
+			track[i] = TrackPoint{X: optionalFloat(p, "x", math.NaN()), Z: optionalFloat(p, "z", math.NaN())}
+		}
+		if err := w.SetRailTrack(track); err != nil {
+			println("setRailway:", err.Error())
+			return js.ValueOf(false)
+		}
+	}
+	r := w.Railway
+	if b := v.Get("enabled"); b.Type() == js.TypeBoolean {
+		r.Enabled = b.Bool()
+	}
+	if interval := optionalFloat(v, "interval", r.Interval); interval > 0 && isFinite(interval) {
+		r.Interval = interval
+		r.NextTrain = math.Min(r.NextTrain, interval)
+	}
+	if speed := optionalFloat(v, "speed", r.Speed); speed > 0 && isFinite(speed) {
+		r.Speed = speed
+	}
+	if wagons := optionalFloat(v, "wagons", float64(r.Wagons)); wagons >= 0 && isFinite(wagons) {
+		r.Wagons = int(math.Min(wagons, TRAIN_MAX_WAGONS))
+	}
+	return js.ValueOf(true)
+}
+
+// sendTrain starts a train at the start of the line now
+// JavaScript signature: sendTrain() -> boolean
+// Returns false if there is no track or a train is already running
+func sendTrain(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.SendTrain())
+}
+
+// getRailway returns the freight line, its train and its crossings
+// JavaScript signature: getRailway()
+//   -> {enabled, running, along, speed, wagons, interval, next_train, trains, length,
+//       crossings: [{edge_id, along, gate, lowered}]}
+// along is the locomotive's distance along the track; gate is "open",
+// "lowering", "closed" or "rising" and lowered goes from 0 up to 1 down
+func getRailway(w *World, args []js.Value) interface{} {
+	r := w.Railway
+	crossings := make([]interface{}, len(r.Crossings))
+	for i, c := range r.Crossings {
+		crossings[i] = map[string]interface{}{
+			"edge_id": c.EdgeID,
+			"along":   c.TrackAlong,
+			"gate":    c.Gate.String(),
+			"lowered": c.Lowered,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":    r.Enabled,
+		"running":    r.Running,
+		"along":      r.Along,
+		"speed":      r.Speed,
+		"wagons":     r.Wagons,
+		"interval":   r.Interval,
+		"next_train": r.NextTrain,
+		"trains":     r.Trains,
+		"length":     r.Length(),
+		"crossings":  crossings,
+	})
+}
+
+// ============================================================================
+// Emergency Bindings
+// ============================================================================
+
+// setEmergency tunes sirens and incident response
+// JavaScript signature: setEmergency({speed_factor?, siren_range?, scene_hours?,
+//   medical_uses?: string[], police_uses?: string[], vehicle?}) -> boolean
+// medical_uses and police_uses are the model uses responders of each kind
+// are sent from; vehicle is used for responders sent from now on
+func setEmergency(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	s := w.Emergency
+	s.SpeedFactor = positiveOr(optionalFloat(v, "speed_factor", s.SpeedFactor), s.SpeedFactor)
+	if r := optionalFloat(v, "siren_range", s.SirenRange); r >= 0 && isFinite(r) {
+		s.SirenRange = r
+	}
+	s.SceneHours = positiveOr(optionalFloat(v, "scene_hours", s.SceneHours), s.SceneHours)
+	for kind, key := range [emergencyKindCount]string{"medical_uses", "police_uses"} {
+		if uses := v.Get(key); uses.Type() == js.TypeObject {
+			list := make([]string, 0, uses.Length())
+			for i := 0; i < uses.Length(); i++ {
+				list = append(list, uses.Index(i).String())
+			}
+			s.StationUses[kind] = list
+		}
+	}
+	if vehicle := v.Get("vehicle"); !vehicle.IsUndefined() && !vehicle.IsNull() {
+		s.Vehicle = parseVehicleConfig(vehicle)
+	}
+	return js.ValueOf(true)
+}
+
+// reportIncident reports an emergency for an ambulance or police car to attend
+// JavaScript signature: reportIncident(kind: "medical"|"police", x: number, z: number) -> number
+// Returns the incident id, or -1 (logging why) for an unknown kind; the
+// responder is sent from the nearest station as soon as there is a route
+func reportIncident(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeNumber || args[2].Type() != js.TypeNumber {
+		return js.ValueOf(-1)
+	}
+	kind, ok := emergencyKindFromString(args[0].String())
+	if !ok {
+		println("reportIncident: unknown incident kind", args[0].String())
+		return js.ValueOf(-1)
+	}
+	return js.ValueOf(w.ReportIncident(kind, args[1].Float(), args[2].Float()))
+}
+
+// cancelIncident calls off an incident and removes its responder
+// JavaScript signature: cancelIncident(id: number) -> boolean
+func cancelIncident(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.CancelIncident(args[0].Int()))
+}
+
+// getIncidents returns the open incidents and their responders
+// JavaScript signature: getIncidents()
+//   -> {reported, resolved, incidents: [{id, kind, x, z, attended, progress,
+//       vehicle_id, vehicle: "none"|"en_route"|"on_scene"}]}
+// attended is game hours on scene and progress (0-1) how close the
+// incident is to being resolved
+func getIncidents(w *World, args []js.Value) interface{} {
+	s := w.Emergency
+	incidents := make([]interface{}, len(s.Incidents()))
+	for i, incident := range s.Incidents() {
+		incidents[i] = map[string]interface{}{
+			"id":         incident.ID,
+			"kind":       incident.Kind.String(),
+			"x":          incident.X,
+			"z":          incident.Z,
+			"attended":   incident.Attended,
+			"progress":   math.Min(incident.Attended/positiveOr(s.SceneHours, INCIDENT_SCENE_HOURS), 1),
+			"vehicle_id": incident.VehicleID,
+			"vehicle":    incident.responderState(),
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"reported":  s.Reported,
+		"resolved":  s.Resolved,
+		"incidents": incidents,
+	})
+}
+
+// clearIncidents calls off every incident, removes the responders and resets the counters
+// JavaScript signature: clearIncidents() -> undefined
+func clearIncidents(w *World, args []js.Value) interface{} {
+	w.ClearIncidents()
+	return nil
+}
+
+// setSiren turns a car's siren on or off
+// JavaScript signature: setSiren(id: number, on: boolean) -> boolean
+// AI cars with a siren drive faster and ignore signals; AI cars they close
+// on pull over. Returns false if the car does not exist
+func setSiren(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeBoolean {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.SetSiren(args[0].Int(), args[1].Bool()))
+}
+
+// getSirensNear returns the ids of the moving cars with a siren on near a point
+// JavaScript signature: getSirensNear(x: number, z: number, radius?: number) -> number[]
+// radius defaults to the siren range, how far AI cars hear a siren
+func getSirensNear(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeNumber {
+		return js.ValueOf([]interface{}{})
+	}
+	radius := w.Emergency.SirenRange
+	if len(args) > 2 && args[2].Type() == js.TypeNumber {
+		radius = args[2].Float()
+	}
+	near := w.SirensNear(args[0].Float(), args[1].Float(), radius)
+	ids := make([]interface{}, len(near))
+	for i, car := range near {
+		ids[i] = car.State.ID
+	}
+	return js.ValueOf(ids)
+}
+
+// ============================================================================
+// Race Bindings
+// ============================================================================
+
+// startRace starts a race over checkpoint gates, replacing any race on
+// JavaScript signature: startRace(gates: Array<{x1, z1, x2, z2}>, carIds: number[], laps?: number) -> boolean
+// gates are passed in order, gate 0 being the start/finish line; laps
+// (default 0) of 0 make an endless practice session. Returns false (logging
+// why) for no gates or an unknown car
+func startRace(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeObject || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	gates := make([]Gate, args[0].Length())
+	for i := range gates {
+		g := args[0].Index(i)
+		gates[i] = Gate{
+			X1: optionalFloat(g, "x1", 0),
+			Z1: optionalFloat(g, "z1", 0),
+			X2: optionalFloat(g, "x2", 0),
+			Z2: optionalFloat(g, "z2", 0),
+		}
+	}
+	carIDs := make([]int, args[1].Length())
+	for i := range carIDs {
+		carIDs[i] = args[1].Index(i).Int()
+	}
+	laps := 0.0
+	if len(args) > 2 && args[2].Type() == js.TypeNumber {
+		laps = args[2].Float()
+	}
+	if !isFinite(laps) {
+		return js.ValueOf(false)
+	}
+	if err := w.StartRace(gates, int(laps), carIDs); err != nil {
+		println("startRace:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// stopRace ends the race and discards its times
+// JavaScript signature: stopRace() -> undefined
+func stopRace(w *World, args []js.Value) interface{} {
+	w.StopRace()
+	return nil
+}
+
+// getRace returns the race clock and standings for a timer and leaderboard
+// JavaScript signature: getRace()
+//   -> null | {laps, gates, elapsed, finishers, standings: [{car_id, position,
+//       place, lap, next_gate, started, finished, current_lap, last_lap,
+//       best_lap, sectors, best_sectors, finish_time}]}
+// Times are simulation seconds: elapsed since the race started, current_lap
+// on the lap being driven; last_lap, best_lap and best_sectors are 0 until driven
+func getRace(w *World, args []js.Value) interface{} {
+	race := w.Race
+	if race == nil {
+		return js.ValueOf(nil)
+	}
+	standings := race.Standings()
+	result := make([]interface{}, len(standings))
+	for i, racer := range standings {
+		current := 0.0
+		if racer.Started && !racer.Finished {
+			current = w.Time - racer.LapStart
+		}
+		result[i] = map[string]interface{}{
+			"car_id":       racer.CarID,
+			"position":     i + 1,
+			"place":        racer.Place,
+			"lap":          racer.Lap,
+			"next_gate":    racer.NextGate,
+			"started":      racer.Started,
+			"finished":     racer.Finished,
+			"current_lap":  current,
+			"last_lap":     racer.LastLap,
+			"best_lap":     racer.BestLap,
+			"sectors":      floatsToJS(racer.Sectors),
+			"best_sectors": floatsToJS(racer.BestSectors),
+			"finish_time":  racer.FinishTime,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"laps":      race.Laps,
+		"gates":     len(race.Gates),
+		"elapsed":   w.Time - race.StartTime,
+		"finishers": race.Finishers,
+		"standings": result,
+	})
+}
+
+// ============================================================================
+// Mission Bindings
+// ============================================================================
+
+// setMissions tunes delivery zones, deadlines and rewards
+// JavaScript signature: setMissions({zone_radius?, trigger_speed?, time_slack?, base_reward?, reward_per_unit?}) -> boolean
+// Deadlines and rewards apply to missions generated from now on
+func setMissions(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	m := w.Missions
+	m.ZoneRadius = positiveOr(optionalFloat(v, "zone_radius", m.ZoneRadius), m.ZoneRadius)
+	m.TriggerSpeed = positiveOr(optionalFloat(v, "trigger_speed", m.TriggerSpeed), m.TriggerSpeed)
+	m.TimeSlack = positiveOr(optionalFloat(v, "time_slack", m.TimeSlack), m.TimeSlack)
+	if reward := optionalFloat(v, "base_reward", m.BaseReward); reward >= 0 && isFinite(reward) {
+		m.BaseReward = reward
+	}
+	if reward := optionalFloat(v, "reward_per_unit", m.RewardPerUnit); reward >= 0 && isFinite(reward) {
+		m.RewardPerUnit = reward
+	}
+	return js.ValueOf(true)
+}
+
+// generateMission gives a car a delivery between two random buildings
+// JavaScript signature: generateMission(carId: number) -> number
+// Returns the mission id, or -1 (logging why) for an unknown car, a town
+// without businesses or roads, or no route
+func generateMission(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(-1)
+	}
+	id, err := w.GenerateMission(args[0].Int())
+	if err != nil {
+		println("generateMission:", err.Error())
+		return js.ValueOf(-1)
+	}
+	return js.ValueOf(id)
+}
+
+// cancelMission abandons a mission without payout
+// JavaScript signature: cancelMission(id: number) -> boolean
+func cancelMission(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.CancelMission(args[0].Int()))
+}
+
+// missionZoneToJS converts a mission trigger zone for JS
+func missionZoneToJS(z MissionZone) map[string]interface{} {
+	return map[string]interface{}{"placement_id": z.PlacementID, "x": z.X, "z": z.Z}
+}
+
+// getMissions returns the active deliveries and the totals
+// JavaScript signature: getMissions()
+//   -> {completed, failed, earned, zone_radius, missions: [{id, car_id,
+//       stage: "pickup"|"dropoff", pickup, dropoff, deadline, time_left, reward}]}
+// pickup and dropoff are {placement_id, x, z} zone centers; deadline is in
+// simulation seconds and time_left the seconds until it
+func getMissions(w *World, args []js.Value) interface{} {
+	m := w.Missions
+	missions := make([]interface{}, len(m.Active()))
+	for i, mission := range m.Active() {
+		missions[i] = map[string]interface{}{
+			"id":        mission.ID,
+			"car_id":    mission.CarID,
+			"stage":     mission.Stage.String(),
+			"pickup":    missionZoneToJS(mission.Pickup),
+			"dropoff":   missionZoneToJS(mission.Dropoff),
+			"deadline":  mission.Deadline,
+			"time_left": math.Max(mission.Deadline-w.Time, 0),
+			"reward":    mission.Reward,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"completed":   m.Completed,
+		"failed":      m.Failed,
+		"earned":      m.Earned,
+		"zone_radius": m.ZoneRadius,
+		"missions":    missions,
+	})
+}
+
+// getMissionRoute returns the road route from a mission's car to its next zone
+// JavaScript signature: getMissionRoute(id: number)
+//   -> null | {nodes: number[], points: Array<{x, z}>, length, travel_time}
+// points are the route's road nodes followed by the zone itself
+func getMissionRoute(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(nil)
+	}
+	route, ok := w.MissionRoute(args[0].Int())
+	if !ok {
+		return js.ValueOf(nil)
+	}
+
+	nodes := make([]interface{}, len(route.Nodes))
+	points := make([]interface{}, 0, len(route.Nodes)+1)
+	for i, id := range route.Nodes {
+		nodes[i] = id
+		n := w.Roads.Node(id)
+		points = append(points, map[string]interface{}{"x": n.X, "z": n.Z})
+	}
+	zone := w.Missions.Mission(args[0].Int()).target()
+	points = append(points, map[string]interface{}{"x": zone.X, "z": zone.Z})
+	return js.ValueOf(map[string]interface{}{
+		"nodes":       nodes,
+		"points":      points,
+		"length":      route.Length,
+		"travel_time": route.TravelTime,
+	})
+}
+
+// clearMissions abandons every mission and resets the totals
+// JavaScript signature: clearMissions() -> undefined
+func clearMissions(w *World, args []js.Value) interface{} {
+	w.ClearMissions()
+	return nil
+}
+
+// ============================================================================
+// Road Wear Bindings
+// ============================================================================
+
+// getRoadWear returns the condition of every road segment for an overlay
+// JavaScript signature: getRoadWear()
+//   -> {passage_wear, weather_wear, speed_loss, grip_loss, repair_cost,
+//       segments: [{from, to, x1, z1, x2, z2, length, condition, repair_cost}]}
+// Segments join road nodes from and to (from < to) and are listed once for
+// both directions; condition is 1 (new) to 0 (worn out)
+func getRoadWear(w *World, args []js.Value) interface{} {
+	r := w.RoadWear
+	segments := w.RoadSegments()
+	result := make([]interface{}, len(segments))
+	for i, s := range segments {
+		a, b := w.Roads.Node(s.From), w.Roads.Node(s.To)
+		result[i] = map[string]interface{}{
+			"from":        s.From,
+			"to":          s.To,
+			"x1":          a.X,
+			"z1":          a.Z,
+			"x2":          b.X,
+			"z2":          b.Z,
+			"length":      s.Length,
+			"condition":   s.Condition(),
+			"repair_cost": w.RepairCostOf(s),
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"passage_wear": r.PassageWear,
+		"weather_wear": r.WeatherWear,
+		"speed_loss":   r.SpeedLoss,
+		"grip_loss":    r.GripLoss,
+		"repair_cost":  r.RepairCost,
+		"segments":     result,
+	})
+}
+
+// setRoadWear tunes how fast roads wear, or sets the condition of one segment
+// JavaScript signature: setRoadWear({passage_wear?, weather_wear?, speed_loss?, grip_loss?, repair_cost?, from?, to?, condition?}) -> boolean
+// condition (0-1) applies to the segment between from and to; returns false
+// when no road joins them
+func setRoadWear(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	r := w.RoadWear
+	if wear := optionalFloat(v, "passage_wear", r.PassageWear); wear >= 0 && isFinite(wear) {
+		r.PassageWear = wear
+	}
+	if wear := optionalFloat(v, "weather_wear", r.WeatherWear); wear >= 0 && isFinite(wear) {
+		r.WeatherWear = wear
+	}
+	if cost := optionalFloat(v, "repair_cost", r.RepairCost); cost >= 0 && isFinite(cost) {
+		r.RepairCost = cost
+	}
+	r.SpeedLoss = clamp(optionalFloat(v, "speed_loss", r.SpeedLoss), 0, 1)
+	r.GripLoss = clamp(optionalFloat(v, "grip_loss", r.GripLoss), 0, 1)
+
+	condition := v.Get("condition")
+	from, to := v.Get("from"), v.Get("to")
+	if condition.Type() != js.TypeNumber || from.Type() != js.TypeNumber || to.Type() != js.TypeNumber {
+		return js.ValueOf(true)
+	}
+	return js.ValueOf(w.SetRoadWear(from.Int(), to.Int(), 1-condition.Float()))
+}
+
+// repairRoad restores a road segment, or every road, to new condition
+// JavaScript signature: repairRoad(from?: number, to?: number) -> number | false
+// Returns the cost paid from the treasury, or false (repairing nothing) when
+// there is no such road or the town cannot afford it
+func repairRoad(w *World, args []js.Value) interface{} {
+	var cost float64
+	var ok bool
+	if len(args) >= 2 && args[0].Type() == js.TypeNumber && args[1].Type() == js.TypeNumber {
+		cost, ok = w.RepairRoad(args[0].Int(), args[1].Int())
+	} else {
+		cost, ok = w.RepairAllRoads()
+	}
+	if !ok {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(cost)
+}
+
+// ============================================================================
+// Road Feature Bindings
+// ============================================================================
+
+// setRoadFeature places a speed bump or pothole, replacing any feature with the same id
+// JavaScript signature: setRoadFeature(id: number, {kind: "speed_bump"|"pothole", x, z,
+//   heading?, length?, radius?, height?}) -> boolean
+// A feature is a strip length long along heading (rad; 0 for a round one)
+// and radius wide either side; height is the bump's height or the pothole's
+// depth. Returns false (logging why) for an unknown kind
+func setRoadFeature(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	kind := FeatureSpeedBump
+	if k := v.Get("kind"); k.Type() == js.TypeString {
+		var ok bool
+		if kind, ok = roadFeatureKindFromString(k.String()); !ok {
+			println("setRoadFeature: unknown road feature kind", k.String())
+			return js.ValueOf(false)
+		}
+	}
+	f := RoadFeature{
+		ID:      args[0].Int(),
+		Kind:    kind,
+		X:       optionalFloat(v, "x", 0),
+		Z:       optionalFloat(v, "z", 0),
+		Heading: optionalFloat(v, "heading", 0),
+		Length:  optionalFloat(v, "length", 0),
+		Radius:  optionalFloat(v, "radius", 0),
+		Height:  optionalFloat(v, "height", 0),
+	}
+	if !isFinite(f.X) || !isFinite(f.Z) || !isFinite(f.Heading) {
+		return js.ValueOf(false)
+	}
+	w.Features.Set(f)
+	return js.ValueOf(true)
+}
+
+// removeRoadFeature removes a speed bump or pothole
+// JavaScript signature: removeRoadFeature(id: number) -> boolean
+func removeRoadFeature(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Features.Remove(args[0].Int()))
+}
+
+// getRoadFeatures lists the speed bumps and potholes
+// JavaScript signature: getRoadFeatures() -> Array<{id, kind, x, z, heading, length, radius, height}>
+func getRoadFeatures(w *World, args []js.Value) interface{} {
+	features := make([]interface{}, len(w.Features.Features()))
+	for i, f := range w.Features.Features() {
+		features[i] = map[string]interface{}{
+			"id":      f.ID,
+			"kind":    f.Kind.String(),
+			"x":       f.X,
+			"z":       f.Z,
+			"heading": f.Heading,
+			"length":  f.Length,
+			"radius":  f.Radius,
+			"height":  f.Height,
+		}
+	}
+	return js.ValueOf(features)
+}
+
+// clearRoadFeatures removes every speed bump and pothole
+// JavaScript signature: clearRoadFeatures() -> undefined
+func clearRoadFeatures(w *World, args []js.Value) interface{} {
+	w.Features.Clear()
+	return nil
+}
+
+// ============================================================================
+// Parking Bindings
+// ============================================================================
+
+// setParkingSpot marks out a parking spot, replacing any spot with the same id
+// JavaScript signature: setParkingSpot(id: number, {x, z, heading?, length?, width?,
+//   angle_tolerance?, both_ways?}) -> boolean
+// A spot is length long along heading (rad, the way parked cars face) and
+// width across; both_ways lets cars face either way along it
+func setParkingSpot(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[1]
+	spot := ParkingSpot{
+		ID:             args[0].Int(),
+		X:              optionalFloat(v, "x", 0),
+		Z:              optionalFloat(v, "z", 0),
+		Heading:        optionalFloat(v, "heading", 0),
+		Length:         optionalFloat(v, "length", 0),
+		Width:          optionalFloat(v, "width", 0),
+		AngleTolerance: optionalFloat(v, "angle_tolerance", 0),
+		BothWays:       v.Get("both_ways").Truthy(),
+	}
+	if !isFinite(spot.X) || !isFinite(spot.Z) || !isFinite(spot.Heading) {
+		return js.ValueOf(false)
+	}
+	w.Parking.Set(spot)
+	return js.ValueOf(true)
+}
+
+// removeParkingSpot removes a parking spot
+// JavaScript signature: removeParkingSpot(id: number) -> boolean
+func removeParkingSpot(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Parking.Remove(args[0].Int()))
+}
+
+// getParkingSpots lists the parking spots and the cars parked in them
+// JavaScript signature: getParkingSpots()
+//   -> Array<{id, x, z, heading, length, width, angle_tolerance, both_ways, occupied, occupant, score}>
+// occupant is the id of the car parked, score (0-1) how precisely it parked
+func getParkingSpots(w *World, args []js.Value) interface{} {
+	spots := make([]interface{}, len(w.Parking.Spots()))
+	for i, spot := range w.Parking.Spots() {
+		spots[i] = map[string]interface{}{
+			"id":              spot.ID,
+			"x":               spot.X,
+			"z":               spot.Z,
+			"heading":         spot.Heading,
+			"length":          spot.Length,
+			"width":           spot.Width,
+			"angle_tolerance": spot.AngleTolerance,
+			"both_ways":       spot.BothWays,
+			"occupied":        spot.Occupied,
+			"occupant":        spot.Occupant,
+			"score":           spot.Score,
+		}
+	}
+	return js.ValueOf(spots)
+}
+
+// clearParkingSpots removes every parking spot
+// JavaScript signature: clearParkingSpots() -> undefined
+func clearParkingSpots(w *World, args []js.Value) interface{} {
+	w.Parking.Clear()
+	return nil
+}
+
+// ============================================================================
+// Lot Parking Bindings
+// ============================================================================
+
+// setLotParking turns business parking demand on or off and tunes it
+// JavaScript signature: setLotParking({enabled?, walk_radius?, dwell_hours?}) -> boolean
+// walk_radius is how far (units) a lot may be from the businesses it serves;
+// dwell_hours how many game hours a trip car stays parked
+func setLotParking(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	lp := w.LotParking
+	radius := optionalFloat(v, "walk_radius", lp.WalkRadius)
+	dwell := optionalFloat(v, "dwell_hours", lp.DwellHours)
+	if !(radius > 0 && dwell > 0) || !isFinite(radius) || !isFinite(dwell) {
+		return js.ValueOf(false)
+	}
+	lp.WalkRadius, lp.DwellHours = radius, dwell
+	if enabled := v.Get("enabled"); enabled.Type() == js.TypeBoolean {
+		lp.Enabled = enabled.Bool()
+	}
+	if !lp.Enabled {
+		lp.Parked, lp.occupied = nil, nil
+	}
+	return js.ValueOf(true)
+}
+
+// setParkingUse sets the spaces a model use provides as a parking lot and
+// the spaces it wants per job as a business
+// JavaScript signature: setParkingUse(use: string, {spaces?, demand_per_job?}) -> boolean
+// 0 for both leaves the use out of lot parking
+func setParkingUse(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v, use := args[1], args[0].String()
+	lp := w.LotParking
+	spaces := optionalFloat(v, "spaces", float64(lp.Spaces[use]))
+	demand := optionalFloat(v, "demand_per_job", lp.DemandPerJob[use])
+	if !(spaces >= 0 && demand >= 0) || !isFinite(spaces) || !isFinite(demand) {
+		return js.ValueOf(false)
+	}
+	delete(lp.Spaces, use)
+	delete(lp.DemandPerJob, use)
+	if spaces > 0 {
+		lp.Spaces[use] = int(spaces)
+	}
+	if demand > 0 {
+		lp.DemandPerJob[use] = demand
+	}
+	return js.ValueOf(true)
+}
+
+// getLotParking reports business parking demand and lot occupancy
+// JavaScript signature: getLotParking() -> {enabled, walk_radius, dwell_hours, demand, unmet,
+//   commercial_happiness, arrivals, turned_away, lots: [{id, x, z, spaces, demand, occupied, occupancy}],
+//   businesses: [{id, demand, unmet}]}
+// Demand is in spaces; commercial_happiness (0-1) scales commercial demand
+// while parking is enabled, and occupancy is the share of a lot's spaces taken
+func getLotParking(w *World, args []js.Value) interface{} {
+	lp := w.LotParking
+	r := w.LotParkingReport()
+	lots := make([]interface{}, len(r.Lots))
+	for i, l := range r.Lots {
+		lots[i] = map[string]interface{}{
+			"id":        l.ID,
+			"x":         l.X,
+			"z":         l.Z,
+			"spaces":    l.Spaces,
+			"demand":    l.Demand,
+			"occupied":  l.Occupied,
+			"occupancy": l.Occupancy(),
+		}
+	}
+	businesses := make([]interface{}, len(r.Businesses))
+	for i, b := range r.Businesses {
+		businesses[i] = map[string]interface{}{
+			"id":     b.ID,
+			"demand": b.Demand,
+			"unmet":  b.Unmet,
+		}
+	}
+	return js.ValueOf(map[string]interface{}{
+		"enabled":              lp.Enabled,
+		"walk_radius":          lp.WalkRadius,
+		"dwell_hours":          lp.DwellHours,
+		"demand":               r.Demand,
+		"unmet":                r.Unmet,
+		"commercial_happiness": w.commercialHappiness(),
+		"arrivals":             lp.Arrivals,
+		"turned_away":          lp.TurnedAway,
+		"lots":                 lots,
+		"businesses":           businesses,
+	})
+}
+
+// ============================================================================
+// Drift Bindings
+// ============================================================================
+
+// getDriftScore reads a car's drift in progress, combo and totals
+// JavaScript signature: getDriftScore(carId: number) -> null | {drifting, time, points,
+//   multiplier, combo, combo_points, total, best}
+// points are the current drift's before the multiplier it will bank at;
+// combo_points, total and best are multiplied
+func getDriftScore(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return nil
+	}
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return nil
+	}
+	d := &car.Drift
+	return js.ValueOf(map[string]interface{}{
+		"drifting":     d.Drifting,
+		"time":         d.Time,
+		"points":       d.Points,
+		"multiplier":   d.Multiplier(),
+		"combo":        d.Combo,
+		"combo_points": d.ComboPoints,
+		"total":        d.Total,
+		"best":         d.Best,
+	})
+}
+
+// resetDriftScore discards a car's drift, combo and totals
+// JavaScript signature: resetDriftScore(carId: number) -> boolean
+func resetDriftScore(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return js.ValueOf(false)
+	}
+	car.Drift = DriftState{}
+	return js.ValueOf(true)
+}
+
+// ============================================================================
+// Violation Bindings
+// ============================================================================
+
+// getViolations counts the traffic offences a car has been booked for
+// JavaScript signature: getViolations(carId: number) -> null | {red_light, speeding, wrong_way, total}
+// Only cars driven from JavaScript are watched; AI traffic always reads 0
+func getViolations(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return nil
+	}
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return nil
+	}
+	result := make(map[string]interface{}, violationKindCount+1)
+	total := 0
+	for kind, count := range car.Violations.Counts {
+		result[ViolationKind(kind).String()] = count
+		total += count
+	}
+	result["total"] = total
+	return js.ValueOf(result)
+}
+
+// clearViolations wipes a car's record of traffic offences
+// JavaScript signature: clearViolations(carId: number) -> boolean
+func clearViolations(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return js.ValueOf(false)
+	}
+	car.Violations.Counts = [violationKindCount]int{}
+	return js.ValueOf(true)
+}
+
+// ============================================================================
+// Stats Bindings
+// ============================================================================
+
+// getStats reads the town's running totals and the achievements unlocked
+// JavaScript signature: getStats()
+//   -> {distance, top_speed, collisions, buildings_bought, residents, unlocked: string[]}
+// distance and top_speed count the cars driven from JavaScript;
+// buildings_bought the buildings paid for with the economy enabled; residents
+// is the most housed at once; unlocked is in unlock order
+func getStats(w *World, args []js.Value) interface{} {
+	s := w.Stats
+	unlocked := make([]interface{}, len(s.Unlocked))
+	for i, name := range s.Unlocked {
+		unlocked[i] = name
+	}
+	return js.ValueOf(map[string]interface{}{
+		"distance":         s.Distance,
+		"top_speed":        s.TopSpeed,
+		"collisions":       s.Collisions,
+		"buildings_bought": s.Buildings,
+		"residents":        s.Residents,
+		"unlocked":         unlocked,
+	})
+}
+
+// getAchievements lists every achievement and how close the town is to it
+// JavaScript signature: getAchievements()
+//   -> Array<{name, stat, threshold, value, unlocked}>
+// stat is "distance", "top_speed", "collisions", "buildings" or "residents"
+func getAchievements(w *World, args []js.Value) interface{} {
+	list := make([]interface{}, len(achievements))
+	for i, a := range achievements {
+		list[i] = map[string]interface{}{
+			"name":      a.Name,
+			"stat":      a.Stat.String(),
+			"threshold": a.Threshold,
+			"value":     w.Stats.Value(a.Stat),
+			"unlocked":  w.Stats.Has(a.Name),
+		}
+	}
+	return js.ValueOf(list)
+}
+
+// resetStats zeroes the statistics and locks every achievement again
+// JavaScript signature: resetStats() -> undefined
+func resetStats(w *World, args []js.Value) interface{} {
+	w.Stats.Reset()
+	return nil
+}
+
+// ============================================================================
+// Camera Bindings
+// ============================================================================
+
+// setChaseCamera tunes the chase camera following a car; omitted fields keep
+// their current values
+// JavaScript signature: setChaseCamera(carId: number, {distance?, speed_distance?,
+//   height?, look_ahead?, look_height?, stiffness?, damping?, fov?, speed_fov?,
+//   clearance?}) -> boolean
+// Distances are in units, stiffness in 1/s², damping a fraction of
+// critical, field of view in degrees
+func setChaseCamera(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	if w.Car(args[0].Int()) == nil {
+		return js.ValueOf(false)
+	}
+
+	cam := w.ChaseCamera(args[0].Int())
+	c, v := cam.Config, args[1]
+	cam.Config = ChaseCameraConfig{
+		Distance:      optionalFloat(v, "distance", c.Distance),
+		SpeedDistance: optionalFloat(v, "speed_distance", c.SpeedDistance),
+		Height:        optionalFloat(v, "height", c.Height),
+		LookAhead:     optionalFloat(v, "look_ahead", c.LookAhead),
+		LookHeight:    optionalFloat(v, "look_height", c.LookHeight),
+		Stiffness:     optionalFloat(v, "stiffness", c.Stiffness),
+		Damping:       optionalFloat(v, "damping", c.Damping),
+		FOV:           optionalFloat(v, "fov", c.FOV),
+		SpeedFOV:      optionalFloat(v, "speed_fov", c.SpeedFOV),
+		Clearance:     optionalFloat(v, "clearance", c.Clearance),
+	}.sanitized()
+	return js.ValueOf(true)
+}
+
+// updateChaseCamera moves the chase camera following a car on by one frame
+// JavaScript signature: updateChaseCamera(carId: number, dt: number)
+//   -> null | {x, y, z, look_x, look_y, look_z, yaw, pitch, fov}
+// Call once per rendered frame after stepping, with the frame time; yaw is
+// as rotation_y, pitch in rad up positive, fov in degrees
+func updateChaseCamera(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeNumber {
+		return nil
+	}
+	view, ok := w.UpdateChaseCamera(args[0].Int(), args[1].Float())
+	if !ok {
+		return nil
+	}
+	return js.ValueOf(map[string]interface{}{
+		"x":      view.X,
+		"y":      view.Y,
+		"z":      view.Z,
+		"look_x": view.LookX,
+		"look_y": view.LookY,
+		"look_z": view.LookZ,
+		"yaw":    view.Yaw,
+		"pitch":  view.Pitch,
+		"fov":    view.FOV,
+	})
+}
+
+// resetChaseCamera makes a car's chase camera jump straight to its place on
+// the next update, e.g. after the car is teleported
+// JavaScript signature: resetChaseCamera(carId: number) -> boolean
+func resetChaseCamera(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber || w.Car(args[0].Int()) == nil {
+		return js.ValueOf(false)
+	}
+	w.ChaseCamera(args[0].Int()).Reset()
+	return js.ValueOf(true)
+}
+
+// ============================================================================
+// Input Bindings
+// ============================================================================
+
+// setInputProfile tunes how raw controls become car input; omitted fields
+// keep their current values
+// JavaScript signature: setInputProfile({deadzone?, steer_curve?, pedal_curve?, steer_rate?,
+//   center_rate?, key_press_rate?, key_release_rate?, key_steer_rate?}) -> boolean
+// Curves are response exponents (1 = linear); rates are full travel per second
+func setInputProfile(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	p, v := w.InputProfile, args[0]
+	w.InputProfile = InputProfile{
+		Deadzone:       optionalFloat(v, "deadzone", p.Deadzone),
+		SteerCurve:     optionalFloat(v, "steer_curve", p.SteerCurve),
+		PedalCurve:     optionalFloat(v, "pedal_curve", p.PedalCurve),
+		SteerRate:      optionalFloat(v, "steer_rate", p.SteerRate),
+		CenterRate:     optionalFloat(v, "center_rate", p.CenterRate),
+		KeyPressRate:   optionalFloat(v, "key_press_rate", p.KeyPressRate),
+		KeyReleaseRate: optionalFloat(v, "key_release_rate", p.KeyReleaseRate),
+		KeySteerRate:   optionalFloat(v, "key_steer_rate", p.KeySteerRate),
+	}.sanitized()
+	return js.ValueOf(true)
+}
+
+// getInputProfile reads the input tuning
+// JavaScript signature: getInputProfile() -> {deadzone, steer_curve, pedal_curve, steer_rate,
+//   center_rate, key_press_rate, key_release_rate, key_steer_rate}
+func getInputProfile(w *World, args []js.Value) interface{} {
+	p := &w.InputProfile
+	return js.ValueOf(map[string]interface{}{
+		"deadzone":         p.Deadzone,
+		"steer_curve":      p.SteerCurve,
+		"pedal_curve":      p.PedalCurve,
+		"steer_rate":       p.SteerRate,
+		"center_rate":      p.CenterRate,
+		"key_press_rate":   p.KeyPressRate,
+		"key_release_rate": p.KeyReleaseRate,
+		"key_steer_rate":   p.KeySteerRate,
+	})
+}
+
+// processCarInput runs one frame of raw controls through the input profile
+// and holds the result as a car's input, like setCarInput
+// JavaScript signature: processCarInput(carId: number, input, dt: number)
+//   -> null | {throttle, brake, steering, handbrake}
+// input is analog {throttle, brake, steering} or keyboard {forward, backward,
+// left, right}, either with {handbrake, cruise_speed, speed_limiter}; dt is
+// the frame time. Returns the input the car will drive with
+func processCarInput(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject || args[2].Type() != js.TypeNumber {
+		return nil
+	}
+	input, ok := w.ProcessInput(args[0].Int(), parseInputState(args[1]), isKeyInput(args[1]), args[2].Float())
+	if !ok {
+		return nil
+	}
+	return js.ValueOf(processedInputToJS(input))
+}
+
+// processedInputToJS converts the input a car drives with for JS
+func processedInputToJS(input InputState) map[string]interface{} {
+	return map[string]interface{}{
+		"throttle":  input.Throttle,
+		"brake":     input.Brake,
+		"steering":  input.Steering,
+		"handbrake": input.Handbrake,
+	}
+}
+
+// setTouchControls tunes the mobile joystick and tilt mapping; omitted
+// fields keep their current values
+// JavaScript signature: setTouchControls({joystick_sensitivity?, tilt_range?,
+//   tilt_sensitivity?, tilt_throttle?, auto_center?, recenter_rate?}) -> boolean
+// tilt_range is the degrees of tilt for full lock; tilt_throttle drives the
+// pedals by pitch; auto_center lets the neutral angles follow the device
+func setTouchControls(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	p, v := w.Touch.Profile, args[0]
+	p.JoystickSensitivity = optionalFloat(v, "joystick_sensitivity", p.JoystickSensitivity)
+	p.TiltRange = optionalFloat(v, "tilt_range", p.TiltRange)
+	p.TiltSensitivity = optionalFloat(v, "tilt_sensitivity", p.TiltSensitivity)
+	p.RecenterRate = optionalFloat(v, "recenter_rate", p.RecenterRate)
+	if t := v.Get("tilt_throttle"); t.Type() == js.TypeBoolean {
+		p.TiltThrottle = t.Bool()
+	}
+	if a := v.Get("auto_center"); a.Type() == js.TypeBoolean {
+		p.AutoCenter = a.Bool()
+	}
+	w.Touch.Profile = p.sanitized()
+	return js.ValueOf(true)
+}
+
+// getTouchControls reads the mobile mapping and the tilt neutral angles
+// JavaScript signature: getTouchControls() -> {joystick_sensitivity, tilt_range, tilt_sensitivity,
+//   tilt_throttle, auto_center, recenter_rate, neutral_roll, neutral_pitch, calibrated}
+func getTouchControls(w *World, args []js.Value) interface{} {
+	t := w.Touch
+	return js.ValueOf(map[string]interface{}{
+		"joystick_sensitivity": t.Profile.JoystickSensitivity,
+		"tilt_range":           t.Profile.TiltRange,
+		"tilt_sensitivity":     t.Profile.TiltSensitivity,
+		"tilt_throttle":        t.Profile.TiltThrottle,
+		"auto_center":          t.Profile.AutoCenter,
+		"recenter_rate":        t.Profile.RecenterRate,
+		"neutral_roll":         t.NeutralRoll,
+		"neutral_pitch":        t.NeutralPitch,
+		"calibrated":           t.Calibrated,
+	})
+}
+
+// calibrateTilt takes the next tilt reading as the device's neutral angles
+// JavaScript signature: calibrateTilt() -> undefined
+func calibrateTilt(w *World, args []js.Value) interface{} {
+	w.Touch.CalibrateTilt()
+	return nil
+}
+
+// processTouchInput drives a car from the on-screen joystick for one frame
+// JavaScript signature: processTouchInput(carId: number, {x, y, handbrake?}, dt: number)
+//   -> null | {throttle, brake, steering, handbrake}
+// x is right and y up, 1 at the edge of the pad; the result went through
+// the input profile like processCarInput's
+func processTouchInput(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject || args[2].Type() != js.TypeNumber {
+		return nil
+	}
+	v := args[1]
+	raw := w.Touch.Joystick(optionalFloat(v, "x", 0), optionalFloat(v, "y", 0))
+	raw.Handbrake = v.Get("handbrake").Truthy()
+	input, ok := w.ProcessInput(args[0].Int(), raw, false, args[2].Float())
+	if !ok {
+		return nil
+	}
+	return js.ValueOf(processedInputToJS(input))
+}
+
+// processTiltInput drives a car from the device's tilt for one frame
+// JavaScript signature: processTiltInput(carId: number, {roll, pitch, throttle?, brake?,
+//   handbrake?}, dt: number) -> null | {throttle, brake, steering, handbrake}
+// roll and pitch are degrees, right and forward positive; throttle and brake
+// (0-1, from on-screen buttons) are used unless tilt_throttle is on
+func processTiltInput(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[0].Type() != js.TypeNumber || args[1].Type() != js.TypeObject || args[2].Type() != js.TypeNumber {
+		return nil
+	}
+	v := args[1]
+	raw := w.Touch.Tilt(optionalFloat(v, "roll", 0), optionalFloat(v, "pitch", 0), args[2].Float())
+	if !w.Touch.Profile.TiltThrottle {
+		raw.Throttle, raw.Brake = optionalFloat(v, "throttle", 0), optionalFloat(v, "brake", 0)
+	}
+	raw.Handbrake = v.Get("handbrake").Truthy()
+	input, ok := w.ProcessInput(args[0].Int(), raw, false, args[2].Float())
+	if !ok {
+		return nil
+	}
+	return js.ValueOf(processedInputToJS(input))
+}
+
+// ============================================================================
+// Economy Bindings
+// ============================================================================
+
+// setEconomy turns the treasury on or off and tunes taxes and demolition refunds
+// JavaScript signature: setEconomy({enabled?, funds?, resident_tax?, business_tax?, refund_share?}) -> boolean
+func setEconomy(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	v := args[0]
+	e := w.Economy
+	if enabled := v.Get("enabled"); enabled.Type() == js.TypeBoolean {
+		e.Enabled = enabled.Bool()
+	}
+	if funds := optionalFloat(v, "funds", e.Funds); isFinite(funds) {
+		e.Funds = funds
+	}
+	if tax := optionalFloat(v, "resident_tax", e.ResidentTax); tax >= 0 && isFinite(tax) {
+		e.ResidentTax = tax
+	}
+	if tax := optionalFloat(v, "business_tax", e.BusinessTax); tax >= 0 && isFinite(tax) {
+		e.BusinessTax = tax
+	}
+	if share := optionalFloat(v, "refund_share", e.RefundShare); share >= 0 && share <= 1 {
+		e.RefundShare = share
+	}
+	return js.ValueOf(true)
+}
+
+// ledgerToJS converts a day's ledger
+func ledgerToJS(l Ledger) map[string]interface{} {
+	return map[string]interface{}{
+		"resident_tax": l.ResidentTax,
+		"business_tax": l.BusinessTax,
+		"refunds":      l.Refunds,
+		"construction": l.Construction,
+		"upkeep":       l.Upkeep,
+		"repairs":      l.Repairs,
+		"missions":     l.Missions,
+		"income":       l.Income(),
+		"expenses":     l.Expenses(),
+		"net":          l.Net(),
+	}
+}
+
+// getBudget returns the treasury and the income/expense breakdown
+// JavaScript signature: getBudget()
+//   -> {enabled, funds, resident_tax, business_tax, refund_share, today: Ledger, yesterday: Ledger}
+// Ledger is {resident_tax, business_tax, refunds, construction, upkeep, repairs, income, expenses, net};
+// taxes and upkeep are booked at the end of each simulated day
+func getBudget(w *World, args []js.Value) interface{} {
+	e := w.Economy
+	return js.ValueOf(map[string]interface{}{
+		"enabled":      e.Enabled,
+		"funds":        e.Funds,
+		"resident_tax": e.ResidentTax,
+		"business_tax": e.BusinessTax,
+		"refund_share": e.RefundShare,
+		"today":        ledgerToJS(e.Today),
+		"yesterday":    ledgerToJS(e.Yesterday),
+	})
+}
+
+// setPrice sets the cost and upkeep of a model use or category
+// JavaScript signature: setPrice(useOrCategory: string, {cost?, upkeep?}) -> boolean
+func setPrice(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	price := w.Prices[args[0].String()]
+	cost := optionalFloat(args[1], "cost", price.Cost)
+	upkeep := optionalFloat(args[1], "upkeep", price.Upkeep)
+	if !(cost >= 0 && upkeep >= 0) || !isFinite(cost) || !isFinite(upkeep) {
+		return js.ValueOf(false)
+	}
+	w.Prices[args[0].String()] = Price{Cost: cost, Upkeep: upkeep}
+	return js.ValueOf(true)
+}
+
+// getPrice returns what a registered model costs
+// JavaScript signature: getPrice(model: string) -> {cost, upkeep}
+func getPrice(w *World, args []js.Value) interface{} {
+	price := Price{}
+	if len(args) >= 1 && args[0].Type() == js.TypeString {
+		price = w.PriceOf(args[0].String())
+	}
+	return js.ValueOf(map[string]interface{}{
+		"cost":   price.Cost,
+		"upkeep": price.Upkeep,
+	})
+}
+
+// purchase pays for building a model
+// JavaScript signature: purchase(model: string) -> boolean
+// Returns false when the treasury cannot afford it (always true while the economy is disabled)
+func purchase(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.Purchase(args[0].String()))
+}
+
+// demolish refunds part of a model's price
+// JavaScript signature: demolish(model: string) -> number (the refund)
+func demolish(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(0)
+	}
+	return js.ValueOf(w.Demolish(args[0].String()))
+}
+
+// demolishPlacement removes a placed object and its collider, refunding part
+// of its price and raising a "demolished" event with its footprint
+// JavaScript signature: demolishPlacement(id: number) -> number | null (the refund, null if there is no such placement)
+func demolishPlacement(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(nil)
+	}
+	refund, ok := w.DemolishPlacement(args[0].Int())
+	if !ok {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(refund)
+}
+
+// ============================================================================
+// Utility Bindings
+// ============================================================================
+
+// setUtilities turns the power and water networks on or off
+// JavaScript signature: setUtilities({enabled?}) -> boolean
+// While enabled, buildings without both utilities hold no residents or jobs
+// and provide no livability services
+func setUtilities(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	if enabled := args[0].Get("enabled"); enabled.Type() == js.TypeBoolean {
+		w.Utilities.Enabled = enabled.Bool()
+	}
+	return js.ValueOf(true)
+}
+
+// setUtilitySupply sets what a model use produces of each utility
+// JavaScript signature: setUtilitySupply(use: string, {power?, water?}) -> boolean
+// Supply is in residents plus jobs served; 0 for both makes the use an ordinary consumer
+func setUtilitySupply(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+
+	supply := w.Utilities.Supply[args[0].String()]
+	for k := UtilityKind(0); k < utilityKindCount; k++ {
+		v := optionalFloat(args[1], k.String(), supply[k])
+		if !(v >= 0) || !isFinite(v) {
+			return js.ValueOf(false)
+		}
+		supply[k] = v
+	}
+	if supply == (UtilitySupply{}) {
+		delete(w.Utilities.Supply, args[0].String())
+	} else {
+		w.Utilities.Supply[args[0].String()] = supply
+	}
+	w.Utilities.invalidate()
+	return js.ValueOf(true)
+}
+
+// getUtilities returns the networks' supply and demand and every building's connection
+// JavaScript signature: getUtilities()
+//   -> {enabled, power: Totals, water: Totals, buildings: Array<{id, network, demand, functional,
+//       power: {connected, served}, water: {connected, served}}>}
+// Totals is {supply, demand, served, unserved}; network is the lowest road tile ID
+// of the building's network, -1 if it is off the roads
+func getUtilities(w *World, args []js.Value) interface{} {
+	result := map[string]interface{}{"enabled": w.Utilities.Enabled}
+	for k, t := range w.UtilityTotals() {
+		result[UtilityKind(k).String()] = map[string]interface{}{
+			"supply":   t.Supply,
+			"demand":   t.Demand,
+			"served":   t.Served,
+			"unserved": t.Unserved,
+		}
+	}
+
+	ids := w.UtilityBuildings()
+	buildings := make([]interface{}, 0, len(ids))
+	for _, id := range ids {
+		st, _ := w.UtilityStatus(id)
+		b := map[string]interface{}{
+			"id":         id,
+			"network":    st.Network,
+			"demand":     st.Demand,
+			"functional": st.Functional(),
+		}
+		for k := UtilityKind(0); k < utilityKindCount; k++ {
+			b[k.String()] = map[string]interface{}{"connected": st.Connected[k], "served": st.Served[k]}
+		}
+		buildings = append(buildings, b)
+	}
+	result["buildings"] = buildings
+	return js.ValueOf(result)
+}
+
+// ============================================================================
+// Event Subscription Bindings
+// ============================================================================
+
+// eventSubscription is a JS callback for one event kind ("*" for every kind)
+type eventSubscription struct {
+	id       int
+	kind     string
+	callback js.Value
+}
+
+// eventSubscriptions are the callbacks registered with each world
+var eventSubscriptions = make(map[*World][]eventSubscription)
+
+var nextSubscriptionID = 1
+
+// eventKindAccepted reports whether onEvent accepts a kind
+func eventKindAccepted(kind string) bool {
+	if kind == "*" || kind == "collision" || kind == "skid" {
+		return true
+	}
+	for _, name := range simEventKindNames {
+		if name == kind {
+			return true
+		}
+	}
+	return false
+}
+
+// onEvent registers a callback for simulation events instead of polling
+// JavaScript signature: onEvent(kind: string, callback: (event) => void) -> number | null
+// kind is "collision", "skid", "building_completed", "building_burned", "milestone",
+// "traffic_jam", "wrecked", "jackknifed", "lap_completed", "race_finished",
+// "mission_completed", "mission_failed", "parked", "drift", "drift_combo", "achievement", "violation",
+// "building_upgraded", "building_downgraded", "demolished" or "*" for all. Callbacks run after every step, advance and
+// stepWorld call with {type, ...}: collisions carry the drainCollisionEvents
+// fields, skids the drainSkidEvents fields; other events {id, to, model, value, x, z},
+// demolitions with the footprint's {width, depth} too (see SimEvent). While a collision or skid callback is registered, those
+// events go to it rather than to the drain. Returns the subscription id, or null for an unknown kind
+func onEvent(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeString || args[1].Type() != js.TypeFunction || !eventKindAccepted(args[0].String()) {
+		return js.ValueOf(nil)
+	}
+	id := nextSubscriptionID
+	nextSubscriptionID++
+	eventSubscriptions[w] = append(eventSubscriptions[w], eventSubscription{id: id, kind: args[0].String(), callback: args[1]})
+	return js.ValueOf(id)
+}
+
+// offEvent removes a callback registered with onEvent
+// JavaScript signature: offEvent(id: number) -> boolean
+func offEvent(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	subs := eventSubscriptions[w]
+	for i, sub := range subs {
+		if sub.id == args[0].Int() {
+			eventSubscriptions[w] = append(subs[:i:i], subs[i+1:]...)
+			return js.ValueOf(true)
+		}
+	}
+	return js.ValueOf(false)
+}
+
+// subscribed reports whether any callback takes events of a kind
+func subscribed(subs []eventSubscription, kind string) bool {
+	for _, sub := range subs {
+		if sub.kind == kind || sub.kind == "*" {
+			return true
+		}
+	}
+	return false
+}
+
+// deliverEvent calls every callback taking events of a kind
+func deliverEvent(subs []eventSubscription, kind string, event map[string]interface{}) {
+	event["type"] = kind
+	value := js.ValueOf(event)
+	for _, sub := range subs {
+		if sub.kind == kind || sub.kind == "*" {
+			sub.callback.Invoke(value)
+		}
+	}
+}
+
+// dispatchEvents delivers the events queued during a step to the world's
+// callbacks; without callbacks they stay queued (and drop once full)
+func dispatchEvents(w *World) {
+	subs := eventSubscriptions[w]
+	if len(subs) == 0 {
+		return
+	}
+
+	if subscribed(subs, "collision") {
+		collisions, _ := w.takeCollisionEvents()
+		for _, e := range collisions {
+			deliverEvent(subs, "collision", collisionEventToJS(e))
+		}
+	}
+	if subscribed(subs, "skid") {
+		skids, _ := w.takeSkidEvents()
+		for _, e := range skids {
+			deliverEvent(subs, "skid", skidEventToJS(e))
+		}
+	}
+	events, _ := w.takeSimEvents()
+	for _, e := range events {
+		event := map[string]interface{}{
+			"id":    e.ID,
+			"to":    e.To,
+			"model": e.Model,
+			"value": e.Value,
+			"x":     e.X,
+			"z":     e.Z,
+		}
+		if e.Kind == SimDemolished {
+			event["width"], event["depth"] = e.Width, e.Depth
+		}
+		deliverEvent(subs, e.Kind.String(), event)
+	}
+}
+
+// ============================================================================
+// World Bindings
+// ============================================================================
+
+// defaultWorld backs the standalone bindings (wasmUpdateCarPhysics, wasmSetTerrain, ...)
+var defaultWorld = NewWorld()
+
+// worldFunc is a binding that operates on one World
+// Standalone globals bind it to defaultWorld; world objects bind it to their own World
+type worldFunc func(w *World, args []js.Value) interface{}
+
+// bindWorld wraps a world binding as a JS function for the given world
+func bindWorld(w *World, fn worldFunc) js.Func {
+	return js.FuncOf(func(this js.Value, args []js.Value) interface{} {
+		return fn(w, args)
+	})
+}
+
+// worldMethods are the methods of JS world objects (see createWorld)
+var worldMethods = map[string]worldFunc{
+	"step":             worldStep,
+	"getTime":          worldGetTime,
+	"addCar":           worldAddCar,
+	"removeCar":        worldRemoveCar,
+	"repairCar":        worldRepairCar,
+	"refuelCar":        worldRefuelCar,
+	"hitchTrailer":     worldHitchTrailer,
+	"unhitchTrailer":   worldUnhitchTrailer,
+	"setCarInput":      worldSetCarInput,
+	"getCar":           worldGetCar,
+	"stepCar":          worldStepCar,
+	"getCarTransforms": worldGetCarTransforms,
+
+	"getTransformViews": worldGetTransformViews,
+	"advance":           worldAdvance,
+	"getFrameStats":     worldGetFrameStats,
+	"saveJSON":          worldSaveJSON,
+	"loadJSON":          worldLoadJSON,
+	"save":              worldSave,
+	"load":              worldLoad,
+	"encodeDelta":       worldEncodeDelta,
+	"applyDelta":        worldApplyDelta,
+	"startPrediction":   worldStartPrediction,
+	"predict":           worldPredict,
+	"reconcile":         worldReconcile,
+	"getPredictedCar":   worldGetPredictedCar,
+	"exportLayout":      worldExportLayout,
+	"importLayout":      worldImportLayout,
+	"undo":              worldUndo,
+	"redo":              worldRedo,
+	"getEditHistory":    worldGetEditHistory,
+	"clearEditHistory":  worldClearEditHistory,
+	"setAutosave":       worldSetAutosave,
+	"autosave":          worldAutosave,
+	"getAutosaves":      worldGetAutosaves,
+	"restoreAutosave":   worldRestoreAutosave,
+	"getAutosave":       worldGetAutosave,
+	"setSeed":           worldSetSeed,
+	"setScale":          worldSetScale,
+	"getScale":          worldGetScale,
+	"getChecksum":       worldGetChecksum,
+	"checkpoint":        worldCheckpoint,
+	"rollback":          worldRollback,
+	"resimulate":        worldResimulate,
+	"releaseCheckpoint": worldReleaseCheckpoint,
+	"startRecording":    worldStartRecording,
+	"stopRecording":     worldStopRecording,
+	"replayRecording":   worldReplayRecording,
+	"addGhost":          worldAddGhost,
+	"removeGhost":       worldRemoveGhost,
+	"getGhosts":         worldGetGhosts,
+	"onEvent":           onEvent,
+	"offEvent":          offEvent,
+
+	"updateCars":           updateCars,
+	"setTerrain":           setTerrain,
+	"clearTerrain":         clearTerrain,
+	"generateTerrain":      generateTerrain,
+	"getTerrainHeight":     getTerrainHeight,
+	"getTerrainNormal":     getTerrainNormal,
+	"setSurfaceMap":        setSurfaceMap,
+	"paintSurface":         paintSurface,
+	"clearSurfaceMap":      clearSurfaceMap,
+	"getSurfaceAt":         getSurfaceAt,
+	"setStaticColliders":   setStaticColliders,
+	"addStaticCollider":    addStaticCollider,
+	"removeStaticCollider": removeStaticCollider,
+	"clearStaticColliders": clearStaticColliders,
+	"drainCollisionEvents": drainCollisionEvents,
+	"drainSkidEvents":      drainSkidEvents,
+	"raycast":              raycast,
+	"setWorldBounds":       setWorldBounds,
+	"clearWorldBounds":     clearWorldBounds,
+
+	"setRoadNetwork":      setRoadNetwork,
+	"clearRoadNetwork":    clearRoadNetwork,
+	"generateRoadNetwork": generateRoadNetwork,
+	"addRoadNode":         addRoadNode,
+	"removeRoadNode":      removeRoadNode,
+	"addRoad":             addRoad,
+	"removeRoadSegment":   removeRoadSegment,
+	"setBikeLane":         setBikeLane,
+	"getRoadGraph":        getRoadGraph,
+	"findRoute":           findRoute,
+	"nearestRoadNode":     nearestRoadNode,
+
+	"spawnAICar":       spawnAICar,
+	"setAIDestination": setAIDestination,
+	"getAIDrivers":     getAIDrivers,
+
+	"setTrafficSignal":    setTrafficSignal,
+	"removeTrafficSignal": removeTrafficSignal,
+	"getTrafficSignals":   getTrafficSignals,
+	"getSignalLight":      getSignalLight,
+
+	"spawnPedestrian":         spawnPedestrian,
+	"removePedestrian":        removePedestrian,
+	"getPedestrianTransforms": getPedestrianTransforms,
+	"setPedestrianGoal":       setPedestrianGoal,
+	"setCrowdConfig":          setCrowdConfig,
+	"getCrowdConfig":          getCrowdConfig,
+
+	"setPlacements":    setPlacements,
+	"addPlacement":     addPlacement,
+	"removePlacement":  removePlacement,
+	"getPlacementAt":   getPlacementAt,
+	"queryPlacements":  queryPlacements,
+	"setOccupancyGrid": setOccupancyGrid,
+	"getOccupancyGrid": getOccupancyGrid,
+	"registerModel":    registerModel,
+	"canPlace":         canPlace,
+	"planLots":         planLots,
+
+	"paintZone":       paintZone,
+	"clearZones":      clearZones,
+	"getZoneAt":       getZoneAt,
+	"setZoneRule":     setZoneRule,
+	"getZoneCoverage": getZoneCoverage,
+	"getZoneMap":      getZoneMap,
+
+	"createDistrict": createDistrict,
+	"removeDistrict": removeDistrict,
+	"paintDistrict":  paintDistrict,
+	"setDistrict":    setDistrict,
+	"getDistricts":   getDistricts,
+	"getDistrictAt":  getDistrictAt,
+
+	"getLivability":      getLivability,
+	"getServiceCoverage": getServiceCoverage,
+	"getCoverageGrids":   getCoverageGrids,
+	"setService":         setService,
+	"getLandValue":       getLandValue,
+	"getLandValueAt":     getLandValueAt,
+	"getNoiseMap":        getNoiseMap,
+	"getNoiseAt":         getNoiseAt,
+	"getGreenery":        getGreenery,
+	"setGreenery":        setGreenery,
+	"getConstruction":    getConstruction,
+	"setConstruction":    setConstruction,
+	"getBuildingLevels":  getBuildingLevels,
+	"setBuildingLevels":  setBuildingLevels,
+
+	"getClock":   getClock,
+	"setClock":   setClock,
+	"getSeason":  getSeason,
+	"getWeather": getWeather,
+	"setWeather": setWeather,
+
+	"getPopulation":  getPopulation,
+	"setUseCapacity": setUseCapacity,
+	"setTripDemand":  setTripDemand,
+	"getTripDemand":  getTripDemand,
+	"clearTrips":     clearTrips,
+
+	"setCitizens":   setCitizens,
+	"getCitizens":   getCitizens,
+	"clearCitizens": clearCitizens,
+	"getHappiness":  getHappiness,
+
+	"setFires":       setFires,
+	"getFires":       getFires,
+	"igniteBuilding": igniteBuilding,
+	"clearFires":     clearFires,
+
+	"setTransit":     setTransit,
+	"setBusRoute":    setBusRoute,
+	"removeBusRoute": removeBusRoute,
+	"getTransit":     getTransit,
+	"clearTransit":   clearTransit,
+
+	"setTrams":       setTrams,
+	"setTramLine":    setTramLine,
+	"removeTramLine": removeTramLine,
+	"getTrams":       getTrams,
+	"clearTrams":     clearTrams,
+
+	"setRailway": setRailway,
+	"sendTrain":  sendTrain,
+	"getRailway": getRailway,
+
+	"setEmergency":   setEmergency,
+	"reportIncident": reportIncident,
+	"cancelIncident": cancelIncident,
+	"getIncidents":   getIncidents,
+	"clearIncidents": clearIncidents,
+	"setSiren":       setSiren,
+	"getSirensNear":  getSirensNear,
+
+	"startRace": startRace,
+	"stopRace":  stopRace,
+	"getRace":   getRace,
+
+	"setMissions":     setMissions,
+	"generateMission": generateMission,
+	"cancelMission":   cancelMission,
+	"getMissions":     getMissions,
+	"getMissionRoute": getMissionRoute,
+	"clearMissions":   clearMissions,
+
+	"getRoadWear": getRoadWear,
+	"setRoadWear": setRoadWear,
+	"repairRoad":  repairRoad,
+
+	"setRoadFeature":    setRoadFeature,
+	"removeRoadFeature": removeRoadFeature,
+	"getRoadFeatures":   getRoadFeatures,
+	"clearRoadFeatures": clearRoadFeatures,
+
+	"setParkingSpot":    setParkingSpot,
+	"removeParkingSpot": removeParkingSpot,
+	"getParkingSpots":   getParkingSpots,
+	"clearParkingSpots": clearParkingSpots,
+
+	"setLotParking": setLotParking,
+	"setParkingUse": setParkingUse,
+	"getLotParking": getLotParking,
+
+	"getDriftScore":   getDriftScore,
+	"resetDriftScore": resetDriftScore,
+
+	"getViolations":   getViolations,
+	"clearViolations": clearViolations,
+
+	"getStats":        getStats,
+	"getAchievements": getAchievements,
+	"resetStats":      resetStats,
+
+	"setChaseCamera":    setChaseCamera,
+	"updateChaseCamera": updateChaseCamera,
+	"resetChaseCamera":  resetChaseCamera,
+
+	"setInputProfile": setInputProfile,
+	"getInputProfile": getInputProfile,
+	"processCarInput": processCarInput,
+
+	"setTouchControls":  setTouchControls,
+	"getTouchControls":  getTouchControls,
+	"calibrateTilt":     calibrateTilt,
+	"processTouchInput": processTouchInput,
+	"processTiltInput":  processTiltInput,
+
+	"setEconomy":        setEconomy,
+	"getBudget":         getBudget,
+	"setPrice":          setPrice,
+	"getPrice":          getPrice,
+	"purchase":          purchase,
+	"demolish":          demolish,
+	"demolishPlacement": demolishPlacement,
+
+	"setUtilities":     setUtilities,
+	"setUtilitySupply": setUtilitySupply,
+	"getUtilities":     getUtilities,
+}
+
+// createWorld creates an independent World owning its own cars, colliders,
+// terrain and surfaces, returned as an object with one method per worldMethods
+// entry plus dispose() to release it
+// JavaScript signature: createWorld() -> World
+func createWorld(this js.Value, args []js.Value) interface{} {
+	w := NewWorld()
+	obj := js.Global().Get("Object").New()
+	funcs := make([]js.Func, 0, len(worldMethods)+1)
+
+	for name, fn := range worldMethods {
+		f := bindWorld(w, fn)
+		funcs = append(funcs, f)
+		obj.Set(name, f)
+	}
+
+	var dispose js.Func
+	dispose = js.FuncOf(func(this js.Value, args []js.Value) interface{} {
+		for _, f := range funcs {
+			f.Release()
+		}
+		delete(eventSubscriptions, w)
+		dispose.Release()
+		return nil
+	})
+	obj.Set("dispose", dispose)
+
+	return obj
+}
+
+// worldStep advances every car in the world with its held input
+// JavaScript signature: world.step(dt?) -> errors | null
+// errors lists sanitization messages prefixed with the car id
+func worldStep(w *World, args []js.Value) interface{} {
+	dt := referenceFrameTime
+	if len(args) >= 1 && args[0].Type() == js.TypeNumber {
+		dt = args[0].Float()
+	}
+	w.Step(dt)
+	w.EndFrame()
+	dispatchEvents(w)
+
+	var messages []interface{}
+	for _, car := range w.Cars() {
+		for _, message := range car.Errors {
+			messages = append(messages, "car "+strconv.Itoa(car.State.ID)+": "+message)
+		}
+	}
+	if len(messages) == 0 {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(messages)
+}
+
+// worldGetTime returns the simulated time in seconds
+// JavaScript signature: world.getTime() -> number
+func worldGetTime(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.Time)
+}
+
+// worldAddCar adds (or replaces) a car
+// JavaScript signature: world.addCar(id: number, carState?, vehicle?, {manual?}?) -> boolean
+// carState uses the updateCarPhysics format; vehicle is a preset name or config.
+// A manual car is left alone by step and advanced with stepCar instead
+func worldAddCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+
+	var state CarState
+	if len(args) >= 2 && args[1].Type() == js.TypeObject {
+		state = parseCarState(args[1])
+	}
+	cfg := DefaultVehicleConfig()
+	if len(args) >= 3 && !args[2].IsUndefined() && !args[2].IsNull() {
+		cfg = parseVehicleConfig(args[2])
+	}
+
+	car := w.AddCar(args[0].Int(), state, cfg)
+	if len(args) >= 4 && args[3].Type() == js.TypeObject {
+		car.Manual = args[3].Get("manual").Truthy()
+	}
+	return js.ValueOf(true)
+}
+
+// worldRemoveCar removes a car
+// JavaScript signature: world.removeCar(id: number) -> boolean
+func worldRemoveCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemoveCar(args[0].Int()))
+}
+
+// worldRepairCar removes collision damage from a car (see physics_damage.go)
+// JavaScript signature: world.repairCar(id: number, amount?: number) -> boolean
+// amount is the damage (0..1) removed; omitted, the car is fully repaired
+func worldRepairCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	amount := 0.0
+	if len(args) >= 2 && args[1].Type() == js.TypeNumber {
+		amount = args[1].Float()
+	}
+	return js.ValueOf(w.RepairCar(args[0].Int(), amount))
+}
+
+// worldRefuelCar adds fuel to a car's tank (see physics_fuel.go)
+// JavaScript signature: world.refuelCar(id: number, amount?: number) -> boolean
+// amount is in fuel units; omitted, the tank is filled
+func worldRefuelCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	amount := 0.0
+	if len(args) >= 2 && args[1].Type() == js.TypeNumber {
+		amount = args[1].Float()
+	}
+	return js.ValueOf(w.Refuel(args[0].Int(), amount))
+}
+
+// worldSetCarInput sets the input a car uses on every following step
+// JavaScript signature: world.setCarInput(id: number, inputState) -> boolean
+func worldSetCarInput(w *World, args []js.Value) interface{} {
+	if len(args) < 2 {
+		return js.ValueOf(false)
+	}
+
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return js.ValueOf(false)
+	}
+	car.Input = parseInputState(args[1])
+	return js.ValueOf(true)
+}
+
+// worldGetCar returns a car's full state
+// JavaScript signature: world.getCar(id: number) -> carState & {siren} | null
+// A car towing a trailer also carries trailer: {x, y, z, heading,
+// articulation, jackknifed, hitch_offset, length, mass, body_radius}
+func worldGetCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(nil)
+	}
+
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return js.ValueOf(nil)
+	}
+	result := carStateToJS(&car.State, &car.Config, car.Errors)
+	result["siren"] = car.Siren
+	if t := car.Trailer; t != nil {
+		result["trailer"] = map[string]interface{}{
+			"x":            t.X,
+			"y":            t.Y,
+			"z":            t.Z,
+			"heading":      t.Heading,
+			"articulation": t.Articulation(&car.State),
+			"jackknifed":   t.Jackknifed,
+			"hitch_offset": t.Config.HitchOffset,
+			"length":       t.Config.Length,
+			"mass":         t.Config.Mass,
+			"body_radius":  t.Config.BodyRadius,
+		}
+	}
+	return js.ValueOf(result)
+}
+
+// parseTrailerConfig reads a JS trailer object; missing fields use
+// DefaultTrailerConfig
+func parseTrailerConfig(v js.Value) TrailerConfig {
+	cfg := DefaultTrailerConfig()
+	if v.Type() != js.TypeObject {
+		return cfg
+	}
+	cfg.HitchOffset = optionalFloat(v, "hitch_offset", cfg.HitchOffset)
+	cfg.Length = optionalFloat(v, "length", cfg.Length)
+	cfg.Mass = optionalFloat(v, "mass", cfg.Mass)
+	cfg.BodyRadius = optionalFloat(v, "body_radius", cfg.BodyRadius)
+	return cfg.sanitized()
+}
+
+// worldHitchTrailer hitches a trailer to a car (see physics_trailer.go)
+// JavaScript signature: world.hitchTrailer(id: number, trailer?) -> boolean
+// trailer is {hitch_offset, length, mass, body_radius}; omitted fields use
+// the defaults
+func worldHitchTrailer(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	cfg := DefaultTrailerConfig()
+	if len(args) >= 2 {
+		cfg = parseTrailerConfig(args[1])
+	}
+	return js.ValueOf(w.HitchTrailer(args[0].Int(), cfg))
+}
+
+// worldUnhitchTrailer drops a car's trailer
+// JavaScript signature: world.unhitchTrailer(id: number) -> boolean
+func worldUnhitchTrailer(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.UnhitchTrailer(args[0].Int()))
+}
+
+// worldStepCar advances one car in place and returns its state in the
+// updateCars buffer layout (see getCarBatchLayout)
+// JavaScript signature: world.stepCar(id: number, inputState?, dt?, out?: Float64Array) -> Float64Array | null
+// inputState, when given, is held for later steps as with setCarInput. The
+// state stays in WASM, so only CAR_STATE_STRIDE numbers cross the boundary;
+// out is reused when it is large enough. Sanitization errors are reported by getCar
+func worldStepCar(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(nil)
+	}
+
+	car := w.Car(args[0].Int())
+	if car == nil {
+		return js.ValueOf(nil)
+	}
+	if len(args) >= 2 && args[1].Type() == js.TypeObject {
+		car.Input = parseInputState(args[1])
+	}
+	dt := referenceFrameTime
+	if len(args) >= 3 && args[2].Type() == js.TypeNumber {
+		dt = args[2].Float()
+	}
+
+	w.StepCar(car.State.ID, dt)
+	var state [CAR_STATE_STRIDE]float64
+	car.State.storeBatch(state[:])
+	var out []js.Value
+	if len(args) >= 4 {
+		out = args[3:]
+	}
+	return reusableFloat64Array(out, state[:])
+}
+
+// reusableFloat64Array copies values into the Float64Array passed as args[0]
+// when it is large enough, or into a new one, avoiding an allocation per frame
+func reusableFloat64Array(args []js.Value, values []float64) js.Value {
+	var out js.Value
+	if len(args) >= 1 && args[0].InstanceOf(js.Global().Get("Float64Array")) && args[0].Length() >= len(values) {
+		out = args[0]
+	} else {
+		out = js.Global().Get("Float64Array").New(len(values))
+	}
+	float64sToJS(out, values)
+	return out
+}
+
+// worldGetCarTransforms returns every car's render transform in one buffer
+// JavaScript signature: world.getCarTransforms(out?: Float64Array) -> Float64Array
+// Each car uses CAR_TRANSFORM_STRIDE values: id, x, y, z, rotation_y, pitch, roll.
+// out is reused when it is large enough, avoiding an allocation per frame
+func worldGetCarTransforms(w *World, args []js.Value) interface{} {
+	transforms := make([]float64, len(w.Cars())*CAR_TRANSFORM_STRIDE)
+	w.WriteTransforms(transforms)
+	return reusableFloat64Array(args, transforms)
+}
+
+// transformStoreToJS describes one transform store for JS views
+func transformStoreToJS(s *TransformStore) map[string]interface{} {
+	return map[string]interface{}{
+		"pointer":  int(s.pointer()),
+		"count":    s.Count,
+		"capacity": s.Capacity,
+	}
+}
+
+// worldGetTransformViews locates the car, pedestrian, trailer, tram and
+// train transform planes in WASM memory, for Float64Array views that read
+// them without copying
+// JavaScript signature: world.getTransformViews()
+//   -> {fields: {id, x, y, z, heading, pitch, roll, state}, alpha,
+//       cars: Store, pedestrians: Store, trailers: Store, trams: Store, trains: Store,
+//       previous_cars: Store, previous_pedestrians: Store, previous_trailers: Store,
+//       previous_trams: Store, previous_trains: Store}
+// Store is {pointer, count, capacity}; fields maps each field to its plane,
+// and plane f of a store holds count values starting at byte
+// pointer + f*capacity*8. The stores are rewritten by every step; views must
+// be rebuilt when pointer or capacity change, or when the WASM memory buffer
+// grows. previous_* hold the transforms before the last fixed step of
+// advance, to be blended with the current ones by alpha (match entities by id)
+func worldGetTransformViews(w *World, args []js.Value) interface{} {
+	fields := make(map[string]interface{}, TRANSFORM_FIELDS)
+	for i, name := range transformFieldNames {
+		fields[name] = i
+	}
+	return js.ValueOf(map[string]interface{}{
+		"fields":               fields,
+		"alpha":                w.Timestep.Alpha,
+		"cars":                 transformStoreToJS(&w.carTransforms),
+		"pedestrians":          transformStoreToJS(&w.pedestrianTransforms),
+		"previous_cars":        transformStoreToJS(&w.prevCarTransforms),
+		"previous_pedestrians": transformStoreToJS(&w.prevPedestrianTransforms),
+		"trailers":             transformStoreToJS(&w.trailerTransforms),
+		"previous_trailers":    transformStoreToJS(&w.prevTrailerTransforms),
+		"trams":                transformStoreToJS(&w.tramTransforms),
+		"previous_trams":       transformStoreToJS(&w.prevTramTransforms),
+		"trains":               transformStoreToJS(&w.trainTransforms),
+		"previous_trains":      transformStoreToJS(&w.prevTrainTransforms),
+	})
+}
+
+// worldAdvance runs the world in fixed steps for a frame's elapsed time
+// JavaScript signature: world.advance(dt: number, step?: number) -> {steps, alpha}
+// step (seconds per fixed step, default 1/60) is kept for later calls. alpha
+// (0-1) is how far rendering should blend from the previous transforms to
+// the current ones (see getTransformViews)
+func worldAdvance(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(nil)
+	}
+	if len(args) >= 2 && args[1].Type() == js.TypeNumber && args[1].Float() > 0 && isFinite(args[1].Float()) {
+		w.Timestep.Step = args[1].Float()
+	}
+	steps := w.Advance(args[0].Float())
+	dispatchEvents(w)
+	return js.ValueOf(map[string]interface{}{
+		"steps": steps,
+		"alpha": w.Timestep.Alpha,
+	})
+}
+
+// worldGetFrameStats reports what the last frame cost, for a performance HUD
+// JavaScript signature: world.getFrameStats() -> {steps, step_ms, systems, counts}
+// A frame is one step call, or an advance call that ran fixed steps. systems
+// maps each subsystem (environment, traffic, fires, pedestrians, physics,
+// collision, grids, ghosts, output) to its ms; counts holds the entity totals
+func worldGetFrameStats(w *World, args []js.Value) interface{} {
+	stats := w.Profile.Last
+	systems := make(map[string]interface{}, profileSystemCount)
+	for i, ms := range stats.Systems {
+		systems[ProfileSystem(i).String()] = ms
+	}
+	c := stats.Counts
+	return js.ValueOf(map[string]interface{}{
+		"steps":   stats.Steps,
+		"step_ms": stats.StepMs,
+		"systems": systems,
+		"counts": map[string]interface{}{
+			"cars":        c.Cars,
+			"ai_cars":     c.AICars,
+			"manual_cars": c.ManualCars,
+			"pedestrians": c.Pedestrians,
+			"colliders":   c.Colliders,
+			"placements":  c.Placements,
+			"road_nodes":  c.RoadNodes,
+			"road_edges":  c.RoadEdges,
+			"signals":     c.Signals,
+			"trips":       c.Trips,
+			"fires":       c.Fires,
+			"ghosts":      c.Ghosts,
+		},
+	})
+}
+
+// worldSaveJSON encodes the world's complete state (see physics_snapshot.go)
+// JavaScript signature: world.saveJSON() -> string | null
+// Returns null (logging why) if the state cannot be encoded
+func worldSaveJSON(w *World, args []js.Value) interface{} {
+	data, err := w.ToJSON()
+	if err != nil {
+		println("saveJSON:", err.Error())
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(string(data))
+}
+
+// worldLoadJSON replaces the world's state with saveJSON output
+// JavaScript signature: world.loadJSON(json: string) -> boolean
+// Returns false (leaving the world unchanged) for invalid or inconsistent state
+func worldLoadJSON(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(false)
+	}
+	if err := w.FromJSON([]byte(args[0].String())); err != nil {
+		println("loadJSON:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldSave encodes the world's complete state as a binary save (see physics_save.go)
+// JavaScript signature: world.save() -> Uint8Array | null
+// Returns null (logging why) if the state cannot be encoded
+func worldSave(w *World, args []js.Value) interface{} {
+	data, err := w.Save()
+	if err != nil {
+		println("save:", err.Error())
+		return js.ValueOf(nil)
+	}
+	out := js.Global().Get("Uint8Array").New(len(data))
+	js.CopyBytesToJS(out, data)
+	return out
+}
+
+// worldLoad replaces the world's state with save output
+// JavaScript signature: world.load(data: Uint8Array) -> boolean
+// Saves from a newer minor format version load, skipping what this build
+// does not know; returns false (leaving the world unchanged) for invalid
+// data or a newer major version
+func worldLoad(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || !args[0].InstanceOf(js.Global().Get("Uint8Array")) {
+		return js.ValueOf(false)
+	}
+	if err := w.Load(bytesFromJS(args[0])); err != nil {
+		println("load:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// deltaBases are, per world, the snapshot its last delta was encoded to or
+// applied to, which the next delta is taken against
+var deltaBases = make(map[*World]*WorldSnapshot)
+
+// worldEncodeDelta encodes the changes since the last encodeDelta call
+// for clients to apply (see physics_delta.go)
+// JavaScript signature: world.encodeDelta(keyFrame?: boolean) -> Uint8Array | null
+// The first call, and any with keyFrame, encodes a key frame that clients
+// can apply without having the stream before it
+func worldEncodeDelta(w *World, args []js.Value) interface{} {
+	base := deltaBases[w]
+	if len(args) > 0 && args[0].Truthy() {
+		base = nil
+	}
+	next := w.Snapshot()
+	data, err := EncodeDelta(base, &next)
+	if err != nil {
+		println("encodeDelta:", err.Error())
+		return js.ValueOf(nil)
+	}
+	deltaBases[w] = &next
+	out := js.Global().Get("Uint8Array").New(len(data))
+	js.CopyBytesToJS(out, data)
+	return out
+}
+
+// worldApplyDelta brings the world to the state an encodeDelta delta
+// describes, applied to the state the last delta brought it to
+// JavaScript signature: world.applyDelta(data: Uint8Array) -> boolean
+// Returns false (leaving the world unchanged) for a delta against another
+// base, e.g. after a lost packet; ask the server for a key frame then
+func worldApplyDelta(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || !args[0].InstanceOf(js.Global().Get("Uint8Array")) {
+		return js.ValueOf(false)
+	}
+	next, err := ApplyDelta(deltaBases[w], bytesFromJS(args[0]))
+	if err == nil {
+		err = w.Restore(next)
+	}
+	if err != nil {
+		println("applyDelta:", err.Error())
+		return js.ValueOf(false)
+	}
+	deltaBases[w] = &next
+	return js.ValueOf(true)
+}
+
+// predictions are the client predictors of multiplayer worlds
+var predictions = make(map[*World]*Prediction)
+
+// worldStartPrediction makes this world a multiplayer client predicting the
+// car it drives (see physics_prediction.go), replacing any earlier predictor
+// JavaScript signature: world.startPrediction(carId: number) -> boolean
+// From then on the world is advanced by predict each tick, not step
+func worldStartPrediction(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	predictions[w] = NewPrediction(w, args[0].Int())
+	return js.ValueOf(true)
+}
+
+// worldPredict runs one predicted tick on the player's input
+// JavaScript signature: world.predict(inputState) -> Uint8Array | null
+// Returns the input packet to send to the server (analog or boolean input,
+// as setCarInput)
+func worldPredict(w *World, args []js.Value) interface{} {
+	p := predictions[w]
+	if p == nil || len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(nil)
+	}
+	data, err := p.Predict(parseInputState(args[0]))
+	if err != nil {
+		println("predict:", err.Error())
+		return js.ValueOf(nil)
+	}
+	out := js.Global().Get("Uint8Array").New(len(data))
+	js.CopyBytesToJS(out, data)
+	return out
+}
+
+// worldReconcile applies a state packet from the server, rewinding and
+// replaying the inputs it has not applied yet
+// JavaScript signature: world.reconcile(data: Uint8Array) -> boolean
+func worldReconcile(w *World, args []js.Value) interface{} {
+	p := predictions[w]
+	if p == nil || len(args) < 1 || !args[0].InstanceOf(js.Global().Get("Uint8Array")) {
+		return js.ValueOf(false)
+	}
+	if err := p.Reconcile(bytesFromJS(args[0])); err != nil {
+		println("reconcile:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldGetPredictedCar returns where to draw the predicted car
+// JavaScript signature: world.getPredictedCar() -> {x, z, rotation_y, correction, pending} | null
+// The pose includes the correction still fading out; pending counts the
+// inputs the server has not applied yet
+func worldGetPredictedCar(w *World, args []js.Value) interface{} {
+	p := predictions[w]
+	if p == nil {
+		return js.ValueOf(nil)
+	}
+	x, z, rot, ok := p.Display()
+	if !ok {
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(map[string]interface{}{
+		"x":          x,
+		"z":          z,
+		"rotation_y": rot,
+		"correction": p.Correction(),
+		"pending":    p.Pending(),
+	})
+}
+
+// worldExportLayout encodes the town's buildings, roads and zones as
+// indented JSON for sharing and hand editing (see physics_layout.go)
+// JavaScript signature: world.exportLayout() -> string | null
+func worldExportLayout(w *World, args []js.Value) interface{} {
+	data, err := w.LayoutJSON()
+	if err != nil {
+		println("exportLayout:", err.Error())
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(string(data))
+}
+
+// worldImportLayout replaces the town's buildings, roads and zones with
+// exportLayout output
+// JavaScript signature: world.importLayout(json: string) -> {ok, errors?: string[]}
+// errors lists every problem found; the town is unchanged unless ok
+func worldImportLayout(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeString {
+		return js.ValueOf(map[string]interface{}{"ok": false, "errors": []interface{}{"layout must be a JSON string"}})
+	}
+	err := w.ImportLayoutJSON([]byte(args[0].String()))
+	if err == nil {
+		return js.ValueOf(map[string]interface{}{"ok": true})
+	}
+	var errs []interface{}
+	if le, ok := err.(*LayoutError); ok {
+		for _, problem := range le.Problems {
+			errs = append(errs, problem)
+		}
+	} else {
+		errs = append(errs, err.Error())
+	}
+	return js.ValueOf(map[string]interface{}{"ok": false, "errors": errs})
+}
+
+// worldUndo reverts the newest building, road or zone edit (see physics_edits.go)
+// JavaScript signature: world.undo() -> boolean (false when there is nothing to undo)
+func worldUndo(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.Undo())
+}
+
+// worldRedo applies the newest undone edit again
+// JavaScript signature: world.redo() -> boolean (false when there is nothing to redo)
+func worldRedo(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.Redo())
+}
+
+// worldGetEditHistory returns how many edits can be undone and redone, for
+// enabling editor buttons
+// JavaScript signature: world.getEditHistory() -> {undo, redo}
+func worldGetEditHistory(w *World, args []js.Value) interface{} {
+	return js.ValueOf(map[string]interface{}{
+		"undo": w.History.UndoCount(),
+		"redo": w.History.RedoCount(),
+	})
+}
+
+// worldClearEditHistory forgets every edit, e.g. after saving
+// JavaScript signature: world.clearEditHistory() -> undefined
+func worldClearEditHistory(w *World, args []js.Value) interface{} {
+	w.History.Clear()
+	return nil
+}
+
+// worldSetAutosave configures the autosave ring (see physics_autosave.go)
+// JavaScript signature: world.setAutosave({interval?, slots?}) -> boolean
+// interval is in simulated seconds; 0 slots turns autosaving off
+func worldSetAutosave(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	a := w.Autosaves
+	slots := a.Slots
+	if v := args[0].Get("slots"); v.Type() == js.TypeNumber {
+		slots = v.Int()
+	}
+	a.Configure(optionalFloat(args[0], "interval", a.Interval), slots)
+	return js.ValueOf(true)
+}
+
+// worldAutosave takes an autosave now, e.g. before a risky edit
+// JavaScript signature: world.autosave() -> number (the autosave id, 0 when autosaving is off)
+func worldAutosave(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.Autosave())
+}
+
+// worldGetAutosaves lists the autosaves in the ring, oldest first
+// JavaScript signature: world.getAutosaves() -> Array<{id, time, day, age}>
+// age is wall-clock seconds since the autosave was taken
+func worldGetAutosaves(w *World, args []js.Value) interface{} {
+	saves := w.Autosaves.List()
+	list := make([]interface{}, len(saves))
+	for i, save := range saves {
+		list[i] = map[string]interface{}{
+			"id":   save.ID,
+			"time": save.Time,
+			"day":  save.Day,
+			"age":  time.Since(save.Taken).Seconds(),
+		}
+	}
+	return js.ValueOf(list)
+}
+
+// worldRestoreAutosave returns the world to an autosave, keeping the ring
+// JavaScript signature: world.restoreAutosave(id: number) -> boolean
+func worldRestoreAutosave(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	if err := w.RestoreAutosave(args[0].Int()); err != nil {
+		println("restoreAutosave:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldGetAutosave encodes an autosave like save, for persisting it
+// JavaScript signature: world.getAutosave(id: number) -> Uint8Array | null
+func worldGetAutosave(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(nil)
+	}
+	save := w.Autosaves.Get(args[0].Int())
+	if save == nil {
+		return js.ValueOf(nil)
+	}
+	data, err := save.Save()
+	if err != nil {
+		println("getAutosave:", err.Error())
+		return js.ValueOf(nil)
+	}
+	out := js.Global().Get("Uint8Array").New(len(data))
+	js.CopyBytesToJS(out, data)
+	return out
+}
+
+// worldSetSeed restarts the world's random generators from a seed, so
+// lockstep peers and replays draw the same numbers (see physics_determinism.go)
+// JavaScript signature: world.setSeed(seed: number) -> boolean
+func worldSetSeed(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber || !isFinite(args[0].Float()) {
+		return js.ValueOf(false)
+	}
+	w.SetSeed(int64(args[0].Float()))
+	return js.ValueOf(true)
+}
+
+// worldSetScale sets how many meters a world unit is, for the real-world
+// speed and odometer readouts on car states (see physics_units.go)
+// JavaScript signature: world.setScale(metersPerUnit: number) -> boolean
+func worldSetScale(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.SetMetersPerUnit(args[0].Float()))
+}
+
+// worldGetScale reads how many meters a world unit is
+// JavaScript signature: world.getScale() -> number
+func worldGetScale(w *World, args []js.Value) interface{} {
+	return js.ValueOf(w.MetersPerUnit)
+}
+
+// worldGetChecksum hashes the world's complete state for lockstep peers to compare
+// JavaScript signature: world.getChecksum() -> string | null
+// The 64-bit hash is returned in hex, since JS numbers cannot hold it
+func worldGetChecksum(w *World, args []js.Value) interface{} {
+	sum, err := w.Checksum()
+	if err != nil {
+		println("getChecksum:", err.Error())
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(strconv.FormatUint(sum, 16))
+}
+
+// checkpoints are the checkpoints held for JS by id, until released
+var checkpoints = make(map[int]*Checkpoint)
+
+var nextCheckpointID = 1
+
+// checkpointArg looks up the checkpoint id passed from JS
+func checkpointArg(v js.Value) *Checkpoint {
+	if v.Type() != js.TypeNumber {
+		return nil
+	}
+	return checkpoints[v.Int()]
+}
+
+// worldCheckpoint keeps the world's state in memory for rolling back to
+// (see physics_rollback.go)
+// JavaScript signature: world.checkpoint() -> number
+// Returns the checkpoint id; release it with releaseCheckpoint once no
+// input older than it can still arrive
+func worldCheckpoint(w *World, args []js.Value) interface{} {
+	id := nextCheckpointID
+	nextCheckpointID++
+	checkpoints[id] = w.Checkpoint()
+	return js.ValueOf(id)
+}
+
+// worldRollback returns the world to a checkpoint, which stays held
+// JavaScript signature: world.rollback(id: number) -> boolean
+func worldRollback(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(false)
+	}
+	c := checkpointArg(args[0])
+	if c == nil {
+		return js.ValueOf(false)
+	}
+	if err := w.Rollback(c); err != nil {
+		println("rollback:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldResimulate rolls back to a checkpoint and steps again with
+// corrected inputs
+// JavaScript signature: world.resimulate(id: number, dt: number,
+//   inputs: Array<Object<carId, inputState>>) -> boolean
+// Each entry of inputs is one step, giving the cars it lists their input
+// (analog or boolean, as setCarInput) before stepping by dt
+func worldResimulate(w *World, args []js.Value) interface{} {
+	if len(args) < 3 || args[1].Type() != js.TypeNumber || args[2].Type() != js.TypeObject {
+		return js.ValueOf(false)
+	}
+	c := checkpointArg(args[0])
+	if c == nil {
+		return js.ValueOf(false)
+	}
+
+	steps := make([]StepInputs, args[2].Length())
+	object := js.Global().Get("Object")
+	for i := range steps {
+		step := args[2].Index(i)
+		steps[i] = make(StepInputs)
+		if step.Type() != js.TypeObject {
+			continue
+		}
+		ids := object.Call("keys", step)
+		for j := 0; j < ids.Length(); j++ {
+			key := ids.Index(j).String()
+			id, err := strconv.Atoi(key)
+			if err != nil {
+				continue
+			}
+			steps[i][id] = parseInputState(step.Get(key))
+		}
+	}
+	if err := w.Resimulate(c, args[1].Float(), steps); err != nil {
+		println("resimulate:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldReleaseCheckpoint frees a checkpoint
+// JavaScript signature: world.releaseCheckpoint(id: number) -> boolean
+func worldReleaseCheckpoint(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || checkpointArg(args[0]) == nil {
+		return js.ValueOf(false)
+	}
+	delete(checkpoints, args[0].Int())
+	return js.ValueOf(true)
+}
+
+// worldStartRecording begins recording a car's input for replays and ghosts
+// (see physics_replay.go), replacing any recording in progress
+// JavaScript signature: world.startRecording(carId: number) -> boolean
+func worldStartRecording(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.StartRecording(args[0].Int()))
+}
+
+// worldStopRecording ends the recording in progress
+// JavaScript signature: world.stopRecording() -> string | null
+// Returns the recording as JSON, or null if nothing was being recorded
+func worldStopRecording(w *World, args []js.Value) interface{} {
+	rec, err := w.StopRecording()
+	if err != nil {
+		println("stopRecording:", err.Error())
+		return js.ValueOf(nil)
+	}
+	if rec == nil {
+		return js.ValueOf(nil)
+	}
+	data, err := RecordingJSON(rec)
+	if err != nil {
+		println("stopRecording:", err.Error())
+		return js.ValueOf(nil)
+	}
+	return js.ValueOf(string(data))
+}
+
+// parseRecording decodes stopRecording output passed from JS
+func parseRecording(name string, v js.Value) *InputRecording {
+	if v.Type() != js.TypeString {
+		return nil
+	}
+	rec, err := RecordingFromJSON([]byte(v.String()))
+	if err != nil {
+		println(name+":", err.Error())
+		return nil
+	}
+	return rec
+}
+
+// worldReplayRecording re-simulates a recording from its starting state,
+// apart from this world, to reproduce a session exactly
+// JavaScript signature: world.replayRecording(json: string) -> {matched, checksum, car} | null
+// matched reports whether the replay ended in the recorded state; car is the
+// recorded car's final state (null if it was removed)
+func worldReplayRecording(w *World, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return js.ValueOf(nil)
+	}
+	rec := parseRecording("replayRecording", args[0])
+	if rec == nil {
+		return js.ValueOf(nil)
+	}
+	replay, matched, err := Replay(rec)
+	if err != nil {
+		println("replayRecording:", err.Error())
+		return js.ValueOf(nil)
+	}
+	sum, _ := replay.Checksum() // Replay already hashed this state
+	result := map[string]interface{}{
+		"matched":  matched,
+		"checksum": strconv.FormatUint(sum, 16),
+		"car":      nil,
+	}
+	if car := replay.Car(rec.CarID); car != nil {
+		result["car"] = carStateToJS(&car.State, &car.Config, car.Errors)
+	}
+	return js.ValueOf(result)
+}
+
+// worldAddGhost plays a recording back next to this world, replacing the
+// ghost with the same id
+// JavaScript signature: world.addGhost(id: number, json: string) -> boolean
+func worldAddGhost(w *World, args []js.Value) interface{} {
+	if len(args) < 2 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	rec := parseRecording("addGhost", args[1])
+	if rec == nil {
+		return js.ValueOf(false)
+	}
+	if err := w.AddGhost(args[0].Int(), rec); err != nil {
+		println("addGhost:", err.Error())
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(true)
+}
+
+// worldRemoveGhost stops a ghost
+// JavaScript signature: world.removeGhost(id: number) -> boolean
+func worldRemoveGhost(w *World, args []js.Value) interface{} {
+	if len(args) < 1 || args[0].Type() != js.TypeNumber {
+		return js.ValueOf(false)
+	}
+	return js.ValueOf(w.RemoveGhost(args[0].Int()))
+}
+
+// worldGetGhosts returns every ghost car's transform for rendering
+// JavaScript signature: world.getGhosts()
+//   -> Array<{id, x, y, z, rotation_y, pitch, roll, progress, finished}>
+// The pose is the rendered one, as in the transform views; progress is the
+// fraction of the recording played; a finished ghost stays where its
+// recording ended until removed
+func worldGetGhosts(w *World, args []js.Value) interface{} {
+	ghosts := w.Ghosts()
+	result := make([]interface{}, 0, len(ghosts))
+	for _, g := range ghosts {
+		car := g.Car()
+		if car == nil {
+			continue // Removed by its own world, e.g. a finished trip car
+		}
+		result = append(result, map[string]interface{}{
+			"id":         g.ID,
+			"x":          car.X,
+			"y":          car.Y + car.Bounce,
+			"z":          car.Z,
+			"rotation_y": car.RotationY,
+			"pitch":      car.Pitch + car.BodyPitch,
+			"roll":       car.Roll + car.BodyRoll + car.Lean,
+			"progress":   g.Progress(),
+			"finished":   g.Finished(),
+		})
+	}
 	return js.ValueOf(result)
 }
 
@@ -795,7 +5958,251 @@ func registerCallbacks() {
 	js.Global().Set("wasmFindObjectsInRadius", js.FuncOf(findObjectsInRadius))
 
 	// Car physics
-	js.Global().Set("wasmUpdateCarPhysics", js.FuncOf(updateCarPhysics))
+	js.Global().Set("wasmUpdateCarPhysics", bindWorld(defaultWorld, updateCarPhysics))
+	js.Global().Set("wasmGetVehiclePreset", js.FuncOf(getVehiclePreset))
+	js.Global().Set("wasmGetCarBatchLayout", js.FuncOf(getCarBatchLayout))
+	js.Global().Set("wasmUpdateCars", bindWorld(defaultWorld, updateCars))
+
+	// Terrain
+	js.Global().Set("wasmSetTerrain", bindWorld(defaultWorld, setTerrain))
+	js.Global().Set("wasmClearTerrain", bindWorld(defaultWorld, clearTerrain))
+	js.Global().Set("wasmGenerateTerrain", bindWorld(defaultWorld, generateTerrain))
+	js.Global().Set("wasmGetTerrainHeight", bindWorld(defaultWorld, getTerrainHeight))
+	js.Global().Set("wasmGetTerrainNormal", bindWorld(defaultWorld, getTerrainNormal))
+
+	// Surface map
+	js.Global().Set("wasmSetSurfaceMap", bindWorld(defaultWorld, setSurfaceMap))
+	js.Global().Set("wasmPaintSurface", bindWorld(defaultWorld, paintSurface))
+	js.Global().Set("wasmClearSurfaceMap", bindWorld(defaultWorld, clearSurfaceMap))
+	js.Global().Set("wasmGetSurfaceAt", bindWorld(defaultWorld, getSurfaceAt))
+
+	// Static collision world
+	js.Global().Set("wasmSetStaticColliders", bindWorld(defaultWorld, setStaticColliders))
+	js.Global().Set("wasmAddStaticCollider", bindWorld(defaultWorld, addStaticCollider))
+	js.Global().Set("wasmRemoveStaticCollider", bindWorld(defaultWorld, removeStaticCollider))
+	js.Global().Set("wasmClearStaticColliders", bindWorld(defaultWorld, clearStaticColliders))
+	js.Global().Set("wasmResolveCarCollisions", bindWorld(defaultWorld, resolveCarCollisionsJS))
+	js.Global().Set("wasmDrainCollisionEvents", bindWorld(defaultWorld, drainCollisionEvents))
+	js.Global().Set("wasmDrainSkidEvents", bindWorld(defaultWorld, drainSkidEvents))
+	js.Global().Set("wasmOnEvent", bindWorld(defaultWorld, onEvent))
+	js.Global().Set("wasmOffEvent", bindWorld(defaultWorld, offEvent))
+
+	// Raycast queries
+	js.Global().Set("wasmRaycast", bindWorld(defaultWorld, raycast))
+
+	// World boundaries
+	js.Global().Set("wasmSetWorldBounds", bindWorld(defaultWorld, setWorldBounds))
+	js.Global().Set("wasmClearWorldBounds", bindWorld(defaultWorld, clearWorldBounds))
+
+	// Road network
+	js.Global().Set("wasmSetRoadNetwork", bindWorld(defaultWorld, setRoadNetwork))
+	js.Global().Set("wasmClearRoadNetwork", bindWorld(defaultWorld, clearRoadNetwork))
+	js.Global().Set("wasmGenerateRoadNetwork", bindWorld(defaultWorld, generateRoadNetwork))
+	js.Global().Set("wasmAddRoadNode", bindWorld(defaultWorld, addRoadNode))
+	js.Global().Set("wasmRemoveRoadNode", bindWorld(defaultWorld, removeRoadNode))
+	js.Global().Set("wasmAddRoad", bindWorld(defaultWorld, addRoad))
+	js.Global().Set("wasmRemoveRoadSegment", bindWorld(defaultWorld, removeRoadSegment))
+	js.Global().Set("wasmSetBikeLane", bindWorld(defaultWorld, setBikeLane))
+	js.Global().Set("wasmGetRoadGraph", bindWorld(defaultWorld, getRoadGraph))
+	js.Global().Set("wasmFindRoute", bindWorld(defaultWorld, findRoute))
+	js.Global().Set("wasmNearestRoadNode", bindWorld(defaultWorld, nearestRoadNode))
+
+	// World-owned cars: AI traffic and manual cars (stepped and rendered through the default world)
+	js.Global().Set("wasmSpawnAICar", bindWorld(defaultWorld, spawnAICar))
+	js.Global().Set("wasmSetAIDestination", bindWorld(defaultWorld, setAIDestination))
+	js.Global().Set("wasmGetAIDrivers", bindWorld(defaultWorld, getAIDrivers))
+	js.Global().Set("wasmAddCar", bindWorld(defaultWorld, worldAddCar))
+	js.Global().Set("wasmRemoveCar", bindWorld(defaultWorld, worldRemoveCar))
+	js.Global().Set("wasmRepairCar", bindWorld(defaultWorld, worldRepairCar))
+	js.Global().Set("wasmRefuelCar", bindWorld(defaultWorld, worldRefuelCar))
+	js.Global().Set("wasmHitchTrailer", bindWorld(defaultWorld, worldHitchTrailer))
+	js.Global().Set("wasmUnhitchTrailer", bindWorld(defaultWorld, worldUnhitchTrailer))
+	js.Global().Set("wasmStepCar", bindWorld(defaultWorld, worldStepCar))
+	js.Global().Set("wasmGetCar", bindWorld(defaultWorld, worldGetCar))
+	js.Global().Set("wasmStepWorld", bindWorld(defaultWorld, worldStep))
+	js.Global().Set("wasmGetCarTransforms", bindWorld(defaultWorld, worldGetCarTransforms))
+	js.Global().Set("wasmGetTransformViews", bindWorld(defaultWorld, worldGetTransformViews))
+	js.Global().Set("wasmAdvanceWorld", bindWorld(defaultWorld, worldAdvance))
+	js.Global().Set("wasmGetFrameStats", bindWorld(defaultWorld, worldGetFrameStats))
+	js.Global().Set("wasmSaveWorld", bindWorld(defaultWorld, worldSaveJSON))
+	js.Global().Set("wasmLoadWorld", bindWorld(defaultWorld, worldLoadJSON))
+	js.Global().Set("wasmSaveWorldBinary", bindWorld(defaultWorld, worldSave))
+	js.Global().Set("wasmLoadWorldBinary", bindWorld(defaultWorld, worldLoad))
+	js.Global().Set("wasmEncodeDelta", bindWorld(defaultWorld, worldEncodeDelta))
+	js.Global().Set("wasmApplyDelta", bindWorld(defaultWorld, worldApplyDelta))
+	js.Global().Set("wasmStartPrediction", bindWorld(defaultWorld, worldStartPrediction))
+	js.Global().Set("wasmPredict", bindWorld(defaultWorld, worldPredict))
+	js.Global().Set("wasmReconcile", bindWorld(defaultWorld, worldReconcile))
+	js.Global().Set("wasmGetPredictedCar", bindWorld(defaultWorld, worldGetPredictedCar))
+	js.Global().Set("wasmExportLayout", bindWorld(defaultWorld, worldExportLayout))
+	js.Global().Set("wasmImportLayout", bindWorld(defaultWorld, worldImportLayout))
+	js.Global().Set("wasmUndo", bindWorld(defaultWorld, worldUndo))
+	js.Global().Set("wasmRedo", bindWorld(defaultWorld, worldRedo))
+	js.Global().Set("wasmGetEditHistory", bindWorld(defaultWorld, worldGetEditHistory))
+	js.Global().Set("wasmClearEditHistory", bindWorld(defaultWorld, worldClearEditHistory))
+	js.Global().Set("wasmSetAutosave", bindWorld(defaultWorld, worldSetAutosave))
+	js.Global().Set("wasmAutosave", bindWorld(defaultWorld, worldAutosave))
+	js.Global().Set("wasmGetAutosaves", bindWorld(defaultWorld, worldGetAutosaves))
+	js.Global().Set("wasmRestoreAutosave", bindWorld(defaultWorld, worldRestoreAutosave))
+	js.Global().Set("wasmGetAutosave", bindWorld(defaultWorld, worldGetAutosave))
+	js.Global().Set("wasmSetSeed", bindWorld(defaultWorld, worldSetSeed))
+	js.Global().Set("wasmSetScale", bindWorld(defaultWorld, worldSetScale))
+	js.Global().Set("wasmGetScale", bindWorld(defaultWorld, worldGetScale))
+	js.Global().Set("wasmGetChecksum", bindWorld(defaultWorld, worldGetChecksum))
+	js.Global().Set("wasmCheckpoint", bindWorld(defaultWorld, worldCheckpoint))
+	js.Global().Set("wasmRollback", bindWorld(defaultWorld, worldRollback))
+	js.Global().Set("wasmResimulate", bindWorld(defaultWorld, worldResimulate))
+	js.Global().Set("wasmReleaseCheckpoint", bindWorld(defaultWorld, worldReleaseCheckpoint))
+	js.Global().Set("wasmStartRecording", bindWorld(defaultWorld, worldStartRecording))
+	js.Global().Set("wasmStopRecording", bindWorld(defaultWorld, worldStopRecording))
+	js.Global().Set("wasmReplayRecording", bindWorld(defaultWorld, worldReplayRecording))
+	js.Global().Set("wasmAddGhost", bindWorld(defaultWorld, worldAddGhost))
+	js.Global().Set("wasmRemoveGhost", bindWorld(defaultWorld, worldRemoveGhost))
+	js.Global().Set("wasmGetGhosts", bindWorld(defaultWorld, worldGetGhosts))
+
+	// Traffic signals
+	js.Global().Set("wasmSetTrafficSignal", bindWorld(defaultWorld, setTrafficSignal))
+	js.Global().Set("wasmRemoveTrafficSignal", bindWorld(defaultWorld, removeTrafficSignal))
+	js.Global().Set("wasmGetTrafficSignals", bindWorld(defaultWorld, getTrafficSignals))
+	js.Global().Set("wasmGetSignalLight", bindWorld(defaultWorld, getSignalLight))
+
+	// Pedestrians
+	js.Global().Set("wasmSpawnPedestrian", bindWorld(defaultWorld, spawnPedestrian))
+	js.Global().Set("wasmRemovePedestrian", bindWorld(defaultWorld, removePedestrian))
+	js.Global().Set("wasmGetPedestrianTransforms", bindWorld(defaultWorld, getPedestrianTransforms))
+	js.Global().Set("wasmSetPedestrianGoal", bindWorld(defaultWorld, setPedestrianGoal))
+	js.Global().Set("wasmSetCrowdConfig", bindWorld(defaultWorld, setCrowdConfig))
+	js.Global().Set("wasmGetCrowdConfig", bindWorld(defaultWorld, getCrowdConfig))
+
+	// Occupancy (placed objects)
+	js.Global().Set("wasmSetPlacements", bindWorld(defaultWorld, setPlacements))
+	js.Global().Set("wasmAddPlacement", bindWorld(defaultWorld, addPlacement))
+	js.Global().Set("wasmRemovePlacement", bindWorld(defaultWorld, removePlacement))
+	js.Global().Set("wasmGetPlacementAt", bindWorld(defaultWorld, getPlacementAt))
+	js.Global().Set("wasmQueryPlacements", bindWorld(defaultWorld, queryPlacements))
+	js.Global().Set("wasmSetOccupancyGrid", bindWorld(defaultWorld, setOccupancyGrid))
+	js.Global().Set("wasmGetOccupancyGrid", bindWorld(defaultWorld, getOccupancyGrid))
+	js.Global().Set("wasmRegisterModel", bindWorld(defaultWorld, registerModel))
+	js.Global().Set("wasmCanPlace", bindWorld(defaultWorld, canPlace))
+	js.Global().Set("wasmPlanLots", bindWorld(defaultWorld, planLots))
+	js.Global().Set("wasmPaintZone", bindWorld(defaultWorld, paintZone))
+	js.Global().Set("wasmClearZones", bindWorld(defaultWorld, clearZones))
+	js.Global().Set("wasmGetZoneAt", bindWorld(defaultWorld, getZoneAt))
+	js.Global().Set("wasmSetZoneRule", bindWorld(defaultWorld, setZoneRule))
+	js.Global().Set("wasmGetZoneCoverage", bindWorld(defaultWorld, getZoneCoverage))
+	js.Global().Set("wasmGetZoneMap", bindWorld(defaultWorld, getZoneMap))
+	js.Global().Set("wasmCreateDistrict", bindWorld(defaultWorld, createDistrict))
+	js.Global().Set("wasmRemoveDistrict", bindWorld(defaultWorld, removeDistrict))
+	js.Global().Set("wasmPaintDistrict", bindWorld(defaultWorld, paintDistrict))
+	js.Global().Set("wasmSetDistrict", bindWorld(defaultWorld, setDistrict))
+	js.Global().Set("wasmGetDistricts", bindWorld(defaultWorld, getDistricts))
+	js.Global().Set("wasmGetDistrictAt", bindWorld(defaultWorld, getDistrictAt))
+	js.Global().Set("wasmGetLivability", bindWorld(defaultWorld, getLivability))
+	js.Global().Set("wasmGetServiceCoverage", bindWorld(defaultWorld, getServiceCoverage))
+	js.Global().Set("wasmGetCoverageGrids", bindWorld(defaultWorld, getCoverageGrids))
+	js.Global().Set("wasmSetService", bindWorld(defaultWorld, setService))
+	js.Global().Set("wasmGetLandValue", bindWorld(defaultWorld, getLandValue))
+	js.Global().Set("wasmGetLandValueAt", bindWorld(defaultWorld, getLandValueAt))
+	js.Global().Set("wasmGetNoiseMap", bindWorld(defaultWorld, getNoiseMap))
+	js.Global().Set("wasmGetNoiseAt", bindWorld(defaultWorld, getNoiseAt))
+	js.Global().Set("wasmGetGreenery", bindWorld(defaultWorld, getGreenery))
+	js.Global().Set("wasmSetGreenery", bindWorld(defaultWorld, setGreenery))
+	js.Global().Set("wasmGetConstruction", bindWorld(defaultWorld, getConstruction))
+	js.Global().Set("wasmSetConstruction", bindWorld(defaultWorld, setConstruction))
+	js.Global().Set("wasmGetBuildingLevels", bindWorld(defaultWorld, getBuildingLevels))
+	js.Global().Set("wasmSetBuildingLevels", bindWorld(defaultWorld, setBuildingLevels))
+	js.Global().Set("wasmGetClock", bindWorld(defaultWorld, getClock))
+	js.Global().Set("wasmSetClock", bindWorld(defaultWorld, setClock))
+	js.Global().Set("wasmGetSeason", bindWorld(defaultWorld, getSeason))
+	js.Global().Set("wasmGetWeather", bindWorld(defaultWorld, getWeather))
+	js.Global().Set("wasmSetWeather", bindWorld(defaultWorld, setWeather))
+	js.Global().Set("wasmGetPopulation", bindWorld(defaultWorld, getPopulation))
+	js.Global().Set("wasmSetUseCapacity", bindWorld(defaultWorld, setUseCapacity))
+	js.Global().Set("wasmSetTripDemand", bindWorld(defaultWorld, setTripDemand))
+	js.Global().Set("wasmGetTripDemand", bindWorld(defaultWorld, getTripDemand))
+	js.Global().Set("wasmClearTrips", bindWorld(defaultWorld, clearTrips))
+	js.Global().Set("wasmSetCitizens", bindWorld(defaultWorld, setCitizens))
+	js.Global().Set("wasmGetCitizens", bindWorld(defaultWorld, getCitizens))
+	js.Global().Set("wasmClearCitizens", bindWorld(defaultWorld, clearCitizens))
+	js.Global().Set("wasmGetHappiness", bindWorld(defaultWorld, getHappiness))
+	js.Global().Set("wasmSetFires", bindWorld(defaultWorld, setFires))
+	js.Global().Set("wasmGetFires", bindWorld(defaultWorld, getFires))
+	js.Global().Set("wasmIgniteBuilding", bindWorld(defaultWorld, igniteBuilding))
+	js.Global().Set("wasmClearFires", bindWorld(defaultWorld, clearFires))
+	js.Global().Set("wasmSetTransit", bindWorld(defaultWorld, setTransit))
+	js.Global().Set("wasmSetBusRoute", bindWorld(defaultWorld, setBusRoute))
+	js.Global().Set("wasmRemoveBusRoute", bindWorld(defaultWorld, removeBusRoute))
+	js.Global().Set("wasmGetTransit", bindWorld(defaultWorld, getTransit))
+	js.Global().Set("wasmClearTransit", bindWorld(defaultWorld, clearTransit))
+	js.Global().Set("wasmSetTrams", bindWorld(defaultWorld, setTrams))
+	js.Global().Set("wasmSetTramLine", bindWorld(defaultWorld, setTramLine))
+	js.Global().Set("wasmRemoveTramLine", bindWorld(defaultWorld, removeTramLine))
+	js.Global().Set("wasmGetTrams", bindWorld(defaultWorld, getTrams))
+	js.Global().Set("wasmClearTrams", bindWorld(defaultWorld, clearTrams))
+	js.Global().Set("wasmSetRailway", bindWorld(defaultWorld, setRailway))
+	js.Global().Set("wasmSendTrain", bindWorld(defaultWorld, sendTrain))
+	js.Global().Set("wasmGetRailway", bindWorld(defaultWorld, getRailway))
+	js.Global().Set("wasmSetEmergency", bindWorld(defaultWorld, setEmergency))
+	js.Global().Set("wasmReportIncident", bindWorld(defaultWorld, reportIncident))
+	js.Global().Set("wasmCancelIncident", bindWorld(defaultWorld, cancelIncident))
+	js.Global().Set("wasmGetIncidents", bindWorld(defaultWorld, getIncidents))
+	js.Global().Set("wasmClearIncidents", bindWorld(defaultWorld, clearIncidents))
+	js.Global().Set("wasmSetSiren", bindWorld(defaultWorld, setSiren))
+	js.Global().Set("wasmGetSirensNear", bindWorld(defaultWorld, getSirensNear))
+	js.Global().Set("wasmStartRace", bindWorld(defaultWorld, startRace))
+	js.Global().Set("wasmStopRace", bindWorld(defaultWorld, stopRace))
+	js.Global().Set("wasmGetRace", bindWorld(defaultWorld, getRace))
+	js.Global().Set("wasmSetMissions", bindWorld(defaultWorld, setMissions))
+	js.Global().Set("wasmGenerateMission", bindWorld(defaultWorld, generateMission))
+	js.Global().Set("wasmCancelMission", bindWorld(defaultWorld, cancelMission))
+	js.Global().Set("wasmGetMissions", bindWorld(defaultWorld, getMissions))
+	js.Global().Set("wasmGetMissionRoute", bindWorld(defaultWorld, getMissionRoute))
+	js.Global().Set("wasmClearMissions", bindWorld(defaultWorld, clearMissions))
+	js.Global().Set("wasmGetRoadWear", bindWorld(defaultWorld, getRoadWear))
+	js.Global().Set("wasmSetRoadWear", bindWorld(defaultWorld, setRoadWear))
+	js.Global().Set("wasmRepairRoad", bindWorld(defaultWorld, repairRoad))
+	js.Global().Set("wasmSetRoadFeature", bindWorld(defaultWorld, setRoadFeature))
+	js.Global().Set("wasmRemoveRoadFeature", bindWorld(defaultWorld, removeRoadFeature))
+	js.Global().Set("wasmGetRoadFeatures", bindWorld(defaultWorld, getRoadFeatures))
+	js.Global().Set("wasmClearRoadFeatures", bindWorld(defaultWorld, clearRoadFeatures))
+	js.Global().Set("wasmSetParkingSpot", bindWorld(defaultWorld, setParkingSpot))
+	js.Global().Set("wasmRemoveParkingSpot", bindWorld(defaultWorld, removeParkingSpot))
+	js.Global().Set("wasmGetParkingSpots", bindWorld(defaultWorld, getParkingSpots))
+	js.Global().Set("wasmClearParkingSpots", bindWorld(defaultWorld, clearParkingSpots))
+	js.Global().Set("wasmSetLotParking", bindWorld(defaultWorld, setLotParking))
+	js.Global().Set("wasmSetParkingUse", bindWorld(defaultWorld, setParkingUse))
+	js.Global().Set("wasmGetLotParking", bindWorld(defaultWorld, getLotParking))
+	js.Global().Set("wasmGetDriftScore", bindWorld(defaultWorld, getDriftScore))
+	js.Global().Set("wasmResetDriftScore", bindWorld(defaultWorld, resetDriftScore))
+	js.Global().Set("wasmGetViolations", bindWorld(defaultWorld, getViolations))
+	js.Global().Set("wasmClearViolations", bindWorld(defaultWorld, clearViolations))
+	js.Global().Set("wasmGetStats", bindWorld(defaultWorld, getStats))
+	js.Global().Set("wasmGetAchievements", bindWorld(defaultWorld, getAchievements))
+	js.Global().Set("wasmResetStats", bindWorld(defaultWorld, resetStats))
+	js.Global().Set("wasmSetChaseCamera", bindWorld(defaultWorld, setChaseCamera))
+	js.Global().Set("wasmUpdateChaseCamera", bindWorld(defaultWorld, updateChaseCamera))
+	js.Global().Set("wasmResetChaseCamera", bindWorld(defaultWorld, resetChaseCamera))
+	js.Global().Set("wasmSetInputProfile", bindWorld(defaultWorld, setInputProfile))
+	js.Global().Set("wasmGetInputProfile", bindWorld(defaultWorld, getInputProfile))
+	js.Global().Set("wasmProcessCarInput", bindWorld(defaultWorld, processCarInput))
+	js.Global().Set("wasmSetTouchControls", bindWorld(defaultWorld, setTouchControls))
+	js.Global().Set("wasmGetTouchControls", bindWorld(defaultWorld, getTouchControls))
+	js.Global().Set("wasmCalibrateTilt", bindWorld(defaultWorld, calibrateTilt))
+	js.Global().Set("wasmProcessTouchInput", bindWorld(defaultWorld, processTouchInput))
+	js.Global().Set("wasmProcessTiltInput", bindWorld(defaultWorld, processTiltInput))
+	js.Global().Set("wasmSetEconomy", bindWorld(defaultWorld, setEconomy))
+	js.Global().Set("wasmGetBudget", bindWorld(defaultWorld, getBudget))
+	js.Global().Set("wasmSetPrice", bindWorld(defaultWorld, setPrice))
+	js.Global().Set("wasmGetPrice", bindWorld(defaultWorld, getPrice))
+	js.Global().Set("wasmPurchase", bindWorld(defaultWorld, purchase))
+	js.Global().Set("wasmDemolish", bindWorld(defaultWorld, demolish))
+	js.Global().Set("wasmDemolishPlacement", bindWorld(defaultWorld, demolishPlacement))
+	js.Global().Set("wasmSetUtilities", bindWorld(defaultWorld, setUtilities))
+	js.Global().Set("wasmSetUtilitySupply", bindWorld(defaultWorld, setUtilitySupply))
+	js.Global().Set("wasmGetUtilities", bindWorld(defaultWorld, getUtilities))
+
+	// Worlds owning their own simulation state
+	js.Global().Set("wasmCreateWorld", js.FuncOf(createWorld))
 
 	// Debugging
 	js.Global().Set("wasmGetGridStats", js.FuncOf(getGridStats))
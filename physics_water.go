@@ -0,0 +1,103 @@
+package main
+
+import (
+	"math"
+	"strings"
+)
+
+// ============================================================================
+// Water (impassable cells, bridges)
+// ============================================================================
+
+// Water is a surface type (physics_surface.go). Cars cannot drive into it:
+// a car that would enter water is stopped at the shore, sliding along it
+// where it can, and a "water" collision event is queued so JS can splash or
+// reset the car. Bridges are placeable models that carry roads over water;
+// wherever a bridge stands the ground counts as road. Buildings and other
+// models cannot be placed on water, and road tiles over water must be
+// bridges.
+
+const (
+	BRIDGE_MODEL_TAG = "bridge" // Models with this in their name are bridges by default
+	WATER_PROBE_SIZE = 0.05     // units, half-size of the box used to look for a bridge
+)
+
+// isBridgeModel reports whether a model name marks a bridge (see BRIDGE_MODEL_TAG)
+func isBridgeModel(name string) bool {
+	return strings.Contains(strings.ToLower(name), BRIDGE_MODEL_TAG)
+}
+
+// onBridge reports whether a bridge placement stands at a world position
+func (w *World) onBridge(x, z float64) bool {
+	probe := BoundingBox{MinX: x - WATER_PROBE_SIZE, MinY: z - WATER_PROBE_SIZE, MaxX: x + WATER_PROBE_SIZE, MaxY: z + WATER_PROBE_SIZE}
+	for _, p := range w.Occupancy.Query(probe) {
+		if w.Models[p.Model].Bridge {
+			return true
+		}
+	}
+	return false
+}
+
+// isWater reports whether a world position is open water (water with no bridge)
+func (w *World) isWater(x, z float64) bool {
+	return w.Surfaces != nil && w.Surfaces.At(x, z) == SurfaceWater && !w.onBridge(x, z)
+}
+
+// Covers reports whether any cell overlapping the bounding box has surface s
+func (m *SurfaceMap) Covers(bbox BoundingBox, s SurfaceType) bool {
+	minX := max(int(math.Floor((bbox.MinX-m.OriginX)/m.CellSize)), 0)
+	minZ := max(int(math.Floor((bbox.MinY-m.OriginZ)/m.CellSize)), 0)
+	maxX := min(int(math.Floor((bbox.MaxX-m.OriginX)/m.CellSize)), m.Width-1)
+	maxZ := min(int(math.Floor((bbox.MaxY-m.OriginZ)/m.CellSize)), m.Depth-1)
+
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			if m.Cells[cz*m.Width+cx] == s {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// footprintInWater reports whether a footprint overlaps any water cell
+func (w *World) footprintInWater(f BoundingBox) bool {
+	return w.Surfaces != nil && w.Surfaces.Covers(f, SurfaceWater)
+}
+
+// applyWater keeps a car that moved from (prevX, prevZ) out of open water,
+// first trying to slide along the shore on one axis, and queues a water
+// event the first time it is stopped in a frame. A car already in the water
+// (placed there, or its bridge removed) is free to drive out
+func (w *World) applyWater(car *CarState, prevX, prevZ float64) {
+	if !w.isWater(car.X, car.Z) || w.isWater(prevX, prevZ) {
+		return
+	}
+
+	speed := simHypot(car.VelocityX, car.VelocityZ)
+	hitX, hitZ := car.X, car.Z
+	switch {
+	case !w.isWater(car.X, prevZ):
+		car.Z, car.VelocityZ = prevZ, 0
+	case !w.isWater(prevX, car.Z):
+		car.X, car.VelocityX = prevX, 0
+	default:
+		car.X, car.Z = prevX, prevZ
+		car.VelocityX, car.VelocityZ = 0, 0
+	}
+
+	if !car.HitWater {
+		car.HitWater = true
+		back := math.Max(simHypot(prevX-hitX, prevZ-hitZ), 1e-9)
+		w.pushCollisionEvent(CollisionEvent{
+			Kind:        CollisionWater,
+			A:           car.ID,
+			B:           -1,
+			ImpactSpeed: speed,
+			PointX:      hitX,
+			PointZ:      hitZ,
+			NormalX:     (prevX - hitX) / back,
+			NormalZ:     (prevZ - hitZ) / back,
+		})
+	}
+}
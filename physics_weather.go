@@ -0,0 +1,169 @@
+package main
+
+import "math"
+
+// ============================================================================
+// Weather (clear, rain and snow with transitions)
+// ============================================================================
+
+// Weather has a type and an intensity 0-1. Changing weather first fades the
+// current precipitation out, then fades the new one in, so the renderer and
+// the physics always agree on what is falling and how hard. Rain costs tire
+// grip; snow lowers top speed and braking. With Auto set, the weather picks
+// its next state from the season's odds (physics_seasons.go) after a random
+// spell measured in game hours.
+
+const (
+	RAIN_GRIP_LOSS           = 0.35 // Share of lateral grip lost in the heaviest rain
+	SNOW_SPEED_LOSS          = 0.4  // Share of top speed lost in the heaviest snow
+	SNOW_BRAKE_LOSS          = 0.5  // Share of braking lost in the heaviest snow
+	WEATHER_TRANSITION_HOURS = 0.5  // Game hours to fade intensity fully in or out
+	WEATHER_MIN_SPELL        = 3.0  // Game hours, shortest spell before the weather changes
+	WEATHER_MAX_SPELL        = 10.0 // Game hours, longest spell
+	WEATHER_SEED             = 7    // Seed for automatic weather (repeats run to run)
+)
+
+// WeatherType is what is falling
+type WeatherType uint8
+
+const (
+	WeatherClear WeatherType = iota
+	WeatherRain
+	WeatherSnow
+	weatherTypeCount
+)
+
+// weatherNames are the JavaScript-facing names, indexed by WeatherType
+var weatherNames = [weatherTypeCount]string{"clear", "rain", "snow"}
+
+// String returns the JavaScript-facing weather name
+func (t WeatherType) String() string {
+	if t >= weatherTypeCount {
+		return weatherNames[WeatherClear]
+	}
+	return weatherNames[t]
+}
+
+// weatherFromString parses a weather name, reporting whether it is known
+func weatherFromString(name string) (WeatherType, bool) {
+	for i, n := range weatherNames {
+		if n == name {
+			return WeatherType(i), true
+		}
+	}
+	return WeatherClear, false
+}
+
+// Weather is the current and upcoming weather
+type Weather struct {
+	Type      WeatherType
+	Intensity float64 // 0-1, always 0 while clear
+
+	Target          WeatherType // Weather being transitioned to (Type when settled)
+	TargetIntensity float64
+
+	Auto bool    // Change weather on its own
+	next float64 // Game hours until the next automatic change
+	rng  *SimRand
+}
+
+// NewWeather creates clear, manually controlled weather
+func NewWeather() *Weather {
+	return &Weather{
+		next: WEATHER_MIN_SPELL,
+		rng:  newSimRand(WEATHER_SEED),
+	}
+}
+
+// Set starts a transition to a weather type and intensity, or switches at
+// once when immediate is true
+func (wx *Weather) Set(t WeatherType, intensity float64, immediate bool) {
+	if t >= weatherTypeCount {
+		t = WeatherClear
+	}
+	intensity = clamp(intensity, 0, 1)
+	if t == WeatherClear {
+		intensity = 0
+	}
+	wx.Target, wx.TargetIntensity = t, intensity
+	if immediate {
+		wx.Type, wx.Intensity = t, intensity
+	}
+}
+
+// Settled reports whether no transition is under way
+func (wx *Weather) Settled() bool {
+	return wx.Type == wx.Target && wx.Intensity == wx.TargetIntensity
+}
+
+// rain and snow return the current precipitation intensities
+func (wx *Weather) rain() float64 {
+	if wx.Type != WeatherRain {
+		return 0
+	}
+	return wx.Intensity
+}
+
+func (wx *Weather) snow() float64 {
+	if wx.Type != WeatherSnow {
+		return 0
+	}
+	return wx.Intensity
+}
+
+// GripScale, MaxSpeedScale and BrakeScale are the multipliers the weather
+// applies to vehicle tuning
+func (wx *Weather) GripScale() float64     { return 1 - RAIN_GRIP_LOSS*wx.rain() }
+func (wx *Weather) MaxSpeedScale() float64 { return 1 - SNOW_SPEED_LOSS*wx.snow() }
+func (wx *Weather) BrakeScale() float64    { return 1 - SNOW_BRAKE_LOSS*wx.snow() }
+
+// adjust scales vehicle tuning for the weather, after the surface (physics_surface.go)
+func (wx *Weather) adjust(cfg *VehicleConfig) {
+	cfg.LateralGrip *= wx.GripScale()
+	cfg.MaxSpeed *= wx.MaxSpeedScale()
+	cfg.ReverseMaxSpeed *= wx.MaxSpeedScale()
+	cfg.BrakePower *= wx.BrakeScale()
+}
+
+// pickNext chooses the next automatic weather by the relative odds of each type
+func (wx *Weather) pickNext(odds [weatherTypeCount]float64) {
+	t := WeatherClear
+	if i := pickWeighted(wx.rng.Rand, odds[:]); i >= 0 {
+		t = WeatherType(i)
+	}
+	wx.Set(t, 0.4+0.6*wx.rng.Float64(), false)
+	wx.next = WEATHER_MIN_SPELL + (WEATHER_MAX_SPELL-WEATHER_MIN_SPELL)*wx.rng.Float64()
+}
+
+// step advances transitions and automatic changes by a number of game hours
+func (wx *Weather) step(hours float64, odds [weatherTypeCount]float64) {
+	if wx.Auto {
+		wx.next -= hours
+		if wx.next <= 0 {
+			wx.pickNext(odds)
+		}
+	}
+
+	change := hours / WEATHER_TRANSITION_HOURS
+	if wx.Type != wx.Target {
+		// Fade the old precipitation out before the new one starts
+		wx.Intensity = math.Max(wx.Intensity-change, 0)
+		if wx.Intensity > 0 {
+			return
+		}
+		wx.Type = wx.Target
+		change = 0
+	}
+	if wx.Intensity < wx.TargetIntensity {
+		wx.Intensity = math.Min(wx.Intensity+change, wx.TargetIntensity)
+	} else {
+		wx.Intensity = math.Max(wx.Intensity-change, wx.TargetIntensity)
+	}
+}
+
+// stepWeather advances the weather on the world clock, drawing automatic
+// changes from the season's odds
+func (w *World) stepWeather(dt float64) {
+	hours := dt / positiveOr(w.Clock.SecondsPerHour, SECONDS_PER_GAME_HOUR)
+	w.Weather.step(hours, w.Clock.Season().Effects().WeatherOdds)
+}
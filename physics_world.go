@@ -0,0 +1,339 @@
+package main
+
+import (
+	"math"
+	"time"
+)
+
+// ============================================================================
+// World (owns all simulation state)
+// ============================================================================
+
+// WorldCar is a car simulated by a World
+type WorldCar struct {
+	State      CarState
+	Config     VehicleConfig
+	Input      InputState     // Held until changed, applied on every step
+	Errors     []string       // Sanitization errors from the last step (nil if none)
+	AI         *AIDriver      // Drives Input each step when set (physics_traffic.go)
+	Manual     bool           // Advanced by StepCar rather than Step (e.g. the player's car)
+	Trailer    *Trailer       // Towed behind the car, nil if none (physics_trailer.go)
+	Siren      bool           // Has priority in traffic (physics_emergency.go)
+	Drift      DriftState     // Drift in progress, combo and score (physics_drift.go)
+	Violations ViolationState // Traffic offences and record (physics_violations.go)
+}
+
+// World owns the simulation: placed objects, cars, pedestrians, static
+// colliders, terrain, surfaces, bounds, the road network and its wear,
+// traffic signals, zoning, the clock, population, the treasury and the
+// collision event queue. Step advances everything at once
+type World struct {
+	Colliders *CollisionWorld
+	Roads     *RoadGraph
+	RoadWear  *RoadWear    // Condition of every road segment (physics_road_wear.go)
+	Terrain   *Terrain     // nil means flat ground at height 0
+	Surfaces  *SurfaceMap  // nil means road everywhere
+	Bounds    *WorldBounds // nil means unbounded
+	Time      float64      // Simulated seconds since creation
+	Seed      int64        // Base of every random generator (physics_determinism.go)
+
+	Signals map[int]*TrafficSignal // Road node ID -> signal (physics_signals.go)
+	Crowd   CrowdConfig            // Pedestrian social forces (physics_crowd.go)
+
+	MetersPerUnit float64        // Real-world size of a world unit (physics_units.go)
+	InputProfile  InputProfile   // Driving feel of processed input (physics_input.go)
+	Touch         *TouchControls // Mobile joystick and tilt mapping (physics_touch.go)
+
+	Occupancy *OccupancyGrid       // Placed objects and their footprints (physics_occupancy.go)
+	Models    map[string]ModelSpec // Placeable models by name (physics_placement.go)
+	Zones     *ZoneMap             // Zone per occupancy cell (physics_zoning.go)
+	Districts *DistrictMap         // District per occupancy cell and their policies (physics_districts.go)
+	ZoneRules map[string]ZoneMask  // Model use -> zones it may be built in
+	Services  []ServiceCategory    // Livability services (physics_livability.go)
+	Noise     *NoiseMap            // Traffic and industry noise per cell (physics_noise.go)
+	Greenery  *Greenery            // Growth of trees and parks (physics_greenery.go)
+	Levels    *BuildingLevels      // Levels of homes and workplaces (physics_levels.go)
+	Sites     *Construction        // Progress of buildings under construction (physics_construction.go)
+
+	Clock      *WorldClock            // Game time of day (physics_clock.go)
+	Weather    *Weather               // Rain and snow affecting driving (physics_weather.go)
+	Population *Population            // Residents, jobs and demand (physics_population.go)
+	Capacities map[string]UseCapacity // Model use -> residents and jobs it holds
+	Trips      *TripDemand            // Generated traffic (physics_trips.go)
+	Citizens   *Citizens              // Residents on daily schedules (physics_citizens.go)
+	Fires      *FireService           // Fires and fire trucks (physics_fire.go)
+	Transit    *Transit               // Bus routes, buses and riders (physics_transit.go)
+	Trams      *Trams                 // Tram lines, trams and passengers (physics_trams.go)
+	Railway    *Railway               // Freight line, its train and gates (physics_trains.go)
+	Emergency  *EmergencyService      // Sirens and incident response (physics_emergency.go)
+	Features   *RoadFeatures          // Speed bumps and potholes (physics_road_features.go)
+	Parking    *ParkingSpots          // Parking challenge spots (physics_parking.go)
+	LotParking *LotParking            // Business parking demand and lots (physics_parking_lots.go)
+	Race       *Race                  // Checkpoint race and lap times, nil if none (physics_race.go)
+	Missions   *Missions              // Delivery missions (physics_missions.go)
+	Stats      *Stats                 // Running totals and achievements (physics_stats.go)
+	Utilities  *Utilities             // Power and water networks (physics_utilities.go)
+	Economy    *Economy               // Treasury and daily ledger (physics_economy.go)
+	Prices     map[string]Price       // Model use or category -> cost and upkeep
+	SimEvents  *SimEvents             // Town events for JS callbacks (physics_sim_events.go)
+	Profile    Profiler               // Time per subsystem of recent frames (physics_profile.go)
+	Recording  *InputRecording        // Input being recorded, nil if none (physics_replay.go)
+	History    EditHistory            // Town edits for undo and redo (physics_edits.go)
+	Autosaves  *AutosaveRing          // Periodic in-memory snapshots (physics_autosave.go)
+
+	cars     []*WorldCar // Stable step order (insertion order)
+	carIndex map[int]int // Car ID -> position in cars
+
+	pedestrians     []*Pedestrian // Stable step order (physics_pedestrians.go)
+	pedestrianIndex map[int]int   // Pedestrian ID -> position in pedestrians
+
+	ghosts []*Ghost // Recordings played back alongside (physics_replay.go)
+
+	cameras map[int]*ChaseCamera // Car ID -> chase camera, render state (physics_camera.go)
+
+	carTransforms        TransformStore // Written every step for JS views (physics_views.go)
+	pedestrianTransforms TransformStore
+	trailerTransforms    TransformStore
+	tramTransforms       TransformStore
+	trainTransforms      TransformStore
+
+	Timestep                 FixedTimestep  // Fixed steps run by Advance (physics_timestep.go)
+	prevCarTransforms        TransformStore // Transforms before the last fixed step
+	prevPedestrianTransforms TransformStore
+	prevTrailerTransforms    TransformStore
+	prevTramTransforms       TransformStore
+	prevTrainTransforms      TransformStore
+
+	events        []CollisionEvent // Queued until drained by JS (physics_events.go)
+	droppedEvents int              // Events lost to a full queue since the last drain
+	skids         []SkidEvent      // Queued until drained by JS (physics_skids.go)
+	droppedSkids  int              // Skid events lost to a full queue since the last drain
+}
+
+// NewWorld creates an empty world
+func NewWorld() *World {
+	town := NewGridSpec(OCCUPANCY_GRID_CELLS, OCCUPANCY_GRID_CELLS, OCCUPANCY_CELL_SIZE)
+	return &World{
+		Colliders: NewCollisionWorld(),
+		Roads:     NewRoadGraph(),
+		RoadWear:  NewRoadWear(),
+		Signals:   make(map[int]*TrafficSignal),
+		Crowd:     DefaultCrowdConfig(),
+
+		MetersPerUnit: METERS_PER_UNIT,
+		InputProfile:  DefaultInputProfile(),
+		Touch:         NewTouchControls(),
+
+		Occupancy: NewOccupancyGrid(town),
+		Models:    make(map[string]ModelSpec),
+		Zones:     NewZoneMap(town),
+		Districts: NewDistrictMap(town),
+		ZoneRules: DefaultZoneRules(),
+		Services:  DefaultServices(),
+		Noise:     NewNoiseMap(town),
+		Greenery:  NewGreenery(),
+		Levels:    NewBuildingLevels(),
+		Sites:     NewConstruction(),
+		carIndex:  make(map[int]int),
+		events:    make([]CollisionEvent, 0, MAX_COLLISION_EVENTS),
+		skids:     make([]SkidEvent, 0, MAX_SKID_EVENTS),
+
+		Clock:      NewWorldClock(),
+		Weather:    NewWeather(),
+		Population: NewPopulation(),
+		Capacities: DefaultCapacities(),
+		Trips:      NewTripDemand(),
+		Citizens:   NewCitizens(),
+		Fires:      NewFireService(),
+		Transit:    NewTransit(),
+		Trams:      NewTrams(),
+		Railway:    NewRailway(),
+		Emergency:  NewEmergencyService(),
+		Features:   NewRoadFeatures(),
+		Parking:    NewParkingSpots(),
+		LotParking: NewLotParking(),
+		Missions:   NewMissions(),
+		Stats:      NewStats(),
+		Utilities:  NewUtilities(),
+		Economy:    NewEconomy(),
+		Prices:     DefaultPrices(),
+		SimEvents:  NewSimEvents(),
+		Autosaves:  NewAutosaveRing(),
+
+		pedestrianIndex: make(map[int]int),
+		cameras:         make(map[int]*ChaseCamera),
+	}
+}
+
+// AddCar adds a car, or replaces the car with the same ID
+func (w *World) AddCar(id int, state CarState, cfg VehicleConfig) *WorldCar {
+	state.ID = id
+	car := &WorldCar{State: state, Config: cfg.sanitized()}
+
+	if i, exists := w.carIndex[id]; exists {
+		w.cars[i] = car
+		return car
+	}
+	w.carIndex[id] = len(w.cars)
+	w.cars = append(w.cars, car)
+	return car
+}
+
+// RemoveCar removes a car, returning false if it does not exist
+// Later cars keep their relative order so stepping stays deterministic
+func (w *World) RemoveCar(id int) bool {
+	i, exists := w.carIndex[id]
+	if !exists {
+		return false
+	}
+
+	w.cars = append(w.cars[:i], w.cars[i+1:]...)
+	delete(w.carIndex, id)
+	delete(w.cameras, id)
+	for j := i; j < len(w.cars); j++ {
+		w.carIndex[w.cars[j].State.ID] = j
+	}
+	return true
+}
+
+// Car returns the car with the given ID, or nil
+func (w *World) Car(id int) *WorldCar {
+	if i, exists := w.carIndex[id]; exists {
+		return w.cars[i]
+	}
+	return nil
+}
+
+// Cars returns every car in step order
+func (w *World) Cars() []*WorldCar {
+	return w.cars
+}
+
+// StepCar advances one car by dt seconds in place with its held input,
+// against the terrain, surfaces and static colliders but not other cars,
+// returning false if it does not exist. It is how manual cars are driven
+// without copying their state across the JS boundary every frame
+func (w *World) StepCar(id int, dt float64) bool {
+	car := w.Car(id)
+	if car == nil {
+		return false
+	}
+	if w.Recording != nil && w.Recording.CarID == id {
+		w.recordFrame(dt, true)
+	}
+	cfg := car.towingConfig()
+	car.Errors = w.stepCar(&car.State, car.Input, &cfg, dt)
+	w.stepTrailer(car, dt)
+	if i := w.carIndex[id]; i < w.carTransforms.Count {
+		w.storeCarTransform(i)
+	}
+	if car.Trailer != nil {
+		w.storeTrailerTransforms()
+	}
+	return true
+}
+
+// Step advances the whole simulation by dt seconds: the town systems and
+// agents first, then every car but the manual ones with its held input and
+// the contacts between them, then the systems that follow from where the
+// cars ended up. Each part is timed for the frame profiler (physics_profile.go)
+func (w *World) Step(dt float64) {
+	if !(dt > 0) {
+		return // Also rejects NaN
+	}
+	if w.Recording != nil {
+		w.recordFrame(dt, false)
+	}
+
+	agentDt := math.Min(dt, maxPhysicsDelta) // Cars clamp dt the same way in stepCar
+	p := &w.Profile
+	start := time.Now()
+	w.stepClock(agentDt)
+	w.stepWeather(agentDt)
+	w.stepRoadWear(agentDt)
+	mark := p.lap(ProfileEnvironment, start)
+	w.stepTrips(agentDt)
+	w.stepCitizens(agentDt)
+	w.stepLotParking()
+	w.stepTransit(agentDt)
+	w.stepTrams(agentDt)
+	w.stepRailway(agentDt)
+	mark = p.lap(ProfileTraffic, mark)
+	w.stepFires(agentDt)
+	w.stepEmergencies(agentDt)
+	mark = p.lap(ProfileFires, mark)
+	w.stepSignals(agentDt)
+	mark = p.lap(ProfileTraffic, mark)
+	w.stepPedestrians(agentDt)
+	mark = p.lap(ProfilePedestrians, mark)
+	w.updateDrivers(agentDt)
+	mark = p.lap(ProfileTraffic, mark)
+
+	bodies := make([]CarBody, len(w.cars))
+	for i, car := range w.cars {
+		if !car.Manual {
+			cfg := car.towingConfig()
+			car.Errors = w.stepCar(&car.State, car.Input, &cfg, dt)
+			w.stepTrailer(car, dt)
+		}
+		bodies[i] = car.State.body(&car.Config) // Manual cars are still bumped
+	}
+	trailers, towing := w.trailerBodies()
+	bodies = append(bodies, trailers...)
+	mark = p.lap(ProfilePhysics, mark)
+
+	contacts := w.resolveCarCollisions(bodies)
+	for i, car := range w.cars {
+		car.State.applyBody(bodies[i])
+	}
+	for k, i := range towing {
+		w.cars[i].Trailer.applyBody(bodies[len(w.cars)+k])
+	}
+	w.damageFromContacts(contacts, bodies, towing)
+	w.collideTrain()
+	w.stepRepairs(agentDt)
+	w.stepRefueling(agentDt)
+	mark = p.lap(ProfileCollision, mark)
+	w.stepNoise(agentDt)
+	w.stepConstruction(agentDt)
+	w.stepGreenery(agentDt)
+	mark = p.lap(ProfileGrids, mark)
+	w.stepGhosts(dt)
+	mark = p.lap(ProfileGhosts, mark)
+	w.stepDrifts(agentDt)
+	w.stepViolations(agentDt)
+	w.stepRace(agentDt)
+	w.stepMissions()
+	w.stepParking()
+	w.stepStats(agentDt)
+	w.stepSimEvents(agentDt)
+	w.storeTransforms()
+	p.lap(ProfileOutput, mark)
+
+	w.Time += agentDt
+	w.stepAutosave()
+	p.stepDone(start)
+}
+
+// CAR_TRANSFORM_STRIDE is the number of values per car written by
+// WriteTransforms: id, x, y, z, rotation_y, pitch, roll; y, pitch and roll
+// include the body's motion on its suspension and roll a two-wheeler's lean
+const CAR_TRANSFORM_STRIDE = 7
+
+// WriteTransforms writes every car's render transform into dst in step order,
+// returning the number of cars written (limited by len(dst))
+func (w *World) WriteTransforms(dst []float64) int {
+	count := min(len(w.cars), len(dst)/CAR_TRANSFORM_STRIDE)
+	for i := 0; i < count; i++ {
+		car := &w.cars[i].State
+		out := dst[i*CAR_TRANSFORM_STRIDE:]
+		out[0] = float64(car.ID)
+		out[1] = car.X
+		out[2] = car.Y + car.Bounce
+		out[3] = car.Z
+		out[4] = car.RotationY
+		out[5] = car.Pitch + car.BodyPitch
+		out[6] = car.Roll + car.BodyRoll + car.Lean
+	}
+	return count
+}
@@ -0,0 +1,205 @@
+package main
+
+// ============================================================================
+// Zoning (residential / commercial / industrial designations)
+// ============================================================================
+
+// Zones are painted onto the occupancy grid's cell layout. A model's use
+// (housing, market, office, ...) decides which zones it may be built in;
+// unzoned cells and uses without a rule accept anything, so freeform building
+// keeps working until the player starts zoning.
+
+// ZoneType is the designation of a cell
+type ZoneType uint8
+
+const (
+	ZoneNone ZoneType = iota
+	ZoneResidential
+	ZoneCommercial
+	ZoneIndustrial
+	zoneTypeCount
+)
+
+// zoneNames are the JavaScript-facing names, indexed by ZoneType
+var zoneNames = [zoneTypeCount]string{"none", "residential", "commercial", "industrial"}
+
+// zoneFromString converts a zone name to its type; ok is false for unknown names
+func zoneFromString(name string) (ZoneType, bool) {
+	for i, n := range zoneNames {
+		if n == name {
+			return ZoneType(i), true
+		}
+	}
+	return ZoneNone, false
+}
+
+// String returns the JavaScript-facing zone name
+func (z ZoneType) String() string {
+	if z >= zoneTypeCount {
+		return zoneNames[ZoneNone]
+	}
+	return zoneNames[z]
+}
+
+// ZoneMask is a set of zones, one bit per ZoneType
+type ZoneMask uint8
+
+// zoneMaskOf returns the set holding the given zones
+func zoneMaskOf(zones ...ZoneType) ZoneMask {
+	var m ZoneMask
+	for _, z := range zones {
+		m |= 1 << z
+	}
+	return m
+}
+
+// Has reports whether the set contains a zone
+func (m ZoneMask) Has(z ZoneType) bool {
+	return m&(1<<z) != 0
+}
+
+// DefaultZoneRules returns where each model use may be built
+// Civic buildings go in residential or commercial zones
+func DefaultZoneRules() map[string]ZoneMask {
+	civic := zoneMaskOf(ZoneResidential, ZoneCommercial)
+	return map[string]ZoneMask{
+		"housing":        zoneMaskOf(ZoneResidential),
+		"market":         zoneMaskOf(ZoneCommercial),
+		"office":         zoneMaskOf(ZoneCommercial),
+		"industry":       zoneMaskOf(ZoneIndustrial),
+		"school":         civic,
+		"library":        civic,
+		"healthcare":     civic,
+		"fire_station":   civic,
+		"police_station": civic,
+		"garage":         zoneMaskOf(ZoneCommercial, ZoneIndustrial),
+		"gas_station":    zoneMaskOf(ZoneCommercial, ZoneIndustrial),
+		"power_plant":    zoneMaskOf(ZoneIndustrial),
+		"water_tower":    zoneMaskOf(ZoneIndustrial),
+		"parking_lot":    zoneMaskOf(ZoneCommercial, ZoneIndustrial),
+	}
+}
+
+// ZoneMap holds a zone per cell, on the same layout as the occupancy grid
+type ZoneMap struct {
+	GridSpec
+	cells []ZoneType
+}
+
+// NewZoneMap creates an unzoned map
+func NewZoneMap(spec GridSpec) *ZoneMap {
+	return &ZoneMap{GridSpec: spec, cells: make([]ZoneType, spec.Len())}
+}
+
+// Cell returns the zone of a cell
+func (m *ZoneMap) Cell(i int) ZoneType {
+	return m.cells[i]
+}
+
+// At returns the zone at a world position (ZoneNone outside the grid)
+func (m *ZoneMap) At(x, z float64) ZoneType {
+	i := m.CellIndex(x, z)
+	if i < 0 {
+		return ZoneNone
+	}
+	return m.cells[i]
+}
+
+// Paint zones every cell whose interior overlaps a bounding box, returning
+// the number of cells covered
+func (m *ZoneMap) Paint(bbox BoundingBox, zone ZoneType) int {
+	minX, minZ, maxX, maxZ, ok := m.CellRange(bbox)
+	if !ok {
+		return 0
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			m.cells[cz*m.Width+cx] = zone
+		}
+	}
+	return (maxX - minX + 1) * (maxZ - minZ + 1)
+}
+
+// Clear unzones every cell
+func (m *ZoneMap) Clear() {
+	for i := range m.cells {
+		m.cells[i] = ZoneNone
+	}
+}
+
+// Resize changes the cell layout, resampling zones at the new cell centers
+func (m *ZoneMap) Resize(spec GridSpec) {
+	cells := make([]ZoneType, spec.Len())
+	for i := range cells {
+		cells[i] = m.At(spec.CellCenter(i))
+	}
+	m.GridSpec = spec
+	m.cells = cells
+}
+
+// allows reports whether every zoned cell under a footprint accepts a use
+func (m *ZoneMap) allows(f BoundingBox, allowed ZoneMask) bool {
+	minX, minZ, maxX, maxZ, ok := m.CellRange(f)
+	if !ok {
+		return true
+	}
+	for cz := minZ; cz <= maxZ; cz++ {
+		for cx := minX; cx <= maxX; cx++ {
+			if z := m.cells[cz*m.Width+cx]; z != ZoneNone && !allowed.Has(z) {
+				return false
+			}
+		}
+	}
+	return true
+}
+
+// ZoneCoverage summarizes one zone
+type ZoneCoverage struct {
+	Cells    int     // Cells designated with the zone
+	Area     float64 // units²
+	Built    int     // Zoned cells covered by a placement
+	Fraction float64 // Built / Cells (0 when nothing is zoned)
+}
+
+// ZoneCoverage returns the coverage of every zone, indexed by ZoneType
+// Built cells count every placement, roads included
+func (w *World) ZoneCoverage() [zoneTypeCount]ZoneCoverage {
+	var cov [zoneTypeCount]ZoneCoverage
+	m := w.Zones
+	cellArea := m.CellSize * m.CellSize
+	for i, z := range m.cells {
+		c := &cov[z]
+		c.Cells++
+		if w.Occupancy.CellOwner(i) != emptyCell {
+			c.Built++
+		}
+	}
+	for z := range cov {
+		c := &cov[z]
+		c.Area = float64(c.Cells) * cellArea
+		if c.Cells > 0 {
+			c.Fraction = float64(c.Built) / float64(c.Cells)
+		}
+	}
+	return cov
+}
+
+// zoneAllows reports whether a model may be built over a footprint
+func (w *World) zoneAllows(spec ModelSpec, f BoundingBox) bool {
+	allowed, ruled := w.ZoneRules[spec.Use]
+	if !ruled {
+		return true
+	}
+	return w.Zones.allows(f, allowed)
+}
+
+// ResizeTownGrid changes the cell layout of the occupancy grid and every
+// map sharing it, clearing the edit history (its zone edits are per cell)
+func (w *World) ResizeTownGrid(spec GridSpec) {
+	w.Occupancy.Resize(spec)
+	w.Zones.Resize(spec)
+	w.Districts.Resize(spec)
+	w.Noise.Resize(spec)
+	w.History.Clear()
+}
+
@@ -2,6 +2,7 @@ import { camera, renderer, placedObjects } from './scene.js'; // Added placedObj
 import * as THREE from './three.module.js'; // Added for Vector3 and Spherical
 import { showNotification, getCurrentMode } from './ui.js'; // Import showNotification and getCurrentMode
 import { getJoystickInput, isJoystickActive } from './joystick.js'; // Import joystick functions
+import { isStaticColliderCategory } from './utils/physics_wasm.js';
 
 let keysPressed = {};
 let isRightMouseDown = false;
@@ -76,6 +77,71 @@ export function setupKeyboardControls() {
     });
 }
 
+// Velocity retained per second by pushed vehicles coasting to a stop
+const PUSHED_VEHICLE_DAMPING = 0.05;
+
+/**
+ * Resolve car-to-car contacts between the driven car and other vehicles in WASM.
+ * Updates newState with the driven car's corrected position/velocity and moves
+ * the vehicles it pushed; pushed vehicles coast to a stop on later frames.
+ */
+function resolveVehicleContacts(car, newState, dt) {
+    if (typeof window.wasmResolveCarCollisions !== 'function') return;
+
+    const others = placedObjects.filter(obj => obj !== car && obj.userData.category === 'vehicles');
+    if (others.length === 0) return;
+
+    // Coast previously pushed vehicles
+    const damping = Math.pow(PUSHED_VEHICLE_DAMPING, dt);
+    for (const other of others) {
+        if (!other.userData.pushVelocityX && !other.userData.pushVelocityZ) continue;
+        other.position.x += other.userData.pushVelocityX * dt;
+        other.position.z += other.userData.pushVelocityZ * dt;
+        other.userData.pushVelocityX *= damping;
+        other.userData.pushVelocityZ *= damping;
+        if (Math.hypot(other.userData.pushVelocityX, other.userData.pushVelocityZ) < 0.05) {
+            other.userData.pushVelocityX = 0;
+            other.userData.pushVelocityZ = 0;
+        }
+        other.userData.boundingBox = null; // Recomputed on next collision check
+    }
+
+    const bodies = [
+        { id: car.id, x: newState.x, z: newState.z, velocity_x: newState.velocity_x, velocity_z: newState.velocity_z, vehicle: car.userData.vehicleConfig || 'car' },
+        ...others.map(other => ({
+            id: other.id,
+            x: other.position.x,
+            z: other.position.z,
+            velocity_x: other.userData.pushVelocityX || 0,
+            velocity_z: other.userData.pushVelocityZ || 0,
+            vehicle: other.userData.vehicleConfig || 'car',
+        })),
+    ];
+
+    const result = window.wasmResolveCarCollisions(bodies);
+    if (!result || result.contacts.length === 0) return;
+
+    newState.x = result.cars[0].x;
+    newState.z = result.cars[0].z;
+    car.userData.velocity_x = newState.velocity_x = result.cars[0].velocity_x;
+    car.userData.velocity_z = newState.velocity_z = result.cars[0].velocity_z;
+
+    others.forEach((other, i) => {
+        const body = result.cars[i + 1];
+        other.position.x = body.x;
+        other.position.z = body.z;
+        other.userData.pushVelocityX = body.velocity_x;
+        other.userData.pushVelocityZ = body.velocity_z;
+        other.userData.boundingBox = null;
+    });
+
+    const hardestHit = Math.max(...result.contacts.map(c => c.impulse));
+    if (hardestHit > 0 && car.userData.collisionCooldown === 0) {
+        showNotification("Bonk!", "error");
+        car.userData.collisionCooldown = 90; // ~1.5 seconds at 60 FPS
+    }
+}
+
 function handleMouseWheel(event) {
     // The model selection panel has the ID 'model-container' in index.html
     const modelPanel = document.getElementById('model-container');
@@ -195,32 +261,65 @@ export function updateControls() {
 
             // Prepare car state
             const carState = {
+                id: car.id, // Reported in collision events
                 x: car.position.x,
                 z: car.position.z,
                 rotation_y: car.rotation.y,
                 velocity_x: car.userData.velocity_x,
                 velocity_z: car.userData.velocity_z,
+                reversing: !!car.userData.reversing,
+                gear: car.userData.gear,
+                rpm: car.userData.rpm,
+                shift_timer: car.userData.shiftTimer,
             };
 
-            // Prepare input state - check both keyboard and joystick
+            // Prepare analog input state - keys give full deflection, joystick is proportional
             const joystickInput = getJoystickInput();
+            const analogAxis = (pressed, joystickValue) => (pressed ? 1 : (joystickValue > 0.1 ? joystickValue : 0));
             const inputState = {
-                forward: !!(keysPressed['w'] || keysPressed['arrowup'] || joystickInput.forward > 0.1),
-                backward: !!(keysPressed['s'] || keysPressed['arrowdown'] || joystickInput.backward > 0.1),
-                left: !!(keysPressed['a'] || keysPressed['arrowleft'] || joystickInput.left > 0.1),
-                right: !!(keysPressed['d'] || keysPressed['arrowright'] || joystickInput.right > 0.1),
+                throttle: analogAxis(keysPressed['w'] || keysPressed['arrowup'], joystickInput.forward),
+                brake: analogAxis(keysPressed['s'] || keysPressed['arrowdown'], joystickInput.backward),
+                steering: analogAxis(keysPressed['d'] || keysPressed['arrowright'], joystickInput.right) -
+                    analogAxis(keysPressed['a'] || keysPressed['arrowleft'], joystickInput.left),
+                handbrake: !!keysPressed[' '], // Space bar
             };
 
             // Read old position for collision detection
             const oldX = carState.x;
             const oldZ = carState.z;
 
-            // Call Go WASM function
-            const newState = window.wasmUpdateCarPhysics(carState, inputState);
+            // Call Go WASM function (velocities are in units per second)
+            const newState = window.wasmUpdateCarPhysics(carState, inputState, window.deltaTime, car.userData.vehicleConfig || 'car');
+
+            // Report state the physics step had to repair (e.g. NaN from a bad save)
+            if (newState.errors) {
+                console.warn('Car physics sanitized invalid state:', newState.errors);
+            }
 
             // Update the velocity on the JS object for the next frame
             car.userData.velocity_x = newState.velocity_x;
             car.userData.velocity_z = newState.velocity_z;
+            car.userData.reversing = newState.reversing; // For reverse lights
+            car.userData.slipAngle = newState.slip_angle; // For skid effects
+            car.userData.surface = newState.surface; // road, grass, dirt, ice, mud
+            car.userData.wheelAngleLeft = newState.wheel_angle_left; // Front wheel rendering
+            car.userData.wheelAngleRight = newState.wheel_angle_right;
+            car.userData.terrainY = newState.y; // Terrain pose (flat ground reports 0)
+            car.userData.pitch = newState.pitch;
+            car.userData.roll = newState.roll;
+
+            // Drivetrain outputs for engine audio and the tachometer
+            car.userData.gear = newState.gear;
+            car.userData.rpm = newState.rpm;
+            car.userData.throttleLoad = newState.throttle_load;
+            car.userData.shiftTimer = newState.shift_timer;
+
+            // World edge (e.g. to show an "edge of town" hint) and shorelines
+            car.userData.hitBoundary = !!newState.hit_boundary;
+            car.userData.hitWater = !!newState.hit_water;
+
+            // Bump other vehicles instead of overlapping them
+            resolveVehicleContacts(car, newState, window.deltaTime);
 
             const attemptedMoveVector = new THREE.Vector3(
                 newState.x - oldX,
@@ -228,7 +327,15 @@ export function updateControls() {
                 newState.z - oldZ
             );
 
-            // Collision detection remains in JS
+            // Buildings, props and trees are resolved inside the WASM step (the car
+            // slides along them); remaining obstacles still use the JS bounding box check below
+            if (newState.collided && car.userData.collisionCooldown === 0) {
+                showNotification("Bonk!", "error");
+                car.userData.collisionCooldown = 90; // ~1.5 seconds at 60 FPS
+            }
+            const wasmHandlesStatics = typeof window.wasmSetPlacements === 'function';
+            const wasmHandlesVehicles = typeof window.wasmResolveCarCollisions === 'function';
+
             const potentialPosition = new THREE.Vector3(newState.x, car.position.y, newState.z);
             const potentialBoundingBox = new THREE.Box3().setFromObject(car);
             potentialBoundingBox.translate(attemptedMoveVector);
@@ -238,6 +345,8 @@ export function updateControls() {
             const forwardDir = new THREE.Vector3(0, 0, 1).applyQuaternion(car.quaternion);
             for (const otherObject of placedObjects) {
                 if (otherObject === car || (otherObject.userData.modelName && otherObject.userData.modelName.includes('road_'))) continue;
+                if (wasmHandlesStatics && isStaticColliderCategory(otherObject.userData.category)) continue;
+                if (wasmHandlesVehicles && otherObject.userData.category === 'vehicles') continue;
                 if (!otherObject.userData.boundingBox) otherObject.userData.boundingBox = new THREE.Box3().setFromObject(otherObject);
                 if (potentialBoundingBox.intersectsBox(otherObject.userData.boundingBox)) {
                     // Only block forward motion; allow backing out
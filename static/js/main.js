@@ -3,7 +3,8 @@ import { initializeScene, animate, loadModelToScene, placedObjects } from './sce
 import { setupSSE, loadTownFromDjango } from './network.js';
 import { setupKeyboardControls } from './controls.js';
 import { showNotification, initUI } from './ui.js';
-import { initPhysicsWasm } from './utils/physics_wasm.js';
+import { initPhysicsWasm, setWorldBounds } from './utils/physics_wasm.js';
+import { GROUND_SIZE } from './scene/scene.js';
 import { applyCategoryStatuses, createStatusLegend } from './category_status.js';
 // Mobile modules
 import { isMobile } from './utils/device-detect.js';
@@ -18,6 +19,10 @@ async function waitForWasm() {
     for (let i = 0; i < 50; i++) {
         if (typeof window.wasmUpdateSpatialGrid === 'function') {
             await initPhysicsWasm();
+
+            // Keep driven cars on the ground plane
+            const half = GROUND_SIZE / 2;
+            setWorldBounds({ minX: -half, minZ: -half, maxX: half, maxZ: half }, 'clamp');
             return true;
         }
         await new Promise(resolve => setTimeout(resolve, 100));
@@ -4,6 +4,9 @@
 import * as THREE from '../three.module.js';
 import { checkCollision } from './collision.js';
 import { getMouseCoordinates } from '../utils/raycaster.js';
+import { canPlace } from '../utils/physics_wasm.js';
+
+const OVERLAP_MESSAGE = 'Cannot place model here, overlaps with another object.';
 
 /**
  * Create a placement indicator
@@ -49,15 +52,8 @@ export function updatePlacementIndicator(event, placementIndicator, groundPlane,
         placementIndicator.position.y += 0.01; // Slightly above ground to avoid z-fighting
         placementIndicator.visible = true;
 
-        // Check for collisions
-        const pendingObjectGeometry = placementIndicator.geometry;
-        if (!pendingObjectGeometry.boundingBox) {
-            pendingObjectGeometry.computeBoundingBox();
-        }
-        const pendingObjectBox = pendingObjectGeometry.boundingBox.clone();
-        pendingObjectBox.applyMatrix4(placementIndicator.matrixWorld);
-
-        const collision = checkCollision(pendingObjectBox, placedObjects);
+        // Check for collisions and placement rules
+        const collision = !isPlacementValid(placementIndicator, placedObjects);
 
         // Update indicator color based on collision
         if (collision) {
@@ -71,12 +67,35 @@ export function updatePlacementIndicator(event, placementIndicator, groundPlane,
 }
 
 /**
- * Check if placement is valid (no collisions)
+ * Check if placement is valid
+ * Uses the WASM placement rules (overlap, road access, slope, bounds) once the
+ * pending model's size is known, otherwise falls back to a collision check.
+ * The reason for an invalid placement is stored in
+ * placementIndicator.userData.placementMessage
  * @param {THREE.Mesh} placementIndicator - Placement indicator mesh
  * @param {Array<THREE.Object3D>} placedObjects - Array of placed objects
  * @returns {boolean} True if placement is valid
  */
 export function isPlacementValid(placementIndicator, placedObjects) {
+    const pending = window.pendingPlacementModelDetails;
+    if (pending) {
+        const { x, z } = placementIndicator.position;
+        const check = canPlace(pending.modelName, x, z);
+        if (check && check.reason !== 'unknown_model') {
+            placementIndicator.userData.placementMessage = check.ok ? '' : check.message;
+            return check.ok;
+        }
+    }
+
+    const valid = !collidesWithPlaced(placementIndicator, placedObjects);
+    placementIndicator.userData.placementMessage = valid ? '' : OVERLAP_MESSAGE;
+    return valid;
+}
+
+/**
+ * Check the placement indicator's footprint against placed objects
+ */
+function collidesWithPlaced(placementIndicator, placedObjects) {
     const pendingObjectGeometry = placementIndicator.geometry;
     if (!pendingObjectGeometry.boundingBox) {
         pendingObjectGeometry.computeBoundingBox();
@@ -84,5 +103,5 @@ export function isPlacementValid(placementIndicator, placedObjects) {
     const pendingObjectBox = pendingObjectGeometry.boundingBox.clone();
     pendingObjectBox.applyMatrix4(placementIndicator.matrixWorld);
 
-    return !checkCollision(pendingObjectBox, placedObjects);
+    return checkCollision(pendingObjectBox, placedObjects);
 }
@@ -13,9 +13,13 @@ import { createPlacementIndicator, updatePlacementIndicator, isPlacementValid }
 import { updateMovingCars, updateDrivingCamera } from './physics/car.js';
 import { disposeObject } from './utils/disposal.js';
 import { getMouseCoordinates, findRootObject } from './utils/raycaster.js';
-import { updateSpatialGrid, isPhysicsWasmReady } from './utils/physics_wasm.js';
+import { updateSpatialGrid, isPhysicsWasmReady, drainCollisionEvents, purchaseModel, refundModel, demolishPlacement } from './utils/physics_wasm.js';
 import { animateCursors, cleanupInactiveCursors } from './collaborative-cursors.js';
 import { sendCursorUpdate } from './network.js';
+import { updateDaylight } from './scene/daylight.js';
+import { updateWeatherEffects } from './scene/weather.js';
+import { updateGreenery } from './scene/greenery.js';
+import { updateFires } from './scene/fire.js';
 // Mobile touch controls
 import { isMobile } from './utils/device-detect.js';
 import touchControls from './mobile/controls-touch.js';
@@ -344,6 +348,12 @@ export async function animate() {
     // Update moving cars
     updateMovingCars(movingCars, placedObjects, groundPlane, window.drivingCar);
 
+    // Forward this frame's physics impacts to listeners (crash sounds, damage decals)
+    const collisionEvents = drainCollisionEvents();
+    if (collisionEvents.length > 0) {
+        window.dispatchEvent(new CustomEvent('physicsCollisions', { detail: collisionEvents }));
+    }
+
     // Periodically update spatial grid for moving objects
     frameCounter++;
     if (frameCounter >= SPATIAL_GRID_UPDATE_INTERVAL && isPhysicsWasmReady()) {
@@ -356,6 +366,14 @@ export async function animate() {
         updateDrivingCamera(camera, window.drivingCar);
     }
 
+    // Follow the world clock's day/night cycle and the weather
+    if (isPhysicsWasmReady()) {
+        updateDaylight(scene, deltaTime);
+        updateWeatherEffects(scene, camera, deltaTime);
+        updateGreenery(placedObjects, deltaTime);
+        updateFires(scene, placedObjects, deltaTime);
+    }
+
     // Animate collaborative cursors (pulsing effect)
     animateCursors(deltaTime);
 
@@ -415,7 +433,8 @@ function handleDriveModeClick(raycaster) {
 function handlePlaceClick() {
     if (placementIndicator && placementIndicator.visible && window.pendingPlacementModelDetails) {
         if (!isPlacementValid(placementIndicator, placedObjects)) {
-            showNotification('Cannot place model here, overlaps with another object.', 'error');
+            const message = placementIndicator.userData.placementMessage || 'Cannot place model here.';
+            showNotification(message, 'error');
             return;
         }
 
@@ -423,7 +442,14 @@ function handlePlaceClick() {
         const wasAlreadyPlaced = hasModelBeenPlaced(category, modelName);
         
         loadModel(scene, placedObjects, movingCars, category, modelName, placementIndicator.position)
-            .then(() => {
+            .then((object) => {
+                // Pay once loaded, so a failed load costs nothing and the
+                // model's size and price are known
+                if (isPhysicsWasmReady() && !purchaseModel(modelName)) {
+                    removeObject(object);
+                    showNotification('Not enough money', 'error');
+                    return;
+                }
                 // Only show the "Placed" notification if this is the first time placing this model
                 if (!wasAlreadyPlaced) {
                     showNotification(`Placed ${displayName || modelName}`, 'success');
@@ -462,6 +488,20 @@ function handleDelete(object) {
         deactivateDriveModeUI();
     }
 
+    let refund = 0;
+    if (isPhysicsWasmReady() && object.userData.modelName) {
+        // Vehicles are not placements, so they are refunded by model
+        refund = demolishPlacement(object.id) ?? refundModel(object.userData.modelName);
+    }
+
+    removeObject(object);
+    showNotification(refund > 0 ? `Object deleted, refunded ${Math.round(refund)}` : 'Object deleted', 'success');
+}
+
+/**
+ * Take an object out of the scene and the placed and moving lists
+ */
+function removeObject(object) {
     disposeObject(object);
     scene.remove(object);
 
@@ -475,8 +515,6 @@ function handleDelete(object) {
     if (isPhysicsWasmReady()) {
         updateSpatialGrid(placedObjects);
     }
-
-    showNotification('Object deleted', 'success');
 }
 
 // Export loadModel with scene context for backwards compatibility
@@ -0,0 +1,43 @@
+/**
+ * Day/night lighting
+ * Moves the sun light and dims the ambient light to follow the WASM world
+ * clock (the sky color stays as the player picked it)
+ */
+import { getClock } from '../utils/physics_wasm.js';
+
+// Distance of the sun light from the origin along the sun direction
+const SUN_DISTANCE = 25;
+
+// Light intensities at full daylight and at night (lights set up in scene/scene.js)
+const SUN_INTENSITY_DAY = 1.2;
+const AMBIENT_INTENSITY_DAY = 0.6;
+const AMBIENT_INTENSITY_NIGHT = 0.15;
+
+// Seconds between clock reads (the clock changes slowly)
+const DAYLIGHT_UPDATE_INTERVAL = 0.25;
+
+let sinceUpdate = DAYLIGHT_UPDATE_INTERVAL;
+
+/**
+ * Update the scene lighting from the world clock, at most every DAYLIGHT_UPDATE_INTERVAL
+ * @param {THREE.Scene} scene - Three.js scene
+ * @param {number} deltaTime - Seconds since the last frame
+ */
+export function updateDaylight(scene, deltaTime) {
+    sinceUpdate += deltaTime;
+    if (sinceUpdate < DAYLIGHT_UPDATE_INTERVAL) return;
+    sinceUpdate = 0;
+
+    const clock = getClock();
+    if (!clock) return;
+
+    const sun = scene.getObjectByName('sunLight');
+    if (sun) {
+        sun.position.set(clock.sun.x * SUN_DISTANCE, clock.sun.y * SUN_DISTANCE, clock.sun.z * SUN_DISTANCE);
+        sun.intensity = SUN_INTENSITY_DAY * clock.daylight;
+    }
+    const ambient = scene.getObjectByName('ambientLight');
+    if (ambient) {
+        ambient.intensity = AMBIENT_INTENSITY_NIGHT + (AMBIENT_INTENSITY_DAY - AMBIENT_INTENSITY_NIGHT) * clock.daylight;
+    }
+}
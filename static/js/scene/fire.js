@@ -0,0 +1,94 @@
+/**
+ * Building fires
+ * Lights burning buildings with a flickering glow and takes burned-down
+ * buildings out of the scene, following the WASM fire simulation. Fire
+ * trucks are ordinary AI cars and are drawn with the rest of the traffic
+ */
+import * as THREE from '../three.module.js';
+import { getFires, updateSpatialGrid } from '../utils/physics_wasm.js';
+import { disposeObject } from '../utils/disposal.js';
+import { showNotification } from '../ui.js';
+
+// Glow of a burning building: color, intensity range, reach and height above its origin
+const FIRE_COLOR = 0xff6a1a;
+const FIRE_INTENSITY = 2.5;
+const FIRE_FLICKER = 1.2;
+const FIRE_LIGHT_DISTANCE = 4;
+const FIRE_LIGHT_HEIGHT = 1.2;
+
+// Seconds between fire reads
+const FIRE_UPDATE_INTERVAL = 0.5;
+
+let sinceUpdate = FIRE_UPDATE_INTERVAL;
+
+// Placement id -> glow light added to a burning building
+const fireLights = new Map();
+
+/**
+ * Remove a building's fire glow
+ * @param {number} id - Placement id
+ */
+function removeFireLight(id) {
+    const light = fireLights.get(id);
+    if (light) {
+        light.parent?.remove(light);
+        light.dispose();
+        fireLights.delete(id);
+    }
+}
+
+/**
+ * Follow the fire simulation: glow on burning buildings, burned-down
+ * buildings removed, re-reading the fires every FIRE_UPDATE_INTERVAL
+ * @param {THREE.Scene} scene - Three.js scene
+ * @param {Array<THREE.Object3D>} placedObjects - Placed scene objects
+ * @param {number} deltaTime - Seconds since the last frame
+ */
+export function updateFires(scene, placedObjects, deltaTime) {
+    const time = performance.now() / 1000;
+    for (const light of fireLights.values()) {
+        light.intensity = FIRE_INTENSITY + Math.sin(time * 13 + light.id) * Math.sin(time * 7) * FIRE_FLICKER;
+    }
+
+    sinceUpdate += deltaTime;
+    if (sinceUpdate < FIRE_UPDATE_INTERVAL) return;
+    sinceUpdate = 0;
+
+    const state = getFires();
+    if (!state) return;
+
+    const byId = new Map(placedObjects.map(object => [object.id, object]));
+    const burning = new Set();
+    for (const fire of state.fires) {
+        burning.add(fire.id);
+        const building = byId.get(fire.id);
+        if (building && !fireLights.has(fire.id)) {
+            const light = new THREE.PointLight(FIRE_COLOR, FIRE_INTENSITY, FIRE_LIGHT_DISTANCE);
+            light.position.set(0, FIRE_LIGHT_HEIGHT, 0);
+            building.add(light);
+            fireLights.set(fire.id, light);
+        }
+        if (building) {
+            building.userData.burning = fire.progress;
+        }
+    }
+    for (const id of [...fireLights.keys()]) {
+        if (!burning.has(id)) {
+            removeFireLight(id);
+            const building = byId.get(id);
+            if (building) delete building.userData.burning;
+        }
+    }
+
+    if (state.destroyed.length === 0) return;
+    for (const id of state.destroyed) {
+        const building = byId.get(id);
+        removeFireLight(id);
+        if (!building) continue;
+        disposeObject(building);
+        scene.remove(building);
+        placedObjects.splice(placedObjects.indexOf(building), 1);
+    }
+    updateSpatialGrid(placedObjects);
+    showNotification(`${state.destroyed.length} building(s) burned down`, 'error');
+}
@@ -0,0 +1,43 @@
+/**
+ * Greenery growth
+ * Scales tree and park models with the growth of their WASM plantings, so
+ * saplings are drawn short and grow to full height as they mature. Only the
+ * height changes, leaving the footprint the placement validator sees alone
+ */
+import { getGreenery } from '../utils/physics_wasm.js';
+
+// Model height of a just-planted sapling, as a share of its full height
+const SAPLING_HEIGHT = 0.4;
+
+// Seconds between growth reads (growth takes game days)
+const GREENERY_UPDATE_INTERVAL = 1;
+
+let sinceUpdate = GREENERY_UPDATE_INTERVAL;
+
+/**
+ * Update planted models from their growth, at most every GREENERY_UPDATE_INTERVAL
+ * Sets userData.growth and userData.growthStage on each planted object
+ * @param {Array<THREE.Object3D>} placedObjects - Placed scene objects
+ * @param {number} deltaTime - Seconds since the last frame
+ */
+export function updateGreenery(placedObjects, deltaTime) {
+    sinceUpdate += deltaTime;
+    if (sinceUpdate < GREENERY_UPDATE_INTERVAL) return;
+    sinceUpdate = 0;
+
+    const greenery = getGreenery();
+    if (!greenery || greenery.plantings.length === 0) return;
+
+    const plantings = new Map(greenery.plantings.map(p => [p.id, p]));
+    for (const object of placedObjects) {
+        const planting = plantings.get(object.id);
+        if (!planting) continue;
+
+        if (object.userData.fullScaleY === undefined) {
+            object.userData.fullScaleY = object.scale.y;
+        }
+        object.userData.growth = planting.growth;
+        object.userData.growthStage = planting.stage;
+        object.scale.y = object.userData.fullScaleY * (SAPLING_HEIGHT + (1 - SAPLING_HEIGHT) * planting.growth);
+    }
+}
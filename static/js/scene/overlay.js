@@ -0,0 +1,200 @@
+/**
+ * Town data overlays
+ * Renders a WASM town grid (service coverage, land value, noise) as a
+ * translucent colored layer just above the ground, or road condition as
+ * colored lines along each road segment
+ */
+import * as THREE from '../three.module.js';
+import { getCoverageGrids, getLandValueGrid, getNoiseGrid, getRoadWear } from '../utils/physics_wasm.js';
+
+// Height of the overlay above the ground plane (avoids z-fighting)
+const OVERLAY_HEIGHT = 0.02;
+const OVERLAY_OPACITY = 0.45;
+
+// Height of road condition lines, above the road tiles
+const ROAD_OVERLAY_HEIGHT = 0.12;
+
+// Seconds between refreshes while an overlay is shown (noise changes live)
+const OVERLAY_REFRESH_INTERVAL = 1.0;
+
+/**
+ * Overlays the user can pick, with how to read each grid
+ * higherIsBetter: 1 renders green (served, valuable, quiet), 0 red
+ * segments: drawn per road segment rather than from a town grid
+ */
+export const OVERLAYS = {
+    schools: { label: 'Schools', higherIsBetter: true },
+    healthcare: { label: 'Healthcare', higherIsBetter: true },
+    library: { label: 'Library', higherIsBetter: true },
+    markets: { label: 'Stores', higherIsBetter: true },
+    green_space: { label: 'Green Space', higherIsBetter: true },
+    underserved: { label: 'Underserved Areas', higherIsBetter: false },
+    land_value: { label: 'Land Value', higherIsBetter: true },
+    noise: { label: 'Noise', higherIsBetter: false },
+    road_condition: { label: 'Road Condition', higherIsBetter: true, segments: true },
+};
+
+let overlayMesh = null;
+let roadLines = null;
+let activeOverlay = null;
+let refreshTimer = null;
+
+/**
+ * Read the grid for an overlay from WASM
+ * @param {string} name - Overlay key from OVERLAYS
+ * @returns {Object|null} {width, depth, cellSize, originX, originZ, cells}
+ */
+function readOverlayGrid(name) {
+    if (name === 'land_value') return getLandValueGrid();
+    if (name === 'noise') return getNoiseGrid();
+
+    const grids = getCoverageGrids();
+    if (!grids) return null;
+    const cells = name === 'underserved' ? grids.underserved : grids.services[name];
+    return cells ? { ...grids, cells } : null;
+}
+
+/**
+ * Map a 0-1 score to a red (bad) - yellow - green (good) color
+ */
+function rampColor(good) {
+    const t = Math.min(Math.max(good, 0), 1);
+    return t < 0.5
+        ? [255, Math.round(510 * t), 0]
+        : [Math.round(510 * (1 - t)), 255, 0];
+}
+
+/**
+ * Create the overlay mesh, or resize it to a new grid layout
+ */
+function ensureOverlayMesh(scene, grid) {
+    const width = grid.width * grid.cellSize;
+    const depth = grid.depth * grid.cellSize;
+    const texture = overlayMesh && overlayMesh.material.map;
+    if (overlayMesh && texture.image.width === grid.width && texture.image.height === grid.depth &&
+        overlayMesh.userData.width === width && overlayMesh.userData.depth === depth) {
+        return overlayMesh;
+    }
+    disposeOverlayMesh(scene);
+
+    const data = new Uint8Array(grid.width * grid.depth * 4);
+    const map = new THREE.DataTexture(data, grid.width, grid.depth, THREE.RGBAFormat);
+    map.magFilter = THREE.NearestFilter;
+    map.minFilter = THREE.NearestFilter;
+
+    const material = new THREE.MeshBasicMaterial({
+        map,
+        transparent: true,
+        opacity: OVERLAY_OPACITY,
+        depthWrite: false,
+    });
+    overlayMesh = new THREE.Mesh(new THREE.PlaneGeometry(width, depth), material);
+    overlayMesh.rotation.x = -Math.PI / 2; // Lay flat; texture row 0 ends up at +Z
+    overlayMesh.position.set(grid.originX + width / 2, OVERLAY_HEIGHT, grid.originZ + depth / 2);
+    overlayMesh.userData = { width, depth };
+    overlayMesh.renderOrder = 1;
+    scene.add(overlayMesh);
+    return overlayMesh;
+}
+
+/**
+ * Remove and dispose the overlay mesh
+ */
+function disposeOverlayMesh(scene) {
+    if (!overlayMesh) return;
+    scene.remove(overlayMesh);
+    overlayMesh.geometry.dispose();
+    overlayMesh.material.map.dispose();
+    overlayMesh.material.dispose();
+    overlayMesh = null;
+}
+
+/**
+ * Remove and dispose the road condition lines
+ */
+function disposeRoadLines(scene) {
+    if (!roadLines) return;
+    scene.remove(roadLines);
+    roadLines.geometry.dispose();
+    roadLines.material.dispose();
+    roadLines = null;
+}
+
+/**
+ * Redraw the road condition lines, one per segment colored by its condition
+ * @param {THREE.Scene} scene - Three.js scene
+ */
+function refreshRoadOverlay(scene) {
+    const wear = getRoadWear();
+    if (!wear) return;
+
+    const positions = new Float32Array(wear.segments.length * 6);
+    const colors = new Float32Array(wear.segments.length * 6);
+    wear.segments.forEach((segment, i) => {
+        positions.set([segment.x1, ROAD_OVERLAY_HEIGHT, segment.z1, segment.x2, ROAD_OVERLAY_HEIGHT, segment.z2], i * 6);
+        const [r, g, b] = rampColor(segment.condition).map(c => c / 255);
+        colors.set([r, g, b, r, g, b], i * 6);
+    });
+
+    disposeRoadLines(scene);
+    const geometry = new THREE.BufferGeometry();
+    geometry.setAttribute('position', new THREE.BufferAttribute(positions, 3));
+    geometry.setAttribute('color', new THREE.BufferAttribute(colors, 3));
+    roadLines = new THREE.LineSegments(geometry, new THREE.LineBasicMaterial({ vertexColors: true, depthWrite: false }));
+    roadLines.renderOrder = 1;
+    scene.add(roadLines);
+}
+
+/**
+ * Redraw the active overlay from the current WASM grid
+ * @param {THREE.Scene} scene - Three.js scene
+ */
+export function refreshTownOverlay(scene) {
+    if (!activeOverlay) return;
+    if (OVERLAYS[activeOverlay].segments) {
+        refreshRoadOverlay(scene);
+        return;
+    }
+    const grid = readOverlayGrid(activeOverlay);
+    if (!grid) return;
+
+    const mesh = ensureOverlayMesh(scene, grid);
+    const texture = mesh.material.map;
+    const data = texture.image.data;
+    const { higherIsBetter } = OVERLAYS[activeOverlay];
+
+    for (let cz = 0; cz < grid.depth; cz++) {
+        // Grid rows run along +Z; the flat plane shows texture row 0 at +Z
+        const row = grid.depth - 1 - cz;
+        for (let cx = 0; cx < grid.width; cx++) {
+            const value = grid.cells[cz * grid.width + cx];
+            const [r, g, b] = rampColor(higherIsBetter ? value : 1 - value);
+            const o = (row * grid.width + cx) * 4;
+            data[o] = r;
+            data[o + 1] = g;
+            data[o + 2] = b;
+            data[o + 3] = 255;
+        }
+    }
+    texture.needsUpdate = true;
+}
+
+/**
+ * Show an overlay, or hide overlays with null
+ * @param {THREE.Scene} scene - Three.js scene
+ * @param {string|null} name - Overlay key from OVERLAYS
+ */
+export function setTownOverlay(scene, name) {
+    if (refreshTimer) {
+        clearInterval(refreshTimer);
+        refreshTimer = null;
+    }
+
+    activeOverlay = OVERLAYS[name] ? name : null;
+    disposeOverlayMesh(scene);
+    disposeRoadLines(scene);
+    if (!activeOverlay) return;
+
+    refreshTownOverlay(scene);
+    refreshTimer = setInterval(() => refreshTownOverlay(scene), OVERLAY_REFRESH_INTERVAL * 1000);
+}
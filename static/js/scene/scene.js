@@ -8,6 +8,9 @@
  */
 import * as THREE from '../three.module.js';
 
+// Side length of the square ground plane (world units), also the drivable area
+export const GROUND_SIZE = 20;
+
 /**
  * Create an HDR-capable environment map with PMREM prefiltering
  * Uses procedural gradient enhanced for HDR range
@@ -146,14 +149,14 @@ export function initScene(container) {
     }, 100);
 
     // Create background
-    const backgroundGeometry = new THREE.PlaneGeometry(20, 20);
+    const backgroundGeometry = new THREE.PlaneGeometry(GROUND_SIZE, GROUND_SIZE);
     const backgroundMaterial = new THREE.MeshBasicMaterial({ color: 0x87CEEB }); // Sky blue
     const backgroundMesh = new THREE.Mesh(backgroundGeometry, backgroundMaterial);
     backgroundMesh.rotation.x = -Math.PI / 2;
     scene.add(backgroundMesh);
 
     // Add ground plane with enhanced material properties
-    const planeGeometry = new THREE.PlaneGeometry(20, 20);
+    const planeGeometry = new THREE.PlaneGeometry(GROUND_SIZE, GROUND_SIZE);
     const planeMaterial = new THREE.MeshStandardMaterial({
         color: 0x2E8B57, // Dark green
         roughness: 0.8,  // Slightly rough for grass/ground
@@ -166,11 +169,13 @@ export function initScene(container) {
 
     // Add lights with enhanced settings for better PBR
     const ambientLight = new THREE.AmbientLight(0xffffff, 0.6); // Slightly brighter ambient
+    ambientLight.name = 'ambientLight'; // Dimmed at night (scene/daylight.js)
     scene.add(ambientLight);
 
     const directionalLight = new THREE.DirectionalLight(0xffffff, 1.2); // Brighter directional
     directionalLight.position.set(10, 20, 10);
     directionalLight.castShadow = false; // Shadows disabled for performance
+    directionalLight.name = 'sunLight'; // Follows the world clock (scene/daylight.js)
     scene.add(directionalLight);
 
     return {
@@ -181,6 +186,50 @@ export function initScene(container) {
     };
 }
 
+// Color and opacity of the water surface drawn over low terrain
+const WATER_COLOR = 0x3a78b5;
+const WATER_OPACITY = 0.75;
+
+/**
+ * Reshape the ground plane to follow a heightmap, with a water surface at the
+ * terrain's water level
+ * @param {THREE.Mesh} groundPlane - Ground mesh from setupScene
+ * @param {Object} terrain - {width, depth, cellSize, originX, originZ, waterLevel, heights} from generateTerrain
+ */
+export function applyTerrainToGround(groundPlane, terrain) {
+    const sizeX = (terrain.width - 1) * terrain.cellSize;
+    const sizeZ = (terrain.depth - 1) * terrain.cellSize;
+    const geometry = new THREE.PlaneGeometry(sizeX, sizeZ, terrain.width - 1, terrain.depth - 1);
+
+    // Plane vertices run row by row from -Z to +Z once the plane is laid
+    // flat, matching the heightmap's row-major order; local Z becomes height
+    const position = geometry.attributes.position;
+    for (let i = 0; i < position.count; i++) {
+        position.setZ(i, terrain.heights[i]);
+    }
+    position.needsUpdate = true;
+    geometry.computeVertexNormals();
+
+    groundPlane.geometry.dispose();
+    groundPlane.geometry = geometry;
+    groundPlane.position.set(terrain.originX + sizeX / 2, 0, terrain.originZ + sizeZ / 2);
+
+    const parent = groundPlane.parent;
+    if (!parent || terrain.waterLevel === undefined) return;
+    let water = parent.getObjectByName('waterPlane');
+    if (!water) {
+        water = new THREE.Mesh(
+            new THREE.PlaneGeometry(1, 1),
+            new THREE.MeshStandardMaterial({ color: WATER_COLOR, transparent: true, opacity: WATER_OPACITY })
+        );
+        water.name = 'waterPlane';
+        water.rotation.x = -Math.PI / 2;
+        parent.add(water);
+    }
+    water.scale.set(sizeX, sizeZ, 1);
+    water.position.set(groundPlane.position.x, terrain.waterLevel, groundPlane.position.z);
+}
+
 /**
  * Handle window resize events
  * @param {THREE.Camera} camera - Three.js camera
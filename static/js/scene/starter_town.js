@@ -0,0 +1,124 @@
+/**
+ * Starter town generator
+ * Lays a small grid of road tiles when the scene has none, then fills the road
+ * frontage with buildings planned in WASM, which applies the same placement
+ * rules (overlap, zoning, road access, slope, budget) as placing by hand
+ */
+import * as THREE from '../three.module.js';
+import { scene, placedObjects, loadModelToScene } from '../scene.js';
+import { updateBoundingBox } from '../models/collision.js';
+import {
+    generateRoadNetwork,
+    getRoadGraph,
+    planLots,
+    purchaseModel,
+    registerModelSize,
+    updateSpatialGrid,
+} from '../utils/physics_wasm.js';
+
+// Buildings a starter town may use (every model with a town use, see MODEL_USES)
+const STARTER_BUILDINGS = [
+    'house.gltf',
+    'building_A.gltf',
+    'building_B.gltf',
+    'building_C.gltf',
+    'building_E.gltf',
+    'building_H.gltf',
+    'medical_facility.gltf',
+];
+
+// Half-size of the generated road grid, and its block size in road tiles
+const STARTER_EXTENT = 7;
+const STARTER_BLOCK_TILES = 4;
+
+/**
+ * Load a model, measure its unrotated footprint for the placement validator,
+ * then take it out of the scene again (the cached model stays loaded)
+ * @returns {Promise<THREE.Vector3>} Footprint size
+ */
+async function measureModel(category, modelName) {
+    const object = await loadModelToScene(category, modelName, new THREE.Vector3(0, 0, 0));
+    const box = new THREE.Box3().setFromObject(object);
+    registerModelSize(modelName, category, box);
+
+    scene.remove(object);
+    placedObjects.splice(placedObjects.indexOf(object), 1);
+    return box.getSize(new THREE.Vector3());
+}
+
+/**
+ * Place a model at a position and Y rotation, paying for it
+ * @returns {Promise<boolean>} False if the treasury could not afford it
+ */
+async function placeModel(category, modelName, x, z, rotation) {
+    if (!purchaseModel(modelName)) {
+        return false;
+    }
+    const object = await loadModelToScene(category, modelName, new THREE.Vector3(x, 0, z));
+    object.rotation.y = rotation;
+    updateBoundingBox(object);
+    return true;
+}
+
+/**
+ * Lay road tiles along a generated grid road network: a junction on every
+ * node and straight tiles between them
+ * @returns {Promise<number>} Tiles placed
+ */
+async function layStarterRoads(seed) {
+    const size = await measureModel('street', 'road_straight.gltf');
+    await measureModel('street', 'road_junction.gltf');
+    const tile = Math.max(size.x, size.z);
+
+    if (!generateRoadNetwork('grid', { seed, extent: STARTER_EXTENT, spacing: tile * STARTER_BLOCK_TILES })) {
+        return 0;
+    }
+    const graph = getRoadGraph();
+    const nodes = new Map(graph.nodes.map(node => [node.id, node]));
+
+    let placed = 0;
+    for (const node of graph.nodes) {
+        if (await placeModel('street', 'road_junction.gltf', node.x, node.z, 0)) placed++;
+    }
+    for (const edge of graph.edges) {
+        if (edge.from > edge.to) continue; // Each two-way road once
+        const a = nodes.get(edge.from);
+        const b = nodes.get(edge.to);
+        const steps = Math.round(edge.length / tile);
+        const rotation = Math.abs(b.x - a.x) > Math.abs(b.z - a.z) ? Math.PI / 2 : 0;
+        for (let i = 1; i < steps; i++) {
+            const t = i / steps;
+            if (await placeModel('street', 'road_straight.gltf', a.x + (b.x - a.x) * t, a.z + (b.z - a.z) * t, rotation)) {
+                placed++;
+            }
+        }
+    }
+    updateSpatialGrid(placedObjects);
+    return placed;
+}
+
+/**
+ * Generate a starter town: roads first if there are none, then buildings on
+ * every open lot along them
+ * @param {number} seed - Seed for the road layout and building choice
+ * @returns {Promise<Object>} {roads, buildings} placed
+ */
+export async function generateStarterTown(seed = Math.floor(Math.random() * 0x7fffffff)) {
+    let roads = 0;
+    const hasRoads = placedObjects.some(obj => (obj.userData.modelName || '').startsWith('road_'));
+    if (!hasRoads) {
+        roads = await layStarterRoads(seed);
+    }
+
+    for (const modelName of STARTER_BUILDINGS) {
+        await measureModel('buildings', modelName);
+    }
+    updateSpatialGrid(placedObjects);
+
+    let buildings = 0;
+    for (const lot of planLots({ seed, models: STARTER_BUILDINGS })) {
+        if (await placeModel('buildings', lot.model, lot.x, lot.z, lot.rotation)) buildings++;
+    }
+    updateSpatialGrid(placedObjects);
+    return { roads, buildings };
+}
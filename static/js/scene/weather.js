@@ -0,0 +1,88 @@
+/**
+ * Weather particles
+ * Draws rain or snow around the camera to match the WASM weather, so what the
+ * player sees falling is what the car physics is driving in
+ */
+import * as THREE from '../three.module.js';
+import { getWeather } from '../utils/physics_wasm.js';
+
+// Particles at full intensity, and the box around the camera they fall in
+const MAX_PARTICLES = 4000;
+const FIELD_SIZE = 40;
+const FIELD_HEIGHT = 20;
+
+// Fall speed (units per second) and look of each precipitation type
+const PRECIPITATION = {
+    rain: { speed: 18, drift: 0.5, color: 0x9fb7d0, size: 0.08, opacity: 0.6 },
+    snow: { speed: 2.5, drift: 1.2, color: 0xffffff, size: 0.18, opacity: 0.9 },
+};
+
+// Seconds between weather reads (transitions take game hours)
+const WEATHER_UPDATE_INTERVAL = 0.25;
+
+let particles = null;
+let weather = null;
+let sinceUpdate = WEATHER_UPDATE_INTERVAL;
+
+/**
+ * Create the particle system, hidden until rain or snow falls
+ * @param {THREE.Scene} scene - Three.js scene
+ */
+function createParticles(scene) {
+    const positions = new Float32Array(MAX_PARTICLES * 3);
+    for (let i = 0; i < MAX_PARTICLES; i++) {
+        positions[i * 3] = (Math.random() - 0.5) * FIELD_SIZE;
+        positions[i * 3 + 1] = Math.random() * FIELD_HEIGHT;
+        positions[i * 3 + 2] = (Math.random() - 0.5) * FIELD_SIZE;
+    }
+    const geometry = new THREE.BufferGeometry();
+    geometry.setAttribute('position', new THREE.BufferAttribute(positions, 3));
+    geometry.setDrawRange(0, 0);
+
+    const material = new THREE.PointsMaterial({ transparent: true, depthWrite: false });
+    particles = new THREE.Points(geometry, material);
+    particles.name = 'weatherParticles';
+    particles.frustumCulled = false;
+    particles.visible = false;
+    scene.add(particles);
+}
+
+/**
+ * Move rain or snow for a frame, re-reading the weather every WEATHER_UPDATE_INTERVAL
+ * @param {THREE.Scene} scene - Three.js scene
+ * @param {THREE.Camera} camera - Camera the particles follow
+ * @param {number} deltaTime - Seconds since the last frame
+ */
+export function updateWeatherEffects(scene, camera, deltaTime) {
+    if (!particles) createParticles(scene);
+
+    sinceUpdate += deltaTime;
+    if (sinceUpdate >= WEATHER_UPDATE_INTERVAL) {
+        sinceUpdate = 0;
+        weather = getWeather();
+        const look = weather && PRECIPITATION[weather.type];
+        particles.visible = Boolean(look) && weather.intensity > 0;
+        if (particles.visible) {
+            particles.material.color.setHex(look.color);
+            particles.material.size = look.size;
+            particles.material.opacity = look.opacity;
+            particles.geometry.setDrawRange(0, Math.floor(MAX_PARTICLES * weather.intensity));
+        }
+    }
+    if (!particles.visible) return;
+
+    const look = PRECIPITATION[weather.type];
+    const positions = particles.geometry.attributes.position.array;
+    const count = particles.geometry.drawRange.count;
+    const time = performance.now() / 1000;
+    for (let i = 0; i < count; i++) {
+        const y = i * 3 + 1;
+        positions[y] -= look.speed * deltaTime;
+        positions[y - 1] += Math.sin(time + i) * look.drift * deltaTime;
+        if (positions[y] < 0) {
+            positions[y] += FIELD_HEIGHT;
+        }
+    }
+    particles.geometry.attributes.position.needsUpdate = true;
+    particles.position.set(camera.position.x, 0, camera.position.z);
+}
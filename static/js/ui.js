@@ -2,6 +2,10 @@ import { saveSceneToServer, loadSceneFromServer } from './network.js';
 import { loadModel, scene, placedObjects, renderer, groundPlane, disposeObject, movingCars } from './scene.js'; // Added movingCars
 import { getActiveLoaderCount } from './models/loader.js'; // Import loader count tracker
 import { cleanupJoystick, initJoystick } from './joystick.js';
+import { setTownOverlay } from './scene/overlay.js';
+import { applyTerrainToGround } from './scene/scene.js';
+import { generateTerrain } from './utils/physics_wasm.js';
+import { generateStarterTown } from './scene/starter_town.js';
 
 let currentMode = 'place';
 window.selectedObject = null; // For edit mode
@@ -329,6 +333,8 @@ export function initUI() {
     });
     // Clear, save, load buttons
     document.getElementById('clear-scene').addEventListener('click', onClearScene);
+    document.getElementById('new-town').addEventListener('click', onNewTown);
+    document.getElementById('starter-town').addEventListener('click', onStarterTown);
     document.getElementById('save-scene').addEventListener('click', onSaveScene);
     document.getElementById('load-scene').addEventListener('click', onLoadScene);
     // Town name display/input
@@ -349,6 +355,12 @@ export function initUI() {
     document.getElementById('skyColorPicker').addEventListener('input', e => setSkyColor(e.target.value));
     document.getElementById('groundColorPicker').addEventListener('input', e => setGroundColor(e.target.value));
 
+    // Town data overlay (service coverage, land value, noise)
+    const overlaySelect = document.getElementById('overlaySelect');
+    if (overlaySelect) {
+        overlaySelect.addEventListener('change', e => setTownOverlay(scene, e.target.value || null));
+    }
+
     // Mode button handling
     document.querySelectorAll('.mode-button').forEach(btn =>
         btn.addEventListener('click', (e) => {
@@ -424,6 +436,41 @@ async function onClearScene() {
     }
 }
 
+async function onNewTown() {
+    if (!window.confirm("Start a new town? The current scene will be cleared and a new landscape generated.")) {
+        return;
+    }
+    placedObjects.forEach(obj => {
+        disposeObject(obj);
+        scene.remove(obj);
+    });
+    placedObjects.length = 0;
+    movingCars.length = 0;
+
+    const seed = Math.floor(Math.random() * 0x7fffffff);
+    const terrain = generateTerrain({ seed });
+    if (!terrain) {
+        showNotification('Scene cleared (terrain generation needs the physics module)', 'info');
+        return;
+    }
+    applyTerrainToGround(groundPlane, terrain);
+    showNotification(`New town generated (seed ${seed})`, 'success');
+}
+
+async function onStarterTown() {
+    try {
+        const { roads, buildings } = await generateStarterTown();
+        if (roads === 0 && buildings === 0) {
+            showNotification('No room for a starter town here', 'info');
+            return;
+        }
+        showNotification(`Starter town generated: ${roads} road tiles, ${buildings} buildings`, 'success');
+    } catch (err) {
+        console.error('Error generating starter town:', err);
+        showNotification('Error generating starter town', 'error');
+    }
+}
+
 async function onSaveScene() {
     try {
         const sceneDataArray = placedObjects.map(obj => ({
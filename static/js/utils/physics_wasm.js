@@ -145,6 +145,10 @@ export function updateSpatialGrid(objects) {
             return false;
         }
 
+        // Keep placements (and their car physics obstacles) and the road graph in sync with the scene
+        syncPlacements(objects);
+        syncRoadNetwork(objects);
+
         return result;
     } catch (error) {
         console.error('Error updating spatial grid:', error);
@@ -155,6 +159,2750 @@ export function updateSpatialGrid(objects) {
     }
 }
 
+/**
+ * Categories the car physics treats as solid static obstacles, and the
+ * collider shape used for each. Round shapes keep sidewalks drivable instead
+ * of blocking the whole bounding box; trees only collide with their trunk
+ */
+const STATIC_COLLIDER_SHAPES = {
+    buildings: { shape: 'box' },
+    props: { shape: 'round', radiusScale: 1.0 },
+    park: { shape: 'round', radiusScale: 1.0 },
+    trees: { shape: 'round', radiusScale: 0.3 },
+};
+
+// Footprints longer than this ratio become capsules instead of circles
+const CAPSULE_ASPECT_RATIO = 1.5;
+
+/**
+ * Check whether objects of a category are resolved by the WASM car physics
+ *
+ * @param {string} category - Object category (model directory)
+ * @returns {boolean} True if the category is registered as static colliders
+ */
+export function isStaticColliderCategory(category) {
+    return Object.prototype.hasOwnProperty.call(STATIC_COLLIDER_SHAPES, category);
+}
+
+/**
+ * Build a static collider descriptor from a world-space bounding box
+ */
+function serializeCollider(object, index) {
+    if (!object.userData.boundingBox) {
+        object.userData.boundingBox = new THREE.Box3().setFromObject(object);
+    }
+
+    // Bounding boxes are already in world space; bbox Y holds world Z
+    const box = object.userData.boundingBox;
+    const id = object.id || index;
+    const spec = STATIC_COLLIDER_SHAPES[object.userData.category];
+    const top = box.max.y; // Lets raycasts pass over low props
+    if (spec.shape === 'box') {
+        return { id, top, bbox: { minX: box.min.x, minY: box.min.z, maxX: box.max.x, maxY: box.max.z } };
+    }
+
+    const cx = (box.min.x + box.max.x) / 2;
+    const cz = (box.min.z + box.max.z) / 2;
+    const halfX = (box.max.x - box.min.x) / 2;
+    const halfZ = (box.max.z - box.min.z) / 2;
+    const radius = Math.min(halfX, halfZ) * spec.radiusScale;
+
+    if (Math.max(halfX, halfZ) > Math.min(halfX, halfZ) * CAPSULE_ASPECT_RATIO) {
+        // Segment runs along the long axis, inset by the short half-extent
+        const reachX = halfX > halfZ ? halfX - halfZ : 0;
+        const reachZ = halfZ > halfX ? halfZ - halfX : 0;
+        return {
+            id,
+            shape: 'capsule',
+            x1: cx - reachX, z1: cz - reachZ,
+            x2: cx + reachX, z2: cz + reachZ,
+            radius,
+            top,
+        };
+    }
+
+    return { id, shape: 'circle', x: cx, z: cz, radius, top };
+}
+
+/**
+ * Register every placed scene object with the WASM occupancy grid
+ * Solid categories carry a static collider, so cars stop and slide along
+ * obstacles inside wasmUpdateCarPhysics. Vehicles are moving, not placed
+ *
+ * @param {Array<THREE.Object3D>} objects - Array of scene objects
+ * @returns {boolean} Success status
+ */
+export function syncPlacements(objects) {
+    if (!validateWasmFunction('wasmSetPlacements')) {
+        return false;
+    }
+
+    const placements = [];
+    objects.forEach((object, idx) => {
+        const category = object.userData.category || 'unknown';
+        if (category === 'vehicles') return;
+        if (!object.userData.boundingBox) {
+            object.userData.boundingBox = new THREE.Box3().setFromObject(object);
+        }
+
+        const box = object.userData.boundingBox;
+        registerModelSize(object.userData.modelName, category, box);
+        placements.push({
+            id: object.id || idx,
+            model: object.userData.modelName || '',
+            category,
+            bbox: { minX: box.min.x, minY: box.min.z, maxX: box.max.x, maxY: box.max.z },
+            collider: isStaticColliderCategory(category) ? serializeCollider(object, idx) : undefined,
+        });
+    });
+
+    try {
+        return !!window.wasmSetPlacements(placements);
+    } catch (error) {
+        console.error('Error syncing placements:', error);
+        return false;
+    }
+}
+
+// Model names already described to canPlace
+const registeredModels = new Set();
+
+/**
+ * What each building model is used for, deciding which zones it may be built
+ * in (see DefaultZoneRules in physics_zoning.go). Matches the town categories
+ * in category_status.js
+ */
+const MODEL_USES = {
+    'house.gltf': 'housing',
+    'building_A.gltf': 'school',
+    'building_D.gltf': 'school',
+    'building_B.gltf': 'library',
+    'building_F.gltf': 'library',
+    'building_E.gltf': 'market',
+    'building_G.gltf': 'market',
+    'building_C.gltf': 'industry',
+    'building_H.gltf': 'office',
+    'medical_facility.gltf': 'healthcare',
+};
+
+/**
+ * Look up a model's use, treating "_withoutBase" variants like the base model
+ *
+ * @param {string} modelName - Model file name
+ * @returns {string} Use name, or '' for models without zoning rules
+ */
+export function getModelUse(modelName) {
+    return MODEL_USES[(modelName || '').replace('_withoutBase', '')] || '';
+}
+
+/**
+ * Describe a model's footprint to the WASM placement validator the first
+ * time it is seen, using its world-space bounding box (assumes it is unrotated)
+ */
+export function registerModelSize(modelName, category, box) {
+    if (!modelName || registeredModels.has(modelName) || !validateWasmFunction('wasmRegisterModel')) {
+        return;
+    }
+    const ok = window.wasmRegisterModel(modelName, {
+        category,
+        use: getModelUse(modelName),
+        width: box.max.x - box.min.x,
+        depth: box.max.z - box.min.z,
+    });
+    if (ok) {
+        registeredModels.add(modelName);
+    }
+}
+
+/**
+ * Check whether a model can be placed at a position
+ *
+ * @param {string} modelName - Model file name
+ * @param {number} x - World X of the model center
+ * @param {number} z - World Z of the model center
+ * @param {number} rotation - Y rotation in radians
+ * @param {Object} size - Optional {width, depth, category} to register an unseen model
+ * @returns {Object|null} {ok, reason, message, conflicts, footprint}, or null without WASM
+ */
+export function canPlace(modelName, x, z, rotation = 0, size = null) {
+    if (!validateWasmFunction('wasmCanPlace')) {
+        return null;
+    }
+    if (size && !registeredModels.has(modelName) && validateWasmFunction('wasmRegisterModel')) {
+        if (window.wasmRegisterModel(modelName, { use: getModelUse(modelName), ...size })) {
+            registeredModels.add(modelName);
+        }
+    }
+    return window.wasmCanPlace(modelName, x, z, rotation);
+}
+
+/**
+ * Plan buildings along the open sides of road tiles. Each lot suits the zone
+ * under it and passes canPlace; nothing is placed
+ *
+ * @param {Object} options - {seed, models: string[], max_lots}; models defaults to every registered building
+ * @returns {Array<Object>} [{model, x, z, rotation, zone, cost}], empty without WASM
+ */
+export function planLots(options = {}) {
+    if (!validateWasmFunction('wasmPlanLots')) {
+        return [];
+    }
+    return window.wasmPlanLots(options);
+}
+
+/**
+ * Zone every cell under a world-space rectangle
+ *
+ * @param {number} minX - Rectangle min X
+ * @param {number} minZ - Rectangle min Z
+ * @param {number} maxX - Rectangle max X
+ * @param {number} maxZ - Rectangle max Z
+ * @param {string} zone - 'none', 'residential', 'commercial' or 'industrial'
+ * @returns {number} Number of cells painted
+ */
+export function paintZone(minX, minZ, maxX, maxZ, zone) {
+    if (!validateWasmFunction('wasmPaintZone')) {
+        return 0;
+    }
+    return window.wasmPaintZone({ minX, minY: minZ, maxX, maxY: maxZ }, zone);
+}
+
+/**
+ * Get how much of each zone is designated and built on
+ *
+ * @returns {Object|null} {residential: {cells, area, built, fraction}, ...}, or null without WASM
+ */
+export function getZoneCoverage() {
+    if (!validateWasmFunction('wasmGetZoneCoverage')) {
+        return null;
+    }
+    return window.wasmGetZoneCoverage();
+}
+
+/**
+ * Create a district, with policies that change nothing until set
+ *
+ * @param {string} name - District name
+ * @returns {number|null} District id, or null without WASM
+ */
+export function createDistrict(name) {
+    if (!validateWasmFunction('wasmCreateDistrict')) {
+        return null;
+    }
+    return window.wasmCreateDistrict(name);
+}
+
+/**
+ * Delete a district, taking its cells out of any district
+ *
+ * @param {number} id - District id
+ * @returns {boolean} False for an unknown district
+ */
+export function removeDistrict(id) {
+    if (!validateWasmFunction('wasmRemoveDistrict')) {
+        return false;
+    }
+    return window.wasmRemoveDistrict(id);
+}
+
+/**
+ * Put every cell under a world-space rectangle in a district (undoable)
+ *
+ * @param {number} minX - Rectangle min X
+ * @param {number} minZ - Rectangle min Z
+ * @param {number} maxX - Rectangle max X
+ * @param {number} maxZ - Rectangle max Z
+ * @param {number} id - District id, 0 to take the cells out of any district
+ * @returns {number} Number of cells painted (0 for an unknown district)
+ */
+export function paintDistrict(minX, minZ, maxX, maxZ, id) {
+    if (!validateWasmFunction('wasmPaintDistrict')) {
+        return 0;
+    }
+    return window.wasmPaintDistrict({ minX, minY: minZ, maxX, maxY: maxZ }, id);
+}
+
+/**
+ * Rename a district or change its policies: speed_factor scales road speed
+ * limits (0.25-2), tax_rate the town's taxes (0-3), and industry_ban keeps
+ * out uses restricted to industrial zones
+ *
+ * @param {number} id - District id
+ * @param {Object} options - {name?, speed_factor?, tax_rate?, industry_ban?}
+ * @returns {boolean} False for an unknown district or non-finite policies
+ */
+export function setDistrict(id, options) {
+    if (!validateWasmFunction('wasmSetDistrict')) {
+        return false;
+    }
+    return window.wasmSetDistrict(id, options);
+}
+
+/**
+ * Get every district with its policies and stats
+ *
+ * @returns {Array<Object>|null} [{id, name, speed_factor, tax_rate, industry_ban, cells, area, buildings, residents, jobs, land_value, taxes}], or null without WASM
+ */
+export function getDistricts() {
+    if (!validateWasmFunction('wasmGetDistricts')) {
+        return null;
+    }
+    return window.wasmGetDistricts();
+}
+
+/**
+ * Get the district at a world position
+ *
+ * @param {number} x - World X
+ * @param {number} z - World Z
+ * @returns {number} District id, 0 outside any district
+ */
+export function getDistrictAt(x, z) {
+    if (!validateWasmFunction('wasmGetDistrictAt')) {
+        return 0;
+    }
+    return window.wasmGetDistrictAt(x, z);
+}
+
+/**
+ * Score the town's service coverage (schools, library, markets, healthcare,
+ * employment, green space) at its homes
+ *
+ * @returns {Object|null} {score, homes, services: [{name, score, providers, radius, weight}]}, or null without WASM
+ */
+export function getLivability() {
+    if (!validateWasmFunction('wasmGetLivability')) {
+        return null;
+    }
+    return window.wasmGetLivability();
+}
+
+/**
+ * Get every service's coverage grid (0-1 per cell) for overlays, plus how
+ * underserved each cell is
+ *
+ * @returns {Object|null} {width, depth, cellSize, originX, originZ, services: {[name]: Float64Array}, underserved}, or null without WASM
+ */
+export function getCoverageGrids() {
+    if (!validateWasmFunction('wasmGetCoverageGrids')) {
+        return null;
+    }
+    return window.wasmGetCoverageGrids();
+}
+
+// Reused buffer for getLandValueGrid (grown by WASM as needed)
+let landValueCells = null;
+
+/**
+ * Get the land value field (0-1 per cell) for rendering as an overlay
+ *
+ * @returns {Object|null} {width, depth, cellSize, originX, originZ, mean, cells}, or null without WASM
+ */
+export function getLandValueGrid() {
+    if (!validateWasmFunction('wasmGetLandValue')) {
+        return null;
+    }
+    const grid = window.wasmGetLandValue(landValueCells);
+    landValueCells = grid.cells;
+    return grid;
+}
+
+// Reused buffer for getNoiseGrid (grown by WASM as needed)
+let noiseCells = null;
+
+/**
+ * Get the traffic and industry noise (0-1 per cell) for rendering as an overlay
+ * Noise builds up as cars drive through a cell and fades over a few seconds
+ *
+ * @returns {Object|null} {width, depth, cellSize, originX, originZ, cells}, or null without WASM
+ */
+export function getNoiseGrid() {
+    if (!validateWasmFunction('wasmGetNoiseMap')) {
+        return null;
+    }
+    const grid = window.wasmGetNoiseMap(noiseCells);
+    noiseCells = grid.cells;
+    return grid;
+}
+
+/**
+ * Get the growth of every tree and park. Plantings start as saplings and
+ * mature over game days, adding more to green space coverage as they grow
+ *
+ * @returns {Object|null} {mature_days, plantings: Array<{id, model, growth, stage}>}, or null without WASM
+ */
+export function getGreenery() {
+    if (!validateWasmFunction('wasmGetGreenery')) {
+        return null;
+    }
+    return window.wasmGetGreenery();
+}
+
+/**
+ * Change how many game days plantings take to mature, or set their growth
+ *
+ * @param {Object} options - {mature_days, growth, id}; growth without an id applies to every planting
+ * @returns {boolean} False for an unknown planting id
+ */
+export function setGreenery(options) {
+    if (!validateWasmFunction('wasmSetGreenery')) {
+        return false;
+    }
+    return window.wasmSetGreenery(options);
+}
+
+/**
+ * Get every building under construction, with its stage for drawing
+ * foundations or scaffolding. A site houses no one and provides nothing
+ * until it completes and raises its 'building_completed' event
+ *
+ * @returns {Object|null} {instant, cost_per_hour, sites}, or null without WASM
+ */
+export function getConstruction() {
+    if (!validateWasmFunction('wasmGetConstruction')) {
+        return null;
+    }
+    return window.wasmGetConstruction();
+}
+
+/**
+ * Tune construction or finish buildings now; instant makes a sandbox where
+ * buildings are complete as soon as they are placed
+ *
+ * @param {Object} options - {instant, cost_per_hour, complete}; complete is a building id or true for every site
+ * @returns {boolean} False for an id not under construction
+ */
+export function setConstruction(options) {
+    if (!validateWasmFunction('wasmSetConstruction')) {
+        return false;
+    }
+    return window.wasmSetConstruction(options);
+}
+
+/**
+ * Get the level of every home and workplace. Buildings rise a level after
+ * days of high land value and service coverage and fall after days of low;
+ * each change also raises a 'building_upgraded' or 'building_downgraded' event
+ * carrying the new level in its to field, for swapping the model
+ *
+ * @returns {Object|null} {enabled, max_level, upgrade_days, downgrade_days, buildings}, or null without WASM
+ */
+export function getBuildingLevels() {
+    if (!validateWasmFunction('wasmGetBuildingLevels')) {
+        return null;
+    }
+    return window.wasmGetBuildingLevels();
+}
+
+/**
+ * Turn building leveling on or off and tune it, or set a building's level
+ *
+ * @param {Object} options - {enabled, upgrade_days, downgrade_days, id, level}, all optional
+ * @returns {boolean} False for an id that is not a home or workplace
+ */
+export function setBuildingLevels(options) {
+    if (!validateWasmFunction('wasmSetBuildingLevels')) {
+        return false;
+    }
+    return window.wasmSetBuildingLevels(options);
+}
+
+// Reused buffer for generateTerrain heights (grown by WASM as needed)
+let terrainHeights = null;
+
+/**
+ * Replace the physics terrain and surface map with a seeded procedural landscape
+ * The same seed and options always produce the same heights and surfaces
+ *
+ * @param {Object} options - {seed, width, depth, cellSize, octaves, frequency,
+ *   persistence, lacunarity, amplitude, flatRadius}; omitted fields use the defaults
+ * @returns {Object|null} {width, depth, cellSize, originX, originZ, waterLevel, heights, surfaces},
+ *   or null without WASM. Cells below waterLevel are water: cars stop at the shore and only
+ *   bridges can be placed there
+ */
+export function generateTerrain(options = {}) {
+    if (!validateWasmFunction('wasmGenerateTerrain')) {
+        return null;
+    }
+    const terrain = window.wasmGenerateTerrain(options, terrainHeights);
+    if (terrain) {
+        terrainHeights = terrain.heights;
+    }
+    return terrain;
+}
+
+/**
+ * Get the world clock: game time, daylight and the sun direction for lighting.
+ * Trips, store hours and the daily population and budget updates follow it
+ *
+ * @returns {Object|null} {day, hour, day_progress, seconds_per_hour, daylight, sun: {x, y, z},
+ *   stores_open, store_open, store_close, season}, or null without WASM
+ */
+export function getClock() {
+    if (!validateWasmFunction('wasmGetClock')) {
+        return null;
+    }
+    return window.wasmGetClock();
+}
+
+/**
+ * Change the clock rate, jump to an hour of the day, or change store hours or
+ * the season length
+ *
+ * @param {Object} options - {seconds_per_hour, hour, store_open, store_close, days_per_season}
+ * @returns {boolean} Success status
+ */
+export function setClock(options) {
+    if (!validateWasmFunction('wasmSetClock')) {
+        return false;
+    }
+    return window.wasmSetClock(options);
+}
+
+/**
+ * Get the season and its effects: road grip, how much green space counts
+ * toward livability, and the odds automatic weather draws from
+ *
+ * @returns {Object|null} {season, progress, days_per_season, road_grip, green_space,
+ *   weather_odds: {clear, rain, snow}}, or null without WASM
+ */
+export function getSeason() {
+    if (!validateWasmFunction('wasmGetSeason')) {
+        return null;
+    }
+    return window.wasmGetSeason();
+}
+
+/**
+ * Get the current weather and its effect on driving. Rain costs tire grip and
+ * snow lowers top speed and braking; particle effects should follow type and intensity
+ *
+ * @returns {Object|null} {type, intensity, target, target_intensity, auto, grip_scale,
+ *   max_speed_scale, brake_scale}, or null without WASM
+ */
+export function getWeather() {
+    if (!validateWasmFunction('wasmGetWeather')) {
+        return null;
+    }
+    return window.wasmGetWeather();
+}
+
+/**
+ * Change the weather (fading over half a game hour unless immediate) or turn
+ * automatic weather changes on or off
+ *
+ * @param {Object} options - {type: 'clear'|'rain'|'snow', intensity, immediate, auto}
+ * @returns {boolean} False for an unknown weather type
+ */
+export function setWeather(options) {
+    if (!validateWasmFunction('wasmSetWeather')) {
+        return false;
+    }
+    return window.wasmSetWeather(options);
+}
+
+/**
+ * Get the town census: residents, housing, jobs, employment, satisfaction and
+ * per-zone demand. Residents move in or out once per simulated day
+ *
+ * @returns {Object|null} Census (see getPopulation in physics_wasm.go), or null without WASM
+ */
+export function getPopulation() {
+    if (!validateWasmFunction('wasmGetPopulation')) {
+        return null;
+    }
+    return window.wasmGetPopulation();
+}
+
+/**
+ * Pay for building a model from the town treasury
+ *
+ * @param {string} modelName - Model file name
+ * @returns {boolean} False only when the economy is enabled and the town cannot afford it
+ */
+export function purchaseModel(modelName) {
+    if (!validateWasmFunction('wasmPurchase')) {
+        return true;
+    }
+    return window.wasmPurchase(modelName);
+}
+
+/**
+ * Refund part of a demolished model's price to the treasury
+ *
+ * @param {string} modelName - Model file name
+ * @returns {number} Refund (0 while the economy is disabled)
+ */
+export function refundModel(modelName) {
+    if (!validateWasmFunction('wasmDemolish')) {
+        return 0;
+    }
+    return window.wasmDemolish(modelName);
+}
+
+/**
+ * Demolish a placed object: remove it and its collider, refund part of its
+ * price and raise a 'demolished' event with its footprint for the debris.
+ * Trips and citizens heading for it turn back
+ *
+ * @param {number} id - Placement ID
+ * @returns {number|null} Refund (0 while the economy is disabled), or null if there is no such placement
+ */
+export function demolishPlacement(id) {
+    if (!validateWasmFunction('wasmDemolishPlacement')) {
+        return null;
+    }
+    return window.wasmDemolishPlacement(id);
+}
+
+/**
+ * Get the treasury and the income/expense breakdown
+ *
+ * @returns {Object|null} {enabled, funds, today, yesterday, ...}, or null without WASM
+ */
+export function getBudget() {
+    if (!validateWasmFunction('wasmGetBudget')) {
+        return null;
+    }
+    return window.wasmGetBudget();
+}
+
+/**
+ * Turn the power and water networks on or off
+ * While enabled, buildings without both utilities hold no residents or jobs
+ * and provide no livability services
+ *
+ * @param {Object} options - {enabled?}
+ * @returns {boolean} Success status
+ */
+export function setUtilities(options) {
+    if (!validateWasmFunction('wasmSetUtilities')) {
+        return false;
+    }
+    return window.wasmSetUtilities(options);
+}
+
+/**
+ * Set what a model use produces of each utility
+ *
+ * @param {string} use - Model use, e.g. 'power_plant'
+ * @param {Object} supply - {power?, water?} in residents plus jobs served
+ * @returns {boolean} Success status
+ */
+export function setUtilitySupply(use, supply) {
+    if (!validateWasmFunction('wasmSetUtilitySupply')) {
+        return false;
+    }
+    return window.wasmSetUtilitySupply(use, supply);
+}
+
+/**
+ * Get the utility networks and every building's connection, for overlays
+ *
+ * @returns {Object|null} {enabled, power, water, buildings: Array<{id, functional, power: {connected, served}, ...}>},
+ *   or null without WASM
+ */
+export function getUtilities() {
+    if (!validateWasmFunction('wasmGetUtilities')) {
+        return null;
+    }
+    return window.wasmGetUtilities();
+}
+
+/**
+ * Find the placed object covering a world position
+ *
+ * @returns {Object|null} {id, model, category, bbox}, or null if the cell is free
+ */
+export function getPlacementAt(x, z) {
+    if (!validateWasmFunction('wasmGetPlacementAt')) {
+        return null;
+    }
+    return window.wasmGetPlacementAt(x, z);
+}
+
+// Road tiles whose bounding boxes are within this gap (world units) are connected
+const ROAD_TILE_GAP = 0.05;
+
+/**
+ * Build the WASM road graph from placed road tiles
+ * Every road tile becomes a node; tiles that touch along X or Z are joined by
+ * a two-way road. Corner/junction openings are not inspected, so the graph is
+ * a superset of the drivable connections. Junction tiles get traffic signals
+ *
+ * @param {Array<THREE.Object3D>} objects - Array of scene objects
+ * @returns {boolean} Success status
+ */
+export function syncRoadNetwork(objects) {
+    if (!validateWasmFunction('wasmSetRoadNetwork')) {
+        return false;
+    }
+
+    const tiles = objects.filter(obj => obj.userData.modelName && obj.userData.modelName.startsWith('road_'));
+    const nodes = tiles.map(tile => ({ id: tile.id, x: tile.position.x, z: tile.position.z }));
+
+    const roads = [];
+    for (let i = 0; i < tiles.length; i++) {
+        const a = tiles[i].userData.boundingBox || new THREE.Box3().setFromObject(tiles[i]);
+        for (let j = i + 1; j < tiles.length; j++) {
+            const b = tiles[j].userData.boundingBox || new THREE.Box3().setFromObject(tiles[j]);
+            const touchX = a.min.x <= b.max.x + ROAD_TILE_GAP && b.min.x <= a.max.x + ROAD_TILE_GAP;
+            const touchZ = a.min.z <= b.max.z + ROAD_TILE_GAP && b.min.z <= a.max.z + ROAD_TILE_GAP;
+            const dx = Math.abs(tiles[i].position.x - tiles[j].position.x);
+            const dz = Math.abs(tiles[i].position.z - tiles[j].position.z);
+            // Neighbors share an edge: boxes touch and centers line up on one axis
+            if (touchX && touchZ && (dx < ROAD_TILE_GAP || dz < ROAD_TILE_GAP)) {
+                roads.push({ from: tiles[i].id, to: tiles[j].id, two_way: true });
+            }
+        }
+    }
+
+    try {
+        const result = window.wasmSetRoadNetwork({ nodes, roads });
+        if (result && result.errors) {
+            console.warn('Road network has invalid segments:', result.errors);
+        }
+
+        // Junction tiles get signals with automatic phases
+        if (result && typeof window.wasmSetTrafficSignal === 'function') {
+            tiles.filter(tile => tile.userData.modelName.startsWith('road_junction'))
+                .forEach(tile => window.wasmSetTrafficSignal(tile.id));
+        }
+        return !!result;
+    } catch (error) {
+        console.error('Error syncing road network:', error);
+        return false;
+    }
+}
+
+/**
+ * Replace the WASM road graph with a seeded procedural layout, for quick-start
+ * maps and traffic stress tests. Arterial crossings get traffic signals
+ * The generated graph has no road tiles, so the next syncRoadNetwork replaces it
+ *
+ * @param {string} style - 'grid', 'radial' or 'organic'
+ * @param {Object} options - {seed, extent, spacing, spokes, iterations, branch_chance, jitter, speed_limit, lanes}
+ * @returns {Object|null} {node_count, edge_count, roads, signals}, or null without WASM or for an unknown style
+ */
+export function generateRoadNetwork(style = 'grid', options = {}) {
+    if (!validateWasmFunction('wasmGenerateRoadNetwork')) {
+        return null;
+    }
+    return window.wasmGenerateRoadNetwork({ ...options, style });
+}
+
+/**
+ * Get the whole WASM road graph
+ *
+ * @returns {Object|null} {nodes: Array<{id, x, z}>, edges: Array<{id, from, to, length, speed_limit, lanes, priority, bike_lane}>}
+ */
+export function getRoadGraph() {
+    if (!validateWasmFunction('wasmGetRoadGraph')) {
+        return null;
+    }
+    return window.wasmGetRoadGraph();
+}
+
+/**
+ * Add or remove the bike lane beside one direction of a road; AI bicycles
+ * and motorcycles ride it, clear of the cars in the lane beside them
+ *
+ * @param {number} edge - Road segment id (see getRoadGraph)
+ * @param {boolean} on - Whether the segment has a bike lane
+ * @returns {boolean} False if WASM unavailable, the segment does not exist or nothing changed
+ */
+export function setBikeLane(edge, on) {
+    if (!validateWasmFunction('wasmSetBikeLane')) {
+        return false;
+    }
+    return window.wasmSetBikeLane(edge, on);
+}
+
+/**
+ * Find the fastest route between two road nodes (road tile object ids)
+ *
+ * @param {number} fromNode - Start node id
+ * @param {number} toNode - Goal node id
+ * @returns {Object|null} {nodes, edges, waypoints, length, travel_time}, or null if unreachable
+ */
+export function findRoute(fromNode, toNode) {
+    if (!validateWasmFunction('wasmFindRoute')) {
+        return null;
+    }
+    return window.wasmFindRoute(fromNode, toNode);
+}
+
+/**
+ * Find the fastest route between two world positions, via their nearest road nodes
+ *
+ * @returns {Object|null} Route (see findRoute), or null if there are no roads or no route
+ */
+export function findRouteBetween(fromX, fromZ, toX, toZ) {
+    if (!validateWasmFunction('wasmNearestRoadNode')) {
+        return null;
+    }
+
+    const start = window.wasmNearestRoadNode(fromX, fromZ);
+    const goal = window.wasmNearestRoadNode(toX, toZ);
+    if (!start || !goal) {
+        return null;
+    }
+    return findRoute(start.id, goal.id);
+}
+
+/**
+ * Install (or replace) a traffic signal at a road node
+ *
+ * @param {number} node - Road node id (road tile object id)
+ * @param {Object} options - {green_time, yellow_time, phases: [{edges, green_time, yellow_time}]};
+ *   without phases, opposite approaches share a green
+ * @returns {boolean} True if the node exists
+ */
+export function setTrafficSignal(node, options = {}) {
+    if (!validateWasmFunction('wasmSetTrafficSignal')) {
+        return false;
+    }
+    return window.wasmSetTrafficSignal(node, options);
+}
+
+/**
+ * Get every traffic signal's current lights, for coloring signal models
+ *
+ * @returns {Array} [{node, phase, elapsed, lights: [{edge, light}]}] (empty if WASM unavailable)
+ */
+export function getTrafficSignals() {
+    if (!validateWasmFunction('wasmGetTrafficSignals')) {
+        return [];
+    }
+    return window.wasmGetTrafficSignals();
+}
+
+/**
+ * Spawn an AI car that drives the route between two road nodes, keeping to
+ * the speed limit and slowing for the car ahead
+ *
+ * @param {number} id - Car id (replaces any car with the same id)
+ * @param {number} fromNode - Spawn node id
+ * @param {number} toNode - Destination node id
+ * @param {string|Object} vehicle - Preset name or vehicle config
+ * @returns {boolean} True if a route was found and the car spawned
+ */
+export function spawnAICar(id, fromNode, toNode, vehicle = 'car') {
+    if (!validateWasmFunction('wasmSpawnAICar')) {
+        return false;
+    }
+    return window.wasmSpawnAICar(id, fromNode, toNode, vehicle);
+}
+
+/**
+ * Send an AI car to a new destination once it finishes its current segment
+ *
+ * @returns {boolean} True if the destination is reachable
+ */
+export function setAIDestination(id, toNode) {
+    if (!validateWasmFunction('wasmSetAIDestination')) {
+        return false;
+    }
+    return window.wasmSetAIDestination(id, toNode);
+}
+
+/**
+ * Despawn an AI car
+ *
+ * @returns {boolean} True if the car existed
+ */
+export function despawnAICar(id) {
+    if (!validateWasmFunction('wasmRemoveCar')) {
+        return false;
+    }
+    return window.wasmRemoveCar(id);
+}
+
+/**
+ * Generate traffic from the town's land use: residents drive to work, to
+ * markets and home again, and the cars are removed when they arrive
+ *
+ * @param {boolean} enabled - Whether trips are generated
+ * @param {Object} options - Optional {trips_per_resident, max_cars, vehicle}
+ * @returns {boolean} True if applied
+ */
+export function setTrafficDemand(enabled, options = {}) {
+    if (!validateWasmFunction('wasmSetTripDemand')) {
+        return false;
+    }
+    return window.wasmSetTripDemand({ ...options, enabled });
+}
+
+/**
+ * Get the generated trips and their counters
+ *
+ * @returns {Object|null} {enabled, generated, completed, failed, suppressed, trips}, or null without WASM
+ */
+export function getTrafficDemand() {
+    if (!validateWasmFunction('wasmGetTripDemand')) {
+        return null;
+    }
+    return window.wasmGetTripDemand();
+}
+
+/**
+ * Turn citizen daily schedules on or off and tune them.
+ * Citizens commute to their jobs, visit markets and parks and go home at
+ * night, walking short legs and driving the rest
+ *
+ * @param {Object} options - {enabled, max_citizens, max_cars, walk_distance, vehicle}, all optional
+ * @returns {boolean} True if applied
+ */
+export function setCitizens(options) {
+    if (!validateWasmFunction('wasmSetCitizens')) {
+        return false;
+    }
+    return window.wasmSetCitizens(options);
+}
+
+/**
+ * Get every citizen with what they are doing and the rest of their day's plan
+ *
+ * @returns {Object|null} {enabled, trips, driven, walked, completed, driving, walking, at, citizens},
+ *   or null without WASM
+ */
+export function getCitizens() {
+    if (!validateWasmFunction('wasmGetCitizens')) {
+        return null;
+    }
+    return window.wasmGetCitizens();
+}
+
+/**
+ * Remove every citizen and their cars and pedestrians and reset the counters
+ */
+export function clearCitizens() {
+    if (!validateWasmFunction('wasmClearCitizens')) {
+        return;
+    }
+    window.wasmClearCitizens();
+}
+
+/**
+ * Get every citizen's happiness (0-100) from their commute, service access,
+ * noise and housing, averaged by neighborhood and over the town. Each score
+ * carries its factors (0-1) and the worst of them, the category failing there
+ *
+ * @returns {Object|null} {town, columns, neighborhoods, citizens}, or null without WASM
+ */
+export function getHappiness() {
+    if (!validateWasmFunction('wasmGetHappiness')) {
+        return null;
+    }
+    return window.wasmGetHappiness();
+}
+
+/**
+ * Turn random building fires on or off and tune how they spread and burn.
+ * Every fire gets a truck from the nearest fire station or hospital
+ *
+ * @param {boolean} enabled - Whether buildings catch fire at random
+ * @param {Object} options - Optional {ignition_per_day, spread_per_hour, burn_hours, station_uses, vehicle}
+ * @returns {boolean} True if applied
+ */
+export function setFires(enabled, options = {}) {
+    if (!validateWasmFunction('wasmSetFires')) {
+        return false;
+    }
+    return window.wasmSetFires({ ...options, enabled });
+}
+
+/**
+ * Get the burning buildings and the placement ids burned down since the last
+ * call (each is reported once; remove them from the scene)
+ *
+ * @returns {Object|null} {enabled, ignited, extinguished, destroyed_count, destroyed, fires}, or null without WASM
+ */
+export function getFires() {
+    if (!validateWasmFunction('wasmGetFires')) {
+        return null;
+    }
+    return window.wasmGetFires();
+}
+
+/**
+ * Set a placed building on fire
+ *
+ * @param {THREE.Object3D} building - Placed building (its id is the placement id)
+ * @returns {boolean} False if it is not a building or is already burning
+ */
+export function igniteBuilding(building) {
+    if (!validateWasmFunction('wasmIgniteBuilding')) {
+        return false;
+    }
+    return window.wasmIgniteBuilding(building.id);
+}
+
+/**
+ * Tune bus ridership and the buses put on the road from now on
+ *
+ * @param {Object} options - {rides_per_resident, capacity, vehicle}, all optional
+ * @returns {boolean} True if applied
+ */
+export function setTransit(options) {
+    if (!validateWasmFunction('wasmSetTransit')) {
+        return false;
+    }
+    return window.wasmSetTransit(options);
+}
+
+/**
+ * Add or replace a bus route looping through placed bus stops
+ * Buses drive between the road nodes nearest each stop and residents near
+ * the stops walk to them, ride and walk on to work
+ *
+ * @param {number} id - Route id
+ * @param {Array<THREE.Object3D|number>} stops - Bus stops (or their placement ids) in order
+ * @param {Object} [options] - {name, buses} (buses defaults to 1)
+ * @returns {boolean} False if WASM unavailable, a stop is not a bus stop or there are fewer than two
+ */
+export function setBusRoute(id, stops, options = {}) {
+    if (!validateWasmFunction('wasmSetBusRoute')) {
+        return false;
+    }
+    const ids = stops.map(stop => (typeof stop === 'number' ? stop : stop.id));
+    return window.wasmSetBusRoute(id, { ...options, stops: ids });
+}
+
+/**
+ * Remove a bus route and take its buses off the road
+ *
+ * @param {number} id - Route id
+ * @returns {boolean} False if WASM unavailable or there is no such route
+ */
+export function removeBusRoute(id) {
+    if (!validateWasmFunction('wasmRemoveBusRoute')) {
+        return false;
+    }
+    return window.wasmRemoveBusRoute(id);
+}
+
+/**
+ * Get the bus routes, their buses and ridership stats
+ *
+ * @returns {Object|null} {rides_per_resident, capacity, generated, boardings, alightings,
+ *   completed, suppressed, routes}, or null without WASM
+ */
+export function getTransit() {
+    if (!validateWasmFunction('wasmGetTransit')) {
+        return null;
+    }
+    return window.wasmGetTransit();
+}
+
+/**
+ * Remove every bus route, bus and rider and reset the ridership counters
+ */
+export function clearTransit() {
+    if (!validateWasmFunction('wasmClearTransit')) {
+        return;
+    }
+    window.wasmClearTransit();
+}
+
+/**
+ * Tune tram ridership and the trams
+ *
+ * @param {Object} options - {rides_per_resident, capacity, speed}, all optional
+ * @returns {boolean} True if applied
+ */
+export function setTrams(options) {
+    if (!validateWasmFunction('wasmSetTrams')) {
+        return false;
+    }
+    return window.wasmSetTrams(options);
+}
+
+/**
+ * Add or replace a tram line
+ * Trams run the track on their own rails, stop at tram stations beside it
+ * and close the level crossings where it meets other roads
+ *
+ * @param {number} id - Line id
+ * @param {Object} line - {track: Array<{x, z}>} or {nodes: number[]} (road node ids to run along),
+ *   plus optional {name, loop, headway, trams}
+ * @returns {boolean} False if WASM unavailable or the track is invalid
+ */
+export function setTramLine(id, line) {
+    if (!validateWasmFunction('wasmSetTramLine')) {
+        return false;
+    }
+    return window.wasmSetTramLine(id, line);
+}
+
+/**
+ * Remove a tram line and take its trams off the rails
+ *
+ * @param {number} id - Line id
+ * @returns {boolean} False if WASM unavailable or there is no such line
+ */
+export function removeTramLine(id) {
+    if (!validateWasmFunction('wasmRemoveTramLine')) {
+        return false;
+    }
+    return window.wasmRemoveTramLine(id);
+}
+
+/**
+ * Get the tram lines, their trams and crossings, and ridership stats
+ * Tram transforms for rendering are in getTransformViews().trams
+ *
+ * @returns {Object|null} {capacity, speed, generated, boardings, alightings, suppressed, lines},
+ *   or null without WASM
+ */
+export function getTrams() {
+    if (!validateWasmFunction('wasmGetTrams')) {
+        return null;
+    }
+    return window.wasmGetTrams();
+}
+
+/**
+ * Remove every tram line and tram and reset the ridership counters
+ */
+export function clearTrams() {
+    if (!validateWasmFunction('wasmClearTrams')) {
+        return;
+    }
+    window.wasmClearTrams();
+}
+
+/**
+ * Lay and tune the freight line
+ * The train runs straight through, lowering the gates at every road it
+ * crosses; AI cars queue at lowered gates
+ *
+ * @param {Object} options - {track: Array<{x, z}>, enabled, interval, speed, wagons}, all optional
+ * @returns {boolean} False if WASM unavailable or the track is invalid
+ */
+export function setRailway(options) {
+    if (!validateWasmFunction('wasmSetRailway')) {
+        return false;
+    }
+    return window.wasmSetRailway(options);
+}
+
+/**
+ * Send a train down the freight line now
+ *
+ * @returns {boolean} False if WASM unavailable, there is no track or a train is running
+ */
+export function sendTrain() {
+    if (!validateWasmFunction('wasmSendTrain')) {
+        return false;
+    }
+    return window.wasmSendTrain();
+}
+
+/**
+ * Get the freight line, its train and the state of its crossing gates
+ * Wagon transforms for rendering are in getTransformViews().trains
+ *
+ * @returns {Object|null} {enabled, running, along, speed, wagons, interval, next_train, trains,
+ *   length, crossings: [{edge_id, along, gate, lowered}]}, or null without WASM
+ */
+export function getRailway() {
+    if (!validateWasmFunction('wasmGetRailway')) {
+        return null;
+    }
+    return window.wasmGetRailway();
+}
+
+/**
+ * Tune sirens and incident response
+ *
+ * @param {Object} options - {speed_factor, siren_range, scene_hours, medical_uses, police_uses, vehicle}
+ * @returns {boolean} True if applied
+ */
+export function setEmergency(options) {
+    if (!validateWasmFunction('wasmSetEmergency')) {
+        return false;
+    }
+    return window.wasmSetEmergency(options);
+}
+
+/**
+ * Report an emergency; an ambulance (medical) or police car (police) drives
+ * to it from the nearest hospital or police station with its siren on
+ *
+ * @param {string} kind - "medical" or "police"
+ * @param {number} x - Incident position
+ * @param {number} z - Incident position
+ * @returns {number} Incident id, or -1 if WASM unavailable or the kind is unknown
+ */
+export function reportIncident(kind, x, z) {
+    if (!validateWasmFunction('wasmReportIncident')) {
+        return -1;
+    }
+    return window.wasmReportIncident(kind, x, z);
+}
+
+/**
+ * Call off an incident and remove its responder
+ *
+ * @param {number} id - Incident id
+ * @returns {boolean} False if WASM unavailable or there is no such incident
+ */
+export function cancelIncident(id) {
+    if (!validateWasmFunction('wasmCancelIncident')) {
+        return false;
+    }
+    return window.wasmCancelIncident(id);
+}
+
+/**
+ * Get the open incidents and how their responders are doing
+ *
+ * @returns {Object|null} {reported, resolved, incidents}, or null without WASM
+ */
+export function getIncidents() {
+    if (!validateWasmFunction('wasmGetIncidents')) {
+        return null;
+    }
+    return window.wasmGetIncidents();
+}
+
+/**
+ * Call off every incident, remove the responders and reset the counters
+ */
+export function clearIncidents() {
+    if (!validateWasmFunction('wasmClearIncidents')) {
+        return;
+    }
+    window.wasmClearIncidents();
+}
+
+/**
+ * Turn a car's siren on or off; AI traffic pulls over for cars running one
+ *
+ * @param {number} id - Car id
+ * @param {boolean} on - Siren state
+ * @returns {boolean} False if WASM unavailable or the car does not exist
+ */
+export function setSiren(id, on) {
+    if (!validateWasmFunction('wasmSetSiren')) {
+        return false;
+    }
+    return window.wasmSetSiren(id, on);
+}
+
+/**
+ * Get the moving cars with a siren on near a point (to play siren audio)
+ *
+ * @param {number} x - Listener position
+ * @param {number} z - Listener position
+ * @param {number} [radius] - Search radius (defaults to the siren range)
+ * @returns {number[]} Car ids, empty without WASM
+ */
+export function getSirensNear(x, z, radius) {
+    if (!validateWasmFunction('wasmGetSirensNear')) {
+        return [];
+    }
+    return radius === undefined ? window.wasmGetSirensNear(x, z) : window.wasmGetSirensNear(x, z, radius);
+}
+
+/**
+ * Start a race over checkpoint gates, replacing any race on
+ * Gates are passed in order, gate 0 being the start/finish line; laps and
+ * sectors are timed in simulation time as the cars cross them
+ *
+ * @param {Array<{x1: number, z1: number, x2: number, z2: number}>} gates - Gate lines across the course
+ * @param {number[]} carIds - Cars entered in the race
+ * @param {number} [laps=0] - Laps to finish, 0 for an endless practice session
+ * @returns {boolean} False if WASM unavailable, there are no gates or a car does not exist
+ */
+export function startRace(gates, carIds, laps = 0) {
+    if (!validateWasmFunction('wasmStartRace')) {
+        return false;
+    }
+    return window.wasmStartRace(gates, carIds, laps);
+}
+
+/**
+ * End the race and discard its times
+ */
+export function stopRace() {
+    if (!validateWasmFunction('wasmStopRace')) {
+        return;
+    }
+    window.wasmStopRace();
+}
+
+/**
+ * Get the race clock and standings (for the race timer and best laps)
+ * Each standing has car_id, position, place, lap, next_gate, started,
+ * finished, current_lap, last_lap, best_lap, sectors, best_sectors and finish_time
+ *
+ * @returns {Object|null} {laps, gates, elapsed, finishers, standings}, or null without WASM or a race
+ */
+export function getRace() {
+    if (!validateWasmFunction('wasmGetRace')) {
+        return null;
+    }
+    return window.wasmGetRace();
+}
+
+/**
+ * Tune delivery missions; deadlines and rewards apply to new missions
+ *
+ * @param {Object} options - {zone_radius?, trigger_speed?, time_slack?, base_reward?, reward_per_unit?}
+ * @returns {boolean} False if WASM unavailable
+ */
+export function setMissions(options) {
+    if (!validateWasmFunction('wasmSetMissions')) {
+        return false;
+    }
+    return window.wasmSetMissions(options);
+}
+
+/**
+ * Give a car a delivery: pick up at a random business, drop off at another
+ * building before the deadline; delivering pays the reward into the treasury
+ *
+ * @param {number} carId - Car driving the delivery
+ * @returns {number} Mission id, or -1 if WASM unavailable or no mission could be made
+ */
+export function generateMission(carId) {
+    if (!validateWasmFunction('wasmGenerateMission')) {
+        return -1;
+    }
+    return window.wasmGenerateMission(carId);
+}
+
+/**
+ * Abandon a mission without payout
+ *
+ * @param {number} id - Mission id
+ * @returns {boolean} False if WASM unavailable or the mission is not active
+ */
+export function cancelMission(id) {
+    if (!validateWasmFunction('wasmCancelMission')) {
+        return false;
+    }
+    return window.wasmCancelMission(id);
+}
+
+/**
+ * Get the active deliveries (stage, pickup and dropoff zones, time left,
+ * reward) and the completed/failed/earned totals
+ *
+ * @returns {Object|null} {completed, failed, earned, zone_radius, missions}, or null without WASM
+ */
+export function getMissions() {
+    if (!validateWasmFunction('wasmGetMissions')) {
+        return null;
+    }
+    return window.wasmGetMissions();
+}
+
+/**
+ * Get the road route from a mission's car to its next zone (for a
+ * minimap or waypoint arrow)
+ *
+ * @param {number} id - Mission id
+ * @returns {Object|null} {nodes, points, length, travel_time}, or null without WASM, mission or route
+ */
+export function getMissionRoute(id) {
+    if (!validateWasmFunction('wasmGetMissionRoute')) {
+        return null;
+    }
+    return window.wasmGetMissionRoute(id);
+}
+
+/**
+ * Abandon every mission and reset the totals
+ */
+export function clearMissions() {
+    if (!validateWasmFunction('wasmClearMissions')) {
+        return;
+    }
+    window.wasmClearMissions();
+}
+
+/**
+ * Get the condition of every road segment (for the road condition overlay)
+ * Segments run between road node ids from and to, with end points x1/z1 and
+ * x2/z2; condition is 1 (new) to 0 (worn out)
+ *
+ * @returns {Object|null} {passage_wear, weather_wear, speed_loss, grip_loss, repair_cost, segments}, or null without WASM
+ */
+export function getRoadWear() {
+    if (!validateWasmFunction('wasmGetRoadWear')) {
+        return null;
+    }
+    return window.wasmGetRoadWear();
+}
+
+/**
+ * Tune how fast roads wear and how much worn roads slow cars, or set the
+ * condition of one segment with {from, to, condition}
+ *
+ * @param {Object} options - {passage_wear?, weather_wear?, speed_loss?, grip_loss?, repair_cost?, from?, to?, condition?}
+ * @returns {boolean} False without WASM or for an unknown segment
+ */
+export function setRoadWear(options) {
+    if (!validateWasmFunction('wasmSetRoadWear')) {
+        return false;
+    }
+    return window.wasmSetRoadWear(options);
+}
+
+/**
+ * Repair a road segment, or every road when called without nodes, paying
+ * from the treasury
+ *
+ * @param {number} [from] - Road node id at one end of the segment
+ * @param {number} [to] - Road node id at the other end
+ * @returns {number|false} Cost paid, or false if there is no such road or the town cannot afford it
+ */
+export function repairRoad(from, to) {
+    if (!validateWasmFunction('wasmRepairRoad')) {
+        return false;
+    }
+    return from === undefined ? window.wasmRepairRoad() : window.wasmRepairRoad(from, to);
+}
+
+/**
+ * Place a speed bump or pothole (replacing any feature with the same id)
+ * Cars driving onto one bounce, lose speed and queue a "road_feature"
+ * collision event (see drainCollisionEvents) for camera shake and sound
+ *
+ * @param {number} id - Feature id
+ * @param {Object} feature - {kind: 'speed_bump'|'pothole', x, z, heading, length, radius, height};
+ *   a strip length long along heading, radius wide either side (length 0 for a round pothole)
+ * @returns {boolean} False if WASM unavailable or the kind is unknown
+ */
+export function setRoadFeature(id, feature) {
+    if (!validateWasmFunction('wasmSetRoadFeature')) {
+        return false;
+    }
+    return window.wasmSetRoadFeature(id, feature);
+}
+
+/**
+ * Remove a speed bump or pothole
+ *
+ * @param {number} id - Feature id
+ * @returns {boolean} False if WASM unavailable or there is no such feature
+ */
+export function removeRoadFeature(id) {
+    if (!validateWasmFunction('wasmRemoveRoadFeature')) {
+        return false;
+    }
+    return window.wasmRemoveRoadFeature(id);
+}
+
+/**
+ * Get every speed bump and pothole
+ *
+ * @returns {Array} [{id, kind, x, z, heading, length, radius, height}] (empty if WASM unavailable)
+ */
+export function getRoadFeatures() {
+    if (!validateWasmFunction('wasmGetRoadFeatures')) {
+        return [];
+    }
+    return window.wasmGetRoadFeatures();
+}
+
+/**
+ * Remove every speed bump and pothole
+ */
+export function clearRoadFeatures() {
+    if (!validateWasmFunction('wasmClearRoadFeatures')) {
+        return;
+    }
+    window.wasmClearRoadFeatures();
+}
+
+/**
+ * Mark out a parking spot for the parking challenge, replacing any spot with
+ * the same id; a car stopping wholly inside it, lined up within the angle
+ * tolerance, raises a 'parked' event with a precision score
+ *
+ * @param {number} id - Spot id
+ * @param {Object} spot - {x, z, heading?, length?, width?, angle_tolerance?, both_ways?}
+ * @returns {boolean} False if WASM unavailable or the position is invalid
+ */
+export function setParkingSpot(id, spot) {
+    if (!validateWasmFunction('wasmSetParkingSpot')) {
+        return false;
+    }
+    return window.wasmSetParkingSpot(id, spot);
+}
+
+/**
+ * Remove a parking spot
+ *
+ * @param {number} id - Spot id
+ * @returns {boolean} False if WASM unavailable or the spot does not exist
+ */
+export function removeParkingSpot(id) {
+    if (!validateWasmFunction('wasmRemoveParkingSpot')) {
+        return false;
+    }
+    return window.wasmRemoveParkingSpot(id);
+}
+
+/**
+ * Get the parking spots with the car parked in each (occupant) and its score
+ *
+ * @returns {Array<Object>} Parking spots, empty without WASM
+ */
+export function getParkingSpots() {
+    if (!validateWasmFunction('wasmGetParkingSpots')) {
+        return [];
+    }
+    return window.wasmGetParkingSpots();
+}
+
+/**
+ * Remove every parking spot
+ */
+export function clearParkingSpots() {
+    if (!validateWasmFunction('wasmClearParkingSpots')) {
+        return;
+    }
+    window.wasmClearParkingSpots();
+}
+
+/**
+ * Turn business parking demand on or off and tune it; while on, shops and
+ * offices want spaces in the parking lots within walk_radius, cars arriving
+ * at them park, and unmet demand lowers commercial demand
+ *
+ * @param {Object} options - {enabled?, walk_radius?, dwell_hours?}
+ * @returns {boolean} False if WASM unavailable or a value is invalid
+ */
+export function setLotParking(options) {
+    if (!validateWasmFunction('wasmSetLotParking')) {
+        return false;
+    }
+    return window.wasmSetLotParking(options);
+}
+
+/**
+ * Set the spaces a model use provides as a parking lot and the spaces it
+ * wants per job as a business
+ *
+ * @param {string} use - Model use, e.g. 'parking_lot' or 'market'
+ * @param {Object} parking - {spaces?, demand_per_job?}
+ * @returns {boolean} False if WASM unavailable or a value is negative
+ */
+export function setParkingUse(use, parking) {
+    if (!validateWasmFunction('wasmSetParkingUse')) {
+        return false;
+    }
+    return window.wasmSetParkingUse(use, parking);
+}
+
+/**
+ * Get business parking demand and lot occupancy
+ *
+ * @returns {Object|null} {enabled, walk_radius, dwell_hours, demand, unmet,
+ *   commercial_happiness, arrivals, turned_away, lots, businesses}, or null
+ *   without WASM
+ */
+export function getLotParking() {
+    if (!validateWasmFunction('wasmGetLotParking')) {
+        return null;
+    }
+    return window.wasmGetLotParking();
+}
+
+/**
+ * Get a car's drift score: the drift in progress, the combo it chains into
+ * and the totals of finished combos. Drifts raise 'drift' events as they
+ * bank and 'drift_combo' events when the combo runs out
+ *
+ * @param {number} carId - Car ID
+ * @returns {Object|null} {drifting, time, points, multiplier, combo, combo_points, total, best}, or null for an unknown car
+ */
+export function getDriftScore(carId) {
+    if (!validateWasmFunction('wasmGetDriftScore')) {
+        return null;
+    }
+    return window.wasmGetDriftScore(carId);
+}
+
+/**
+ * Reset a car's drift score
+ *
+ * @param {number} carId - Car ID
+ * @returns {boolean} False for an unknown car
+ */
+export function resetDriftScore(carId) {
+    if (!validateWasmFunction('wasmResetDriftScore')) {
+        return false;
+    }
+    return window.wasmResetDriftScore(carId);
+}
+
+/**
+ * Count the traffic offences a car driven from JS has been booked for.
+ * Each offence raises a 'violation' event naming it in the event's model
+ * ('red_light', 'speeding' or 'wrong_way') with a 0-1 severity in its value
+ *
+ * @param {number} carId - Car ID
+ * @returns {Object|null} {red_light, speeding, wrong_way, total}, or null for an unknown car
+ */
+export function getViolations(carId) {
+    if (!validateWasmFunction('wasmGetViolations')) {
+        return null;
+    }
+    return window.wasmGetViolations(carId);
+}
+
+/**
+ * Wipe a car's record of traffic offences
+ *
+ * @param {number} carId - Car ID
+ * @returns {boolean} False for an unknown car
+ */
+export function clearViolations(carId) {
+    if (!validateWasmFunction('wasmClearViolations')) {
+        return false;
+    }
+    return window.wasmClearViolations(carId);
+}
+
+/**
+ * Get the town's running totals and the achievements unlocked. Unlocks
+ * raise 'achievement' events named in the event's model
+ *
+ * @returns {Object|null} {distance, top_speed, collisions, buildings_bought, residents, unlocked}, or null without WASM
+ */
+export function getStats() {
+    if (!validateWasmFunction('wasmGetStats')) {
+        return null;
+    }
+    return window.wasmGetStats();
+}
+
+/**
+ * Get every achievement with its statistic, threshold, the statistic's
+ * current value and whether it is unlocked
+ *
+ * @returns {Array<Object>} Achievements, empty without WASM
+ */
+export function getAchievements() {
+    if (!validateWasmFunction('wasmGetAchievements')) {
+        return [];
+    }
+    return window.wasmGetAchievements();
+}
+
+/**
+ * Zero the statistics and lock every achievement again
+ */
+export function resetStats() {
+    if (!validateWasmFunction('wasmResetStats')) {
+        return;
+    }
+    window.wasmResetStats();
+}
+
+/**
+ * Tune the chase camera following a car; omitted fields keep their values
+ *
+ * @param {number} carId - Car ID
+ * @param {Object} config - {distance, speed_distance, height, look_ahead, look_height, stiffness, damping, fov, speed_fov, clearance}
+ * @returns {boolean} False if WASM unavailable or the car does not exist
+ */
+export function setChaseCamera(carId, config) {
+    if (!validateWasmFunction('wasmSetChaseCamera')) {
+        return false;
+    }
+    return window.wasmSetChaseCamera(carId, config);
+}
+
+/**
+ * Move the chase camera following a car on by one rendered frame. The
+ * camera springs after the car, pulls back and widens its field of view
+ * with speed and moves in front of buildings blocking the view
+ *
+ * @param {number} carId - Car ID
+ * @param {number} dt - Frame time in seconds
+ * @returns {Object|null} {x, y, z, look_x, look_y, look_z, yaw, pitch, fov}, or null for an unknown car
+ */
+export function updateChaseCamera(carId, dt) {
+    if (!validateWasmFunction('wasmUpdateChaseCamera')) {
+        return null;
+    }
+    return window.wasmUpdateChaseCamera(carId, dt);
+}
+
+/**
+ * Make a car's chase camera jump straight to its place on the next update
+ *
+ * @param {number} carId - Car ID
+ * @returns {boolean} False if WASM unavailable or the car does not exist
+ */
+export function resetChaseCamera(carId) {
+    if (!validateWasmFunction('wasmResetChaseCamera')) {
+        return false;
+    }
+    return window.wasmResetChaseCamera(carId);
+}
+
+/**
+ * Tune how raw controls become car input (deadzone, response curves,
+ * steering slew and key ramping); omitted fields keep their values
+ *
+ * @param {Object} profile - {deadzone, steer_curve, pedal_curve, steer_rate, center_rate, key_press_rate, key_release_rate, key_steer_rate}
+ * @returns {boolean} False if WASM unavailable
+ */
+export function setInputProfile(profile) {
+    if (!validateWasmFunction('wasmSetInputProfile')) {
+        return false;
+    }
+    return window.wasmSetInputProfile(profile);
+}
+
+/**
+ * Get the input tuning
+ *
+ * @returns {Object|null} The input profile, or null if WASM unavailable
+ */
+export function getInputProfile() {
+    if (!validateWasmFunction('wasmGetInputProfile')) {
+        return null;
+    }
+    return window.wasmGetInputProfile();
+}
+
+/**
+ * Feed one frame of raw controls to a car through the input profile. Pass
+ * analog {throttle, brake, steering} from a gamepad or {forward, backward,
+ * left, right} from the keyboard; keys ramp rather than snap
+ *
+ * @param {number} carId - Car ID
+ * @param {Object} input - Raw controls, optionally with handbrake, cruise_speed and speed_limiter
+ * @param {number} dt - Frame time in seconds
+ * @returns {Object|null} {throttle, brake, steering, handbrake} the car drives with, or null for an unknown car
+ */
+export function processCarInput(carId, input, dt) {
+    if (!validateWasmFunction('wasmProcessCarInput')) {
+        return null;
+    }
+    return window.wasmProcessCarInput(carId, input, dt);
+}
+
+/**
+ * Tune the mobile joystick and tilt mapping; omitted fields keep their values
+ *
+ * @param {Object} profile - {joystick_sensitivity, tilt_range, tilt_sensitivity, tilt_throttle, auto_center, recenter_rate}
+ * @returns {boolean} False if WASM unavailable
+ */
+export function setTouchControls(profile) {
+    if (!validateWasmFunction('wasmSetTouchControls')) {
+        return false;
+    }
+    return window.wasmSetTouchControls(profile);
+}
+
+/**
+ * Get the mobile mapping with the tilt neutral angles
+ *
+ * @returns {Object|null} The touch profile and {neutral_roll, neutral_pitch, calibrated}, or null if WASM unavailable
+ */
+export function getTouchControls() {
+    if (!validateWasmFunction('wasmGetTouchControls')) {
+        return null;
+    }
+    return window.wasmGetTouchControls();
+}
+
+/**
+ * Take the next tilt reading as the device's neutral angles
+ */
+export function calibrateTilt() {
+    if (!validateWasmFunction('wasmCalibrateTilt')) {
+        return;
+    }
+    window.wasmCalibrateTilt();
+}
+
+/**
+ * Drive a car from the on-screen joystick for one frame
+ *
+ * @param {number} carId - Car ID
+ * @param {Object} stick - {x, y, handbrake}, x right and y up, 1 at the edge of the pad
+ * @param {number} dt - Frame time in seconds
+ * @returns {Object|null} {throttle, brake, steering, handbrake} the car drives with, or null for an unknown car
+ */
+export function processTouchInput(carId, stick, dt) {
+    if (!validateWasmFunction('wasmProcessTouchInput')) {
+        return null;
+    }
+    return window.wasmProcessTouchInput(carId, stick, dt);
+}
+
+/**
+ * Drive a car by tilting the device for one frame. Pass the
+ * deviceorientation angles mapped to roll (right positive) and pitch
+ * (forward positive); throttle and brake come from on-screen buttons
+ * unless the profile's tilt_throttle is on
+ *
+ * @param {number} carId - Car ID
+ * @param {Object} tilt - {roll, pitch, throttle, brake, handbrake}, angles in degrees
+ * @param {number} dt - Frame time in seconds
+ * @returns {Object|null} {throttle, brake, steering, handbrake} the car drives with, or null for an unknown car
+ */
+export function processTiltInput(carId, tilt, dt) {
+    if (!validateWasmFunction('wasmProcessTiltInput')) {
+        return null;
+    }
+    return window.wasmProcessTiltInput(carId, tilt, dt);
+}
+
+// Reused transform buffer for stepTraffic (grown by WASM as needed)
+let trafficTransforms = null;
+
+/**
+ * Step AI traffic and pedestrians and return the car render transforms
+ * Each car uses 7 values: id, x, y, z, rotation_y, pitch, roll
+ *
+ * @param {number} dt - Seconds since the last step
+ * @returns {Float64Array|null} Transforms, or null if WASM unavailable
+ */
+export function stepTraffic(dt) {
+    if (!validateWasmFunction('wasmStepWorld')) {
+        return null;
+    }
+
+    const errors = window.wasmStepWorld(dt);
+    if (errors) {
+        console.warn('Traffic step errors:', errors);
+    }
+    trafficTransforms = window.wasmGetCarTransforms(trafficTransforms);
+    return trafficTransforms;
+}
+
+// Views over the WASM transform stores, kept until the stores move
+let transformViews = null;
+
+/**
+ * Build Float64Array views for one transform store, reusing the cached ones
+ * while the store and WASM memory are unchanged
+ */
+function transformStoreViews(store, fields, buffer, cached) {
+    if (cached && cached.buffer === buffer && cached.pointer === store.pointer &&
+        cached.capacity === store.capacity && cached.count === store.count) {
+        return cached;
+    }
+    const views = {};
+    for (const [name, plane] of Object.entries(fields)) {
+        views[name] = new Float64Array(buffer, store.pointer + plane * store.capacity * 8, store.count);
+    }
+    return { ...store, buffer, fields: views };
+}
+
+/**
+ * Get zero-copy views of every car, pedestrian, trailer, tram and train wagon transform, rewritten by
+ * each stepTraffic or advanceWorld (struct-of-arrays: one Float64Array per field)
+ * The views read WASM memory directly: copy values out if they must outlive
+ * the next step, and call again after each step since they move as the
+ * number of entities grows. Trailers use their towing car's id, and state is
+ * 1 while jackknifed; a tram's state is 1 while stopped at a station; train
+ * wagons have ids from 0 (the locomotive, state 0) back.
+ * previousCars/previousPedestrians/previousTrailers/previousTrams/previousTrains hold the
+ * transforms before the last fixed step; draw previous + (current - previous) * alpha
+ * for entities whose ids match
+ *
+ * @returns {{alpha, cars, pedestrians, trailers, trams, trains, previousCars, previousPedestrians,
+ *   previousTrailers, previousTrams, previousTrains}|null} each
+ *   {count, fields} maps id, x, y, z, heading, pitch, roll and state to a
+ *   Float64Array of count values, or null if WASM (or its memory) is unavailable
+ */
+export function getTransformViews() {
+    if (!validateWasmFunction('wasmGetTransformViews') || !window.wasmMemory) {
+        return null;
+    }
+    const layout = window.wasmGetTransformViews();
+    const buffer = window.wasmMemory.buffer;
+    const views = (store, cached) => transformStoreViews(store, layout.fields, buffer, cached);
+    transformViews = {
+        alpha: layout.alpha,
+        cars: views(layout.cars, transformViews?.cars),
+        pedestrians: views(layout.pedestrians, transformViews?.pedestrians),
+        trailers: views(layout.trailers, transformViews?.trailers),
+        previousCars: views(layout.previous_cars, transformViews?.previousCars),
+        previousPedestrians: views(layout.previous_pedestrians, transformViews?.previousPedestrians),
+        previousTrailers: views(layout.previous_trailers, transformViews?.previousTrailers),
+        trams: views(layout.trams, transformViews?.trams),
+        previousTrams: views(layout.previous_trams, transformViews?.previousTrams),
+        trains: views(layout.trains, transformViews?.trains),
+        previousTrains: views(layout.previous_trains, transformViews?.previousTrains),
+    };
+    return transformViews;
+}
+
+/**
+ * Run AI traffic and pedestrians in fixed steps (60 Hz by default) for a
+ * frame's elapsed time, so the simulation is the same at any frame rate
+ *
+ * @param {number} dt - Seconds since the last frame
+ * @param {number} [step] - Seconds per fixed step (kept for later calls)
+ * @returns {{steps: number, alpha: number}|null} Fixed steps run and the render
+ *   interpolation alpha, or null if WASM unavailable
+ */
+export function advanceWorld(dt, step) {
+    if (!validateWasmFunction('wasmAdvanceWorld')) {
+        return null;
+    }
+    return step === undefined ? window.wasmAdvanceWorld(dt) : window.wasmAdvanceWorld(dt, step);
+}
+
+/**
+ * What the last simulated frame cost, for a performance HUD
+ * A frame is one stepWorld call, or an advanceWorld call that ran fixed steps
+ *
+ * @returns {{steps: number, step_ms: number, systems: Object<string, number>, counts: Object<string, number>}|null}
+ *   ms per subsystem (environment, traffic, fires, pedestrians, physics, collision,
+ *   grids, ghosts, output) and entity counts, or null if WASM unavailable
+ */
+export function getFrameStats() {
+    if (!validateWasmFunction('wasmGetFrameStats')) {
+        return null;
+    }
+    return window.wasmGetFrameStats();
+}
+
+/**
+ * Encode the complete simulation state (cars, roads, placements, zoning,
+ * clock, weather, treasury, ...) for saves and debugging dumps
+ *
+ * @returns {string|null} JSON, or null if WASM unavailable or the state cannot be encoded
+ */
+export function saveWorldState() {
+    if (!validateWasmFunction('wasmSaveWorld')) {
+        return null;
+    }
+    return window.wasmSaveWorld();
+}
+
+/**
+ * Replace the simulation state with saveWorldState output
+ *
+ * @param {string} json - Saved state
+ * @returns {boolean} False (state unchanged) if WASM unavailable or the save is invalid
+ */
+export function loadWorldState(json) {
+    if (!validateWasmFunction('wasmLoadWorld')) {
+        return false;
+    }
+    return window.wasmLoadWorld(json);
+}
+
+/**
+ * Encode the complete simulation state as a compact, versioned binary save
+ * Same contents as saveWorldState, a fraction of the size
+ *
+ * @returns {Uint8Array|null} Save bytes, or null if WASM unavailable or the state cannot be encoded
+ */
+export function saveWorldBinary() {
+    if (!validateWasmFunction('wasmSaveWorldBinary')) {
+        return null;
+    }
+    return window.wasmSaveWorldBinary();
+}
+
+/**
+ * Replace the simulation state with saveWorldBinary output
+ * Saves written by newer builds load as long as their major format version matches
+ *
+ * @param {Uint8Array} data - Save bytes
+ * @returns {boolean} False (state unchanged) if WASM unavailable or the save is invalid or incompatible
+ */
+export function loadWorldBinary(data) {
+    if (!validateWasmFunction('wasmLoadWorldBinary')) {
+        return false;
+    }
+    return window.wasmLoadWorldBinary(data);
+}
+
+/**
+ * Encode what changed in the simulation since the last call, for networked clients
+ * Only changed fields of changed entities are included
+ *
+ * @param {boolean} [keyFrame=false] - Encode everything, for clients joining or out of sync
+ * @returns {Uint8Array|null} Delta bytes, or null if WASM unavailable
+ */
+export function encodeStateDelta(keyFrame = false) {
+    if (!validateWasmFunction('wasmEncodeDelta')) {
+        return null;
+    }
+    return window.wasmEncodeDelta(keyFrame);
+}
+
+/**
+ * Apply a delta from encodeStateDelta, continuing from the last delta applied
+ *
+ * @param {Uint8Array} data - Delta bytes
+ * @returns {boolean} False (state unchanged) if WASM unavailable or the delta does not follow the last one;
+ *   request a key frame then
+ */
+export function applyStateDelta(data) {
+    if (!validateWasmFunction('wasmApplyDelta')) {
+        return false;
+    }
+    return window.wasmApplyDelta(data);
+}
+
+/**
+ * Make the simulation a multiplayer client predicting the car it drives
+ * From then on advance it with predictCarInput each server tick instead of stepWorld
+ *
+ * @param {number} carId - The local player's car
+ * @returns {boolean} False if WASM unavailable
+ */
+export function startPrediction(carId) {
+    if (!validateWasmFunction('wasmStartPrediction')) {
+        return false;
+    }
+    return window.wasmStartPrediction(carId);
+}
+
+/**
+ * Run one predicted tick on the player's input
+ *
+ * @param {Object} inputState - Analog or boolean input, as setCarInput
+ * @returns {Uint8Array|null} Input packet to send to the server, or null if WASM unavailable or not predicting
+ */
+export function predictCarInput(inputState) {
+    if (!validateWasmFunction('wasmPredict')) {
+        return null;
+    }
+    return window.wasmPredict(inputState);
+}
+
+/**
+ * Apply a state packet from the server: rewind, replay unapplied inputs, smooth the correction
+ *
+ * @param {Uint8Array} data - Server state packet
+ * @returns {boolean} False if WASM unavailable, not predicting or the packet cannot be applied
+ */
+export function reconcileServerState(data) {
+    if (!validateWasmFunction('wasmReconcile')) {
+        return false;
+    }
+    return window.wasmReconcile(data);
+}
+
+/**
+ * Where to draw the predicted car, including the correction still fading out
+ *
+ * @returns {{x: number, z: number, rotation_y: number, correction: number, pending: number}|null}
+ *   null if WASM unavailable, not predicting or the car does not exist
+ */
+export function getPredictedCar() {
+    if (!validateWasmFunction('wasmGetPredictedCar')) {
+        return null;
+    }
+    return window.wasmGetPredictedCar();
+}
+
+/**
+ * Export the town's buildings, roads and zones as human-readable JSON
+ * Holds no simulation state, so layouts can be shared, diffed and hand-edited
+ *
+ * @returns {string|null} Indented layout JSON, or null if WASM unavailable
+ */
+export function exportTownLayout() {
+    if (!validateWasmFunction('wasmExportLayout')) {
+        return null;
+    }
+    return window.wasmExportLayout();
+}
+
+/**
+ * Replace the town's buildings, roads and zones with exportTownLayout output
+ * Static colliders are cleared; re-register them with setPlacements as the scene is rebuilt
+ *
+ * @param {string} json - Layout JSON
+ * @returns {{ok: boolean, errors?: string[]}} Every problem found; the town is unchanged unless ok
+ */
+export function importTownLayout(json) {
+    if (!validateWasmFunction('wasmImportLayout')) {
+        return { ok: false, errors: ['WASM not available'] };
+    }
+    return window.wasmImportLayout(json);
+}
+
+/**
+ * Revert the newest building, road or zone edit
+ * Placements, road edits and zone painting made through this module are
+ * recorded; replacing placements or the road network clears the history
+ *
+ * @returns {boolean} False if WASM unavailable or there is nothing to undo
+ */
+export function undoEdit() {
+    if (!validateWasmFunction('wasmUndo')) {
+        return false;
+    }
+    return window.wasmUndo();
+}
+
+/**
+ * Apply the newest undone edit again
+ *
+ * @returns {boolean} False if WASM unavailable or there is nothing to redo
+ */
+export function redoEdit() {
+    if (!validateWasmFunction('wasmRedo')) {
+        return false;
+    }
+    return window.wasmRedo();
+}
+
+/**
+ * Number of edits that can be undone and redone, for enabling editor buttons
+ *
+ * @returns {{undo: number, redo: number}} Zero counts if WASM unavailable
+ */
+export function getEditHistory() {
+    if (!validateWasmFunction('wasmGetEditHistory')) {
+        return { undo: 0, redo: 0 };
+    }
+    return window.wasmGetEditHistory();
+}
+
+/**
+ * Forget every recorded edit (e.g. after saving the town)
+ */
+export function clearEditHistory() {
+    if (!validateWasmFunction('wasmClearEditHistory')) {
+        return;
+    }
+    window.wasmClearEditHistory();
+}
+
+/**
+ * Configure the ring of periodic in-memory autosaves
+ *
+ * @param {Object} options - {interval?: simulated seconds between autosaves, slots?: autosaves kept (0 turns autosaving off)}
+ * @returns {boolean} False if WASM unavailable
+ */
+export function setAutosave(options) {
+    if (!validateWasmFunction('wasmSetAutosave')) {
+        return false;
+    }
+    return window.wasmSetAutosave(options);
+}
+
+/**
+ * Take an autosave now (e.g. before a risky bulk edit)
+ *
+ * @returns {number} Autosave id, or 0 if WASM unavailable or autosaving is off
+ */
+export function autosaveNow() {
+    if (!validateWasmFunction('wasmAutosave')) {
+        return 0;
+    }
+    return window.wasmAutosave();
+}
+
+/**
+ * List the autosaves held in memory, oldest first
+ *
+ * @returns {Array<{id: number, time: number, day: number, age: number}>} age in seconds; empty if WASM unavailable
+ */
+export function getAutosaves() {
+    if (!validateWasmFunction('wasmGetAutosaves')) {
+        return [];
+    }
+    return window.wasmGetAutosaves();
+}
+
+/**
+ * Return the town to an autosave; the other autosaves are kept
+ *
+ * @param {number} id - Autosave id from getAutosaves
+ * @returns {boolean} False if WASM unavailable or the autosave was overwritten
+ */
+export function restoreAutosave(id) {
+    if (!validateWasmFunction('wasmRestoreAutosave')) {
+        return false;
+    }
+    return window.wasmRestoreAutosave(id);
+}
+
+/**
+ * Encode an autosave as a binary save, for persisting it across reloads
+ *
+ * @param {number} id - Autosave id from getAutosaves
+ * @returns {Uint8Array|null} Save data loadable like a normal save, or null
+ */
+export function getAutosaveData(id) {
+    if (!validateWasmFunction('wasmGetAutosave')) {
+        return null;
+    }
+    return window.wasmGetAutosave(id);
+}
+
+/**
+ * Restart the simulation's random generators (weather, trips, fires) from a seed
+ * Peers and replays with the same seed, state and inputs stay bit-identical
+ *
+ * @param {number} seed - Integer seed
+ * @returns {boolean} False if WASM unavailable or the seed is not a number
+ */
+export function setSimulationSeed(seed) {
+    if (!validateWasmFunction('wasmSetSeed')) {
+        return false;
+    }
+    return window.wasmSetSeed(seed);
+}
+
+/**
+ * Set the world scale, how many meters one world unit is. Car states then
+ * carry speed_kmh, speed_mph, odometer_km and odometer_miles at that scale
+ *
+ * @param {number} metersPerUnit - Positive meters per world unit
+ * @returns {boolean} False if WASM unavailable or the scale is not positive
+ */
+export function setWorldScale(metersPerUnit) {
+    if (!validateWasmFunction('wasmSetScale')) {
+        return false;
+    }
+    return window.wasmSetScale(metersPerUnit);
+}
+
+/**
+ * Get the world scale in meters per world unit
+ *
+ * @returns {number|null} Meters per unit, or null if WASM unavailable
+ */
+export function getWorldScale() {
+    if (!validateWasmFunction('wasmGetScale')) {
+        return null;
+    }
+    return window.wasmGetScale();
+}
+
+/**
+ * Hash of the complete simulation state, for lockstep peers to detect desyncs
+ *
+ * @returns {string|null} 64-bit hash in hex, or null if WASM unavailable
+ */
+export function getWorldChecksum() {
+    if (!validateWasmFunction('wasmGetChecksum')) {
+        return null;
+    }
+    return window.wasmGetChecksum();
+}
+
+/**
+ * Keep the simulation state in memory for rollback netcode (no JSON, cheap enough every step)
+ *
+ * @returns {number|null} Checkpoint id, or null if WASM unavailable; release it with releaseCheckpoint
+ */
+export function createCheckpoint() {
+    if (!validateWasmFunction('wasmCheckpoint')) {
+        return null;
+    }
+    return window.wasmCheckpoint();
+}
+
+/**
+ * Return the simulation to a checkpoint, which stays available to roll back to again
+ *
+ * @param {number} id - Checkpoint id
+ * @returns {boolean} False if WASM unavailable or the checkpoint does not exist
+ */
+export function rollbackToCheckpoint(id) {
+    if (!validateWasmFunction('wasmRollback')) {
+        return false;
+    }
+    return window.wasmRollback(id);
+}
+
+/**
+ * Roll back to a checkpoint and step again with corrected inputs
+ * Inputs cover AI or JS-driven cars; manual cars are not stepped by the world
+ *
+ * @param {number} id - Checkpoint id
+ * @param {number} dt - Seconds per step
+ * @param {Array<Object<number, Object>>} inputs - Per step, car id to input state (as setCarInput)
+ * @returns {boolean} False if WASM unavailable or the checkpoint does not exist
+ */
+export function resimulateFromCheckpoint(id, dt, inputs) {
+    if (!validateWasmFunction('wasmResimulate')) {
+        return false;
+    }
+    return window.wasmResimulate(id, dt, inputs);
+}
+
+/**
+ * Free a checkpoint once no input older than it can still arrive
+ *
+ * @param {number} id - Checkpoint id
+ * @returns {boolean} False if WASM unavailable or the checkpoint does not exist
+ */
+export function releaseCheckpoint(id) {
+    if (!validateWasmFunction('wasmReleaseCheckpoint')) {
+        return false;
+    }
+    return window.wasmReleaseCheckpoint(id);
+}
+
+/**
+ * Start recording a car's input, from a snapshot of the whole world, for replays and ghosts
+ * Replaces any recording in progress
+ *
+ * @param {number} carId - Car to record (usually the player's)
+ * @returns {boolean} False if WASM unavailable or the car does not exist
+ */
+export function startInputRecording(carId) {
+    if (!validateWasmFunction('wasmStartRecording')) {
+        return false;
+    }
+    return window.wasmStartRecording(carId);
+}
+
+/**
+ * Stop recording and return the recording
+ *
+ * @returns {string|null} Recording JSON, or null if WASM unavailable or nothing was recorded
+ */
+export function stopInputRecording() {
+    if (!validateWasmFunction('wasmStopRecording')) {
+        return null;
+    }
+    return window.wasmStopRecording();
+}
+
+/**
+ * Re-simulate a recording from its starting state, apart from the live world, to reproduce a session exactly
+ *
+ * @param {string} recording - stopInputRecording output
+ * @returns {{matched: boolean, checksum: string, car: Object|null}|null} Whether the replay ended in the
+ *   recorded state, and the car's final state; null if WASM unavailable or the recording is invalid
+ */
+export function replayInputRecording(recording) {
+    if (!validateWasmFunction('wasmReplayRecording')) {
+        return null;
+    }
+    return window.wasmReplayRecording(recording);
+}
+
+/**
+ * Play a recording back as a ghost car alongside the live world, at the recorded pace
+ * Replaces the ghost with the same id
+ *
+ * @param {number} id - Ghost id
+ * @param {string} recording - stopInputRecording output
+ * @returns {boolean} False if WASM unavailable or the recording is invalid
+ */
+export function addGhostCar(id, recording) {
+    if (!validateWasmFunction('wasmAddGhost')) {
+        return false;
+    }
+    return window.wasmAddGhost(id, recording);
+}
+
+/**
+ * Stop a ghost car
+ *
+ * @param {number} id - Ghost id
+ * @returns {boolean} False if WASM unavailable or the ghost does not exist
+ */
+export function removeGhostCar(id) {
+    if (!validateWasmFunction('wasmRemoveGhost')) {
+        return false;
+    }
+    return window.wasmRemoveGhost(id);
+}
+
+/**
+ * Every ghost car's transform, for rendering
+ * A finished ghost stays where its recording ended until removed
+ *
+ * @returns {Array<{id: number, x: number, y: number, z: number, rotation_y: number, pitch: number,
+ *   roll: number, progress: number, finished: boolean}>} Empty if WASM unavailable
+ */
+export function getGhostCars() {
+    if (!validateWasmFunction('wasmGetGhosts')) {
+        return [];
+    }
+    return window.wasmGetGhosts();
+}
+
+/**
+ * Hand a car's state to WASM to be advanced in place with stepManualCar,
+ * instead of passing the whole state to updateCarPhysics every frame
+ * AI traffic bumps into it, but stepTraffic leaves its driving alone
+ *
+ * @param {number} id - Car id (replaces any car with the same id)
+ * @param {Object} carState - Starting state in the updateCarPhysics format
+ * @param {string|Object} vehicle - Preset name or config
+ * @returns {boolean} Success status
+ */
+export function addManualCar(id, carState, vehicle = 'car') {
+    if (!validateWasmFunction('wasmAddCar')) {
+        return false;
+    }
+    return window.wasmAddCar(id, carState, vehicle, { manual: true });
+}
+
+// Reused state buffer for stepManualCar
+let manualCarState = null;
+
+/**
+ * Advance a car added with addManualCar, whose state stays in WASM
+ * The returned buffer is reused between calls; read it with the
+ * getCarBatchLayout state offsets
+ *
+ * @param {number} id - Car id
+ * @param {Object} inputState - Analog or boolean input, as for updateCarPhysics
+ * @param {number} dt - Elapsed time in seconds
+ * @returns {Float64Array|null} The car's state, or null if WASM unavailable or the car is unknown
+ */
+export function stepManualCar(id, inputState, dt) {
+    if (!validateWasmFunction('wasmStepCar')) {
+        return null;
+    }
+    const state = window.wasmStepCar(id, inputState, dt, manualCarState);
+    if (state) {
+        manualCarState = state;
+    }
+    return state;
+}
+
+/**
+ * Remove collision damage from a car held in WASM (e.g. after a tow or a
+ * paid repair); cars stopped on a garage are repaired over time on their own
+ *
+ * @param {number} id - Car id
+ * @param {number} [amount] - Damage (0..1) to remove; omitted for a full repair
+ * @returns {boolean} False if WASM unavailable or the car is unknown
+ */
+export function repairCar(id, amount) {
+    if (!validateWasmFunction('wasmRepairCar')) {
+        return false;
+    }
+    return window.wasmRepairCar(id, amount);
+}
+
+/**
+ * Add fuel to a car held in WASM (vehicles with a fuel_capacity only);
+ * cars stopped at a gas station refuel over time on their own. The car
+ * state's fuel (0..1) drives the HUD gauge
+ *
+ * @param {number} id - Car id
+ * @param {number} [amount] - Fuel units to add; omitted to fill the tank
+ * @returns {boolean} False if WASM unavailable or the car is unknown
+ */
+export function refuelCar(id, amount) {
+    if (!validateWasmFunction('wasmRefuelCar')) {
+        return false;
+    }
+    return window.wasmRefuelCar(id, amount);
+}
+
+/**
+ * Hitch a trailer behind a car held in WASM, lined up straight behind it
+ * The trailer follows the car, jackknifes if reversed too far (a 'jackknifed'
+ * event) and is drawn from the trailers transform views
+ *
+ * @param {number} id - Car id
+ * @param {Object} [trailer] - {hitch_offset, length, mass, body_radius}; omitted fields use defaults
+ * @returns {boolean} False if WASM unavailable or the car is unknown
+ */
+export function hitchTrailer(id, trailer) {
+    if (!validateWasmFunction('wasmHitchTrailer')) {
+        return false;
+    }
+    return trailer === undefined ? window.wasmHitchTrailer(id) : window.wasmHitchTrailer(id, trailer);
+}
+
+/**
+ * Drop a car's trailer
+ *
+ * @param {number} id - Car id
+ * @returns {boolean} False if WASM unavailable, the car is unknown or tows no trailer
+ */
+export function unhitchTrailer(id) {
+    if (!validateWasmFunction('wasmUnhitchTrailer')) {
+        return false;
+    }
+    return window.wasmUnhitchTrailer(id);
+}
+
+/**
+ * Spawn a pedestrian at a building who walks the sidewalks to a destination
+ * Pedestrians are stepped by stepTraffic
+ *
+ * @param {number} id - Pedestrian id (replaces any pedestrian with the same id)
+ * @param {THREE.Object3D} building - Object the pedestrian leaves from
+ * @param {{x: number, z: number}} destination - World position to walk to
+ * @param {number} speed - Walking speed in units/s (WASM default if omitted)
+ * @param {boolean} direct - Walk straight to the destination instead of along sidewalks (plazas, parks)
+ * @returns {boolean} True if a sidewalk route was found
+ */
+export function spawnPedestrianAt(id, building, destination, speed, direct = false) {
+    if (!validateWasmFunction('wasmSpawnPedestrian')) {
+        return false;
+    }
+    const from = { x: building.position.x, z: building.position.z };
+    return window.wasmSpawnPedestrian(id, from, { x: destination.x, z: destination.z }, speed, direct);
+}
+
+/**
+ * Tune crowd avoidance (social forces); omitted keys keep their current value
+ *
+ * @param {Object} config - {relaxation_time, repulsion_strength, repulsion_range,
+ *   obstacle_strength, obstacle_range, anisotropy, max_speed_scale}
+ * @returns {Object|null} The sanitized config, or null if WASM unavailable
+ */
+export function setCrowdConfig(config) {
+    if (!validateWasmFunction('wasmSetCrowdConfig')) {
+        return null;
+    }
+    return window.wasmSetCrowdConfig(config);
+}
+
+// Reused transform buffer for getPedestrianTransforms (grown by WASM as needed)
+let pedestrianTransforms = null;
+
+/**
+ * Get pedestrian render transforms
+ * Each pedestrian uses 5 values: id, x, z, heading, state
+ * (0 walking, 1 waiting at a crosswalk, 2 crossing, 3 arrived)
+ *
+ * @returns {Float64Array|null} Transforms, or null if WASM unavailable
+ */
+export function getPedestrianTransforms() {
+    if (!validateWasmFunction('wasmGetPedestrianTransforms')) {
+        return null;
+    }
+    pedestrianTransforms = window.wasmGetPedestrianTransforms(pedestrianTransforms);
+    return pedestrianTransforms;
+}
+
+/**
+ * Create a WASM World that owns its own cars, colliders, terrain and surfaces
+ * Call world.step(dt) once per frame and read world.getCarTransforms();
+ * call world.dispose() when done
+ *
+ * @returns {Object|null} World object, or null if WASM unavailable
+ */
+export function createWorld() {
+    if (!validateWasmFunction('wasmCreateWorld')) {
+        return null;
+    }
+    return window.wasmCreateWorld();
+}
+
+// Cached buffer layout for batch car updates (fetched once from WASM)
+let carBatchLayout = null;
+
+/**
+ * Get the flat buffer layout used by updateCars
+ *
+ * @returns {Object|null} {state_stride, input_stride, state_fields, input_fields}, or null if WASM unavailable
+ */
+export function getCarBatchLayout() {
+    if (!carBatchLayout && validateWasmFunction('wasmGetCarBatchLayout')) {
+        carBatchLayout = window.wasmGetCarBatchLayout();
+    }
+    return carBatchLayout;
+}
+
+/**
+ * Allocate state and input buffers for a batch of cars
+ *
+ * @param {number} count - Number of cars
+ * @returns {{states: Float64Array, inputs: Float64Array, count: number}|null} Buffers, or null if WASM unavailable
+ */
+export function createCarBatch(count) {
+    const layout = getCarBatchLayout();
+    if (!layout) {
+        return null;
+    }
+
+    return {
+        states: new Float64Array(count * layout.state_stride),
+        inputs: new Float64Array(count * layout.input_stride),
+        count,
+    };
+}
+
+/**
+ * Advance every car in a batch with one WASM call (states are updated in place)
+ * Cars in the batch also collide with each other
+ *
+ * @param {{states: Float64Array, inputs: Float64Array, count: number}} batch - Buffers from createCarBatch
+ * @param {number} dt - Elapsed time in seconds
+ * @param {string|Object|Array} vehicles - Preset/config for all cars, or one per car
+ * @returns {boolean} Success status
+ */
+export function updateCars(batch, dt, vehicles = 'car') {
+    if (!validateWasmFunction('wasmUpdateCars')) {
+        return false;
+    }
+
+    try {
+        const errors = window.wasmUpdateCars(batch.states, batch.inputs, batch.count, dt, vehicles);
+        if (errors) {
+            console.warn('Car physics sanitized invalid batch state:', errors);
+        }
+        return true;
+    } catch (error) {
+        console.error('Error updating car batch:', error);
+        return false;
+    }
+}
+
+/**
+ * Cast a ray against the static colliders and terrain
+ * Useful for mouse picking and AI sensors without a Three.js scene traversal
+ *
+ * @param {{x: number, y: number, z: number}} origin - Ray start (world space)
+ * @param {{x: number, y: number, z: number}} direction - Ray direction (need not be normalized)
+ * @param {number} maxDistance - Maximum hit distance
+ * @returns {Object|null} {x, y, z, normal, distance, id, kind} for the first hit, or null
+ */
+export function raycast(origin, direction, maxDistance = 1000) {
+    if (!physicsWasmEnabled || !validateWasmFunction('wasmRaycast')) {
+        return null;
+    }
+
+    try {
+        return window.wasmRaycast(
+            { x: origin.x, y: origin.y, z: origin.z },
+            { x: direction.x, y: direction.y, z: direction.z },
+            maxDistance
+        );
+    } catch (error) {
+        console.error('Error in raycast:', error);
+        return null;
+    }
+}
+
+/**
+ * Take the collision events queued by the car physics since the last call
+ * Call once per frame; events carry entity ids (Three.js object ids),
+ * impact_speed, and the contact point/normal for sounds and decals
+ *
+ * @returns {Array<Object>} Collision events (empty if WASM unavailable)
+ */
+export function drainCollisionEvents() {
+    if (!validateWasmFunction('wasmDrainCollisionEvents')) {
+        return [];
+    }
+
+    try {
+        const result = window.wasmDrainCollisionEvents();
+        if (result.dropped > 0) {
+            console.warn(`Collision event queue overflowed, dropped ${result.dropped} events`);
+        }
+        return result.events;
+    } catch (error) {
+        console.error('Error draining collision events:', error);
+        return [];
+    }
+}
+
+/**
+ * Take the skid events queued by the car physics since the last call
+ * Call once per frame; each skidding car reports the world-space contact
+ * points of its wheels (front left, front right, rear left, rear right, or
+ * front and rear for two-wheelers) to extend its tire marks, start when a
+ * new mark begins, and an intensity (0..1) for mark opacity and screech volume
+ *
+ * @returns {Array<Object>} Skid events (empty if WASM unavailable)
+ */
+export function drainSkidEvents() {
+    if (!validateWasmFunction('wasmDrainSkidEvents')) {
+        return [];
+    }
+
+    try {
+        const result = window.wasmDrainSkidEvents();
+        if (result.dropped > 0) {
+            console.warn(`Skid event queue overflowed, dropped ${result.dropped} events`);
+        }
+        return result.events;
+    } catch (error) {
+        console.error('Error draining skid events:', error);
+        return [];
+    }
+}
+
+/**
+ * Register a callback for simulation events instead of polling
+ * Callbacks run after each WASM world step with {type, ...}; collision and
+ * skid events carry the drainCollisionEvents and drainSkidEvents fields, the
+ * others {id, to, model, value, x, z}, demolitions with the footprint's
+ * {width, depth} too. While a collision or skid callback is registered, the
+ * matching drain stays empty
+ *
+ * @param {'collision'|'skid'|'building_completed'|'building_burned'|'milestone'|'traffic_jam'|'wrecked'|'jackknifed'|'lap_completed'|'race_finished'|'mission_completed'|'mission_failed'|'parked'|'drift'|'drift_combo'|'achievement'|'violation'|'building_upgraded'|'building_downgraded'|'demolished'|'*'} kind - Event kind, '*' for all
+ * @param {function(Object): void} callback - Called once per event
+ * @returns {number|null} Subscription id for offSimulationEvent, or null if unavailable
+ */
+export function onSimulationEvent(kind, callback) {
+    if (!validateWasmFunction('wasmOnEvent')) {
+        return null;
+    }
+
+    try {
+        return window.wasmOnEvent(kind, callback);
+    } catch (error) {
+        console.error('Error registering simulation event callback:', error);
+        return null;
+    }
+}
+
+/**
+ * Remove a callback registered with onSimulationEvent
+ *
+ * @param {number} id - Subscription id
+ * @returns {boolean} True if the callback was registered
+ */
+export function offSimulationEvent(id) {
+    if (!validateWasmFunction('wasmOffEvent')) {
+        return false;
+    }
+
+    try {
+        return window.wasmOffEvent(id);
+    } catch (error) {
+        console.error('Error removing simulation event callback:', error);
+        return false;
+    }
+}
+
+/**
+ * Limit where cars can drive; the WASM step enforces the edge and reports
+ * hit_boundary in its result
+ *
+ * @param {{minX: number, minZ: number, maxX: number, maxZ: number}} bounds - World extents
+ * @param {'clamp'|'bounce'|'wrap'} mode - What happens at the edge
+ * @returns {boolean} Success status
+ */
+export function setWorldBounds(bounds, mode = 'clamp') {
+    if (!validateWasmFunction('wasmSetWorldBounds')) {
+        return false;
+    }
+
+    try {
+        return window.wasmSetWorldBounds(
+            { minX: bounds.minX, minY: bounds.minZ, maxX: bounds.maxX, maxY: bounds.maxZ },
+            mode
+        );
+    } catch (error) {
+        console.error('Error setting world bounds:', error);
+        return false;
+    }
+}
+
 /**
  * Check collisions for a single object
  * Uses spatial grid for O(k) complexity where k = nearby objects